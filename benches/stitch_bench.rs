@@ -1,18 +1,21 @@
 // benches/stitch_bench.rs
 use criterion::{BatchSize, BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
 use std::collections::HashSet;
+use std::fmt::Write;
 use std::fs;
 use std::hint::black_box;
-use std::path::{Path, PathBuf};
+use std::path::PathBuf;
 use std::time::Duration;
 use tempfile::TempDir;
 use walkdir::WalkDir;
 
 use stitch::core::{
-    clean_remove_regex, collapse_consecutive_blank_lines, compile_remove_regex_opt,
-    parse_extension_filters, parse_hierarchy_text, render_unicode_tree_from_paths,
-    scan_dir_to_node, split_prefix_list, strip_lines_and_inline_comments,
+    HierarchyCache, TokenCache, clean_remove_regex, collapse_consecutive_blank_lines,
+    compile_remove_regex_opt, parse_extension_filters, parse_hierarchy_text,
+    render_unicode_tree_from_paths, scan_dir_to_node, split_prefix_list,
+    strip_lines_and_inline_comments,
 };
+use stitch::testing::write_file;
 
 // ---------- Fixture: synthetic repo tree we reuse across benches ----------
 static FS_FIXTURE: std::sync::LazyLock<Fixture> = std::sync::LazyLock::new(|| {
@@ -54,12 +57,12 @@ static FS_FIXTURE: std::sync::LazyLock<Fixture> = std::sync::LazyLock::new(|| {
         ("README.md", "# readme\n"),
     ];
     for (rel, body) in files {
-        write_file(&root.join(rel), body);
+        write_file(&root, rel, body).unwrap();
     }
 
     // Generate many small files to stress scan/render
     for i in 0..1200 {
-        write_file(&root.join(format!("src/gen/file_{i:04}.rs")), "fn f(){}\n");
+        write_file(&root, &format!("src/gen/file_{i:04}.rs"), "fn f(){}\n").unwrap();
     }
 
     // Collect file list
@@ -83,13 +86,6 @@ struct Fixture {
     all_files: Vec<PathBuf>,
 }
 
-fn write_file(path: &Path, body: &str) {
-    if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent).unwrap();
-    }
-    fs::write(path, body).unwrap();
-}
-
 // ---------- Benches ----------
 
 fn bench_relative_paths(c: &mut Criterion) {
@@ -259,6 +255,79 @@ fn bench_tokenization(c: &mut Criterion) {
     g.finish();
 }
 
+fn bench_token_cache(c: &mut Criterion) {
+    // ~2MB of stitched output spread over 400 files; one file changes between runs.
+    let bpe = tiktoken_rs::o200k_base().expect("load o200k_base");
+    let count = |s: &str| bpe.encode_with_special_tokens(s).len();
+    let body = r#"fn main() { println!("hello // not a comment # here"); }"#.repeat(90);
+    let build = |edited: usize| {
+        let mut out = String::from("=== FILE CONTENTS ===\n\n");
+        for i in 0..400 {
+            let extra = if i == 7 {
+                format!("// edit {edited}\n")
+            } else {
+                String::new()
+            };
+            let _ = write!(
+                out,
+                "--- Start of file: src/f{i}.rs ---\n{extra}{body}\n--- End of file: src/f{i}.rs ---\n\n"
+            );
+        }
+        out
+    };
+
+    let mut g = c.benchmark_group("token_count_after_single_file_edit");
+    g.sample_size(10);
+    g.measurement_time(Duration::from_secs(10));
+
+    let texts = [build(0), build(1)];
+    g.throughput(Throughput::Bytes(texts[0].len() as u64));
+    g.bench_function("full_recount", |b| {
+        let mut i = 0;
+        b.iter(|| {
+            i += 1;
+            black_box(count(black_box(&texts[i % 2])))
+        });
+    });
+    g.bench_function("token_cache", |b| {
+        let mut cache = TokenCache::new();
+        let _ = cache.count(&texts[0], count);
+        let mut i = 0;
+        b.iter(|| {
+            i += 1;
+            black_box(cache.count(black_box(&texts[i % 2]), count))
+        });
+    });
+
+    g.finish();
+}
+
+fn bench_hierarchy_cache(c: &mut Criterion) {
+    // 50k selected files; regenerating with the same selection.
+    let paths: Vec<String> = (0..50_000)
+        .map(|i| format!("src/area_{:02}/pkg_{:03}/file_{i:05}.rs", i % 40, i % 400))
+        .collect();
+
+    let mut g = c.benchmark_group("hierarchy_unchanged_selection_50k");
+    g.sample_size(10);
+
+    g.bench_function("full_render", |b| {
+        b.iter(|| {
+            black_box(render_unicode_tree_from_paths(
+                black_box(&paths),
+                Some("project"),
+            ))
+        });
+    });
+    g.bench_function("hierarchy_cache", |b| {
+        let mut cache = HierarchyCache::new();
+        let _ = cache.render(&paths, Some("project"));
+        b.iter(|| black_box(cache.render(black_box(&paths), Some("project"))));
+    });
+
+    g.finish();
+}
+
 fn bench_hierarchy_parse_render(c: &mut Criterion) {
     // Build a tree text via renderer, then parse it back
     let paths: Vec<String> = (0..500)
@@ -296,6 +365,8 @@ criterion_group!(
     bench_strip_and_collapse,
     bench_remove_regex,
     bench_tokenization,
+    bench_token_cache,
+    bench_hierarchy_cache,
     bench_hierarchy_parse_render
 );
 criterion_main!(benches);
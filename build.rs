@@ -5,6 +5,9 @@ fn main() {
         return;
     }
 
-    // Compiles ui/app.slint and sets SLINT_INCLUDE_GENERATED for slint::include_modules!()
-    slint_build::compile("ui/app.slint").expect("Failed to compile Slint UI");
+    // Compiles ui/app.slint and sets SLINT_INCLUDE_GENERATED for slint::include_modules!().
+    // Translations in ui/lang/<lang>/LC_MESSAGES/stitch.po are bundled into the binary.
+    let config = slint_build::CompilerConfiguration::new().with_bundled_translations("ui/lang");
+    slint_build::compile_with_config("ui/app.slint", config).expect("Failed to compile Slint UI");
+    println!("cargo:rerun-if-changed=ui/lang");
 }
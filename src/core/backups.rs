@@ -0,0 +1,199 @@
+use std::{
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::core::{WRITE_LOCK_TIMEOUT, with_write_lock, workspace_dir};
+
+/* ============================ Settings backups ============================== */
+
+/// Backups kept per file when the local settings don't say otherwise.
+pub const DEFAULT_BACKUP_COUNT: usize = 5;
+
+/// A copy of a workspace or profile file taken before it was overwritten.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Backup {
+    /// Millisecond timestamp; sorts oldest to newest.
+    pub id: String,
+    /// Where the copy is stored.
+    pub path: PathBuf,
+    /// The file it was taken from.
+    pub target: PathBuf,
+}
+
+#[must_use]
+pub fn backups_dir(project_root: &Path) -> PathBuf {
+    workspace_dir(project_root).join("backups")
+}
+
+/// Folder and file-name prefix of `target`'s backups: the backups folder mirrors the
+/// workspace folder, e.g. `profiles/api.json` → `backups/profiles/api.<id>.json`.
+fn backup_location(project_root: &Path, target: &Path) -> io::Result<(PathBuf, String)> {
+    let rel = target
+        .strip_prefix(workspace_dir(project_root))
+        .ok()
+        .filter(|rel| !rel.starts_with("backups"))
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{} is not a workspace file", target.display()),
+            )
+        })?;
+    let stem = rel
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "file name is not UTF-8"))?;
+    let dir = rel.parent().map_or_else(
+        || backups_dir(project_root),
+        |parent| backups_dir(project_root).join(parent),
+    );
+    Ok((dir, format!("{stem}.")))
+}
+
+/// Backups of `target`, newest first.
+#[must_use]
+pub fn list_backups(project_root: &Path, target: &Path) -> Vec<Backup> {
+    let Ok((dir, prefix)) = backup_location(project_root, target) else {
+        return Vec::new();
+    };
+    let Ok(read_dir) = fs::read_dir(&dir) else {
+        return Vec::new();
+    };
+    let mut backups: Vec<Backup> = read_dir
+        .flatten()
+        .filter_map(|ent| {
+            let name = ent.file_name().into_string().ok()?;
+            let id = name.strip_prefix(&prefix)?.strip_suffix(".json")?;
+            id.bytes().all(|b| b.is_ascii_digit()).then(|| Backup {
+                id: id.to_string(),
+                path: ent.path(),
+                target: target.to_path_buf(),
+            })
+        })
+        .collect();
+    backups.sort_by(|a, b| b.id.cmp(&a.id));
+    backups
+}
+
+/// Write `data` to `target` through a synced temporary file and a rename, so a crash
+/// leaves either the old or the new contents.
+///
+/// # Errors
+/// Returns I/O errors from writing, syncing or renaming.
+pub fn write_atomic(target: &Path, data: &[u8]) -> io::Result<()> {
+    let mut tmp_name = target.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(".tmp");
+    let tmp = target.with_file_name(tmp_name);
+    {
+        let mut file = fs::File::create(&tmp)?;
+        file.write_all(data)?;
+        file.sync_all()?;
+    }
+    fs::rename(&tmp, target)
+}
+
+/// Replace the workspace file `target` with `data`, keeping a backup of what it held.
+///
+/// The current contents are copied to the backups folder first and the oldest backups
+/// beyond `keep` are dropped. All of it runs under the project's write lock (see
+/// [`with_write_lock`]).
+///
+/// No backup is taken when `target` doesn't exist yet, already holds `data`, or `keep`
+/// is 0.
+///
+/// # Errors
+/// Returns I/O errors from writing, renaming or removing files, `InvalidInput` when
+/// `target` is not inside the workspace folder, and `WouldBlock` when another writer
+/// holds the lock for longer than [`WRITE_LOCK_TIMEOUT`].
+pub fn write_with_backup(
+    project_root: &Path,
+    target: &Path,
+    data: &[u8],
+    keep: usize,
+) -> io::Result<()> {
+    with_write_lock(project_root, WRITE_LOCK_TIMEOUT, || {
+        backup_and_write(project_root, target, data, keep)
+    })
+}
+
+fn backup_and_write(
+    project_root: &Path,
+    target: &Path,
+    data: &[u8],
+    keep: usize,
+) -> io::Result<()> {
+    if keep > 0
+        && let Ok(current) = fs::read(target)
+        && current != data
+    {
+        let (dir, prefix) = backup_location(project_root, target)?;
+        fs::create_dir_all(&dir)?;
+        ensure_backups_ignored(project_root)?;
+
+        let existing = list_backups(project_root, target);
+        let id = next_backup_id(&existing);
+        write_atomic(&dir.join(format!("{prefix}{id}.json")), &current)?;
+        for old in existing.iter().skip(keep.saturating_sub(1)) {
+            match fs::remove_file(&old.path) {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+                _ => {}
+            }
+        }
+    }
+    write_atomic(target, data)
+}
+
+/// Backups are per machine even for shared files, so keep them out of version control.
+fn ensure_backups_ignored(project_root: &Path) -> io::Result<()> {
+    let gitignore = backups_dir(project_root).join(".gitignore");
+    if !gitignore.exists() {
+        fs::write(gitignore, "*\n")?;
+    }
+    Ok(())
+}
+
+/// Millisecond timestamp, bumped past the newest backup so ids stay unique and ordered.
+fn next_backup_id(existing: &[Backup]) -> String {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_millis());
+    let newest = existing
+        .first()
+        .and_then(|b| b.id.parse::<u128>().ok())
+        .unwrap_or(0);
+    format!("{:015}", now.max(newest + 1))
+}
+
+/// Put `backup` back in place of its file. The contents being replaced are backed up in
+/// turn, so a restore can itself be undone.
+///
+/// # Errors
+/// Returns `InvalidData` when the backup isn't valid JSON, and I/O errors from reading
+/// or writing files.
+pub fn restore_backup(project_root: &Path, backup: &Backup, keep: usize) -> io::Result<()> {
+    let data = fs::read(&backup.path)?;
+    serde_json::from_slice::<serde_json::Value>(&data)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+    write_with_backup(project_root, &backup.target, &data, keep)
+}
+
+/// Restore the newest backup of `target` that is valid JSON, if there is one.
+///
+/// # Errors
+/// Returns I/O errors from writing the restored file.
+pub fn restore_latest_backup(
+    project_root: &Path,
+    target: &Path,
+    keep: usize,
+) -> io::Result<Option<Backup>> {
+    for backup in list_backups(project_root, target) {
+        match restore_backup(project_root, &backup, keep) {
+            Ok(()) => return Ok(Some(backup)),
+            Err(e) if e.kind() == io::ErrorKind::InvalidData => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(None)
+}
@@ -0,0 +1,523 @@
+use std::collections::HashSet;
+
+use serde::Serialize;
+
+use crate::core::{
+    collapse_consecutive_blank_lines, is_ident_byte, scan_string_literal, skip_line_comment,
+    trim_leading_blank_lines, trim_trailing_ws_current_line,
+};
+
+/// Extensions of the C, C++, Java and C# files the C-like filters apply to.
+pub const C_LIKE_EXTENSIONS: &[&str] = &[
+    "c", "h", "cc", "cpp", "cxx", "hh", "hpp", "hxx", "java", "cs",
+];
+
+/// Words that take a parenthesized group before a `{` without starting a function body.
+const CONTROL_KEYWORDS: &[&str] = &[
+    "if",
+    "for",
+    "foreach",
+    "while",
+    "switch",
+    "catch",
+    "using",
+    "lock",
+    "fixed",
+    "synchronized",
+    "return",
+    "new",
+    "sizeof",
+    "typeof",
+    "nameof",
+    "when",
+    "checked",
+    "unchecked",
+];
+
+/// Words that make a `{` the body of a type or namespace rather than of a function.
+const TYPE_KEYWORDS: &[&str] = &[
+    "class",
+    "struct",
+    "interface",
+    "enum",
+    "namespace",
+    "record",
+    "union",
+];
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CLikeFilterOptions {
+    pub remove_line_comments: bool,
+    pub remove_block_comments: bool,
+    pub function_signatures_only: bool,
+}
+
+/// Returns true for C, C++, Java and C# sources (see [`C_LIKE_EXTENSIONS`]).
+#[must_use]
+pub fn is_c_like_file_path(path: &std::path::Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|ext| {
+            C_LIKE_EXTENSIONS
+                .iter()
+                .any(|e| e.eq_ignore_ascii_case(ext))
+        })
+}
+
+/// Apply the C-like filters to a source file's string contents.
+/// This function only transforms when at least one option is enabled; otherwise returns input as-is.
+#[must_use]
+pub fn apply_c_like_filters(source: &str, opts: &CLikeFilterOptions) -> String {
+    if !(opts.remove_line_comments || opts.remove_block_comments || opts.function_signatures_only) {
+        return source.to_string();
+    }
+    let cleaned = if opts.remove_line_comments || opts.remove_block_comments {
+        remove_slash_comments(
+            source,
+            opts.remove_line_comments,
+            opts.remove_block_comments,
+            skip_literal,
+        )
+    } else {
+        source.to_string()
+    };
+    let reduced = if opts.function_signatures_only {
+        SignatureReducer::new(&cleaned).run()
+    } else {
+        cleaned
+    };
+    trim_leading_blank_lines(&collapse_consecutive_blank_lines(&reduced))
+}
+
+/// End of the string or character literal starting at `idx`, if one does: plain,
+/// C++ raw (`R"x(...)x"`), C# verbatim (`@"..."`) and Java text block (`"""..."""`).
+fn skip_literal(bytes: &[u8], len: usize, idx: usize) -> Option<usize> {
+    match bytes[idx] {
+        b'"' if bytes[idx..].starts_with(b"\"\"\"") => Some(skip_text_block(bytes, len, idx + 3)),
+        b'"' if idx > 0 && bytes[idx - 1] == b'@'
+            || idx > 1 && bytes[idx - 1] == b'$' && bytes[idx - 2] == b'@' =>
+        {
+            Some(skip_verbatim_string(bytes, len, idx + 1))
+        }
+        b'"' if is_raw_string_prefix(bytes, idx) => Some(skip_raw_string(bytes, len, idx)),
+        b'"' => Some(scan_string_literal(bytes, len, idx, b'"')),
+        // `1'000'000` uses the quote as a digit separator.
+        b'\'' if !in_number_literal(bytes, idx) => {
+            Some(scan_string_literal(bytes, len, idx, b'\''))
+        }
+        _ => None,
+    }
+}
+
+fn skip_text_block(bytes: &[u8], len: usize, mut idx: usize) -> usize {
+    while idx < len {
+        if bytes[idx] == b'\\' {
+            idx += 2;
+            continue;
+        }
+        if bytes[idx..].starts_with(b"\"\"\"") {
+            return idx + 3;
+        }
+        idx += 1;
+    }
+    len
+}
+
+fn skip_verbatim_string(bytes: &[u8], len: usize, mut idx: usize) -> usize {
+    while idx < len {
+        if bytes[idx] == b'"' {
+            if idx + 1 < len && bytes[idx + 1] == b'"' {
+                idx += 2;
+                continue;
+            }
+            return idx + 1;
+        }
+        idx += 1;
+    }
+    len
+}
+
+fn is_raw_string_prefix(bytes: &[u8], quote: usize) -> bool {
+    if quote == 0 || bytes[quote - 1] != b'R' {
+        return false;
+    }
+    let mut start = quote - 1;
+    if start >= 2 && &bytes[start - 2..start] == b"u8" {
+        start -= 2;
+    } else if start >= 1 && matches!(bytes[start - 1], b'L' | b'u' | b'U') {
+        start -= 1;
+    }
+    start == 0 || !is_ident_byte(bytes[start - 1])
+}
+
+fn skip_raw_string(bytes: &[u8], len: usize, quote: usize) -> usize {
+    let Some(open) = bytes[quote + 1..len.min(quote + 18)]
+        .iter()
+        .position(|&b| b == b'(')
+        .map(|p| quote + 1 + p)
+    else {
+        return scan_string_literal(bytes, len, quote, b'"');
+    };
+    let mut closing = Vec::with_capacity(open - quote + 1);
+    closing.push(b')');
+    closing.extend_from_slice(&bytes[quote + 1..open]);
+    closing.push(b'"');
+    bytes[open..len]
+        .windows(closing.len())
+        .position(|w| w == closing.as_slice())
+        .map_or(len, |p| open + p + closing.len())
+}
+
+fn in_number_literal(bytes: &[u8], quote: usize) -> bool {
+    let mut start = quote;
+    while start > 0 && (is_ident_byte(bytes[start - 1]) || bytes[start - 1] == b'\'') {
+        start -= 1;
+    }
+    start < quote && bytes[start].is_ascii_digit()
+}
+
+/// End of the `/* ... */` comment whose body starts at `idx`; these don't nest.
+fn skip_block_comment(bytes: &[u8], len: usize, idx: usize) -> usize {
+    bytes[idx..len]
+        .windows(2)
+        .position(|w| w == b"*/")
+        .map_or(len, |p| idx + p + 2)
+}
+
+/// End of the `//` or `/* */` comment starting at `idx`, if one does.
+#[must_use]
+pub fn skip_slash_comment(bytes: &[u8], len: usize, idx: usize) -> Option<usize> {
+    if idx + 1 >= len || bytes[idx] != b'/' {
+        return None;
+    }
+    match bytes[idx + 1] {
+        b'/' => Some(skip_line_comment(bytes, len, idx + 2)),
+        b'*' => Some(skip_block_comment(bytes, len, idx + 2)),
+        _ => None,
+    }
+}
+
+/// Finds the end of a string literal starting at an index, for languages with `/* */`
+/// comments.
+pub type LiteralScanner = fn(&[u8], usize, usize) -> Option<usize>;
+
+/// Remove `//` and/or `/* */` comments outside literals; lines left blank by a removal are
+/// dropped.
+#[must_use]
+pub fn remove_slash_comments(
+    source: &str,
+    remove_line: bool,
+    remove_block: bool,
+    skip_literal: LiteralScanner,
+) -> String {
+    remove_marked_comments(source, b"//", remove_line, remove_block, skip_literal)
+}
+
+/// [`remove_slash_comments`] with `line_marker` (e.g. `--`) starting line comments.
+#[must_use]
+pub fn remove_marked_comments(
+    source: &str,
+    line_marker: &[u8],
+    remove_line: bool,
+    remove_block: bool,
+    skip_literal: LiteralScanner,
+) -> String {
+    let bytes = source.as_bytes();
+    let len = bytes.len();
+    let mut out = String::with_capacity(len);
+    // Output lines a comment was cut from; those left blank are dropped below.
+    let mut cut_lines: HashSet<usize> = HashSet::new();
+    let mut line = 0usize;
+    let mut last_emit = 0usize;
+    let mut idx = 0usize;
+    while idx < len {
+        let is_line = bytes[idx..].starts_with(line_marker);
+        let is_block = bytes[idx..].starts_with(b"/*");
+        if (is_line && remove_line) || (is_block && remove_block) {
+            out.push_str(&source[last_emit..idx]);
+            trim_trailing_ws_current_line(&mut out);
+            cut_lines.insert(line);
+            idx = if is_line {
+                // Keep the newline ending the comment.
+                bytes[idx..]
+                    .iter()
+                    .position(|&b| b == b'\n')
+                    .map_or(len, |p| idx + p)
+            } else {
+                skip_block_comment(bytes, len, idx + 2)
+            };
+            last_emit = idx;
+            continue;
+        }
+        let next = if is_line {
+            skip_line_comment(bytes, len, idx + line_marker.len())
+        } else if is_block {
+            skip_block_comment(bytes, len, idx + 2)
+        } else {
+            skip_literal(bytes, len, idx).unwrap_or(idx + 1)
+        };
+        #[allow(clippy::naive_bytecount)] // spans are short; not worth a bytecount dependency
+        let newlines = bytes[idx..next.min(len)]
+            .iter()
+            .filter(|&&b| b == b'\n')
+            .count();
+        line += newlines;
+        idx = next;
+    }
+    out.push_str(&source[last_emit..]);
+
+    let mut kept = String::with_capacity(out.len());
+    for (n, l) in out.split_inclusive('\n').enumerate() {
+        if l.trim().is_empty() && cut_lines.contains(&n) {
+            continue;
+        }
+        kept.push_str(l);
+    }
+    kept
+}
+
+/// Replaces function bodies with `;`, keeping everything else as written.
+struct SignatureReducer<'a> {
+    src: &'a str,
+    bytes: &'a [u8],
+    len: usize,
+    index: usize,
+    last_emit: usize,
+    /// Start of the declaration the next `{` belongs to.
+    stmt_start: usize,
+    output: String,
+}
+
+impl<'a> SignatureReducer<'a> {
+    fn new(src: &'a str) -> Self {
+        let bytes = src.as_bytes();
+        Self {
+            src,
+            bytes,
+            len: bytes.len(),
+            index: 0,
+            last_emit: 0,
+            stmt_start: 0,
+            output: String::with_capacity(bytes.len()),
+        }
+    }
+
+    fn run(mut self) -> String {
+        while self.index < self.len {
+            if let Some(next) = skip_slash_comment(self.bytes, self.len, self.index)
+                .or_else(|| skip_literal(self.bytes, self.len, self.index))
+            {
+                self.index = next;
+                continue;
+            }
+            match self.bytes[self.index] {
+                b'#' if self.at_line_start() => {
+                    self.index = skip_directive(self.bytes, self.len, self.index);
+                    self.stmt_start = self.index;
+                    continue;
+                }
+                b'{' if is_member_initializer(&self.bytes[self.stmt_start..self.index]) => {
+                    self.index =
+                        skip_braced_block(self.bytes, self.len, self.index + 1, skip_literal);
+                    continue;
+                }
+                b'{' if is_function_header(&self.bytes[self.stmt_start..self.index]) => {
+                    self.emit_signature();
+                    continue;
+                }
+                b'{' | b'}' | b';' => self.stmt_start = self.index + 1,
+                _ => {}
+            }
+            self.index += 1;
+        }
+        self.output.push_str(&self.src[self.last_emit..]);
+        self.output
+    }
+
+    fn at_line_start(&self) -> bool {
+        self.bytes[..self.index]
+            .iter()
+            .rev()
+            .find(|b| !matches!(b, b' ' | b'\t'))
+            .is_none_or(|&b| b == b'\n')
+    }
+
+    fn emit_signature(&mut self) {
+        let mut sig_end = self.index;
+        while sig_end > self.stmt_start && self.bytes[sig_end - 1].is_ascii_whitespace() {
+            sig_end -= 1;
+        }
+        self.output.push_str(&self.src[self.last_emit..sig_end]);
+        self.output.push_str(";\n");
+        let mut resume = skip_braced_block(self.bytes, self.len, self.index + 1, skip_literal);
+        while resume < self.len && matches!(self.bytes[resume], b' ' | b'\t') {
+            resume += 1;
+        }
+        if self.bytes[resume..].starts_with(b"\r\n") {
+            resume += 2;
+        } else if resume < self.len && self.bytes[resume] == b'\n' {
+            resume += 1;
+        }
+        self.last_emit = resume;
+        self.index = resume;
+        self.stmt_start = resume;
+    }
+}
+
+fn skip_directive(bytes: &[u8], len: usize, mut idx: usize) -> usize {
+    while idx < len && bytes[idx] != b'\n' {
+        if bytes[idx] == b'\\' && idx + 1 < len && bytes[idx + 1] == b'\n' {
+            idx += 1;
+        }
+        idx += 1;
+    }
+    idx
+}
+
+/// End of the `{ ... }` block whose contents start at `idx` (just past the `{`).
+#[must_use]
+pub fn skip_braced_block(
+    bytes: &[u8],
+    len: usize,
+    mut idx: usize,
+    skip_literal: LiteralScanner,
+) -> usize {
+    let mut depth = 1usize;
+    while idx < len && depth > 0 {
+        if let Some(next) =
+            skip_slash_comment(bytes, len, idx).or_else(|| skip_literal(bytes, len, idx))
+        {
+            idx = next;
+            continue;
+        }
+        match bytes[idx] {
+            b'{' => depth += 1,
+            b'}' => depth -= 1,
+            _ => {}
+        }
+        idx += 1;
+    }
+    idx.min(len)
+}
+
+/// Whether the text before a `{` declares a function: a name followed by a parenthesized
+/// parameter list, outside of brackets, not a control statement, type or initializer.
+fn is_function_header(header: &[u8]) -> bool {
+    let len = header.len();
+    let mut depth = 0i32;
+    let mut name: Option<&[u8]> = None;
+    let mut idx = 0usize;
+    while idx < len {
+        if let Some(next) =
+            skip_slash_comment(header, len, idx).or_else(|| skip_literal(header, len, idx))
+        {
+            idx = next;
+            continue;
+        }
+        let b = header[idx];
+        match b {
+            b'(' | b'[' => {
+                if b == b'(' && depth == 0 && name.is_none() {
+                    match word_before(header, idx) {
+                        Some((start, word)) if start == 0 || header[start - 1] != b'@' => {
+                            name = Some(word);
+                        }
+                        // Annotation arguments like `@Test(timeout = 1)`.
+                        Some(_) => {}
+                        // Generic methods like `T Max<T>(T a, T b)`.
+                        None if header[..idx].trim_ascii_end().ends_with(b">") => {
+                            name = Some(b">");
+                        }
+                        None => return false,
+                    }
+                }
+                depth += 1;
+            }
+            b')' | b']' => depth -= 1,
+            b'=' if depth == 0 && name.is_none() => {
+                return header.windows(8).any(|w| w == b"operator");
+            }
+            _ if depth == 0
+                && is_ident_byte(b)
+                && (idx == 0 || !is_ident_byte(header[idx - 1])) =>
+            {
+                let end = header[idx..]
+                    .iter()
+                    .position(|&c| !is_ident_byte(c))
+                    .map_or(len, |p| idx + p);
+                let word = std::str::from_utf8(&header[idx..end]).unwrap_or_default();
+                if TYPE_KEYWORDS.contains(&word) {
+                    return false;
+                }
+                idx = end;
+                continue;
+            }
+            _ => {}
+        }
+        idx += 1;
+    }
+    depth == 0
+        && name.is_some_and(|word| {
+            !CONTROL_KEYWORDS.contains(&std::str::from_utf8(word).unwrap_or_default())
+        })
+}
+
+/// Whether a `{` after `header` opens a brace initializer in a C++ constructor's member
+/// initializer list (`Foo::Foo(int x) : m_x{x}`) rather than the constructor's body.
+fn is_member_initializer(header: &[u8]) -> bool {
+    initializer_list_start(header).is_some_and(|colon| {
+        let before = header.trim_ascii_end();
+        before.len() > colon + 1
+            && before
+                .last()
+                .is_some_and(|&b| is_ident_byte(b) || b == b'>')
+    })
+}
+
+/// Index of the `:` after a function header's parameter list that starts a member
+/// initializer list (not `::`, nor a C# `where T : ...` constraint).
+fn initializer_list_start(header: &[u8]) -> Option<usize> {
+    let len = header.len();
+    let mut depth = 0i32;
+    let mut after_params = false;
+    let mut idx = 0usize;
+    while idx < len {
+        if let Some(next) =
+            skip_slash_comment(header, len, idx).or_else(|| skip_literal(header, len, idx))
+        {
+            idx = next;
+            continue;
+        }
+        match header[idx] {
+            b'(' | b'[' => depth += 1,
+            b')' | b']' => {
+                depth -= 1;
+                after_params |= depth == 0;
+            }
+            b':' if header.get(idx + 1) == Some(&b':') => idx += 1,
+            b':' if depth == 0 && after_params => {
+                let params = &header[..idx];
+                let constraint = params
+                    .split(|&b| !is_ident_byte(b))
+                    .any(|word| word == b"where");
+                return (!constraint && is_function_header(params)).then_some(idx);
+            }
+            _ => {}
+        }
+        idx += 1;
+    }
+    None
+}
+
+/// The identifier ending right before `idx` (whitespace allowed in between) and its start.
+fn word_before(bytes: &[u8], idx: usize) -> Option<(usize, &[u8])> {
+    let mut end = idx;
+    while end > 0 && bytes[end - 1].is_ascii_whitespace() {
+        end -= 1;
+    }
+    let mut start = end;
+    while start > 0 && is_ident_byte(bytes[start - 1]) {
+        start -= 1;
+    }
+    (start < end).then(|| (start, &bytes[start..end]))
+}
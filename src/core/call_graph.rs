@@ -0,0 +1,437 @@
+use std::{
+    collections::{BTreeSet, HashMap, HashSet, VecDeque},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use syn::visit::{self, Visit};
+
+/* =========================== Call-graph context ============================= */
+
+/// How far [`call_graph_context`] follows references by default.
+pub const DEFAULT_CALL_GRAPH_DEPTH: usize = 3;
+
+/// What a [`RustItem`] is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum RustItemKind {
+    Function,
+    Method,
+    Struct,
+    Enum,
+    Union,
+    Trait,
+    TypeAlias,
+    Const,
+    Static,
+    Macro,
+}
+
+impl RustItemKind {
+    #[must_use]
+    pub const fn keyword(self) -> &'static str {
+        match self {
+            Self::Function | Self::Method => "fn",
+            Self::Struct => "struct",
+            Self::Enum => "enum",
+            Self::Union => "union",
+            Self::Trait => "trait",
+            Self::TypeAlias => "type",
+            Self::Const => "const",
+            Self::Static => "static",
+            Self::Macro => "macro_rules!",
+        }
+    }
+}
+
+/// A name the code of an item mentions.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+enum Reference {
+    /// A path ending in this name: a free function, type, constant or macro.
+    Name(String),
+    /// `.name(...)` on some value.
+    Method(String),
+    /// `Owner::name`: an associated function, or `name` in module `Owner`.
+    Assoc(String, String),
+}
+
+/// A top-level, module or `impl` item of a project's Rust code.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RustItem {
+    pub name: String,
+    /// Type (or trait) of the `impl` block / trait a method belongs to.
+    pub owner: Option<String>,
+    pub kind: RustItemKind,
+    pub file: PathBuf,
+    references: BTreeSet<Reference>,
+}
+
+impl RustItem {
+    /// `Parser::parse` for methods, the name otherwise.
+    #[must_use]
+    pub fn qualified_name(&self) -> String {
+        self.owner.as_ref().map_or_else(
+            || self.name.clone(),
+            |owner| format!("{owner}::{}", self.name),
+        )
+    }
+}
+
+fn type_name(ty: &syn::Type) -> Option<String> {
+    match ty {
+        syn::Type::Path(p) => p.path.segments.last().map(|s| s.ident.to_string()),
+        syn::Type::Reference(r) => type_name(&r.elem),
+        syn::Type::Paren(p) => type_name(&p.elem),
+        syn::Type::Group(g) => type_name(&g.elem),
+        _ => None,
+    }
+}
+
+/// Collects the [`Reference`]s of one item, resolving `use ... as` renames and `Self`.
+struct ReferenceCollector<'a> {
+    aliases: &'a HashMap<String, String>,
+    owner: Option<&'a str>,
+    out: BTreeSet<Reference>,
+}
+
+impl ReferenceCollector<'_> {
+    fn resolve(&self, ident: &syn::Ident) -> String {
+        let name = ident.to_string();
+        if name == "Self"
+            && let Some(owner) = self.owner
+        {
+            return owner.to_string();
+        }
+        self.aliases.get(&name).cloned().unwrap_or(name)
+    }
+}
+
+impl<'ast> Visit<'ast> for ReferenceCollector<'_> {
+    fn visit_path(&mut self, path: &'ast syn::Path) {
+        let names: Vec<String> = path
+            .segments
+            .iter()
+            .map(|s| self.resolve(&s.ident))
+            .collect();
+        if let Some(last) = names.last() {
+            self.out.insert(Reference::Name(last.clone()));
+        }
+        if let [.., owner, name] = names.as_slice() {
+            self.out
+                .insert(Reference::Assoc(owner.clone(), name.clone()));
+            self.out.insert(Reference::Name(owner.clone()));
+        }
+        visit::visit_path(self, path);
+    }
+
+    fn visit_expr_method_call(&mut self, call: &'ast syn::ExprMethodCall) {
+        self.out.insert(Reference::Method(call.method.to_string()));
+        visit::visit_expr_method_call(self, call);
+    }
+
+    fn visit_macro(&mut self, mac: &'ast syn::Macro) {
+        // Macro arguments are plain tokens; parse the common expression-list form.
+        if let Ok(args) = mac.parse_body_with(
+            syn::punctuated::Punctuated::<syn::Expr, syn::Token![,]>::parse_terminated,
+        ) {
+            for arg in &args {
+                self.visit_expr(arg);
+            }
+        }
+        visit::visit_macro(self, mac);
+    }
+}
+
+/// `use a::b as c;` renames in `items` (inline modules included), as `c` → `b`.
+fn use_aliases(items: &[syn::Item], out: &mut HashMap<String, String>) {
+    fn tree(t: &syn::UseTree, out: &mut HashMap<String, String>) {
+        match t {
+            syn::UseTree::Path(p) => tree(&p.tree, out),
+            syn::UseTree::Rename(r) => {
+                out.insert(r.rename.to_string(), r.ident.to_string());
+            }
+            syn::UseTree::Group(g) => g.items.iter().for_each(|t| tree(t, out)),
+            syn::UseTree::Name(_) | syn::UseTree::Glob(_) => {}
+        }
+    }
+    for item in items {
+        match item {
+            syn::Item::Use(u) => tree(&u.tree, out),
+            syn::Item::Mod(m) => {
+                if let Some((_, inner)) = &m.content {
+                    use_aliases(inner, out);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+struct FileIndexer<'a> {
+    file: &'a Path,
+    aliases: HashMap<String, String>,
+    out: Vec<RustItem>,
+}
+
+impl FileIndexer<'_> {
+    fn push(
+        &mut self,
+        name: String,
+        owner: Option<String>,
+        kind: RustItemKind,
+        walk: impl FnOnce(&mut ReferenceCollector<'_>),
+    ) {
+        let mut collector = ReferenceCollector {
+            aliases: &self.aliases,
+            owner: owner.as_deref(),
+            out: BTreeSet::new(),
+        };
+        walk(&mut collector);
+        let references = collector.out;
+        self.out.push(RustItem {
+            name,
+            owner,
+            kind,
+            file: self.file.to_path_buf(),
+            references,
+        });
+    }
+
+    fn items(&mut self, items: &[syn::Item]) {
+        for item in items {
+            match item {
+                syn::Item::Fn(f) => {
+                    self.push(f.sig.ident.to_string(), None, RustItemKind::Function, |c| {
+                        c.visit_item_fn(f);
+                    });
+                }
+                syn::Item::Struct(s) => {
+                    self.push(s.ident.to_string(), None, RustItemKind::Struct, |c| {
+                        c.visit_item_struct(s);
+                    });
+                }
+                syn::Item::Enum(e) => {
+                    self.push(e.ident.to_string(), None, RustItemKind::Enum, |c| {
+                        c.visit_item_enum(e);
+                    });
+                }
+                syn::Item::Union(u) => {
+                    self.push(u.ident.to_string(), None, RustItemKind::Union, |c| {
+                        c.visit_item_union(u);
+                    });
+                }
+                syn::Item::Type(t) => {
+                    self.push(t.ident.to_string(), None, RustItemKind::TypeAlias, |c| {
+                        c.visit_item_type(t);
+                    });
+                }
+                syn::Item::Const(k) => {
+                    self.push(k.ident.to_string(), None, RustItemKind::Const, |c| {
+                        c.visit_item_const(k);
+                    });
+                }
+                syn::Item::Static(s) => {
+                    self.push(s.ident.to_string(), None, RustItemKind::Static, |c| {
+                        c.visit_item_static(s);
+                    });
+                }
+                syn::Item::Macro(m) => {
+                    if let Some(ident) = &m.ident {
+                        self.push(ident.to_string(), None, RustItemKind::Macro, |_| {});
+                    }
+                }
+                syn::Item::Trait(t) => {
+                    let owner = t.ident.to_string();
+                    self.push(owner.clone(), None, RustItemKind::Trait, |c| {
+                        c.visit_generics(&t.generics);
+                        t.supertraits
+                            .iter()
+                            .for_each(|b| c.visit_type_param_bound(b));
+                    });
+                    for inner in &t.items {
+                        if let syn::TraitItem::Fn(f) = inner {
+                            self.push(
+                                f.sig.ident.to_string(),
+                                Some(owner.clone()),
+                                RustItemKind::Method,
+                                |c| c.visit_trait_item_fn(f),
+                            );
+                        }
+                    }
+                }
+                syn::Item::Impl(imp) => {
+                    let Some(owner) = type_name(&imp.self_ty) else {
+                        continue;
+                    };
+                    for inner in &imp.items {
+                        if let syn::ImplItem::Fn(f) = inner {
+                            self.push(
+                                f.sig.ident.to_string(),
+                                Some(owner.clone()),
+                                RustItemKind::Method,
+                                |c| {
+                                    c.visit_impl_item_fn(f);
+                                    // The trait being implemented is context for the method.
+                                    if let Some((_, path, _)) = &imp.trait_ {
+                                        c.visit_path(path);
+                                    }
+                                },
+                            );
+                        }
+                    }
+                }
+                syn::Item::Mod(m) => {
+                    if let Some((_, inner)) = &m.content {
+                        self.items(inner);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// The items of one Rust source file; empty when it doesn't parse.
+#[must_use]
+pub fn index_rust_items(file: &Path, source: &str) -> Vec<RustItem> {
+    let Ok(parsed) = syn::parse_file(source) else {
+        return Vec::new();
+    };
+    let mut aliases = HashMap::new();
+    use_aliases(&parsed.items, &mut aliases);
+    let mut indexer = FileIndexer {
+        file,
+        aliases,
+        out: Vec::new(),
+    };
+    indexer.items(&parsed.items);
+    indexer.out
+}
+
+/// Functions and methods of `source` as offered for a call-graph start, e.g. `main`,
+/// `Parser::parse`, in source order.
+#[must_use]
+pub fn rust_function_names(source: &str) -> Vec<String> {
+    index_rust_items(Path::new(""), source)
+        .into_iter()
+        .filter(|i| matches!(i.kind, RustItemKind::Function | RustItemKind::Method))
+        .map(|i| i.qualified_name())
+        .collect()
+}
+
+/// An item reached from the start function.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContextItem {
+    pub item: RustItem,
+    /// Reference hops from the start function (0 for the function itself).
+    pub depth: usize,
+}
+
+/// Items reachable from a function, and the files that hold them.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CallGraphContext {
+    /// In the order they were reached (breadth first).
+    pub items: Vec<ContextItem>,
+    /// Files of `items`, start file first, without duplicates.
+    pub files: Vec<PathBuf>,
+}
+
+impl CallGraphContext {
+    /// One line per item, indented by depth: `  fn helper  (src/util.rs)`.
+    #[must_use]
+    pub fn render(&self, project_root: &Path) -> String {
+        use std::fmt::Write;
+
+        let mut out = String::new();
+        for ci in &self.items {
+            let file = ci
+                .item
+                .file
+                .strip_prefix(project_root)
+                .unwrap_or(&ci.item.file);
+            let _ = writeln!(
+                out,
+                "{}{} {}  ({})",
+                "  ".repeat(ci.depth),
+                ci.item.kind.keyword(),
+                ci.item.qualified_name(),
+                crate::core::path_to_unix(file)
+            );
+        }
+        out
+    }
+}
+
+/// Indices of the `items` `reference` may point at.
+fn resolve(items: &[RustItem], reference: &Reference) -> Vec<usize> {
+    let matching = |keep: &dyn Fn(&RustItem) -> bool| -> Vec<usize> {
+        (0..items.len()).filter(|&i| keep(&items[i])).collect()
+    };
+    let free = |name: &str| matching(&|i| i.owner.is_none() && i.name == name);
+    match reference {
+        Reference::Name(name) => free(name),
+        Reference::Method(name) => matching(&|i| i.kind == RustItemKind::Method && i.name == *name),
+        Reference::Assoc(owner, name) => {
+            let assoc =
+                matching(&|i| i.owner.as_deref() == Some(owner.as_str()) && i.name == *name);
+            if assoc.is_empty() { free(name) } else { assoc }
+        }
+    }
+}
+
+/// Items `function` (`name` or `Type::method`, defined in `start_file`) references,
+/// directly or through up to `max_depth` hops, among the Rust `files` of the project.
+///
+/// References are matched by name (following `use ... as` renames and `Self`), so
+/// methods called on a value match every project method of that name. `None` when
+/// `function` isn't in `start_file`.
+#[must_use]
+pub fn call_graph_context(
+    files: &[PathBuf],
+    start_file: &Path,
+    function: &str,
+    max_depth: usize,
+) -> Option<CallGraphContext> {
+    let mut items: Vec<RustItem> = Vec::new();
+    for file in files
+        .iter()
+        .chain(std::iter::once(&start_file.to_path_buf()))
+    {
+        if items.iter().any(|i| i.file == *file) {
+            continue;
+        }
+        if let Ok(source) = fs::read_to_string(file) {
+            items.extend(index_rust_items(file, &source));
+        }
+    }
+
+    let start = items.iter().position(|i| {
+        i.file == start_file
+            && matches!(i.kind, RustItemKind::Function | RustItemKind::Method)
+            && i.qualified_name() == function
+    })?;
+
+    let mut seen: HashSet<usize> = HashSet::from([start]);
+    let mut queue = VecDeque::from([(start, 0usize)]);
+    let mut context = CallGraphContext::default();
+    while let Some((index, depth)) = queue.pop_front() {
+        let item = &items[index];
+        if !context.files.contains(&item.file) {
+            context.files.push(item.file.clone());
+        }
+        context.items.push(ContextItem {
+            item: item.clone(),
+            depth,
+        });
+        if depth >= max_depth {
+            continue;
+        }
+        for reference in &item.references {
+            for target in resolve(&items, reference) {
+                if seen.insert(target) {
+                    queue.push_back((target, depth + 1));
+                }
+            }
+        }
+    }
+    Some(context)
+}
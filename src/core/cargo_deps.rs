@@ -0,0 +1,345 @@
+use std::{
+    fmt::Write,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use toml::{Table, Value};
+
+use crate::core::{ManifestSummary, path_to_unix};
+
+/* ============================ Dependency summary ============================ */
+
+/// One dependency entry of a Cargo manifest.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CargoDependency {
+    /// Name the crate is used under (the table key).
+    pub name: String,
+    /// Real package name when the dependency is renamed (`package = "..."`).
+    pub package: Option<String>,
+    pub version: Option<String>,
+    /// `path ...` or `git ...` (with branch/tag/rev) for non-registry dependencies.
+    pub source: Option<String>,
+    pub features: Vec<String>,
+    pub optional: bool,
+    pub default_features: bool,
+}
+
+impl CargoDependency {
+    /// One summary line, e.g. `serde 1.0 [derive] (optional)`.
+    #[must_use]
+    pub fn describe(&self) -> String {
+        let mut line = self.name.clone();
+        if let Some(version) = &self.version {
+            let _ = write!(line, " {version}");
+        }
+        if !self.features.is_empty() {
+            let _ = write!(line, " [{}]", self.features.join(", "));
+        }
+        let mut notes: Vec<String> = Vec::new();
+        if let Some(package) = &self.package {
+            notes.push(format!("package {package}"));
+        }
+        if let Some(source) = &self.source {
+            notes.push(source.clone());
+        }
+        if self.optional {
+            notes.push("optional".to_string());
+        }
+        if !self.default_features {
+            notes.push("no default features".to_string());
+        }
+        if !notes.is_empty() {
+            let _ = write!(line, " ({})", notes.join(", "));
+        }
+        line
+    }
+}
+
+/// Dependencies of one crate, grouped by manifest table.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct CrateManifest {
+    /// Project-relative manifest path using forward slashes, e.g. `crates/core/Cargo.toml`.
+    pub manifest: String,
+    pub name: Option<String>,
+    pub version: Option<String>,
+    /// `(table, dependencies)`, e.g. `("dev-dependencies", [...])`; empty tables are left out.
+    pub tables: Vec<(String, Vec<CargoDependency>)>,
+}
+
+impl CrateManifest {
+    #[must_use]
+    pub fn summary(&self) -> ManifestSummary {
+        ManifestSummary {
+            manifest: self.manifest.clone(),
+            name: self.name.clone(),
+            version: self.version.clone(),
+            groups: self
+                .tables
+                .iter()
+                .map(|(table, deps)| {
+                    (
+                        table.clone(),
+                        deps.iter().map(CargoDependency::describe).collect(),
+                    )
+                })
+                .collect(),
+        }
+    }
+}
+
+const DEPENDENCY_TABLES: [&str; 3] = ["dependencies", "dev-dependencies", "build-dependencies"];
+
+/// Parse a manifest's dependency tables.
+///
+/// `workspace_deps` is the root `[workspace.dependencies]` table used to resolve
+/// `workspace = true` entries (and `version.workspace = true` via `workspace_version`).
+///
+/// # Errors
+/// Returns the TOML parse error message.
+pub fn parse_cargo_manifest(
+    manifest: &str,
+    source: &str,
+    workspace_deps: Option<&Table>,
+    workspace_version: Option<&str>,
+) -> Result<CrateManifest, String> {
+    let doc: Table = source
+        .parse()
+        .map_err(|e: toml::de::Error| e.message().to_string())?;
+    let package = doc.get("package").and_then(Value::as_table);
+    let version = package
+        .and_then(|p| p.get("version"))
+        .and_then(|v| match v.as_str() {
+            Some(version) => Some(version.to_string()),
+            None if is_workspace_inherited(v) => workspace_version.map(str::to_string),
+            None => None,
+        });
+
+    let mut tables = Vec::new();
+    for key in DEPENDENCY_TABLES {
+        push_table(&mut tables, key.to_string(), doc.get(key), workspace_deps);
+    }
+    if let Some(targets) = doc.get("target").and_then(Value::as_table) {
+        for (target, spec) in targets {
+            for key in DEPENDENCY_TABLES {
+                let label = format!("target.'{target}'.{key}");
+                push_table(&mut tables, label, spec.get(key), workspace_deps);
+            }
+        }
+    }
+
+    Ok(CrateManifest {
+        manifest: manifest.to_string(),
+        name: package
+            .and_then(|p| p.get("name"))
+            .and_then(Value::as_str)
+            .map(str::to_string),
+        version,
+        tables,
+    })
+}
+
+fn push_table(
+    tables: &mut Vec<(String, Vec<CargoDependency>)>,
+    label: String,
+    table: Option<&Value>,
+    workspace_deps: Option<&Table>,
+) {
+    let Some(table) = table.and_then(Value::as_table) else {
+        return;
+    };
+    let deps: Vec<CargoDependency> = table
+        .iter()
+        .map(|(name, spec)| parse_dependency(name, spec, workspace_deps))
+        .collect();
+    if !deps.is_empty() {
+        tables.push((label, deps));
+    }
+}
+
+fn is_workspace_inherited(value: &Value) -> bool {
+    value
+        .as_table()
+        .and_then(|t| t.get("workspace"))
+        .and_then(Value::as_bool)
+        .unwrap_or(false)
+}
+
+fn parse_dependency(name: &str, spec: &Value, workspace_deps: Option<&Table>) -> CargoDependency {
+    let mut dep = CargoDependency {
+        name: name.to_string(),
+        package: None,
+        version: None,
+        source: None,
+        features: Vec::new(),
+        optional: false,
+        default_features: true,
+    };
+    let table = match spec {
+        Value::String(version) => {
+            dep.version = Some(version.clone());
+            return dep;
+        }
+        Value::Table(table) => table,
+        _ => return dep,
+    };
+
+    // `foo = { workspace = true, features = [...] }` starts from the workspace entry;
+    // features add up, `optional` is the member's own.
+    if is_workspace_inherited(spec)
+        && let Some(inherited) = workspace_deps.and_then(|ws| ws.get(name))
+    {
+        dep = parse_dependency(name, inherited, None);
+        dep.optional = false;
+    }
+
+    if let Some(package) = table.get("package").and_then(Value::as_str) {
+        dep.package = Some(package.to_string());
+    }
+    if let Some(version) = table.get("version").and_then(Value::as_str) {
+        dep.version = Some(version.to_string());
+    }
+    if let Some(source) = dependency_source(table) {
+        dep.source = Some(source);
+    }
+    if let Some(features) = table.get("features").and_then(Value::as_array) {
+        for feature in features.iter().filter_map(Value::as_str) {
+            if !dep.features.iter().any(|f| f == feature) {
+                dep.features.push(feature.to_string());
+            }
+        }
+    }
+    if let Some(optional) = table.get("optional").and_then(Value::as_bool) {
+        dep.optional = optional;
+    }
+    let default_features = table
+        .get("default-features")
+        .or_else(|| table.get("default_features"))
+        .and_then(Value::as_bool);
+    if let Some(default_features) = default_features {
+        dep.default_features = default_features;
+    }
+    dep
+}
+
+fn dependency_source(table: &Table) -> Option<String> {
+    if let Some(path) = table.get("path").and_then(Value::as_str) {
+        return Some(format!("path {path}"));
+    }
+    let git = table.get("git").and_then(Value::as_str)?;
+    let reference = ["branch", "tag", "rev"]
+        .into_iter()
+        .find_map(|key| Some((key, table.get(key)?.as_str()?)));
+    Some(match reference {
+        Some((key, value)) => format!("git {git} {key} {value}"),
+        None => format!("git {git}"),
+    })
+}
+
+/// Manifests of the project: the root `Cargo.toml` (if it has a `[package]`) followed by
+/// its workspace members, in `members` order (globs such as `crates/*` sorted by path).
+///
+/// Manifests that can't be parsed are reported as `Err((manifest, message))`.
+#[must_use]
+pub fn collect_cargo_manifests(
+    project_root: &Path,
+) -> Vec<Result<CrateManifest, (String, String)>> {
+    let root_manifest = project_root.join("Cargo.toml");
+    let Ok(root_source) = fs::read_to_string(&root_manifest) else {
+        return Vec::new();
+    };
+    let root_doc: Table = match root_source.parse() {
+        Ok(doc) => doc,
+        Err(e) => {
+            let e: toml::de::Error = e;
+            return vec![Err(("Cargo.toml".to_string(), e.message().to_string()))];
+        }
+    };
+
+    let workspace = root_doc.get("workspace").and_then(Value::as_table);
+    let workspace_deps = workspace
+        .and_then(|ws| ws.get("dependencies"))
+        .and_then(Value::as_table);
+    let workspace_version = workspace
+        .and_then(|ws| ws.get("package"))
+        .and_then(|p| p.get("version"))
+        .and_then(Value::as_str);
+
+    let mut manifests = Vec::new();
+    if root_doc.contains_key("package") {
+        manifests.push(
+            parse_cargo_manifest(
+                "Cargo.toml",
+                &root_source,
+                workspace_deps,
+                workspace_version,
+            )
+            .map_err(|e| ("Cargo.toml".to_string(), e)),
+        );
+    }
+    for dir in workspace_member_dirs(project_root, workspace) {
+        let path = dir.join("Cargo.toml");
+        let rel = path_to_unix(path.strip_prefix(project_root).unwrap_or(&path));
+        let Ok(source) = fs::read_to_string(&path) else {
+            continue;
+        };
+        manifests.push(
+            parse_cargo_manifest(&rel, &source, workspace_deps, workspace_version)
+                .map_err(|e| (rel, e)),
+        );
+    }
+    manifests
+}
+
+/// Member directories listed in `[workspace] members`, minus `exclude`.
+///
+/// A `*` in the last path component matches directory names (`crates/*`, `tools/x-*`).
+fn workspace_member_dirs(project_root: &Path, workspace: Option<&Table>) -> Vec<PathBuf> {
+    let patterns = |key: &str| -> Vec<String> {
+        workspace
+            .and_then(|ws| ws.get(key))
+            .and_then(Value::as_array)
+            .map(|a| {
+                a.iter()
+                    .filter_map(Value::as_str)
+                    .map(|s| s.trim_matches('/').to_string())
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    let excluded: Vec<PathBuf> = patterns("exclude")
+        .iter()
+        .map(|p| project_root.join(p))
+        .collect();
+
+    let mut dirs: Vec<PathBuf> = Vec::new();
+    for pattern in patterns("members") {
+        let (parent, last) = pattern.rsplit_once('/').unwrap_or(("", &pattern));
+        let mut matched: Vec<PathBuf> = if let Some((prefix, suffix)) = last.split_once('*') {
+            let Ok(entries) = fs::read_dir(project_root.join(parent)) else {
+                continue;
+            };
+            entries
+                .filter_map(Result::ok)
+                .filter(|e| {
+                    let name = e.file_name();
+                    let name = name.to_string_lossy();
+                    name.len() >= prefix.len() + suffix.len()
+                        && name.starts_with(prefix)
+                        && name.ends_with(suffix)
+                })
+                .map(|e| e.path())
+                .filter(|p| p.join("Cargo.toml").is_file())
+                .collect()
+        } else {
+            vec![project_root.join(&pattern)]
+        };
+        matched.sort();
+        for dir in matched {
+            if dir != project_root && !excluded.contains(&dir) && !dirs.contains(&dir) {
+                dirs.push(dir);
+            }
+        }
+    }
+    dirs
+}
@@ -0,0 +1,76 @@
+use std::fmt::Write;
+
+use sha2::{Digest, Sha256};
+
+/* ============================== Checksum footer ============================= */
+
+/// Header line of the footer [`append_checksum_footer`] adds.
+pub const CHECKSUM_FOOTER_HEADER: &str = "=== CHECKSUMS ===";
+
+const CONTENT_LINE_PREFIX: &str = "SHA-256 of everything above this section: ";
+
+/// Hashes read back from a checksum footer.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ChecksumFooter {
+    /// SHA-256 (lowercase hex) of the output above the footer.
+    pub content: String,
+    /// `(sha256, path)` of each file block, in output order.
+    pub files: Vec<(String, String)>,
+}
+
+/// Append a footer with the SHA-256 of `text` and of each `(path, block)` in `blocks`.
+///
+/// The path is as shown in the block's header and the block as generated, markers
+/// included; the footer has one `sha256sum`-style line per file.
+pub fn append_checksum_footer(text: &mut String, blocks: &[(&str, &str)]) {
+    let content = sha256_hex(text.as_bytes());
+    let _ = write!(
+        text,
+        "\n{CHECKSUM_FOOTER_HEADER}\n\n{CONTENT_LINE_PREFIX}{content}\n"
+    );
+    if !blocks.is_empty() {
+        text.push('\n');
+    }
+    for (path, block) in blocks {
+        let _ = writeln!(text, "{}  {path}", sha256_hex(block.as_bytes()));
+    }
+}
+
+/// The output above the footer and the footer's hashes; `None` without a footer.
+#[must_use]
+pub fn parse_checksum_footer(text: &str) -> Option<(&str, ChecksumFooter)> {
+    let start = text.rfind(&format!("\n{CHECKSUM_FOOTER_HEADER}\n"))?;
+    let mut footer = ChecksumFooter::default();
+    for line in text[start..].lines().skip(2) {
+        if let Some(hash) = line.strip_prefix(CONTENT_LINE_PREFIX) {
+            footer.content = hash.to_string();
+        } else if let Some((hash, path)) = line.split_once("  ") {
+            footer.files.push((hash.to_string(), path.to_string()));
+        }
+    }
+    Some((&text[..start], footer))
+}
+
+/// Check that the output above `text`'s checksum footer is what was generated.
+///
+/// # Errors
+/// Returns a one-line message when there is no footer or the content hash differs
+/// (the output was cut off or edited).
+pub fn verify_checksum_footer(text: &str) -> Result<ChecksumFooter, String> {
+    let (body, footer) = parse_checksum_footer(text).ok_or("no checksum footer")?;
+    if sha256_hex(body.as_bytes()) != footer.content {
+        return Err("checksum mismatch: the output was truncated or modified".to_string());
+    }
+    Ok(footer)
+}
+
+/// SHA-256 of `data` as 64 lowercase hex digits.
+#[must_use]
+pub fn sha256_hex(data: &[u8]) -> String {
+    Sha256::digest(data)
+        .iter()
+        .fold(String::with_capacity(64), |mut out, b| {
+            let _ = write!(out, "{b:02x}");
+            out
+        })
+}
@@ -0,0 +1,357 @@
+use std::{ffi::OsString, path::PathBuf};
+
+use clap::{
+    CommandFactory, Parser, Subcommand, ValueEnum, ValueHint,
+    builder::{NonEmptyStringValueParser, PossibleValue},
+    error::ErrorKind,
+};
+
+use crate::core::{OpenTarget, Platform, SchemaTarget, is_open_url, parse_open_target};
+
+/* ============================ Command-line launch ============================ */
+
+/// The command line of `stitch`. Help, completions and the man page are generated from it.
+// Doc comments of the arguments are the `--help` text, so they stay plain text.
+#[allow(clippy::doc_markdown)]
+#[derive(Debug, Parser)]
+#[command(
+    name = "stitch",
+    version,
+    about = "Select files from a project and stitch them into one text",
+    long_about = "Opens a window to browse PROJECT_DIR, check files and directories, and \
+                  generate one text with the file hierarchy and the selected files' contents, \
+                  ready to paste into an LLM prompt. Settings and profiles are kept in the \
+                  project's .stitchworkspace folder.",
+    args_conflicts_with_subcommands = true,
+    disable_help_subcommand = true
+)]
+struct Cli {
+    /// Folder to open on startup (write ./apply, ./man, ./run, ./stats or ./completions
+    /// for a folder named like a command)
+    #[arg(value_name = "PROJECT_DIR", value_hint = ValueHint::DirPath)]
+    project: Option<PathBuf>,
+    /// Open this folder, file:// URL or stitch://open?path=… URL
+    #[arg(long, value_name = "PATH|URL", value_hint = ValueHint::AnyPath)]
+    open: Option<String>,
+    /// Select this saved profile (requires PROJECT_DIR)
+    #[arg(long, global = true, value_name = "NAME", value_parser = NonEmptyStringValueParser::new())]
+    profile: Option<String>,
+    /// Check this project-relative file or folder (repeatable)
+    #[arg(long, value_name = "PATH", value_parser = NonEmptyStringValueParser::new())]
+    select: Vec<String>,
+    /// Uncheck this project-relative file or folder (repeatable)
+    #[arg(long, value_name = "PATH", value_parser = NonEmptyStringValueParser::new())]
+    deselect: Vec<String>,
+    /// Generate the output right after opening
+    #[arg(long)]
+    generate: bool,
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[allow(clippy::doc_markdown)]
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Write the file blocks of an LLM response (a file, or - for stdin) to PROJECT_DIR
+    /// (default: .) after showing what changes, reviewed in the local merge_tool if set
+    Apply {
+        #[arg(value_name = "RESPONSE", value_hint = ValueHint::FilePath)]
+        response: PathBuf,
+        #[arg(value_name = "PROJECT_DIR", value_hint = ValueHint::DirPath)]
+        project: Option<PathBuf>,
+        /// Write without asking for confirmation
+        #[arg(long)]
+        yes: bool,
+    },
+    /// Print a completion script for bash, zsh or fish
+    Completions {
+        #[arg(value_name = "SHELL")]
+        shell: Shell,
+    },
+    /// Print the "Open with Stitch" registration for macos, windows or linux
+    Integrate {
+        #[arg(value_name = "PLATFORM")]
+        platform: Platform,
+    },
+    /// Print the man page
+    Man,
+    /// Run a task from .stitchworkspace/tasks.toml of PROJECT_DIR (default: .) and print
+    /// its output unless it writes a file
+    Run {
+        #[arg(value_name = "TASK", value_parser = NonEmptyStringValueParser::new())]
+        task: String,
+        #[arg(value_name = "PROJECT_DIR", value_hint = ValueHint::DirPath)]
+        project: Option<PathBuf>,
+    },
+    /// Print the JSON Schema of workspace.json or profile files
+    Schema {
+        #[arg(value_name = "FILE")]
+        target: SchemaTarget,
+    },
+    /// Print file, line and size statistics of PROJECT_DIR (default: .) under its saved
+    /// filters or --profile
+    Stats {
+        #[arg(value_name = "PROJECT_DIR", value_hint = ValueHint::DirPath)]
+        project: Option<PathBuf>,
+    },
+}
+
+/// Shells `stitch completions` writes scripts for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+impl Shell {
+    pub const ALL: [Self; 3] = [Self::Bash, Self::Zsh, Self::Fish];
+
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Bash => "bash",
+            Self::Zsh => "zsh",
+            Self::Fish => "fish",
+        }
+    }
+
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|shell| shell.name() == name)
+    }
+}
+
+impl From<Shell> for clap_complete::Shell {
+    fn from(shell: Shell) -> Self {
+        match shell {
+            Shell::Bash => Self::Bash,
+            Shell::Zsh => Self::Zsh,
+            Shell::Fish => Self::Fish,
+        }
+    }
+}
+
+impl ValueEnum for Platform {
+    fn value_variants<'a>() -> &'a [Self] {
+        &Self::ALL
+    }
+
+    fn to_possible_value(&self) -> Option<PossibleValue> {
+        Some(PossibleValue::new(self.name()))
+    }
+}
+
+impl ValueEnum for SchemaTarget {
+    fn value_variants<'a>() -> &'a [Self] {
+        &Self::ALL
+    }
+
+    fn to_possible_value(&self) -> Option<PossibleValue> {
+        Some(PossibleValue::new(self.name()))
+    }
+}
+
+/// A command that prints something and exits instead of opening a window.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CliCommand {
+    /// Apply the response file (`-` for stdin) to `LaunchOptions::project` (the current
+    /// directory if unset); `yes` skips the confirmation.
+    Apply {
+        response: PathBuf,
+        yes: bool,
+    },
+    Completions(Shell),
+    Integrate(Platform),
+    Man,
+    /// The named task of `LaunchOptions::project` (the current directory if unset).
+    Run(String),
+    Schema(SchemaTarget),
+    /// Project statistics of `LaunchOptions::project` (the current directory if unset).
+    Stats,
+}
+
+/// What the app should do on startup, as requested on the command line.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LaunchOptions {
+    pub project: Option<PathBuf>,
+    pub profile: Option<String>,
+    /// Project-relative paths to check / uncheck after the profile is applied.
+    pub select: Vec<String>,
+    pub deselect: Vec<String>,
+    pub generate: bool,
+    pub help: bool,
+    pub version: bool,
+    pub command: Option<CliCommand>,
+}
+
+/// The `--help` text.
+#[must_use]
+pub fn usage() -> String {
+    Cli::command().render_help().to_string()
+}
+
+/// Parse the arguments after the program name.
+///
+/// # Errors
+/// Returns a one-line message for unknown flags, missing values, extra positionals,
+/// unknown shells, or `--profile` without a project directory.
+pub fn parse_launch_args<I, S>(args: I) -> Result<LaunchOptions, String>
+where
+    I: IntoIterator<Item = S>,
+    S: Into<OsString>,
+{
+    // Process serial number that macOS Finder may pass to app bundles.
+    let args = args
+        .into_iter()
+        .map(Into::into)
+        .filter(|arg: &OsString| !arg.to_str().is_some_and(|a| a.starts_with("-psn_")));
+    let cli = match Cli::try_parse_from(std::iter::once(OsString::from("stitch")).chain(args)) {
+        Ok(cli) => cli,
+        Err(e) if e.kind() == ErrorKind::DisplayHelp => {
+            return Ok(LaunchOptions {
+                help: true,
+                ..LaunchOptions::default()
+            });
+        }
+        Err(e) if e.kind() == ErrorKind::DisplayVersion => {
+            return Ok(LaunchOptions {
+                version: true,
+                ..LaunchOptions::default()
+            });
+        }
+        Err(e) => return Err(error_line(&e)),
+    };
+
+    let mut opts = LaunchOptions {
+        profile: cli.profile,
+        generate: cli.generate,
+        ..LaunchOptions::default()
+    };
+    for path in cli.select {
+        push_selection_path(&mut opts, true, path);
+    }
+    for path in cli.deselect {
+        push_selection_path(&mut opts, false, path);
+    }
+    if let Some(project) = cli.project {
+        // URL handlers registered without `--open` pass the URL on its own.
+        match project.to_str().filter(|arg| is_open_url(arg)) {
+            Some(url) => set_open_target(&mut opts, parse_open_target(url)?)?,
+            None => opts.project = Some(project),
+        }
+    }
+    if let Some(open) = cli.open {
+        set_open_target(&mut opts, parse_open_target(&open)?)?;
+    }
+    let command = cli.command.map(|command| match command {
+        Command::Apply {
+            response,
+            project,
+            yes,
+        } => {
+            opts.project = project;
+            CliCommand::Apply { response, yes }
+        }
+        Command::Completions { shell } => CliCommand::Completions(shell),
+        Command::Integrate { platform } => CliCommand::Integrate(platform),
+        Command::Man => CliCommand::Man,
+        Command::Run { task, project } => {
+            opts.project = project;
+            CliCommand::Run(task)
+        }
+        Command::Schema { target } => CliCommand::Schema(target),
+        Command::Stats { project } => {
+            opts.project = project;
+            CliCommand::Stats
+        }
+    });
+    opts.command = command;
+
+    if matches!(opts.command, Some(CliCommand::Run(_))) && opts.profile.is_some() {
+        return Err("run only takes TASK and PROJECT_DIR".into());
+    }
+    if matches!(opts.command, Some(CliCommand::Apply { .. })) && opts.profile.is_some() {
+        return Err("apply only takes RESPONSE, PROJECT_DIR and --yes".into());
+    }
+    if opts.project.is_none()
+        && !matches!(
+            opts.command,
+            Some(CliCommand::Stats | CliCommand::Run(_) | CliCommand::Apply { .. })
+        )
+    {
+        if opts.profile.is_some() {
+            return Err("--profile requires a project directory".into());
+        }
+        if !opts.select.is_empty() || !opts.deselect.is_empty() {
+            return Err("--select and --deselect require a project directory".into());
+        }
+    }
+    Ok(opts)
+}
+
+/// The first paragraph of clap's message on one line, without the `error: ` prefix.
+fn error_line(e: &clap::Error) -> String {
+    let text = e.render().to_string();
+    let first = text.split("\n\n").next().unwrap_or_default();
+    let line = first.split_whitespace().collect::<Vec<_>>().join(" ");
+    line.strip_prefix("error: ").unwrap_or(&line).to_string()
+}
+
+/// Record a `--select`/`--deselect` path with forward slashes and without `./` or a
+/// trailing slash, as profiles store them.
+fn push_selection_path(opts: &mut LaunchOptions, select: bool, path: String) {
+    let path = if cfg!(windows) {
+        path.replace('\\', "/")
+    } else {
+        path
+    };
+    let path = path.trim_start_matches("./").trim_end_matches('/');
+    let path = if path == "." { "" } else { path }.to_string();
+    if select {
+        opts.select.push(path);
+    } else {
+        opts.deselect.push(path);
+    }
+}
+
+/// Take the folder from `--open`; a profile or generate flag on the command line wins
+/// over the URL's.
+fn set_open_target(opts: &mut LaunchOptions, target: OpenTarget) -> Result<(), String> {
+    if opts.project.is_some() {
+        return Err(format!(
+            "unexpected argument '{}'",
+            target.path.to_string_lossy()
+        ));
+    }
+    opts.project = Some(target.path);
+    if opts.profile.is_none() {
+        opts.profile = target.profile;
+    }
+    opts.generate |= target.generate;
+    Ok(())
+}
+
+/* ========================= Completions and man page ========================= */
+
+/// Completion script for `shell`, generated from the command line definition.
+#[must_use]
+pub fn render_completions(shell: Shell) -> String {
+    let mut out = Vec::new();
+    clap_complete::generate(
+        clap_complete::Shell::from(shell),
+        &mut Cli::command(),
+        "stitch",
+        &mut out,
+    );
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// `stitch(1)` man page in roff, generated from the command line definition.
+#[must_use]
+pub fn render_man_page(version: &'static str) -> String {
+    let mut out = Vec::new();
+    // Writing to a Vec can't fail.
+    let _ = clap_mangen::Man::new(Cli::command().version(version)).render(&mut out);
+    String::from_utf8_lossy(&out).into_owned()
+}
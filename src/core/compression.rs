@@ -0,0 +1,107 @@
+use std::{
+    io::{self, Cursor, Write},
+    path::Path,
+};
+
+use flate2::{Compression, write::GzEncoder};
+use zip::{CompressionMethod, DateTime, ZipWriter, write::SimpleFileOptions};
+
+/* ============================ Compressed export ============================= */
+
+/// Name of the output inside a `.zip` export.
+pub const ZIP_OUTPUT_ENTRY: &str = "stitch.md";
+
+/// Name of the selection tree inside a `.zip` export.
+pub const ZIP_HIERARCHY_ENTRY: &str = "hierarchy.txt";
+
+/// Container written by "Export Compressed".
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompressedFormat {
+    /// The output alone as `.md.gz`.
+    Gzip,
+    /// The output and the hierarchy as two entries of a `.zip`.
+    Zip,
+}
+
+impl CompressedFormat {
+    /// `Zip` for a `.zip` path (any case), `Gzip` otherwise.
+    #[must_use]
+    pub fn from_path(path: &Path) -> Self {
+        let is_zip = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("zip"));
+        if is_zip { Self::Zip } else { Self::Gzip }
+    }
+
+    /// Suggested file name for an export of this format.
+    #[must_use]
+    pub const fn default_file_name(self) -> &'static str {
+        match self {
+            Self::Gzip => "stitch.md.gz",
+            Self::Zip => "stitch.zip",
+        }
+    }
+}
+
+/// `output` compressed as `format`; `hierarchy` becomes [`ZIP_HIERARCHY_ENTRY`] in a
+/// `.zip` and is left out of a `.gz`.
+///
+/// # Errors
+/// From the encoder, or when a `.zip` would need Zip64 (over 4 GiB).
+pub fn compress_bundle(
+    format: CompressedFormat,
+    output: &str,
+    hierarchy: Option<&str>,
+) -> io::Result<Vec<u8>> {
+    match format {
+        CompressedFormat::Gzip => gzip_text(output),
+        CompressedFormat::Zip => {
+            let mut entries = vec![(ZIP_OUTPUT_ENTRY, output)];
+            if let Some(tree) = hierarchy {
+                entries.push((ZIP_HIERARCHY_ENTRY, tree));
+            }
+            zip_entries(&entries)
+        }
+    }
+}
+
+/// Bytes [`compress_bundle`] would write, to check against attachment size limits.
+///
+/// # Errors
+/// As [`compress_bundle`].
+pub fn compressed_size(
+    format: CompressedFormat,
+    output: &str,
+    hierarchy: Option<&str>,
+) -> io::Result<usize> {
+    compress_bundle(format, output, hierarchy).map(|bytes| bytes.len())
+}
+
+/// `text` as a gzip stream.
+///
+/// # Errors
+/// From the encoder.
+pub fn gzip_text(text: &str) -> io::Result<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(text.as_bytes())?;
+    encoder.finish()
+}
+
+/// A `.zip` archive of deflated `(name, contents)` entries.
+///
+/// Every entry is dated 1980-01-01, so the same input gives the same archive.
+///
+/// # Errors
+/// From the encoder, or when the archive would need Zip64 (over 4 GiB).
+pub fn zip_entries(entries: &[(&str, &str)]) -> io::Result<Vec<u8>> {
+    let options = SimpleFileOptions::default()
+        .compression_method(CompressionMethod::Deflated)
+        .last_modified_time(DateTime::default());
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    for (name, contents) in entries {
+        zip.start_file(*name, options).map_err(io::Error::other)?;
+        zip.write_all(contents.as_bytes())?;
+    }
+    Ok(zip.finish().map_err(io::Error::other)?.into_inner())
+}
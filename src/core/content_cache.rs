@@ -0,0 +1,244 @@
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::core::{
+    CLikeFilterOptions, GoFilterOptions, RustFilterOptions, SlintFilterOptions, SqlFilterOptions,
+    TextQuirks, TransformOptions, TransformPipeline, WebFilterOptions, workspace_dir,
+};
+
+/* ============================== Content cache =============================== */
+
+/// Total size of cached contents above which entries the last generation didn't use are
+/// dropped.
+pub const CONTENT_CACHE_MAX_BYTES: usize = 64 * 1024 * 1024;
+
+/// Modification time and size of a file when it was read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileStamp {
+    pub modified: SystemTime,
+    pub len: u64,
+}
+
+impl FileStamp {
+    /// `None` if the metadata can't be read or has no modification time.
+    #[must_use]
+    pub fn of(path: &Path) -> Option<Self> {
+        let meta = fs::metadata(path).ok()?;
+        Some(Self {
+            modified: meta.modified().ok()?,
+            len: meta.len(),
+        })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedFile {
+    stamp: FileStamp,
+    contents: String,
+    /// BOM and line endings of the file as read.
+    #[serde(default)]
+    quirks: TextQuirks,
+    /// Generation that last used the entry.
+    #[serde(skip)]
+    used: u64,
+}
+
+/// Emitted (transformed) contents of text files.
+///
+/// Entries are kept while each file's [`FileStamp`] and the transform configuration stay
+/// the same, so regenerating after a small edit only re-reads and re-filters the edited files.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ContentCache {
+    /// [`transform_config_key`] the entries were produced with.
+    config: u64,
+    entries: HashMap<PathBuf, CachedFile>,
+    #[serde(skip)]
+    generation: u64,
+    /// Entries were added or dropped since [`ContentCache::take_changed`].
+    #[serde(skip)]
+    changed: bool,
+}
+
+impl ContentCache {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start a generation using transform configuration `config`; entries produced with
+    /// another configuration are dropped.
+    pub fn begin(&mut self, config: u64) {
+        if self.config != config {
+            self.changed |= !self.entries.is_empty();
+            self.entries.clear();
+            self.config = config;
+        }
+        self.generation += 1;
+    }
+
+    /// Cached contents of `path` if it was cached at `stamp`.
+    pub fn get(&mut self, path: &Path, stamp: FileStamp) -> Option<String> {
+        let entry = self.entries.get_mut(path)?;
+        if entry.stamp != stamp {
+            return None;
+        }
+        entry.used = self.generation;
+        Some(entry.contents.clone())
+    }
+
+    pub fn insert(&mut self, path: PathBuf, stamp: FileStamp, contents: String) {
+        self.insert_with_quirks(path, stamp, contents, TextQuirks::default());
+    }
+
+    /// [`Self::insert`], remembering what the file looked like before normalizing.
+    pub fn insert_with_quirks(
+        &mut self,
+        path: PathBuf,
+        stamp: FileStamp,
+        contents: String,
+        quirks: TextQuirks,
+    ) {
+        let used = self.generation;
+        self.entries.insert(
+            path,
+            CachedFile {
+                stamp,
+                contents,
+                quirks,
+                used,
+            },
+        );
+        self.changed = true;
+    }
+
+    /// Quirks stored with `path`'s entry; none if it isn't cached.
+    #[must_use]
+    pub fn quirks(&self, path: &Path) -> TextQuirks {
+        self.entries.get(path).map(|e| e.quirks).unwrap_or_default()
+    }
+
+    /// When the cached contents exceed `max_bytes`, drop the entries the current
+    /// generation didn't use.
+    pub fn trim(&mut self, max_bytes: usize) {
+        if self.total_bytes() <= max_bytes {
+            return;
+        }
+        let generation = self.generation;
+        let before = self.entries.len();
+        self.entries.retain(|_, entry| entry.used == generation);
+        self.changed |= self.entries.len() != before;
+    }
+
+    /// Bytes of cached contents.
+    #[must_use]
+    pub fn total_bytes(&self) -> usize {
+        self.entries.values().map(|e| e.contents.len()).sum()
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Whether entries were added or dropped since the last call.
+    pub const fn take_changed(&mut self) -> bool {
+        std::mem::replace(&mut self.changed, false)
+    }
+}
+
+/// Everything besides a file's contents that shapes its emitted text, in the form that is
+/// hashed into [`transform_config_key`].
+#[derive(Serialize)]
+struct TransformConfig<'a> {
+    pipeline: &'a TransformPipeline,
+    remove_prefixes: &'a [String],
+    remove_regex: Option<&'a str>,
+    rust: &'a RustFilterOptions,
+    rust_signatures_filter: &'a str,
+    slint: &'a SlintFilterOptions,
+    c_like: &'a CLikeFilterOptions,
+    go: &'a GoFilterOptions,
+    web: &'a WebFilterOptions,
+    sql: &'a SqlFilterOptions,
+    allow_commands: bool,
+    normalize_text: bool,
+    working_dir: &'a Path,
+    line_numbers: bool,
+}
+
+/// Hash of everything besides a file's contents that shapes its emitted text.
+///
+/// That is the pipeline, the filter options and whether lines are numbered. The key is persisted with
+/// the cache, so it is a SHA-256 of the serialized configuration rather than a
+/// [`std::hash::Hash`] value, which may change between builds.
+#[must_use]
+pub fn transform_config_key(
+    pipeline: &TransformPipeline,
+    opts: &TransformOptions,
+    line_numbers: bool,
+) -> u64 {
+    let config = TransformConfig {
+        pipeline,
+        remove_prefixes: &opts.remove_prefixes,
+        remove_regex: opts.remove_regex.as_ref().map(regex::Regex::as_str),
+        rust: &opts.rust,
+        rust_signatures_filter: &opts.rust_signatures_filter,
+        slint: &opts.slint,
+        c_like: &opts.c_like,
+        go: &opts.go,
+        web: &opts.web,
+        sql: &opts.sql,
+        allow_commands: opts.allow_commands,
+        normalize_text: opts.normalize_text,
+        working_dir: &opts.working_dir,
+        line_numbers,
+    };
+    let digest = Sha256::digest(serde_json::to_vec(&config).unwrap_or_default());
+    let mut key = [0; 8];
+    key.copy_from_slice(&digest[..8]);
+    u64::from_le_bytes(key)
+}
+
+#[must_use]
+pub fn content_cache_file(project_root: &Path) -> PathBuf {
+    workspace_dir(project_root)
+        .join("local")
+        .join("content-cache.json")
+}
+
+/// The project's saved content cache; empty if there is none or it can't be read.
+#[must_use]
+pub fn load_content_cache(project_root: &Path) -> ContentCache {
+    fs::read(content_cache_file(project_root))
+        .ok()
+        .and_then(|data| serde_json::from_slice(&data).ok())
+        .unwrap_or_default()
+}
+
+/// Write `cache` to [`content_cache_file`].
+///
+/// # Errors
+/// Returns I/O errors from writing/renaming the file, or serialization errors (e.g. for
+/// non-UTF-8 paths).
+pub fn save_content_cache(project_root: &Path, cache: &ContentCache) -> io::Result<()> {
+    let path = content_cache_file(project_root);
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let data = serde_json::to_vec(cache).map_err(|e| io::Error::other(e.to_string()))?;
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, data)?;
+    fs::rename(&tmp, &path)
+}
@@ -0,0 +1,202 @@
+use std::{
+    fs,
+    ops::Range,
+    path::{Path, PathBuf},
+    thread,
+};
+
+use regex::{Regex, RegexBuilder};
+
+use crate::core::path_to_unix;
+
+/* ========================= Select by file content =========================== */
+
+/// Compiled search of file contents; see [`ContentQuery::new`].
+#[derive(Debug, Clone)]
+pub struct ContentQuery {
+    re: Regex,
+}
+
+impl ContentQuery {
+    /// Search for `pattern`, as a regex or literally, optionally ignoring case.
+    ///
+    /// # Errors
+    /// A one-line reason when `pattern` is empty or not a valid regex.
+    pub fn new(pattern: &str, regex: bool, case_sensitive: bool) -> Result<Self, String> {
+        if pattern.is_empty() {
+            return Err("empty search".to_string());
+        }
+        let source = if regex {
+            pattern.to_string()
+        } else {
+            regex::escape(pattern)
+        };
+        RegexBuilder::new(&source)
+            .case_insensitive(!case_sensitive)
+            .multi_line(true)
+            .build()
+            .map(|re| Self { re })
+            .map_err(|e| match e {
+                regex::Error::Syntax(msg) => {
+                    let last = msg.lines().last().unwrap_or_default().trim();
+                    format!("invalid regex: {}", last.trim_start_matches("error: "))
+                }
+                other => other.to_string(),
+            })
+    }
+
+    /// Non-overlapping matches in `text`.
+    #[must_use]
+    pub fn count(&self, text: &str) -> usize {
+        self.re.find_iter(text).count()
+    }
+
+    /// Byte ranges of the non-empty, non-overlapping matches in `text`.
+    #[must_use]
+    pub fn find_ranges(&self, text: &str) -> Vec<Range<usize>> {
+        self.re
+            .find_iter(text)
+            .filter(|m| !m.is_empty())
+            .map(|m| m.range())
+            .collect()
+    }
+}
+
+/// Byte range of at most `max_chars` chars of `text` around `range`, more if the match
+/// itself is longer.
+///
+/// The excerpt starts a third of the way before the match and is trimmed to whole lines
+/// where that doesn't cut into the match.
+#[must_use]
+pub fn excerpt_around(text: &str, range: Range<usize>, max_chars: usize) -> Range<usize> {
+    let before = max_chars / 3;
+    let mut start = text[..range.start]
+        .char_indices()
+        .rev()
+        .take(before)
+        .last()
+        .map_or(range.start, |(i, _)| i);
+    if start > 0 {
+        start = text[start..range.start]
+            .find('\n')
+            .map_or(start, |i| start + i + 1);
+    }
+    let mut end = text[start..]
+        .char_indices()
+        .nth(max_chars)
+        .map_or(text.len(), |(i, _)| start + i)
+        .max(range.end);
+    if end < text.len() {
+        end = text[range.end..end]
+            .rfind('\n')
+            .map_or(end, |i| range.end + i + 1);
+    }
+    start..end
+}
+
+/// A file with at least one match.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContentMatch {
+    pub path: PathBuf,
+    pub matches: usize,
+}
+
+/// What [`search_file_contents`] found.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ContentSearchReport {
+    /// Matching files, in the order they were given.
+    pub files: Vec<ContentMatch>,
+    /// Files read and searched.
+    pub searched: usize,
+    /// Files that couldn't be read as UTF-8 text.
+    pub skipped: usize,
+}
+
+impl ContentSearchReport {
+    #[must_use]
+    pub fn total_matches(&self) -> usize {
+        self.files.iter().map(|f| f.matches).sum()
+    }
+
+    #[must_use]
+    pub fn paths(&self) -> Vec<PathBuf> {
+        self.files.iter().map(|f| f.path.clone()).collect()
+    }
+
+    /// `12 matches in 3 of 40 files (2 unreadable)`, then one `  N  path` line per file.
+    #[must_use]
+    pub fn render(&self, project_root: &Path) -> String {
+        use std::fmt::Write;
+
+        let mut out = format!(
+            "{} {} in {} of {} files",
+            self.total_matches(),
+            if self.total_matches() == 1 {
+                "match"
+            } else {
+                "matches"
+            },
+            self.files.len(),
+            self.searched
+        );
+        if self.skipped > 0 {
+            let _ = write!(out, " ({} unreadable)", self.skipped);
+        }
+        out.push('\n');
+        for f in &self.files {
+            let rel = f
+                .path
+                .strip_prefix(project_root)
+                .map_or_else(|_| path_to_unix(&f.path), path_to_unix);
+            let _ = writeln!(out, "{:>6}  {rel}", f.matches);
+        }
+        out
+    }
+}
+
+/// Search `files` for `query` on up to one thread per core. Files that aren't UTF-8
+/// text count as skipped.
+///
+/// # Panics
+/// Re-raises a panic of a search thread.
+#[must_use]
+pub fn search_file_contents(files: &[PathBuf], query: &ContentQuery) -> ContentSearchReport {
+    let threads = thread::available_parallelism().map_or(1, usize::from);
+    let chunk = files.len().div_ceil(threads).max(1);
+
+    let results: Vec<Option<usize>> = thread::scope(|scope| {
+        // Collected so every thread is started before the first join.
+        #[allow(clippy::needless_collect)]
+        let handles: Vec<_> = files
+            .chunks(chunk)
+            .map(|part| {
+                scope.spawn(move || {
+                    part.iter()
+                        .map(|path| fs::read_to_string(path).ok().map(|t| query.count(&t)))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|h| h.join().expect("content search thread"))
+            .collect()
+    });
+
+    let mut report = ContentSearchReport::default();
+    for (path, result) in files.iter().zip(results) {
+        match result {
+            None => report.skipped += 1,
+            Some(matches) => {
+                report.searched += 1;
+                if matches > 0 {
+                    report.files.push(ContentMatch {
+                        path: path.clone(),
+                        matches,
+                    });
+                }
+            }
+        }
+    }
+    report
+}
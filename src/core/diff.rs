@@ -0,0 +1,181 @@
+use std::fmt::Write;
+
+/* ================================ Line diff ================================= */
+
+/// One line of a line-by-line diff.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffLine<'a> {
+    Same(&'a str),
+    Removed(&'a str),
+    Added(&'a str),
+}
+
+/// Above this many edits the changed middle is reported as removed then added
+/// instead of being aligned line by line.
+pub const MAX_DIFF_EDITS: usize = 2000;
+
+/// Line diff turning `old` into `new` (Myers' algorithm, shortest edit script).
+#[must_use]
+pub fn diff_lines<'a>(old: &'a str, new: &'a str) -> Vec<DiffLine<'a>> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+
+    // Unchanged head and tail are common for outputs of the same project.
+    let prefix = old.iter().zip(&new).take_while(|(o, n)| o == n).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(o, n)| o == n)
+        .count();
+    let changed_old = &old[prefix..old.len() - suffix];
+    let changed_new = &new[prefix..new.len() - suffix];
+
+    let mut out: Vec<DiffLine<'a>> = old[..prefix].iter().map(|l| DiffLine::Same(l)).collect();
+    if let Some(middle) = shortest_edit(changed_old, changed_new) {
+        out.extend(middle);
+    } else {
+        out.extend(changed_old.iter().map(|l| DiffLine::Removed(l)));
+        out.extend(changed_new.iter().map(|l| DiffLine::Added(l)));
+    }
+    out.extend(old[old.len() - suffix..].iter().map(|l| DiffLine::Same(l)));
+    out
+}
+
+/// Myers' greedy algorithm; `None` if the edit distance exceeds [`MAX_DIFF_EDITS`].
+///
+/// `x` indexes `old`, `y` indexes `new`; diagonals `x - y` are stored shifted by
+/// `offset` so indices stay unsigned.
+fn shortest_edit<'a>(old: &[&'a str], new: &[&'a str]) -> Option<Vec<DiffLine<'a>>> {
+    let max = old.len() + new.len();
+    let offset = max + 1;
+    // Furthest `x` reached on each diagonal.
+    let mut furthest = vec![0usize; 2 * max + 3];
+    // trace[edits] holds furthest[offset - edits - 1 ..= offset + edits + 1] before that step.
+    let mut trace: Vec<Vec<usize>> = Vec::new();
+
+    for edits in 0..=max.min(MAX_DIFF_EDITS) {
+        trace.push(furthest[offset - edits - 1..=offset + edits + 1].to_vec());
+        for diag in (offset - edits..=offset + edits).step_by(2) {
+            let down = diag == offset - edits
+                || (diag != offset + edits && furthest[diag - 1] < furthest[diag + 1]);
+            let mut x = if down {
+                furthest[diag + 1]
+            } else {
+                furthest[diag - 1] + 1
+            };
+            let mut y = x + offset - diag;
+            while x < old.len() && y < new.len() && old[x] == new[y] {
+                x += 1;
+                y += 1;
+            }
+            furthest[diag] = x;
+            if x >= old.len() && y >= new.len() {
+                return Some(backtrack(old, new, &trace, offset));
+            }
+        }
+    }
+    None
+}
+
+fn backtrack<'a>(
+    old: &[&'a str],
+    new: &[&'a str],
+    trace: &[Vec<usize>],
+    offset: usize,
+) -> Vec<DiffLine<'a>> {
+    let (mut x, mut y) = (old.len(), new.len());
+    let mut out = Vec::new();
+    for (edits, furthest) in trace.iter().enumerate().skip(1).rev() {
+        // `furthest` starts at diagonal offset - edits - 1.
+        let at = |diag: usize| furthest[diag + edits + 1 - offset];
+        let diag = x + offset - y;
+        let down =
+            diag == offset - edits || (diag != offset + edits && at(diag - 1) < at(diag + 1));
+        let prev_diag = if down { diag + 1 } else { diag - 1 };
+        let prev_x = at(prev_diag);
+        let prev_y = prev_x + offset - prev_diag;
+        while x > prev_x && y > prev_y {
+            x -= 1;
+            y -= 1;
+            out.push(DiffLine::Same(old[x]));
+        }
+        if x == prev_x {
+            y -= 1;
+            out.push(DiffLine::Added(new[y]));
+        } else {
+            x -= 1;
+            out.push(DiffLine::Removed(old[x]));
+        }
+        (x, y) = (prev_x, prev_y);
+    }
+    // Step 0 is the snake from the origin along the main diagonal.
+    out.extend(old[..x].iter().rev().map(|l| DiffLine::Same(l)));
+    out.reverse();
+    out
+}
+
+/// Unified-style diff of two outputs with `context` unchanged lines around each change:
+/// `@@ -old_start,old_len +new_start,new_len @@` hunks of ` `, `-` and `+` lines.
+///
+/// Empty if the texts have the same lines.
+#[must_use]
+pub fn render_diff(old: &str, new: &str, context: usize) -> String {
+    let lines = diff_lines(old, new);
+    let changed: Vec<usize> = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, l)| !matches!(l, DiffLine::Same(_)))
+        .map(|(i, _)| i)
+        .collect();
+
+    // Merge changes whose context windows touch into one hunk.
+    let mut hunks: Vec<(usize, usize)> = Vec::new();
+    for &i in &changed {
+        let start = i.saturating_sub(context);
+        let end = (i + context + 1).min(lines.len());
+        match hunks.last_mut() {
+            Some(last) if start <= last.1 => last.1 = end,
+            _ => hunks.push((start, end)),
+        }
+    }
+
+    // 1-based line numbers of each diff line's position in the old and new text.
+    let mut positions = Vec::with_capacity(lines.len());
+    let (mut old_line, mut new_line) = (1, 1);
+    for line in &lines {
+        positions.push((old_line, new_line));
+        match line {
+            DiffLine::Same(_) => {
+                old_line += 1;
+                new_line += 1;
+            }
+            DiffLine::Removed(_) => old_line += 1,
+            DiffLine::Added(_) => new_line += 1,
+        }
+    }
+
+    let mut out = String::new();
+    for (start, end) in hunks {
+        let hunk = &lines[start..end];
+        let old_len = hunk
+            .iter()
+            .filter(|l| !matches!(l, DiffLine::Added(_)))
+            .count();
+        let new_len = hunk
+            .iter()
+            .filter(|l| !matches!(l, DiffLine::Removed(_)))
+            .count();
+        let (old_start, new_start) = positions[start];
+        let _ = writeln!(out, "@@ -{old_start},{old_len} +{new_start},{new_len} @@");
+        for line in hunk {
+            let (mark, text) = match line {
+                DiffLine::Same(t) => (' ', t),
+                DiffLine::Removed(t) => ('-', t),
+                DiffLine::Added(t) => ('+', t),
+            };
+            let _ = writeln!(out, "{mark}{text}");
+        }
+    }
+    out
+}
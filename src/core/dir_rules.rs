@@ -0,0 +1,62 @@
+use serde::{Deserialize, Serialize};
+
+/* ========================== Per-directory overrides ========================= */
+
+/// What a directory override does to the directory and everything below it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DirRuleKind {
+    /// Leave the directory out of the tree, whatever the global filters say.
+    Exclude,
+    /// Show every file below the directory, ignoring the extension filters.
+    IgnoreExtensionFilters,
+}
+
+/// Path-scoped scan override, saved with the profile.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct DirectoryRule {
+    /// Project-relative directory path using forward slashes ("" = project root).
+    pub path: String,
+    pub kind: DirRuleKind,
+}
+
+impl DirectoryRule {
+    /// Short label used for tree badges, e.g. "all extensions".
+    #[must_use]
+    pub const fn describe(&self) -> &'static str {
+        match self.kind {
+            DirRuleKind::Exclude => "excluded",
+            DirRuleKind::IgnoreExtensionFilters => "all extensions",
+        }
+    }
+
+    /// Whether the rule applies to project-relative `rel` (the directory itself or below).
+    #[must_use]
+    pub fn covers(&self, rel: &str) -> bool {
+        is_rel_path_within(rel, &self.path)
+    }
+}
+
+/// Whether project-relative `rel` is `dir` or below it ("" = project root).
+#[must_use]
+pub fn is_rel_path_within(rel: &str, dir: &str) -> bool {
+    dir.is_empty()
+        || rel
+            .strip_prefix(dir)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+}
+
+/// The override in effect for project-relative `rel` (forward slashes).
+///
+/// An exclusion anywhere above wins, since nothing below an excluded directory is scanned.
+#[must_use]
+pub fn dir_rule_for(rules: &[DirectoryRule], rel: &str) -> Option<DirRuleKind> {
+    let mut found = None;
+    for rule in rules.iter().filter(|rule| rule.covers(rel)) {
+        if rule.kind == DirRuleKind::Exclude {
+            return Some(DirRuleKind::Exclude);
+        }
+        found = Some(rule.kind);
+    }
+    found
+}
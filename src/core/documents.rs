@@ -0,0 +1,174 @@
+use std::{path::Path, sync::OnceLock};
+
+use regex::Regex;
+
+use crate::core::estimate_file_tokens;
+
+/* ========================= PDF/DOCX text extraction ========================= */
+
+/// Extensions whose text is extracted (with the `doc-extract` feature) instead of read.
+pub const DOCUMENT_EXTENSIONS: [&str; 2] = ["pdf", "docx"];
+
+/// Larger documents are skipped rather than parsed.
+pub const DOCUMENT_MAX_BYTES: u64 = 32 * 1024 * 1024;
+
+/// Extracted text is cut after this many estimated tokens.
+pub const DOCUMENT_MAX_TOKENS: usize = 20_000;
+
+/// Text pulled out of a document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtractedDocument {
+    pub text: String,
+    /// The text was cut at the token cap.
+    pub truncated: bool,
+}
+
+/// Whether `path` has one of [`DOCUMENT_EXTENSIONS`] (any case).
+#[must_use]
+pub fn is_document_path(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|ext| {
+            DOCUMENT_EXTENSIONS
+                .iter()
+                .any(|d| d.eq_ignore_ascii_case(ext))
+        })
+}
+
+/// Keep whole lines of `text` while they fit in `max_tokens` (estimated with `path`'s
+/// ratio); the flag says whether anything was cut.
+#[must_use]
+pub fn cap_document_text(path: &str, text: &str, max_tokens: usize) -> (String, bool) {
+    let mut out = String::new();
+    let mut tokens = 0;
+    for line in text.split_inclusive('\n') {
+        tokens += estimate_file_tokens(path, line);
+        if tokens > max_tokens {
+            return (out, true);
+        }
+        out.push_str(line);
+    }
+    (out, false)
+}
+
+fn docx_tokens() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"<(/?)([A-Za-z][\w:.-]*)[^>]*?(/?)>|([^<]+)").expect("docx token regex")
+    })
+}
+
+/// Plain text of a DOCX `word/document.xml`: the runs' text, one line per paragraph,
+/// with tabs and line breaks kept.
+#[must_use]
+pub fn docx_text_from_xml(xml: &str) -> String {
+    let mut out = String::new();
+    let mut in_text = false;
+    for cap in docx_tokens().captures_iter(xml) {
+        if let Some(text) = cap.get(4) {
+            if in_text {
+                out.push_str(&unescape_xml(text.as_str()));
+            }
+            continue;
+        }
+        let closing = !cap[1].is_empty();
+        let self_closing = !cap[3].is_empty();
+        match &cap[2] {
+            "w:t" => in_text = !closing && !self_closing,
+            "w:p" if closing => out.push('\n'),
+            "w:tab" if !closing => out.push('\t'),
+            "w:br" | "w:cr" if !closing => out.push('\n'),
+            _ => {}
+        }
+    }
+    out
+}
+
+fn unescape_xml(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_string();
+    }
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let Some(semi) = rest.find(';') else { break };
+        let entity = &rest[1..semi];
+        let decoded = match entity {
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "amp" => Some('&'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => entity
+                .strip_prefix("#x")
+                .map(|hex| u32::from_str_radix(hex, 16))
+                .or_else(|| entity.strip_prefix('#').map(str::parse::<u32>))
+                .and_then(Result::ok)
+                .and_then(char::from_u32),
+        };
+        if let Some(c) = decoded {
+            out.push(c);
+            rest = &rest[semi + 1..];
+        } else {
+            out.push('&');
+            rest = &rest[1..];
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Extract the text of the PDF or DOCX file at `path`, cut to `max_tokens`.
+///
+/// # Errors
+/// Returns a short message when the file is larger than [`DOCUMENT_MAX_BYTES`], can't be
+/// read, or isn't a document the extractors understand.
+#[cfg(feature = "doc-extract")]
+pub fn extract_document(path: &Path, max_tokens: usize) -> Result<ExtractedDocument, String> {
+    use std::io::Read;
+
+    let size = std::fs::metadata(path).map_err(|e| e.to_string())?.len();
+    if size > DOCUMENT_MAX_BYTES {
+        return Err(format!(
+            "document larger than {} MiB",
+            DOCUMENT_MAX_BYTES / (1024 * 1024)
+        ));
+    }
+    let bytes = std::fs::read(path).map_err(|e| e.to_string())?;
+    let is_pdf = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("pdf"));
+    let text = if is_pdf {
+        // The PDF parser panics on some malformed files; treat that like any other failure.
+        std::panic::catch_unwind(|| pdf_extract::extract_text_from_mem(&bytes))
+            .map_err(|_| "could not parse PDF".to_string())?
+            .map_err(|e| format!("could not parse PDF: {e}"))?
+    } else {
+        let mut archive = zip::ZipArchive::new(std::io::Cursor::new(bytes))
+            .map_err(|e| format!("not a DOCX file: {e}"))?;
+        let mut xml = String::new();
+        archive
+            .by_name("word/document.xml")
+            .map_err(|e| format!("not a DOCX file: {e}"))?
+            .read_to_string(&mut xml)
+            .map_err(|e| e.to_string())?;
+        docx_text_from_xml(&xml)
+    };
+    let (text, truncated) = cap_document_text(&path.to_string_lossy(), &text, max_tokens);
+    Ok(ExtractedDocument { text, truncated })
+}
+
+/// Without the `doc-extract` feature documents can't be read.
+///
+/// # Errors
+/// Always.
+#[cfg(not(feature = "doc-extract"))]
+pub fn extract_document(path: &Path, _max_tokens: usize) -> Result<ExtractedDocument, String> {
+    Err(format!(
+        "{} needs the `doc-extract` feature",
+        path.display()
+    ))
+}
@@ -0,0 +1,98 @@
+use std::{
+    cell::{Cell, RefCell},
+    collections::VecDeque,
+    rc::Rc,
+};
+
+use crate::core::SelectionStats;
+
+/// A change in the open workspace, emitted by the orchestration and handled by front ends
+/// (the window, the CLI, ...).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WorkspaceEvent {
+    /// Files were checked or unchecked.
+    SelectionChanged,
+    /// Filter settings changed; the tree has to be rescanned.
+    FiltersChanged,
+    /// A generation finished with `output`; `stats` has the size of each emitted file.
+    GenerationFinished {
+        output: String,
+        stats: SelectionStats,
+    },
+    /// A generation failed; `message` says why and is shown in place of the output.
+    GenerationFailed { message: String },
+}
+
+/// Returned by [`EventBus::subscribe`], to unsubscribe later.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SubscriptionId(u64);
+
+type Subscriber = Rc<dyn Fn(&WorkspaceEvent)>;
+
+/// Delivers [`WorkspaceEvent`]s to subscribers, in subscription order.
+///
+/// Events emitted while one is being delivered (e.g. from a subscriber) are queued and
+/// delivered afterwards, so subscribers never run re-entrantly.
+#[derive(Default)]
+pub struct EventBus {
+    subscribers: RefCell<Vec<(SubscriptionId, Subscriber)>>,
+    queue: RefCell<VecDeque<WorkspaceEvent>>,
+    dispatching: Cell<bool>,
+    next_id: Cell<u64>,
+}
+
+impl EventBus {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn subscribe(&self, subscriber: impl Fn(&WorkspaceEvent) + 'static) -> SubscriptionId {
+        let id = SubscriptionId(self.next_id.get());
+        self.next_id.set(id.0 + 1);
+        self.subscribers
+            .borrow_mut()
+            .push((id, Rc::new(subscriber)));
+        id
+    }
+
+    /// Returns false if `id` was not subscribed.
+    pub fn unsubscribe(&self, id: SubscriptionId) -> bool {
+        let mut subscribers = self.subscribers.borrow_mut();
+        let before = subscribers.len();
+        subscribers.retain(|(sid, _)| *sid != id);
+        subscribers.len() != before
+    }
+
+    pub fn emit(&self, event: WorkspaceEvent) {
+        self.queue.borrow_mut().push_back(event);
+        if self.dispatching.replace(true) {
+            return;
+        }
+        let _reset = DispatchGuard(&self.dispatching);
+        loop {
+            let Some(event) = self.queue.borrow_mut().pop_front() else {
+                break;
+            };
+            // Subscribers may (un)subscribe while the event is delivered.
+            let subscribers: Vec<Subscriber> = self
+                .subscribers
+                .borrow()
+                .iter()
+                .map(|(_, s)| Rc::clone(s))
+                .collect();
+            for subscriber in subscribers {
+                subscriber(&event);
+            }
+        }
+    }
+}
+
+/// Clears the dispatching flag even if a subscriber panics.
+struct DispatchGuard<'a>(&'a Cell<bool>);
+
+impl Drop for DispatchGuard<'_> {
+    fn drop(&mut self) {
+        self.0.set(false);
+    }
+}
@@ -0,0 +1,203 @@
+use std::collections::HashMap;
+
+use crate::core::Node;
+
+/* ============================ Exclude suggestions =========================== */
+
+/// Which exclude setting a suggestion adds to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExcludeTarget {
+    /// A directory name for *Exclude Directories*.
+    Directory,
+    /// An extension for the extension filter (added as `-.ext`).
+    Extension,
+}
+
+/// Why something is suggested.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SuggestionReason {
+    /// Build output, caches, minified or generated files.
+    Noise,
+    /// A directory holding a large share of the project's files.
+    Large,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExcludeSuggestion {
+    pub target: ExcludeTarget,
+    /// Directory name (`dist`) or extension (`.min.js`).
+    pub pattern: String,
+    /// Files in the tree the exclusion would hide.
+    pub files: usize,
+    pub reason: SuggestionReason,
+}
+
+impl ExcludeSuggestion {
+    /// Short label for the UI, e.g. `dist/ (120 files)` or `*.min.js (3 files)`.
+    #[must_use]
+    pub fn label(&self) -> String {
+        let noun = if self.files == 1 { "file" } else { "files" };
+        match self.target {
+            ExcludeTarget::Directory => format!("{}/ ({} {noun})", self.pattern, self.files),
+            ExcludeTarget::Extension => format!("*{} ({} {noun})", self.pattern, self.files),
+        }
+    }
+
+    /// The token to add to the matching filter field (`dist` or `-.min.js`).
+    #[must_use]
+    pub fn filter_token(&self) -> String {
+        match self.target {
+            ExcludeTarget::Directory => self.pattern.clone(),
+            ExcludeTarget::Extension => format!("-{}", self.pattern),
+        }
+    }
+}
+
+/// Directory names that are almost always build output, caches or tool state.
+pub const NOISE_DIR_NAMES: &[&str] = &[
+    "dist",
+    "build",
+    "out",
+    "coverage",
+    ".nyc_output",
+    ".next",
+    ".nuxt",
+    ".turbo",
+    ".cache",
+    ".parcel-cache",
+    ".pytest_cache",
+    ".mypy_cache",
+    ".ruff_cache",
+    ".tox",
+    ".gradle",
+    "htmlcov",
+];
+
+/// File suffixes of minified, bundled or generated files.
+pub const NOISE_EXTENSIONS: &[&str] = &[".min.js", ".min.css", ".map", ".snap", ".pyc"];
+
+/// Directories are suggested as large once they hold this many files...
+pub const LARGE_DIR_MIN_FILES: usize = 50;
+/// ...and at least this percentage of the project's files.
+pub const LARGE_DIR_MIN_PERCENT: usize = 25;
+/// At most this many large directories are suggested.
+pub const MAX_LARGE_DIR_SUGGESTIONS: usize = 3;
+
+/// Exclusions worth offering for a scanned tree: noise directories and file patterns
+/// found in it (most files first), then its largest directories.
+///
+/// The tree is the scan result, so anything already excluded is not in it and never
+/// suggested. Files inside a noise directory only count towards that directory.
+#[must_use]
+pub fn suggest_excludes(root: &Node) -> Vec<ExcludeSuggestion> {
+    let mut tally = Tally::default();
+    let total: usize = root
+        .children
+        .iter()
+        .map(|child| tally.visit(child, false))
+        .sum();
+
+    let mut noise_dirs: HashMap<&str, usize> = HashMap::new();
+    let mut large: Vec<(&Node, usize)> = Vec::new();
+    for &(dir, files, inside_noise) in &tally.dirs {
+        if files == 0 || inside_noise {
+            continue;
+        }
+        if is_noise_dir(dir) {
+            *noise_dirs.entry(dir.name.as_str()).or_default() += files;
+        } else if files >= LARGE_DIR_MIN_FILES && files * 100 >= total * LARGE_DIR_MIN_PERCENT {
+            large.push((dir, files));
+        }
+    }
+
+    let mut suggestions: Vec<ExcludeSuggestion> = Vec::new();
+    for (target, counts) in [
+        (ExcludeTarget::Directory, noise_dirs),
+        (ExcludeTarget::Extension, tally.noise_exts),
+    ] {
+        suggestions.extend(sorted_by_files(counts).into_iter().map(|(pattern, files)| {
+            ExcludeSuggestion {
+                target,
+                pattern: pattern.to_string(),
+                files,
+                reason: SuggestionReason::Noise,
+            }
+        }));
+    }
+
+    // Largest first; a directory inside one already suggested adds nothing.
+    large.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.path.cmp(&b.0.path)));
+    let mut picked: Vec<&Node> = Vec::new();
+    for (dir, files) in large {
+        if picked.len() == MAX_LARGE_DIR_SUGGESTIONS {
+            break;
+        }
+        if picked.iter().any(|p| dir.path.starts_with(&p.path))
+            || suggestions.iter().any(|s| s.pattern == dir.name)
+        {
+            continue;
+        }
+        picked.push(dir);
+        suggestions.push(ExcludeSuggestion {
+            target: ExcludeTarget::Directory,
+            pattern: dir.name.clone(),
+            files,
+            reason: SuggestionReason::Large,
+        });
+    }
+    suggestions
+}
+
+fn is_noise_dir(node: &Node) -> bool {
+    NOISE_DIR_NAMES.contains(&node.name.as_str())
+}
+
+#[derive(Default)]
+struct Tally<'a> {
+    /// Every directory with its file count and whether it sits inside a noise directory.
+    dirs: Vec<(&'a Node, usize, bool)>,
+    noise_exts: HashMap<&'static str, usize>,
+}
+
+impl<'a> Tally<'a> {
+    /// Files below `node` (1 for a file).
+    fn visit(&mut self, node: &'a Node, inside_noise: bool) -> usize {
+        if !node.is_dir {
+            let name = node.name.to_lowercase();
+            if !inside_noise
+                && let Some(ext) = NOISE_EXTENSIONS.iter().find(|ext| name.ends_with(**ext))
+            {
+                *self.noise_exts.entry(ext).or_default() += 1;
+            }
+            return 1;
+        }
+        let below_noise = inside_noise || is_noise_dir(node);
+        let files = node
+            .children
+            .iter()
+            .map(|child| self.visit(child, below_noise))
+            .sum();
+        self.dirs.push((node, files, inside_noise));
+        files
+    }
+}
+
+fn sorted_by_files(counts: HashMap<&str, usize>) -> Vec<(&str, usize)> {
+    let mut counts: Vec<(&str, usize)> = counts.into_iter().collect();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+    counts
+}
+
+/// Add `token` to a comma-separated filter field unless it's already listed.
+#[must_use]
+pub fn append_filter_token(raw: &str, token: &str) -> String {
+    let trimmed = raw.trim().trim_end_matches(',').trim_end();
+    if trimmed.split(',').any(|t| t.trim() == token) {
+        return raw.to_string();
+    }
+    if trimmed.is_empty() {
+        token.to_string()
+    } else {
+        format!("{trimmed}, {token}")
+    }
+}
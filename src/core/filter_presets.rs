@@ -0,0 +1,152 @@
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::WorkspaceSettings;
+
+/* ============================== Filter presets ============================== */
+
+/// Named set of filter fields, stored per user and usable in any project or profile.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct FilterPreset {
+    pub name: String,
+    #[serde(default)]
+    pub ext_filter: String,
+    #[serde(default)]
+    pub exclude_dirs: String,
+    #[serde(default)]
+    pub exclude_files: String,
+    #[serde(default)]
+    pub remove_prefix: String,
+}
+
+impl FilterPreset {
+    /// Capture the filter fields of `settings` under `name`.
+    #[must_use]
+    pub fn from_settings(name: &str, settings: &WorkspaceSettings) -> Self {
+        Self {
+            name: name.trim().to_string(),
+            ext_filter: settings.ext_filter.clone(),
+            exclude_dirs: settings.exclude_dirs.clone(),
+            exclude_files: settings.exclude_files.clone(),
+            remove_prefix: settings.remove_prefix.clone(),
+        }
+    }
+
+    /// Overwrite the filter fields of `settings`; everything else is left alone.
+    pub fn apply_to(&self, settings: &mut WorkspaceSettings) {
+        settings.ext_filter.clone_from(&self.ext_filter);
+        settings.exclude_dirs.clone_from(&self.exclude_dirs);
+        settings.exclude_files.clone_from(&self.exclude_files);
+        settings.remove_prefix.clone_from(&self.remove_prefix);
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+struct FilterPresetsFile {
+    version: u32,
+    presets: Vec<FilterPreset>,
+}
+
+/// Per-user configuration directory of stitch.
+///
+/// `STITCH_CONFIG_DIR` wins if set; otherwise the platform's config location
+/// (`%APPDATA%\stitch`, `~/Library/Application Support/stitch`, or
+/// `$XDG_CONFIG_HOME/stitch` falling back to `~/.config/stitch`).
+#[must_use]
+pub fn user_config_dir() -> Option<PathBuf> {
+    let var = |name: &str| {
+        env::var_os(name)
+            .filter(|v| !v.is_empty())
+            .map(PathBuf::from)
+    };
+    if let Some(dir) = var("STITCH_CONFIG_DIR") {
+        return Some(dir);
+    }
+    let base = if cfg!(windows) {
+        var("APPDATA")?
+    } else if cfg!(target_os = "macos") {
+        var("HOME")?.join("Library").join("Application Support")
+    } else {
+        var("XDG_CONFIG_HOME").or_else(|| var("HOME").map(|home| home.join(".config")))?
+    };
+    Some(base.join("stitch"))
+}
+
+#[must_use]
+pub fn filter_presets_file(config_dir: &Path) -> PathBuf {
+    config_dir.join("filter_presets.json")
+}
+
+/// Presets saved in `config_dir`, sorted by name (case-insensitive).
+///
+/// A missing or unreadable file yields no presets.
+#[must_use]
+pub fn load_filter_presets(config_dir: &Path) -> Vec<FilterPreset> {
+    let Ok(data) = fs::read(filter_presets_file(config_dir)) else {
+        return Vec::new();
+    };
+    let mut presets = serde_json::from_slice::<FilterPresetsFile>(&data)
+        .map(|file| file.presets)
+        .unwrap_or_default();
+    sort_presets(&mut presets);
+    presets
+}
+
+/// Save `preset`, replacing any preset with the same name (case-insensitive).
+///
+/// # Errors
+/// Returns I/O errors from writing/renaming files, an `InvalidInput` error for an
+/// empty name, or serialization errors.
+pub fn save_filter_preset(config_dir: &Path, preset: &FilterPreset) -> io::Result<()> {
+    let name = preset.name.trim();
+    if name.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "preset name is empty",
+        ));
+    }
+    let mut presets = load_filter_presets(config_dir);
+    presets.retain(|p| !p.name.eq_ignore_ascii_case(name));
+    presets.push(FilterPreset {
+        name: name.to_string(),
+        ..preset.clone()
+    });
+    write_presets(config_dir, presets)
+}
+
+/// Delete the preset called `name` (case-insensitive) if it exists.
+///
+/// # Errors
+/// Returns I/O errors from writing/renaming files, or serialization errors.
+pub fn delete_filter_preset(config_dir: &Path, name: &str) -> io::Result<()> {
+    let mut presets = load_filter_presets(config_dir);
+    let before = presets.len();
+    presets.retain(|p| !p.name.eq_ignore_ascii_case(name.trim()));
+    if presets.len() == before {
+        return Ok(());
+    }
+    write_presets(config_dir, presets)
+}
+
+fn sort_presets(presets: &mut [FilterPreset]) {
+    presets.sort_by_cached_key(|p| p.name.to_lowercase());
+}
+
+fn write_presets(config_dir: &Path, mut presets: Vec<FilterPreset>) -> io::Result<()> {
+    fs::create_dir_all(config_dir)?;
+    sort_presets(&mut presets);
+    let path = filter_presets_file(config_dir);
+    let tmp = path.with_extension("json.tmp");
+    let file = FilterPresetsFile {
+        version: 1,
+        presets,
+    };
+    let data = serde_json::to_vec_pretty(&file).map_err(|e| io::Error::other(e.to_string()))?;
+    fs::write(&tmp, data)?;
+    fs::rename(&tmp, &path)?;
+    Ok(())
+}
@@ -1,4 +1,6 @@
-use crate::core::Node;
+use crate::core::{
+    DirRuleKind, DirectoryRule, IgnoreRules, NameMatching, NameSet, Node, dir_rule_for,
+};
 use std::{
     collections::{HashMap, HashSet},
     ffi::OsString,
@@ -46,6 +48,16 @@ pub fn path_to_unix(p: &Path) -> String {
     s
 }
 
+/// Absolute path of a project-relative, forward-slash path ("" = the project root).
+#[must_use]
+pub fn project_path_from_rel(root: &Path, rel: &str) -> PathBuf {
+    if rel.is_empty() {
+        root.to_path_buf()
+    } else {
+        root.join(rel.replace('/', std::path::MAIN_SEPARATOR_STR))
+    }
+}
+
 #[must_use]
 pub fn is_ancestor_of(ancestor: &Path, p: &Path) -> bool {
     // Try to canonicalize both paths first
@@ -64,6 +76,29 @@ pub fn is_ancestor_of(ancestor: &Path, p: &Path) -> bool {
     pp_canon.starts_with(&anc_canon)
 }
 
+/// Re-root `path` from `old_root` to the same relative location under `new_root`
+/// (used when a project folder was moved). Paths outside `old_root` are returned unchanged.
+#[must_use]
+pub fn rebase_path(path: &Path, old_root: &Path, new_root: &Path) -> PathBuf {
+    match path.strip_prefix(old_root) {
+        Ok(rel) if rel.as_os_str().is_empty() => new_root.to_path_buf(),
+        Ok(rel) => new_root.join(rel),
+        Err(_) => path.to_path_buf(),
+    }
+}
+
+/// Re-root every key of a path-keyed cache; see [`rebase_path`].
+#[must_use]
+pub fn rebase_path_keys<V, S: ::std::hash::BuildHasher + Default>(
+    map: HashMap<PathBuf, V, S>,
+    old_root: &Path,
+    new_root: &Path,
+) -> HashMap<PathBuf, V, S> {
+    map.into_iter()
+        .map(|(p, v)| (rebase_path(&p, old_root, new_root), v))
+        .collect()
+}
+
 #[must_use]
 pub fn normalize_path(p: &Path) -> PathBuf {
     if p.as_os_str().is_empty() {
@@ -142,7 +177,8 @@ pub fn normalize_path(p: &Path) -> PathBuf {
 /// 1. Full filename (for extensionless files like "justfile")
 /// 2. Multi-dot extensions (for files like "file.tar.gz")
 /// 3. Single extensions (for files like "file.rs")
-fn path_matches_extension_filters<S: ::std::hash::BuildHasher>(
+#[must_use]
+pub fn path_matches_extension_filters<S: ::std::hash::BuildHasher>(
     p: &Path,
     filters: &HashSet<String, S>,
 ) -> bool {
@@ -181,10 +217,75 @@ fn path_matches_extension_filters<S: ::std::hash::BuildHasher>(
     false
 }
 
-#[derive(Default, Debug)]
+/// How many files in the tree each extension filter token matches, sorted by token.
+///
+/// Uses the same matching as the scan, so an include token that counts zero here (a typo
+/// such as `.tx`) is why files are missing from the tree.
+#[must_use]
+pub fn extension_filter_match_counts<S: ::std::hash::BuildHasher>(
+    root: &Node,
+    filters: &HashSet<String, S>,
+) -> Vec<(String, usize)> {
+    fn walk(node: &Node, counts: &mut HashMap<String, usize>) {
+        if node.is_dir {
+            for child in &node.children {
+                walk(child, counts);
+            }
+            return;
+        }
+        let filename = node.name.to_lowercase();
+        let mut matched: Vec<String> = vec![format!(".{filename}")];
+        matched.extend(
+            filename
+                .match_indices('.')
+                .map(|(i, _)| filename[i..].to_string()),
+        );
+        matched.dedup();
+        for token in matched {
+            if let Some(n) = counts.get_mut(&token) {
+                *n += 1;
+            }
+        }
+    }
+
+    let mut counts: HashMap<String, usize> = filters.iter().map(|f| (f.clone(), 0)).collect();
+    walk(root, &mut counts);
+    let mut counts: Vec<(String, usize)> = counts.into_iter().collect();
+    counts.sort_unstable();
+    counts
+}
+
+/// What a scan left out of the tree.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
 pub struct ScanStats {
-    pub excluded_dirs_found: HashSet<String>,
-    pub excluded_files_found: HashSet<String>,
+    /// Excluded directory names present in the project, with how many entries each skipped.
+    pub excluded_dirs_found: HashMap<String, usize>,
+    /// Excluded file names present in the project, with how many entries each skipped.
+    pub excluded_files_found: HashMap<String, usize>,
+    /// Files hidden by the extension filters.
+    pub filtered_files: usize,
+    /// Directories left out by per-directory "exclude" overrides.
+    pub override_excluded_dirs: usize,
+    /// Files and directories left out by the project's `.stitchignore`.
+    pub stitchignored: usize,
+    /// Directories that couldn't be read for lack of permission; they appear empty.
+    pub permission_denied: Vec<PathBuf>,
+}
+
+impl ScanStats {
+    /// Add the counts of `child` (another part of the same tree) to these.
+    pub fn absorb(&mut self, child: Self) {
+        for (name, n) in child.excluded_dirs_found {
+            *self.excluded_dirs_found.entry(name).or_default() += n;
+        }
+        for (name, n) in child.excluded_files_found {
+            *self.excluded_files_found.entry(name).or_default() += n;
+        }
+        self.filtered_files += child.filtered_files;
+        self.override_excluded_dirs += child.override_excluded_dirs;
+        self.stitchignored += child.stitchignored;
+        self.permission_denied.extend(child.permission_denied);
+    }
 }
 
 #[derive(Debug)]
@@ -201,9 +302,12 @@ pub fn scan_dir_to_node<S: ::std::hash::BuildHasher>(
     exclude_dirs: &HashSet<String, S>,
     exclude_files: &HashSet<String, S>,
 ) -> Node {
-    scan_dir_to_node_internal(dir, include_exts, exclude_exts, exclude_dirs, exclude_files).node
+    scan_dir_to_node_with_stats(dir, include_exts, exclude_exts, exclude_dirs, exclude_files).node
 }
 
+/// Like [`scan_dir_to_node`], also reporting what was left out.
+///
+/// The `.stitchignore` file in `dir`, if any, is applied on top of the other exclusions.
 pub fn scan_dir_to_node_with_stats<S: ::std::hash::BuildHasher>(
     dir: &Path,
     include_exts: &HashSet<String, S>,
@@ -211,33 +315,228 @@ pub fn scan_dir_to_node_with_stats<S: ::std::hash::BuildHasher>(
     exclude_dirs: &HashSet<String, S>,
     exclude_files: &HashSet<String, S>,
 ) -> ScanResult {
-    scan_dir_to_node_internal(dir, include_exts, exclude_exts, exclude_dirs, exclude_files)
+    scan_dir_to_node_with_rules(
+        dir,
+        include_exts,
+        exclude_exts,
+        exclude_dirs,
+        exclude_files,
+        &[],
+    )
 }
 
-fn scan_dir_to_node_internal<S: ::std::hash::BuildHasher>(
+/// Like [`scan_dir_to_node_with_stats`], applying the per-directory overrides `rules`
+/// (paths relative to `dir`).
+pub fn scan_dir_to_node_with_rules<S: ::std::hash::BuildHasher>(
     dir: &Path,
     include_exts: &HashSet<String, S>,
     exclude_exts: &HashSet<String, S>,
     exclude_dirs: &HashSet<String, S>,
     exclude_files: &HashSet<String, S>,
+    rules: &[DirectoryRule],
 ) -> ScanResult {
-    let name = dir
-        .file_name()
-        .unwrap_or_default()
-        .to_string_lossy()
-        .to_string();
+    scan_dir_to_node_with_name_matching(
+        dir,
+        include_exts,
+        exclude_exts,
+        exclude_dirs,
+        exclude_files,
+        rules,
+        NameMatching::Exact,
+    )
+}
 
-    let mut node = Node {
-        name,
-        path: dir.to_path_buf(),
-        is_dir: true,
-        children: Vec::new(),
-        expanded: true,
-        has_children: false,
+/// Like [`scan_dir_to_node_with_rules`], comparing excluded directory and file names
+/// with names on disk as `names` says.
+pub fn scan_dir_to_node_with_name_matching<S: ::std::hash::BuildHasher>(
+    dir: &Path,
+    include_exts: &HashSet<String, S>,
+    exclude_exts: &HashSet<String, S>,
+    exclude_dirs: &HashSet<String, S>,
+    exclude_files: &HashSet<String, S>,
+    rules: &[DirectoryRule],
+    names: NameMatching,
+) -> ScanResult {
+    let scope = RuleScope {
+        root: dir,
+        rules,
+        ignore: IgnoreRules::load(dir),
+        exclude_dirs: NameSet::new(exclude_dirs, names),
+        exclude_files: NameSet::new(exclude_files, names),
     };
+    scan_dir_to_node_internal(dir, include_exts, exclude_exts, &scope, None)
+}
+
+/// The filters and overrides a scan applies, for the lazy scanning functions.
+pub struct ScanFilters<'a, S: ::std::hash::BuildHasher> {
+    pub include_exts: &'a HashSet<String, S>,
+    pub exclude_exts: &'a HashSet<String, S>,
+    pub exclude_dirs: &'a HashSet<String, S>,
+    pub exclude_files: &'a HashSet<String, S>,
+    /// Per-directory overrides, relative to the project root.
+    pub rules: &'a [DirectoryRule],
+    pub names: NameMatching,
+}
+
+impl<S: ::std::hash::BuildHasher> ScanFilters<'_, S> {
+    fn scope<'b>(&'b self, project_root: &'b Path) -> RuleScope<'b> {
+        RuleScope {
+            root: project_root,
+            rules: self.rules,
+            ignore: IgnoreRules::load(project_root),
+            exclude_dirs: NameSet::new(self.exclude_dirs, self.names),
+            exclude_files: NameSet::new(self.exclude_files, self.names),
+        }
+    }
+}
+
+/// Like [`scan_dir_to_node_with_name_matching`], but only reading the directories in
+/// `loaded` (and `dir` itself).
+///
+/// Other directories are left unloaded: no children, collapsed, with `has_children`
+/// telling whether anything in them passes the filters. Load them with [`load_dir`] on
+/// first expand.
+///
+/// Passing the [`loaded_dirs`] of the previous tree keeps a rescan's shape.
+pub fn scan_dir_to_node_lazy<S: ::std::hash::BuildHasher, T: ::std::hash::BuildHasher>(
+    dir: &Path,
+    filters: &ScanFilters<'_, S>,
+    loaded: &HashSet<PathBuf, T>,
+) -> ScanResult {
+    let scope = filters.scope(dir);
+    scan_dir_to_node_internal(
+        dir,
+        filters.include_exts,
+        filters.exclude_exts,
+        &scope,
+        Some(&|path: &Path| loaded.contains(path)),
+    )
+}
+
+/// A directory left unloaded by [`scan_dir_to_node_lazy`].
+#[must_use]
+pub const fn is_unloaded_dir(node: &Node) -> bool {
+    node.is_dir && node.has_children && node.children.is_empty()
+}
+
+/// Directories of `root` whose children have been read.
+#[must_use]
+pub fn loaded_dirs(root: &Node) -> HashSet<PathBuf> {
+    fn rec(n: &Node, out: &mut HashSet<PathBuf>) {
+        if n.is_dir && !is_unloaded_dir(n) {
+            out.insert(n.path.clone());
+            for c in &n.children {
+                rec(c, out);
+            }
+        }
+    }
+    let mut out = HashSet::new();
+    rec(root, &mut out);
+    out
+}
+
+/// Read the children of the unloaded directory `node` (one level, or its whole subtree
+/// when `full`). Does nothing for a directory that's already loaded.
+pub fn load_dir<S: ::std::hash::BuildHasher>(
+    project_root: &Path,
+    node: &mut Node,
+    filters: &ScanFilters<'_, S>,
+    full: bool,
+) -> ScanStats {
+    if !is_unloaded_dir(node) {
+        return ScanStats::default();
+    }
+    let scope = filters.scope(project_root);
+    let only_this = |path: &Path| path == node.path;
+    let lazy: Option<&dyn Fn(&Path) -> bool> = if full { None } else { Some(&only_this) };
+    let scan = scan_dir_to_node_internal(
+        &node.path,
+        filters.include_exts,
+        filters.exclude_exts,
+        &scope,
+        lazy,
+    );
+    node.children = scan.node.children;
+    node.has_children = scan.node.has_children;
+    scan.stats
+}
+
+/// Fully load every unloaded directory that is checked or holds an explicitly checked
+/// path, so that [`collect_selected_paths`] sees all the selected files.
+pub fn load_selected_dirs<S: ::std::hash::BuildHasher, T: ::std::hash::BuildHasher>(
+    project_root: &Path,
+    root: &mut Node,
+    explicit: &HashMap<PathBuf, bool, T>,
+    filters: &ScanFilters<'_, S>,
+) -> ScanStats {
+    fn rec<S: ::std::hash::BuildHasher, T: ::std::hash::BuildHasher>(
+        project_root: &Path,
+        node: &mut Node,
+        explicit: &HashMap<PathBuf, bool, T>,
+        filters: &ScanFilters<'_, S>,
+        inherited: bool,
+        stats: &mut ScanStats,
+    ) {
+        let effective = explicit.get(&node.path).copied().unwrap_or(inherited);
+        if is_unloaded_dir(node) {
+            let wanted = effective
+                || explicit
+                    .iter()
+                    .any(|(p, &on)| on && p.starts_with(&node.path));
+            if wanted {
+                stats.absorb(load_dir(project_root, node, filters, true));
+            }
+            return;
+        }
+        for c in &mut node.children {
+            if c.is_dir {
+                rec(project_root, c, explicit, filters, effective, stats);
+            }
+        }
+    }
+    let mut stats = ScanStats::default();
+    rec(project_root, root, explicit, filters, false, &mut stats);
+    stats
+}
+
+/// The scan root, the overrides and ignore rules relative to it, and the excluded names.
+struct RuleScope<'a> {
+    root: &'a Path,
+    rules: &'a [DirectoryRule],
+    ignore: IgnoreRules,
+    exclude_dirs: NameSet,
+    exclude_files: NameSet,
+}
+
+impl RuleScope<'_> {
+    fn rule_for(&self, path: &Path) -> Option<DirRuleKind> {
+        if self.rules.is_empty() {
+            return None;
+        }
+        let rel = path_to_unix(path.strip_prefix(self.root).ok()?);
+        dir_rule_for(self.rules, &rel)
+    }
+
+    fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        if self.ignore.is_empty() {
+            return false;
+        }
+        path.strip_prefix(self.root)
+            .is_ok_and(|rel| self.ignore.matches(&path_to_unix(rel), is_dir))
+    }
+}
+
+fn scan_dir_to_node_internal<S: ::std::hash::BuildHasher>(
+    dir: &Path,
+    include_exts: &HashSet<String, S>,
+    exclude_exts: &HashSet<String, S>,
+    scope: &RuleScope<'_>,
+    lazy: Option<&dyn Fn(&Path) -> bool>,
+) -> ScanResult {
+    let mut node = dir_node(dir);
 
     let (mut files, mut dirs, mut stats) =
-        gather_dir_entries(dir, include_exts, exclude_exts, exclude_dirs, exclude_files);
+        gather_dir_entries(dir, include_exts, exclude_exts, scope);
 
     files.sort_by(|a, b| a.0.cmp(&b.0));
     dirs.sort_by(|a, b| a.0.cmp(&b.0));
@@ -257,75 +556,150 @@ fn scan_dir_to_node_internal<S: ::std::hash::BuildHasher>(
     }
 
     let include_mode = !include_exts.is_empty();
-    for (_basename, path) in dirs {
-        let ScanResult {
-            node: child,
-            stats: mut child_stats,
-        } = scan_dir_to_node_internal(
-            &path,
-            include_exts,
-            exclude_exts,
-            exclude_dirs,
-            exclude_files,
-        );
+    for (basename, path) in dirs {
+        let child = match lazy {
+            Some(is_loaded) if !is_loaded(&path) => {
+                // Nothing in it passes the filters: the probe has scanned it in full then.
+                let Some(scanned) = probe_dir(&path, include_exts, exclude_exts, scope) else {
+                    node.has_children = true;
+                    node.children.push(Node {
+                        name: basename,
+                        path,
+                        is_dir: true,
+                        children: Vec::new(),
+                        expanded: false,
+                        has_children: true,
+                    });
+                    continue;
+                };
+                scanned
+            }
+            _ => scan_dir_to_node_internal(&path, include_exts, exclude_exts, scope, lazy),
+        };
+        add_child_dir(&mut node, &mut stats, include_mode, &path, child);
+    }
 
-        stats
-            .excluded_dirs_found
-            .extend(child_stats.excluded_dirs_found.drain());
-        stats
-            .excluded_files_found
-            .extend(child_stats.excluded_files_found.drain());
+    ScanResult { node, stats }
+}
 
-        let child_visible = if include_mode {
-            !child.children.is_empty() || child.has_children
-        } else {
-            true
-        };
+/// The lazy scan's `has_children` probe: `None` as soon as a file under `dir` passes
+/// the filters, otherwise the full scan of `dir`, built along the way.
+fn probe_dir<S: ::std::hash::BuildHasher>(
+    dir: &Path,
+    include_exts: &HashSet<String, S>,
+    exclude_exts: &HashSet<String, S>,
+    scope: &RuleScope<'_>,
+) -> Option<ScanResult> {
+    let (files, mut dirs, mut stats) = gather_dir_entries(dir, include_exts, exclude_exts, scope);
+    if !files.is_empty() {
+        return None;
+    }
+    dirs.sort_by(|a, b| a.0.cmp(&b.0));
 
-        if child_visible {
-            node.has_children =
-                node.has_children || !child.children.is_empty() || child.has_children;
-            node.children.push(child);
-        }
+    let mut node = dir_node(dir);
+    let include_mode = !include_exts.is_empty();
+    for (_, path) in dirs {
+        let child = probe_dir(&path, include_exts, exclude_exts, scope)?;
+        add_child_dir(&mut node, &mut stats, include_mode, &path, child);
     }
+    Some(ScanResult { node, stats })
+}
 
-    ScanResult { node, stats }
+fn dir_node(dir: &Path) -> Node {
+    let name = dir
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string();
+
+    Node {
+        name,
+        path: dir.to_path_buf(),
+        is_dir: true,
+        children: Vec::new(),
+        expanded: true,
+        has_children: false,
+    }
+}
+
+/// Add the scanned directory `child` at `path` below `node`, unless include filters
+/// hide it for having no matching files.
+fn add_child_dir(
+    node: &mut Node,
+    stats: &mut ScanStats,
+    include_mode: bool,
+    path: &Path,
+    child: ScanResult,
+) {
+    let ScanResult {
+        node: child,
+        stats: child_stats,
+    } = child;
+
+    // Kept even with include filters, so its warning badge shows.
+    let unreadable = child_stats.permission_denied.first().map(PathBuf::as_path) == Some(path);
+    stats.absorb(child_stats);
+
+    let child_visible = if include_mode && !unreadable {
+        !child.children.is_empty() || child.has_children
+    } else {
+        true
+    };
+
+    if child_visible {
+        node.has_children = node.has_children || !child.children.is_empty() || child.has_children;
+        node.children.push(child);
+    }
 }
 
 fn gather_dir_entries<S: ::std::hash::BuildHasher>(
     dir: &Path,
     include_exts: &HashSet<String, S>,
     exclude_exts: &HashSet<String, S>,
-    exclude_dirs: &HashSet<String, S>,
-    exclude_files: &HashSet<String, S>,
+    scope: &RuleScope<'_>,
 ) -> (Vec<NamePath>, Vec<NamePath>, ScanStats) {
-    let Ok(entries) = fs::read_dir(dir) else {
-        return (Vec::new(), Vec::new(), ScanStats::default());
+    let mut stats = ScanStats::default();
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            if e.kind() == std::io::ErrorKind::PermissionDenied {
+                stats.permission_denied.push(dir.to_path_buf());
+            }
+            return (Vec::new(), Vec::new(), stats);
+        }
     };
 
     let mut dirs: Vec<NamePath> = Vec::new();
     let mut files: Vec<NamePath> = Vec::new();
-    let mut stats = ScanStats::default();
 
-    let include_mode = !include_exts.is_empty();
-    let exclude_mode = !exclude_exts.is_empty();
+    let ignore_exts = scope.rule_for(dir) == Some(DirRuleKind::IgnoreExtensionFilters);
+    let include_mode = !include_exts.is_empty() && !ignore_exts;
+    let exclude_mode = !exclude_exts.is_empty() && !ignore_exts;
 
     for ent in entries.flatten() {
         let path = ent.path();
         let base: String = ent.file_name().to_string_lossy().into_owned();
 
         let is_dir = ent.file_type().map(|ft| ft.is_dir()).unwrap_or(false);
+        if scope.is_ignored(&path, is_dir) {
+            stats.stitchignored += 1;
+            continue;
+        }
         if is_dir {
-            if exclude_dirs.contains(&base) {
-                stats.excluded_dirs_found.insert(base);
+            if scope.exclude_dirs.contains(&base) {
+                *stats.excluded_dirs_found.entry(base).or_default() += 1;
+                continue;
+            }
+            if scope.rule_for(&path) == Some(DirRuleKind::Exclude) {
+                stats.override_excluded_dirs += 1;
                 continue;
             }
             dirs.push((base, path));
             continue;
         }
 
-        if exclude_files.contains(&base) {
-            stats.excluded_files_found.insert(base);
+        if scope.exclude_files.contains(&base) {
+            *stats.excluded_files_found.entry(base).or_default() += 1;
             continue;
         }
 
@@ -341,6 +715,8 @@ fn gather_dir_entries<S: ::std::hash::BuildHasher>(
 
         if matches_file {
             files.push((base, path));
+        } else {
+            stats.filtered_files += 1;
         }
     }
 
@@ -392,6 +768,49 @@ pub fn collect_selected_paths<T: ::std::hash::BuildHasher>(
     }
 }
 
+/// A top-level directory none of whose files are selected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnselectedDir {
+    pub name: String,
+    pub file_count: usize,
+}
+
+/// List top-level directories of `root` that contain files but have none selected,
+/// in tree order. Used to hint at what was left out of the output.
+#[must_use]
+pub fn summarize_unselected_top_level_dirs<T: ::std::hash::BuildHasher>(
+    root: &Node,
+    explicit: &HashMap<PathBuf, bool, T>,
+) -> Vec<UnselectedDir> {
+    fn count(
+        n: &Node,
+        explicit: &HashMap<PathBuf, bool, impl ::std::hash::BuildHasher>,
+        inherited: bool,
+    ) -> (usize, usize) {
+        let effective = explicit.get(&n.path).copied().unwrap_or(inherited);
+        if !n.is_dir {
+            return (1, usize::from(effective));
+        }
+        n.children.iter().fold((0, 0), |(total, sel), c| {
+            let (t, s) = count(c, explicit, effective);
+            (total + t, sel + s)
+        })
+    }
+
+    let root_effective = explicit.get(&root.path).copied().unwrap_or(false);
+    root.children
+        .iter()
+        .filter(|c| c.is_dir)
+        .filter_map(|c| {
+            let (total, selected) = count(c, explicit, root_effective);
+            (total > 0 && selected == 0).then(|| UnselectedDir {
+                name: c.name.clone(),
+                file_count: total,
+            })
+        })
+        .collect()
+}
+
 #[must_use]
 pub fn drain_channel_nonblocking<T>(rx: &std::sync::mpsc::Receiver<T>) -> bool {
     let mut any = false;
@@ -410,6 +829,30 @@ pub fn is_event_path_relevant<S: ::std::hash::BuildHasher>(
     exclude_dirs: &std::collections::HashSet<String, S>,
     exclude_files: &std::collections::HashSet<String, S>,
 ) -> bool {
+    is_event_path_relevant_with_name_matching(
+        project_root,
+        abs_path,
+        include_exts,
+        exclude_exts,
+        exclude_dirs,
+        exclude_files,
+        NameMatching::Exact,
+    )
+}
+
+/// Like [`is_event_path_relevant`], comparing excluded names as `names` says.
+#[must_use]
+pub fn is_event_path_relevant_with_name_matching<S: ::std::hash::BuildHasher>(
+    project_root: &std::path::Path,
+    abs_path: &std::path::Path,
+    include_exts: &std::collections::HashSet<String, S>,
+    exclude_exts: &std::collections::HashSet<String, S>,
+    exclude_dirs: &std::collections::HashSet<String, S>,
+    exclude_files: &std::collections::HashSet<String, S>,
+    names: NameMatching,
+) -> bool {
+    let exclude_dirs = NameSet::new(exclude_dirs, names);
+    let exclude_files = NameSet::new(exclude_files, names);
     if !abs_path.starts_with(project_root) {
         return false;
     }
@@ -426,7 +869,7 @@ pub fn is_event_path_relevant<S: ::std::hash::BuildHasher>(
     for comp in rel.components() {
         if let Component::Normal(os) = comp {
             let name = os.to_string_lossy();
-            if exclude_dirs.contains(name.as_ref()) {
+            if exclude_dirs.contains(&name) {
                 return false;
             }
         }
@@ -0,0 +1,706 @@
+use std::{
+    cell::Cell,
+    collections::{BTreeMap, HashMap},
+    fs,
+    path::{Path, PathBuf},
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, Ordering},
+        mpsc,
+    },
+    time::SystemTime,
+};
+
+use crate::core::{
+    CONTENT_CACHE_MAX_BYTES, ContentCache, ContextWindow, DEFAULT_SVG_INLINE_LIMIT,
+    DOCUMENT_MAX_TOKENS, FileMarkers, FileStamp, OutputLayout, OutputOrder, OutputParts,
+    PathRewrite, PostProcess, REMOVE_REGEX_TIMEOUT, SelectionStats, SplitSource, TextQuirks,
+    TransformOptions, TransformPipeline, WorkspaceSettings, append_checksum_footer,
+    append_post_process_note, estimate_tokens_with_markers, extract_document, format_modified_time,
+    image_placeholder, is_document_path, is_generated_go_file_path, is_image_path, lang_for_path,
+    load_local_settings, normalize_text, number_lines_from_original, order_output_files,
+    over_budget_note, path_to_unix, save_content_cache, skipped_file_reason, transform_config_key,
+};
+
+/* ================================ Generation ================================ */
+
+/// Everything one generation needs, gathered on the caller's thread so the work itself
+/// touches no UI state.
+#[allow(clippy::struct_excessive_bools)] // independent output toggles, as in WorkspaceSettings
+pub struct GenerationRequest {
+    /// Sections produced up front (hierarchy, notes, ...); the file contents are added.
+    pub parts: OutputParts,
+    /// Emit only `parts` ("Hierarchy Only" / "Directories Only"); `files` is not read.
+    pub hierarchy_only: bool,
+    pub layout: OutputLayout,
+    pub path_rewrite: PathRewrite,
+    pub file_markers: FileMarkers,
+    pub languages: BTreeMap<String, String>,
+    pub line_numbers: bool,
+    pub deterministic: bool,
+    /// Absolute paths of the files to emit.
+    pub files: Vec<PathBuf>,
+    pub project_root: PathBuf,
+    pub transforms: TransformPipeline,
+    pub transform_opts: TransformOptions,
+    /// Modification times for the file headers; empty leaves them out.
+    pub modified_times: HashMap<PathBuf, SystemTime>,
+    pub content_cache: Arc<Mutex<ContentCache>>,
+    /// Save the content cache when the generation changed it.
+    pub persist_content_cache: bool,
+    pub svg_inline_limit: u64,
+    /// Set only when external commands are allowed.
+    pub post_process: Option<PostProcess>,
+    pub context_window: ContextWindow,
+    pub output_order: OutputOrder,
+}
+
+impl GenerationRequest {
+    /// A request for `files` with the default settings and a fresh content cache.
+    #[must_use]
+    pub fn new(project_root: impl Into<PathBuf>, files: Vec<PathBuf>, parts: OutputParts) -> Self {
+        let project_root = project_root.into();
+        Self {
+            parts,
+            hierarchy_only: false,
+            layout: OutputLayout::default(),
+            path_rewrite: PathRewrite::default(),
+            file_markers: FileMarkers::default(),
+            languages: BTreeMap::new(),
+            line_numbers: false,
+            deterministic: false,
+            files,
+            transforms: TransformPipeline::default(),
+            transform_opts: TransformOptions {
+                working_dir: project_root.clone(),
+                ..TransformOptions::default()
+            },
+            project_root,
+            modified_times: HashMap::new(),
+            content_cache: Arc::default(),
+            persist_content_cache: false,
+            svg_inline_limit: DEFAULT_SVG_INLINE_LIMIT,
+            post_process: None,
+            context_window: ContextWindow::default(),
+            output_order: OutputOrder::default(),
+        }
+    }
+
+    /// The request "Generate" sends for `files` with `settings`, as tasks run it too.
+    /// Generated Go files are dropped unless the settings keep them, and pipeline
+    /// commands and the post-processor are kept only when the project's local settings
+    /// allow external commands. The content cache is a fresh one.
+    #[must_use]
+    pub fn from_settings(
+        project_root: &Path,
+        mut files: Vec<PathBuf>,
+        parts: OutputParts,
+        settings: &WorkspaceSettings,
+    ) -> Self {
+        if !settings.go.go_keep_generated {
+            files.retain(|f| !is_generated_go_file_path(f));
+        }
+        // Times would make deterministic output drift.
+        let modified_times = if settings.layout.modified_times && !settings.deterministic {
+            files
+                .iter()
+                .filter_map(|f| Some((f.clone(), fs::metadata(f).ok()?.modified().ok()?)))
+                .collect()
+        } else {
+            HashMap::new()
+        };
+        let external_commands = (settings.transforms.has_commands()
+            || settings.post_process.is_some())
+            && load_local_settings(project_root).is_some_and(|ls| ls.external_commands);
+        Self {
+            parts,
+            hierarchy_only: settings.hierarchy_only || settings.dirs_only,
+            layout: settings.layout.clone(),
+            path_rewrite: settings.path_rewrite.clone(),
+            file_markers: if settings.cell_markers {
+                FileMarkers::cells()
+            } else {
+                settings.file_markers.clone()
+            },
+            languages: settings.languages.clone(),
+            line_numbers: settings.line_numbers,
+            deterministic: settings.deterministic,
+            files,
+            project_root: project_root.to_path_buf(),
+            transforms: settings.transforms.clone(),
+            transform_opts: TransformOptions::from_settings(
+                project_root,
+                settings,
+                external_commands,
+            ),
+            modified_times,
+            content_cache: Arc::default(),
+            persist_content_cache: false,
+            svg_inline_limit: settings
+                .svg_inline_limit
+                .unwrap_or(DEFAULT_SVG_INLINE_LIMIT),
+            post_process: settings.post_process.clone().filter(|_| external_commands),
+            context_window: settings.context_window,
+            output_order: settings.output_order,
+        }
+    }
+}
+
+/// How far a generation got: files emitted (or skipped) out of all files.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GenerationProgress {
+    pub files_done: usize,
+    pub files_total: usize,
+}
+
+/// A finished generation.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GenerationOutput {
+    pub text: String,
+    /// Size of each emitted file.
+    pub stats: SelectionStats,
+    /// The blocks a split export is built from.
+    pub split: SplitSource,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GenerationError {
+    Cancelled,
+    /// The generation panicked; the message says why.
+    Failed(String),
+}
+
+pub type GenerationResult = Result<GenerationOutput, GenerationError>;
+
+/// Turns a [`GenerationRequest`] into the output text, on the calling thread
+/// ([`Generator::run`]) or a background one ([`Generator::spawn`]).
+pub struct Generator {
+    request: GenerationRequest,
+}
+
+impl Generator {
+    #[must_use]
+    pub const fn new(request: GenerationRequest) -> Self {
+        Self { request }
+    }
+
+    /// Generate on this thread. `cancel` is checked and `progress` called before each
+    /// file, and `progress` once more when all files are done.
+    ///
+    /// # Errors
+    /// Returns [`GenerationError::Cancelled`] once `cancel` is set.
+    pub fn run(
+        self,
+        cancel: &AtomicBool,
+        progress: impl FnMut(GenerationProgress),
+    ) -> GenerationResult {
+        run_request(self.request, cancel, progress)
+    }
+
+    /// Generate on a background thread. A panic is reported as
+    /// [`GenerationError::Failed`] rather than leaving the handle waiting forever.
+    #[must_use]
+    pub fn spawn(self) -> GenerationHandle {
+        let cancel = Arc::new(AtomicBool::new(false));
+        let (progress_tx, progress_rx) = mpsc::channel();
+        let (result_tx, result_rx) = mpsc::channel();
+        let worker_cancel = Arc::clone(&cancel);
+        std::thread::spawn(move || {
+            let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                self.run(&worker_cancel, |p| {
+                    let _ = progress_tx.send(p);
+                })
+            }))
+            .unwrap_or_else(|payload| Err(GenerationError::Failed(panic_message(&payload))));
+            let _ = result_tx.send(result);
+        });
+        GenerationHandle {
+            cancel,
+            progress_rx,
+            result_rx,
+            progress: Cell::new(GenerationProgress::default()),
+            finished: Cell::new(false),
+        }
+    }
+}
+
+/// A generation running on a background thread.
+pub struct GenerationHandle {
+    cancel: Arc<AtomicBool>,
+    progress_rx: mpsc::Receiver<GenerationProgress>,
+    result_rx: mpsc::Receiver<GenerationResult>,
+    progress: Cell<GenerationProgress>,
+    /// The result was taken by [`GenerationHandle::try_result`].
+    finished: Cell<bool>,
+}
+
+impl GenerationHandle {
+    /// The latest progress the worker reported.
+    pub fn progress(&self) -> GenerationProgress {
+        while let Ok(p) = self.progress_rx.try_recv() {
+            self.progress.set(p);
+        }
+        self.progress.get()
+    }
+
+    /// Ask the worker to stop before its next file; it then finishes with
+    /// [`GenerationError::Cancelled`].
+    pub fn cancel(&self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel.load(Ordering::Relaxed)
+    }
+
+    /// The result if the worker has finished; `Some` only once.
+    #[must_use]
+    pub fn try_result(&self) -> Option<GenerationResult> {
+        if self.finished.get() {
+            return None;
+        }
+        let result = match self.result_rx.try_recv() {
+            Ok(result) => result,
+            Err(mpsc::TryRecvError::Empty) => return None,
+            Err(mpsc::TryRecvError::Disconnected) => Err(worker_stopped()),
+        };
+        self.finished.set(true);
+        Some(result)
+    }
+
+    /// Block until the worker has finished.
+    ///
+    /// # Errors
+    /// Returns the worker's [`GenerationError`], or [`GenerationError::Failed`] if
+    /// [`GenerationHandle::try_result`] already took the result.
+    pub fn wait(self) -> GenerationResult {
+        self.result_rx
+            .recv()
+            .unwrap_or_else(|_| Err(worker_stopped()))
+    }
+}
+
+fn worker_stopped() -> GenerationError {
+    GenerationError::Failed("the generation thread stopped".to_string())
+}
+
+fn panic_message(payload: &Box<dyn std::any::Any + Send>) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|s| (*s).to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown error".to_string())
+}
+
+/// Generate `request` on this thread; what [`Generator::run`] does.
+///
+/// # Errors
+/// Returns [`GenerationError::Cancelled`] once `cancel` is set.
+#[allow(clippy::too_many_lines)]
+pub fn run_request(
+    request: GenerationRequest,
+    cancel: &AtomicBool,
+    mut progress: impl FnMut(GenerationProgress),
+) -> GenerationResult {
+    if request.hierarchy_only {
+        return Ok(render_parts_only(request));
+    }
+    let GenerationRequest {
+        mut parts,
+        hierarchy_only: _,
+        layout,
+        path_rewrite,
+        file_markers,
+        languages,
+        line_numbers,
+        deterministic,
+        files,
+        project_root,
+        transforms,
+        mut transform_opts,
+        modified_times,
+        content_cache,
+        persist_content_cache,
+        svg_inline_limit,
+        post_process,
+        context_window,
+        output_order,
+    } = request;
+
+    // Command output may depend on more than the file itself, so it's never reused.
+    let cache_config = (!(transform_opts.allow_commands && transforms.has_commands()))
+        .then(|| transform_config_key(&transforms, &transform_opts, line_numbers));
+    if let Some(config) = cache_config
+        && let Ok(mut cache) = content_cache.lock()
+    {
+        cache.begin(config);
+    }
+
+    let mut skipped: Vec<(PathBuf, std::io::Error)> = Vec::new();
+    let mut regex_timed_out: Option<PathBuf> = None;
+    let mut command_failures: Vec<(PathBuf, String)> = Vec::new();
+    // Documents whose text was extracted, and whether it was cut at the token cap.
+    let mut documents: Vec<(PathBuf, bool)> = Vec::new();
+    // Files with a BOM or odd line endings, and what they had.
+    let mut text_quirks: Vec<(PathBuf, TextQuirks)> = Vec::new();
+    let mut file_sections = String::new();
+    let mut file_tokens: Vec<(String, usize)> = Vec::new();
+    let mut selection_stats = SelectionStats::default();
+    let mut split = SplitSource::default();
+    // Paths as shown in the headers, in block order, for the checksum footer.
+    let mut shown_paths: Vec<String> = Vec::new();
+
+    let ordered = order_output_files(&project_root, files, output_order);
+    let files_total = ordered.len();
+    for (files_done, fp) in ordered.into_iter().enumerate() {
+        if cancel.load(Ordering::Relaxed) {
+            return Err(GenerationError::Cancelled);
+        }
+        progress(GenerationProgress {
+            files_done,
+            files_total,
+        });
+        let rel: PathBuf = fp.strip_prefix(&project_root).map_or_else(
+            |_| PathBuf::from(fp.file_name().unwrap_or_default()),
+            std::path::Path::to_path_buf,
+        );
+
+        let rel_for_match = path_to_unix(&rel);
+        let is_document = cfg!(feature = "doc-extract") && is_document_path(&fp);
+        // Taken before reading, so an edit during the read invalidates the entry.
+        let stamp = (cache_config.is_some()
+            && regex_timed_out.is_none()
+            && !is_document
+            && !is_image_path(&fp))
+        .then(|| FileStamp::of(&fp))
+        .flatten();
+        let cached = stamp.and_then(|stamp| {
+            content_cache.lock().ok().and_then(|mut cache| {
+                let contents = cache.get(&fp, stamp)?;
+                Some((contents, cache.quirks(&fp)))
+            })
+        });
+        let mut quirks = TextQuirks::default();
+        let contents = if let Some((cached, cached_quirks)) = cached {
+            quirks = cached_quirks;
+            cached
+        } else if is_image_path(&fp) {
+            // Placeholders stay as they are: no transforms or line numbers.
+            match fs::read(&fp) {
+                Ok(bytes) => image_placeholder(&fp, &bytes, svg_inline_limit),
+                Err(e) => {
+                    skipped.push((fp.clone(), e));
+                    continue;
+                }
+            }
+        } else {
+            let contents = if is_document {
+                match extract_document(&fp, DOCUMENT_MAX_TOKENS) {
+                    Ok(doc) => {
+                        documents.push((rel.clone(), doc.truncated));
+                        doc.text
+                    }
+                    Err(e) => {
+                        skipped.push((fp.clone(), std::io::Error::other(e)));
+                        continue;
+                    }
+                }
+            } else {
+                match fs::read_to_string(&fp) {
+                    Ok(s) => {
+                        quirks = TextQuirks::detect(&s);
+                        if transform_opts.normalize_text && quirks.needs_normalizing() {
+                            normalize_text(&s)
+                        } else {
+                            s
+                        }
+                    }
+                    Err(e) => {
+                        skipped.push((fp.clone(), e));
+                        continue;
+                    }
+                }
+            };
+            let original = line_numbers.then(|| contents.clone());
+
+            let out = transforms.apply(&rel_for_match, contents, &transform_opts);
+            let cacheable = !out.regex_timed_out && out.command_failures.is_empty();
+            let mut contents = out.contents;
+            if out.regex_timed_out {
+                // Don't keep feeding a runaway pattern; emit this and later files unfiltered.
+                regex_timed_out = Some(rel.clone());
+                transform_opts.remove_regex = None;
+            }
+            command_failures.extend(out.command_failures.into_iter().map(|e| (rel.clone(), e)));
+
+            if let Some(original) = original {
+                contents = number_lines_from_original(&original, &contents);
+            }
+            if let Some(stamp) = stamp.filter(|_| cacheable)
+                && let Ok(mut cache) = content_cache.lock()
+            {
+                cache.insert_with_quirks(fp.clone(), stamp, contents.clone(), quirks);
+            }
+            contents
+        };
+        let worth_noting = if transform_opts.normalize_text {
+            quirks.needs_normalizing()
+        } else {
+            quirks.is_suspicious()
+        };
+        if worth_noting {
+            text_quirks.push((rel.clone(), quirks));
+        }
+
+        let rel_display = path_rewrite.display(&rel, deterministic);
+        let block_start = file_sections.len();
+        let lang = lang_for_path(&rel_display, &languages);
+        let modified = modified_times.get(&fp).map(|t| format_modified_time(*t));
+        file_markers.write_block(
+            &mut file_sections,
+            &rel_display,
+            lang,
+            &contents,
+            modified.as_deref(),
+        );
+        let block = &file_sections[block_start..];
+        let block_tokens = estimate_tokens_with_markers(block, &file_markers);
+        selection_stats.add_file(&rel_for_match, block.chars().count(), block_tokens);
+        split.add_block(&rel_for_match, block);
+        if layout.checksum_footer {
+            shown_paths.push(rel_display.clone());
+        }
+        if context_window != ContextWindow::Off {
+            file_tokens.push((rel_display, block_tokens));
+        }
+    }
+    progress(GenerationProgress {
+        files_done: files_total,
+        files_total,
+    });
+
+    // Merge skipped file notes into the existing NOTES section
+    if let Some(notes) = parts.notes.as_mut()
+        && !skipped.is_empty()
+    {
+        append_skipped_notes(notes, skipped, &project_root, &path_rewrite, deterministic);
+    }
+
+    if let Some(notes) = parts.notes.as_mut()
+        && !documents.is_empty()
+    {
+        append_document_notes(notes, &documents, &path_rewrite);
+    }
+
+    if let Some(notes) = parts.notes.as_mut()
+        && !text_quirks.is_empty()
+    {
+        append_text_quirk_notes(
+            notes,
+            &text_quirks,
+            transform_opts.normalize_text,
+            &path_rewrite,
+        );
+    }
+
+    if let Some(rel) = regex_timed_out {
+        let notes = parts.notes.get_or_insert_with(String::new);
+        append_regex_timeout_note(notes, &rel, &path_rewrite);
+    }
+
+    if !command_failures.is_empty() {
+        let notes = parts.notes.get_or_insert_with(String::new);
+        append_command_failure_notes(notes, &command_failures, &path_rewrite);
+    }
+
+    if let Ok(mut cache) = content_cache.lock() {
+        cache.trim(CONTENT_CACHE_MAX_BYTES);
+        if persist_content_cache && cache.take_changed() {
+            let _ = save_content_cache(&project_root, &cache);
+        }
+    }
+
+    split.parts = parts.clone();
+    parts.contents = Some(file_sections);
+    let mut rendered = match post_process {
+        None => {
+            render_with_budget_note(&layout, parts, &file_markers, context_window, &file_tokens)
+        }
+        Some(post) => {
+            let rendered = render_with_budget_note(
+                &layout,
+                parts.clone(),
+                &file_markers,
+                context_window,
+                &file_tokens,
+            );
+            post.run(&rendered, &project_root).unwrap_or_else(|e| {
+                append_post_process_note(parts.notes.get_or_insert_with(String::new), &e);
+                render_with_budget_note(&layout, parts, &file_markers, context_window, &file_tokens)
+            })
+        }
+    };
+    // Last, so the checksum covers what the post-processor made of the output.
+    if layout.checksum_footer {
+        let blocks: Vec<(&str, &str)> = shown_paths
+            .iter()
+            .zip(&split.blocks)
+            .map(|(path, (_, block))| (path.as_str(), block.as_str()))
+            .collect();
+        append_checksum_footer(&mut rendered, &blocks);
+    }
+    Ok(GenerationOutput {
+        text: rendered,
+        stats: selection_stats,
+        split,
+    })
+}
+
+/// The output of a hierarchy-only request: the sections produced up front, run through the
+/// post-processor if there is one.
+fn render_parts_only(request: GenerationRequest) -> GenerationOutput {
+    let GenerationRequest {
+        mut parts,
+        layout,
+        project_root,
+        post_process,
+        ..
+    } = request;
+    let mut text = layout.render(&parts);
+    if let Some(post) = post_process {
+        text = post.run(&text, &project_root).unwrap_or_else(|e| {
+            append_post_process_note(parts.notes.get_or_insert_with(String::new), &e);
+            layout.render(&parts)
+        });
+    }
+    GenerationOutput {
+        text,
+        ..GenerationOutput::default()
+    }
+}
+
+/// Render the output, adding a NOTES warning when it doesn't fit the context window.
+fn render_with_budget_note(
+    layout: &OutputLayout,
+    mut parts: OutputParts,
+    file_markers: &FileMarkers,
+    context_window: ContextWindow,
+    file_tokens: &[(String, usize)],
+) -> String {
+    let rendered = layout.render(&parts);
+    // Estimated so the check stays cheap; the stats line re-checks with the exact count.
+    let Some(note) = over_budget_note(
+        estimate_tokens_with_markers(&rendered, file_markers),
+        context_window,
+        file_tokens,
+    ) else {
+        return rendered;
+    };
+    let notes = parts.notes.get_or_insert_with(String::new);
+    notes.push_str(&note);
+    notes.push('\n');
+    layout.render(&parts)
+}
+
+fn append_regex_timeout_note(notes: &mut String, rel: &Path, path_rewrite: &PathRewrite) {
+    use std::fmt::Write;
+
+    let _ = writeln!(
+        notes,
+        "Remove regex too slow: gave up on {} after {}s; it was not applied to that file or any later file.\n",
+        path_rewrite.apply(&path_to_unix(rel)),
+        REMOVE_REGEX_TIMEOUT.as_secs()
+    );
+}
+
+fn append_text_quirk_notes(
+    notes: &mut String,
+    files: &[(PathBuf, TextQuirks)],
+    normalized: bool,
+    path_rewrite: &PathRewrite,
+) {
+    use std::fmt::Write;
+
+    let count = files.len();
+    if normalized {
+        let _ = writeln!(notes, "BOMs and line endings normalized to LF ({count}):");
+    } else {
+        let _ = writeln!(
+            notes,
+            "Files with a BOM or mixed line endings, emitted as is ({count}; set \"normalize_text\": true to fix):"
+        );
+    }
+    for (rel, quirks) in files {
+        let path = path_rewrite.apply(&path_to_unix(rel));
+        let _ = writeln!(notes, "- {path}: {}", quirks.describe());
+    }
+    notes.push('\n');
+}
+
+fn append_document_notes(
+    notes: &mut String,
+    documents: &[(PathBuf, bool)],
+    path_rewrite: &PathRewrite,
+) {
+    use std::fmt::Write;
+
+    let _ = writeln!(
+        notes,
+        "Text extracted from documents ({}):",
+        documents.len()
+    );
+    for (rel, truncated) in documents {
+        let path = path_rewrite.apply(&path_to_unix(rel));
+        if *truncated {
+            let _ = writeln!(notes, "- {path} (cut at ~{DOCUMENT_MAX_TOKENS} tokens)");
+        } else {
+            let _ = writeln!(notes, "- {path}");
+        }
+    }
+    notes.push('\n');
+}
+
+fn append_command_failure_notes(
+    notes: &mut String,
+    failures: &[(PathBuf, String)],
+    path_rewrite: &PathRewrite,
+) {
+    use std::fmt::Write;
+
+    let _ = writeln!(
+        notes,
+        "Transform commands failed ({}); those files were emitted without them:",
+        failures.len()
+    );
+    for (rel, err) in failures {
+        let _ = writeln!(notes, "- {}: {err}", path_rewrite.apply(&path_to_unix(rel)));
+    }
+    notes.push('\n');
+}
+
+fn append_skipped_notes(
+    notes: &mut String,
+    skipped: Vec<(PathBuf, std::io::Error)>,
+    selected_dir: &Path,
+    path_rewrite: &PathRewrite,
+    deterministic: bool,
+) {
+    use std::fmt::Write;
+
+    let count = skipped.len();
+    let _ = writeln!(notes, "Skipped files ({count}):");
+    for (path, err) in skipped {
+        let rel: PathBuf = path.strip_prefix(selected_dir).map_or_else(
+            |_| PathBuf::from(path.to_string_lossy().to_string()),
+            std::path::Path::to_path_buf,
+        );
+        let _ = writeln!(
+            notes,
+            "- {}: {}",
+            path_rewrite.apply(&path_to_unix(&rel)),
+            skipped_file_reason(&err, deterministic)
+        );
+    }
+    notes.push('\n');
+}
@@ -0,0 +1,63 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::wildcard_match;
+
+/* ============================= Branch profiles ============================== */
+
+/// A profile picked automatically while a matching git branch is checked out.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct BranchProfile {
+    /// Branch name or pattern; `*` matches any run of characters, `/` included
+    /// (`feature/ui-*`).
+    pub branch: String,
+    pub profile: String,
+}
+
+/// Profile bound to `branch`: the first binding whose pattern matches.
+#[must_use]
+pub fn profile_for_branch<'a>(bindings: &'a [BranchProfile], branch: &str) -> Option<&'a str> {
+    bindings
+        .iter()
+        .find(|b| wildcard_match(b.branch.trim(), branch))
+        .map(|b| b.profile.as_str())
+        .filter(|p| !p.trim().is_empty())
+}
+
+/* ================================ Git HEAD ================================== */
+
+/// `HEAD` file of the git repository `project_root` is in (the project folder or one of
+/// its parents). `.git` files of worktrees and submodules are followed.
+#[must_use]
+pub fn git_head_file(project_root: &Path) -> Option<PathBuf> {
+    project_root.ancestors().find_map(|dir| {
+        let dot_git = dir.join(".git");
+        if dot_git.is_dir() {
+            return Some(dot_git.join("HEAD"));
+        }
+        let link = fs::read_to_string(&dot_git).ok()?;
+        let git_dir = link.trim().strip_prefix("gitdir:")?.trim();
+        Some(dir.join(git_dir).join("HEAD"))
+    })
+}
+
+/// Branch named by the contents of a `HEAD` file; `None` for a detached HEAD.
+#[must_use]
+pub fn branch_from_head(head: &str) -> Option<String> {
+    head.trim()
+        .strip_prefix("ref:")?
+        .trim()
+        .strip_prefix("refs/heads/")
+        .map(str::to_string)
+}
+
+/// Branch checked out in the repository of `project_root`, if any.
+#[must_use]
+pub fn current_git_branch(project_root: &Path) -> Option<String> {
+    let head = fs::read_to_string(git_head_file(project_root)?).ok()?;
+    branch_from_head(&head)
+}
@@ -0,0 +1,161 @@
+use serde::Serialize;
+
+use crate::core::{
+    collapse_consecutive_blank_lines, is_ident_byte, remove_slash_comments, scan_string_literal,
+    skip_braced_block, skip_slash_comment, trim_leading_blank_lines,
+};
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct GoFilterOptions {
+    pub remove_line_comments: bool,
+    pub remove_block_comments: bool,
+    pub function_signatures_only: bool,
+}
+
+/// Returns true if the given path ends with ".go".
+#[must_use]
+pub fn is_go_file_path(path: &std::path::Path) -> bool {
+    path.extension().and_then(|e| e.to_str()) == Some("go")
+}
+
+/// Returns true for protobuf-generated Go files (`*.pb.go`, including `*_grpc.pb.go`).
+#[must_use]
+pub fn is_generated_go_file_path(path: &std::path::Path) -> bool {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .is_some_and(|n| n.ends_with(".pb.go"))
+}
+
+/// Apply Go-specific filters to a source file's string contents.
+/// This function only transforms when at least one option is enabled; otherwise returns input as-is.
+#[must_use]
+pub fn apply_go_filters(source: &str, opts: &GoFilterOptions) -> String {
+    if !(opts.remove_line_comments || opts.remove_block_comments || opts.function_signatures_only) {
+        return source.to_string();
+    }
+    let cleaned = if opts.remove_line_comments || opts.remove_block_comments {
+        remove_slash_comments(
+            source,
+            opts.remove_line_comments,
+            opts.remove_block_comments,
+            skip_go_literal,
+        )
+    } else {
+        source.to_string()
+    };
+    let reduced = if opts.function_signatures_only {
+        go_signatures_only(&cleaned)
+    } else {
+        cleaned
+    };
+    trim_leading_blank_lines(&collapse_consecutive_blank_lines(&reduced))
+}
+
+/// End of the interpreted string, rune or raw (`` `...` ``) string starting at `idx`.
+fn skip_go_literal(bytes: &[u8], len: usize, idx: usize) -> Option<usize> {
+    match bytes[idx] {
+        b'"' | b'\'' => Some(scan_string_literal(bytes, len, idx, bytes[idx])),
+        b'`' => Some(
+            bytes[idx + 1..len]
+                .iter()
+                .position(|&b| b == b'`')
+                .map_or(len, |p| idx + p + 2),
+        ),
+        _ => None,
+    }
+}
+
+/// Drop the bodies of top-level `func` declarations, methods included, keeping the
+/// signature line (a body-less declaration is valid Go).
+fn go_signatures_only(src: &str) -> String {
+    let bytes = src.as_bytes();
+    let len = bytes.len();
+    let mut out = String::with_capacity(len);
+    let mut last_emit = 0usize;
+    let mut depth = 0usize;
+    let mut idx = 0usize;
+    while idx < len {
+        if let Some(next) =
+            skip_slash_comment(bytes, len, idx).or_else(|| skip_go_literal(bytes, len, idx))
+        {
+            idx = next;
+            continue;
+        }
+        match bytes[idx] {
+            b'{' => depth += 1,
+            b'}' => depth = depth.saturating_sub(1),
+            b'f' if depth == 0 && starts_func_declaration(bytes, idx) => {
+                if let Some(body) = find_func_body(bytes, len, idx + 4) {
+                    let mut sig_end = body;
+                    while sig_end > idx && bytes[sig_end - 1].is_ascii_whitespace() {
+                        sig_end -= 1;
+                    }
+                    out.push_str(&src[last_emit..sig_end]);
+                    out.push('\n');
+                    idx = skip_braced_block(bytes, len, body + 1, skip_go_literal);
+                    while idx < len && matches!(bytes[idx], b' ' | b'\t' | b'\r') {
+                        idx += 1;
+                    }
+                    if idx < len && bytes[idx] == b'\n' {
+                        idx += 1;
+                    }
+                    last_emit = idx;
+                    continue;
+                }
+            }
+            _ => {}
+        }
+        idx += 1;
+    }
+    out.push_str(&src[last_emit..]);
+    out
+}
+
+/// `func` as the first word of a line.
+fn starts_func_declaration(bytes: &[u8], idx: usize) -> bool {
+    bytes[idx..].starts_with(b"func")
+        && bytes.get(idx + 4).is_none_or(|&b| !is_ident_byte(b))
+        && bytes[..idx]
+            .iter()
+            .rev()
+            .find(|b| !matches!(b, b' ' | b'\t'))
+            .is_none_or(|&b| b == b'\n')
+}
+
+/// Index of the `{` opening the body of the declaration whose receiver, name and
+/// signature start at `idx`; `None` for a body-less declaration.
+fn find_func_body(bytes: &[u8], len: usize, mut idx: usize) -> Option<usize> {
+    let mut depth = 0i32;
+    while idx < len {
+        if let Some(next) =
+            skip_slash_comment(bytes, len, idx).or_else(|| skip_go_literal(bytes, len, idx))
+        {
+            idx = next;
+            continue;
+        }
+        match bytes[idx] {
+            b'(' | b'[' => depth += 1,
+            b')' | b']' => depth -= 1,
+            // Results like `interface{}` or `struct{ a int }` come before the body.
+            b'{' if depth == 0 && follows_type_keyword(bytes, idx) => {
+                idx = skip_braced_block(bytes, len, idx + 1, skip_go_literal);
+                continue;
+            }
+            b'{' if depth == 0 => return Some(idx),
+            b'\n' if depth == 0 => return None,
+            _ => {}
+        }
+        idx += 1;
+    }
+    None
+}
+
+fn follows_type_keyword(bytes: &[u8], brace: usize) -> bool {
+    let before = bytes[..brace].trim_ascii_end();
+    [b"struct".as_slice(), b"interface".as_slice()]
+        .iter()
+        .any(|kw| {
+            before.ends_with(kw)
+                && (before.len() == kw.len() || !is_ident_byte(before[before.len() - kw.len() - 1]))
+        })
+}
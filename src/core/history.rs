@@ -0,0 +1,165 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::workspace_dir;
+
+/* ============================== Output history ============================== */
+
+/// Outputs kept per project when the local settings don't say otherwise.
+pub const DEFAULT_HISTORY_LIMIT: usize = 20;
+
+/// Metadata of one output kept in the history; the text is stored next to it.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct HistoryEntry {
+    /// File stem in the history folder; sorts oldest to newest.
+    #[serde(skip)]
+    pub id: String,
+    /// Local time the output was recorded, as shown in the UI.
+    pub created: String,
+    /// Profile selected at the time (`None` = workspace settings).
+    #[serde(default)]
+    pub profile: Option<String>,
+    /// Token count as shown in the stats (a heuristic estimate when `tokens_estimated`).
+    pub tokens: usize,
+    #[serde(default)]
+    pub tokens_estimated: bool,
+    pub chars: usize,
+}
+
+impl HistoryEntry {
+    /// One line for the history list, e.g. `2026-10-15 14:03:22 • backend • ~12034 tokens`.
+    #[must_use]
+    pub fn label(&self) -> String {
+        let profile = self
+            .profile
+            .as_ref()
+            .map_or_else(String::new, |profile| format!("{profile} • "));
+        let approx = if self.tokens_estimated { "~" } else { "" };
+        format!("{} • {profile}{approx}{} tokens", self.created, self.tokens)
+    }
+}
+
+#[must_use]
+pub fn history_dir(project_root: &Path) -> PathBuf {
+    workspace_dir(project_root).join("local").join("history")
+}
+
+/// `[metadata, text]` files of the entry `id`.
+fn entry_paths(project_root: &Path, id: &str) -> [PathBuf; 2] {
+    let dir = history_dir(project_root);
+    [
+        dir.join(format!("{id}.json")),
+        dir.join(format!("{id}.txt")),
+    ]
+}
+
+/// Add `output` to the project's history and drop the oldest entries beyond `keep`.
+///
+/// Nothing is recorded when `output` is identical to the newest entry or `keep` is 0.
+/// Returns the stored entry (with its `id` filled in), if one was added.
+///
+/// # Errors
+/// Returns I/O errors from writing/renaming/removing files, or serialization errors.
+pub fn record_history(
+    project_root: &Path,
+    entry: &HistoryEntry,
+    output: &str,
+    keep: usize,
+) -> io::Result<Option<HistoryEntry>> {
+    if keep == 0 {
+        return Ok(None);
+    }
+    let existing = list_history(project_root);
+    if existing
+        .first()
+        .and_then(|newest| load_history_output(project_root, &newest.id))
+        .is_some_and(|text| text == output)
+    {
+        return Ok(None);
+    }
+
+    let dir = history_dir(project_root);
+    fs::create_dir_all(&dir)?;
+    let id = next_history_id(&existing);
+    let [meta_path, text_path] = entry_paths(project_root, &id);
+
+    let tmp = text_path.with_extension("txt.tmp");
+    fs::write(&tmp, output)?;
+    fs::rename(&tmp, &text_path)?;
+    let data = serde_json::to_vec_pretty(entry).map_err(|e| io::Error::other(e.to_string()))?;
+    let tmp = meta_path.with_extension("json.tmp");
+    fs::write(&tmp, data)?;
+    fs::rename(&tmp, &meta_path)?;
+
+    for old in existing.iter().skip(keep.saturating_sub(1)) {
+        delete_history_entry(project_root, &old.id)?;
+    }
+    Ok(Some(HistoryEntry {
+        id,
+        ..entry.clone()
+    }))
+}
+
+/// Millisecond timestamp, bumped past the newest entry so ids stay unique and ordered.
+fn next_history_id(existing: &[HistoryEntry]) -> String {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_millis());
+    let newest = existing
+        .first()
+        .and_then(|e| e.id.parse::<u128>().ok())
+        .unwrap_or(0);
+    format!("{:015}", now.max(newest + 1))
+}
+
+/// Entries of the project's history, newest first. Entries whose text is missing are skipped.
+#[must_use]
+pub fn list_history(project_root: &Path) -> Vec<HistoryEntry> {
+    let Ok(read_dir) = fs::read_dir(history_dir(project_root)) else {
+        return Vec::new();
+    };
+    let mut entries: Vec<HistoryEntry> = read_dir
+        .flatten()
+        .filter_map(|ent| {
+            let path = ent.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                return None;
+            }
+            let id = path.file_stem()?.to_str()?.to_string();
+            if !path.with_extension("txt").is_file() {
+                return None;
+            }
+            let data = fs::read(&path).ok()?;
+            let entry = serde_json::from_slice::<HistoryEntry>(&data).ok()?;
+            Some(HistoryEntry { id, ..entry })
+        })
+        .collect();
+    entries.sort_by(|a, b| b.id.cmp(&a.id));
+    entries
+}
+
+/// Full text of the history entry `id`.
+#[must_use]
+pub fn load_history_output(project_root: &Path, id: &str) -> Option<String> {
+    let [_, text_path] = entry_paths(project_root, id);
+    fs::read_to_string(text_path).ok()
+}
+
+/// Remove the history entry `id` (both files) if it exists.
+///
+/// # Errors
+/// Returns I/O errors other than "not found".
+pub fn delete_history_entry(project_root: &Path, id: &str) -> io::Result<()> {
+    for path in entry_paths(project_root, id) {
+        match fs::remove_file(&path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+            _ => {}
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,174 @@
+use std::{path::Path, sync::OnceLock};
+
+use regex::Regex;
+
+/* ============================ Image placeholders ============================ */
+
+/// Extensions emitted as a placeholder block instead of their bytes.
+pub const IMAGE_EXTENSIONS: [&str; 9] = [
+    "png", "jpg", "jpeg", "gif", "webp", "bmp", "ico", "svg", "avif",
+];
+
+/// SVG files up to this many bytes are emitted as XML under their placeholder line
+/// when the workspace doesn't set `svg_inline_limit`.
+pub const DEFAULT_SVG_INLINE_LIMIT: u64 = 4096;
+
+/// Whether `path` has one of [`IMAGE_EXTENSIONS`] (any case).
+#[must_use]
+pub fn is_image_path(path: &Path) -> bool {
+    image_extension(path).is_some()
+}
+
+fn image_extension(path: &Path) -> Option<String> {
+    let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+    IMAGE_EXTENSIONS.contains(&ext.as_str()).then_some(ext)
+}
+
+/// Width and height in pixels read from the image header (PNG, JPEG, GIF, BMP, WebP,
+/// ICO) or the `width`/`height`/`viewBox` attributes of an SVG root element.
+#[must_use]
+pub fn image_dimensions(bytes: &[u8]) -> Option<(u32, u32)> {
+    let be16 = |i: usize| {
+        Some(u32::from(u16::from_be_bytes(
+            bytes.get(i..i + 2)?.try_into().ok()?,
+        )))
+    };
+    let le16 = |i: usize| {
+        Some(u32::from(u16::from_le_bytes(
+            bytes.get(i..i + 2)?.try_into().ok()?,
+        )))
+    };
+    let be32 = |i: usize| Some(u32::from_be_bytes(bytes.get(i..i + 4)?.try_into().ok()?));
+    let le32 = |i: usize| Some(i32::from_le_bytes(bytes.get(i..i + 4)?.try_into().ok()?));
+
+    if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        return Some((be32(16)?, be32(20)?));
+    }
+    if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+        return Some((le16(6)?, le16(8)?));
+    }
+    if bytes.starts_with(b"BM") {
+        // Height is negative for top-down bitmaps.
+        return Some((le32(18)?.unsigned_abs(), le32(22)?.unsigned_abs()));
+    }
+    if bytes.starts_with(b"\0\0\x01\0") {
+        // ICO: first entry; 0 means 256.
+        let side = |b: u8| if b == 0 { 256 } else { u32::from(b) };
+        return Some((side(*bytes.get(6)?), side(*bytes.get(7)?)));
+    }
+    if bytes.starts_with(b"\xff\xd8") {
+        return jpeg_dimensions(bytes, be16);
+    }
+    if bytes.starts_with(b"RIFF") && bytes.get(8..12) == Some(b"WEBP") {
+        let le24 = |i: usize| {
+            let b = bytes.get(i..i + 3)?;
+            Some(u32::from(b[0]) | u32::from(b[1]) << 8 | u32::from(b[2]) << 16)
+        };
+        return match bytes.get(12..16)? {
+            b"VP8X" => Some((le24(24)? + 1, le24(27)? + 1)),
+            b"VP8L" => {
+                let bits = u32::from_le_bytes(bytes.get(21..25)?.try_into().ok()?);
+                Some(((bits & 0x3fff) + 1, ((bits >> 14) & 0x3fff) + 1))
+            }
+            b"VP8 " => Some((le16(26)? & 0x3fff, le16(28)? & 0x3fff)),
+            _ => None,
+        };
+    }
+    // The root element of an SVG is near the top.
+    svg_dimensions(&String::from_utf8_lossy(&bytes[..bytes.len().min(4096)]))
+}
+
+fn jpeg_dimensions(bytes: &[u8], be16: impl Fn(usize) -> Option<u32>) -> Option<(u32, u32)> {
+    let mut i = 2;
+    while i + 4 <= bytes.len() {
+        if bytes[i] != 0xff {
+            return None;
+        }
+        let marker = bytes[i + 1];
+        if marker == 0xff {
+            i += 1;
+            continue;
+        }
+        // SOF0..SOF15 except DHT (C4), JPG (C8) and DAC (CC).
+        if (0xc0..=0xcf).contains(&marker) && !matches!(marker, 0xc4 | 0xc8 | 0xcc) {
+            return Some((be16(i + 7)?, be16(i + 5)?));
+        }
+        i += 2 + usize::try_from(be16(i + 2)?).ok()?;
+    }
+    None
+}
+
+fn svg_dimensions(text: &str) -> Option<(u32, u32)> {
+    static ROOT: OnceLock<Regex> = OnceLock::new();
+    let root = ROOT.get_or_init(|| Regex::new(r"(?s)<svg\b[^>]*>").expect("svg root regex"));
+    let tag = root.find(text)?.as_str();
+
+    let attr = |name: &str| -> Option<String> {
+        let re = Regex::new(&format!(r#"\s{name}\s*=\s*["']([^"']*)["']"#)).ok()?;
+        Some(re.captures(tag)?[1].trim().to_string())
+    };
+    // Positive and finite; `as` saturates sizes beyond `u32`.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let pixels = |value: String| -> Option<u32> {
+        let number = value.strip_suffix("px").unwrap_or(&value);
+        let n: f64 = number.parse().ok()?;
+        (n.is_finite() && n > 0.0).then(|| n.round() as u32)
+    };
+    if let (Some(w), Some(h)) = (
+        attr("width").and_then(pixels),
+        attr("height").and_then(pixels),
+    ) {
+        return Some((w, h));
+    }
+    let view_box = attr("viewBox")?;
+    let parts: Vec<&str> = view_box
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|p| !p.is_empty())
+        .collect();
+    match parts.as_slice() {
+        [_, _, w, h] => Some((pixels((*w).to_string())?, pixels((*h).to_string())?)),
+        _ => None,
+    }
+}
+
+/// `12.3 KiB`-style size (bytes below 1 KiB).
+#[must_use]
+pub fn format_byte_size(bytes: u64) -> String {
+    const UNITS: [&str; 3] = ["KiB", "MiB", "GiB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    #[allow(clippy::cast_precision_loss)]
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit + 1 < UNITS.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{value:.1} {}", UNITS[unit])
+}
+
+/// The block emitted for the image at `path` (its bytes in `bytes`):
+/// `[image: logo.png, 64x64 px, 1.2 KiB]`, followed by the XML of SVG files up to
+/// `svg_inline_limit` bytes (`0` never inlines).
+#[must_use]
+pub fn image_placeholder(path: &Path, bytes: &[u8], svg_inline_limit: u64) -> String {
+    let name = path
+        .file_name()
+        .map_or_else(String::new, |n| n.to_string_lossy().into_owned());
+    let size = format_byte_size(bytes.len() as u64);
+    let mut out = match image_dimensions(bytes) {
+        Some((w, h)) => format!("[image: {name}, {w}x{h} px, {size}]\n"),
+        None => format!("[image: {name}, {size}]\n"),
+    };
+    if image_extension(path).as_deref() == Some("svg")
+        && bytes.len() as u64 <= svg_inline_limit
+        && let Ok(xml) = std::str::from_utf8(bytes)
+    {
+        out.push_str(xml);
+        if !xml.ends_with('\n') {
+            out.push('\n');
+        }
+    }
+    out
+}
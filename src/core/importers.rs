@@ -0,0 +1,390 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde_json::Value as Json;
+use toml::{Table, Value as Toml};
+
+use crate::core::{
+    BUILTIN_TOKENIZERS, FileMarkers, OutputOrder, OutputSection, WorkspaceSettings, display_path,
+};
+
+/* ====================== Importing other tools' configs ====================== */
+
+/// Config files of other tools that can be imported, in lookup order.
+pub const IMPORTABLE_CONFIG_FILES: [&str; 4] = [
+    "repomix.config.json",
+    ".c2pconfig",
+    ".code2prompt.toml",
+    "code2prompt.toml",
+];
+
+/// First config in the project root that [`import_tool_config`] understands.
+#[must_use]
+pub fn find_tool_config(project_root: &Path) -> Option<PathBuf> {
+    IMPORTABLE_CONFIG_FILES
+        .iter()
+        .map(|name| project_root.join(name))
+        .find(|path| path.is_file())
+}
+
+/// Read a repomix (`*.json`) or code2prompt (TOML) config into `settings`.
+///
+/// Only settings present in the file are changed. Returns one note per setting or
+/// pattern that has no stitch equivalent.
+///
+/// # Errors
+/// Returns a one-line message if the file can't be read or parsed.
+pub fn import_tool_config(
+    path: &Path,
+    settings: &mut WorkspaceSettings,
+) -> Result<Vec<String>, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("{}: {e}", display_path(path)))?;
+    let is_json = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+    if is_json {
+        import_repomix_config(&text, settings)
+    } else {
+        import_code2prompt_config(&text, settings)
+    }
+}
+
+/// Map a `repomix.config.json` into `settings`.
+///
+/// Reads `include`, `ignore.customPatterns`, `output.style`, `output.showLineNumbers`,
+/// `output.removeComments`, `output.directoryStructure` and `tokenCount.encoding`.
+///
+/// # Errors
+/// Returns a one-line message if `text` is not a JSON object.
+pub fn import_repomix_config(
+    text: &str,
+    settings: &mut WorkspaceSettings,
+) -> Result<Vec<String>, String> {
+    let config: Json =
+        serde_json::from_str(text).map_err(|e| format!("invalid repomix config: {e}"))?;
+    if !config.is_object() {
+        return Err("invalid repomix config: expected a JSON object".into());
+    }
+    let mut skipped = Vec::new();
+
+    let include = json_strings(&config["include"]);
+    let ignore = json_strings(&config["ignore"]["customPatterns"]);
+    if !config["include"].is_null() || !config["ignore"]["customPatterns"].is_null() {
+        PatternFilters::from_patterns(&include, &ignore, &mut skipped).apply_to(settings);
+    }
+
+    let output = &config["output"];
+    if let Some(style) = output["style"].as_str() {
+        match repomix_markers(style) {
+            Some(markers) => settings.file_markers = markers,
+            None => skipped.push(format!("output.style \"{style}\"")),
+        }
+    }
+    if let Some(on) = output["showLineNumbers"].as_bool() {
+        settings.line_numbers = on;
+    }
+    if let Some(on) = output["removeComments"].as_bool() {
+        set_remove_comments(settings, on);
+    }
+    if let Some(on) = output["directoryStructure"].as_bool() {
+        set_hierarchy_enabled(settings, on);
+    }
+    for key in ["headerText", "instructionFilePath"] {
+        if output[key].as_str().is_some_and(|v| !v.is_empty()) {
+            skipped.push(format!("output.{key}"));
+        }
+    }
+    for key in ["removeEmptyLines", "compress", "includeEmptyDirectories"] {
+        if output[key].as_bool() == Some(true) {
+            skipped.push(format!("output.{key}"));
+        }
+    }
+    if let Some(encoding) = config["tokenCount"]["encoding"].as_str() {
+        set_tokenizer(settings, encoding, "tokenCount.encoding", &mut skipped);
+    }
+    Ok(skipped)
+}
+
+/// Map a code2prompt TOML config into `settings`.
+///
+/// Reads `include_patterns`, `exclude_patterns`, `output_format`, `no_codeblock`,
+/// `line_numbers`, `sort_method` and `encoding`.
+///
+/// # Errors
+/// Returns a one-line message if `text` is not valid TOML.
+pub fn import_code2prompt_config(
+    text: &str,
+    settings: &mut WorkspaceSettings,
+) -> Result<Vec<String>, String> {
+    let config: Table = text
+        .parse()
+        .map_err(|e: toml::de::Error| format!("invalid code2prompt config: {}", e.message()))?;
+    let mut skipped = Vec::new();
+
+    let include = config.get("include_patterns").map(toml_strings);
+    let exclude = config.get("exclude_patterns").map(toml_strings);
+    if include.is_some() || exclude.is_some() {
+        PatternFilters::from_patterns(
+            &include.unwrap_or_default(),
+            &exclude.unwrap_or_default(),
+            &mut skipped,
+        )
+        .apply_to(settings);
+    }
+
+    let no_codeblock = config.get("no_codeblock").and_then(Toml::as_bool) == Some(true);
+    let format = config.get("output_format").and_then(Toml::as_str);
+    match format.or_else(|| no_codeblock.then_some("markdown")) {
+        Some("markdown") => {
+            settings.file_markers = if no_codeblock {
+                FileMarkers {
+                    header: "`{path}`:\n".into(),
+                    footer: String::new(),
+                }
+            } else {
+                FileMarkers {
+                    header: "`{path}`:\n\n```{lang}".into(),
+                    footer: "```".into(),
+                }
+            };
+        }
+        Some("xml") => settings.file_markers = xml_markers(),
+        Some(other) => skipped.push(format!("output_format \"{other}\"")),
+        None => {}
+    }
+    if let Some(on) = config.get("line_numbers").and_then(Toml::as_bool) {
+        settings.line_numbers = on;
+    }
+    if let Some(sort) = config.get("sort_method").and_then(Toml::as_str) {
+        match sort {
+            "name_asc" => settings.output_order = OutputOrder::Alphabetical,
+            other => skipped.push(format!("sort_method \"{other}\"")),
+        }
+    }
+    if let Some(encoding) = config.get("encoding").and_then(Toml::as_str) {
+        set_tokenizer(settings, encoding, "encoding", &mut skipped);
+    }
+    for key in ["absolute_path", "full_directory_tree", "follow_symlinks"] {
+        if config.get(key).and_then(Toml::as_bool) == Some(true) {
+            skipped.push(key.to_string());
+        }
+    }
+    if config.get("template").is_some() {
+        skipped.push("template".to_string());
+    }
+    Ok(skipped)
+}
+
+fn json_strings(value: &Json) -> Vec<String> {
+    match value {
+        Json::Array(items) => items
+            .iter()
+            .filter_map(Json::as_str)
+            .map(str::to_string)
+            .collect(),
+        Json::String(s) => split_patterns(s),
+        _ => Vec::new(),
+    }
+}
+
+/// code2prompt accepts both arrays and comma-separated strings.
+fn toml_strings(value: &Toml) -> Vec<String> {
+    match value {
+        Toml::Array(items) => items
+            .iter()
+            .filter_map(Toml::as_str)
+            .map(str::to_string)
+            .collect(),
+        Toml::String(s) => split_patterns(s),
+        _ => Vec::new(),
+    }
+}
+
+fn split_patterns(raw: &str) -> Vec<String> {
+    raw.split(',')
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn xml_markers() -> FileMarkers {
+    FileMarkers {
+        header: "<file path=\"{path}\">".into(),
+        footer: "</file>".into(),
+    }
+}
+
+fn repomix_markers(style: &str) -> Option<FileMarkers> {
+    match style {
+        "xml" => Some(xml_markers()),
+        "markdown" => Some(FileMarkers {
+            header: "## File: {path}\n```{lang}".into(),
+            footer: "```".into(),
+        }),
+        "plain" => Some(FileMarkers {
+            header: "================\nFile: {path}\n================".into(),
+            footer: String::new(),
+        }),
+        _ => None,
+    }
+}
+
+const fn set_remove_comments(settings: &mut WorkspaceSettings, on: bool) {
+    settings.rust.rust_remove_inline_comments = on;
+    settings.rust.rust_remove_doc_comments = on;
+    settings.slint.slint_remove_line_comments = on;
+    settings.slint.slint_remove_block_comments = on;
+}
+
+fn set_hierarchy_enabled(settings: &mut WorkspaceSettings, on: bool) {
+    let mut sections = settings.layout.resolved();
+    for section in &mut sections {
+        if section.section == OutputSection::Hierarchy {
+            section.enabled = on;
+        }
+    }
+    settings.layout.sections = sections;
+}
+
+fn set_tokenizer(
+    settings: &mut WorkspaceSettings,
+    encoding: &str,
+    key: &str,
+    skipped: &mut Vec<String>,
+) {
+    let name = encoding.trim().to_ascii_lowercase();
+    let name = if name.ends_with("_base") {
+        name
+    } else {
+        format!("{name}_base")
+    };
+    if BUILTIN_TOKENIZERS.contains(&name.as_str()) {
+        settings.tokenizer = name;
+    } else {
+        skipped.push(format!("{key} \"{encoding}\""));
+    }
+}
+
+/* ---------------------------- Glob pattern mapping --------------------------- */
+
+/// stitch filters approximating a list of include/exclude globs.
+#[derive(Debug, Default)]
+struct PatternFilters {
+    include_exts: Vec<String>,
+    exclude_exts: Vec<String>,
+    exclude_dirs: Vec<String>,
+    exclude_files: Vec<String>,
+}
+
+impl PatternFilters {
+    fn from_patterns(include: &[String], exclude: &[String], skipped: &mut Vec<String>) -> Self {
+        let mut filters = Self::default();
+        for pattern in include {
+            filters.add_include(pattern, skipped);
+        }
+        for pattern in exclude {
+            filters.add_exclude(pattern, skipped);
+        }
+        if !filters.include_exts.is_empty() {
+            // stitch ignores extension exclusions next to inclusions.
+            for ext in filters.exclude_exts.drain(..) {
+                skipped.push(format!(
+                    "exclude \"*.{ext}\" (only listed extensions are included)"
+                ));
+            }
+        }
+        filters
+    }
+
+    fn add_include(&mut self, pattern: &str, skipped: &mut Vec<String>) {
+        let glob = strip_any_dir_prefix(pattern.trim());
+        if matches!(glob, "" | "*" | "**" | "*.*") {
+            return;
+        }
+        let (dir, name) = glob.rsplit_once('/').unwrap_or(("", glob));
+        match extensions_of(name) {
+            Some(exts) => {
+                push_unique(&mut self.include_exts, exts);
+                if !dir.is_empty() {
+                    skipped.push(format!(
+                        "include \"{pattern}\" (kept the extension, not the path)"
+                    ));
+                }
+            }
+            None => skipped.push(format!("include \"{pattern}\"")),
+        }
+    }
+
+    fn add_exclude(&mut self, pattern: &str, skipped: &mut Vec<String>) {
+        let raw = pattern.trim();
+        let glob = strip_any_dir_prefix(raw);
+        let (glob, is_dir) = glob
+            .strip_suffix("/**/*")
+            .or_else(|| glob.strip_suffix("/**"))
+            .or_else(|| glob.strip_suffix('/'))
+            .map_or((glob, false), |dir| (dir, true));
+        if glob.is_empty() || glob.contains('/') {
+            skipped.push(format!("exclude \"{pattern}\""));
+        } else if let Some(exts) = extensions_of(glob).filter(|_| !is_dir) {
+            push_unique(&mut self.exclude_exts, exts);
+        } else if glob.contains(['*', '?', '[', '{']) {
+            skipped.push(format!("exclude \"{pattern}\""));
+        } else if is_dir || !glob.trim_start_matches('.').contains('.') {
+            // Names without an extension are taken to be directories.
+            push_unique(&mut self.exclude_dirs, vec![glob.to_string()]);
+        } else {
+            push_unique(&mut self.exclude_files, vec![glob.to_string()]);
+        }
+    }
+
+    /// Replace the filter fields of `settings`.
+    fn apply_to(self, settings: &mut WorkspaceSettings) {
+        let exts: Vec<String> = if self.include_exts.is_empty() {
+            self.exclude_exts.iter().map(|e| format!("-.{e}")).collect()
+        } else {
+            self.include_exts.iter().map(|e| format!(".{e}")).collect()
+        };
+        settings.ext_filter = exts.join(",");
+        settings.exclude_dirs = self.exclude_dirs.join(",");
+        settings.exclude_files = self.exclude_files.join(",");
+    }
+}
+
+/// Drop `./` and leading `**/` (stitch filters match at any depth anyway).
+fn strip_any_dir_prefix(mut glob: &str) -> &str {
+    glob = glob.strip_prefix("./").unwrap_or(glob);
+    while let Some(rest) = glob.strip_prefix("**/") {
+        glob = rest;
+    }
+    glob
+}
+
+/// `*.rs` → `["rs"]`, `*.{ts,tsx}` → `["ts", "tsx"]`, `*.min.js` → `["min.js"]`.
+fn extensions_of(name: &str) -> Option<Vec<String>> {
+    let ext = name.strip_prefix("*.")?;
+    let exts: Vec<String> = ext
+        .strip_prefix('{')
+        .and_then(|e| e.strip_suffix('}'))
+        .map_or_else(
+            || vec![ext.to_ascii_lowercase()],
+            |list| {
+                list.split(',')
+                    .map(|e| e.trim().to_ascii_lowercase())
+                    .collect()
+            },
+        );
+    exts.iter()
+        .all(|e| !e.is_empty() && !e.contains(['*', '?', '[', '{', '}', '/']))
+        .then_some(exts)
+}
+
+fn push_unique(list: &mut Vec<String>, items: Vec<String>) {
+    for item in items {
+        if !list.contains(&item) {
+            list.push(item);
+        }
+    }
+}
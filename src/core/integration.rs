@@ -0,0 +1,252 @@
+use std::{fmt::Write, path::Path, path::PathBuf};
+
+/* ======================== "Open with Stitch" integration ====================== */
+
+/// URL scheme registered by [`render_integration`]: `stitch://open?path=/work/app`.
+pub const URL_SCHEME: &str = "stitch";
+
+/// Desktops `stitch integrate` writes registration files for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Platform {
+    Macos,
+    Windows,
+    Linux,
+}
+
+impl Platform {
+    pub const ALL: [Self; 3] = [Self::Macos, Self::Windows, Self::Linux];
+
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Macos => "macos",
+            Self::Windows => "windows",
+            Self::Linux => "linux",
+        }
+    }
+
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|p| p.name() == name)
+    }
+}
+
+/// What an `--open` argument asks for: a folder, plus the profile and generate flags a
+/// `stitch://` URL may carry.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OpenTarget {
+    pub path: PathBuf,
+    pub profile: Option<String>,
+    pub generate: bool,
+}
+
+/// Whether `arg` is a `stitch:` URL rather than a path.
+#[must_use]
+pub fn is_open_url(arg: &str) -> bool {
+    arg.get(..URL_SCHEME.len() + 1)
+        .is_some_and(|s| s.eq_ignore_ascii_case("stitch:"))
+}
+
+/// Parse an `--open` argument: a plain path, a `file://` URL (what Linux file managers
+/// pass for `%u`), or `stitch://open?path=…&profile=…&generate`.
+///
+/// # Errors
+/// Returns a one-line message for empty arguments, URLs that aren't `open` requests,
+/// missing or badly encoded paths, and `file://` URLs for other hosts.
+pub fn parse_open_target(arg: &str) -> Result<OpenTarget, String> {
+    if arg.trim().is_empty() {
+        return Err("--open requires a folder or URL".into());
+    }
+    if is_open_url(arg) {
+        return parse_stitch_url(arg);
+    }
+    if let Some(rest) = strip_prefix_ignore_case(arg, "file://") {
+        // `file:///path` or `file://localhost/path`
+        let path = rest.strip_prefix("localhost").unwrap_or(rest);
+        if !path.starts_with('/') {
+            return Err(format!("unsupported file URL '{arg}'"));
+        }
+        let decoded = percent_decode(path).ok_or_else(|| format!("invalid file URL '{arg}'"))?;
+        return Ok(OpenTarget {
+            path: file_url_path(&decoded),
+            ..OpenTarget::default()
+        });
+    }
+    Ok(OpenTarget {
+        path: PathBuf::from(arg),
+        ..OpenTarget::default()
+    })
+}
+
+fn strip_prefix_ignore_case<'a>(s: &'a str, prefix: &str) -> Option<&'a str> {
+    s.get(..prefix.len())
+        .filter(|head| head.eq_ignore_ascii_case(prefix))
+        .map(|_| &s[prefix.len()..])
+}
+
+/// `/C:/work` from a Windows file URL is `C:/work`.
+fn file_url_path(decoded: &str) -> PathBuf {
+    let bytes = decoded.as_bytes();
+    if bytes.len() >= 3 && bytes[0] == b'/' && bytes[1].is_ascii_alphabetic() && bytes[2] == b':' {
+        PathBuf::from(&decoded[1..])
+    } else {
+        PathBuf::from(decoded)
+    }
+}
+
+fn parse_stitch_url(url: &str) -> Result<OpenTarget, String> {
+    let rest = &url[URL_SCHEME.len() + 1..];
+    let rest = rest.strip_prefix("//").unwrap_or(rest);
+    let (action, query) = rest.split_once('?').unwrap_or((rest, ""));
+    if !action.trim_end_matches('/').eq_ignore_ascii_case("open") {
+        return Err(format!(
+            "unsupported URL '{url}' (expected stitch://open?path=…)"
+        ));
+    }
+
+    let mut target = OpenTarget::default();
+    let mut path = None;
+    for pair in query.split('&').filter(|p| !p.is_empty()) {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        let value = percent_decode(&value.replace('+', " "))
+            .ok_or_else(|| format!("invalid encoding in URL '{url}'"))?;
+        match key {
+            "path" => path = Some(value),
+            "profile" if !value.trim().is_empty() => target.profile = Some(value),
+            "generate" => target.generate = !matches!(value.as_str(), "0" | "false"),
+            _ => {}
+        }
+    }
+    let path = path
+        .filter(|p| !p.trim().is_empty())
+        .ok_or_else(|| format!("URL '{url}' has no path"))?;
+    target.path = PathBuf::from(path);
+    Ok(target)
+}
+
+/// Decode `%XX` escapes; `None` for truncated escapes or invalid UTF-8.
+fn percent_decode(s: &str) -> Option<String> {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = s.get(i + 1..i + 3)?;
+            out.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(out).ok()
+}
+
+/// Registration file for `platform` that adds "Open with Stitch" for folders and hands
+/// `stitch://` URLs to `exe` with `--open`:
+///
+/// - Windows: a `.reg` file for the current user (folder and folder-background context
+///   menus, plus the URL protocol);
+/// - Linux: a `.desktop` entry for `inode/directory` and `x-scheme-handler/stitch`;
+/// - macOS: a Finder Quick Action script, since Finder hands folders to app bundles as
+///   Apple Events rather than arguments.
+#[must_use]
+pub fn render_integration(platform: Platform, exe: &Path) -> String {
+    match platform {
+        Platform::Windows => windows_registry(exe),
+        Platform::Linux => linux_desktop_entry(exe),
+        Platform::Macos => macos_quick_action(exe),
+    }
+}
+
+fn reg_string(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn windows_registry(exe: &Path) -> String {
+    let exe = exe.display().to_string();
+    let command = |arg: &str| reg_string(&format!("\"{exe}\" --open \"{arg}\""));
+    let icon = reg_string(&exe);
+    let classes = "HKEY_CURRENT_USER\\Software\\Classes";
+
+    let mut out = String::from("Windows Registry Editor Version 5.00\n\n");
+    out.push_str("; Save as stitch.reg and double-click it to add \"Open with Stitch\".\n");
+    for (key, arg) in [("Directory", "%1"), ("Directory\\Background", "%V")] {
+        let _ = writeln!(
+            out,
+            "\n[{classes}\\{key}\\shell\\Stitch]\n@=\"Open with Stitch\"\n\"Icon\"={icon}"
+        );
+        let _ = writeln!(
+            out,
+            "\n[{classes}\\{key}\\shell\\Stitch\\command]\n@={}",
+            command(arg)
+        );
+    }
+    let _ = writeln!(
+        out,
+        "\n[{classes}\\{URL_SCHEME}]\n@=\"URL:Stitch\"\n\"URL Protocol\"=\"\""
+    );
+    let _ = writeln!(
+        out,
+        "\n[{classes}\\{URL_SCHEME}\\shell\\open\\command]\n@={}",
+        command("%1")
+    );
+    out
+}
+
+/// Quote an `Exec=` argument per the desktop entry spec.
+fn desktop_exec_arg(arg: &str) -> String {
+    let needs_quotes = arg
+        .chars()
+        .any(|c| c.is_whitespace() || "\"'\\><~|&;$*?#()`".contains(c));
+    let arg = arg.replace('%', "%%");
+    if !needs_quotes {
+        return arg;
+    }
+    let escaped = arg
+        .replace('\\', "\\\\\\\\")
+        .replace('"', "\\\\\"")
+        .replace('`', "\\\\`")
+        .replace('$', "\\\\$");
+    format!("\"{escaped}\"")
+}
+
+fn linux_desktop_entry(exe: &Path) -> String {
+    let mut out = String::from(
+        "# Save as ~/.local/share/applications/stitch.desktop, then run\n\
+         # update-desktop-database ~/.local/share/applications\n",
+    );
+    out.push_str("[Desktop Entry]\nType=Application\nName=Stitch\n");
+    out.push_str("GenericName=Open with Stitch\n");
+    out.push_str("Comment=Select files from a project and stitch them into one text\n");
+    let _ = writeln!(
+        out,
+        "Exec={} --open %u",
+        desktop_exec_arg(&exe.display().to_string())
+    );
+    out.push_str("Terminal=false\nCategories=Development;\n");
+    let _ = writeln!(
+        out,
+        "MimeType=inode/directory;x-scheme-handler/{URL_SCHEME};"
+    );
+    out
+}
+
+fn shell_quote(text: &str) -> String {
+    format!("'{}'", text.replace('\'', "'\\''"))
+}
+
+fn macos_quick_action(exe: &Path) -> String {
+    let mut out = String::from(
+        "#!/bin/sh\n\
+         # Finder Quick Action: in Automator, create a Quick Action that receives\n\
+         # \"folders\" in \"Finder\", add \"Run Shell Script\" with \"Pass input: as arguments\",\n\
+         # paste this script and save it as \"Open with Stitch\".\n",
+    );
+    let _ = writeln!(
+        out,
+        "for f in \"$@\"; do\n    {} --open \"$f\" >/dev/null 2>&1 &\ndone",
+        shell_quote(&exe.display().to_string())
+    );
+    out
+}
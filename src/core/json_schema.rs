@@ -0,0 +1,513 @@
+use serde_json::{Map, Value, json};
+
+use crate::core::OutputSection;
+
+/* ============================ Settings JSON Schema ============================ */
+
+/// Settings file a JSON Schema is written for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SchemaTarget {
+    /// `.stitchworkspace/workspace.json` ([`crate::core::WorkspaceSettings`]).
+    Workspace,
+    /// `.stitchworkspace/profiles/*.json` ([`crate::core::Profile`]).
+    Profile,
+}
+
+impl SchemaTarget {
+    pub const ALL: [Self; 2] = [Self::Workspace, Self::Profile];
+
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Workspace => "workspace",
+            Self::Profile => "profile",
+        }
+    }
+
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|target| target.name() == name)
+    }
+}
+
+/// JSON Schema (draft-07) of the settings file `target`, for editor validation and CI
+/// linting. Unknown keys are rejected so typos are reported; `$schema` is allowed.
+#[must_use]
+pub fn json_schema(target: SchemaTarget) -> Value {
+    let (title, root) = match target {
+        SchemaTarget::Workspace => ("Stitch workspace settings", workspace_settings_schema()),
+        SchemaTarget::Profile => ("Stitch profile", profile_schema()),
+    };
+    let mut schema = Map::new();
+    schema.insert(
+        "$schema".into(),
+        json!("http://json-schema.org/draft-07/schema#"),
+    );
+    schema.insert("title".into(), json!(title));
+    if let Value::Object(root) = root {
+        schema.extend(root);
+    }
+    schema.insert("definitions".into(), definitions());
+    Value::Object(schema)
+}
+
+/// [`json_schema`] as pretty-printed JSON with a trailing newline.
+#[must_use]
+pub fn render_json_schema(target: SchemaTarget) -> String {
+    let mut out = serde_json::to_string_pretty(&json_schema(target)).unwrap_or_default();
+    out.push('\n');
+    out
+}
+
+fn string(description: &str) -> Value {
+    json!({ "type": "string", "description": description })
+}
+
+fn boolean(description: &str) -> Value {
+    json!({ "type": "boolean", "description": description })
+}
+
+fn count(description: &str) -> Value {
+    json!({ "type": "integer", "minimum": 0, "description": description })
+}
+
+fn reference(name: &str) -> Value {
+    json!({ "$ref": format!("#/definitions/{name}") })
+}
+
+fn object(properties: &[(&str, Value)], required: &[&str]) -> Value {
+    let properties: Map<String, Value> = properties
+        .iter()
+        .map(|(name, schema)| ((*name).to_string(), schema.clone()))
+        .collect();
+    json!({
+        "type": "object",
+        "properties": properties,
+        "required": required,
+        "additionalProperties": false,
+    })
+}
+
+fn workspace_settings_schema() -> Value {
+    let mut properties = vec![("$schema", string("URL or path of this schema."))];
+    properties.extend(workspace_settings_properties());
+    object(
+        &properties,
+        &[
+            "version",
+            "ext_filter",
+            "exclude_dirs",
+            "exclude_files",
+            "remove_prefix",
+            "remove_regex",
+            "hierarchy_only",
+            "dirs_only",
+        ],
+    )
+}
+
+fn profile_schema() -> Value {
+    object(
+        &[
+            ("$schema", string("URL or path of this schema.")),
+            ("name", string("Profile name.")),
+            ("settings", reference("workspace_settings")),
+            (
+                "explicit",
+                json!({
+                    "type": "array",
+                    "description": "Explicit on/off checks, relative to the project root.",
+                    "items": object(
+                        &[
+                            ("path", string("Project-relative path using forward slashes.")),
+                            ("state", boolean("Checked (true) or unchecked (false).")),
+                        ],
+                        &["path", "state"],
+                    ),
+                }),
+            ),
+            (
+                "samples",
+                json!({
+                    "type": "array",
+                    "description": "Directories that emit only a sample of their files.",
+                    "items": reference("directory_sample"),
+                }),
+            ),
+            (
+                "dir_rules",
+                json!({
+                    "type": "array",
+                    "description": "Per-directory scan overrides.",
+                    "items": reference("directory_rule"),
+                }),
+            ),
+            (
+                "pinned",
+                json!({
+                    "type": "array",
+                    "description": "Files always emitted first (project-relative, forward slashes).",
+                    "items": { "type": "string" },
+                }),
+            ),
+        ],
+        &["name", "settings", "explicit"],
+    )
+}
+
+#[allow(clippy::too_many_lines)] // one entry per settings field
+fn workspace_settings_properties() -> Vec<(&'static str, Value)> {
+    vec![
+        ("version", count("Settings format version.")),
+        (
+            "ext_filter",
+            string("Comma-separated extensions to include (`.rs`) or exclude (`-.lock`)."),
+        ),
+        (
+            "exclude_dirs",
+            string("Comma-separated directory names left out of the tree."),
+        ),
+        (
+            "exclude_files",
+            string("Comma-separated file names left out of the tree."),
+        ),
+        (
+            "remove_prefix",
+            string("Comma-separated line/comment prefixes to strip."),
+        ),
+        ("remove_regex", string("Regex whose matches are removed.")),
+        ("hierarchy_only", boolean("Emit only the file hierarchy.")),
+        ("dirs_only", boolean("Emit only the directories.")),
+        (
+            "summarize_unselected",
+            boolean("Summarize fully unselected top-level directories."),
+        ),
+        (
+            "line_numbers",
+            boolean("Prefix file lines with their original line numbers."),
+        ),
+        (
+            "dependencies_section",
+            boolean("Add a dependencies summary parsed from the project's manifests."),
+        ),
+        (
+            "deterministic",
+            boolean("Emit output that only depends on the selected files and settings."),
+        ),
+        ("layout", reference("output_layout")),
+        (
+            "tree_style",
+            json!({
+                "enum": ["unicode", "ascii"],
+                "description": "Branch characters of the hierarchy.",
+            }),
+        ),
+        (
+            "path_rewrite",
+            object(
+                &[
+                    ("strip_prefix", string("Prefix removed from emitted paths.")),
+                    ("prepend", string("Prefix added to emitted paths.")),
+                ],
+                &[],
+            ),
+        ),
+        (
+            "file_markers",
+            object(
+                &[
+                    (
+                        "header",
+                        string("Line before each file; `{path}`, `{lang}`, `{size}`, `{tokens}`."),
+                    ),
+                    (
+                        "footer",
+                        string("Line after each file; same placeholders, empty = none."),
+                    ),
+                ],
+                &[],
+            ),
+        ),
+        (
+            "cell_markers",
+            boolean("Separate files with `# %% path` cell markers instead of `file_markers`."),
+        ),
+        (
+            "languages",
+            json!({
+                "type": "object",
+                "description": "Extension or file name → code-fence language.",
+                "additionalProperties": { "type": "string" },
+            }),
+        ),
+        (
+            "transforms",
+            json!({
+                "type": "array",
+                "description": "Per-file transforms, in order.",
+                "items": reference("transform_step"),
+            }),
+        ),
+        (
+            "normalize_text",
+            boolean("Strip UTF-8 BOMs and convert CRLF/CR line endings to LF."),
+        ),
+        (
+            "svg_inline_limit",
+            count("Largest SVG, in bytes, emitted as XML (0 = never)."),
+        ),
+        (
+            "post_process",
+            object(
+                &[
+                    (
+                        "command",
+                        string("Command the finished output is piped through."),
+                    ),
+                    (
+                        "timeout_secs",
+                        count("Seconds before the command is stopped."),
+                    ),
+                ],
+                &["command"],
+            ),
+        ),
+        (
+            "tokenizer",
+            string("Builtin encoding name or `tokenizer.json` path (empty = default)."),
+        ),
+        (
+            "context_window",
+            json!({
+                "enum": ["off", "128k", "200k", "1m"],
+                "description": "Context-window preset the output is checked against.",
+            }),
+        ),
+        (
+            "output_order",
+            json!({
+                "enum": [
+                    "pinned_first",
+                    "alphabetical",
+                    "directory_grouped",
+                    "smallest_first",
+                    "rust_modules",
+                ],
+                "description": "Order of the file blocks in the output.",
+            }),
+        ),
+        (
+            "name_matching",
+            json!({
+                "enum": ["platform", "exact", "insensitive"],
+                "description": "How excluded names are compared with names on disk.",
+            }),
+        ),
+        (
+            "rust_remove_inline_comments",
+            boolean("Rust: remove `//` and `/* */` comments."),
+        ),
+        (
+            "rust_remove_doc_comments",
+            boolean("Rust: remove doc comments."),
+        ),
+        (
+            "rust_function_signatures_only",
+            boolean("Rust: replace function bodies."),
+        ),
+        (
+            "rust_signatures_only_filter",
+            string("Rust: comma-separated wildcards limiting signatures-only mode to some paths."),
+        ),
+        (
+            "slint_remove_line_comments",
+            boolean("Slint: remove `//` comments."),
+        ),
+        (
+            "slint_remove_block_comments",
+            boolean("Slint: remove `/* */` comments."),
+        ),
+        (
+            "c_like_remove_line_comments",
+            boolean("C, C++, Java, C#: remove `//` comments."),
+        ),
+        (
+            "c_like_remove_block_comments",
+            boolean("C, C++, Java, C#: remove `/* */` comments."),
+        ),
+        (
+            "c_like_function_signatures_only",
+            boolean("C, C++, Java, C#: replace function bodies."),
+        ),
+        (
+            "go_remove_line_comments",
+            boolean("Go: remove `//` comments."),
+        ),
+        (
+            "go_remove_block_comments",
+            boolean("Go: remove `/* */` comments."),
+        ),
+        (
+            "go_function_signatures_only",
+            boolean("Go: drop function bodies."),
+        ),
+        ("go_keep_generated", boolean("Go: keep `*.pb.go` files.")),
+        ("web_remove_comments", boolean("HTML/CSS: remove comments.")),
+        (
+            "web_collapse_whitespace",
+            boolean("HTML/CSS: collapse whitespace."),
+        ),
+        ("sql_remove_comments", boolean("SQL: remove comments.")),
+        (
+            "sql_collapse_data",
+            boolean("SQL: summarize large INSERT/COPY data blocks."),
+        ),
+    ]
+}
+
+fn definitions() -> Value {
+    let sections: Vec<&str> = OutputSection::ALL.into_iter().map(section_name).collect();
+    json!({
+        "workspace_settings": workspace_settings_schema(),
+        "output_layout": object(
+            &[
+                (
+                    "sections",
+                    json!({
+                        "type": "array",
+                        "description": "Section order, visibility and headers.",
+                        "items": object(
+                            &[
+                                ("section", json!({ "enum": sections })),
+                                ("enabled", boolean("Emit the section (default true).")),
+                                ("header", string("Full header line.")),
+                            ],
+                            &["section"],
+                        ),
+                    }),
+                ),
+                (
+                    "provenance",
+                    string("Provenance header format (empty = default)."),
+                ),
+                (
+                    "modified_times",
+                    boolean("Add each file's last modification time to its header line."),
+                ),
+                (
+                    "checksum_footer",
+                    boolean("End the output with SHA-256 checksums of it and of each file."),
+                ),
+            ],
+            &["sections"],
+        ),
+        "transform_step": transform_step_schema(),
+        "directory_sample": object(
+            &[
+                ("path", string("Project-relative directory (\"\" = project root).")),
+                ("count", count("Files to emit.")),
+                (
+                    "order",
+                    json!({ "enum": ["first", "most_recent"], "description": "Which files are emitted." }),
+                ),
+            ],
+            &["path", "count"],
+        ),
+        "directory_rule": object(
+            &[
+                ("path", string("Project-relative directory (\"\" = project root).")),
+                ("kind", json!({ "enum": ["exclude", "ignore_extension_filters"] })),
+            ],
+            &["path", "kind"],
+        ),
+    })
+}
+
+const fn section_name(section: OutputSection) -> &'static str {
+    match section {
+        OutputSection::Hierarchy => "hierarchy",
+        OutputSection::UnselectedDirectories => "unselected_directories",
+        OutputSection::Dependencies => "dependencies",
+        OutputSection::Notes => "notes",
+        OutputSection::Contents => "contents",
+    }
+}
+
+/// Transform kinds without options.
+const PLAIN_TRANSFORM_KINDS: [&str; 13] = [
+    "strip_prefixes",
+    "remove_regex",
+    "rust_filters",
+    "slint_filters",
+    "c_like_filters",
+    "go_filters",
+    "web_filters",
+    "sql_filters",
+    "schema_only",
+    "minify",
+    "redact",
+    "collapse_generated",
+    "summarize_lockfiles",
+];
+
+fn transform_step_schema() -> Value {
+    let extensions = (
+        "extensions",
+        json!({
+            "type": "array",
+            "description": "Extensions the step applies to (empty = every file).",
+            "items": { "type": "string" },
+        }),
+    );
+    let kind = |name: &str| json!({ "const": name });
+    let mut variants = vec![object(
+        &[
+            ("kind", json!({ "enum": PLAIN_TRANSFORM_KINDS })),
+            extensions.clone(),
+        ],
+        &["kind"],
+    )];
+    variants.push(object(
+        &[
+            ("kind", kind("table_head")),
+            ("rows", count("Data rows kept.")),
+            extensions.clone(),
+        ],
+        &["kind"],
+    ));
+    variants.push(object(
+        &[
+            ("kind", kind("notebook")),
+            ("markdown", boolean("Add markdown cells as comments.")),
+            extensions.clone(),
+        ],
+        &["kind"],
+    ));
+    variants.push(object(
+        &[
+            ("kind", kind("command")),
+            (
+                "command",
+                string("Shell command the contents are piped through."),
+            ),
+            (
+                "timeout_secs",
+                count("Seconds before the command is stopped."),
+            ),
+            extensions.clone(),
+        ],
+        &["kind", "command"],
+    ));
+    variants.push(object(
+        &[
+            ("kind", kind("macro_expand")),
+            (
+                "timeout_secs",
+                count("Seconds before `cargo expand` is stopped."),
+            ),
+            extensions,
+        ],
+        &["kind"],
+    ));
+    json!({ "oneOf": variants })
+}
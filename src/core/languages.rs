@@ -0,0 +1,86 @@
+use std::collections::BTreeMap;
+
+/* ============================ Language identifiers ========================== */
+
+/// Language identifier of a file for syntax-aware consumers (Markdown fences, XML
+/// attributes, JSON records): `rust`, `python`, ...
+///
+/// `overrides` maps extensions or file names to languages (see [`override_for`]) and
+/// wins over the built-in table. Unknown extensions map to themselves; files without
+/// an extension map to an empty string.
+#[must_use]
+pub fn lang_for_path<'a>(path: &'a str, overrides: &'a BTreeMap<String, String>) -> &'a str {
+    let name = path.rsplit(['/', '\\']).next().unwrap_or(path);
+    if let Some(lang) = override_for(name, overrides) {
+        return lang;
+    }
+    if let Some(lang) = builtin_lang_for_name(name) {
+        return lang;
+    }
+    name.rsplit_once('.')
+        .filter(|(stem, _)| !stem.is_empty())
+        .map_or("", |(_, ext)| builtin_lang_for_ext(ext).unwrap_or(ext))
+}
+
+/// The user mapping for file `name`: a key matches a file named like it or ending in
+/// `.key` (`"tpl"`, `".tpl"`, `"d.ts"`, `"Justfile"`), case-insensitively. The longest
+/// matching key wins.
+#[must_use]
+pub fn override_for<'a>(name: &str, overrides: &'a BTreeMap<String, String>) -> Option<&'a str> {
+    let name = name.to_ascii_lowercase();
+    overrides
+        .iter()
+        .filter_map(|(key, lang)| {
+            let key = key.trim().trim_start_matches('.').to_ascii_lowercase();
+            let matches = !key.is_empty()
+                && (name == key
+                    || name
+                        .strip_suffix(key.as_str())
+                        .is_some_and(|stem| stem.len() > 1 && stem.ends_with('.')));
+            matches.then_some((key.len(), lang.trim()))
+        })
+        .max_by_key(|(len, _)| *len)
+        .map(|(_, lang)| lang)
+}
+
+fn builtin_lang_for_name(name: &str) -> Option<&'static str> {
+    Some(match name.to_ascii_lowercase().as_str() {
+        "dockerfile" | "containerfile" => "dockerfile",
+        "makefile" | "gnumakefile" => "makefile",
+        "cmakelists.txt" => "cmake",
+        _ => return None,
+    })
+}
+
+fn builtin_lang_for_ext(ext: &str) -> Option<&'static str> {
+    Some(match ext.to_ascii_lowercase().as_str() {
+        "rs" => "rust",
+        "py" | "pyi" => "python",
+        "js" | "mjs" | "cjs" => "javascript",
+        "ts" | "mts" | "cts" => "typescript",
+        "jsx" => "jsx",
+        "tsx" => "tsx",
+        "c" | "h" => "c",
+        "cc" | "cpp" | "cxx" | "hpp" | "hh" | "hxx" => "cpp",
+        "cs" => "csharp",
+        "go" => "go",
+        "java" => "java",
+        "kt" | "kts" => "kotlin",
+        "swift" => "swift",
+        "rb" => "ruby",
+        "php" => "php",
+        "sh" | "bash" | "zsh" => "bash",
+        "ps1" => "powershell",
+        "md" | "markdown" => "markdown",
+        "yml" | "yaml" => "yaml",
+        "toml" => "toml",
+        "json" => "json",
+        "html" | "htm" => "html",
+        "css" => "css",
+        "scss" => "scss",
+        "sql" => "sql",
+        "xml" => "xml",
+        "slint" => "slint",
+        _ => return None,
+    })
+}
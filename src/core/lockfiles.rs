@@ -0,0 +1,153 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Write,
+};
+
+use serde_json::Value as JsonValue;
+use toml::{Table, Value};
+
+/* ============================ Lockfile summaries ============================ */
+
+/// File names [`summarize_lockfile`] understands.
+pub const SUMMARIZED_LOCKFILES: [&str; 2] = ["Cargo.lock", "package-lock.json"];
+
+/// The direct dependencies of the project's own packages with their resolved versions,
+/// in place of a whole lockfile (see [`SUMMARIZED_LOCKFILES`]).
+///
+/// `path` is `/`-separated; only its file name is looked at. `None` for other files and
+/// for lockfiles that can't be parsed.
+#[must_use]
+pub fn summarize_lockfile(path: &str, contents: &str) -> Option<String> {
+    match path.rsplit('/').next().unwrap_or(path) {
+        "Cargo.lock" => summarize_cargo_lock(contents),
+        "package-lock.json" => summarize_package_lock(contents),
+        _ => None,
+    }
+}
+
+fn render_summary(
+    file: &str,
+    roots: &[String],
+    locked: usize,
+    deps: &BTreeMap<String, BTreeSet<String>>,
+) -> String {
+    let mut out = format!(
+        "{file} summary: direct dependencies of {} ({locked} packages locked)\n",
+        if roots.is_empty() {
+            "the project".to_string()
+        } else {
+            roots.join(", ")
+        }
+    );
+    for (name, versions) in deps {
+        let versions: Vec<&str> = versions.iter().map(String::as_str).collect();
+        let _ = writeln!(out, "{name} {}", versions.join(", "));
+    }
+    out
+}
+
+/// Roots are the `[[package]]` entries without a `source` (workspace members and path
+/// dependencies); their `dependencies` entries are `name`, `name version` or
+/// `name version (source)`.
+fn summarize_cargo_lock(contents: &str) -> Option<String> {
+    let table: Table = contents.parse().ok()?;
+    let packages = table.get("package")?.as_array()?;
+
+    let mut versions_by_name: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+    for pkg in packages {
+        if let (Some(name), Some(version)) = (str_field(pkg, "name"), str_field(pkg, "version")) {
+            versions_by_name.entry(name).or_default().push(version);
+        }
+    }
+
+    let local: Vec<&Value> = packages
+        .iter()
+        .filter(|p| p.get("source").is_none())
+        .collect();
+    let local_names: BTreeSet<&str> = local.iter().filter_map(|p| str_field(p, "name")).collect();
+    let mut roots: Vec<String> = Vec::new();
+    let mut deps: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+    for pkg in local {
+        if let Some(name) = str_field(pkg, "name") {
+            roots.push(name.to_string());
+        }
+        let entries = pkg.get("dependencies").and_then(Value::as_array);
+        for entry in entries.into_iter().flatten().filter_map(Value::as_str) {
+            let mut parts = entry.split_whitespace();
+            let Some(name) = parts.next() else { continue };
+            if local_names.contains(name) {
+                continue;
+            }
+            // Without a version only one of `name` is locked, so the entry leaves it out.
+            let version = parts.next().map_or_else(
+                || {
+                    versions_by_name
+                        .get(name)
+                        .and_then(|v| v.first())
+                        .map_or_else(|| "?".to_string(), |v| (*v).to_string())
+                },
+                str::to_string,
+            );
+            deps.entry(name.to_string()).or_default().insert(version);
+        }
+    }
+    roots.sort();
+    Some(render_summary("Cargo.lock", &roots, packages.len(), &deps))
+}
+
+fn str_field<'a>(value: &'a Value, key: &str) -> Option<&'a str> {
+    value.get(key)?.as_str()
+}
+
+/// lockfileVersion 2/3: the root package's dependency names resolved through
+/// `packages["node_modules/<name>"]`; version 1: the top-level `dependencies` map.
+fn summarize_package_lock(contents: &str) -> Option<String> {
+    let json: JsonValue = serde_json::from_str(contents).ok()?;
+    let name = json.get("name").and_then(JsonValue::as_str);
+    let roots: Vec<String> = name.map(str::to_string).into_iter().collect();
+    let mut deps: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+
+    if let Some(packages) = json.get("packages").and_then(JsonValue::as_object) {
+        let root = packages.get("")?;
+        for group in [
+            "dependencies",
+            "devDependencies",
+            "optionalDependencies",
+            "peerDependencies",
+        ] {
+            let Some(names) = root.get(group).and_then(JsonValue::as_object) else {
+                continue;
+            };
+            for (dep, range) in names {
+                let version = packages
+                    .get(&format!("node_modules/{dep}"))
+                    .and_then(|p| p.get("version"))
+                    .and_then(JsonValue::as_str)
+                    .or_else(|| range.as_str())
+                    .unwrap_or("?");
+                deps.entry(dep.clone())
+                    .or_default()
+                    .insert(version.to_string());
+            }
+        }
+        let locked = packages.keys().filter(|k| !k.is_empty()).count();
+        return Some(render_summary("package-lock.json", &roots, locked, &deps));
+    }
+
+    let dependencies = json.get("dependencies")?.as_object()?;
+    for (dep, info) in dependencies {
+        let version = info
+            .get("version")
+            .and_then(JsonValue::as_str)
+            .unwrap_or("?");
+        deps.entry(dep.clone())
+            .or_default()
+            .insert(version.to_string());
+    }
+    Some(render_summary(
+        "package-lock.json",
+        &roots,
+        dependencies.len(),
+        &deps,
+    ))
+}
@@ -0,0 +1,65 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    thread,
+    time::{Duration, Instant},
+};
+
+use fd_lock::RwLock;
+
+use crate::core::workspace_dir;
+
+/* ============================== Write locking =============================== */
+
+/// How long a save waits for another writer to finish before giving up.
+pub const WRITE_LOCK_TIMEOUT: Duration = Duration::from_secs(3);
+
+const LOCK_RETRY_INTERVAL: Duration = Duration::from_millis(50);
+
+/// The file locked while workspace or profile files are written. It lives in the
+/// per-user folder, so it never shows up in version control.
+#[must_use]
+pub fn write_lock_file(project_root: &Path) -> PathBuf {
+    workspace_dir(project_root).join("local").join("write.lock")
+}
+
+/// Run `f` while holding the project's advisory write lock, retrying for up to `timeout`
+/// while another process (or window) holds it.
+///
+/// # Errors
+/// Returns a `WouldBlock` error when the lock is still held after `timeout`, I/O errors
+/// from creating the lock file, and whatever `f` returns.
+pub fn with_write_lock<T>(
+    project_root: &Path,
+    timeout: Duration,
+    f: impl FnOnce() -> io::Result<T>,
+) -> io::Result<T> {
+    let path = write_lock_file(project_root);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let file = fs::OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&path)?;
+    let mut lock = RwLock::new(file);
+
+    let start = Instant::now();
+    let _guard = loop {
+        match lock.try_write() {
+            Ok(guard) => break guard,
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock && start.elapsed() < timeout => {
+                thread::sleep(LOCK_RETRY_INTERVAL);
+            }
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                return Err(io::Error::new(
+                    io::ErrorKind::WouldBlock,
+                    "another Stitch window or program is writing the workspace files; try saving again",
+                ));
+            }
+            Err(e) => return Err(e),
+        }
+    };
+    f()
+}
@@ -0,0 +1,208 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use toml::Table;
+
+/* =========================== Rust macro expansion =========================== */
+
+/// How long one `cargo expand` run may take by default (it builds the crate).
+pub const MACRO_EXPAND_TIMEOUT: Duration = Duration::from_mins(2);
+
+/// What to pass to `cargo expand` for one source file.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ExpandTarget {
+    /// Directory of the crate's `Cargo.toml`; the command runs there.
+    pub crate_dir: PathBuf,
+    /// `--lib` or `--bin <name>`, then the module path (`net::http`) unless it's the
+    /// crate root.
+    pub args: Vec<String>,
+}
+
+impl ExpandTarget {
+    /// The shell command that expands this target.
+    #[must_use]
+    pub fn command(&self) -> String {
+        format!("cargo expand --color never {}", self.args.join(" "))
+    }
+}
+
+fn is_identifier(s: &str) -> bool {
+    let s = s.strip_prefix("r#").unwrap_or(s);
+    s.chars()
+        .next()
+        .is_some_and(|c| c == '_' || c.is_alphabetic())
+        && s.chars().all(|c| c == '_' || c.is_alphanumeric())
+}
+
+fn package_name(crate_dir: &Path) -> Option<String> {
+    let manifest: Table = fs::read_to_string(crate_dir.join("Cargo.toml"))
+        .ok()?
+        .parse()
+        .ok()?;
+    let name = manifest.get("package")?.get("name")?.as_str()?;
+    name.chars()
+        .all(|c| c == '_' || c == '-' || c.is_ascii_alphanumeric())
+        .then(|| name.to_string())
+}
+
+/// The `cargo expand` target for `rel` (`/`-separated, relative to `project_root`).
+///
+/// That is the crate whose `Cargo.toml` is nearest above it, and the library, binary or
+/// module the file is. `None` for files outside a crate's `src/`, in a binary's
+/// submodules, or with names that aren't Rust identifiers.
+#[must_use]
+pub fn expand_target(project_root: &Path, rel: &str) -> Option<ExpandTarget> {
+    let parts: Vec<&str> = rel.split('/').collect();
+    let (crate_depth, crate_dir) = (0..parts.len()).rev().find_map(|depth| {
+        let dir = parts[..depth]
+            .iter()
+            .fold(project_root.to_path_buf(), |dir, part| dir.join(part));
+        dir.join("Cargo.toml").is_file().then_some((depth, dir))
+    })?;
+    let inner = &parts[crate_depth..];
+    let (&"src", inner) = inner.split_first()? else {
+        return None;
+    };
+    let target = |args: Vec<String>| {
+        Some(ExpandTarget {
+            crate_dir: crate_dir.clone(),
+            args,
+        })
+    };
+
+    match inner {
+        ["lib.rs"] => return target(vec!["--lib".into()]),
+        ["main.rs"] => return target(vec!["--bin".into(), package_name(&crate_dir)?]),
+        ["bin", file] | ["bin", file, "main.rs"] => {
+            let name = file.strip_suffix(".rs").unwrap_or(file);
+            return target(vec!["--bin".into(), name.to_string()]);
+        }
+        ["bin", ..] => return None,
+        _ => {}
+    }
+
+    let (file, dirs) = inner.split_last()?;
+    let stem = file.strip_suffix(".rs")?;
+    let mut module: Vec<&str> = dirs.to_vec();
+    if stem != "mod" {
+        module.push(stem);
+    }
+    if module.is_empty() || !module.iter().all(|m| is_identifier(m)) {
+        return None;
+    }
+    let mut args = if crate_dir.join("src/lib.rs").is_file() {
+        vec!["--lib".to_string()]
+    } else {
+        vec!["--bin".to_string(), package_name(&crate_dir)?]
+    };
+    args.push(module.join("::"));
+    target(args)
+}
+
+/// Number of files and newest modification time among a crate's `Cargo.toml`,
+/// `build.rs` and `src/`; an expansion is reused while this is unchanged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CrateFingerprint {
+    pub files: usize,
+    pub newest: Option<SystemTime>,
+}
+
+impl CrateFingerprint {
+    #[must_use]
+    pub fn of(crate_dir: &Path) -> Self {
+        fn visit(path: &Path, fp: &mut CrateFingerprint) {
+            let Ok(meta) = fs::metadata(path) else {
+                return;
+            };
+            if meta.is_dir() {
+                for entry in fs::read_dir(path).into_iter().flatten().flatten() {
+                    visit(&entry.path(), fp);
+                }
+                return;
+            }
+            fp.files += 1;
+            let modified = meta.modified().ok();
+            if modified > fp.newest {
+                fp.newest = modified;
+            }
+        }
+        let mut fp = Self::default();
+        for name in ["Cargo.toml", "build.rs", "src"] {
+            visit(&crate_dir.join(name), &mut fp);
+        }
+        fp
+    }
+}
+
+/// Expansions by target, kept while their crate's [`CrateFingerprint`] is unchanged.
+/// Failures are cached too, so a broken crate isn't rebuilt for every file.
+#[derive(Debug, Default)]
+pub struct ExpandCache {
+    entries: HashMap<ExpandTarget, (CrateFingerprint, Result<String, String>)>,
+}
+
+impl ExpandCache {
+    /// The cached expansion of `target`, or the result of `run` (stored for next time).
+    ///
+    /// # Errors
+    /// The (possibly cached) error of `run`.
+    pub fn expand_with(
+        &mut self,
+        target: &ExpandTarget,
+        run: impl FnOnce(&ExpandTarget) -> Result<String, String>,
+    ) -> Result<String, String> {
+        let fingerprint = CrateFingerprint::of(&target.crate_dir);
+        if let Some((cached, result)) = self.entries.get(target)
+            && *cached == fingerprint
+        {
+            return result.clone();
+        }
+        let result = run(target);
+        self.entries
+            .insert(target.clone(), (fingerprint, result.clone()));
+        result
+    }
+}
+
+/// `rel`'s code after macro expansion by `cargo expand`, through `cache`.
+///
+/// # Errors
+/// A one-line reason when the file has no expand target, `cargo expand` fails or runs
+/// past `timeout`, or the `cargo-expand` feature is off.
+#[cfg(feature = "cargo-expand")]
+pub fn expand_rust_file(
+    project_root: &Path,
+    rel: &str,
+    cache: &std::sync::Mutex<ExpandCache>,
+    timeout: Duration,
+) -> Result<String, String> {
+    use crate::core::{ExternalFile, run_external_command};
+
+    let target = expand_target(project_root, rel)
+        .ok_or_else(|| "is not a crate root or module under a crate's src/".to_string())?;
+    let mut cache = cache
+        .lock()
+        .map_err(|_| "cache is unavailable".to_string())?;
+    cache.expand_with(&target, |target| {
+        let file = ExternalFile {
+            path: rel,
+            full_path: &project_root.join(rel),
+            working_dir: &target.crate_dir,
+        };
+        run_external_command(&target.command(), "", &file, timeout)
+    })
+}
+
+#[cfg(not(feature = "cargo-expand"))]
+pub fn expand_rust_file(
+    _project_root: &Path,
+    _rel: &str,
+    _cache: &std::sync::Mutex<ExpandCache>,
+    _timeout: Duration,
+) -> Result<String, String> {
+    Err("needs the `cargo-expand` feature".to_string())
+}
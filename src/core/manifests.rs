@@ -0,0 +1,318 @@
+use std::{fmt::Write, fs, path::Path};
+
+use serde_json::Value as JsonValue;
+use toml::{Table, Value};
+
+use crate::core::collect_cargo_manifests;
+
+/* ============================ Manifest summaries ============================ */
+
+/// Condensed view of one project manifest (`Cargo.toml`, `package.json`, ...).
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ManifestSummary {
+    /// Project-relative manifest path using forward slashes.
+    pub manifest: String,
+    pub name: Option<String>,
+    pub version: Option<String>,
+    /// `(group, lines)`, e.g. `("scripts", ["build: vite build"])`; empty groups are left out.
+    pub groups: Vec<(String, Vec<String>)>,
+}
+
+impl ManifestSummary {
+    fn new(manifest: &str) -> Self {
+        Self {
+            manifest: manifest.to_string(),
+            ..Self::default()
+        }
+    }
+
+    fn push_group(&mut self, group: impl Into<String>, lines: Vec<String>) {
+        if !lines.is_empty() {
+            self.groups.push((group.into(), lines));
+        }
+    }
+
+    fn render(&self, out: &mut String) {
+        let name = self.name.as_deref().unwrap_or("(unnamed)");
+        match &self.version {
+            Some(version) => {
+                let _ = writeln!(out, "{name} {version} ({})", self.manifest);
+            }
+            None => {
+                let _ = writeln!(out, "{name} ({})", self.manifest);
+            }
+        }
+        if self.groups.is_empty() {
+            out.push_str("  (no dependencies)\n");
+        }
+        for (group, lines) in &self.groups {
+            let _ = writeln!(out, "  {group}:");
+            for line in lines {
+                let _ = writeln!(out, "    {line}");
+            }
+        }
+    }
+}
+
+/* -------------------------------- package.json ------------------------------- */
+
+const PACKAGE_JSON_GROUPS: [&str; 5] = [
+    "scripts",
+    "dependencies",
+    "devDependencies",
+    "peerDependencies",
+    "optionalDependencies",
+];
+
+/// Scripts and dependency maps of a `package.json`.
+///
+/// # Errors
+/// Returns the JSON parse error message.
+pub fn summarize_package_json(manifest: &str, source: &str) -> Result<ManifestSummary, String> {
+    let doc: JsonValue = serde_json::from_str(source).map_err(|e| e.to_string())?;
+    let mut summary = ManifestSummary::new(manifest);
+    summary.name = doc
+        .get("name")
+        .and_then(JsonValue::as_str)
+        .map(str::to_string);
+    summary.version = doc
+        .get("version")
+        .and_then(JsonValue::as_str)
+        .map(str::to_string);
+    for group in PACKAGE_JSON_GROUPS {
+        let Some(map) = doc.get(group).and_then(JsonValue::as_object) else {
+            continue;
+        };
+        let separator = if group == "scripts" { ": " } else { " " };
+        let lines = map
+            .iter()
+            .filter_map(|(key, value)| Some(format!("{key}{separator}{}", value.as_str()?)))
+            .collect();
+        summary.push_group(group, lines);
+    }
+    Ok(summary)
+}
+
+/* ------------------------------- pyproject.toml ------------------------------ */
+
+/// Dependencies and scripts of a `pyproject.toml`: PEP 621 `[project]` tables,
+/// `[dependency-groups]`, and Poetry's `[tool.poetry]` tables.
+///
+/// # Errors
+/// Returns the TOML parse error message.
+pub fn summarize_pyproject(manifest: &str, source: &str) -> Result<ManifestSummary, String> {
+    let doc: Table = source
+        .parse()
+        .map_err(|e: toml::de::Error| e.message().to_string())?;
+    let project = doc.get("project").and_then(Value::as_table);
+    let poetry = doc
+        .get("tool")
+        .and_then(|t| t.get("poetry"))
+        .and_then(Value::as_table);
+    let field = |key: &str| {
+        project
+            .and_then(|p| p.get(key))
+            .or_else(|| poetry.and_then(|p| p.get(key)))
+            .and_then(Value::as_str)
+            .map(str::to_string)
+    };
+
+    let mut summary = ManifestSummary::new(manifest);
+    summary.name = field("name");
+    summary.version = field("version");
+
+    if let Some(project) = project {
+        if let Some(python) = project.get("requires-python").and_then(Value::as_str) {
+            summary.push_group("requires-python", vec![python.to_string()]);
+        }
+        summary.push_group("dependencies", string_array(project.get("dependencies")));
+        for (extra, deps) in tables_in(project.get("optional-dependencies")) {
+            summary.push_group(
+                format!("optional-dependencies.{extra}"),
+                string_array(Some(deps)),
+            );
+        }
+        summary.push_group("scripts", string_map(project.get("scripts")));
+    }
+    for (group, deps) in tables_in(doc.get("dependency-groups")) {
+        summary.push_group(
+            format!("dependency-groups.{group}"),
+            string_array(Some(deps)),
+        );
+    }
+
+    if let Some(poetry) = poetry {
+        summary.push_group(
+            "tool.poetry.dependencies",
+            poetry_deps(poetry.get("dependencies")),
+        );
+        summary.push_group(
+            "tool.poetry.dev-dependencies",
+            poetry_deps(poetry.get("dev-dependencies")),
+        );
+        for (group, spec) in tables_in(poetry.get("group")) {
+            summary.push_group(
+                format!("tool.poetry.group.{group}.dependencies"),
+                poetry_deps(spec.get("dependencies")),
+            );
+        }
+        summary.push_group("tool.poetry.scripts", string_map(poetry.get("scripts")));
+    }
+    Ok(summary)
+}
+
+fn tables_in(value: Option<&Value>) -> impl Iterator<Item = (&String, &Value)> {
+    value.and_then(Value::as_table).into_iter().flatten()
+}
+
+fn string_array(value: Option<&Value>) -> Vec<String> {
+    value
+        .and_then(Value::as_array)
+        .map(|a| {
+            a.iter()
+                .filter_map(Value::as_str)
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn string_map(value: Option<&Value>) -> Vec<String> {
+    tables_in(value)
+        .filter_map(|(key, value)| Some(format!("{key}: {}", value.as_str()?)))
+        .collect()
+}
+
+/// `requests ^2.31`, `black 24.1 [d] (optional)`, `mylib (path ../mylib)`.
+fn poetry_deps(value: Option<&Value>) -> Vec<String> {
+    tables_in(value)
+        .map(|(name, spec)| {
+            let mut line = name.clone();
+            let Some(table) = spec.as_table() else {
+                if let Some(version) = spec.as_str() {
+                    let _ = write!(line, " {version}");
+                }
+                return line;
+            };
+            if let Some(version) = table.get("version").and_then(Value::as_str) {
+                let _ = write!(line, " {version}");
+            }
+            let extras = string_array(table.get("extras"));
+            if !extras.is_empty() {
+                let _ = write!(line, " [{}]", extras.join(", "));
+            }
+            let mut notes: Vec<String> = ["path", "git", "url"]
+                .into_iter()
+                .filter_map(|key| Some(format!("{key} {}", table.get(key)?.as_str()?)))
+                .collect();
+            if table.get("optional").and_then(Value::as_bool) == Some(true) {
+                notes.push("optional".to_string());
+            }
+            if !notes.is_empty() {
+                let _ = write!(line, " ({})", notes.join(", "));
+            }
+            line
+        })
+        .collect()
+}
+
+/* ----------------------------------- go.mod ---------------------------------- */
+
+/// Module path, Go version, `require` and `replace` directives of a `go.mod`.
+#[must_use]
+pub fn summarize_go_mod(manifest: &str, source: &str) -> ManifestSummary {
+    let mut summary = ManifestSummary::new(manifest);
+    let mut require = Vec::new();
+    let mut replace = Vec::new();
+    let mut block: Option<String> = None;
+
+    for raw in source.lines() {
+        let (code, comment) = raw.split_once("//").unwrap_or((raw, ""));
+        let code = code.trim();
+        if code.is_empty() {
+            continue;
+        }
+        if block.is_some() && code == ")" {
+            block = None;
+            continue;
+        }
+        let (directive, rest) = if let Some(directive) = &block {
+            (directive.clone(), code)
+        } else {
+            let (directive, rest) = code.split_once(char::is_whitespace).unwrap_or((code, ""));
+            let rest = rest.trim();
+            if rest == "(" {
+                block = Some(directive.to_string());
+                continue;
+            }
+            (directive.to_string(), rest)
+        };
+        let rest = rest.split_whitespace().collect::<Vec<_>>().join(" ");
+        match directive.as_str() {
+            "module" => summary.name = Some(rest.trim_matches('"').to_string()),
+            "go" => summary.version = Some(format!("go {rest}")),
+            "require" if comment.trim() == "indirect" => require.push(format!("{rest} (indirect)")),
+            "require" => require.push(rest),
+            "replace" => replace.push(rest),
+            _ => {}
+        }
+    }
+    summary.push_group("require", require);
+    summary.push_group("replace", replace);
+    summary
+}
+
+/* ---------------------------------- Section ---------------------------------- */
+
+type Summarize = fn(&str, &str) -> Result<ManifestSummary, String>;
+
+/// Summaries of the manifests found in the project: Cargo (root and workspace members),
+/// then the root `package.json`, `pyproject.toml` and `go.mod`.
+///
+/// Manifests that can't be parsed are reported as `Err((manifest, message))`.
+#[must_use]
+pub fn collect_manifest_summaries(
+    project_root: &Path,
+) -> Vec<Result<ManifestSummary, (String, String)>> {
+    let mut summaries: Vec<Result<ManifestSummary, (String, String)>> =
+        collect_cargo_manifests(project_root)
+            .into_iter()
+            .map(|m| m.map(|krate| krate.summary()))
+            .collect();
+
+    let root_manifests: [(&str, Summarize); 3] = [
+        ("package.json", summarize_package_json),
+        ("pyproject.toml", summarize_pyproject),
+        ("go.mod", |manifest, source| {
+            Ok(summarize_go_mod(manifest, source))
+        }),
+    ];
+    for (name, summarize) in root_manifests {
+        if let Ok(source) = fs::read_to_string(project_root.join(name)) {
+            summaries.push(summarize(name, &source).map_err(|e| (name.to_string(), e)));
+        }
+    }
+    summaries
+}
+
+/// Body of the `=== DEPENDENCIES ===` section, or `None` if the project has no manifest.
+#[must_use]
+pub fn render_dependencies_section(project_root: &Path) -> Option<String> {
+    let summaries = collect_manifest_summaries(project_root);
+    if summaries.is_empty() {
+        return None;
+    }
+    let mut out = String::new();
+    for summary in summaries {
+        match summary {
+            Ok(summary) => summary.render(&mut out),
+            Err((manifest, message)) => {
+                // Parser messages can span lines; keep the note on one.
+                let message = message.split_whitespace().collect::<Vec<_>>().join(" ");
+                let _ = writeln!(out, "{manifest}: could not parse ({message})");
+            }
+        }
+        out.push('\n');
+    }
+    Some(out)
+}
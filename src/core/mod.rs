@@ -10,14 +10,142 @@ pub struct Node {
     pub has_children: bool,
 }
 
+mod backups;
+mod c_like_filters;
+mod call_graph;
+mod cargo_deps;
+mod checksum;
+mod cli;
+mod compression;
+mod content_cache;
+mod content_search;
+mod diff;
+mod dir_rules;
+mod documents;
+mod events;
+mod exclude_suggestions;
+mod filter_presets;
 mod fs;
+mod generate;
+mod git_branch;
+mod go_filters;
+mod history;
+mod images;
+mod importers;
+mod integration;
+mod json_schema;
+mod languages;
+mod lockfiles;
+mod locking;
+mod macro_expand;
+mod manifests;
+mod name_matching;
+mod notes;
+mod output;
+mod paths;
+mod pinning;
+mod platform_prefs;
+mod post_process;
+mod preview;
+mod pricing;
+mod profile_changes;
+mod profile_cleanup;
+mod ranking;
+mod renames;
+mod renderers;
 mod rust_filters;
+mod rust_modules;
+mod sampling;
+mod schema_only;
+mod selection_ops;
+mod selection_stats;
 mod slint_filters;
+mod snapshots;
+mod split_output;
+mod sql_filters;
+mod stats;
+mod stitchignore;
+mod symbols;
+mod tasks;
 mod text;
+mod text_quirks;
+mod tokens;
+mod transforms;
+mod trimming;
+mod updater;
+mod user_preferences;
+mod validation;
+mod watch_scope;
+mod web_filters;
 mod workspace;
+mod write_back;
 
+pub use backups::*;
+pub use c_like_filters::*;
+pub use call_graph::*;
+pub use cargo_deps::*;
+pub use checksum::*;
+pub use cli::*;
+pub use compression::*;
+pub use content_cache::*;
+pub use content_search::*;
+pub use diff::*;
+pub use dir_rules::*;
+pub use documents::*;
+pub use events::*;
+pub use exclude_suggestions::*;
+pub use filter_presets::*;
 pub use fs::*;
+pub use generate::*;
+pub use git_branch::*;
+pub use go_filters::*;
+pub use history::*;
+pub use images::*;
+pub use importers::*;
+pub use integration::*;
+pub use json_schema::*;
+pub use languages::*;
+pub use lockfiles::*;
+pub use locking::*;
+pub use macro_expand::*;
+pub use manifests::*;
+pub use name_matching::*;
+pub use notes::*;
+pub use output::*;
+pub use paths::*;
+pub use pinning::*;
+pub use platform_prefs::*;
+pub use post_process::*;
+pub use preview::*;
+pub use pricing::*;
+pub use profile_changes::*;
+pub use profile_cleanup::*;
+pub use ranking::*;
+pub use renames::*;
+pub use renderers::*;
 pub use rust_filters::*;
+pub use rust_modules::*;
+pub use sampling::*;
+pub use schema_only::*;
+pub use selection_ops::*;
+pub use selection_stats::*;
 pub use slint_filters::*;
+pub use snapshots::*;
+pub use split_output::*;
+pub use sql_filters::*;
+pub use stats::*;
+pub use stitchignore::*;
+pub use symbols::*;
+pub use tasks::*;
 pub use text::*;
+pub use text_quirks::*;
+pub use tokens::*;
+pub use transforms::*;
+pub use trimming::*;
+pub use updater::*;
+pub use user_preferences::*;
+pub use validation::*;
+pub use watch_scope::*;
+pub use web_filters::*;
 pub use workspace::*;
+pub use write_back::*;
@@ -0,0 +1,89 @@
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+use unicode_normalization::{UnicodeNormalization, char::is_combining_mark};
+
+/* ============================ Name matching ================================= */
+
+/// How "Exclude Directories" / "Exclude Files" entries are compared with names on disk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum NameMatching {
+    /// Like the platform's usual file system: ignoring case and accents on Windows and
+    /// macOS, exact elsewhere.
+    #[default]
+    Platform,
+    /// Byte-for-byte (`LICENSE` doesn't match `License`).
+    Exact,
+    /// Ignoring case and accents everywhere (`resume.md` matches `Résumé.md`).
+    Insensitive,
+}
+
+impl NameMatching {
+    /// Whether names are compared through [`fold_name`] on this platform.
+    #[must_use]
+    pub const fn folds(self) -> bool {
+        match self {
+            Self::Platform => cfg!(any(windows, target_os = "macos")),
+            Self::Exact => false,
+            Self::Insensitive => true,
+        }
+    }
+
+    /// Position in the UI selector.
+    #[must_use]
+    pub const fn index(self) -> i32 {
+        match self {
+            Self::Platform => 0,
+            Self::Exact => 1,
+            Self::Insensitive => 2,
+        }
+    }
+
+    #[must_use]
+    pub const fn from_index(index: i32) -> Self {
+        match index {
+            1 => Self::Exact,
+            2 => Self::Insensitive,
+            _ => Self::Platform,
+        }
+    }
+}
+
+/// `name` lowercased with accents removed, in one Unicode normalization form, so
+/// `Résumé`, `RESUME` and a decomposed `re\u{301}sume\u{301}` all fold to `resume`.
+#[must_use]
+pub fn fold_name(name: &str) -> String {
+    name.nfd()
+        .filter(|c| !is_combining_mark(*c))
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// A set of names prepared for lookups with [`NameMatching`].
+#[derive(Debug, Clone, Default)]
+pub struct NameSet {
+    names: HashSet<String>,
+    fold: bool,
+}
+
+impl NameSet {
+    #[must_use]
+    pub fn new<'a>(names: impl IntoIterator<Item = &'a String>, matching: NameMatching) -> Self {
+        let fold = matching.folds();
+        let names = names
+            .into_iter()
+            .map(|n| if fold { fold_name(n) } else { n.clone() })
+            .collect();
+        Self { names, fold }
+    }
+
+    #[must_use]
+    pub fn contains(&self, name: &str) -> bool {
+        if self.fold {
+            self.names.contains(&fold_name(name))
+        } else {
+            self.names.contains(name)
+        }
+    }
+}
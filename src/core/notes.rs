@@ -0,0 +1,447 @@
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    path::Path,
+};
+
+use crate::core::{
+    GoOptions, SQL_COLLAPSE_MIN_ROWS, STITCHIGNORE_FILE, SampleOmission, ScanStats, SqlOptions,
+    WebOptions, WorkspaceSettings, clean_remove_regex, compile_remove_regex,
+    is_generated_go_file_path, is_go_file_path, is_sql_file_path, is_web_file_path,
+    parse_extension_filters, path_to_unix, signatures_filter_matches, split_prefix_list,
+};
+
+/* =================================== Notes ================================== */
+
+/// The NOTES section for `rel_selected_paths` generated with `settings`.
+///
+/// It lists what the filters and transforms left out or changed, limited to what applies
+/// to the selection. `stats`
+/// comes from the scan the selection was made in. Empty when there is nothing to note.
+#[must_use]
+pub fn render_notes(
+    settings: &WorkspaceSettings,
+    stats: &ScanStats,
+    project_root: &Path,
+    rel_selected_paths: &[String],
+    sample_omissions: &[SampleOmission],
+) -> String {
+    let ctx = NotesContext::new(settings, stats, project_root);
+    let selected = SelectedPresence::new(rel_selected_paths);
+    let mut lines: Vec<String> = Vec::new();
+
+    if let Some(line) = note_excluded_dirs(&ctx, &selected) {
+        lines.push(line);
+    }
+    if let Some(line) = note_excluded_files(&ctx, &selected) {
+        lines.push(line);
+    }
+    lines.extend(note_extension_filters(&ctx, &selected));
+    lines.extend(note_directory_overrides(&ctx));
+    lines.extend(note_stitchignore(&ctx));
+    lines.extend(note_permission_denied(&ctx));
+    lines.extend(note_remove_settings(&ctx));
+    lines.extend(note_rust_settings(&ctx, &selected));
+    lines.extend(note_slint_settings(&ctx, &selected));
+    lines.extend(note_go_settings(&ctx, &selected));
+    lines.extend(note_web_settings(&ctx, &selected));
+    lines.extend(note_sql_settings(&ctx, &selected));
+    lines.extend(note_directory_samples(sample_omissions));
+
+    lines.join("\n")
+}
+
+#[allow(clippy::struct_excessive_bools)] // one flag per setting a note can mention
+struct NotesContext {
+    exclude_dirs: Vec<String>,
+    exclude_files: Vec<String>,
+    /// Listed excluded names found in the project, with how many entries each skipped.
+    existing_excluded_dirs: HashMap<String, usize>,
+    existing_excluded_files: HashMap<String, usize>,
+    filtered_files: usize,
+    override_excluded_dirs: usize,
+    stitchignored: usize,
+    /// Project-relative directories the scan wasn't allowed to read.
+    permission_denied: Vec<String>,
+    include_exts: HashSet<String>,
+    exclude_exts: HashSet<String>,
+    remove_prefixes: Vec<String>,
+    remove_regex: Option<String>,
+    remove_regex_error: Option<String>,
+    rust_remove_inline_comments: bool,
+    rust_remove_doc_comments: bool,
+    signatures_filter: Option<String>,
+    slint_remove_line_comments: bool,
+    slint_remove_block_comments: bool,
+    go: GoOptions,
+    web: WebOptions,
+    sql: SqlOptions,
+}
+
+impl NotesContext {
+    fn new(settings: &WorkspaceSettings, stats: &ScanStats, project_root: &Path) -> Self {
+        let sorted = |raw: &str| -> Vec<String> {
+            split_prefix_list(raw)
+                .into_iter()
+                .filter(|name| name != ".stitchworkspace")
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .collect()
+        };
+        let exclude_dirs = sorted(&settings.exclude_dirs);
+        let exclude_files = sorted(&settings.exclude_files);
+        let found = |names: &[String], counts: &HashMap<String, usize>| {
+            names
+                .iter()
+                .filter_map(|name| Some((name.clone(), *counts.get(name)?)))
+                .collect::<HashMap<_, _>>()
+        };
+        let existing_excluded_dirs = found(&exclude_dirs, &stats.excluded_dirs_found);
+        let existing_excluded_files = found(&exclude_files, &stats.excluded_files_found);
+
+        let (include_exts, exclude_exts) = parse_extension_filters(&settings.ext_filter);
+        let remove_regex = Some(clean_remove_regex(&settings.remove_regex))
+            .filter(|pattern| !pattern.trim().is_empty());
+        let remove_regex_error = remove_regex
+            .as_deref()
+            .and_then(|pattern| compile_remove_regex(pattern).err());
+        let rust = &settings.rust;
+
+        Self {
+            exclude_dirs,
+            exclude_files,
+            existing_excluded_dirs,
+            existing_excluded_files,
+            filtered_files: stats.filtered_files,
+            override_excluded_dirs: stats.override_excluded_dirs,
+            stitchignored: stats.stitchignored,
+            permission_denied: stats
+                .permission_denied
+                .iter()
+                .map(|p| path_to_unix(p.strip_prefix(project_root).unwrap_or(p)))
+                .collect(),
+            include_exts,
+            exclude_exts,
+            remove_prefixes: split_prefix_list(&settings.remove_prefix),
+            remove_regex,
+            remove_regex_error,
+            rust_remove_inline_comments: rust.rust_remove_inline_comments,
+            rust_remove_doc_comments: rust.rust_remove_doc_comments,
+            signatures_filter: rust
+                .rust_function_signatures_only
+                .then(|| rust.rust_signatures_only_filter.clone()),
+            slint_remove_line_comments: settings.slint.slint_remove_line_comments,
+            slint_remove_block_comments: settings.slint.slint_remove_block_comments,
+            go: settings.go.clone(),
+            web: settings.web.clone(),
+            sql: settings.sql.clone(),
+        }
+    }
+}
+
+struct SelectedPresence {
+    entries: BTreeSet<String>,
+}
+
+impl SelectedPresence {
+    fn new(paths: &[String]) -> Self {
+        let entries = paths.iter().cloned().collect();
+        Self { entries }
+    }
+
+    fn collect_present_extensions(&self, filters: &HashSet<String>) -> Vec<String> {
+        let mut present = BTreeSet::new();
+        for rel in &self.entries {
+            if let Some(ext) = Path::new(rel).extension().and_then(|e| e.to_str()) {
+                let dot = format!(".{}", ext.to_lowercase());
+                if filters.contains(&dot) {
+                    present.insert(dot);
+                }
+            }
+        }
+        present.into_iter().collect()
+    }
+
+    fn has_rust_files(&self) -> bool {
+        self.entries.iter().any(|rel| {
+            Path::new(rel)
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("rs"))
+        })
+    }
+
+    fn has_slint_files(&self) -> bool {
+        self.entries.iter().any(|rel| {
+            Path::new(rel)
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("slint"))
+        })
+    }
+
+    fn has_go_files(&self) -> bool {
+        self.entries
+            .iter()
+            .any(|rel| is_go_file_path(Path::new(rel)))
+    }
+
+    fn has_web_files(&self) -> bool {
+        self.entries
+            .iter()
+            .any(|rel| is_web_file_path(Path::new(rel)))
+    }
+
+    fn has_sql_files(&self) -> bool {
+        self.entries
+            .iter()
+            .any(|rel| is_sql_file_path(Path::new(rel)))
+    }
+
+    fn count_generated_go_files(&self) -> usize {
+        self.entries
+            .iter()
+            .filter(|rel| is_generated_go_file_path(Path::new(rel)))
+            .count()
+    }
+
+    fn any_matches_filter(&self, filter: &str) -> bool {
+        self.entries
+            .iter()
+            .any(|rel| signatures_filter_matches(rel, filter))
+    }
+}
+
+fn note_excluded_dirs(ctx: &NotesContext, _selected: &SelectedPresence) -> Option<String> {
+    note_exclusions(
+        "Excluded directories",
+        &ctx.exclude_dirs,
+        &ctx.existing_excluded_dirs,
+    )
+}
+
+fn note_excluded_files(ctx: &NotesContext, _selected: &SelectedPresence) -> Option<String> {
+    note_exclusions(
+        "Excluded files",
+        &ctx.exclude_files,
+        &ctx.existing_excluded_files,
+    )
+}
+
+/// `Excluded files: .env, secrets.txt (3 entries skipped)`, listing only names that exist.
+fn note_exclusions(
+    label: &str,
+    names: &[String],
+    found: &HashMap<String, usize>,
+) -> Option<String> {
+    let mut present: Vec<&str> = names
+        .iter()
+        .filter(|name| found.contains_key(*name))
+        .map(String::as_str)
+        .collect();
+    if present.is_empty() {
+        return None;
+    }
+    present.sort_unstable();
+    let skipped: usize = found.values().sum();
+    let entries = if skipped == 1 { "entry" } else { "entries" };
+    Some(format!(
+        "{label}: {} ({skipped} {entries} skipped)",
+        present.join(", ")
+    ))
+}
+
+fn note_extension_filters(ctx: &NotesContext, selected: &SelectedPresence) -> Vec<String> {
+    let mut lines = Vec::new();
+    if !ctx.include_exts.is_empty() {
+        let present = selected.collect_present_extensions(&ctx.include_exts);
+        if !present.is_empty() {
+            lines.push(format!("Included extensions: {}", present.join(", ")));
+        }
+    }
+    if !ctx.exclude_exts.is_empty() {
+        let present = selected.collect_present_extensions(&ctx.exclude_exts);
+        if !present.is_empty() {
+            lines.push(format!("Excluded extensions: {}", present.join(", ")));
+        }
+    }
+    if ctx.filtered_files > 0 {
+        lines.push(format!(
+            "Files hidden by extension filters: {}",
+            ctx.filtered_files
+        ));
+    }
+    lines
+}
+
+fn note_directory_overrides(ctx: &NotesContext) -> Option<String> {
+    (ctx.override_excluded_dirs > 0).then(|| {
+        format!(
+            "Directories left out by overrides: {}",
+            ctx.override_excluded_dirs
+        )
+    })
+}
+
+fn note_stitchignore(ctx: &NotesContext) -> Option<String> {
+    (ctx.stitchignored > 0).then(|| {
+        format!(
+            "Entries left out by {STITCHIGNORE_FILE}: {}",
+            ctx.stitchignored
+        )
+    })
+}
+
+fn note_permission_denied(ctx: &NotesContext) -> Option<String> {
+    if ctx.permission_denied.is_empty() {
+        return None;
+    }
+    let mut dirs: Vec<String> = ctx
+        .permission_denied
+        .iter()
+        .map(|rel| {
+            if rel.is_empty() {
+                "./".into()
+            } else {
+                format!("{rel}/")
+            }
+        })
+        .collect();
+    dirs.sort_unstable();
+    Some(format!(
+        "Unreadable directories (permission denied, shown empty): {}",
+        dirs.join(", ")
+    ))
+}
+
+fn note_remove_settings(ctx: &NotesContext) -> Vec<String> {
+    let mut lines = Vec::new();
+    if !ctx.remove_prefixes.is_empty() {
+        lines.push(format!(
+            "Removed lines starting with: {}",
+            ctx.remove_prefixes.join(", ")
+        ));
+    }
+    if let Some(pattern) = ctx.remove_regex.as_ref()
+        && !pattern.trim().is_empty()
+    {
+        match ctx.remove_regex_error.as_ref() {
+            Some(err) => lines.push(format!("Ignored remove-regex ({err}): {pattern}")),
+            None => lines.push(format!("Applied remove-regex: {pattern}")),
+        }
+    }
+    lines
+}
+
+fn note_rust_settings(ctx: &NotesContext, selected: &SelectedPresence) -> Vec<String> {
+    let mut lines = Vec::new();
+    if !selected.has_rust_files() {
+        return lines;
+    }
+    if ctx.rust_remove_inline_comments {
+        lines.push("Removed Rust inline comments (//, /* */)".to_string());
+    }
+    if ctx.rust_remove_doc_comments {
+        lines.push("Removed Rust doc comments (///, //!, /** */)".to_string());
+    }
+    if let Some(filter) = ctx.signatures_filter.as_ref() {
+        let trimmed = filter.trim();
+        if trimmed.is_empty() {
+            if selected.has_rust_files() {
+                lines.push(
+                    "Functions bodies omitted (signatures only) for all Rust files".to_string(),
+                );
+            }
+        } else if selected.any_matches_filter(trimmed) {
+            lines.push(format!(
+                "Functions bodies omitted (signatures only) for: {filter}"
+            ));
+        }
+    }
+    lines
+}
+
+fn note_slint_settings(ctx: &NotesContext, selected: &SelectedPresence) -> Vec<String> {
+    let mut lines = Vec::new();
+    if !selected.has_slint_files() {
+        return lines;
+    }
+    if ctx.slint_remove_line_comments {
+        lines.push("Removed Slint single-line comments (//)".to_string());
+    }
+    if ctx.slint_remove_block_comments {
+        lines.push("Removed Slint multi-line comments (/* */)".to_string());
+    }
+    lines
+}
+
+fn note_go_settings(ctx: &NotesContext, selected: &SelectedPresence) -> Vec<String> {
+    let mut lines = Vec::new();
+    if !selected.has_go_files() {
+        return lines;
+    }
+    if ctx.go.go_remove_line_comments {
+        lines.push("Removed Go single-line comments (//)".to_string());
+    }
+    if ctx.go.go_remove_block_comments {
+        lines.push("Removed Go multi-line comments (/* */)".to_string());
+    }
+    if ctx.go.go_function_signatures_only {
+        lines.push("Go function bodies omitted (signatures only)".to_string());
+    }
+    let generated = selected.count_generated_go_files();
+    if generated > 0 && !ctx.go.go_keep_generated {
+        lines.push(format!(
+            "Left out {generated} generated Go file(s) (*.pb.go); set go_keep_generated to include them"
+        ));
+    }
+    lines
+}
+
+fn note_web_settings(ctx: &NotesContext, selected: &SelectedPresence) -> Vec<String> {
+    let mut lines = Vec::new();
+    if !selected.has_web_files() {
+        return lines;
+    }
+    if ctx.web.web_remove_comments {
+        lines.push("Removed HTML/CSS comments".to_string());
+    }
+    if ctx.web.web_collapse_whitespace {
+        lines.push("Collapsed whitespace in HTML/CSS files".to_string());
+    }
+    lines
+}
+
+fn note_sql_settings(ctx: &NotesContext, selected: &SelectedPresence) -> Vec<String> {
+    let mut lines = Vec::new();
+    if !selected.has_sql_files() {
+        return lines;
+    }
+    if ctx.sql.sql_remove_comments {
+        lines.push("Removed SQL comments (--, /* */)".to_string());
+    }
+    if ctx.sql.sql_collapse_data {
+        lines.push(format!(
+            "Collapsed SQL INSERT/COPY data of {SQL_COLLAPSE_MIN_ROWS}+ rows to summary lines"
+        ));
+    }
+    lines
+}
+
+fn note_directory_samples(omissions: &[SampleOmission]) -> Vec<String> {
+    omissions
+        .iter()
+        .map(|o| {
+            let dir = if o.path.is_empty() {
+                "."
+            } else {
+                o.path.as_str()
+            };
+            format!(
+                "Sampled {dir}/: included {} of {} files ({} omitted)",
+                o.kept,
+                o.total,
+                o.omitted()
+            )
+        })
+        .collect()
+}
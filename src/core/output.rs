@@ -0,0 +1,734 @@
+use std::{
+    collections::HashMap,
+    fmt::Write,
+    hash::BuildHasher,
+    io,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::{
+    Node, TreeStyle, collect_selected_paths, current_git_branch, estimate_file_tokens,
+    path_to_unix, render_tree_from_paths, rust_module_order,
+};
+
+/* ============================== Output layout =============================== */
+
+/// A top-level section of the generated output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputSection {
+    Hierarchy,
+    UnselectedDirectories,
+    Dependencies,
+    Notes,
+    Contents,
+}
+
+impl OutputSection {
+    /// Every section, in the default emission order.
+    pub const ALL: [Self; 5] = [
+        Self::Hierarchy,
+        Self::UnselectedDirectories,
+        Self::Dependencies,
+        Self::Notes,
+        Self::Contents,
+    ];
+
+    #[must_use]
+    pub const fn default_header(self) -> &'static str {
+        match self {
+            Self::Hierarchy => "=== FILE HIERARCHY ===",
+            Self::UnselectedDirectories => "=== UNSELECTED DIRECTORIES ===",
+            Self::Dependencies => "=== DEPENDENCIES ===",
+            Self::Notes => "=== NOTES ===",
+            Self::Contents => "=== FILE CONTENTS ===",
+        }
+    }
+}
+
+const fn default_true() -> bool {
+    true
+}
+
+/// How one section is emitted.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SectionLayout {
+    pub section: OutputSection,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Full header line; `None` uses the built-in `=== … ===` header.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub header: Option<String>,
+}
+
+impl SectionLayout {
+    #[must_use]
+    pub const fn new(section: OutputSection) -> Self {
+        Self {
+            section,
+            enabled: true,
+            header: None,
+        }
+    }
+
+    #[must_use]
+    pub fn header(&self) -> &str {
+        self.header
+            .as_deref()
+            .unwrap_or_else(|| self.section.default_header())
+    }
+}
+
+/// Order, visibility and headers of the output sections.
+///
+/// Sections missing from `sections` are emitted after the listed ones in their
+/// default order, so layouts saved before a section existed keep working.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OutputLayout {
+    pub sections: Vec<SectionLayout>,
+    /// Provenance header written above the first section (see [`Provenance::render`]);
+    /// `None` leaves it out, `""` uses [`DEFAULT_PROVENANCE_FORMAT`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provenance: Option<String>,
+    /// Add each file's last modification time to its header line (see
+    /// [`FileMarkers::write_block`]).
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub modified_times: bool,
+    /// End the output with SHA-256 checksums of it and of each file block (see
+    /// [`crate::core::append_checksum_footer`]).
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub checksum_footer: bool,
+}
+
+impl Default for OutputLayout {
+    fn default() -> Self {
+        Self {
+            sections: OutputSection::ALL
+                .into_iter()
+                .map(SectionLayout::new)
+                .collect(),
+            provenance: None,
+            modified_times: false,
+            checksum_footer: false,
+        }
+    }
+}
+
+impl OutputLayout {
+    /// The effective section list: listed sections first (duplicates dropped), then missing ones.
+    #[must_use]
+    pub fn resolved(&self) -> Vec<SectionLayout> {
+        let mut out: Vec<SectionLayout> = Vec::with_capacity(OutputSection::ALL.len());
+        for s in &self.sections {
+            if !out.iter().any(|o| o.section == s.section) {
+                out.push(s.clone());
+            }
+        }
+        for section in OutputSection::ALL {
+            if !out.iter().any(|o| o.section == section) {
+                out.push(SectionLayout::new(section));
+            }
+        }
+        out
+    }
+
+    #[must_use]
+    pub fn is_enabled(&self, section: OutputSection) -> bool {
+        self.resolved()
+            .iter()
+            .any(|s| s.section == section && s.enabled)
+    }
+
+    /// The provenance format in effect, if the header is on.
+    #[must_use]
+    pub fn provenance_format(&self) -> Option<&str> {
+        self.provenance.as_deref().map(|f| {
+            if f.trim().is_empty() {
+                DEFAULT_PROVENANCE_FORMAT
+            } else {
+                f
+            }
+        })
+    }
+
+    /// Join the produced section bodies according to the layout.
+    ///
+    /// Sections are separated by a blank line; each starts with its header line
+    /// followed by a blank line. Disabled or absent sections are skipped. The
+    /// provenance header, when produced, comes first.
+    #[must_use]
+    pub fn render(&self, parts: &OutputParts) -> String {
+        let mut out = String::new();
+        if let Some(provenance) = parts.provenance.as_deref().filter(|p| !p.is_empty()) {
+            out.push_str(provenance);
+        }
+        for s in self.resolved().iter().filter(|s| s.enabled) {
+            let Some(body) = parts.body(s.section) else {
+                continue;
+            };
+            if !out.is_empty() {
+                out.push('\n');
+            }
+            out.push_str(s.header());
+            out.push_str("\n\n");
+            out.push_str(body);
+        }
+        out
+    }
+}
+
+/// Section bodies produced by a generation run; `None` means "not produced".
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OutputParts {
+    /// Rendered provenance header (see [`OutputLayout::provenance`]).
+    pub provenance: Option<String>,
+    pub hierarchy: Option<String>,
+    pub unselected_directories: Option<String>,
+    pub dependencies: Option<String>,
+    pub notes: Option<String>,
+    pub contents: Option<String>,
+}
+
+impl OutputParts {
+    #[must_use]
+    pub fn body(&self, section: OutputSection) -> Option<&str> {
+        match section {
+            OutputSection::Hierarchy => self.hierarchy.as_deref(),
+            OutputSection::UnselectedDirectories => self.unselected_directories.as_deref(),
+            OutputSection::Dependencies => self.dependencies.as_deref(),
+            OutputSection::Notes => self.notes.as_deref(),
+            OutputSection::Contents => self.contents.as_deref(),
+        }
+    }
+}
+
+/* ============================ Provenance header ============================= */
+
+pub const DEFAULT_PROVENANCE_FORMAT: &str =
+    "Generated by stitch {version} at {time}\nProfile: {profile}\nRoot: {root_hash}";
+
+/// Where an output came from, for the layout's provenance header.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Provenance {
+    /// Local generation time (RFC 3339); empty leaves it out.
+    pub time: String,
+    pub version: String,
+    /// Active profile; empty when none is.
+    pub profile: String,
+    /// Short hash of the project root path, identifying the checkout without naming it.
+    pub root_hash: String,
+    /// Checked-out git branch; empty when detached or not a repository.
+    pub git_branch: String,
+    /// Subject line of the last commit.
+    pub git_commit: String,
+    /// Author name of the last commit.
+    pub git_author: String,
+    /// Allowlisted environment variables as `(name, value)`.
+    pub env: Vec<(String, String)>,
+}
+
+impl Provenance {
+    /// Provenance for `project_root` now, or without a time when `deterministic`.
+    #[must_use]
+    pub fn new(project_root: &Path, profile: Option<&str>, deterministic: bool) -> Self {
+        Self {
+            time: if deterministic {
+                String::new()
+            } else {
+                chrono::Local::now()
+                    .format("%Y-%m-%dT%H:%M:%S%:z")
+                    .to_string()
+            },
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            profile: profile.unwrap_or_default().to_string(),
+            root_hash: root_path_hash(project_root),
+            ..Self::default()
+        }
+    }
+
+    /// Resolve the git and `{env:NAME}` variables `format` uses. Git is only asked when
+    /// a `{git_*}` placeholder is present; environment variables are read only when their
+    /// name is in `env_allowlist`.
+    #[must_use]
+    pub fn with_sources(
+        mut self,
+        project_root: &Path,
+        format: &str,
+        env_allowlist: &[String],
+    ) -> Self {
+        if format.contains("{git_") {
+            self.git_branch = current_git_branch(project_root).unwrap_or_default();
+            if let Some((subject, author)) = last_commit(project_root) {
+                self.git_commit = subject;
+                self.git_author = author;
+            }
+        }
+        for name in env_placeholders(format) {
+            if env_allowlist.iter().any(|a| a == name) && !self.env.iter().any(|(n, _)| n == name) {
+                let value = std::env::var(name).unwrap_or_default();
+                self.env.push((name.to_string(), value));
+            }
+        }
+        self
+    }
+
+    /// Fill `format`'s `{time}`, `{version}`, `{profile}`, `{root_hash}`, `{git_branch}`,
+    /// `{git_commit}`, `{git_author}` and `{env:NAME}`, one output line per format line.
+    /// Lines whose placeholders are all empty are dropped, so there's no `Profile:` line
+    /// without a profile and no time in deterministic output.
+    #[must_use]
+    pub fn render(&self, format: &str) -> String {
+        let fields = [
+            ("{time}", self.time.as_str()),
+            ("{version}", self.version.as_str()),
+            ("{profile}", self.profile.as_str()),
+            ("{root_hash}", self.root_hash.as_str()),
+            ("{git_branch}", self.git_branch.as_str()),
+            ("{git_commit}", self.git_commit.as_str()),
+            ("{git_author}", self.git_author.as_str()),
+        ];
+        let mut out = String::new();
+        for line in format.lines() {
+            let env_names = env_placeholders(line);
+            let used: Vec<&str> = fields
+                .iter()
+                .filter(|(key, _)| line.contains(key))
+                .map(|(_, value)| *value)
+                .chain(env_names.iter().map(|name| self.env_value(name)))
+                .collect();
+            if !used.is_empty() && used.iter().all(|v| v.is_empty()) {
+                continue;
+            }
+            let mut filled = line.to_string();
+            for (key, value) in fields {
+                filled = filled.replace(key, value);
+            }
+            for name in env_names {
+                filled = filled.replace(&format!("{{env:{name}}}"), self.env_value(name));
+            }
+            out.push_str(filled.trim_end());
+            out.push('\n');
+        }
+        out
+    }
+
+    fn env_value(&self, name: &str) -> &str {
+        self.env
+            .iter()
+            .find(|(n, _)| n == name)
+            .map_or("", |(_, v)| v.as_str())
+    }
+}
+
+/// Names of the `{env:NAME}` placeholders in `format`, in order.
+fn env_placeholders(format: &str) -> Vec<&str> {
+    let mut names = Vec::new();
+    let mut rest = format;
+    while let Some(start) = rest.find("{env:") {
+        rest = &rest[start + 5..];
+        let Some(end) = rest.find('}') else { break };
+        let name = &rest[..end];
+        if !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            names.push(name);
+        }
+        rest = &rest[end + 1..];
+    }
+    names
+}
+
+/// Subject and author name of `HEAD`'s commit, if `root` is in a git repository.
+fn last_commit(root: &Path) -> Option<(String, String)> {
+    let output = std::process::Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args(["log", "-1", "--format=%s%x00%an"])
+        .output()
+        .ok()
+        .filter(|o| o.status.success())?;
+    let text = String::from_utf8_lossy(&output.stdout);
+    let (subject, author) = text.trim_end().split_once('\0')?;
+    Some((subject.to_string(), author.to_string()))
+}
+
+/// First 12 hex digits of the FNV-1a hash of the root's `/`-separated path.
+fn root_path_hash(root: &Path) -> String {
+    let hash = path_to_unix(root)
+        .bytes()
+        .fold(0xcbf2_9ce4_8422_2325_u64, |h, b| {
+            (h ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3)
+        });
+    format!("{hash:016x}")[..12].to_string()
+}
+
+/* =============================== File markers =============================== */
+
+pub const DEFAULT_FILE_HEADER: &str = "--- Start of file: {path} ---";
+pub const DEFAULT_FILE_FOOTER: &str = "--- End of file: {path} ---";
+/// Header of [`FileMarkers::cells`]: a `# %%` cell per file, as in Jupytext's percent format.
+pub const CELL_FILE_HEADER: &str = "# %% {path}";
+
+fn default_file_header() -> String {
+    DEFAULT_FILE_HEADER.to_string()
+}
+
+fn default_file_footer() -> String {
+    DEFAULT_FILE_FOOTER.to_string()
+}
+
+/// Format strings of the lines around each file block.
+///
+/// Placeholders: `{path}` (emitted path), `{lang}` (code-fence language, e.g. `rust`),
+/// `{size}` (bytes of the emitted contents) and `{tokens}` (estimated tokens of the
+/// emitted contents). Other braces are kept as written; an empty format drops its line.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileMarkers {
+    #[serde(default = "default_file_header")]
+    pub header: String,
+    #[serde(default = "default_file_footer")]
+    pub footer: String,
+}
+
+impl Default for FileMarkers {
+    fn default() -> Self {
+        Self {
+            header: default_file_header(),
+            footer: default_file_footer(),
+        }
+    }
+}
+
+impl FileMarkers {
+    /// `# %% path` cell markers and no footer, so editors with cell notation (VS Code,
+    /// Spyder, ...) can navigate the output file by file.
+    #[must_use]
+    pub fn cells() -> Self {
+        Self {
+            header: CELL_FILE_HEADER.to_string(),
+            footer: String::new(),
+        }
+    }
+
+    /// Literal start of every header line: the header format up to its first `{`.
+    #[must_use]
+    pub fn header_prefix(&self) -> &str {
+        &self.header[..self.header.find('{').unwrap_or(self.header.len())]
+    }
+
+    /// The `{path}` of a header `line` written by [`Self::write_block`], if the format
+    /// has no other placeholder before it.
+    #[must_use]
+    pub fn header_path<'a>(&self, line: &'a str) -> Option<&'a str> {
+        let (before, after) = self.header.split_once("{path}")?;
+        if before.contains('{') {
+            return None;
+        }
+        let rest = line.strip_prefix(before)?;
+        // The header may go on after the path, e.g. with a modification time.
+        let suffix = &after[..after.find('{').unwrap_or(after.len())];
+        let end = if suffix.is_empty() {
+            rest.rfind(" (modified ").unwrap_or(rest.len())
+        } else {
+            rest.rfind(suffix)?
+        };
+        Some(&rest[..end])
+    }
+
+    /// Append one file block: header line, `contents`, footer line, then a blank line.
+    ///
+    /// `lang` fills `{lang}`, usually [`crate::core::lang_for_path`] of `path`. `modified`
+    /// (see [`format_modified_time`]) is added to the header line as `(modified ...)`.
+    pub fn write_block(
+        &self,
+        out: &mut String,
+        path: &str,
+        lang: &str,
+        contents: &str,
+        modified: Option<&str>,
+    ) {
+        let needs_tokens = self.header.contains("{tokens}") || self.footer.contains("{tokens}");
+        let fields = MarkerFields {
+            path,
+            lang,
+            size: contents.len(),
+            tokens: if needs_tokens {
+                estimate_file_tokens(path, contents)
+            } else {
+                0
+            },
+        };
+        if !self.header.is_empty() {
+            fields.fill(out, &self.header);
+            if let Some(modified) = modified {
+                let _ = write!(out, " (modified {modified})");
+            }
+            out.push('\n');
+        }
+        out.push_str(contents);
+        out.push('\n');
+        if !self.footer.is_empty() {
+            fields.fill(out, &self.footer);
+            out.push('\n');
+        }
+        out.push('\n');
+    }
+}
+
+/// A file's modification time as shown in its header, in UTC to the minute.
+#[must_use]
+pub fn format_modified_time(time: SystemTime) -> String {
+    chrono::DateTime::<chrono::Utc>::from(time)
+        .format("%Y-%m-%d %H:%M UTC")
+        .to_string()
+}
+
+struct MarkerFields<'a> {
+    path: &'a str,
+    lang: &'a str,
+    size: usize,
+    tokens: usize,
+}
+
+impl MarkerFields<'_> {
+    fn fill(&self, out: &mut String, format: &str) {
+        let mut rest = format;
+        while let Some(open) = rest.find('{') {
+            out.push_str(&rest[..open]);
+            let tail = &rest[open..];
+            rest = if let Some(after) = tail.strip_prefix("{path}") {
+                out.push_str(self.path);
+                after
+            } else if let Some(after) = tail.strip_prefix("{lang}") {
+                out.push_str(self.lang);
+                after
+            } else if let Some(after) = tail.strip_prefix("{size}") {
+                let _ = write!(out, "{}", self.size);
+                after
+            } else if let Some(after) = tail.strip_prefix("{tokens}") {
+                let _ = write!(out, "{}", self.tokens);
+                after
+            } else {
+                out.push('{');
+                &tail[1..]
+            };
+        }
+        out.push_str(rest);
+    }
+}
+
+/* ================================ File order ================================ */
+
+/// Order of the file blocks in the output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputOrder {
+    /// Pinned files, then the rest in selection (tree) order.
+    #[default]
+    PinnedFirst,
+    /// By relative path.
+    Alphabetical,
+    /// Directory by directory (sorted by path), each directory's files by name.
+    DirectoryGrouped,
+    /// By file size, smallest first.
+    SmallestFirst,
+    /// Rust crates top-down: crate root, then modules in declaration order.
+    RustModules,
+}
+
+impl OutputOrder {
+    /// Position in the UI order selector.
+    #[must_use]
+    pub const fn index(self) -> i32 {
+        match self {
+            Self::PinnedFirst => 0,
+            Self::Alphabetical => 1,
+            Self::DirectoryGrouped => 2,
+            Self::SmallestFirst => 3,
+            Self::RustModules => 4,
+        }
+    }
+
+    #[must_use]
+    pub const fn from_index(index: i32) -> Self {
+        match index {
+            1 => Self::Alphabetical,
+            2 => Self::DirectoryGrouped,
+            3 => Self::SmallestFirst,
+            4 => Self::RustModules,
+            _ => Self::PinnedFirst,
+        }
+    }
+}
+
+/// Reorder the files to emit. `files` is expected in pinned-first selection order, which
+/// [`OutputOrder::PinnedFirst`] keeps; the other orders ignore pins.
+///
+/// Smallest-first reads file sizes; unreadable files sort last. Ties keep path order.
+#[must_use]
+pub fn order_output_files(
+    project_root: &Path,
+    mut files: Vec<PathBuf>,
+    order: OutputOrder,
+) -> Vec<PathBuf> {
+    let rel = |p: &Path| path_to_unix(p.strip_prefix(project_root).unwrap_or(p));
+    match order {
+        OutputOrder::PinnedFirst => {}
+        OutputOrder::Alphabetical => files.sort_by_cached_key(|p| rel(p)),
+        OutputOrder::DirectoryGrouped => files.sort_by_cached_key(|p| {
+            let path = rel(p);
+            match path.rsplit_once('/') {
+                Some((dir, name)) => (dir.to_string(), name.to_string()),
+                None => (String::new(), path),
+            }
+        }),
+        OutputOrder::SmallestFirst => files.sort_by_cached_key(|p| {
+            let size = std::fs::metadata(p).map_or(u64::MAX, |m| m.len());
+            (size, rel(p))
+        }),
+        OutputOrder::RustModules => files = rust_module_order(&files),
+    }
+    files
+}
+
+/* ============================== Path rewriting ============================== */
+
+/// Rewrites project-relative paths before they are emitted (hierarchy and file headers).
+///
+/// `strip_prefix` removes a leading directory (e.g. `packages/foo`) from paths below it;
+/// `prepend` then adds a virtual root directory in front of every path.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PathRewrite {
+    #[serde(default)]
+    pub strip_prefix: String,
+    #[serde(default)]
+    pub prepend: String,
+}
+
+fn trim_slashes(s: &str) -> &str {
+    s.trim().trim_matches('/')
+}
+
+impl PathRewrite {
+    #[must_use]
+    pub fn is_identity(&self) -> bool {
+        trim_slashes(&self.strip_prefix).is_empty() && trim_slashes(&self.prepend).is_empty()
+    }
+
+    /// Rewrite a forward-slash relative path.
+    #[must_use]
+    pub fn apply(&self, rel: &str) -> String {
+        let strip = trim_slashes(&self.strip_prefix);
+        let stripped = if strip.is_empty() {
+            rel
+        } else {
+            rel.strip_prefix(strip)
+                .and_then(|rest| rest.strip_prefix('/'))
+                .filter(|rest| !rest.is_empty())
+                .unwrap_or(rel)
+        };
+        let prepend = trim_slashes(&self.prepend);
+        if prepend.is_empty() {
+            stripped.to_string()
+        } else {
+            format!("{prepend}/{stripped}")
+        }
+    }
+
+    /// The path written in a file header for `rel`. Without a rewrite it keeps the
+    /// platform's separators unless `deterministic` asks for `/` everywhere.
+    #[must_use]
+    pub fn display(&self, rel: &Path, deterministic: bool) -> String {
+        if self.is_identity() && !deterministic {
+            rel.to_string_lossy().into_owned()
+        } else {
+            self.apply(&path_to_unix(rel))
+        }
+    }
+}
+
+/* ============================ Placeholder output ============================ */
+
+/// Output shown when no project is open.
+pub const NO_FOLDER_SELECTED: &str = "No folder selected.\n";
+/// Output shown when nothing in the tree is selected.
+pub const NO_ITEMS_SELECTED: &str = "No items selected.\n";
+
+/// Whether `text` is one of the placeholder outputs rather than generated content.
+#[must_use]
+pub fn is_placeholder_message(text: &str) -> bool {
+    text == NO_FOLDER_SELECTED || text == NO_ITEMS_SELECTED
+}
+
+/// What the output pane holds.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputKind {
+    /// A message standing in for output: a placeholder such as [`NO_ITEMS_SELECTED`], or
+    /// why a generation failed. It has no stats and is never copied or saved.
+    #[default]
+    Message,
+    /// The result of a successful generation.
+    Generated,
+}
+
+/// Whether `text` of `kind` is worth copying or saving: generated and not blank.
+#[must_use]
+pub fn has_output_content(kind: OutputKind, text: &str) -> bool {
+    kind == OutputKind::Generated && !text.trim().is_empty()
+}
+
+/* ============================ Deterministic output ========================== */
+
+/// Why a file was skipped, as listed in NOTES. OS error messages differ between
+/// platforms and are translated on some, so `deterministic` output names the error kind
+/// instead.
+#[must_use]
+pub fn skipped_file_reason(err: &io::Error, deterministic: bool) -> String {
+    match err.kind() {
+        io::ErrorKind::InvalidData => "not UTF-8".to_string(),
+        io::ErrorKind::PermissionDenied => "Permission denied".to_string(),
+        kind if deterministic => kind.to_string(),
+        _ => err.to_string(),
+    }
+}
+
+/* ============================= Hierarchy export ============================= */
+
+/// Tree of `rel_paths` under `root_name` after `rewrite`, drawn in `style`, i.e. the
+/// body of the hierarchy section without its header.
+#[must_use]
+pub fn render_hierarchy(
+    rel_paths: &[String],
+    root_name: &str,
+    rewrite: &PathRewrite,
+    style: TreeStyle,
+) -> String {
+    let shown: Vec<String> = rel_paths.iter().map(|p| rewrite.apply(p)).collect();
+    render_tree_from_paths(&shown, Some(root_name), style)
+}
+
+/// Tree of the files selected below `root`, as "Hierarchy Only" shows it.
+///
+/// `None` if no file is selected.
+#[must_use]
+pub fn selection_hierarchy<S: BuildHasher>(
+    root: &Node,
+    explicit: &HashMap<PathBuf, bool, S>,
+    rewrite: &PathRewrite,
+    style: TreeStyle,
+) -> Option<String> {
+    let mut files = Vec::new();
+    let mut dirs = Vec::new();
+    collect_selected_paths(root, explicit, None, &mut files, &mut dirs);
+    let rels: Vec<String> = files
+        .iter()
+        .filter_map(|f| f.strip_prefix(&root.path).ok())
+        .filter(|r| !r.as_os_str().is_empty())
+        .map(path_to_unix)
+        .collect();
+    if rels.is_empty() {
+        return None;
+    }
+    Some(render_hierarchy(&rels, &root.name, rewrite, style))
+}
@@ -0,0 +1,67 @@
+use std::env;
+use std::path::{Path, PathBuf};
+
+/* ============================== Display paths =============================== */
+
+/// The user's home directory: `USERPROFILE` on Windows, `HOME` elsewhere, each
+/// falling back to the other. Empty variables count as unset.
+#[must_use]
+pub fn home_dir() -> Option<PathBuf> {
+    let var = |name: &str| {
+        env::var_os(name)
+            .filter(|v| !v.is_empty())
+            .map(PathBuf::from)
+    };
+    if cfg!(windows) {
+        var("USERPROFILE").or_else(|| var("HOME"))
+    } else {
+        var("HOME").or_else(|| var("USERPROFILE"))
+    }
+}
+
+/// `dir` as shown to the user in the title bar, CLI errors and dialogs: forward
+/// slashes, with the home directory written as `~`.
+#[must_use]
+pub fn display_path(dir: &Path) -> String {
+    home_dir().map_or_else(|| slashed(dir), |home| display_path_with_home(dir, &home))
+}
+
+/// [`display_path`] with an explicit home directory. Windows compares paths
+/// case-insensitively (`C:\Users\Ann` and `c:\users\ann` are the same folder); a home
+/// of `/` is never abbreviated.
+#[must_use]
+pub fn display_path_with_home(dir: &Path, home: &Path) -> String {
+    let path = slashed(dir);
+    let home = slashed(home);
+    let home = home.trim_end_matches('/');
+    if home.is_empty() || path.len() < home.len() || !path.is_char_boundary(home.len()) {
+        return path;
+    }
+    let (head, rest) = path.split_at(home.len());
+    let same = if cfg!(windows) {
+        head.eq_ignore_ascii_case(home)
+    } else {
+        head == home
+    };
+    match rest.strip_prefix('/') {
+        _ if !same => path,
+        _ if rest.is_empty() => "~".to_string(),
+        Some(rel) => format!("~/{rel}"),
+        None => path,
+    }
+}
+
+/// `path` with `/` separators and without a trailing one, except for roots like `/`
+/// and `C:/`.
+fn slashed(path: &Path) -> String {
+    let text = path.to_string_lossy();
+    let text = if cfg!(windows) {
+        text.replace('\\', "/")
+    } else {
+        text.into_owned()
+    };
+    match text.trim_end_matches('/') {
+        trimmed if trimmed.is_empty() || trimmed.ends_with(':') => text,
+        trimmed => trimmed.to_string(),
+    }
+}
@@ -0,0 +1,39 @@
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
+
+use crate::core::project_path_from_rel;
+
+/* =============================== Pinned files =============================== */
+
+/// Put the pinned files (project-relative, forward slashes) first, in the given order,
+/// followed by the rest of `files` in their original order.
+///
+/// Pinned files are emitted whether or not they are in `files` (unchecked, filtered out
+/// by extension, ...); only pins whose file no longer exists are dropped.
+#[must_use]
+pub fn apply_pinned_files(
+    project_root: &Path,
+    files: &[PathBuf],
+    pinned: &[String],
+) -> Vec<PathBuf> {
+    if pinned.is_empty() {
+        return files.to_vec();
+    }
+
+    let mut seen: HashSet<PathBuf> = HashSet::with_capacity(files.len() + pinned.len());
+    let mut ordered = Vec::with_capacity(files.len() + pinned.len());
+    for rel in pinned {
+        let path = project_path_from_rel(project_root, rel);
+        if path.is_file() && seen.insert(path.clone()) {
+            ordered.push(path);
+        }
+    }
+    for path in files {
+        if seen.insert(path.clone()) {
+            ordered.push(path.clone());
+        }
+    }
+    ordered
+}
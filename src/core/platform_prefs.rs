@@ -0,0 +1,134 @@
+use std::process::Command;
+
+/* ======================== OS accessibility preferences ====================== */
+
+/// How long toasts stay up when animations are reduced, at the least: they appear and
+/// vanish without a fade, so they get more time to be read.
+pub const REDUCED_MOTION_TOAST_MILLIS: u64 = 4000;
+
+/// Accessibility preferences the UI follows: a high-contrast palette, and no fades or
+/// short-lived toasts.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PlatformPreferences {
+    pub high_contrast: bool,
+    pub reduced_motion: bool,
+}
+
+impl PlatformPreferences {
+    /// These preferences with the project's local overrides applied (`None` follows the OS).
+    #[must_use]
+    pub fn with_overrides(self, high_contrast: Option<bool>, reduced_motion: Option<bool>) -> Self {
+        Self {
+            high_contrast: high_contrast.unwrap_or(self.high_contrast),
+            reduced_motion: reduced_motion.unwrap_or(self.reduced_motion),
+        }
+    }
+
+    /// How long to show a toast meant to stay up for `millis`.
+    #[must_use]
+    pub fn toast_millis(self, millis: u64) -> u64 {
+        if self.reduced_motion {
+            millis.max(REDUCED_MOTION_TOAST_MILLIS)
+        } else {
+            millis
+        }
+    }
+}
+
+/// Read the OS high-contrast and reduced-motion settings; those that can't be read count
+/// as off.
+///
+/// They come from `HKCU\Control Panel` on Windows, `com.apple.universalaccess` on macOS,
+/// and GNOME's `gsettings` (or a `GTK_THEME` naming a high-contrast theme) elsewhere.
+#[must_use]
+pub fn detect_platform_preferences() -> PlatformPreferences {
+    if cfg!(windows) {
+        let high_contrast = command_output(
+            "reg",
+            &[
+                "query",
+                r"HKCU\Control Panel\Accessibility\HighContrast",
+                "/v",
+                "Flags",
+            ],
+        )
+        .and_then(|out| parse_reg_query_value(&out, "Flags").map(str::to_string))
+        .and_then(|flags| flags.parse::<u32>().ok())
+        .is_some_and(|flags| flags & HCF_HIGHCONTRASTON != 0);
+        let reduced_motion = command_output(
+            "reg",
+            &[
+                "query",
+                r"HKCU\Control Panel\Desktop\WindowMetrics",
+                "/v",
+                "MinAnimate",
+            ],
+        )
+        .and_then(|out| parse_reg_query_value(&out, "MinAnimate").map(str::to_string))
+        .is_some_and(|v| v == "0");
+        PlatformPreferences {
+            high_contrast,
+            reduced_motion,
+        }
+    } else if cfg!(target_os = "macos") {
+        let read = |key: &str| {
+            command_output("defaults", &["read", "com.apple.universalaccess", key])
+                .and_then(|out| parse_bool_setting(&out))
+                .unwrap_or(false)
+        };
+        PlatformPreferences {
+            high_contrast: read("increaseContrast"),
+            reduced_motion: read("reduceMotion"),
+        }
+    } else {
+        let gsettings = |schema: &str, key: &str| {
+            command_output("gsettings", &["get", schema, key])
+                .and_then(|out| parse_bool_setting(&out))
+        };
+        let gtk_theme_high_contrast = std::env::var("GTK_THEME")
+            .is_ok_and(|theme| theme.to_ascii_lowercase().contains("highcontrast"));
+        PlatformPreferences {
+            high_contrast: gtk_theme_high_contrast
+                || gsettings("org.gnome.desktop.a11y.interface", "high-contrast").unwrap_or(false),
+            reduced_motion: gsettings("org.gnome.desktop.interface", "enable-animations")
+                .is_some_and(|enabled| !enabled),
+        }
+    }
+}
+
+/// `HCF_HIGHCONTRASTON` in the `HighContrast\Flags` value.
+const HCF_HIGHCONTRASTON: u32 = 0x1;
+
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// The data of value `name` in `reg query` output (`    Flags    REG_SZ    126`).
+#[must_use]
+pub fn parse_reg_query_value<'a>(output: &'a str, name: &str) -> Option<&'a str> {
+    output.lines().find_map(|line| {
+        let mut parts = line.split_whitespace();
+        if !parts.next()?.eq_ignore_ascii_case(name) {
+            return None;
+        }
+        let kind = parts.next()?;
+        if !kind.starts_with("REG_") {
+            return None;
+        }
+        Some(parts.next().unwrap_or(""))
+    })
+}
+
+/// A boolean printed by `defaults read` (`1` / `0`) or `gsettings get` (`true` / `false`).
+#[must_use]
+pub fn parse_bool_setting(output: &str) -> Option<bool> {
+    match output.trim().trim_matches('\'') {
+        "1" | "true" | "TRUE" | "YES" => Some(true),
+        "0" | "false" | "FALSE" | "NO" => Some(false),
+        _ => None,
+    }
+}
@@ -0,0 +1,55 @@
+use std::{path::Path, time::Duration};
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::{ExternalFile, run_external_command};
+
+/// How long the post-processing command may run by default.
+pub const POST_PROCESS_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A shell command the finished output is piped through before it's shown or copied.
+///
+/// Typically a sanitizer or a prompt wrapper. Like the pipeline's `command` steps, it only runs
+/// when the local settings allow external commands.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PostProcess {
+    pub command: String,
+    /// Overrides [`POST_PROCESS_TIMEOUT`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_secs: Option<u64>,
+}
+
+impl PostProcess {
+    #[must_use]
+    pub fn timeout(&self) -> Duration {
+        self.timeout_secs
+            .map_or(POST_PROCESS_TIMEOUT, Duration::from_secs)
+    }
+
+    /// Pipe `output` through the command in `project_root` and return its stdout.
+    /// `STITCH_PATH` is empty and `STITCH_FILE` is the project root.
+    ///
+    /// # Errors
+    /// Returns a one-line message when the command is blank, can't start, times out,
+    /// fails or prints something other than UTF-8.
+    pub fn run(&self, output: &str, project_root: &Path) -> Result<String, String> {
+        let command = self.command.trim();
+        if command.is_empty() {
+            return Err("Post-processing command is empty".to_string());
+        }
+        let file = ExternalFile {
+            path: "",
+            full_path: project_root,
+            working_dir: project_root,
+        };
+        run_external_command(command, output, &file, self.timeout())
+            .map_err(|e| format!("Post-processing command `{command}` {e}"))
+    }
+}
+
+/// Add a NOTES line for a failed post-processing command (`err` from [`PostProcess::run`]).
+pub fn append_post_process_note(notes: &mut String, err: &str) {
+    use std::fmt::Write;
+
+    let _ = writeln!(notes, "{err}; the output was left as generated.\n");
+}
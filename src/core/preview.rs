@@ -0,0 +1,40 @@
+use std::ops::Range;
+
+/* ============================== Output preview ============================== */
+
+/// Chars of output shown at once in the output pane when the local settings don't say
+/// otherwise.
+pub const DEFAULT_PREVIEW_CHAR_LIMIT: usize = 50_000;
+
+/// Byte ranges of the pages `text` is shown in: at most `limit` chars each (one page
+/// when `limit` is `0`), ending after the page's last line break when it has one.
+#[must_use]
+pub fn preview_pages(text: &str, limit: usize) -> Vec<Range<usize>> {
+    if limit == 0 || text.is_empty() {
+        return std::iter::once(0..text.len()).collect();
+    }
+    let mut pages = Vec::new();
+    let mut start = 0;
+    while start < text.len() {
+        let mut end = text[start..]
+            .char_indices()
+            .nth(limit)
+            .map_or(text.len(), |(i, _)| start + i);
+        if end < text.len()
+            && let Some(i) = text[start..end].rfind('\n').filter(|&i| i > 0)
+        {
+            end = start + i + 1;
+        }
+        pages.push(start..end);
+        start = end;
+    }
+    pages
+}
+
+/// Index of the page in `pages` that holds byte `offset` (the last page past the end).
+#[must_use]
+pub fn page_of(pages: &[Range<usize>], offset: usize) -> usize {
+    pages
+        .partition_point(|p| p.end <= offset)
+        .min(pages.len().saturating_sub(1))
+}
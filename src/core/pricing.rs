@@ -0,0 +1,73 @@
+use std::{collections::BTreeMap, fs, path::Path, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::workspace_dir;
+
+/* ============================== Cost estimation ============================= */
+
+/// Input prices in USD per million tokens, plus the model shown next to the token count.
+///
+/// Read from `.stitchworkspace/prices.json`; missing fields fall back to the defaults.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PriceTable {
+    /// Model whose price is displayed; empty hides the estimate.
+    pub model: String,
+    pub prices: BTreeMap<String, f64>,
+}
+
+impl Default for PriceTable {
+    fn default() -> Self {
+        let prices = [
+            ("gpt-4o", 2.50),
+            ("gpt-4o-mini", 0.15),
+            ("gpt-4.1", 2.00),
+            ("claude-sonnet-4", 3.00),
+            ("claude-opus-4", 15.00),
+            ("gemini-2.5-pro", 1.25),
+        ];
+        Self {
+            model: "gpt-4o".to_string(),
+            prices: prices
+                .into_iter()
+                .map(|(model, price)| (model.to_string(), price))
+                .collect(),
+        }
+    }
+}
+
+#[must_use]
+pub fn prices_file(project_root: &Path) -> PathBuf {
+    workspace_dir(project_root).join("prices.json")
+}
+
+/// Load the project's price table, if it has one.
+#[must_use]
+pub fn load_price_table(project_root: &Path) -> Option<PriceTable> {
+    let data = fs::read(prices_file(project_root)).ok()?;
+    serde_json::from_slice::<PriceTable>(&data).ok()
+}
+
+/// Estimated input cost in USD of sending `tokens` tokens to `model`.
+///
+/// `None` if the table has no (finite, non-negative) price for the model.
+#[must_use]
+#[allow(clippy::cast_precision_loss)] // token counts are far below 2^52
+pub fn estimate_cost(tokens: usize, model: &str, table: &PriceTable) -> Option<f64> {
+    let per_million = *table.prices.get(model)?;
+    (per_million.is_finite() && per_million >= 0.0)
+        .then(|| tokens as f64 * per_million / 1_000_000.0)
+}
+
+/// Cost label for the stats line, e.g. `$0.43 @ gpt-4o`, using the table's selected model.
+#[must_use]
+pub fn format_cost(tokens: usize, table: &PriceTable) -> Option<String> {
+    let cost = estimate_cost(tokens, &table.model, table)?;
+    let amount = if cost > 0.0 && cost < 0.01 {
+        "<$0.01".to_string()
+    } else {
+        format!("${cost:.2}")
+    };
+    Some(format!("{amount} @ {}", table.model))
+}
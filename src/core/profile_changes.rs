@@ -0,0 +1,239 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Write,
+};
+
+use serde_json::Value;
+
+use crate::core::{Profile, WorkspaceSettings};
+
+/* ============================ Unsaved changes =============================== */
+
+/// Longest value shown for a changed setting before it's cut with `…`.
+const MAX_VALUE_CHARS: usize = 60;
+
+/// Paths listed per kind of selection change before the rest is summed up.
+const MAX_LISTED_PATHS: usize = 10;
+
+/// A settings field whose value differs from the baseline.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SettingChange {
+    /// Field name as stored in `workspace.json` (nested fields as `path_rewrite.prepend`).
+    pub field: String,
+    pub before: String,
+    pub after: String,
+}
+
+/// What the current profile (or workspace settings) changes relative to its saved baseline.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProfileChanges {
+    /// `(old, new)` when the profile name was edited.
+    pub renamed: Option<(String, String)>,
+    pub settings: Vec<SettingChange>,
+    /// Paths now explicitly checked (new, or previously unchecked).
+    pub checked: Vec<String>,
+    /// Paths now explicitly unchecked (new, or previously checked).
+    pub unchecked: Vec<String>,
+    /// Paths that no longer have an explicit check and follow their parent again.
+    pub cleared: Vec<String>,
+    /// Directory samples and rules added, removed or changed, by path.
+    pub samples: Vec<String>,
+    pub dir_rules: Vec<String>,
+    pub pinned: Vec<String>,
+    pub unpinned: Vec<String>,
+}
+
+impl ProfileChanges {
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Human-readable summary, one section per kind of change; empty when nothing changed.
+    #[must_use]
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        if let Some((old, new)) = &self.renamed {
+            let _ = writeln!(out, "Renamed: {old} → {new}");
+        }
+        if !self.settings.is_empty() {
+            let _ = writeln!(out, "Settings changed ({}):", self.settings.len());
+            for c in &self.settings {
+                let _ = writeln!(out, "  {}: {} → {}", c.field, c.before, c.after);
+            }
+        }
+        let counts: Vec<String> = [
+            (self.checked.len(), "checked"),
+            (self.unchecked.len(), "unchecked"),
+            (self.cleared.len(), "cleared"),
+        ]
+        .iter()
+        .filter(|(n, _)| *n > 0)
+        .map(|(n, what)| format!("{n} {what}"))
+        .collect();
+        if !counts.is_empty() {
+            let _ = writeln!(out, "Selections: {}", counts.join(", "));
+            list_paths(&mut out, "+", &self.checked);
+            list_paths(&mut out, "-", &self.unchecked);
+            list_paths(&mut out, "~", &self.cleared);
+        }
+        for (title, paths) in [
+            ("Samples changed", &self.samples),
+            ("Directory rules changed", &self.dir_rules),
+            ("Pinned", &self.pinned),
+            ("Unpinned", &self.unpinned),
+        ] {
+            if !paths.is_empty() {
+                let _ = writeln!(out, "{title}: {}", paths.len());
+                list_paths(&mut out, " ", paths);
+            }
+        }
+        out
+    }
+}
+
+fn list_paths(out: &mut String, marker: &str, paths: &[String]) {
+    for p in paths.iter().take(MAX_LISTED_PATHS) {
+        let shown = if p.is_empty() { "(project root)" } else { p };
+        let _ = writeln!(out, "  {marker} {shown}");
+    }
+    if paths.len() > MAX_LISTED_PATHS {
+        let _ = writeln!(out, "  … and {} more", paths.len() - MAX_LISTED_PATHS);
+    }
+}
+
+/// The settings fields that differ between `baseline` and `current`, sorted by name.
+#[must_use]
+pub fn diff_workspace_settings(
+    baseline: &WorkspaceSettings,
+    current: &WorkspaceSettings,
+) -> Vec<SettingChange> {
+    let mut changes = Vec::new();
+    if let (Ok(a), Ok(b)) = (
+        serde_json::to_value(baseline),
+        serde_json::to_value(current),
+    ) {
+        diff_values("", &a, &b, &mut changes);
+    }
+    changes
+}
+
+fn diff_values(prefix: &str, a: &Value, b: &Value, out: &mut Vec<SettingChange>) {
+    if a == b {
+        return;
+    }
+    // Recurse into option groups so the change names the toggle, not the whole group.
+    if let (Value::Object(ma), Value::Object(mb)) = (a, b) {
+        let mut keys: Vec<&String> = ma.keys().collect();
+        keys.extend(mb.keys().filter(|k| !ma.contains_key(*k)));
+        for key in keys {
+            let field = if prefix.is_empty() {
+                key.clone()
+            } else {
+                format!("{prefix}.{key}")
+            };
+            diff_values(
+                &field,
+                ma.get(key).unwrap_or(&Value::Null),
+                mb.get(key).unwrap_or(&Value::Null),
+                out,
+            );
+        }
+        return;
+    }
+    out.push(SettingChange {
+        field: prefix.to_string(),
+        before: display_value(a),
+        after: display_value(b),
+    });
+}
+
+fn display_value(v: &Value) -> String {
+    let s = match v {
+        Value::Null => "(unset)".to_string(),
+        Value::String(s) if s.is_empty() => "(empty)".to_string(),
+        Value::String(s) => format!("\"{s}\""),
+        other => other.to_string(),
+    };
+    if s.chars().count() > MAX_VALUE_CHARS {
+        let cut: String = s.chars().take(MAX_VALUE_CHARS - 1).collect();
+        format!("{cut}…")
+    } else {
+        s
+    }
+}
+
+/// Compare `current` against the saved `baseline`: renamed, changed settings, and which
+/// explicit checks, samples, rules and pins were added or removed (paths sorted).
+#[must_use]
+pub fn diff_profiles(baseline: &Profile, current: &Profile) -> ProfileChanges {
+    let mut changes = ProfileChanges {
+        settings: diff_workspace_settings(&baseline.settings, &current.settings),
+        ..ProfileChanges::default()
+    };
+    if baseline.name != current.name {
+        changes.renamed = Some((baseline.name.clone(), current.name.clone()));
+    }
+
+    let before: BTreeMap<&str, bool> = baseline
+        .explicit
+        .iter()
+        .map(|s| (s.path.as_str(), s.state))
+        .collect();
+    let after: BTreeMap<&str, bool> = current
+        .explicit
+        .iter()
+        .map(|s| (s.path.as_str(), s.state))
+        .collect();
+    for (path, state) in &after {
+        if before.get(path) != Some(state) {
+            let list = if *state {
+                &mut changes.checked
+            } else {
+                &mut changes.unchecked
+            };
+            list.push((*path).to_string());
+        }
+    }
+    changes.cleared = before
+        .keys()
+        .filter(|p| !after.contains_key(*p))
+        .map(|p| (*p).to_string())
+        .collect();
+
+    changes.samples = changed_paths(
+        baseline.samples.iter().map(|s| (s.path.as_str(), s)),
+        current.samples.iter().map(|s| (s.path.as_str(), s)),
+    );
+    changes.dir_rules = changed_paths(
+        baseline.dir_rules.iter().map(|r| (r.path.as_str(), r)),
+        current.dir_rules.iter().map(|r| (r.path.as_str(), r)),
+    );
+
+    let pins_before: BTreeSet<&String> = baseline.pinned.iter().collect();
+    let pins_after: BTreeSet<&String> = current.pinned.iter().collect();
+    changes.pinned = pins_after
+        .difference(&pins_before)
+        .map(|p| (*p).clone())
+        .collect();
+    changes.unpinned = pins_before
+        .difference(&pins_after)
+        .map(|p| (*p).clone())
+        .collect();
+    changes
+}
+
+/// Paths whose entry was added, removed or changed between the two lists.
+fn changed_paths<'a, T: PartialEq + 'a>(
+    before: impl Iterator<Item = (&'a str, &'a T)>,
+    after: impl Iterator<Item = (&'a str, &'a T)>,
+) -> Vec<String> {
+    let before: BTreeMap<&str, &T> = before.collect();
+    let after: BTreeMap<&str, &T> = after.collect();
+    let paths: BTreeSet<&str> = before.keys().chain(after.keys()).copied().collect();
+    paths
+        .into_iter()
+        .filter(|p| before.get(p) != after.get(p))
+        .map(str::to_string)
+        .collect()
+}
@@ -0,0 +1,148 @@
+use std::{collections::HashSet, fmt::Write, path::Path};
+
+use crate::core::{Node, Profile, is_unloaded_dir, path_to_unix};
+
+/* ============================== Stale entries =============================== */
+
+/// Paths listed in the confirmation text before the rest is summed up.
+const MAX_LISTED_PATHS: usize = 20;
+
+/// Profile entries whose path isn't in the current scan, by kind. Paths are
+/// project-relative with forward slashes, in profile order.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StaleEntries {
+    pub explicit: Vec<String>,
+    pub samples: Vec<String>,
+    pub dir_rules: Vec<String>,
+    pub pinned: Vec<String>,
+}
+
+impl StaleEntries {
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.explicit.len() + self.samples.len() + self.dir_rules.len() + self.pinned.len()
+    }
+
+    /// Add the entries of `other` that aren't listed yet.
+    pub fn absorb(&mut self, other: Self) {
+        for (list, more) in [
+            (&mut self.explicit, other.explicit),
+            (&mut self.samples, other.samples),
+            (&mut self.dir_rules, other.dir_rules),
+            (&mut self.pinned, other.pinned),
+        ] {
+            for p in more {
+                if !list.contains(&p) {
+                    list.push(p);
+                }
+            }
+        }
+    }
+
+    /// One section per kind of entry, for the confirmation dialog.
+    #[must_use]
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        for (title, paths) in [
+            ("Selections", &self.explicit),
+            ("Samples", &self.samples),
+            ("Directory rules", &self.dir_rules),
+            ("Pinned files", &self.pinned),
+        ] {
+            if paths.is_empty() {
+                continue;
+            }
+            let _ = writeln!(out, "{title} ({}):", paths.len());
+            for p in paths.iter().take(MAX_LISTED_PATHS) {
+                let _ = writeln!(out, "  {p}");
+            }
+            if paths.len() > MAX_LISTED_PATHS {
+                let _ = writeln!(out, "  … and {} more", paths.len() - MAX_LISTED_PATHS);
+            }
+        }
+        out
+    }
+}
+
+/// Relative paths present in a scanned tree, walking it recursively.
+///
+/// Directories that lazy scanning hasn't loaded yet are reported separately: nothing
+/// can be said about the paths below them.
+#[derive(Debug, Clone, Default)]
+pub struct ScannedPaths {
+    paths: HashSet<String>,
+    unloaded: Vec<String>,
+}
+
+impl ScannedPaths {
+    #[must_use]
+    pub fn from_tree(project_root: &Path, root: &Node) -> Self {
+        fn rec(project_root: &Path, n: &Node, out: &mut ScannedPaths) {
+            let Ok(rel) = n.path.strip_prefix(project_root) else {
+                return;
+            };
+            let rel = path_to_unix(rel);
+            if is_unloaded_dir(n) {
+                out.unloaded.push(rel.clone());
+            }
+            out.paths.insert(rel);
+            for c in &n.children {
+                rec(project_root, c, out);
+            }
+        }
+        let mut out = Self::default();
+        rec(project_root, root, &mut out);
+        out
+    }
+
+    /// Whether `rel` was scanned, or may exist under a directory not loaded yet.
+    /// The project root (`""`) is always present.
+    #[must_use]
+    pub fn contains(&self, rel: &str) -> bool {
+        rel.is_empty()
+            || self.paths.contains(rel)
+            || self
+                .unloaded
+                .iter()
+                .any(|dir| dir.is_empty() || is_below(rel, dir))
+    }
+}
+
+fn is_below(rel: &str, dir: &str) -> bool {
+    rel.strip_prefix(dir)
+        .is_some_and(|rest| rest.starts_with('/'))
+}
+
+/// Entries of `profile` whose path isn't in `scanned`.
+#[must_use]
+pub fn find_stale_entries(profile: &Profile, scanned: &ScannedPaths) -> StaleEntries {
+    let stale = |paths: Vec<&String>| -> Vec<String> {
+        paths
+            .into_iter()
+            .filter(|p| !scanned.contains(p))
+            .cloned()
+            .collect()
+    };
+    StaleEntries {
+        explicit: stale(profile.explicit.iter().map(|s| &s.path).collect()),
+        samples: stale(profile.samples.iter().map(|s| &s.path).collect()),
+        dir_rules: stale(profile.dir_rules.iter().map(|r| &r.path).collect()),
+        pinned: stale(profile.pinned.iter().collect()),
+    }
+}
+
+/// Drop the entries listed in `stale` from `profile`.
+pub fn remove_stale_entries(profile: &mut Profile, stale: &StaleEntries) {
+    let keep = |list: &[String], path: &str| !list.iter().any(|p| p == path);
+    profile.explicit.retain(|s| keep(&stale.explicit, &s.path));
+    profile.samples.retain(|s| keep(&stale.samples, &s.path));
+    profile
+        .dir_rules
+        .retain(|r| keep(&stale.dir_rules, &r.path));
+    profile.pinned.retain(|p| keep(&stale.pinned, p));
+}
@@ -0,0 +1,162 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::core::Node;
+
+/// Per-term (content occurrences, appears in path) for one file.
+type TermHits<'a> = HashMap<&'a str, (usize, bool)>;
+
+/* ============================ Relevance ranking ============================= */
+
+/// Words too common to carry any signal about which files matter.
+const STOPWORDS: &[&str] = &[
+    "a", "an", "and", "are", "as", "at", "be", "by", "can", "do", "does", "for", "from", "how",
+    "i", "if", "in", "is", "it", "of", "on", "or", "the", "this", "to", "what", "when", "where",
+    "which", "why", "with",
+];
+
+/// Extra weight given to a query term appearing in the file's relative path.
+const PATH_MATCH_WEIGHT: f64 = 3.0;
+
+/// Files bigger than this (usually generated or vendored) are left out of the ranking.
+pub const RANK_MAX_FILE_BYTES: u64 = 1024 * 1024;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RankedFile {
+    pub path: PathBuf,
+    pub score: f64,
+    /// Rough token estimate (bytes / 4) used for budget selection.
+    pub est_tokens: usize,
+}
+
+/// Split a free-text query into lowercase search terms, dropping stopwords and duplicates.
+#[must_use]
+pub fn query_terms(query: &str) -> Vec<String> {
+    let mut seen = HashSet::new();
+    query
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .map(str::to_lowercase)
+        .filter(|t| t.chars().count() >= 2 && !STOPWORDS.contains(&t.as_str()))
+        .filter(|t| seen.insert(t.clone()))
+        .collect()
+}
+
+/// Collect every file path in a scanned tree (the candidates for ranking).
+#[must_use]
+pub fn collect_file_paths(root: &Node) -> Vec<PathBuf> {
+    fn rec(n: &Node, out: &mut Vec<PathBuf>) {
+        if n.is_dir {
+            for c in &n.children {
+                rec(c, out);
+            }
+        } else {
+            out.push(n.path.clone());
+        }
+    }
+    let mut out = Vec::new();
+    rec(root, &mut out);
+    out
+}
+
+/// Score files by term overlap with `query`.
+///
+/// Uses TF-IDF over the candidate set, with a bonus for matches in the relative path.
+/// Unreadable files, files over [`RANK_MAX_FILE_BYTES`] and files scoring zero are
+/// dropped; the result is sorted by descending score, then by path.
+#[must_use]
+pub fn rank_files_by_query(project_root: &Path, query: &str, files: &[PathBuf]) -> Vec<RankedFile> {
+    let terms = query_terms(query);
+    if terms.is_empty() {
+        return Vec::new();
+    }
+
+    // Per-file term counts (content + path) and document frequencies.
+    let mut per_file: Vec<(PathBuf, TermHits, usize)> = Vec::new();
+    let mut doc_freq: HashMap<&str, usize> = HashMap::new();
+
+    for fp in files {
+        if fs::metadata(fp).map_or(true, |meta| meta.len() > RANK_MAX_FILE_BYTES) {
+            continue;
+        }
+        let Ok(contents) = fs::read_to_string(fp) else {
+            continue;
+        };
+        let lower = contents.to_lowercase();
+        let rel = fp
+            .strip_prefix(project_root)
+            .unwrap_or(fp)
+            .to_string_lossy()
+            .to_lowercase();
+
+        let mut counts = TermHits::new();
+        for term in &terms {
+            let in_content = lower.matches(term.as_str()).count();
+            let in_path = rel.contains(term.as_str());
+            if in_content > 0 || in_path {
+                counts.insert(term.as_str(), (in_content, in_path));
+                *doc_freq.entry(term.as_str()).or_default() += 1;
+            }
+        }
+        per_file.push((fp.clone(), counts, contents.len().div_ceil(4)));
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    let n_docs = per_file.len() as f64;
+    let mut ranked: Vec<RankedFile> = per_file
+        .into_iter()
+        .filter_map(|(path, counts, est_tokens)| {
+            let score: f64 = counts
+                .iter()
+                .map(|(term, &(in_content, in_path))| {
+                    #[allow(clippy::cast_precision_loss)]
+                    let df = doc_freq.get(term).copied().unwrap_or(1) as f64;
+                    let idf = (n_docs / df).ln_1p();
+                    #[allow(clippy::cast_precision_loss)]
+                    let tf = (in_content as f64).ln_1p();
+                    let path_bonus = if in_path { PATH_MATCH_WEIGHT } else { 0.0 };
+                    (tf + path_bonus) * idf
+                })
+                .sum();
+            (score > 0.0).then_some(RankedFile {
+                path,
+                score,
+                est_tokens,
+            })
+        })
+        .collect();
+
+    ranked.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then_with(|| a.path.cmp(&b.path))
+    });
+    ranked
+}
+
+/// Greedily pick up to `top_k` files in rank order whose estimated tokens fit `token_budget`.
+///
+/// Files that would overflow the budget are skipped so smaller, lower-ranked files can still fit.
+/// A budget of zero means "no budget".
+#[must_use]
+pub fn select_top_within_budget(
+    ranked: &[RankedFile],
+    top_k: usize,
+    token_budget: usize,
+) -> Vec<PathBuf> {
+    let mut picked = Vec::new();
+    let mut used = 0usize;
+    for rf in ranked {
+        if picked.len() >= top_k {
+            break;
+        }
+        if token_budget > 0 && used + rf.est_tokens > token_budget {
+            continue;
+        }
+        used += rf.est_tokens;
+        picked.push(rf.path.clone());
+    }
+    picked
+}
@@ -0,0 +1,148 @@
+use std::path::{Path, PathBuf};
+
+use notify::{
+    Event, EventKind,
+    event::{ModifyKind, RenameMode},
+};
+
+use crate::core::{Profile, path_to_unix};
+
+/* ============================== Rename pairing ============================== */
+
+/// Unpaired "renamed from" halves kept while waiting for their other half. Files moved
+/// out of the watched tree never get one, so old ones are dropped.
+const MAX_PENDING_RENAMES: usize = 32;
+
+/// A file or directory moved from `from` to `to` (absolute paths, as the watcher reports
+/// them).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathRename {
+    pub from: PathBuf,
+    pub to: PathBuf,
+}
+
+impl PathRename {
+    /// Both paths relative to `project_root` with forward slashes, or `None` when either
+    /// side is outside the project.
+    #[must_use]
+    pub fn relative_to(&self, project_root: &Path) -> Option<(String, String)> {
+        let from = self.from.strip_prefix(project_root).ok()?;
+        let to = self.to.strip_prefix(project_root).ok()?;
+        if from.as_os_str().is_empty() || to.as_os_str().is_empty() {
+            return None;
+        }
+        Some((path_to_unix(from), path_to_unix(to)))
+    }
+}
+
+/// Pairs watcher events into renames.
+///
+/// Backends report a rename differently: one event carrying both paths, a "from" and a
+/// "to" event sharing a tracker id (inotify, which then also sends the combined event),
+/// a "from" followed by a "to" without an id (Windows), or one "renamed" event per path
+/// (`FSEvents`), where the side that no longer exists is the old name.
+#[derive(Debug, Default)]
+pub struct RenameTracker {
+    pending: Vec<(Option<usize>, PathBuf)>,
+    last: Option<PathRename>,
+}
+
+impl RenameTracker {
+    /// Feed one watcher event; returns the rename it completes, if any.
+    pub fn observe(&mut self, event: &Event) -> Option<PathRename> {
+        let EventKind::Modify(ModifyKind::Name(mode)) = event.kind else {
+            return None;
+        };
+        let tracker = event.attrs.tracker();
+        let rename = match (mode, event.paths.as_slice()) {
+            (RenameMode::Both, [from, to]) => Some(PathRename {
+                from: from.clone(),
+                to: to.clone(),
+            }),
+            (RenameMode::From, [from]) => {
+                self.push_pending(tracker, from.clone());
+                None
+            }
+            (RenameMode::To, [to]) => self.complete(tracker, to),
+            (RenameMode::Any | RenameMode::Other, [path]) if path.exists() => {
+                self.complete(tracker, path)
+            }
+            (RenameMode::Any | RenameMode::Other, [path]) => {
+                self.push_pending(tracker, path.clone());
+                None
+            }
+            _ => None,
+        }?;
+        if rename.from == rename.to || self.last.as_ref() == Some(&rename) {
+            return None;
+        }
+        self.last = Some(rename.clone());
+        Some(rename)
+    }
+
+    fn push_pending(&mut self, tracker: Option<usize>, path: PathBuf) {
+        if self.pending.len() == MAX_PENDING_RENAMES {
+            self.pending.remove(0);
+        }
+        self.pending.push((tracker, path));
+    }
+
+    /// Pair `to` with the newest pending "from" that has the same tracker id.
+    fn complete(&mut self, tracker: Option<usize>, to: &Path) -> Option<PathRename> {
+        let idx = self.pending.iter().rposition(|(t, _)| *t == tracker)?;
+        let (_, from) = self.pending.remove(idx);
+        Some(PathRename {
+            from,
+            to: to.to_path_buf(),
+        })
+    }
+}
+
+/* ============================= Profile remapping ============================ */
+
+/// `path` after `from` was renamed to `to`: the path itself or anything below it.
+/// `None` when the rename doesn't touch it. Paths are project-relative, forward slashes.
+#[must_use]
+pub fn renamed_path(path: &str, from: &str, to: &str) -> Option<String> {
+    if path == from {
+        return Some(to.to_string());
+    }
+    let rest = path.strip_prefix(from)?.strip_prefix('/')?;
+    Some(format!("{to}/{rest}"))
+}
+
+/// Point the entries of `profile` that refer to `from` (or anything below it) at `to`.
+///
+/// That covers selections, samples, directory rules and pins. Entries already at `to`
+/// are replaced, as the rename replaced the file. Returns how many entries changed.
+pub fn remap_profile_paths(profile: &mut Profile, from: &str, to: &str) -> usize {
+    remap_paths(&mut profile.explicit, |s| &mut s.path, from, to)
+        + remap_paths(&mut profile.samples, |s| &mut s.path, from, to)
+        + remap_paths(&mut profile.dir_rules, |r| &mut r.path, from, to)
+        + remap_paths(&mut profile.pinned, |p| p, from, to)
+}
+
+fn remap_paths<T>(
+    items: &mut Vec<T>,
+    path: impl Fn(&mut T) -> &mut String,
+    from: &str,
+    to: &str,
+) -> usize {
+    let touched = |p: &str| renamed_path(p, from, to).is_some();
+    if !items.iter_mut().any(|item| touched(path(item))) {
+        return 0;
+    }
+    items.retain_mut(|item| {
+        let p = path(item);
+        touched(p) || renamed_path(p, to, to).is_none()
+    });
+    let mut changed = 0;
+    for item in items.iter_mut() {
+        let p = path(item);
+        if let Some(new) = renamed_path(p, from, to) {
+            *p = new;
+            changed += 1;
+        }
+    }
+    changed
+}
@@ -0,0 +1,103 @@
+use std::fmt::Write;
+
+use serde_json::Value;
+
+/* ========================= CSV/TSV and notebook views ======================= */
+
+/// Data rows kept by [`table_head`] when the step doesn't set `rows`.
+pub const DEFAULT_TABLE_ROWS: usize = 20;
+
+/// The header and first `rows` data rows of a CSV/TSV file, followed by a note with the
+/// number of rows left out. Tables that fit are returned unchanged.
+///
+/// Records are split on newlines outside double quotes, so quoted multi-line fields
+/// count as one row.
+#[must_use]
+pub fn table_head(text: &str, rows: usize) -> String {
+    let records = split_records(text);
+    // The first record is the header.
+    let data_rows = records.len().saturating_sub(1);
+    if data_rows <= rows {
+        return text.to_string();
+    }
+    let mut out: String = records[..=rows].concat();
+    if !out.ends_with('\n') {
+        out.push('\n');
+    }
+    let omitted = data_rows - rows;
+    let noun = if omitted == 1 { "row" } else { "rows" };
+    let _ = writeln!(
+        out,
+        "[... {omitted} more {noun} omitted; {data_rows} data rows in total]"
+    );
+    out
+}
+
+/// Split `text` into records (each with its line ending), ignoring newlines inside quotes.
+fn split_records(text: &str) -> Vec<&str> {
+    let mut records = Vec::new();
+    let mut start = 0;
+    let mut in_quotes = false;
+    for (i, c) in text.char_indices() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            '\n' if !in_quotes => {
+                records.push(&text[start..=i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    if start < text.len() {
+        records.push(&text[start..]);
+    }
+    records
+}
+
+/// The cells of a Jupyter notebook as a `# %%` script (the "percent" format editors and
+/// jupytext understand), dropping outputs and metadata.
+///
+/// Code cells are emitted as is; markdown cells are left out unless `markdown` is set,
+/// and then emitted as `# `-prefixed comments under `# %% [markdown]`. `None` when
+/// `text` isn't notebook JSON.
+#[must_use]
+pub fn notebook_cells(text: &str, markdown: bool) -> Option<String> {
+    let notebook: Value = serde_json::from_str(text).ok()?;
+    let cells = notebook.get("cells")?.as_array()?;
+
+    let mut out = String::new();
+    for cell in cells {
+        let source = cell_source(cell.get("source"));
+        match cell.get("cell_type").and_then(Value::as_str) {
+            Some("code") => {
+                out.push_str("# %%\n");
+                out.push_str(source.trim_end_matches('\n'));
+                out.push_str("\n\n");
+            }
+            Some("markdown") if markdown => {
+                out.push_str("# %% [markdown]\n");
+                for line in source.trim_end_matches('\n').lines() {
+                    if line.is_empty() {
+                        out.push_str("#\n");
+                    } else {
+                        out.push_str("# ");
+                        out.push_str(line);
+                        out.push('\n');
+                    }
+                }
+                out.push('\n');
+            }
+            _ => {}
+        }
+    }
+    Some(out)
+}
+
+/// A cell's `source`: nbformat allows a string or a list of line strings.
+fn cell_source(source: Option<&Value>) -> String {
+    match source {
+        Some(Value::String(s)) => s.clone(),
+        Some(Value::Array(lines)) => lines.iter().filter_map(Value::as_str).collect(),
+        _ => String::new(),
+    }
+}
@@ -1,7 +1,10 @@
+use serde::Serialize;
+
 // `syn` no longer used in this module
 
 // Helpers for scanning string literals in a byte buffer
-fn scan_string_literal(bytes: &[u8], len_bytes: usize, mut cursor: usize, quote: u8) -> usize {
+#[must_use]
+pub fn scan_string_literal(bytes: &[u8], len_bytes: usize, mut cursor: usize, quote: u8) -> usize {
     let mut is_escaped = false;
     cursor += 1;
     while cursor < len_bytes {
@@ -45,7 +48,7 @@ fn scan_raw_string_literal(bytes: &[u8], len_bytes: usize, mut cursor: usize) ->
 }
 
 // Helper shared by comment stripping logic
-fn trim_trailing_ws_current_line(buf: &mut String) {
+pub fn trim_trailing_ws_current_line(buf: &mut String) {
     let mut idx = buf.len();
     while idx > 0 {
         let b = buf.as_bytes()[idx - 1];
@@ -64,7 +67,7 @@ fn trim_trailing_ws_current_line(buf: &mut String) {
 }
 // no token printing — we preserve original formatting; only function bodies are replaced
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize)]
 pub struct RustFilterOptions {
     pub remove_inline_regular_comments: bool,
     pub remove_doc_comments: bool,
@@ -269,7 +272,8 @@ fn transform_functions_to_signatures(src: &str) -> String {
     SignatureReducer::new(src).run()
 }
 
-fn skip_line_comment(bytes: &[u8], len: usize, mut idx: usize) -> usize {
+#[must_use]
+pub fn skip_line_comment(bytes: &[u8], len: usize, mut idx: usize) -> usize {
     while idx < len && bytes[idx] != b'\n' {
         idx += 1;
     }
@@ -399,7 +403,8 @@ fn skip_function_body(bytes: &[u8], len: usize, mut idx: usize) -> usize {
     idx
 }
 
-const fn is_ident_byte(b: u8) -> bool {
+#[must_use]
+pub const fn is_ident_byte(b: u8) -> bool {
     b == b'_' || (b as char).is_ascii_alphanumeric()
 }
 
@@ -462,7 +467,8 @@ pub fn signatures_filter_matches(rel_path: &str, filter: &str) -> bool {
     false
 }
 
-fn wildcard_match(pat: &str, text: &str) -> bool {
+#[must_use]
+pub fn wildcard_match(pat: &str, text: &str) -> bool {
     // Simple '*' wildcard matcher. Case-sensitive; '*' matches any sequence including '/'.
     let (pbytes, tbytes) = (pat.as_bytes(), text.as_bytes());
     let (mut pi, mut ti) = (0usize, 0usize);
@@ -495,7 +501,8 @@ fn wildcard_match(pat: &str, text: &str) -> bool {
     pi == pbytes.len()
 }
 
-fn trim_leading_blank_lines(s: &str) -> String {
+#[must_use]
+pub fn trim_leading_blank_lines(s: &str) -> String {
     let mut start = 0usize;
     let bytes = s.as_bytes();
     let n = bytes.len();
@@ -0,0 +1,132 @@
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::core::{RustFilterOptions, apply_rust_filters};
+
+/* ============================ Rust module order ============================= */
+
+/// Names of the out-of-line modules (`mod foo;`) declared at the top level of `source`,
+/// in declaration order. Inline modules (`mod foo { ... }`) and comments are skipped.
+#[must_use]
+pub fn parse_mod_declarations(source: &str) -> Vec<String> {
+    let opts = RustFilterOptions {
+        remove_inline_regular_comments: true,
+        remove_doc_comments: true,
+        function_signatures_only: false,
+    };
+    let code = apply_rust_filters(source, &opts);
+
+    let mut mods = Vec::new();
+    let mut depth = 0usize;
+    for line in code.lines() {
+        if depth == 0
+            && let Some(name) = mod_declaration(line)
+        {
+            mods.push(name.to_string());
+        }
+        for c in line.chars() {
+            match c {
+                '{' => depth += 1,
+                '}' => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+        }
+    }
+    mods
+}
+
+/// `foo` from `#[cfg(x)] pub(crate) mod foo;`.
+fn mod_declaration(line: &str) -> Option<&str> {
+    let mut rest = line.trim();
+    // Attributes on the same line.
+    while let Some(attr) = rest.strip_prefix("#[") {
+        rest = attr.split_once(']')?.1.trim_start();
+    }
+    if let Some(vis) = rest.strip_prefix("pub") {
+        rest = vis.trim_start();
+        if let Some(scoped) = rest.strip_prefix('(') {
+            rest = scoped.split_once(')')?.1;
+        }
+        rest = rest.trim_start();
+    }
+    let rest = rest.strip_prefix("mod")?;
+    if !rest.starts_with(char::is_whitespace) {
+        return None;
+    }
+    let name = rest.trim().strip_suffix(';')?.trim_end();
+    let name = name.strip_prefix("r#").unwrap_or(name);
+    (!name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_')).then_some(name)
+}
+
+/// Reorder `files` so each Rust crate reads top-down.
+///
+/// The crate root (`lib.rs` before `main.rs`) comes first, then its modules depth-first
+/// in declaration order. Files that aren't reached from a crate root keep their relative
+/// order after the crates.
+///
+/// Module files are read from disk, so modules that aren't in `files` still lead to
+/// their selected submodules.
+#[must_use]
+pub fn rust_module_order(files: &[PathBuf]) -> Vec<PathBuf> {
+    let is_root = |p: &Path| {
+        p.file_name()
+            .is_some_and(|n| n == "lib.rs" || n == "main.rs")
+    };
+    let selected: HashSet<&Path> = files.iter().map(PathBuf::as_path).collect();
+    let mut roots: Vec<&PathBuf> = files.iter().filter(|p| is_root(p)).collect();
+    // Crates in selection order; lib.rs before main.rs within a crate.
+    roots.sort_by_cached_key(|p| {
+        let crate_pos = files
+            .iter()
+            .position(|f| is_root(f) && f.parent() == p.parent());
+        (crate_pos, p.ends_with("main.rs"))
+    });
+
+    let mut visited: HashSet<PathBuf> = HashSet::new();
+    let mut ordered = Vec::with_capacity(files.len());
+    for root in roots {
+        visit_module(root, &selected, &mut visited, &mut ordered);
+    }
+    let emitted: HashSet<PathBuf> = ordered.iter().cloned().collect();
+    ordered.extend(files.iter().filter(|p| !emitted.contains(*p)).cloned());
+    ordered
+}
+
+fn visit_module(
+    file: &Path,
+    selected: &HashSet<&Path>,
+    visited: &mut HashSet<PathBuf>,
+    ordered: &mut Vec<PathBuf>,
+) {
+    if !visited.insert(file.to_path_buf()) {
+        return;
+    }
+    if selected.contains(file) {
+        ordered.push(file.to_path_buf());
+    }
+    let Ok(source) = fs::read_to_string(file) else {
+        return;
+    };
+    let dir = module_dir(file);
+    for name in parse_mod_declarations(&source) {
+        let flat = dir.join(format!("{name}.rs"));
+        let nested = dir.join(&name).join("mod.rs");
+        if flat.is_file() {
+            visit_module(&flat, selected, visited, ordered);
+        } else if nested.is_file() {
+            visit_module(&nested, selected, visited, ordered);
+        }
+    }
+}
+
+/// Directory holding the submodules declared in `file`.
+fn module_dir(file: &Path) -> PathBuf {
+    let parent = file.parent().unwrap_or_else(|| Path::new(""));
+    match file.file_name().and_then(|n| n.to_str()) {
+        Some("lib.rs" | "main.rs" | "mod.rs") => parent.to_path_buf(),
+        _ => parent.join(file.file_stem().unwrap_or_default()),
+    }
+}
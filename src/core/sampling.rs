@@ -0,0 +1,137 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::path_to_unix;
+
+/* ============================ Directory sampling ============================ */
+
+/// Which files a sampled directory keeps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SampleOrder {
+    /// The first N files by relative path.
+    #[default]
+    First,
+    /// The N most recently modified files.
+    MostRecent,
+}
+
+/// Per-directory rule: emit only `count` files below `path`, omit the rest.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct DirectorySample {
+    /// Project-relative directory path using forward slashes ("" = project root).
+    pub path: String,
+    pub count: usize,
+    #[serde(default)]
+    pub order: SampleOrder,
+}
+
+impl DirectorySample {
+    /// Short label used for tree badges and notes, e.g. "first 5" or "5 most recent".
+    #[must_use]
+    pub fn describe(&self) -> String {
+        match self.order {
+            SampleOrder::First => format!("first {}", self.count),
+            SampleOrder::MostRecent => format!("{} most recent", self.count),
+        }
+    }
+
+    fn covers(&self, rel_file: &str) -> bool {
+        self.path.is_empty()
+            || rel_file
+                .strip_prefix(self.path.as_str())
+                .is_some_and(|rest| rest.starts_with('/'))
+    }
+}
+
+/// A sampled directory that had more matching files than its rule keeps.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SampleOmission {
+    pub path: String,
+    pub kept: usize,
+    pub total: usize,
+}
+
+impl SampleOmission {
+    #[must_use]
+    pub const fn omitted(&self) -> usize {
+        self.total.saturating_sub(self.kept)
+    }
+}
+
+/// Apply directory sampling rules to a list of selected files.
+///
+/// Each file is governed by the deepest rule whose directory contains it. Kept files
+/// preserve their input order; omissions are reported sorted by directory path.
+#[must_use]
+pub fn apply_directory_samples(
+    project_root: &Path,
+    files: &[PathBuf],
+    samples: &[DirectorySample],
+) -> (Vec<PathBuf>, Vec<SampleOmission>) {
+    if samples.is_empty() {
+        return (files.to_vec(), Vec::new());
+    }
+
+    let mut groups: HashMap<usize, Vec<(usize, String)>> = HashMap::new();
+    for (idx, fp) in files.iter().enumerate() {
+        let Ok(rel) = fp.strip_prefix(project_root) else {
+            continue;
+        };
+        let rel = path_to_unix(rel);
+        let governing = samples
+            .iter()
+            .enumerate()
+            .filter(|(_, s)| s.covers(&rel))
+            .max_by_key(|(_, s)| s.path.len())
+            .map(|(i, _)| i);
+        if let Some(rule_idx) = governing {
+            groups.entry(rule_idx).or_default().push((idx, rel));
+        }
+    }
+
+    let mut dropped: HashSet<usize> = HashSet::new();
+    let mut omissions = Vec::new();
+    for (rule_idx, mut members) in groups {
+        let rule = &samples[rule_idx];
+        let total = members.len();
+        if total <= rule.count {
+            continue;
+        }
+        match rule.order {
+            SampleOrder::First => members.sort_by(|a, b| a.1.cmp(&b.1)),
+            SampleOrder::MostRecent => {
+                let mtime = |i: usize| -> Option<SystemTime> {
+                    fs::metadata(&files[i]).and_then(|m| m.modified()).ok()
+                };
+                let mut keyed: Vec<(Option<SystemTime>, usize, String)> = members
+                    .into_iter()
+                    .map(|(i, rel)| (mtime(i), i, rel))
+                    .collect();
+                keyed.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.2.cmp(&b.2)));
+                members = keyed.into_iter().map(|(_, i, rel)| (i, rel)).collect();
+            }
+        }
+        dropped.extend(members[rule.count..].iter().map(|(i, _)| *i));
+        omissions.push(SampleOmission {
+            path: rule.path.clone(),
+            kept: rule.count,
+            total,
+        });
+    }
+    omissions.sort_by(|a, b| a.path.cmp(&b.path));
+
+    let kept = files
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| !dropped.contains(i))
+        .map(|(_, p)| p.clone())
+        .collect();
+    (kept, omissions)
+}
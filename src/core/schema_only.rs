@@ -0,0 +1,397 @@
+use std::fmt::{self, Write};
+
+use serde::de::{Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+
+/// Keys shown per object before the rest are counted.
+pub const SCHEMA_MAX_KEYS: usize = 50;
+/// Characters of a string value kept as its sample.
+pub const SCHEMA_SAMPLE_CHARS: usize = 40;
+
+/// Key structure of a JSON or YAML file, or `None` for other files and JSON that doesn't parse.
+///
+/// Every key is listed with its value's type and a truncated sample; arrays show their
+/// length and first item (with the keys of every item, for objects).
+#[must_use]
+pub fn schema_only(path: &str, contents: &str) -> Option<String> {
+    let name = path.rsplit('/').next().unwrap_or(path);
+    let ext = name.rsplit_once('.')?.1.to_ascii_lowercase();
+    let documents = match ext.as_str() {
+        "json" => vec![serde_json::from_str::<Shape>(contents).ok()?],
+        "yaml" | "yml" => parse_yaml(contents),
+        _ => return None,
+    };
+    let mut out = String::new();
+    for (i, doc) in documents.iter().enumerate() {
+        if i > 0 {
+            out.push_str("---\n");
+        }
+        render(&mut out, 0, "", doc);
+    }
+    Some(out)
+}
+
+/// A parsed value, with object keys in file order.
+#[derive(Debug, Clone, PartialEq)]
+enum Shape {
+    Null,
+    Bool(bool),
+    Number(String),
+    String(String),
+    Array(Vec<Self>),
+    Object(Vec<(String, Self)>),
+    /// A YAML flow collection (`[a, b]`, `{a: 1}`) kept as text.
+    Inline(&'static str, String),
+}
+
+impl<'de> Deserialize<'de> for Shape {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(ShapeVisitor)
+    }
+}
+
+struct ShapeVisitor;
+
+impl<'de> Visitor<'de> for ShapeVisitor {
+    type Value = Shape;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a JSON value")
+    }
+
+    fn visit_unit<E>(self) -> Result<Shape, E> {
+        Ok(Shape::Null)
+    }
+
+    fn visit_bool<E>(self, v: bool) -> Result<Shape, E> {
+        Ok(Shape::Bool(v))
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Shape, E> {
+        Ok(Shape::Number(v.to_string()))
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Shape, E> {
+        Ok(Shape::Number(v.to_string()))
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<Shape, E> {
+        Ok(Shape::Number(v.to_string()))
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Shape, E> {
+        Ok(Shape::String(v.to_string()))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Shape, A::Error> {
+        let mut items = Vec::new();
+        while let Some(item) = seq.next_element()? {
+            items.push(item);
+        }
+        Ok(Shape::Array(items))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Shape, A::Error> {
+        let mut entries = Vec::new();
+        while let Some(entry) = map.next_entry::<String, Shape>()? {
+            entries.push(entry);
+        }
+        Ok(Shape::Object(entries))
+    }
+}
+
+fn render(out: &mut String, pad: usize, lead: &str, shape: &Shape) {
+    let line = |out: &mut String, text: &str| {
+        out.push_str(&" ".repeat(pad));
+        out.push_str(lead);
+        if !lead.is_empty() && !text.is_empty() {
+            out.push(' ');
+        }
+        out.push_str(text);
+        out.push('\n');
+    };
+    match shape {
+        Shape::Object(entries) if entries.is_empty() => line(out, "object  # empty"),
+        Shape::Object(entries) => {
+            let child_pad = if lead.is_empty() {
+                pad
+            } else {
+                line(out, "");
+                pad + 2
+            };
+            for (key, value) in entries.iter().take(SCHEMA_MAX_KEYS) {
+                render(out, child_pad, &format!("{key}:"), value);
+            }
+            if entries.len() > SCHEMA_MAX_KEYS {
+                out.push_str(&" ".repeat(child_pad));
+                let _ = writeln!(out, "… {} more keys", entries.len() - SCHEMA_MAX_KEYS);
+            }
+        }
+        Shape::Array(items) => {
+            let noun = if items.len() == 1 { "item" } else { "items" };
+            line(out, &format!("array  # {} {noun}", items.len()));
+            if let Some(first) = items.first() {
+                let item = items[1..].iter().fold(first.clone(), merge_shapes);
+                render(out, pad + 2, "-", &item);
+            }
+        }
+        Shape::Null => line(out, "null"),
+        Shape::Bool(v) => line(out, &format!("bool  # {v}")),
+        Shape::Number(v) => line(out, &format!("number  # {v}")),
+        Shape::String(v) => line(out, &format!("string  # \"{}\"", sample(v))),
+        Shape::Inline(kind, text) => line(out, &format!("{kind}  # {}", sample(text))),
+    }
+}
+
+/// `shape` with the keys of `other` it lacks added, so the items of an array show every
+/// key any of them has; for keys both have, nested objects are merged the same way and
+/// other values keep `shape`'s.
+fn merge_shapes(shape: Shape, other: &Shape) -> Shape {
+    match (shape, other) {
+        (Shape::Object(mut entries), Shape::Object(more)) => {
+            for (key, value) in more {
+                match entries.iter_mut().find(|(k, _)| k == key) {
+                    Some((_, existing)) => {
+                        *existing = merge_shapes(std::mem::replace(existing, Shape::Null), value);
+                    }
+                    None => entries.push((key.clone(), value.clone())),
+                }
+            }
+            Shape::Object(entries)
+        }
+        (shape, _) => shape,
+    }
+}
+
+/// First line of `text`, cut to [`SCHEMA_SAMPLE_CHARS`] characters.
+fn sample(text: &str) -> String {
+    let first = text.lines().next().unwrap_or("");
+    let mut out: String = first.chars().take(SCHEMA_SAMPLE_CHARS).collect();
+    if out.len() < text.len() {
+        out.push('…');
+    }
+    out
+}
+
+/* ------------------------------- YAML subset ------------------------------- */
+
+#[derive(Debug, Clone, Copy)]
+struct YamlLine<'a> {
+    indent: usize,
+    text: &'a str,
+}
+
+/// Parse the block-style YAML most configuration uses: mappings, sequences, block and
+/// flow scalars; flow collections are kept as text. Anything else is read leniently.
+fn parse_yaml(contents: &str) -> Vec<Shape> {
+    let mut documents: Vec<Vec<YamlLine>> = vec![Vec::new()];
+    for raw in contents.lines() {
+        let trimmed = raw.trim_end();
+        if trimmed == "---" || trimmed.starts_with("--- ") || trimmed == "..." {
+            if !documents.last().is_some_and(Vec::is_empty) {
+                documents.push(Vec::new());
+            }
+            continue;
+        }
+        let text = strip_yaml_comment(trimmed.trim_start());
+        if text.is_empty() || text.starts_with('%') {
+            continue;
+        }
+        let indent = trimmed.len() - trimmed.trim_start().len();
+        if let Some(doc) = documents.last_mut() {
+            doc.push(YamlLine { indent, text });
+        }
+    }
+    documents
+        .into_iter()
+        .filter(|doc| !doc.is_empty())
+        .map(|lines| {
+            let mut parser = YamlParser { lines, pos: 0 };
+            parser.parse_node()
+        })
+        .collect()
+}
+
+/// `text` without a trailing ` # comment` outside quotes.
+fn strip_yaml_comment(text: &str) -> &str {
+    if text.starts_with('#') {
+        return "";
+    }
+    let mut quote: Option<char> = None;
+    let mut prev = ' ';
+    for (i, c) in text.char_indices() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            // Quotes open a string only at the start of a value, not in `don't`.
+            None if (c == '"' || c == '\'') && (prev.is_whitespace() || "[{,:".contains(prev)) => {
+                quote = Some(c);
+            }
+            None if c == '#' && prev.is_whitespace() => return text[..i].trim_end(),
+            _ => {}
+        }
+        prev = c;
+    }
+    text
+}
+
+struct YamlParser<'a> {
+    lines: Vec<YamlLine<'a>>,
+    pos: usize,
+}
+
+impl YamlParser<'_> {
+    fn parse_node(&mut self) -> Shape {
+        let Some(line) = self.lines.get(self.pos).copied() else {
+            return Shape::Null;
+        };
+        if is_sequence_item(line.text) {
+            self.parse_sequence(line.indent)
+        } else if split_key(line.text).is_some() {
+            self.parse_mapping(line.indent)
+        } else {
+            self.pos += 1;
+            if is_block_scalar(line.text) {
+                Shape::String(self.take_block(line.indent))
+            } else {
+                scalar(line.text)
+            }
+        }
+    }
+
+    fn parse_sequence(&mut self, indent: usize) -> Shape {
+        let mut items = Vec::new();
+        while let Some(line) = self.lines.get(self.pos).copied() {
+            if line.indent < indent || (line.indent == indent && !is_sequence_item(line.text)) {
+                break;
+            }
+            if line.indent > indent {
+                // Continuation of a plain multi-line scalar.
+                self.pos += 1;
+                continue;
+            }
+            let rest = line.text[1..].trim_start();
+            if rest.is_empty() {
+                self.pos += 1;
+                items.push(self.nested_value(indent));
+            } else if is_block_scalar(rest) {
+                self.pos += 1;
+                items.push(Shape::String(self.take_block(indent)));
+            } else {
+                // Parse `- key: value` / `- item` as a node starting at the item's column.
+                self.lines[self.pos] = YamlLine {
+                    indent: indent + (line.text.len() - rest.len()),
+                    text: rest,
+                };
+                items.push(self.parse_node());
+            }
+        }
+        Shape::Array(items)
+    }
+
+    fn parse_mapping(&mut self, indent: usize) -> Shape {
+        let mut entries = Vec::new();
+        while let Some(line) = self.lines.get(self.pos).copied() {
+            if line.indent < indent || (line.indent == indent && is_sequence_item(line.text)) {
+                break;
+            }
+            self.pos += 1;
+            let Some((key, value)) = split_key(line.text).filter(|_| line.indent == indent) else {
+                continue;
+            };
+            let shape = if value.is_empty() {
+                self.nested_value(indent)
+            } else if is_block_scalar(value) {
+                Shape::String(self.take_block(indent))
+            } else {
+                scalar(value)
+            };
+            entries.push((key, shape));
+        }
+        Shape::Object(entries)
+    }
+
+    /// The block under a key or `-` at `indent`; a sequence may sit at the same column.
+    fn nested_value(&mut self, indent: usize) -> Shape {
+        match self.lines.get(self.pos) {
+            Some(next)
+                if next.indent > indent
+                    || (next.indent == indent && is_sequence_item(next.text)) =>
+            {
+                self.parse_node()
+            }
+            _ => Shape::Null,
+        }
+    }
+
+    fn take_block(&mut self, indent: usize) -> String {
+        let mut text = Vec::new();
+        while let Some(line) = self.lines.get(self.pos).filter(|l| l.indent > indent) {
+            text.push(line.text);
+            self.pos += 1;
+        }
+        text.join("\n")
+    }
+}
+
+fn is_sequence_item(text: &str) -> bool {
+    text == "-" || text.starts_with("- ")
+}
+
+fn is_block_scalar(value: &str) -> bool {
+    value.starts_with('|') || value.starts_with('>')
+}
+
+/// `key: value` split into the unquoted key and the trimmed value.
+fn split_key(text: &str) -> Option<(String, &str)> {
+    if text.starts_with(['{', '[']) || is_sequence_item(text) {
+        return None;
+    }
+    let (key, rest) = if let Some(q) = text.chars().next().filter(|c| *c == '"' || *c == '\'') {
+        let end = text[1..].find(q)? + 1;
+        (text[1..end].to_string(), text[end + 1..].trim_start())
+    } else {
+        let colon = text
+            .match_indices(':')
+            .map(|(i, _)| i)
+            .find(|&i| text[i + 1..].is_empty() || text[i + 1..].starts_with([' ', '\t']))?;
+        (text[..colon].trim_end().to_string(), &text[colon..])
+    };
+    let value = rest.strip_prefix(':')?;
+    Some((key, value.trim()))
+}
+
+fn scalar(value: &str) -> Shape {
+    // Tags (`!Ref`) and anchors (`&base`) don't change the value's type.
+    let mut value = value.trim();
+    while value.starts_with(['!', '&']) {
+        value = value
+            .split_once(' ')
+            .map_or("", |(_, rest)| rest.trim_start());
+    }
+    match value {
+        "" | "~" | "null" | "Null" | "NULL" => Shape::Null,
+        "true" | "True" | "TRUE" => Shape::Bool(true),
+        "false" | "False" | "FALSE" => Shape::Bool(false),
+        _ if value.starts_with('[') => Shape::Inline("array", value.to_string()),
+        _ if value.starts_with('{') => Shape::Inline("object", value.to_string()),
+        _ if value.len() >= 2 && (value.starts_with('"') || value.starts_with('\'')) => {
+            Shape::String(value[1..value.len() - 1].to_string())
+        }
+        _ if is_yaml_number(value) => Shape::Number(value.to_string()),
+        _ => Shape::String(value.to_string()),
+    }
+}
+
+fn is_yaml_number(value: &str) -> bool {
+    let unsigned = value.trim_start_matches(['-', '+']);
+    value.parse::<f64>().is_ok() && !unsigned.starts_with(['i', 'I', 'n', 'N'])
+        || matches!(
+            unsigned,
+            ".inf" | ".Inf" | ".INF" | ".nan" | ".NaN" | ".NAN"
+        )
+        || unsigned
+            .strip_prefix("0x")
+            .is_some_and(|h| !h.is_empty() && h.chars().all(|c| c.is_ascii_hexdigit()))
+        || unsigned
+            .strip_prefix("0o")
+            .is_some_and(|o| !o.is_empty() && o.chars().all(|c| ('0'..='7').contains(&c)))
+}
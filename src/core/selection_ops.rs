@@ -0,0 +1,53 @@
+use std::{
+    collections::HashMap,
+    hash::BuildHasher,
+    path::{Path, PathBuf},
+};
+
+/* ========================= Bulk selection operations ======================== */
+
+// The tree's check state is a map of explicit on/off entries keyed by absolute path;
+// paths without an entry inherit from their nearest ancestor that has one.
+
+/// Check (`state = true`) or uncheck `path` and everything below it. Entries of its
+/// descendants are dropped so they follow `path` again.
+pub fn set_selection_for_subtree<S: BuildHasher>(
+    explicit: &mut HashMap<PathBuf, bool, S>,
+    path: &Path,
+    state: bool,
+) {
+    explicit.retain(|p, _| p == path || !p.starts_with(path));
+    explicit.insert(path.to_path_buf(), state);
+}
+
+/// Check every path in `adds` and uncheck every path in `removes`, each with its
+/// subtree.
+///
+/// Shallower paths go first so that a nested entry refines its parent (`add src`,
+/// `remove src/generated` keeps the generated folder out); a path listed in both lists
+/// ends up unchecked.
+pub fn apply_selection_diff<S: BuildHasher>(
+    explicit: &mut HashMap<PathBuf, bool, S>,
+    adds: &[PathBuf],
+    removes: &[PathBuf],
+) {
+    let mut ops: Vec<(&Path, bool)> = adds
+        .iter()
+        .map(|p| (p.as_path(), true))
+        .chain(removes.iter().map(|p| (p.as_path(), false)))
+        .collect();
+    // Stable: at equal depth, adds stay ahead of removes.
+    ops.sort_by_key(|(p, _)| p.components().count());
+    for (path, state) in ops {
+        set_selection_for_subtree(explicit, path, state);
+    }
+}
+
+/// Replace the selection with exactly `paths`, checked.
+pub fn select_only<S: BuildHasher>(
+    explicit: &mut HashMap<PathBuf, bool, S>,
+    paths: impl IntoIterator<Item = PathBuf>,
+) {
+    explicit.clear();
+    explicit.extend(paths.into_iter().map(|p| (p, true)));
+}
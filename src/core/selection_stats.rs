@@ -0,0 +1,154 @@
+use std::collections::HashMap;
+
+/* ============================ Selection breakdown =========================== */
+
+/// Key used for files without an extension.
+pub const NO_EXTENSION: &str = "(none)";
+/// Key used for files directly in the project root.
+pub const ROOT_DIRECTORY: &str = "(root)";
+
+/// Totals for one extension or top-level directory.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StatGroup {
+    /// `.rs`, [`NO_EXTENSION`], `src/` or [`ROOT_DIRECTORY`].
+    pub key: String,
+    pub files: usize,
+    pub chars: usize,
+    pub tokens: usize,
+}
+
+/// Size of each emitted file, grouped on demand by extension or top-level directory.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SelectionStats {
+    files: Vec<FileStat>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct FileStat {
+    path: String,
+    chars: usize,
+    tokens: usize,
+}
+
+impl SelectionStats {
+    /// Record one emitted file: `path` is `/`-separated and relative to the project,
+    /// `chars` and `tokens` measure its block in the output (markers included).
+    pub fn add_file(&mut self, path: &str, chars: usize, tokens: usize) {
+        self.files.push(FileStat {
+            path: path.to_string(),
+            chars,
+            tokens,
+        });
+    }
+
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    #[must_use]
+    pub fn total_tokens(&self) -> usize {
+        self.files.iter().map(|f| f.tokens).sum()
+    }
+
+    #[must_use]
+    pub fn total_chars(&self) -> usize {
+        self.files.iter().map(|f| f.chars).sum()
+    }
+
+    /// Each emitted file's path and tokens, in output order.
+    pub fn file_tokens(&self) -> impl Iterator<Item = (&str, usize)> {
+        self.files.iter().map(|f| (f.path.as_str(), f.tokens))
+    }
+
+    /// Totals per lowercase extension (`.rs`), largest token count first.
+    #[must_use]
+    pub fn by_extension(&self) -> Vec<StatGroup> {
+        self.group_by(|path| {
+            let name = path.rsplit('/').next().unwrap_or(path);
+            match name.rsplit_once('.') {
+                Some((stem, ext)) if !stem.is_empty() => format!(".{}", ext.to_lowercase()),
+                _ => NO_EXTENSION.to_string(),
+            }
+        })
+    }
+
+    /// Totals per top-level directory (`src/`), largest token count first.
+    #[must_use]
+    pub fn by_directory(&self) -> Vec<StatGroup> {
+        self.group_by(|path| {
+            path.split_once('/')
+                .map_or_else(|| ROOT_DIRECTORY.to_string(), |(dir, _)| format!("{dir}/"))
+        })
+    }
+
+    fn group_by(&self, key_of: impl Fn(&str) -> String) -> Vec<StatGroup> {
+        let mut groups: HashMap<String, StatGroup> = HashMap::new();
+        for file in &self.files {
+            let key = key_of(&file.path);
+            let group = groups.entry(key.clone()).or_insert_with(|| StatGroup {
+                key,
+                ..StatGroup::default()
+            });
+            group.files += 1;
+            group.chars += file.chars;
+            group.tokens += file.tokens;
+        }
+        let mut groups: Vec<StatGroup> = groups.into_values().collect();
+        groups.sort_by(|a, b| b.tokens.cmp(&a.tokens).then_with(|| a.key.cmp(&b.key)));
+        groups
+    }
+
+    /// Plain-text tables of [`Self::by_extension`] and [`Self::by_directory`] with each
+    /// group's share of the file tokens. Empty when no file was emitted.
+    #[must_use]
+    pub fn render(&self) -> String {
+        if self.is_empty() {
+            return String::new();
+        }
+        let total = self.total_tokens();
+        let mut out = String::new();
+        render_table(&mut out, "Extension", &self.by_extension(), total);
+        out.push('\n');
+        render_table(&mut out, "Directory", &self.by_directory(), total);
+        out
+    }
+}
+
+fn render_table(out: &mut String, label: &str, groups: &[StatGroup], total_tokens: usize) {
+    use std::fmt::Write;
+
+    let width = groups
+        .iter()
+        .map(|g| g.key.chars().count())
+        .chain([label.len()])
+        .max()
+        .unwrap_or(0);
+    let _ = writeln!(
+        out,
+        "{label:<width$}  {:>6}  {:>10}  {:>6}  {:>10}",
+        "files", "tokens", "share", "chars"
+    );
+    for g in groups {
+        let _ = writeln!(
+            out,
+            "{:<width$}  {:>6}  {:>10}  {:>6}  {:>10}",
+            g.key,
+            g.files,
+            format!("~{}", g.tokens),
+            share_percent(g.tokens, total_tokens),
+            g.chars
+        );
+    }
+}
+
+/// `part` as a percentage of `total` with one decimal, e.g. `40.0%`.
+#[must_use]
+pub fn share_percent(part: usize, total: usize) -> String {
+    if total == 0 {
+        return "0.0%".to_string();
+    }
+    // Integer math keeps this exact for any realistic token count.
+    let tenths = (part * 1000 + total / 2) / total;
+    format!("{}.{}%", tenths / 10, tenths % 10)
+}
@@ -1,4 +1,6 @@
-#[derive(Debug, Clone, Default)]
+use serde::Serialize;
+
+#[derive(Debug, Clone, Default, Serialize)]
 pub struct SlintFilterOptions {
     pub remove_line_comments: bool,
     pub remove_block_comments: bool,
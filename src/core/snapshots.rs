@@ -0,0 +1,114 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::{ProfileSelection, ensure_workspace_dir, workspace_dir};
+
+/* =========================== Selection snapshots =========================== */
+
+/// Labeled capture of the explicit checks only (no settings), kept per user.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct SelectionSnapshot {
+    /// Increasing with every snapshot taken in the project.
+    pub id: u64,
+    pub label: String,
+    /// Local time the snapshot was taken, as shown in the UI.
+    pub created: String,
+    /// Explicit on/off checks relative to the project root, as in profiles.
+    pub explicit: Vec<ProfileSelection>,
+}
+
+impl SelectionSnapshot {
+    /// One line for the snapshot list, e.g. `before refactor (2026-10-15 14:03)`.
+    #[must_use]
+    pub fn display(&self) -> String {
+        format!("{} ({})", self.label, self.created)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+struct SnapshotsFile {
+    version: u32,
+    snapshots: Vec<SelectionSnapshot>,
+}
+
+#[must_use]
+pub fn snapshots_file(project_root: &Path) -> PathBuf {
+    workspace_dir(project_root)
+        .join("local")
+        .join("snapshots.json")
+}
+
+/// Snapshots of the project, oldest first.
+#[must_use]
+pub fn load_snapshots(project_root: &Path) -> Vec<SelectionSnapshot> {
+    let Ok(data) = fs::read(snapshots_file(project_root)) else {
+        return Vec::new();
+    };
+    let mut snapshots = serde_json::from_slice::<SnapshotsFile>(&data)
+        .map(|file| file.snapshots)
+        .unwrap_or_default();
+    snapshots.sort_by_key(|s| s.id);
+    snapshots
+}
+
+/// Store a new snapshot of `explicit`. A blank `label` becomes `Snapshot N`.
+///
+/// # Errors
+/// Returns I/O errors from writing/renaming files, or serialization errors.
+pub fn add_snapshot(
+    project_root: &Path,
+    label: &str,
+    created: &str,
+    explicit: Vec<ProfileSelection>,
+) -> io::Result<SelectionSnapshot> {
+    let mut snapshots = load_snapshots(project_root);
+    let id = snapshots.last().map_or(1, |s| s.id + 1);
+    let label = match label.trim() {
+        "" => format!("Snapshot {id}"),
+        label => label.to_string(),
+    };
+    let snapshot = SelectionSnapshot {
+        id,
+        label,
+        created: created.to_string(),
+        explicit,
+    };
+    snapshots.push(snapshot.clone());
+    write_snapshots(project_root, snapshots)?;
+    Ok(snapshot)
+}
+
+/// Delete the snapshot `id` if it exists.
+///
+/// # Errors
+/// Returns I/O errors from writing/renaming files, or serialization errors.
+pub fn delete_snapshot(project_root: &Path, id: u64) -> io::Result<()> {
+    let mut snapshots = load_snapshots(project_root);
+    let before = snapshots.len();
+    snapshots.retain(|s| s.id != id);
+    if snapshots.len() == before {
+        return Ok(());
+    }
+    write_snapshots(project_root, snapshots)
+}
+
+fn write_snapshots(project_root: &Path, snapshots: Vec<SelectionSnapshot>) -> io::Result<()> {
+    ensure_workspace_dir(project_root)?;
+    let path = snapshots_file(project_root);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let tmp = path.with_extension("json.tmp");
+    let file = SnapshotsFile {
+        version: 1,
+        snapshots,
+    };
+    let data = serde_json::to_vec_pretty(&file).map_err(|e| io::Error::other(e.to_string()))?;
+    fs::write(&tmp, data)?;
+    fs::rename(&tmp, &path)?;
+    Ok(())
+}
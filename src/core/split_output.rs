@@ -0,0 +1,89 @@
+use std::collections::BTreeMap;
+
+use crate::core::{OutputLayout, OutputParts, ROOT_DIRECTORY};
+
+/* ======================== Split by top-level directory ====================== */
+
+/// File name stem of the part holding files directly in the project root.
+pub const SPLIT_ROOT_STEM: &str = "root";
+
+/// What a generation leaves behind for "Split Export": the sections every part shares
+/// and each emitted file block.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SplitSource {
+    /// Hierarchy, notes and other sections; `contents` is ignored.
+    pub parts: OutputParts,
+    /// `(path, block)`: `/`-separated project-relative path and the block as emitted
+    /// (markers included), in output order.
+    pub blocks: Vec<(String, String)>,
+}
+
+impl SplitSource {
+    pub fn add_block(&mut self, path: &str, block: &str) {
+        self.blocks.push((path.to_string(), block.to_string()));
+    }
+}
+
+/// One file of a split export.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutputSplit {
+    /// Top-level directory (`backend/`), or `None` for files in the project root.
+    pub directory: Option<String>,
+    /// `backend.md`, or `root.md` for root files (suffixed if a directory takes the name).
+    pub file_name: String,
+    pub text: String,
+}
+
+/// One output per top-level directory with selected files, sorted by directory (root
+/// files last).
+///
+/// Each starts with a `=== PART i/n: dir/ ===` line and repeats the shared sections of
+/// `source`; FILE CONTENTS only holds that directory's blocks, in their original order.
+#[must_use]
+pub fn split_output_by_top_level_dir(
+    layout: &OutputLayout,
+    source: &SplitSource,
+) -> Vec<OutputSplit> {
+    let mut groups: BTreeMap<Option<&str>, String> = BTreeMap::new();
+    for (path, block) in &source.blocks {
+        let top = path.split_once('/').map(|(dir, _)| dir);
+        groups.entry(top).or_default().push_str(block);
+    }
+    // `None` sorts first in a BTreeMap; move the root part to the end.
+    let mut groups: Vec<(Option<&str>, String)> = groups.into_iter().collect();
+    if groups.first().is_some_and(|(dir, _)| dir.is_none()) {
+        groups.rotate_left(1);
+    }
+
+    let total = groups.len();
+    let mut used: Vec<String> = Vec::with_capacity(total);
+    groups
+        .into_iter()
+        .enumerate()
+        .map(|(i, (dir, contents))| {
+            let stem = dir.unwrap_or(SPLIT_ROOT_STEM);
+            let mut file_name = format!("{stem}.md");
+            let mut n = 2;
+            // Root files come last, so a `root/` directory already holds `root.md`.
+            while used.iter().any(|u| u.eq_ignore_ascii_case(&file_name)) {
+                file_name = format!("{stem}-{n}.md");
+                n += 1;
+            }
+            used.push(file_name.clone());
+
+            let label = dir.map_or_else(|| ROOT_DIRECTORY.to_string(), |d| format!("{d}/"));
+            let mut parts = source.parts.clone();
+            parts.contents = Some(contents);
+            let text = format!(
+                "=== PART {}/{total}: {label} ===\n\n{}",
+                i + 1,
+                layout.render(&parts)
+            );
+            OutputSplit {
+                directory: dir.map(|d| format!("{d}/")),
+                file_name,
+                text,
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,323 @@
+use std::fmt::Write;
+
+use serde::Serialize;
+
+use crate::core::{collapse_consecutive_blank_lines, is_ident_byte, remove_marked_comments};
+
+/// Smallest INSERT or COPY data block (in rows) that is collapsed to a summary line.
+pub const SQL_COLLAPSE_MIN_ROWS: usize = 10;
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SqlFilterOptions {
+    /// `--` and `/* */` comments.
+    pub remove_comments: bool,
+    /// Replace INSERT/COPY data blocks of [`SQL_COLLAPSE_MIN_ROWS`] rows or more with a
+    /// summary line; DDL is kept.
+    pub collapse_data: bool,
+}
+
+/// Returns true if the given path ends with ".sql" (any case).
+#[must_use]
+pub fn is_sql_file_path(path: &std::path::Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("sql"))
+}
+
+/// Apply SQL-specific filters to a file's string contents.
+/// This function only transforms when at least one option is enabled; otherwise returns input as-is.
+#[must_use]
+pub fn apply_sql_filters(source: &str, opts: &SqlFilterOptions) -> String {
+    if !(opts.remove_comments || opts.collapse_data) {
+        return source.to_string();
+    }
+    let mut out = if opts.remove_comments {
+        let cleaned = remove_marked_comments(source, b"--", true, true, skip_sql_literal);
+        collapse_consecutive_blank_lines(&cleaned)
+    } else {
+        source.to_string()
+    };
+    if opts.collapse_data {
+        out = collapse_sql_data(&out);
+    }
+    out
+}
+
+/// End of the string, quoted identifier or dollar-quoted body (`$$...$$`, `$fn$...$fn$`)
+/// starting at `idx`. Quotes are escaped by doubling them.
+fn skip_sql_literal(bytes: &[u8], len: usize, idx: usize) -> Option<usize> {
+    match bytes[idx] {
+        quote @ (b'\'' | b'"' | b'`') => {
+            let mut i = idx + 1;
+            while i < len {
+                if bytes[i] == quote {
+                    if i + 1 < len && bytes[i + 1] == quote {
+                        i += 2;
+                        continue;
+                    }
+                    return Some(i + 1);
+                }
+                i += 1;
+            }
+            Some(len)
+        }
+        b'$' => {
+            let tag_end = bytes[idx + 1..len]
+                .iter()
+                .position(|&b| !is_ident_byte(b))
+                .map_or(len, |p| idx + 1 + p);
+            // `$1` is a parameter, not a tag.
+            let valid = tag_end < len
+                && bytes[tag_end] == b'$'
+                && bytes.get(idx + 1).is_some_and(|b| !b.is_ascii_digit());
+            if !valid {
+                return None;
+            }
+            let tag = &bytes[idx..=tag_end];
+            Some(
+                bytes[tag_end + 1..len]
+                    .windows(tag.len())
+                    .position(|w| w == tag)
+                    .map_or(len, |p| tag_end + 1 + p + tag.len()),
+            )
+        }
+        _ => None,
+    }
+}
+
+/// Index of the first byte after whitespace and comments from `idx`.
+fn skip_sql_trivia(bytes: &[u8], len: usize, mut idx: usize) -> usize {
+    while idx < len {
+        if bytes[idx].is_ascii_whitespace() {
+            idx += 1;
+        } else if bytes[idx..].starts_with(b"--") {
+            idx = bytes[idx..len]
+                .iter()
+                .position(|&b| b == b'\n')
+                .map_or(len, |p| idx + p);
+        } else if bytes[idx..].starts_with(b"/*") {
+            idx = bytes[idx + 2..len]
+                .windows(2)
+                .position(|w| w == b"*/")
+                .map_or(len, |p| idx + 2 + p + 2);
+        } else {
+            break;
+        }
+    }
+    idx
+}
+
+/// End of the statement starting at `idx`: just past its `;`, or the end of the input.
+fn statement_end(bytes: &[u8], len: usize, mut idx: usize) -> usize {
+    while idx < len {
+        if bytes[idx] == b';' {
+            return idx + 1;
+        }
+        let trivia = skip_sql_trivia(bytes, len, idx);
+        if trivia > idx {
+            idx = trivia;
+            continue;
+        }
+        idx = skip_sql_literal(bytes, len, idx).unwrap_or(idx + 1);
+    }
+    len
+}
+
+/// Consecutive INSERTs into the same table and columns.
+struct InsertRun {
+    head: String,
+    rows: usize,
+    /// Start of the first statement, including the whitespace before it.
+    start: usize,
+    /// Start of the first statement's `INSERT`.
+    body: usize,
+    end: usize,
+}
+
+fn collapse_sql_data(source: &str) -> String {
+    let bytes = source.as_bytes();
+    let len = bytes.len();
+    let mut out = String::with_capacity(len);
+    let mut pending: Option<InsertRun> = None;
+    let mut idx = 0usize;
+    while idx < len {
+        let start = idx;
+        let body = skip_sql_trivia(bytes, len, idx);
+        if body >= len {
+            break;
+        }
+        let end = statement_end(bytes, len, body);
+        let statement = &source[body..end];
+        if let Some((head, rows)) = insert_rows(statement) {
+            let contiguous = source[start..body].trim().is_empty();
+            match pending.as_mut() {
+                Some(run) if contiguous && run.head == head => {
+                    run.rows += rows;
+                    run.end = end;
+                }
+                _ => {
+                    flush_insert_run(&mut out, source, pending.take());
+                    pending = Some(InsertRun {
+                        head,
+                        rows,
+                        start,
+                        body,
+                        end,
+                    });
+                }
+            }
+            idx = end;
+            continue;
+        }
+        flush_insert_run(&mut out, source, pending.take());
+        if let Some(header) = copy_from_stdin(statement) {
+            let (data_end, rows) = copy_data_end(bytes, len, end);
+            out.push_str(&source[start..body]);
+            if rows >= SQL_COLLAPSE_MIN_ROWS {
+                let _ = write!(out, "-- {header}: {rows} rows omitted");
+            } else {
+                out.push_str(&source[body..data_end]);
+            }
+            idx = data_end;
+            continue;
+        }
+        out.push_str(&source[start..end]);
+        idx = end;
+    }
+    flush_insert_run(&mut out, source, pending);
+    out.push_str(&source[idx.min(len)..]);
+    out
+}
+
+fn flush_insert_run(out: &mut String, source: &str, run: Option<InsertRun>) {
+    let Some(run) = run else {
+        return;
+    };
+    out.push_str(&source[run.start..run.body]);
+    if run.rows >= SQL_COLLAPSE_MIN_ROWS {
+        let _ = write!(out, "-- {}: {} rows omitted", run.head, run.rows);
+    } else {
+        out.push_str(&source[run.body..run.end]);
+    }
+}
+
+/// `INSERT ... VALUES` head (up to `VALUES`, whitespace squeezed) and its row count.
+fn insert_rows(statement: &str) -> Option<(String, usize)> {
+    if !starts_with_keyword(statement.as_bytes(), "insert") {
+        return None;
+    }
+    let bytes = statement.as_bytes();
+    let len = bytes.len();
+    let mut depth = 0i32;
+    let mut idx = 0usize;
+    let mut values = None;
+    while idx < len {
+        if let Some(next) = skip_sql_literal(bytes, len, idx) {
+            idx = next;
+            continue;
+        }
+        match bytes[idx] {
+            b'(' => depth += 1,
+            b')' => depth -= 1,
+            _ if depth == 0
+                && starts_with_keyword(&bytes[idx..], "values")
+                && (idx == 0 || !is_ident_byte(bytes[idx - 1])) =>
+            {
+                values = Some(idx);
+                break;
+            }
+            _ => {}
+        }
+        idx += 1;
+    }
+    let values = values?;
+    let mut rows = 0usize;
+    let mut idx = values + "values".len();
+    while idx < len {
+        match bytes[idx] {
+            b'(' => {
+                rows += 1;
+                idx = group_end(bytes, len, idx);
+            }
+            b',' => idx += 1,
+            b if b.is_ascii_whitespace() => idx += 1,
+            // `ON CONFLICT ...`, `RETURNING ...` or the closing `;`.
+            _ => break,
+        }
+    }
+    let head = statement[..values]
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    Some((head, rows))
+}
+
+/// End of the parenthesized group opening at `idx`.
+fn group_end(bytes: &[u8], len: usize, mut idx: usize) -> usize {
+    let mut depth = 0usize;
+    while idx < len {
+        if let Some(next) = skip_sql_literal(bytes, len, idx) {
+            idx = next;
+            continue;
+        }
+        match bytes[idx] {
+            b'(' => depth += 1,
+            b')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return idx + 1;
+                }
+            }
+            _ => {}
+        }
+        idx += 1;
+    }
+    len
+}
+
+/// The `COPY ... FROM stdin` statement without its `;`, whitespace squeezed.
+fn copy_from_stdin(statement: &str) -> Option<String> {
+    if !starts_with_keyword(statement.as_bytes(), "copy") {
+        return None;
+    }
+    let head = statement
+        .trim_end_matches(';')
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    head.to_ascii_lowercase()
+        .contains("from stdin")
+        .then_some(head)
+}
+
+/// End of the COPY data after the statement ending at `statement_end` (at the newline
+/// after the `\.` line) and its number of rows.
+fn copy_data_end(bytes: &[u8], len: usize, statement_end: usize) -> (usize, usize) {
+    let Some(first) = bytes[statement_end..len]
+        .iter()
+        .position(|&b| b == b'\n')
+        .map(|p| statement_end + p + 1)
+    else {
+        return (len, 0);
+    };
+    let mut rows = 0usize;
+    let mut line_start = first;
+    while line_start < len {
+        let line_end = bytes[line_start..len]
+            .iter()
+            .position(|&b| b == b'\n')
+            .map_or(len, |p| line_start + p);
+        if bytes[line_start..line_end].trim_ascii_end() == b"\\." {
+            return (line_end, rows);
+        }
+        rows += 1;
+        line_start = line_end + 1;
+    }
+    (len, rows)
+}
+
+fn starts_with_keyword(bytes: &[u8], keyword: &str) -> bool {
+    bytes.len() >= keyword.len()
+        && bytes[..keyword.len()].eq_ignore_ascii_case(keyword.as_bytes())
+        && bytes.get(keyword.len()).is_none_or(|&b| !is_ident_byte(b))
+}
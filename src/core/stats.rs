@@ -0,0 +1,363 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Write,
+    fs,
+    hash::BuildHasher,
+    io::Read,
+    path::{Path, PathBuf},
+};
+
+use crate::core::{
+    DEFAULT_EXCLUDE_DIRS, DEFAULT_EXCLUDE_FILES, DirectoryRule, NO_EXTENSION, Node, ROOT_DIRECTORY,
+    ScanFilters, WorkspaceSettings, collect_file_paths, display_path, format_byte_size,
+    load_local_settings, load_profile, load_workspace, parse_extension_filters, path_to_unix,
+    scan_dir_to_node_with_name_matching, share_percent,
+};
+
+/* ============================ Project statistics ============================ */
+
+/// Files listed under "Largest files" in [`ProjectStats::render`].
+pub const LARGEST_FILES_SHOWN: usize = 10;
+/// Files bigger than this are measured by size only; their lines aren't counted.
+pub const MAX_LINE_COUNT_BYTES: u64 = 8 * 1024 * 1024;
+
+/// Size of one file of the project.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProjectFile {
+    /// `/`-separated, relative to the project.
+    pub path: String,
+    pub bytes: u64,
+    /// `0` for binary files and files over [`MAX_LINE_COUNT_BYTES`].
+    pub lines: usize,
+    /// Whether the current filters keep the file.
+    pub included: bool,
+}
+
+/// Totals for the project, one extension or one top-level directory.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProjectStatGroup {
+    /// `.rs`, [`NO_EXTENSION`], `src/`, [`ROOT_DIRECTORY`] or empty for the whole project.
+    pub key: String,
+    pub files: usize,
+    pub lines: usize,
+    pub bytes: u64,
+    pub included_files: usize,
+    pub included_lines: usize,
+    pub included_bytes: u64,
+}
+
+impl ProjectStatGroup {
+    const fn add(&mut self, file: &ProjectFile) {
+        self.files += 1;
+        self.lines += file.lines;
+        self.bytes += file.bytes;
+        if file.included {
+            self.included_files += 1;
+            self.included_lines += file.lines;
+            self.included_bytes += file.bytes;
+        }
+    }
+}
+
+/// Files of a scanned project, grouped on demand by extension or top-level directory.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProjectStats {
+    files: Vec<ProjectFile>,
+}
+
+impl ProjectStats {
+    /// Measure every file of `tree` (scanned from `project_root`); files that are also in
+    /// `included` count as kept by the filters.
+    #[must_use]
+    pub fn from_trees(project_root: &Path, tree: &Node, included: &Node) -> Self {
+        let kept: HashSet<PathBuf> = collect_file_paths(included).into_iter().collect();
+        let mut paths = collect_file_paths(tree);
+        paths.sort();
+        paths.dedup();
+
+        let mut stats = Self::default();
+        for path in paths {
+            let Ok(meta) = fs::metadata(&path) else {
+                continue;
+            };
+            let rel = path.strip_prefix(project_root).unwrap_or(&path);
+            stats.add_file(ProjectFile {
+                path: path_to_unix(rel),
+                bytes: meta.len(),
+                lines: if meta.len() > MAX_LINE_COUNT_BYTES {
+                    0
+                } else {
+                    count_file_lines(&path)
+                },
+                included: kept.contains(&path),
+            });
+        }
+        stats
+    }
+
+    pub fn add_file(&mut self, file: ProjectFile) {
+        self.files.push(file);
+    }
+
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    #[must_use]
+    pub fn files(&self) -> &[ProjectFile] {
+        &self.files
+    }
+
+    /// Totals over every file, with an empty key.
+    #[must_use]
+    pub fn total(&self) -> ProjectStatGroup {
+        let mut total = ProjectStatGroup::default();
+        for file in &self.files {
+            total.add(file);
+        }
+        total
+    }
+
+    /// Totals per lowercase extension (`.rs`), most files first.
+    #[must_use]
+    pub fn by_extension(&self) -> Vec<ProjectStatGroup> {
+        let mut groups = self.group_by(|path| {
+            let name = path.rsplit('/').next().unwrap_or(path);
+            match name.rsplit_once('.') {
+                Some((stem, ext)) if !stem.is_empty() => format!(".{}", ext.to_lowercase()),
+                _ => NO_EXTENSION.to_string(),
+            }
+        });
+        groups.sort_by(|a, b| b.files.cmp(&a.files).then_with(|| a.key.cmp(&b.key)));
+        groups
+    }
+
+    /// Totals per top-level directory (`src/`), most lines first.
+    #[must_use]
+    pub fn by_directory(&self) -> Vec<ProjectStatGroup> {
+        let mut groups = self.group_by(|path| {
+            path.split_once('/')
+                .map_or_else(|| ROOT_DIRECTORY.to_string(), |(dir, _)| format!("{dir}/"))
+        });
+        groups.sort_by(|a, b| b.lines.cmp(&a.lines).then_with(|| a.key.cmp(&b.key)));
+        groups
+    }
+
+    /// The `count` biggest files, largest first.
+    #[must_use]
+    pub fn largest(&self, count: usize) -> Vec<&ProjectFile> {
+        let mut files: Vec<&ProjectFile> = self.files.iter().collect();
+        files.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.path.cmp(&b.path)));
+        files.truncate(count);
+        files
+    }
+
+    fn group_by(&self, key_of: impl Fn(&str) -> String) -> Vec<ProjectStatGroup> {
+        let mut groups: HashMap<String, ProjectStatGroup> = HashMap::new();
+        for file in &self.files {
+            let key = key_of(&file.path);
+            groups
+                .entry(key.clone())
+                .or_insert_with(|| ProjectStatGroup {
+                    key,
+                    ..ProjectStatGroup::default()
+                })
+                .add(file);
+        }
+        groups.into_values().collect()
+    }
+
+    /// Plain-text report: totals and how much the filters keep, tables per extension and
+    /// top-level directory, and the largest files.
+    #[must_use]
+    pub fn render(&self) -> String {
+        let total = self.total();
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            "Files: {} ({} included, {})",
+            total.files,
+            total.included_files,
+            share_percent(total.included_files, total.files)
+        );
+        let _ = writeln!(
+            out,
+            "Lines: {} ({} included, {})",
+            total.lines,
+            total.included_lines,
+            share_percent(total.included_lines, total.lines)
+        );
+        let _ = writeln!(
+            out,
+            "Size:  {} ({} included)",
+            format_byte_size(total.bytes),
+            format_byte_size(total.included_bytes)
+        );
+        if self.is_empty() {
+            return out;
+        }
+        out.push('\n');
+        render_table(&mut out, "Extension", &self.by_extension());
+        out.push('\n');
+        render_table(&mut out, "Directory", &self.by_directory());
+        out.push_str("\nLargest files\n");
+        for file in self.largest(LARGEST_FILES_SHOWN) {
+            let _ = writeln!(
+                out,
+                "{:>10}  {:>8} lines  {}{}",
+                format_byte_size(file.bytes),
+                file.lines,
+                file.path,
+                if file.included {
+                    ""
+                } else {
+                    "  (filtered out)"
+                }
+            );
+        }
+        out
+    }
+}
+
+fn render_table(out: &mut String, label: &str, groups: &[ProjectStatGroup]) {
+    let width = groups
+        .iter()
+        .map(|g| g.key.chars().count())
+        .chain([label.len()])
+        .max()
+        .unwrap_or(0);
+    let _ = writeln!(
+        out,
+        "{label:<width$}  {:>6}  {:>8}  {:>10}  {:>10}  {:>10}",
+        "files", "included", "lines", "included", "size"
+    );
+    for g in groups {
+        let _ = writeln!(
+            out,
+            "{:<width$}  {:>6}  {:>8}  {:>10}  {:>10}  {:>10}",
+            g.key,
+            g.files,
+            g.included_files,
+            g.lines,
+            g.included_lines,
+            format_byte_size(g.bytes)
+        );
+    }
+}
+
+/// Lines of `bytes`, counting a last line without a newline; `0` for binary data.
+#[must_use]
+pub fn count_lines(bytes: &[u8]) -> usize {
+    if bytes.contains(&0) {
+        return 0;
+    }
+    #[allow(clippy::naive_bytecount)] // not worth a bytecount dependency
+    let newlines = bytes.iter().filter(|&&b| b == b'\n').count();
+    newlines + usize::from(bytes.last().is_some_and(|&b| b != b'\n'))
+}
+
+fn count_file_lines(path: &Path) -> usize {
+    let mut bytes = Vec::new();
+    match fs::File::open(path).and_then(|mut f| f.read_to_end(&mut bytes)) {
+        Ok(_) => count_lines(&bytes),
+        Err(_) => 0,
+    }
+}
+
+/// Scan and measure `project_root`. Excluded directories and per-directory overrides are
+/// left out of the totals; the extension and file filters decide what counts as included.
+#[must_use]
+pub fn collect_project_stats<S: BuildHasher + Default>(
+    project_root: &Path,
+    filters: &ScanFilters<'_, S>,
+) -> ProjectStats {
+    let none: HashSet<String, S> = HashSet::default();
+    let tree = scan_dir_to_node_with_name_matching(
+        project_root,
+        &none,
+        &none,
+        filters.exclude_dirs,
+        &none,
+        filters.rules,
+        filters.names,
+    )
+    .node;
+    let included = scan_dir_to_node_with_name_matching(
+        project_root,
+        filters.include_exts,
+        filters.exclude_exts,
+        filters.exclude_dirs,
+        filters.exclude_files,
+        filters.rules,
+        filters.names,
+    )
+    .node;
+    ProjectStats::from_trees(project_root, &tree, &included)
+}
+
+/// [`collect_project_stats`] with the filters of saved workspace or profile `settings`.
+#[must_use]
+pub fn project_stats_for_settings(
+    project_root: &Path,
+    settings: &WorkspaceSettings,
+    rules: &[DirectoryRule],
+) -> ProjectStats {
+    let names = |raw: &str| -> HashSet<String> {
+        raw.split(',')
+            .map(|x| x.trim().to_string())
+            .filter(|x| !x.is_empty())
+            .collect()
+    };
+    let (include_exts, exclude_exts) = parse_extension_filters(&settings.ext_filter);
+    let mut exclude_dirs = names(&settings.exclude_dirs);
+    exclude_dirs.insert(".stitchworkspace".to_string());
+    let exclude_files = names(&settings.exclude_files);
+    collect_project_stats(
+        project_root,
+        &ScanFilters {
+            include_exts: &include_exts,
+            exclude_exts: &exclude_exts,
+            exclude_dirs: &exclude_dirs,
+            exclude_files: &exclude_files,
+            rules,
+            names: settings.name_matching,
+        },
+    )
+}
+
+/// Report of `project_root` for `stitch stats`, under the filters of `profile` (or the
+/// project's current one), the saved workspace settings, or the defaults for a project
+/// without either.
+///
+/// # Errors
+/// Returns a one-line message when the folder doesn't exist or `profile` isn't found.
+pub fn project_stats_report(project_root: &Path, profile: Option<&str>) -> Result<String, String> {
+    if !project_root.is_dir() {
+        return Err(format!(
+            "'{}' is not a directory",
+            display_path(project_root)
+        ));
+    }
+    let loaded = match profile {
+        Some(name) => Some(
+            load_profile(project_root, name)
+                .ok_or_else(|| format!("no profile named '{name}' in this project"))?,
+        ),
+        None => load_local_settings(project_root)
+            .and_then(|ls| ls.current_profile)
+            .and_then(|name| load_profile(project_root, &name)),
+    };
+    let (settings, rules) = match loaded {
+        Some((profile, _)) => (profile.settings, profile.dir_rules),
+        None => (
+            load_workspace(project_root).unwrap_or_else(|| WorkspaceSettings {
+                exclude_dirs: DEFAULT_EXCLUDE_DIRS.to_string(),
+                exclude_files: DEFAULT_EXCLUDE_FILES.to_string(),
+                ..WorkspaceSettings::default()
+            }),
+            Vec::new(),
+        ),
+    };
+    Ok(project_stats_for_settings(project_root, &settings, &rules).render())
+}
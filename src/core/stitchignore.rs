@@ -0,0 +1,158 @@
+use std::{fs, path::Path};
+
+use regex::Regex;
+
+/* ================================ .stitchignore ============================== */
+
+/// Name of the ignore file read from the project root at scan time.
+pub const STITCHIGNORE_FILE: &str = ".stitchignore";
+
+#[derive(Debug, Clone)]
+struct IgnorePattern {
+    regex: Regex,
+    negated: bool,
+    dir_only: bool,
+}
+
+/// Exclusions in gitignore syntax: `#` comments, `!` negation, trailing `/` for
+/// directories only, leading or inner `/` to anchor at the root, `*`, `?`, `[...]` and `**`.
+#[derive(Debug, Clone, Default)]
+pub struct IgnoreRules {
+    patterns: Vec<IgnorePattern>,
+}
+
+impl IgnoreRules {
+    /// Parse the lines of an ignore file; lines that don't form a valid pattern are skipped.
+    #[must_use]
+    pub fn parse(text: &str) -> Self {
+        Self {
+            patterns: text.lines().filter_map(parse_pattern).collect(),
+        }
+    }
+
+    /// The rules of `<root>/.stitchignore`, or none when it is missing or unreadable.
+    #[must_use]
+    pub fn load(root: &Path) -> Self {
+        fs::read_to_string(root.join(STITCHIGNORE_FILE))
+            .map(|text| Self::parse(&text))
+            .unwrap_or_default()
+    }
+
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+
+    /// Whether the entry at project-relative `rel` (forward slashes) matches, ignoring
+    /// its parents. The last matching pattern decides, so `!keep.log` after `*.log` wins.
+    #[must_use]
+    pub fn matches(&self, rel: &str, is_dir: bool) -> bool {
+        let mut ignored = false;
+        for p in &self.patterns {
+            if (is_dir || !p.dir_only) && p.regex.is_match(rel) {
+                ignored = !p.negated;
+            }
+        }
+        ignored
+    }
+
+    /// Whether `rel` or one of its parent directories is ignored. As with git, a file
+    /// can't be re-included when a directory above it is excluded.
+    #[must_use]
+    pub fn is_ignored(&self, rel: &str, is_dir: bool) -> bool {
+        let rel = rel.trim_matches('/');
+        rel.match_indices('/')
+            .any(|(i, _)| self.matches(&rel[..i], true))
+            || self.matches(rel, is_dir)
+    }
+}
+
+fn parse_pattern(line: &str) -> Option<IgnorePattern> {
+    let line = trim_unescaped_trailing_spaces(line);
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+    let (negated, line) = line.strip_prefix('!').map_or_else(
+        || (false, line.strip_prefix('\\').unwrap_or(line)),
+        |rest| (true, rest),
+    );
+    let (dir_only, line) = line
+        .strip_suffix('/')
+        .map_or((false, line), |rest| (true, rest));
+    let anchored = line.contains('/');
+    let glob = line.strip_prefix('/').unwrap_or(line);
+    if glob.is_empty() {
+        return None;
+    }
+
+    let body = glob_to_regex(glob);
+    let pattern = if anchored {
+        format!("^{body}$")
+    } else {
+        format!("^(?:.*/)?{body}$")
+    };
+    Some(IgnorePattern {
+        regex: Regex::new(&pattern).ok()?,
+        negated,
+        dir_only,
+    })
+}
+
+fn trim_unescaped_trailing_spaces(line: &str) -> &str {
+    let trimmed = line.trim_end();
+    if trimmed.ends_with('\\') && trimmed.len() < line.len() {
+        // `foo\ ` keeps its escaped space.
+        &line[..=trimmed.len()]
+    } else {
+        trimmed
+    }
+}
+
+fn glob_to_regex(glob: &str) -> String {
+    let mut out = String::new();
+    let chars: Vec<char> = glob.chars().collect();
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            '*' if chars.get(i + 1) == Some(&'*') => {
+                let at_start = i == 0 || chars[i - 1] == '/';
+                let next = chars.get(i + 2);
+                if at_start && next == Some(&'/') {
+                    // `**/` = zero or more directories.
+                    out.push_str("(?:.*/)?");
+                    i += 3;
+                } else if at_start && next.is_none() {
+                    out.push_str(".*");
+                    i += 2;
+                } else {
+                    out.push_str("[^/]*");
+                    i += 2;
+                }
+                continue;
+            }
+            '*' => out.push_str("[^/]*"),
+            '?' => out.push_str("[^/]"),
+            '[' => {
+                if let Some(len) = chars[i + 1..].iter().skip(1).position(|&c| c == ']') {
+                    let class: String = chars[i + 1..i + 2 + len].iter().collect();
+                    let class = class
+                        .strip_prefix('!')
+                        .map_or_else(|| class.clone(), |rest| format!("^{rest}"));
+                    out.push('[');
+                    out.push_str(&class.replace('\\', "\\\\"));
+                    out.push(']');
+                    i += len + 3;
+                    continue;
+                }
+                out.push_str(r"\[");
+            }
+            '\\' if i + 1 < chars.len() => {
+                i += 1;
+                out.push_str(&regex::escape(&chars[i].to_string()));
+            }
+            c => out.push_str(&regex::escape(&c.to_string())),
+        }
+        i += 1;
+    }
+    out
+}
@@ -0,0 +1,208 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+use regex::Regex;
+
+use crate::core::path_to_unix;
+
+/* =============================== Symbol index =============================== */
+
+/// What a [`SymbolDefinition`] defines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum SymbolKind {
+    Function,
+    /// Struct, enum, class, trait, interface, type alias or UI component.
+    Type,
+    /// Constant, static or top-level variable.
+    Constant,
+    Module,
+    Macro,
+}
+
+/// A line that defines a symbol.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SymbolDefinition {
+    pub name: String,
+    pub kind: SymbolKind,
+    /// 0-based line of the definition.
+    pub line: usize,
+    /// Byte range of the name within the whole text.
+    pub start: usize,
+    pub end: usize,
+}
+
+fn definition_patterns() -> &'static [(Regex, SymbolKind)] {
+    static PATTERNS: OnceLock<Vec<(Regex, SymbolKind)>> = OnceLock::new();
+    PATTERNS.get_or_init(|| {
+        const VIS: &str = r"(?:pub(?:\([^)]*\))?\s+|export\s+(?:default\s+)?)?";
+        [
+            // Rust
+            (
+                r#"(?:(?:const|async|unsafe|extern\s+"[^"]*")\s+)*fn\s+(?P<name>[A-Za-z_]\w*)"#,
+                SymbolKind::Function,
+            ),
+            (
+                r"(?:struct|enum|union|trait|type)\s+(?P<name>[A-Za-z_]\w*)",
+                SymbolKind::Type,
+            ),
+            (
+                r"(?:const|static(?:\s+mut)?)\s+(?P<name>[A-Za-z_]\w*)\s*:",
+                SymbolKind::Constant,
+            ),
+            (r"mod\s+(?P<name>[A-Za-z_]\w*)", SymbolKind::Module),
+            (r"macro_rules!\s*(?P<name>[A-Za-z_]\w*)", SymbolKind::Macro),
+            // Python
+            (
+                r"(?:async\s+)?def\s+(?P<name>[A-Za-z_]\w*)\s*\(",
+                SymbolKind::Function,
+            ),
+            (r"class\s+(?P<name>[A-Za-z_$][\w$]*)", SymbolKind::Type),
+            // JavaScript / TypeScript
+            (
+                r"(?:async\s+)?function\*?\s+(?P<name>[A-Za-z_$][\w$]*)",
+                SymbolKind::Function,
+            ),
+            (
+                r"(?:abstract\s+)?(?:interface|enum)\s+(?P<name>[A-Za-z_$][\w$]*)",
+                SymbolKind::Type,
+            ),
+            (
+                r"(?:const|var)\s+(?P<name>[A-Za-z_$][\w$]*)\s*(?::[^=]+)?=",
+                SymbolKind::Constant,
+            ),
+            // Go
+            (
+                r"func\s+(?:\([^)]*\)\s*)?(?P<name>[A-Za-z_]\w*)\s*[\[(]",
+                SymbolKind::Function,
+            ),
+            // Slint
+            (r"component\s+(?P<name>[A-Za-z_][\w-]*)", SymbolKind::Type),
+        ]
+        .into_iter()
+        .map(|(pattern, kind)| {
+            let re = Regex::new(&format!(r"^\s*{VIS}{pattern}")).expect("valid symbol pattern");
+            (re, kind)
+        })
+        .collect()
+    })
+}
+
+/// Definitions in `text`, one per line at most, in order.
+///
+/// Matching is by line shape (`fn name`, `class Name`, `export const name =`, …) for
+/// Rust, Python, JavaScript / TypeScript, Go and Slint, so it works on mixed text like a
+/// generated output.
+#[must_use]
+pub fn symbol_definitions(text: &str) -> Vec<SymbolDefinition> {
+    let mut out = Vec::new();
+    let mut offset = 0;
+    for (line, content) in text.split('\n').enumerate() {
+        let found = definition_patterns().iter().find_map(|(re, kind)| {
+            re.captures(content)
+                .and_then(|c| c.name("name"))
+                .map(|m| (m, *kind))
+        });
+        if let Some((m, kind)) = found {
+            out.push(SymbolDefinition {
+                name: m.as_str().to_string(),
+                kind,
+                line,
+                start: offset + m.start(),
+                end: offset + m.end(),
+            });
+        }
+        offset += content.len() + 1;
+    }
+    out
+}
+
+/// The definition of `name` in `text`: the first exact match, else the first that
+/// matches ignoring ASCII case.
+#[must_use]
+pub fn find_definition(text: &str, name: &str) -> Option<SymbolDefinition> {
+    let name = name.trim();
+    if name.is_empty() {
+        return None;
+    }
+    let defs = symbol_definitions(text);
+    let exact = defs.iter().position(|d| d.name == name);
+    let index = exact.or_else(|| defs.iter().position(|d| d.name.eq_ignore_ascii_case(name)))?;
+    defs.into_iter().nth(index)
+}
+
+/// Where a symbol is defined among a project's files.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SymbolLocation {
+    /// `/`-separated, relative to the project root.
+    pub path: String,
+    pub kind: SymbolKind,
+    /// 0-based line.
+    pub line: usize,
+}
+
+/// Definitions of a set of files by symbol name.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SymbolIndex {
+    symbols: BTreeMap<String, Vec<SymbolLocation>>,
+}
+
+impl SymbolIndex {
+    /// Index the readable text files among `files` (absolute, under `project_root`).
+    #[must_use]
+    pub fn build(project_root: &Path, files: &[PathBuf]) -> Self {
+        let mut index = Self::default();
+        for file in files {
+            let Ok(text) = fs::read_to_string(file) else {
+                continue;
+            };
+            let rel = file
+                .strip_prefix(project_root)
+                .map_or_else(|_| path_to_unix(file), path_to_unix);
+            index.add_file(&rel, &text);
+        }
+        index
+    }
+
+    /// Add the definitions of one file's `text`.
+    pub fn add_file(&mut self, path: &str, text: &str) {
+        for def in symbol_definitions(text) {
+            self.symbols
+                .entry(def.name)
+                .or_default()
+                .push(SymbolLocation {
+                    path: path.to_string(),
+                    kind: def.kind,
+                    line: def.line,
+                });
+        }
+    }
+
+    /// Where `name` is defined, in indexing order.
+    #[must_use]
+    pub fn find(&self, name: &str) -> &[SymbolLocation] {
+        self.symbols.get(name).map_or(&[], Vec::as_slice)
+    }
+
+    /// Files defining `name`, sorted, without duplicates.
+    #[must_use]
+    pub fn files_defining(&self, name: &str) -> Vec<String> {
+        let mut files: Vec<String> = self.find(name).iter().map(|l| l.path.clone()).collect();
+        files.sort();
+        files.dedup();
+        files
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.symbols.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.symbols.is_empty()
+    }
+}
@@ -0,0 +1,350 @@
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+    sync::atomic::AtomicBool,
+};
+
+use serde::Deserialize;
+
+use crate::core::{
+    CompressedFormat, DEFAULT_EXCLUDE_DIRS, DEFAULT_EXCLUDE_FILES, GenerationError,
+    GenerationRequest, OutputParts, PostProcess, Profile, Provenance, SampleOmission, ScanResult,
+    ScanStats, WorkspaceSettings, apply_directory_samples, apply_pinned_files,
+    collect_selected_paths, compress_bundle, display_path, load_local_settings, load_profile,
+    load_workspace, parse_extension_filters, path_to_unix, project_path_from_rel,
+    render_dependencies_section, render_hierarchy, render_notes, render_unselected_dirs_summary,
+    run_request, scan_dir_to_node_with_name_matching, split_prefix_list,
+    summarize_unselected_top_level_dirs, workspace_dir, write_target,
+};
+
+/* =================================== Tasks ================================== */
+
+/// What a task writes to its output path.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TaskFormat {
+    /// The output as generated.
+    #[default]
+    Text,
+    /// The output gzip-compressed, as "Export Compressed" writes a `.md.gz`.
+    Gzip,
+    /// The output and the hierarchy in a `.zip`, as "Export Compressed" writes one.
+    Zip,
+}
+
+impl TaskFormat {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Text => "text",
+            Self::Gzip => "gzip",
+            Self::Zip => "zip",
+        }
+    }
+}
+
+/// A named bundle recipe from `.stitchworkspace/tasks.toml` (`[tasks.<name>]`).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Task {
+    /// Profile whose settings and selection are used; unset = the saved workspace
+    /// settings with every file that passes the filters selected.
+    pub profile: Option<String>,
+    /// Where the bundle is written, relative to the project root; unset = printed
+    /// (`stitch run`) or copied ("Run Task").
+    pub output: Option<String>,
+    pub format: TaskFormat,
+    /// Command the output is piped through, instead of the profile's `post_process`.
+    pub post_process: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TaskFile {
+    #[serde(default)]
+    tasks: BTreeMap<String, Task>,
+}
+
+/// A finished task.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaskRun {
+    pub text: String,
+    /// Files emitted.
+    pub files: usize,
+    /// Where the bundle was written; `None` when the task has no output path.
+    pub written: Option<PathBuf>,
+}
+
+#[must_use]
+pub fn tasks_file(project_root: &Path) -> PathBuf {
+    workspace_dir(project_root).join("tasks.toml")
+}
+
+/// Tasks defined in `text` (the contents of a tasks file), by name.
+///
+/// # Errors
+/// Returns the TOML error, or unknown keys, as a one-line message.
+pub fn parse_tasks(text: &str) -> Result<BTreeMap<String, Task>, String> {
+    toml::from_str::<TaskFile>(text)
+        .map(|file| file.tasks)
+        .map_err(|e| e.message().trim().to_string())
+}
+
+/// The project's tasks, by name; empty if it has no [`tasks_file`].
+///
+/// # Errors
+/// Returns a one-line message when the file can't be read or parsed.
+pub fn load_tasks(project_root: &Path) -> Result<BTreeMap<String, Task>, String> {
+    let path = tasks_file(project_root);
+    let text = match fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(BTreeMap::new()),
+        Err(e) => return Err(format!("can't read {}: {e}", display_path(&path))),
+    };
+    parse_tasks(&text).map_err(|e| format!("{}: {e}", display_path(&path)))
+}
+
+/// Run the task `name` of the project at `project_root`; see [`run_project_task`].
+///
+/// # Errors
+/// Returns a one-line message when the tasks file is invalid, there is no such task, or
+/// the task fails.
+pub fn run_task(project_root: &Path, name: &str) -> Result<TaskRun, String> {
+    let tasks = load_tasks(project_root)?;
+    let task = tasks.get(name).ok_or_else(|| {
+        format!(
+            "no task named '{name}' in {}",
+            display_path(&tasks_file(project_root))
+        )
+    })?;
+    run_project_task(project_root, task).map_err(|e| format!("task '{name}': {e}"))
+}
+
+/// Generate `task`'s bundle without the UI and write it to the task's output path.
+///
+/// The output is what "Generate" shows for the same profile and settings, NOTES and the
+/// "Hierarchy Only" / "Directories Only" toggles included. Pipeline commands and the
+/// post-processor run only when the local settings allow external commands.
+///
+/// # Errors
+/// Returns a one-line message when the profile doesn't exist, the output path is outside
+/// the project, nothing is selected, the task's post-processor isn't allowed to run, or
+/// the output can't be written.
+#[allow(clippy::too_many_lines)]
+pub fn run_project_task(project_root: &Path, task: &Task) -> Result<TaskRun, String> {
+    if task.output.is_none() && task.format != TaskFormat::Text {
+        return Err(format!(
+            "format '{}' needs an output path",
+            task.format.as_str()
+        ));
+    }
+    let output_path = task
+        .output
+        .as_deref()
+        .map(|rel| write_target(project_root, rel).map_err(|e| format!("output: {e}")))
+        .transpose()?;
+    let profile = match task.profile.as_deref() {
+        Some(name) => Some(
+            load_profile(project_root, name)
+                .map(|(profile, _)| profile)
+                .ok_or_else(|| format!("no profile named '{name}' in this project"))?,
+        ),
+        None => None,
+    };
+    let mut settings = profile.as_ref().map_or_else(
+        || {
+            load_workspace(project_root).unwrap_or_else(|| WorkspaceSettings {
+                exclude_dirs: DEFAULT_EXCLUDE_DIRS.to_string(),
+                exclude_files: DEFAULT_EXCLUDE_FILES.to_string(),
+                ..WorkspaceSettings::default()
+            })
+        },
+        |p| p.settings.clone(),
+    );
+    if let Some(command) = task.post_process.as_deref() {
+        if !load_local_settings(project_root).is_some_and(|ls| ls.external_commands) {
+            return Err(
+                "post_process needs external commands enabled in the local settings".to_string(),
+            );
+        }
+        settings.post_process = Some(PostProcess {
+            command: command.to_string(),
+            timeout_secs: None,
+        });
+    }
+
+    let dirs_only = settings.dirs_only;
+    let hierarchy_only = settings.hierarchy_only || dirs_only;
+    let selection = select_task_files(project_root, &settings, profile.as_ref());
+    let (shown, empty) = if dirs_only {
+        (&selection.relative_dirs, selection.relative_dirs.is_empty())
+    } else {
+        (&selection.relative_paths, selection.files.is_empty())
+    };
+    if empty {
+        return Err("nothing is selected".to_string());
+    }
+    let hierarchy = render_hierarchy(
+        shown,
+        &root_name(project_root),
+        &settings.path_rewrite,
+        settings.tree_style,
+    );
+    let notes = render_notes(
+        &settings,
+        &selection.stats,
+        project_root,
+        shown,
+        &selection.sample_omissions,
+    );
+    let mut parts = OutputParts {
+        hierarchy: Some(hierarchy.clone()),
+        unselected_directories: selection.unselected_directories.filter(|_| !hierarchy_only),
+        dependencies: (settings.dependencies_section && !dirs_only)
+            .then(|| render_dependencies_section(project_root))
+            .flatten(),
+        notes: (!notes.trim().is_empty()).then(|| notes + "\n"),
+        ..OutputParts::default()
+    };
+    if let Some(format) = settings.layout.provenance_format() {
+        let provenance = Provenance::new(
+            project_root,
+            task.profile.as_deref(),
+            settings.deterministic,
+        )
+        .with_sources(
+            project_root,
+            format,
+            &load_local_settings(project_root)
+                .map(|ls| ls.provenance_env)
+                .unwrap_or_default(),
+        );
+        parts.provenance = Some(provenance.render(format));
+    }
+
+    let file_count = if hierarchy_only {
+        0
+    } else {
+        selection.files.len()
+    };
+    let request = GenerationRequest::from_settings(project_root, selection.files, parts, &settings);
+    let text = match run_request(request, &AtomicBool::new(false), |_| {}) {
+        Ok(output) => output.text,
+        Err(GenerationError::Cancelled) => return Err("generation was cancelled".to_string()),
+        Err(GenerationError::Failed(detail)) => return Err(detail),
+    };
+    let written = match output_path {
+        Some(path) => Some(write_task_output(&path, task.format, &text, &hierarchy)?),
+        None => None,
+    };
+    Ok(TaskRun {
+        text,
+        files: file_count,
+        written,
+    })
+}
+
+struct TaskSelection {
+    files: Vec<PathBuf>,
+    relative_paths: Vec<String>,
+    /// Selected directories, for "Directories Only".
+    relative_dirs: Vec<String>,
+    sample_omissions: Vec<SampleOmission>,
+    unselected_directories: Option<String>,
+    stats: ScanStats,
+}
+
+/// Scan with `settings` and apply `profile`'s checks, samples and pins (or select the
+/// whole tree without a profile).
+fn select_task_files(
+    project_root: &Path,
+    settings: &WorkspaceSettings,
+    profile: Option<&Profile>,
+) -> TaskSelection {
+    let names = |raw: &str| -> HashSet<String> { split_prefix_list(raw).into_iter().collect() };
+    let (include_exts, exclude_exts) = parse_extension_filters(&settings.ext_filter);
+    let mut exclude_dirs = names(&settings.exclude_dirs);
+    exclude_dirs.insert(".stitchworkspace".to_string());
+    let exclude_files = names(&settings.exclude_files);
+    let rules = profile.map_or(&[][..], |p| &p.dir_rules);
+    let ScanResult { node: root, stats } = scan_dir_to_node_with_name_matching(
+        project_root,
+        &include_exts,
+        &exclude_exts,
+        &exclude_dirs,
+        &exclude_files,
+        rules,
+        settings.name_matching,
+    );
+
+    let explicit: HashMap<PathBuf, bool> = profile.map_or_else(
+        || HashMap::from([(project_root.to_path_buf(), true)]),
+        |p| {
+            p.explicit
+                .iter()
+                .map(|sel| (project_path_from_rel(project_root, &sel.path), sel.state))
+                .collect()
+        },
+    );
+    let mut files = Vec::new();
+    let mut dirs = Vec::new();
+    collect_selected_paths(&root, &explicit, None, &mut files, &mut dirs);
+    let mut sample_omissions = Vec::new();
+    if let Some(p) = profile {
+        (files, sample_omissions) = apply_directory_samples(project_root, &files, &p.samples);
+        files = apply_pinned_files(project_root, &files, &p.pinned);
+    }
+
+    let relative = |paths: &[PathBuf]| -> Vec<String> {
+        paths
+            .iter()
+            .filter_map(|f| f.strip_prefix(project_root).ok())
+            .filter(|r| !r.as_os_str().is_empty())
+            .map(path_to_unix)
+            .collect()
+    };
+    let relative_paths = relative(&files);
+    let relative_dirs = relative(&dirs);
+    let unselected_directories = settings
+        .summarize_unselected
+        .then(|| summarize_unselected_top_level_dirs(&root, &explicit))
+        .filter(|dirs| !dirs.is_empty())
+        .map(|dirs| render_unselected_dirs_summary(&dirs));
+    TaskSelection {
+        files,
+        relative_paths,
+        relative_dirs,
+        sample_omissions,
+        unselected_directories,
+        stats,
+    }
+}
+
+fn root_name(project_root: &Path) -> String {
+    project_root
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string()
+}
+
+fn write_task_output(
+    path: &Path,
+    format: TaskFormat,
+    text: &str,
+    hierarchy: &str,
+) -> Result<PathBuf, String> {
+    let data = match format {
+        TaskFormat::Text => text.as_bytes().to_vec(),
+        TaskFormat::Gzip => compress_bundle(CompressedFormat::Gzip, text, None)
+            .map_err(|e| format!("can't compress the output: {e}"))?,
+        TaskFormat::Zip => compress_bundle(CompressedFormat::Zip, text, Some(hierarchy))
+            .map_err(|e| format!("can't compress the output: {e}"))?,
+    };
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("can't create {}: {e}", display_path(dir)))?;
+    }
+    fs::write(path, data).map_err(|e| format!("can't write {}: {e}", display_path(path)))?;
+    Ok(path.to_path_buf())
+}
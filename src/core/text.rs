@@ -1,5 +1,12 @@
-use regex::Regex;
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
 use std::collections::{BTreeMap, HashSet};
+use std::fmt::Write as _;
+use std::hash::{Hash, Hasher};
+use std::time::{Duration, Instant};
+
+use crate::core::UnselectedDir;
 
 // ============================== Unicode glyphs ===============================
 // Box-drawing characters used for tree parsing/rendering
@@ -12,6 +19,40 @@ pub const GLYPH_BRANCH_TEE: &str = "├── ";
 pub const GLYPH_VERT_PREFIX: &str = "│   ";
 pub const GLYPH_INDENT: &str = "    ";
 
+// ASCII branches for destinations that mangle Unicode (e-mail, some terminals)
+pub const ASCII_BRANCH_END: &str = "`-- ";
+pub const ASCII_BRANCH_TEE: &str = "|-- ";
+pub const ASCII_VERT_PREFIX: &str = "|   ";
+
+/// Branch characters of rendered trees.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TreeStyle {
+    /// Box drawing (`├── `, `└── `, `│   `).
+    #[default]
+    Unicode,
+    /// `|-- `, `` `-- ``, `|   `.
+    Ascii,
+}
+
+impl TreeStyle {
+    /// Tee, end and vertical-continuation prefixes, each four columns wide.
+    const fn branches(self) -> (&'static str, &'static str, &'static str) {
+        match self {
+            Self::Unicode => (GLYPH_BRANCH_TEE, GLYPH_BRANCH_END, GLYPH_VERT_PREFIX),
+            Self::Ascii => (ASCII_BRANCH_TEE, ASCII_BRANCH_END, ASCII_VERT_PREFIX),
+        }
+    }
+}
+
+/// Whether `ch` can be part of a tree line's branch prefix (either style).
+const fn is_tree_glyph(ch: char) -> bool {
+    matches!(
+        ch,
+        GLYPH_VERT | GLYPH_END | GLYPH_TEE | GLYPH_HORI | '|' | '`' | '-'
+    )
+}
+
 // Common UI glyphs shared by multiple components
 pub const GLYPH_BULLET: &str = "•";
 pub const GLYPH_ELLIPSIS: &str = "…";
@@ -30,53 +71,42 @@ pub fn repair_mojibake_if_present(input: &str) -> String {
 
 /* =========================== Parsing & Text utils =========================== */
 
+/// Relative paths listed in a tree pasted as text; the first line is the root folder.
+///
+/// Accepts the trees Stitch renders (Unicode or ASCII branches, four columns per
+/// level) and plain indented listings, whose indentation width is detected from the
+/// lines themselves. A trailing `/` on directory names is ignored.
 #[must_use]
 pub fn parse_hierarchy_text(text: &str) -> Option<HashSet<String>> {
     let normalized_input = normalize_mojibake_tree_input(text);
     let mut lines = normalized_input.lines();
     let _root = lines.next()?;
 
+    // (column of the name, whether only whitespace comes before it, name)
+    let entries: Vec<(usize, bool, &str)> = lines
+        .filter_map(|raw| {
+            let line = raw.trim_end();
+            let (name_char_idx, name_byte_idx) = tree_line_name_start(line);
+            let name = line[name_byte_idx..].trim().trim_end_matches('/');
+            let indent_only = line[..name_byte_idx].chars().all(char::is_whitespace);
+            (!name.is_empty()).then_some((name_char_idx, indent_only, name))
+        })
+        .collect();
+
+    let indented = entries.iter().all(|e| e.1) && entries.iter().any(|e| e.0 > 0);
+    let base = entries.iter().map(|e| e.0).min().unwrap_or(0);
+    let width = entries.iter().map(|e| e.0 - base).fold(0, gcd).max(1);
+
     let mut paths: HashSet<String> = HashSet::new();
     let mut parts: Vec<String> = Vec::new();
 
-    for raw in lines {
-        let line = raw.trim_end();
-        if line.is_empty() {
-            continue;
-        }
-
-        let mut name_char_idx: Option<usize> = None;
-        let mut name_byte_idx: usize = 0;
-        let mut byte_pos: usize = 0;
-
-        for (i, ch) in line.chars().enumerate() {
-            if ch != GLYPH_VERT
-                && ch != GLYPH_END
-                && ch != GLYPH_TEE
-                && ch != GLYPH_HORI
-                && !ch.is_whitespace()
-            {
-                name_char_idx = Some(i);
-                name_byte_idx = byte_pos;
-                break;
-            }
-            byte_pos += ch.len_utf8();
-        }
-        let Some(name_char_idx) = name_char_idx else {
-            continue;
-        };
-
-        let level = if name_char_idx > 0 {
-            (name_char_idx.saturating_sub(1)) / 4
+    for (name_char_idx, _, name) in entries {
+        let level = if indented {
+            (name_char_idx - base) / width
         } else {
-            0
+            name_char_idx.saturating_sub(1) / 4
         };
 
-        let name = line[name_byte_idx..].trim();
-        if name.is_empty() {
-            continue;
-        }
-
         if parts.len() > level {
             parts.truncate(level);
         }
@@ -89,6 +119,37 @@ pub fn parse_hierarchy_text(text: &str) -> Option<HashSet<String>> {
     Some(paths)
 }
 
+/// Char and byte index where the name of a tree line starts.
+///
+/// The name starts at the first character that can't be part of a branch prefix.
+/// Prefixes end in a space, so when an ASCII branch character comes right before it,
+/// the name itself starts with `-`, `|` or `` ` `` (after the last space).
+fn tree_line_name_start(line: &str) -> (usize, usize) {
+    let mut name_char_idx = 0;
+    let mut name_byte_idx = line.len();
+    let mut after_space = (0, 0);
+    for (i, (byte, ch)) in line.char_indices().enumerate() {
+        name_char_idx = i + 1;
+        if !is_tree_glyph(ch) && !ch.is_whitespace() {
+            name_char_idx = i;
+            name_byte_idx = byte;
+            break;
+        }
+        if ch.is_whitespace() {
+            after_space = (i + 1, byte + ch.len_utf8());
+        }
+    }
+    if line[..name_byte_idx].ends_with(['-', '|', '`']) {
+        after_space
+    } else {
+        (name_char_idx, name_byte_idx)
+    }
+}
+
+const fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
 #[must_use]
 pub fn split_prefix_list(raw: &str) -> Vec<String> {
     raw.split(',')
@@ -99,6 +160,16 @@ pub fn split_prefix_list(raw: &str) -> Vec<String> {
 
 #[must_use]
 pub fn render_unicode_tree_from_paths(paths: &[String], root_name: Option<&str>) -> String {
+    render_tree_from_paths(paths, root_name, TreeStyle::Unicode)
+}
+
+/// Tree of `paths` (`/`-separated, sorted per level) under `root_name`, drawn in `style`.
+#[must_use]
+pub fn render_tree_from_paths(
+    paths: &[String],
+    root_name: Option<&str>,
+    style: TreeStyle,
+) -> String {
     #[derive(Default)]
     struct T {
         children: BTreeMap<String, Box<T>>,
@@ -112,27 +183,20 @@ pub fn render_unicode_tree_from_paths(paths: &[String], root_name: Option<&str>)
             insert_path(entry, &parts[1..]);
         }
     }
-    fn render(node: &T, prefix: &mut String, out: &mut String) {
+    fn render(node: &T, style: TreeStyle, prefix: &mut String, out: &mut String) {
+        let (tee, end, vert) = style.branches();
         let len = node.children.len();
         for (idx, (name, child)) in node.children.iter().enumerate() {
             let last = idx + 1 == len;
             out.push_str(prefix);
-            out.push_str(if last {
-                GLYPH_BRANCH_END
-            } else {
-                GLYPH_BRANCH_TEE
-            });
+            out.push_str(if last { end } else { tee });
             out.push_str(name);
             out.push('\n');
 
             if !child.children.is_empty() {
                 let saved = prefix.len();
-                prefix.push_str(if last {
-                    GLYPH_INDENT
-                } else {
-                    GLYPH_VERT_PREFIX
-                });
-                render(child, prefix, out);
+                prefix.push_str(if last { GLYPH_INDENT } else { vert });
+                render(child, style, prefix, out);
                 prefix.truncate(saved);
             }
         }
@@ -150,10 +214,67 @@ pub fn render_unicode_tree_from_paths(paths: &[String], root_name: Option<&str>)
         out.push('\n');
     }
     let mut prefix = String::new();
-    render(&root, &mut prefix, &mut out);
+    render(&root, style, &mut prefix, &mut out);
     out
 }
 
+/// The last rendered hierarchy, keyed by a hash of the sorted paths and root name, so
+/// regenerating an unchanged selection skips rebuilding the tree.
+#[derive(Debug, Default)]
+pub struct HierarchyCache {
+    key: Option<u64>,
+    rendered: String,
+    style: TreeStyle,
+}
+
+impl HierarchyCache {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Draw later trees in `style` (Unicode until set).
+    pub fn set_style(&mut self, style: TreeStyle) {
+        if self.style != style {
+            self.style = style;
+            self.key = None;
+        }
+    }
+
+    /// Same output as [`render_tree_from_paths`], reusing the previous rendering
+    /// when the paths (in any order) and root name are unchanged.
+    pub fn render(&mut self, paths: &[String], root_name: Option<&str>) -> String {
+        let key = hierarchy_key(paths, root_name);
+        if self.key != Some(key) {
+            self.rendered = render_tree_from_paths(paths, root_name, self.style);
+            self.key = Some(key);
+        }
+        self.rendered.clone()
+    }
+
+    /// Whether the next [`Self::render`] with these arguments is served from the cache.
+    #[must_use]
+    pub fn is_cached(&self, paths: &[String], root_name: Option<&str>) -> bool {
+        self.key == Some(hierarchy_key(paths, root_name))
+    }
+
+    pub fn clear(&mut self) {
+        self.key = None;
+        self.rendered.clear();
+    }
+}
+
+fn hierarchy_key(paths: &[String], root_name: Option<&str>) -> u64 {
+    let mut sorted: Vec<&str> = paths.iter().map(String::as_str).collect();
+    sorted.sort_unstable();
+    let mut h = DefaultHasher::new();
+    root_name.hash(&mut h);
+    for path in &sorted {
+        path.hash(&mut h);
+    }
+    h.finish()
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 enum StripState {
     Normal,
@@ -204,7 +325,13 @@ impl<'a> CommentStripper<'a> {
 
     fn strip(mut self, contents: &str) -> String {
         let mut out = String::with_capacity(contents.len());
-        for line in contents.lines() {
+        let mut lines = contents.lines().peekable();
+        // A `#!` shebang is never a comment, whatever the prefixes.
+        if let Some(first) = lines.next_if(|l| l.starts_with("#!")) {
+            out.push_str(first);
+            out.push('\n');
+        }
+        for line in lines {
             self.process_line(line, &mut out);
         }
         out
@@ -435,6 +562,19 @@ fn push_trimmed_prefix(out: &mut String, line: &str, mut end: usize) {
     out.push_str(&line[..end]);
 }
 
+/// Render unselected top-level directories as one `name/ (N files)` line each.
+#[must_use]
+pub fn render_unselected_dirs_summary(dirs: &[UnselectedDir]) -> String {
+    let mut out = String::new();
+    for d in dirs {
+        let noun = if d.file_count == 1 { "file" } else { "files" };
+        let _ = writeln!(out, "{}/ ({} {noun})", d.name, d.file_count);
+    }
+    out
+}
+
+/// Strip comment lines and inline comments starting with one of `prefixes`. A `#!` first
+/// line is always kept.
 #[must_use]
 pub fn strip_lines_and_inline_comments(contents: &str, prefixes: &[String]) -> String {
     if prefixes.is_empty() {
@@ -443,12 +583,125 @@ pub fn strip_lines_and_inline_comments(contents: &str, prefixes: &[String]) -> S
     CommentStripper::new(prefixes).strip(contents)
 }
 
+/// Comment prefix of shell, Python and Ruby files, by extension.
+pub const SCRIPT_COMMENT_PREFIXES: [(&str, &str); 8] = [
+    ("sh", "#"),
+    ("bash", "#"),
+    ("zsh", "#"),
+    ("py", "#"),
+    ("pyw", "#"),
+    ("rb", "#"),
+    ("rake", "#"),
+    ("gemspec", "#"),
+];
+
+/// Interpreters whose scripts use `#` comments, for extensionless files with a shebang.
+const SCRIPT_INTERPRETERS: [&str; 6] = ["sh", "bash", "zsh", "python", "python3", "ruby"];
+
+/// Comment prefix of a script at `path` (`/`-separated): by extension, or for files
+/// without one, by the interpreter named in a `#!` first line of `contents`.
+#[must_use]
+pub fn script_comment_prefix(path: &str, contents: &str) -> Option<&'static str> {
+    let name = path.rsplit('/').next().unwrap_or(path);
+    if let Some((stem, ext)) = name.rsplit_once('.')
+        && !stem.is_empty()
+    {
+        return SCRIPT_COMMENT_PREFIXES
+            .iter()
+            .find(|(e, _)| e.eq_ignore_ascii_case(ext))
+            .map(|(_, prefix)| *prefix);
+    }
+    let shebang = contents.lines().next()?.strip_prefix("#!")?;
+    let mut words = shebang.split_whitespace();
+    let program = words.next()?.rsplit('/').next()?;
+    let interpreter = if program == "env" {
+        words.find(|w| !w.starts_with('-'))?
+    } else {
+        program
+    };
+    SCRIPT_INTERPRETERS.contains(&interpreter).then_some("#")
+}
+
+/// The prefixes to strip from `path`: `prefixes` plus the file's
+/// [`script_comment_prefix`], which only applies once some prefix is configured.
+#[must_use]
+pub fn prefixes_for_file<'a>(
+    path: &str,
+    contents: &str,
+    prefixes: &'a [String],
+) -> std::borrow::Cow<'a, [String]> {
+    match script_comment_prefix(path, contents) {
+        Some(extra) if !prefixes.is_empty() && !prefixes.iter().any(|p| p == extra) => {
+            let mut all = prefixes.to_vec();
+            all.push(extra.to_string());
+            std::borrow::Cow::Owned(all)
+        }
+        _ => std::borrow::Cow::Borrowed(prefixes),
+    }
+}
+
+/// Upper bound on the compiled size of a user-supplied remove regex.
+pub const REMOVE_REGEX_SIZE_LIMIT: usize = 1 << 20;
+
+/// How long a remove-regex replacement may run on one file before it is stopped.
+pub const REMOVE_REGEX_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Compile a remove regex (wrapped as `(?ms)`) with size limits, returning a
+/// user-facing message on failure.
+///
+/// # Errors
+/// Returns a short description when the pattern is invalid or too complex.
+pub fn compile_remove_regex(raw: &str) -> Result<Regex, String> {
+    RegexBuilder::new(&format!("(?ms){raw}"))
+        .size_limit(REMOVE_REGEX_SIZE_LIMIT)
+        .dfa_size_limit(REMOVE_REGEX_SIZE_LIMIT)
+        .build()
+        .map_err(|e| match e {
+            regex::Error::CompiledTooBig(_) => "regex too complex".to_string(),
+            regex::Error::Syntax(msg) => {
+                let last = msg.lines().last().unwrap_or_default().trim();
+                format!("invalid regex: {}", last.trim_start_matches("error: "))
+            }
+            other => other.to_string(),
+        })
+}
+
 #[must_use]
 pub fn compile_remove_regex_opt(raw: Option<&str>) -> Option<Regex> {
-    raw.and_then(|s| {
-        let pattern = format!("(?ms){s}");
-        Regex::new(&pattern).ok()
-    })
+    raw.and_then(|s| compile_remove_regex(s).ok())
+}
+
+/// A replacement that did not finish within its time limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RegexTimeout;
+
+/// Remove all matches of `re` from `text`, giving up once `timeout` has elapsed.
+///
+/// Runs on the calling thread and checks the deadline between matches; the regex engine
+/// is linear-time, so no single search can run away. `text` is left untouched either way.
+///
+/// # Errors
+/// Returns [`RegexTimeout`] when the replacement takes longer than `timeout`.
+pub fn remove_matches_with_timeout(
+    re: &Regex,
+    text: &str,
+    timeout: Duration,
+) -> Result<String, RegexTimeout> {
+    let deadline = Instant::now() + timeout;
+    let mut out = String::with_capacity(text.len());
+    let mut last = 0;
+    for m in re.find_iter(text) {
+        if Instant::now() >= deadline {
+            return Err(RegexTimeout);
+        }
+        out.push_str(&text[last..m.start()]);
+        last = m.end();
+    }
+    if Instant::now() >= deadline {
+        return Err(RegexTimeout);
+    }
+    out.push_str(&text[last..]);
+    Ok(out)
 }
 
 #[must_use]
@@ -524,3 +777,48 @@ pub fn collapse_consecutive_blank_lines(s: &str) -> String {
     }
     out
 }
+
+/// Prefix each line of `emitted` with its line number in `original` (`42 | code`).
+///
+/// `emitted` is `original` after filtering (removed lines, stripped comments), so lines are
+/// matched in order: a line keeps the number of the next original line it equals or is a
+/// prefix of. Lines with no counterpart (e.g. synthesized by filters) get a blank gutter.
+#[must_use]
+pub fn number_lines_from_original(original: &str, emitted: &str) -> String {
+    let orig: Vec<&str> = original.lines().collect();
+    let width = orig.len().max(1).to_string().len();
+    let mut out = String::with_capacity(emitted.len() + emitted.len() / 4);
+    let mut cursor = 0usize;
+
+    for line in emitted.lines() {
+        let needle = line.trim_end();
+        let found = if needle.is_empty() {
+            orig.get(cursor)
+                .filter(|o| o.trim().is_empty())
+                .map(|_| cursor)
+        } else {
+            orig[cursor.min(orig.len())..]
+                .iter()
+                .position(|o| o.starts_with(needle))
+                .map(|off| cursor + off)
+        };
+        match found {
+            Some(idx) => {
+                cursor = idx + 1;
+                let _ = write!(out, "{:>width$} |", idx + 1);
+            }
+            None => {
+                let _ = write!(out, "{:>width$} |", "");
+            }
+        }
+        if !line.is_empty() {
+            out.push(' ');
+            out.push_str(line);
+        }
+        out.push('\n');
+    }
+    if !emitted.ends_with('\n') && out.ends_with('\n') {
+        out.pop();
+    }
+    out
+}
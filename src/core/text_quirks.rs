@@ -0,0 +1,85 @@
+use serde::{Deserialize, Serialize};
+
+/* ========================== BOMs and line endings ========================== */
+
+const BOM: char = '\u{feff}';
+
+/// A UTF-8 byte order mark and the kinds of line endings found in a file.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[allow(clippy::struct_excessive_bools)] // independent properties of the file, not states
+pub struct TextQuirks {
+    /// Starts with a UTF-8 byte order mark.
+    pub bom: bool,
+    /// Has `\r\n` line endings.
+    pub crlf: bool,
+    /// Has `\n` line endings.
+    pub lf: bool,
+    /// Has lone `\r` line endings (classic Mac OS).
+    pub cr: bool,
+}
+
+impl TextQuirks {
+    #[must_use]
+    pub fn detect(text: &str) -> Self {
+        let bytes = text.as_bytes();
+        let mut quirks = Self {
+            bom: text.starts_with(BOM),
+            ..Self::default()
+        };
+        for (i, b) in bytes.iter().enumerate() {
+            match b {
+                b'\n' if i > 0 && bytes[i - 1] == b'\r' => quirks.crlf = true,
+                b'\n' => quirks.lf = true,
+                b'\r' if bytes.get(i + 1) != Some(&b'\n') => quirks.cr = true,
+                _ => {}
+            }
+        }
+        quirks
+    }
+
+    /// More than one kind of line ending.
+    #[must_use]
+    pub const fn mixed_line_endings(self) -> bool {
+        (self.crlf as u8) + (self.lf as u8) + (self.cr as u8) > 1
+    }
+
+    /// [`normalize_text`] would change the file.
+    #[must_use]
+    pub const fn needs_normalizing(self) -> bool {
+        self.bom || self.crlf || self.cr
+    }
+
+    /// Worth a warning when the file is emitted as is: the BOM ends up in the output and
+    /// mixed endings trip up line-based tools.
+    #[must_use]
+    pub const fn is_suspicious(self) -> bool {
+        self.bom || self.mixed_line_endings()
+    }
+
+    /// E.g. `BOM, mixed line endings` or `CRLF`.
+    #[must_use]
+    pub fn describe(self) -> String {
+        let mut parts = Vec::new();
+        if self.bom {
+            parts.push("BOM");
+        }
+        if self.mixed_line_endings() {
+            parts.push("mixed line endings");
+        } else if self.crlf {
+            parts.push("CRLF");
+        } else if self.cr {
+            parts.push("CR");
+        }
+        parts.join(", ")
+    }
+}
+
+/// `text` without a leading BOM and with `\r\n` and lone `\r` turned into `\n`.
+#[must_use]
+pub fn normalize_text(text: &str) -> String {
+    let text = text.strip_prefix(BOM).unwrap_or(text);
+    if !text.contains('\r') {
+        return text.to_string();
+    }
+    text.replace("\r\n", "\n").replace('\r', "\n")
+}
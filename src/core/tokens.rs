@@ -0,0 +1,433 @@
+use std::{
+    collections::{HashMap, HashSet},
+    hash::{DefaultHasher, Hash, Hasher},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, OnceLock},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::{FileMarkers, display_path};
+
+/* ============================== Token counting ============================== */
+
+/// Marker that opens each file block in the generated output with the default
+/// [`FileMarkers`].
+pub const FILE_START_MARKER: &str = "--- Start of file: ";
+
+/// Split stitched output into independently countable pieces: everything before the
+/// first file block (hierarchy, notes, ...) and then one piece per file block.
+///
+/// Pieces are contiguous and concatenate back to `text`. Assumes the default
+/// [`FileMarkers`]; see [`split_output_segments_with_markers`].
+#[must_use]
+pub fn split_output_segments(text: &str) -> Vec<&str> {
+    split_output_segments_with_markers(text, &FileMarkers::default())
+}
+
+/// [`split_output_segments`] for output whose file blocks were written with `markers`.
+///
+/// A block starts at each line beginning with [`FileMarkers::header_prefix`]; without
+/// such a prefix (e.g. a header of just `{path}`) the output stays one piece.
+#[must_use]
+pub fn split_output_segments_with_markers<'a>(
+    text: &'a str,
+    markers: &FileMarkers,
+) -> Vec<&'a str> {
+    let prefix = markers.header_prefix();
+    let mut segments = Vec::new();
+    let mut start = 0;
+    let mut line_start = 0;
+    for line in text.split_inclusive('\n') {
+        if !prefix.is_empty() && line.starts_with(prefix) && line_start > start {
+            segments.push(&text[start..line_start]);
+            start = line_start;
+        }
+        line_start += line.len();
+    }
+    if start < text.len() {
+        segments.push(&text[start..]);
+    }
+    segments
+}
+
+/// Fast heuristic token count: bytes divided by a per-language bytes-per-token ratio,
+/// applied to each file block by its extension (header text uses the generic ratio).
+///
+/// Typically within ~15% of `o200k_base`; no tokenizer is loaded. Assumes the default
+/// [`FileMarkers`]; see [`estimate_tokens_with_markers`].
+#[must_use]
+pub fn estimate_tokens(text: &str) -> usize {
+    estimate_tokens_with_markers(text, &FileMarkers::default())
+}
+
+/// [`estimate_tokens`] for output whose file blocks were written with `markers`.
+#[must_use]
+pub fn estimate_tokens_with_markers(text: &str, markers: &FileMarkers) -> usize {
+    split_output_segments_with_markers(text, markers)
+        .into_iter()
+        .map(|segment| {
+            let ext = segment_extension(segment, markers).map(str::to_ascii_lowercase);
+            (segment.len() * 10).div_ceil(bytes_per_token_tenths(ext.as_deref()))
+        })
+        .sum()
+}
+
+/// [`estimate_tokens`] for the emitted contents of one file, using the ratio of `path`'s
+/// extension.
+#[must_use]
+pub fn estimate_file_tokens(path: &str, contents: &str) -> usize {
+    let ext = Path::new(path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_ascii_lowercase);
+    (contents.len() * 10).div_ceil(bytes_per_token_tenths(ext.as_deref()))
+}
+
+/// Bytes per token (in tenths) observed for `o200k_base` on typical files of each kind.
+fn bytes_per_token_tenths(ext: Option<&str>) -> usize {
+    match ext {
+        Some(
+            "json" | "lock" | "xml" | "html" | "svg" | "csv" | "toml" | "yaml" | "yml" | "ini",
+        ) => 30,
+        Some("py" | "rb" | "sh" | "sql") => 38,
+        Some("md" | "txt" | "rst" | "adoc") => 41,
+        Some(
+            "rs" | "c" | "h" | "cc" | "cpp" | "hpp" | "cs" | "go" | "java" | "kt" | "swift" | "js"
+            | "jsx" | "ts" | "tsx" | "css" | "scss",
+        ) => 42,
+        // Declarative UI files are indentation-heavy.
+        Some("slint" | "qml") => 46,
+        _ => 37,
+    }
+}
+
+fn segment_extension<'a>(segment: &'a str, markers: &FileMarkers) -> Option<&'a str> {
+    let path = markers.header_path(segment.lines().next()?)?;
+    Path::new(path).extension()?.to_str()
+}
+
+/// Per-segment token counts keyed by content hash, so regenerating the output only
+/// re-tokenizes the files (and header) whose text actually changed.
+#[derive(Debug, Default)]
+pub struct TokenCache {
+    entries: HashMap<u64, usize>,
+    tokenizer: String,
+    markers: FileMarkers,
+}
+
+impl TokenCache {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Count tokens in `text` by summing cached per-segment counts, calling `count` only
+    /// for segments not seen in the previous call. Entries not used by this call are dropped.
+    pub fn count<F>(&mut self, text: &str, count: F) -> usize
+    where
+        F: Fn(&str) -> usize,
+    {
+        let mut used = HashSet::new();
+        let mut total = 0;
+        for segment in split_output_segments_with_markers(text, &self.markers) {
+            let key = content_key(segment);
+            used.insert(key);
+            total += *self.entries.entry(key).or_insert_with(|| count(segment));
+        }
+        self.entries.retain(|k, _| used.contains(k));
+        total
+    }
+
+    /// Number of cached segments.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Split counted text at file blocks written with `markers` instead of the defaults.
+    pub fn set_markers(&mut self, markers: &FileMarkers) {
+        markers.clone_into(&mut self.markers);
+    }
+
+    /// Tie the cached counts to tokenizer `key`, dropping them if it differs from last time.
+    pub fn set_tokenizer(&mut self, key: &str) {
+        if self.tokenizer != key {
+            self.entries.clear();
+            key.clone_into(&mut self.tokenizer);
+        }
+    }
+}
+
+/* ============================== Context window ============================== */
+
+/// Model context-window preset; output above it is flagged as over budget.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub enum ContextWindow {
+    #[default]
+    #[serde(rename = "off")]
+    Off,
+    #[serde(rename = "128k")]
+    K128,
+    #[serde(rename = "200k")]
+    K200,
+    #[serde(rename = "1m")]
+    M1,
+}
+
+impl ContextWindow {
+    /// Window size in tokens; `None` when no budget is set.
+    #[must_use]
+    pub const fn tokens(self) -> Option<usize> {
+        match self {
+            Self::Off => None,
+            Self::K128 => Some(128_000),
+            Self::K200 => Some(200_000),
+            Self::M1 => Some(1_000_000),
+        }
+    }
+
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Off => "off",
+            Self::K128 => "128k",
+            Self::K200 => "200k",
+            Self::M1 => "1M",
+        }
+    }
+
+    /// Position in the UI preset selector (Off, 128k, 200k, 1M).
+    #[must_use]
+    pub const fn index(self) -> i32 {
+        match self {
+            Self::Off => 0,
+            Self::K128 => 1,
+            Self::K200 => 2,
+            Self::M1 => 3,
+        }
+    }
+
+    #[must_use]
+    pub const fn from_index(index: i32) -> Self {
+        match index {
+            1 => Self::K128,
+            2 => Self::K200,
+            3 => Self::M1,
+            _ => Self::Off,
+        }
+    }
+
+    /// Whether `tokens` doesn't fit in this window.
+    #[must_use]
+    pub fn is_exceeded_by(self, tokens: usize) -> bool {
+        self.tokens().is_some_and(|limit| tokens > limit)
+    }
+}
+
+/// Files listed in the over-budget note.
+pub const OVER_BUDGET_LARGEST_FILES: usize = 5;
+
+/// NOTES text for output that doesn't fit `window`: the overshoot and the largest
+/// files (`(path, tokens)`, in any order) as trimming candidates.
+///
+/// `None` when the output fits or no window is selected.
+#[must_use]
+pub fn over_budget_note(
+    tokens: usize,
+    window: ContextWindow,
+    file_tokens: &[(String, usize)],
+) -> Option<String> {
+    use std::fmt::Write;
+
+    let limit = window.tokens().filter(|&limit| tokens > limit)?;
+    let mut note = format!(
+        "Over the {} context window: ~{tokens} tokens (~{} too many).\n",
+        window.label(),
+        tokens - limit
+    );
+
+    let mut largest: Vec<&(String, usize)> = file_tokens.iter().collect();
+    largest.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    if !largest.is_empty() {
+        note.push_str("Largest files:\n");
+        for (path, file_tokens) in largest.into_iter().take(OVER_BUDGET_LARGEST_FILES) {
+            let _ = writeln!(note, "- {path} (~{file_tokens} tokens)");
+        }
+    }
+    note.push_str(
+        "To trim: exclude some of these, remove comments, or use Rust function signatures only.\n",
+    );
+    Some(note)
+}
+
+/* ================================ Tokenizers ================================ */
+
+/// Tokenizer used when the setting is empty.
+pub const DEFAULT_TOKENIZER: &str = "o200k_base";
+
+/// tiktoken encodings available with the `tokens` feature.
+pub const BUILTIN_TOKENIZERS: [&str; 2] = ["o200k_base", "cl100k_base"];
+
+/// A tokenizer named in the per-profile `tokenizer` setting.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TokenizerSpec {
+    /// One of [`BUILTIN_TOKENIZERS`].
+    Builtin(&'static str),
+    /// A Hugging Face `tokenizer.json` (as shipped with Llama, Mistral, ...);
+    /// relative paths are resolved against the project root.
+    HuggingFace(PathBuf),
+}
+
+impl TokenizerSpec {
+    /// Parse a setting value: empty, a builtin encoding name, or a path to a `.json` tokenizer.
+    ///
+    /// # Errors
+    /// Returns a message naming the accepted values.
+    pub fn parse(raw: &str) -> Result<Self, String> {
+        let raw = raw.trim();
+        if raw.is_empty() {
+            return Ok(Self::Builtin(DEFAULT_TOKENIZER));
+        }
+        if let Some(name) = BUILTIN_TOKENIZERS
+            .iter()
+            .find(|name| name.eq_ignore_ascii_case(raw))
+        {
+            return Ok(Self::Builtin(name));
+        }
+        if Path::new(raw)
+            .extension()
+            .is_some_and(|e| e.eq_ignore_ascii_case("json"))
+        {
+            return Ok(Self::HuggingFace(PathBuf::from(raw)));
+        }
+        Err(format!(
+            "unknown tokenizer '{raw}' (use {} or a path to tokenizer.json)",
+            BUILTIN_TOKENIZERS.join(", ")
+        ))
+    }
+
+    /// Short name for the stats line.
+    #[must_use]
+    pub fn label(&self) -> String {
+        match self {
+            Self::Builtin(name) => (*name).to_string(),
+            Self::HuggingFace(path) => path.file_stem().map_or_else(
+                || path.display().to_string(),
+                |stem| {
+                    // "mistral-7b.tokenizer.json" reads better than "tokenizer"
+                    let stem = stem.to_string_lossy();
+                    let parent = path.parent().and_then(Path::file_name);
+                    match parent {
+                        Some(dir) if stem == "tokenizer" => dir.to_string_lossy().into_owned(),
+                        _ => stem.into_owned(),
+                    }
+                },
+            ),
+        }
+    }
+}
+
+/// Counts tokens for one model family.
+pub trait TokenCounter: Send + Sync {
+    fn count(&self, text: &str) -> usize;
+}
+
+type CounterCache = Mutex<HashMap<String, Arc<dyn TokenCounter>>>;
+
+/// Load the counter for `spec`, reusing it across calls (loading a vocabulary is slow).
+///
+/// Returns the counter and a key identifying it (for [`TokenCache::set_tokenizer`]).
+///
+/// # Errors
+/// Fails if the tokenizer's feature is not compiled in or its file can't be loaded.
+pub fn load_tokenizer(
+    spec: &TokenizerSpec,
+    project_root: &Path,
+) -> Result<(String, Arc<dyn TokenCounter>), String> {
+    static LOADED: OnceLock<CounterCache> = OnceLock::new();
+
+    let key = match spec {
+        TokenizerSpec::Builtin(name) => (*name).to_string(),
+        TokenizerSpec::HuggingFace(path) => project_root.join(path).to_string_lossy().into_owned(),
+    };
+    let loaded = LOADED.get_or_init(CounterCache::default);
+    if let Some(counter) = loaded.lock().ok().and_then(|m| m.get(&key).cloned()) {
+        return Ok((key, counter));
+    }
+
+    let counter: Arc<dyn TokenCounter> = match spec {
+        TokenizerSpec::Builtin(name) => load_tiktoken(name)?,
+        TokenizerSpec::HuggingFace(_) => load_hugging_face(Path::new(&key))?,
+    };
+    if let Ok(mut m) = loaded.lock() {
+        m.insert(key.clone(), Arc::clone(&counter));
+    }
+    Ok((key, counter))
+}
+
+#[cfg(feature = "tokens")]
+struct Tiktoken(tiktoken_rs::CoreBPE);
+
+#[cfg(feature = "tokens")]
+impl TokenCounter for Tiktoken {
+    fn count(&self, text: &str) -> usize {
+        self.0.encode_with_special_tokens(text).len()
+    }
+}
+
+#[cfg(feature = "tokens")]
+fn load_tiktoken(name: &str) -> Result<Arc<dyn TokenCounter>, String> {
+    let bpe = match name {
+        "cl100k_base" => tiktoken_rs::cl100k_base(),
+        _ => tiktoken_rs::o200k_base(),
+    }
+    .map_err(|e| format!("failed to load {name}: {e}"))?;
+    Ok(Arc::new(Tiktoken(bpe)))
+}
+
+#[cfg(not(feature = "tokens"))]
+fn load_tiktoken(name: &str) -> Result<Arc<dyn TokenCounter>, String> {
+    Err(format!("{name} needs the `tokens` feature"))
+}
+
+#[cfg(feature = "hf-tokenizers")]
+struct HuggingFace(tokenizers::Tokenizer);
+
+#[cfg(feature = "hf-tokenizers")]
+impl TokenCounter for HuggingFace {
+    fn count(&self, text: &str) -> usize {
+        self.0.encode(text, false).map_or(0, |e| e.len())
+    }
+}
+
+#[cfg(feature = "hf-tokenizers")]
+fn load_hugging_face(path: &Path) -> Result<Arc<dyn TokenCounter>, String> {
+    let tokenizer = tokenizers::Tokenizer::from_file(path)
+        .map_err(|e| format!("failed to load {}: {e}", display_path(path)))?;
+    Ok(Arc::new(HuggingFace(tokenizer)))
+}
+
+#[cfg(not(feature = "hf-tokenizers"))]
+fn load_hugging_face(path: &Path) -> Result<Arc<dyn TokenCounter>, String> {
+    Err(format!(
+        "{} needs the `hf-tokenizers` feature",
+        display_path(path)
+    ))
+}
+
+fn content_key(segment: &str) -> u64 {
+    let mut h = DefaultHasher::new();
+    segment.len().hash(&mut h);
+    segment.hash(&mut h);
+    h.finish()
+}
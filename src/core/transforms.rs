@@ -0,0 +1,590 @@
+use std::{
+    io::{Read, Write},
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    sync::{Arc, Mutex, OnceLock},
+    thread,
+    time::{Duration, Instant},
+};
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::core::{
+    C_LIKE_EXTENSIONS, CLikeFilterOptions, DEFAULT_TABLE_ROWS, ExpandCache, GoFilterOptions,
+    MACRO_EXPAND_TIMEOUT, REMOVE_REGEX_TIMEOUT, RustFilterOptions, SlintFilterOptions,
+    SqlFilterOptions, WEB_EXTENSIONS, WebFilterOptions, WebLanguage, WorkspaceSettings,
+    apply_c_like_filters, apply_go_filters, apply_rust_filters, apply_slint_filters,
+    apply_sql_filters, apply_web_filters, clean_remove_regex, compile_remove_regex_opt,
+    expand_rust_file, notebook_cells, prefixes_for_file, remove_matches_with_timeout, schema_only,
+    signatures_filter_matches, split_prefix_list, strip_lines_and_inline_comments,
+    summarize_lockfile, table_head,
+};
+
+/* ============================ Transform pipeline ============================ */
+
+/// A named per-file transform.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TransformKind {
+    /// Drop lines and inline comments starting with the "remove prefix" entries.
+    StripPrefixes,
+    /// Remove every match of the "remove regex" (stopped after a timeout).
+    RemoveRegex,
+    /// Comment and signature filters of the Rust options.
+    RustFilters,
+    /// Comment filters of the Slint options.
+    SlintFilters,
+    /// Comment and signature filters of the C, C++, Java and C# options.
+    CLikeFilters,
+    /// Comment and signature filters of the Go options.
+    GoFilters,
+    /// Comment and whitespace filters of the HTML/CSS options.
+    WebFilters,
+    /// Comment and data filters of the SQL options.
+    SqlFilters,
+    /// Reduce JSON and YAML files to their keys, value types and truncated samples; see
+    /// [`schema_only`].
+    SchemaOnly,
+    /// Drop trailing whitespace and blank lines; see [`minify`].
+    Minify,
+    /// Mask values that look like secrets; see [`redact_secrets`].
+    Redact,
+    /// Replace generated files with a one-line summary; see [`collapse_generated`].
+    CollapseGenerated,
+    /// Replace `Cargo.lock`/`package-lock.json` with their direct dependencies and
+    /// resolved versions; see [`summarize_lockfile`].
+    SummarizeLockfiles,
+    /// Keep a CSV/TSV file's header and first `rows` data rows; see [`table_head`].
+    TableHead {
+        #[serde(default = "default_table_rows")]
+        rows: usize,
+    },
+    /// Code cells, and markdown cells if `markdown` is set, of a Jupyter notebook instead
+    /// of its JSON; see [`notebook_cells`].
+    Notebook {
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        markdown: bool,
+    },
+    /// Pipe the contents through a shell command and use its stdout instead; see
+    /// [`run_external_command`]. Failures keep the contents as they were.
+    Command {
+        command: String,
+        /// Overrides [`TransformOptions::command_timeout`].
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        timeout_secs: Option<u64>,
+    },
+    /// Replace a Rust crate root or module with its `cargo expand` output (needs the
+    /// `cargo-expand` feature); see [`expand_rust_file`]. Runs only where commands are
+    /// allowed, and failures keep the source as it was.
+    MacroExpand {
+        /// Overrides [`MACRO_EXPAND_TIMEOUT`].
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        timeout_secs: Option<u64>,
+    },
+}
+
+const fn default_table_rows() -> usize {
+    DEFAULT_TABLE_ROWS
+}
+
+/// One entry of a [`TransformPipeline`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransformStep {
+    #[serde(flatten)]
+    pub kind: TransformKind,
+    /// File extensions the step applies to (`rs` or `.rs`, any case); empty = every file.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extensions: Vec<String>,
+}
+
+impl TransformStep {
+    #[must_use]
+    pub const fn new(kind: TransformKind) -> Self {
+        Self {
+            kind,
+            extensions: Vec::new(),
+        }
+    }
+
+    /// The step limited to files with one of `extensions`.
+    #[must_use]
+    pub fn for_extensions(kind: TransformKind, extensions: &[&str]) -> Self {
+        Self {
+            kind,
+            extensions: extensions.iter().map(|e| (*e).to_string()).collect(),
+        }
+    }
+
+    /// Whether the step runs on `path` (`/`-separated, relative to the project).
+    #[must_use]
+    pub fn applies_to(&self, path: &str) -> bool {
+        if self.extensions.is_empty() {
+            return true;
+        }
+        let name = path.rsplit('/').next().unwrap_or(path);
+        let Some((_, ext)) = name.rsplit_once('.') else {
+            return false;
+        };
+        self.extensions
+            .iter()
+            .any(|e| e.trim().trim_start_matches('.').eq_ignore_ascii_case(ext))
+    }
+}
+
+/// Settings the transforms read; which of them run, and in what order, is up to the pipeline.
+#[derive(Debug, Clone)]
+pub struct TransformOptions {
+    pub remove_prefixes: Vec<String>,
+    /// Compiled remove regex; `None` skips the step.
+    pub remove_regex: Option<Regex>,
+    /// How long the remove regex may run on one file.
+    pub regex_timeout: Duration,
+    pub rust: RustFilterOptions,
+    /// Paths `rust.function_signatures_only` is limited to (see [`signatures_filter_matches`]).
+    /// Blank = every Rust file.
+    pub rust_signatures_filter: String,
+    pub slint: SlintFilterOptions,
+    pub c_like: CLikeFilterOptions,
+    pub go: GoFilterOptions,
+    pub web: WebFilterOptions,
+    pub sql: SqlFilterOptions,
+    /// Run [`TransformKind::Command`] steps; off unless the user opted in locally.
+    pub allow_commands: bool,
+    /// Strip BOMs and turn CRLF/CR line endings into LF before the pipeline runs (see
+    /// [`crate::core::normalize_text`]).
+    pub normalize_text: bool,
+    /// Project root: commands run there and file paths are resolved against it.
+    pub working_dir: PathBuf,
+    /// How long an external command may run on one file.
+    pub command_timeout: Duration,
+    /// Expansions of [`TransformKind::MacroExpand`], shared across generations.
+    pub expand_cache: Arc<Mutex<ExpandCache>>,
+}
+
+impl Default for TransformOptions {
+    fn default() -> Self {
+        Self {
+            remove_prefixes: Vec::new(),
+            remove_regex: None,
+            regex_timeout: REMOVE_REGEX_TIMEOUT,
+            rust: RustFilterOptions::default(),
+            rust_signatures_filter: String::new(),
+            slint: SlintFilterOptions::default(),
+            c_like: CLikeFilterOptions::default(),
+            go: GoFilterOptions::default(),
+            web: WebFilterOptions::default(),
+            sql: SqlFilterOptions::default(),
+            allow_commands: false,
+            normalize_text: false,
+            working_dir: PathBuf::new(),
+            command_timeout: EXTERNAL_COMMAND_TIMEOUT,
+            expand_cache: Arc::default(),
+        }
+    }
+}
+
+impl TransformOptions {
+    /// The options `settings` ask for, with commands allowed only if `allow_commands`.
+    /// An invalid remove regex is skipped; NOTES reports it (see [`crate::core::render_notes`]).
+    #[must_use]
+    pub fn from_settings(
+        project_root: &Path,
+        settings: &WorkspaceSettings,
+        allow_commands: bool,
+    ) -> Self {
+        let raw_regex = clean_remove_regex(&settings.remove_regex);
+        let rust = &settings.rust;
+        Self {
+            remove_prefixes: split_prefix_list(&settings.remove_prefix),
+            remove_regex: compile_remove_regex_opt(
+                Some(raw_regex.as_str()).filter(|raw| !raw.trim().is_empty()),
+            ),
+            rust: RustFilterOptions {
+                remove_inline_regular_comments: rust.rust_remove_inline_comments,
+                remove_doc_comments: rust.rust_remove_doc_comments,
+                function_signatures_only: rust.rust_function_signatures_only,
+            },
+            rust_signatures_filter: if rust.rust_function_signatures_only {
+                rust.rust_signatures_only_filter.clone()
+            } else {
+                String::new()
+            },
+            slint: SlintFilterOptions {
+                remove_line_comments: settings.slint.slint_remove_line_comments,
+                remove_block_comments: settings.slint.slint_remove_block_comments,
+            },
+            c_like: settings.c_like.filter_options(),
+            go: settings.go.filter_options(),
+            web: settings.web.filter_options(),
+            sql: settings.sql.filter_options(),
+            allow_commands,
+            normalize_text: settings.normalize_text,
+            working_dir: project_root.to_path_buf(),
+            ..Self::default()
+        }
+    }
+}
+
+/// Result of running a pipeline on one file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransformOutput {
+    pub contents: String,
+    /// The remove regex hit its timeout; that step was skipped for this file.
+    pub regex_timed_out: bool,
+    /// One line per external command that failed (its step was skipped).
+    pub command_failures: Vec<String>,
+}
+
+/// Ordered list of transforms applied to each file's contents before it is emitted.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct TransformPipeline {
+    pub steps: Vec<TransformStep>,
+}
+
+impl Default for TransformPipeline {
+    /// Prefixes, regex, then the language filters on their own files. The notebook and
+    /// table views change what a file reads like, so they are only run when listed.
+    fn default() -> Self {
+        Self {
+            steps: vec![
+                TransformStep::new(TransformKind::StripPrefixes),
+                TransformStep::new(TransformKind::RemoveRegex),
+                TransformStep::for_extensions(TransformKind::RustFilters, &["rs"]),
+                TransformStep::for_extensions(TransformKind::SlintFilters, &["slint"]),
+                TransformStep::for_extensions(TransformKind::CLikeFilters, C_LIKE_EXTENSIONS),
+                TransformStep::for_extensions(TransformKind::GoFilters, &["go"]),
+                TransformStep::for_extensions(TransformKind::WebFilters, WEB_EXTENSIONS),
+                TransformStep::for_extensions(TransformKind::SqlFilters, &["sql"]),
+            ],
+        }
+    }
+}
+
+impl TransformPipeline {
+    /// Whether this is the built-in order, which workspace files leave out so they pick up
+    /// later changes to it.
+    #[must_use]
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Whether any step runs an external command (`cargo expand` included).
+    #[must_use]
+    pub fn has_commands(&self) -> bool {
+        self.steps.iter().any(|s| {
+            matches!(
+                s.kind,
+                TransformKind::Command { .. } | TransformKind::MacroExpand { .. }
+            )
+        })
+    }
+
+    /// Run the steps that apply to `path` (`/`-separated, relative to the project) in order.
+    ///
+    /// A remove regex that times out, or an external command that fails, leaves the
+    /// contents as they were before that step.
+    #[must_use]
+    pub fn apply(&self, path: &str, contents: String, opts: &TransformOptions) -> TransformOutput {
+        let mut out = TransformOutput {
+            contents,
+            regex_timed_out: false,
+            command_failures: Vec::new(),
+        };
+        for step in self.steps.iter().filter(|s| s.applies_to(path)) {
+            out.contents = match &step.kind {
+                TransformKind::StripPrefixes => {
+                    let prefixes = prefixes_for_file(path, &out.contents, &opts.remove_prefixes);
+                    strip_lines_and_inline_comments(&out.contents, &prefixes)
+                }
+                TransformKind::RemoveRegex => match &opts.remove_regex {
+                    Some(re) => remove_matches_with_timeout(re, &out.contents, opts.regex_timeout)
+                        .unwrap_or_else(|_| {
+                            out.regex_timed_out = true;
+                            out.contents
+                        }),
+                    None => out.contents,
+                },
+                TransformKind::SqlFilters => apply_sql_filters(&out.contents, &opts.sql),
+                TransformKind::RustFilters => {
+                    let mut rust = opts.rust.clone();
+                    if !opts.rust_signatures_filter.trim().is_empty()
+                        && !signatures_filter_matches(path, &opts.rust_signatures_filter)
+                    {
+                        rust.function_signatures_only = false;
+                    }
+                    apply_rust_filters(&out.contents, &rust)
+                }
+                TransformKind::SlintFilters => apply_slint_filters(&out.contents, &opts.slint),
+                TransformKind::CLikeFilters => apply_c_like_filters(&out.contents, &opts.c_like),
+                TransformKind::GoFilters => apply_go_filters(&out.contents, &opts.go),
+                TransformKind::WebFilters => match WebLanguage::from_path(Path::new(path)) {
+                    Some(lang) => apply_web_filters(&out.contents, lang, &opts.web),
+                    None => out.contents,
+                },
+                TransformKind::SchemaOnly => {
+                    schema_only(path, &out.contents).unwrap_or(out.contents)
+                }
+                TransformKind::Minify => minify(&out.contents),
+                TransformKind::Redact => redact_secrets(&out.contents),
+                TransformKind::CollapseGenerated => {
+                    collapse_generated(&out.contents).unwrap_or(out.contents)
+                }
+                TransformKind::SummarizeLockfiles => {
+                    summarize_lockfile(path, &out.contents).unwrap_or(out.contents)
+                }
+                TransformKind::TableHead { rows } => table_head(&out.contents, *rows),
+                TransformKind::Notebook { markdown } => {
+                    notebook_cells(&out.contents, *markdown).unwrap_or(out.contents)
+                }
+                TransformKind::Command { .. } | TransformKind::MacroExpand { .. }
+                    if !opts.allow_commands =>
+                {
+                    out.contents
+                }
+                TransformKind::Command {
+                    command,
+                    timeout_secs,
+                } => {
+                    let timeout = timeout_secs.map_or(opts.command_timeout, Duration::from_secs);
+                    let file = ExternalFile {
+                        path,
+                        full_path: &opts.working_dir.join(path),
+                        working_dir: &opts.working_dir,
+                    };
+                    match run_external_command(command, &out.contents, &file, timeout) {
+                        Ok(replaced) => replaced,
+                        Err(e) => {
+                            out.command_failures.push(format!("`{command}` {e}"));
+                            out.contents
+                        }
+                    }
+                }
+                TransformKind::MacroExpand { timeout_secs } => {
+                    let timeout = timeout_secs.map_or(MACRO_EXPAND_TIMEOUT, Duration::from_secs);
+                    match expand_rust_file(&opts.working_dir, path, &opts.expand_cache, timeout) {
+                        Ok(expanded) => expanded,
+                        Err(e) => {
+                            out.command_failures.push(format!("`cargo expand` {e}"));
+                            out.contents
+                        }
+                    }
+                }
+            };
+        }
+        out
+    }
+}
+
+/// Trim trailing whitespace and drop blank lines. Indentation is kept, since it is
+/// significant in some languages.
+#[must_use]
+pub fn minify(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for line in text.lines().map(str::trim_end).filter(|l| !l.is_empty()) {
+        out.push_str(line);
+        out.push('\n');
+    }
+    out
+}
+
+/// Lines at the top of a file searched for a [`GENERATED_MARKERS`] entry.
+pub const GENERATED_MARKER_LINES: usize = 10;
+
+/// Header markers of generated code, matched case-insensitively (`// @generated`,
+/// Go's `// Code generated ... DO NOT EDIT.`, C#'s `<auto-generated>`, ...).
+pub const GENERATED_MARKERS: [&str; 4] = [
+    "@generated",
+    "do not edit",
+    "<auto-generated",
+    "automatically generated",
+];
+
+/// The marker found in the first [`GENERATED_MARKER_LINES`] lines of `text`, if any.
+#[must_use]
+pub fn generated_marker(text: &str) -> Option<&'static str> {
+    text.lines().take(GENERATED_MARKER_LINES).find_map(|line| {
+        let line = line.to_lowercase();
+        GENERATED_MARKERS
+            .into_iter()
+            .find(|marker| line.contains(marker))
+    })
+}
+
+/// One-line summary replacing a generated file's contents, e.g.
+/// `[generated file collapsed: 1200 lines, marker "@generated"]`; `None` when `text`
+/// has no generated marker.
+#[must_use]
+pub fn collapse_generated(text: &str) -> Option<String> {
+    let marker = generated_marker(text)?;
+    let lines = text.lines().count();
+    let noun = if lines == 1 { "line" } else { "lines" };
+    Some(format!(
+        "[generated file collapsed: {lines} {noun}, marker \"{marker}\"]\n"
+    ))
+}
+
+/// Text that replaces each redacted value.
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Patterns and replacements used by [`redact_secrets`], compiled once.
+fn redactions() -> &'static [(Regex, String)] {
+    static REDACTIONS: OnceLock<Vec<(Regex, String)>> = OnceLock::new();
+    REDACTIONS.get_or_init(|| {
+        let key = r"[\w.-]*(?:password|passwd|secret|token|api[_-]?key|access[_-]?key|private[_-]?key|credentials?)[\w.-]*";
+        [
+            // PEM private keys, keeping the BEGIN/END lines.
+            (
+                r"(?s)(-----BEGIN [A-Z ]*PRIVATE KEY-----).*?(-----END [A-Z ]*PRIVATE KEY-----)"
+                    .to_string(),
+                format!("${{1}}\n{REDACTED}\n${{2}}"),
+            ),
+            // Well-known token shapes (AWS, GitHub, OpenAI-style, Slack, Google).
+            (
+                r"\b(?:AKIA[0-9A-Z]{16}|gh[pousr]_[A-Za-z0-9]{36,}|github_pat_[A-Za-z0-9_]{22,}|sk-[A-Za-z0-9_-]{20,}|xox[abprs]-[A-Za-z0-9-]{10,}|AIza[0-9A-Za-z_-]{35})"
+                    .to_string(),
+                REDACTED.to_string(),
+            ),
+            // Quoted values of secret-looking keys: `api_key = "..."`, `"token": "..."`.
+            (
+                format!(r#"(?i)({key}["']?\s*[:=]\s*)(["'])[^"'\n]+["']"#),
+                format!("${{1}}${{2}}{REDACTED}${{2}}"),
+            ),
+            // Unquoted values in env/ini files: `DB_PASSWORD=hunter2`.
+            (
+                format!(r#"(?im)^(\s*(?:export\s+)?{key}\s*=\s*)[^\s"'#]+"#),
+                format!("${{1}}{REDACTED}"),
+            ),
+        ]
+        .into_iter()
+        .map(|(pattern, replacement)| {
+            (
+                Regex::new(&pattern).expect("built-in redaction pattern"),
+                replacement,
+            )
+        })
+        .collect()
+    })
+}
+
+/// Mask private keys, well-known API token formats and the values assigned to
+/// secret-looking keys (`password`, `token`, `api_key`, ...) with [`REDACTED`].
+#[must_use]
+pub fn redact_secrets(text: &str) -> String {
+    let mut out = text.to_string();
+    for (re, replacement) in redactions() {
+        if let std::borrow::Cow::Owned(replaced) = re.replace_all(&out, replacement.as_str()) {
+            out = replaced;
+        }
+    }
+    out
+}
+
+/* ============================= External commands ============================ */
+
+/// How long an external transform command may run on one file by default.
+pub const EXTERNAL_COMMAND_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The file an external command is run for.
+#[derive(Debug, Clone, Copy)]
+pub struct ExternalFile<'a> {
+    /// `/`-separated, relative to the project; exported as `STITCH_PATH`.
+    pub path: &'a str,
+    /// The file on disk; exported as `STITCH_FILE`.
+    pub full_path: &'a Path,
+    /// Directory the command runs in (empty = the current one).
+    pub working_dir: &'a Path,
+}
+
+/// Run `command` through the platform shell (`sh -c`, `cmd /C` on Windows) with `input`
+/// on stdin, and return its stdout.
+///
+/// The command sees `STITCH_PATH` and `STITCH_FILE` (see [`ExternalFile`]); `input` is the
+/// file's contents after the earlier pipeline steps, which may differ from the file on disk.
+///
+/// # Errors
+/// Returns the rest of a one-line message (`timed out after 10s`, `exited with ...`)
+/// when the command can't start, runs longer than `timeout`, exits unsuccessfully or
+/// prints something other than UTF-8.
+pub fn run_external_command(
+    command: &str,
+    input: &str,
+    file: &ExternalFile<'_>,
+    timeout: Duration,
+) -> Result<String, String> {
+    #[cfg(target_os = "windows")]
+    let mut cmd = {
+        let mut c = Command::new("cmd");
+        c.arg("/C").arg(command);
+        c
+    };
+    #[cfg(not(target_os = "windows"))]
+    let mut cmd = {
+        let mut c = Command::new("sh");
+        c.arg("-c").arg(command);
+        c
+    };
+    if !file.working_dir.as_os_str().is_empty() {
+        cmd.current_dir(file.working_dir);
+    }
+    let mut child = cmd
+        .env("STITCH_PATH", file.path)
+        .env("STITCH_FILE", file.full_path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("could not be started: {e}"))?;
+
+    // Feed and drain the pipes on helper threads so a chatty command can't deadlock us.
+    let stdin = child.stdin.take();
+    let input = input.to_string();
+    thread::spawn(move || {
+        if let Some(mut stdin) = stdin {
+            let _ = stdin.write_all(input.as_bytes());
+        }
+    });
+    let stdout = read_pipe(child.stdout.take());
+    let stderr = read_pipe(child.stderr.take());
+
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if Instant::now() >= deadline => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(format!("timed out after {}s", timeout.as_secs_f64()));
+            }
+            Ok(None) => thread::sleep(Duration::from_millis(5)),
+            Err(e) => return Err(format!("failed: {e}")),
+        }
+    };
+    let stdout = stdout.join().unwrap_or_default();
+    if !status.success() {
+        let stderr = stderr.join().unwrap_or_default();
+        let stderr = String::from_utf8_lossy(&stderr);
+        let exit = status.code().map_or_else(
+            || "was stopped by a signal".to_string(),
+            |code| format!("exited with code {code}"),
+        );
+        return Err(
+            match stderr.lines().map(str::trim).find(|l| !l.is_empty()) {
+                Some(line) => format!("{exit}: {line}"),
+                None => exit,
+            },
+        );
+    }
+    String::from_utf8(stdout).map_err(|_| "printed output that is not UTF-8".to_string())
+}
+
+fn read_pipe<R: Read + Send + 'static>(pipe: Option<R>) -> thread::JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut buf = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut buf);
+        }
+        buf
+    })
+}
@@ -0,0 +1,55 @@
+use crate::core::SelectionStats;
+
+/* =========================== Token budget trimming ========================== */
+
+/// An emitted file the trimming assistant offers to drop.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrimCandidate {
+    /// `/`-separated, relative to the project.
+    pub path: String,
+    /// Estimated tokens of the file's block in the output.
+    pub tokens: usize,
+}
+
+/// The emitted files of `stats`, most tokens first (then by path).
+#[must_use]
+pub fn trim_candidates(stats: &SelectionStats) -> Vec<TrimCandidate> {
+    let mut candidates: Vec<TrimCandidate> = stats
+        .file_tokens()
+        .map(|(path, tokens)| TrimCandidate {
+            path: path.to_string(),
+            tokens,
+        })
+        .collect();
+    candidates.sort_by(|a, b| b.tokens.cmp(&a.tokens).then_with(|| a.path.cmp(&b.path)));
+    candidates
+}
+
+/// Tokens left of `total` once the candidates flagged in `dropped` are left out.
+#[must_use]
+pub fn projected_tokens(total: usize, candidates: &[TrimCandidate], dropped: &[bool]) -> usize {
+    let removed: usize = candidates
+        .iter()
+        .zip(dropped)
+        .filter(|&(_, &drop)| drop)
+        .map(|(c, _)| c.tokens)
+        .sum();
+    total.saturating_sub(removed)
+}
+
+/// Flags for dropping the largest candidates (in order) until `total` fits in `limit`.
+/// Nothing is flagged when it already fits.
+#[must_use]
+pub fn suggest_trim(total: usize, candidates: &[TrimCandidate], limit: usize) -> Vec<bool> {
+    let mut remaining = total;
+    candidates
+        .iter()
+        .map(|c| {
+            let drop = remaining > limit;
+            if drop {
+                remaining = remaining.saturating_sub(c.tokens);
+            }
+            drop
+        })
+        .collect()
+}
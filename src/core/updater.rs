@@ -0,0 +1,77 @@
+use serde::Deserialize;
+
+/* =============================== Update check =============================== */
+
+/// GitHub endpoint for the newest published (non-draft, non-prerelease) release.
+pub const LATEST_RELEASE_URL: &str =
+    "https://api.github.com/repos/gramistella/stitch/releases/latest";
+
+/// A published release as far as the update notice cares.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReleaseInfo {
+    pub version: String,
+    pub url: String,
+}
+
+#[derive(Deserialize)]
+struct GithubRelease {
+    tag_name: String,
+    html_url: String,
+}
+
+/// Parse the JSON body of a GitHub "latest release" response.
+#[must_use]
+pub fn parse_latest_release(json: &str) -> Option<ReleaseInfo> {
+    let release: GithubRelease = serde_json::from_str(json).ok()?;
+    Some(ReleaseInfo {
+        version: release.tag_name.trim_start_matches('v').to_string(),
+        url: release.html_url,
+    })
+}
+
+/// Parse `major.minor.patch` (optional leading `v`, pre-release/build suffix ignored).
+#[must_use]
+pub fn parse_version(raw: &str) -> Option<(u64, u64, u64)> {
+    let core = raw
+        .trim()
+        .trim_start_matches('v')
+        .split(['-', '+'])
+        .next()?;
+    let mut parts = core.split('.').map(|p| p.parse::<u64>().ok());
+    let major = parts.next()??;
+    let minor = parts.next().unwrap_or(Some(0))?;
+    let patch = parts.next().unwrap_or(Some(0))?;
+    if parts.next().is_some() {
+        return None;
+    }
+    Some((major, minor, patch))
+}
+
+/// True when `latest` is a strictly newer version than `current`; unparsable versions never are.
+#[must_use]
+pub fn is_newer_version(current: &str, latest: &str) -> bool {
+    match (parse_version(current), parse_version(latest)) {
+        (Some(cur), Some(new)) => new > cur,
+        _ => false,
+    }
+}
+
+/// Query GitHub for the latest release and return it if it is newer than `current`.
+///
+/// # Errors
+/// Returns an error if the request fails or the response can't be parsed.
+#[cfg(feature = "update-check")]
+pub fn check_for_update(current: &str) -> anyhow::Result<Option<ReleaseInfo>> {
+    let body = ureq::get(LATEST_RELEASE_URL)
+        .header("User-Agent", concat!("stitch/", env!("CARGO_PKG_VERSION")))
+        .header("Accept", "application/vnd.github+json")
+        .config()
+        .timeout_global(Some(std::time::Duration::from_secs(10)))
+        .build()
+        .call()?
+        .body_mut()
+        .read_to_string()?;
+    let release = parse_latest_release(&body)
+        .ok_or_else(|| anyhow::anyhow!("unexpected release response"))?;
+    Ok(is_newer_version(current, &release.version).then_some(release))
+}
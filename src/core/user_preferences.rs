@@ -0,0 +1,109 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+/* ============================= User preferences ============================= */
+
+/// UI color scheme; `System` follows the OS setting.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Theme {
+    #[default]
+    System,
+    Light,
+    Dark,
+}
+
+impl Theme {
+    /// Position in the UI theme selector (System, Light, Dark).
+    #[must_use]
+    pub const fn index(self) -> i32 {
+        match self {
+            Self::System => 0,
+            Self::Light => 1,
+            Self::Dark => 2,
+        }
+    }
+
+    #[must_use]
+    pub const fn from_index(index: i32) -> Self {
+        match index {
+            1 => Self::Light,
+            2 => Self::Dark,
+            _ => Self::System,
+        }
+    }
+}
+
+/// Font used by the output pane when none is configured (bundled with the app).
+pub const DEFAULT_OUTPUT_FONT_FAMILY: &str = "JetBrains Mono";
+pub const DEFAULT_OUTPUT_FONT_SIZE: u32 = 11;
+/// Output pane font sizes accepted from settings; anything else is clamped.
+pub const OUTPUT_FONT_SIZE_RANGE: std::ops::RangeInclusive<u32> = 6..=48;
+
+/// Appearance settings that belong to the user rather than a project: stored in the
+/// user config dir and applied when the window opens, before any project is.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct UserPreferences {
+    #[serde(default)]
+    pub theme: Theme,
+    #[serde(default)]
+    pub output_font_family: Option<String>,
+    #[serde(default)]
+    pub output_font_size: Option<u32>,
+}
+
+impl UserPreferences {
+    /// Configured output pane font family, or the bundled default.
+    #[must_use]
+    pub fn output_font_family(&self) -> &str {
+        self.output_font_family
+            .as_deref()
+            .map(str::trim)
+            .filter(|f| !f.is_empty())
+            .unwrap_or(DEFAULT_OUTPUT_FONT_FAMILY)
+    }
+
+    /// Configured output pane font size in pixels, clamped to [`OUTPUT_FONT_SIZE_RANGE`].
+    #[must_use]
+    pub fn output_font_size(&self) -> u32 {
+        self.output_font_size
+            .map_or(DEFAULT_OUTPUT_FONT_SIZE, |size| {
+                size.clamp(
+                    *OUTPUT_FONT_SIZE_RANGE.start(),
+                    *OUTPUT_FONT_SIZE_RANGE.end(),
+                )
+            })
+    }
+}
+
+#[must_use]
+pub fn user_preferences_file(config_dir: &Path) -> PathBuf {
+    config_dir.join("preferences.json")
+}
+
+/// Preferences saved in `config_dir`; a missing or unreadable file yields the defaults.
+#[must_use]
+pub fn load_user_preferences(config_dir: &Path) -> UserPreferences {
+    fs::read(user_preferences_file(config_dir))
+        .ok()
+        .and_then(|data| serde_json::from_slice(&data).ok())
+        .unwrap_or_default()
+}
+
+/// Save `prefs` to `config_dir` atomically.
+///
+/// # Errors
+/// Returns I/O errors from writing/renaming files, or serialization errors.
+pub fn save_user_preferences(config_dir: &Path, prefs: &UserPreferences) -> io::Result<()> {
+    fs::create_dir_all(config_dir)?;
+    let path = user_preferences_file(config_dir);
+    let tmp = path.with_extension("json.tmp");
+    let data = serde_json::to_vec_pretty(prefs).map_err(|e| io::Error::other(e.to_string()))?;
+    fs::write(&tmp, data)?;
+    fs::rename(&tmp, &path)?;
+    Ok(())
+}
@@ -0,0 +1,315 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+};
+
+use crate::core::{
+    TokenizerSpec, WorkspaceSettings, clean_remove_regex, compile_remove_regex, fold_name,
+    parse_extension_filters, path_matches_extension_filters,
+};
+
+/* ============================ Settings validation =========================== */
+
+/// A free-text settings field that can be rejected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SettingsField {
+    ExtFilter,
+    ExcludeDirs,
+    ExcludeFiles,
+    RemoveRegex,
+    PathStripPrefix,
+    PathPrepend,
+    Tokenizer,
+}
+
+/// Why a field's value won't do what the user expects.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldError {
+    pub field: SettingsField,
+    pub message: String,
+}
+
+impl FieldError {
+    fn new(field: SettingsField, message: impl Into<String>) -> Self {
+        Self {
+            field,
+            message: message.into(),
+        }
+    }
+}
+
+/// Check the free-text fields of `ws`, returning one entry per problem in field order.
+#[must_use]
+pub fn validate_workspace_settings(ws: &WorkspaceSettings) -> Vec<FieldError> {
+    let mut errors = Vec::new();
+    validate_ext_filter(&ws.ext_filter, &mut errors);
+    validate_name_list(SettingsField::ExcludeDirs, &ws.exclude_dirs, &mut errors);
+    validate_name_list(SettingsField::ExcludeFiles, &ws.exclude_files, &mut errors);
+
+    let regex = clean_remove_regex(&ws.remove_regex);
+    if !regex.trim().is_empty()
+        && let Err(e) = compile_remove_regex(&regex)
+    {
+        errors.push(FieldError::new(SettingsField::RemoveRegex, e));
+    }
+
+    validate_rel_path(
+        SettingsField::PathStripPrefix,
+        &ws.path_rewrite.strip_prefix,
+        &mut errors,
+    );
+    validate_rel_path(
+        SettingsField::PathPrepend,
+        &ws.path_rewrite.prepend,
+        &mut errors,
+    );
+
+    if let Err(e) = TokenizerSpec::parse(&ws.tokenizer) {
+        errors.push(FieldError::new(SettingsField::Tokenizer, e));
+    }
+    errors
+}
+
+/// Join the messages reported for `field` (empty when the field is valid).
+#[must_use]
+pub fn field_error_message(errors: &[FieldError], field: SettingsField) -> String {
+    errors
+        .iter()
+        .filter(|e| e.field == field)
+        .map(|e| e.message.as_str())
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+fn validate_ext_filter(raw: &str, errors: &mut Vec<FieldError>) {
+    let field = SettingsField::ExtFilter;
+    let mut has_include = false;
+    let mut has_exclude = false;
+    for token in raw.split(',').map(str::trim).filter(|t| !t.is_empty()) {
+        let (is_exclude, rest) = token
+            .strip_prefix('-')
+            .map_or((false, token), |r| (true, r.trim()));
+        let ext = rest.trim_start_matches('.');
+        if ext.is_empty() {
+            errors.push(FieldError::new(
+                field,
+                format!("'{token}' has no extension"),
+            ));
+        } else if ext
+            .chars()
+            .any(|c| c.is_whitespace() || matches!(c, '/' | '\\' | '*'))
+        {
+            errors.push(FieldError::new(
+                field,
+                format!("'{token}' is not an extension (use e.g. .rs or -.lock)"),
+            ));
+        } else if is_exclude {
+            has_exclude = true;
+        } else {
+            has_include = true;
+        }
+    }
+    if has_include && has_exclude {
+        errors.push(FieldError::new(
+            field,
+            "exclusions (-.ext) are ignored when inclusions are listed",
+        ));
+    }
+}
+
+fn validate_name_list(field: SettingsField, raw: &str, errors: &mut Vec<FieldError>) {
+    for name in raw.split(',').map(str::trim).filter(|t| !t.is_empty()) {
+        if name.contains(['/', '\\']) {
+            errors.push(FieldError::new(
+                field,
+                format!("'{name}': only plain names are matched, not paths"),
+            ));
+        } else if name.contains('*') {
+            errors.push(FieldError::new(
+                field,
+                format!("'{name}': wildcards are not supported"),
+            ));
+        }
+    }
+}
+
+fn validate_rel_path(field: SettingsField, raw: &str, errors: &mut Vec<FieldError>) {
+    if raw.contains('\\') {
+        errors.push(FieldError::new(field, "use forward slashes"));
+    }
+    if raw.split('/').any(|seg| seg.trim() == "..") {
+        errors.push(FieldError::new(field, "'..' segments are not supported"));
+    }
+}
+
+/* ============================ Filter overlaps =============================== */
+
+/// Filter entries that repeat or cancel out another entry. Unlike
+/// [`validate_workspace_settings`] these change nothing about what's scanned, so the UI
+/// shows them as warnings.
+///
+/// Reported: extensions listed twice (any case, with or without the dot), an extension
+/// both included and excluded, directory or file names listed twice, and file names
+/// the extension filter already leaves out.
+#[must_use]
+pub fn find_filter_overlaps(ws: &WorkspaceSettings) -> Vec<FieldError> {
+    let mut overlaps = Vec::new();
+    dedup_ext_filter(&ws.ext_filter, &mut overlaps);
+    let fold = ws.name_matching.folds();
+    dedup_name_list(
+        SettingsField::ExcludeDirs,
+        &ws.exclude_dirs,
+        fold,
+        &mut overlaps,
+    );
+    dedup_name_list(
+        SettingsField::ExcludeFiles,
+        &ws.exclude_files,
+        fold,
+        &mut overlaps,
+    );
+
+    let (include_exts, exclude_exts) = parse_extension_filters(&ws.ext_filter);
+    let mut seen = HashSet::new();
+    for name in split_tokens(&ws.exclude_files).filter(|n| seen.insert(*n)) {
+        let path = Path::new(name);
+        let hidden = if include_exts.is_empty() {
+            path_matches_extension_filters(path, &exclude_exts)
+        } else {
+            !path_matches_extension_filters(path, &include_exts)
+        };
+        if hidden {
+            overlaps.push(FieldError::new(
+                SettingsField::ExcludeFiles,
+                format!("'{name}' is already left out by the extension filter"),
+            ));
+        }
+    }
+    overlaps
+}
+
+/// Drop repeated filter entries and exclusions of included extensions from `ws`,
+/// keeping the first spelling of each; returns whether a field changed.
+///
+/// File names the extension filter already hides are kept: a directory rule can turn
+/// the extension filter off below it, and the name still applies there.
+pub fn normalize_filter_settings(ws: &mut WorkspaceSettings) -> bool {
+    let ext_filter = dedup_ext_filter(&ws.ext_filter, &mut Vec::new());
+    let fold = ws.name_matching.folds();
+    let exclude_dirs = dedup_name_list(
+        SettingsField::ExcludeDirs,
+        &ws.exclude_dirs,
+        fold,
+        &mut Vec::new(),
+    );
+    let exclude_files = dedup_name_list(
+        SettingsField::ExcludeFiles,
+        &ws.exclude_files,
+        fold,
+        &mut Vec::new(),
+    );
+
+    let mut changed = false;
+    for (field, kept) in [
+        (&mut ws.ext_filter, ext_filter),
+        (&mut ws.exclude_dirs, exclude_dirs),
+        (&mut ws.exclude_files, exclude_files),
+    ] {
+        if let Some(kept) = kept {
+            *field = kept;
+            changed = true;
+        }
+    }
+    changed
+}
+
+fn split_tokens(raw: &str) -> impl Iterator<Item = &str> {
+    raw.split(',').map(str::trim).filter(|t| !t.is_empty())
+}
+
+/// Report repeats and include/exclude conflicts in the extension filter; the
+/// de-duplicated field when anything was dropped.
+fn dedup_ext_filter(raw: &str, overlaps: &mut Vec<FieldError>) -> Option<String> {
+    let field = SettingsField::ExtFilter;
+    let key = |token: &str| {
+        let (is_exclude, rest) = token
+            .strip_prefix('-')
+            .map_or((false, token), |r| (true, r.trim()));
+        (is_exclude, rest.trim_start_matches('.').to_lowercase())
+    };
+    let included: Vec<(String, &str)> = split_tokens(raw)
+        .filter_map(|t| match key(t) {
+            (false, ext) if !ext.is_empty() => Some((ext, t)),
+            _ => None,
+        })
+        .collect();
+
+    let mut seen: Vec<((bool, String), &str)> = Vec::new();
+    let mut kept = Vec::new();
+    let mut dropped = false;
+    for token in split_tokens(raw) {
+        let k = key(token);
+        if k.1.is_empty() {
+            // Reported by `validate_workspace_settings`.
+            kept.push(token);
+            continue;
+        }
+        if let Some((_, first)) = seen.iter().find(|(s, _)| *s == k) {
+            overlaps.push(FieldError::new(
+                field,
+                format!("'{token}' repeats '{first}'"),
+            ));
+            dropped = true;
+            continue;
+        }
+        if k.0
+            && let Some((_, inc)) = included.iter().find(|(ext, _)| *ext == k.1)
+        {
+            overlaps.push(FieldError::new(
+                field,
+                format!("'{token}' conflicts with '{inc}' (the inclusion wins)"),
+            ));
+            dropped = true;
+            continue;
+        }
+        seen.push((k, token));
+        kept.push(token);
+    }
+    dropped.then(|| kept.join(", "))
+}
+
+/// Report names listed more than once (compared through [`fold_name`] when `fold`); the
+/// de-duplicated field when anything was dropped.
+fn dedup_name_list(
+    field: SettingsField,
+    raw: &str,
+    fold: bool,
+    overlaps: &mut Vec<FieldError>,
+) -> Option<String> {
+    let mut first_by_key: HashMap<String, &str> = HashMap::new();
+    let mut reported = HashSet::new();
+    let mut kept = Vec::new();
+    for name in split_tokens(raw) {
+        let key = if fold {
+            fold_name(name)
+        } else {
+            name.to_string()
+        };
+        match first_by_key.get(&key) {
+            None => {
+                first_by_key.insert(key, name);
+                kept.push(name);
+            }
+            Some(first) if reported.insert(name) => {
+                let message = if *first == name {
+                    format!("'{name}' is listed twice")
+                } else {
+                    format!("'{name}' repeats '{first}'")
+                };
+                overlaps.push(FieldError::new(field, message));
+            }
+            Some(_) => {}
+        }
+    }
+    (kept.len() < split_tokens(raw).count()).then(|| kept.join(", "))
+}
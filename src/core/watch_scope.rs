@@ -0,0 +1,68 @@
+use std::path::{Path, PathBuf};
+
+/* =============================== Watch scope ================================ */
+
+/// A directory for the file watcher, with or without its subdirectories.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct WatchTarget {
+    pub path: PathBuf,
+    pub recursive: bool,
+}
+
+impl WatchTarget {
+    #[must_use]
+    pub fn recursive(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            recursive: true,
+        }
+    }
+
+    #[must_use]
+    pub fn flat(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            recursive: false,
+        }
+    }
+}
+
+/// What a watcher limited to the selection needs.
+///
+/// That is the checked directories `dirs` with their subtrees, the folders of the checked `files`, and the project root itself (for
+/// `.stitchignore` and new top-level entries). Paths outside `project_root` are dropped.
+#[must_use]
+pub fn selection_watch_targets(
+    project_root: &Path,
+    files: &[PathBuf],
+    dirs: &[PathBuf],
+) -> Vec<WatchTarget> {
+    let mut targets = vec![WatchTarget::flat(project_root)];
+    targets.extend(dirs.iter().map(WatchTarget::recursive));
+    targets.extend(
+        files
+            .iter()
+            .filter_map(|f| f.parent())
+            .map(WatchTarget::flat),
+    );
+    targets.retain(|t| t.path.starts_with(project_root));
+    minimize_watch_targets(targets)
+}
+
+/// `targets` sorted by path, without those another recursive target already covers.
+#[must_use]
+pub fn minimize_watch_targets(mut targets: Vec<WatchTarget>) -> Vec<WatchTarget> {
+    // Recursive before flat for the same path, shallower before deeper.
+    targets.sort_by(|a, b| a.path.cmp(&b.path).then(b.recursive.cmp(&a.recursive)));
+    let mut kept: Vec<WatchTarget> = Vec::with_capacity(targets.len());
+    for target in targets {
+        let covered = kept.iter().any(|k| {
+            (k.recursive && target.path.starts_with(&k.path))
+                || (k.path == target.path && !target.recursive)
+        });
+        if !covered {
+            kept.push(target);
+        }
+    }
+    kept
+}
@@ -0,0 +1,251 @@
+use std::collections::HashSet;
+
+use serde::Serialize;
+
+use crate::core::{remove_slash_comments, scan_string_literal};
+
+/// Extensions of the markup and stylesheet files the web filters apply to.
+pub const WEB_EXTENSIONS: &[&str] = &["html", "htm", "css", "scss"];
+
+/// HTML elements whose contents are whitespace-sensitive.
+const VERBATIM_TAGS: &[&str] = &["pre", "textarea"];
+/// HTML elements holding code, whose lines are only trimmed.
+const CODE_TAGS: &[&str] = &["script", "style"];
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct WebFilterOptions {
+    /// `<!-- -->` in HTML, `/* */` in CSS and SCSS, plus `//` in SCSS.
+    pub remove_comments: bool,
+    /// Trim lines, drop blank ones and squeeze runs of spaces and tabs.
+    pub collapse_whitespace: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WebLanguage {
+    Html,
+    Css,
+    Scss,
+}
+
+impl WebLanguage {
+    /// Language of `path` by extension (any case), if it is one of [`WEB_EXTENSIONS`].
+    #[must_use]
+    pub fn from_path(path: &std::path::Path) -> Option<Self> {
+        let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+        match ext.as_str() {
+            "html" | "htm" => Some(Self::Html),
+            "css" => Some(Self::Css),
+            "scss" => Some(Self::Scss),
+            _ => None,
+        }
+    }
+}
+
+/// Returns true for HTML, CSS and SCSS files (see [`WEB_EXTENSIONS`]).
+#[must_use]
+pub fn is_web_file_path(path: &std::path::Path) -> bool {
+    WebLanguage::from_path(path).is_some()
+}
+
+/// Apply the web filters to a markup or stylesheet file's string contents.
+/// This function only transforms when at least one option is enabled; otherwise returns input as-is.
+#[must_use]
+pub fn apply_web_filters(source: &str, lang: WebLanguage, opts: &WebFilterOptions) -> String {
+    if !(opts.remove_comments || opts.collapse_whitespace) {
+        return source.to_string();
+    }
+    let cleaned = if opts.remove_comments {
+        match lang {
+            WebLanguage::Html => remove_html_comments(source),
+            WebLanguage::Css => remove_slash_comments(source, false, true, skip_css_literal),
+            WebLanguage::Scss => remove_slash_comments(source, true, true, skip_css_literal),
+        }
+    } else {
+        source.to_string()
+    };
+    if !opts.collapse_whitespace {
+        return cleaned;
+    }
+    match lang {
+        WebLanguage::Html => collapse_html_whitespace(&cleaned),
+        WebLanguage::Css | WebLanguage::Scss => collapse_css_whitespace(&cleaned),
+    }
+}
+
+/// End of the string or unquoted `url(...)` starting at `idx`, so `//` in
+/// `url(https://...)` isn't read as an SCSS comment.
+fn skip_css_literal(bytes: &[u8], len: usize, idx: usize) -> Option<usize> {
+    match bytes[idx] {
+        b'"' | b'\'' => Some(scan_string_literal(bytes, len, idx, bytes[idx])),
+        b'u' | b'U'
+            if bytes[idx..].len() >= 4 && bytes[idx..idx + 4].eq_ignore_ascii_case(b"url(") =>
+        {
+            Some(
+                bytes[idx..len]
+                    .iter()
+                    .position(|&b| b == b')' || b == b'\n')
+                    .map_or(len, |p| idx + p + 1),
+            )
+        }
+        _ => None,
+    }
+}
+
+/// Remove `<!-- ... -->` outside `<script>` and `<style>` elements, whose contents may
+/// spell one in a string; lines left blank by a removal are dropped.
+fn remove_html_comments(source: &str) -> String {
+    let lower = source.to_ascii_lowercase();
+    let mut out = String::with_capacity(source.len());
+    let mut cut_lines: HashSet<usize> = HashSet::new();
+    let mut line = 0usize;
+    let mut last_emit = 0usize;
+    let mut idx = 0usize;
+    while let Some(at) = lower[idx..].find('<').map(|p| idx + p) {
+        if lower[at..].starts_with("<!--") {
+            let kept = &source[last_emit..at];
+            out.push_str(kept.trim_end_matches([' ', '\t']));
+            line += kept.matches('\n').count();
+            cut_lines.insert(line);
+            idx = lower[at + 4..]
+                .find("-->")
+                .map_or(source.len(), |end| at + 4 + end + 3);
+            last_emit = idx;
+        } else if let Some(tag) = CODE_TAGS.iter().find(|tag| tag_opens_at(&lower, at, tag)) {
+            let close = format!("</{tag}");
+            idx = lower[at..]
+                .find(&close)
+                .map_or(source.len(), |end| at + end + close.len());
+        } else {
+            idx = at + 1;
+        }
+    }
+    out.push_str(&source[last_emit..]);
+
+    out.split_inclusive('\n')
+        .enumerate()
+        .filter(|(n, l)| !(l.trim().is_empty() && cut_lines.contains(n)))
+        .map(|(_, l)| l)
+        .collect()
+}
+
+/// Trim lines, drop blank ones and squeeze spaces, leaving `<pre>` and `<textarea>`
+/// contents as written and only trimming lines of `<script>` and `<style>`.
+fn collapse_html_whitespace(source: &str) -> String {
+    let mut out = String::with_capacity(source.len());
+    let mut verbatim: Option<&str> = None;
+    let mut code: Option<&str> = None;
+    for line in source.lines() {
+        let lower = line.to_ascii_lowercase();
+        if let Some(tag) = verbatim {
+            if closes_tag(&lower, tag) {
+                verbatim = None;
+                push_line(&mut out, line.trim_end());
+            } else {
+                push_line(&mut out, line);
+            }
+            continue;
+        }
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        if let Some(tag) = VERBATIM_TAGS
+            .iter()
+            .find(|tag| opens_tag(&lower, tag) && !closes_tag(&lower, tag))
+        {
+            // Whitespace after the opening tag belongs to the element.
+            verbatim = Some(tag);
+            push_line(&mut out, line.trim_start());
+            continue;
+        }
+        if code.is_some_and(|tag| closes_tag(&lower, tag)) {
+            code = None;
+        } else if let Some(tag) = CODE_TAGS
+            .iter()
+            .find(|tag| opens_tag(&lower, tag) && !closes_tag(&lower, tag))
+        {
+            code = Some(tag);
+            push_line(&mut out, &squeeze_spaces(trimmed, false));
+            continue;
+        }
+        if code.is_some() {
+            push_line(&mut out, trimmed);
+        } else {
+            push_line(&mut out, &squeeze_spaces(trimmed, false));
+        }
+    }
+    if !source.ends_with('\n') && out.ends_with('\n') {
+        out.pop();
+    }
+    out
+}
+
+fn opens_tag(lower_line: &str, tag: &str) -> bool {
+    lower_line
+        .match_indices('<')
+        .any(|(i, _)| tag_opens_at(lower_line, i, tag))
+}
+
+/// Whether the `<` at `i` starts an opening `tag`.
+fn tag_opens_at(lower: &str, i: usize, tag: &str) -> bool {
+    lower[i + 1..].starts_with(tag)
+        && lower[i + 1 + tag.len()..]
+            .chars()
+            .next()
+            .is_none_or(|c| c == '>' || c.is_ascii_whitespace())
+}
+
+fn closes_tag(lower_line: &str, tag: &str) -> bool {
+    lower_line.contains(&format!("</{tag}"))
+}
+
+/// Trim lines, drop blank ones and squeeze spaces outside strings.
+fn collapse_css_whitespace(source: &str) -> String {
+    let mut out = String::with_capacity(source.len());
+    for line in source.lines() {
+        let trimmed = line.trim();
+        if !trimmed.is_empty() {
+            push_line(&mut out, &squeeze_spaces(trimmed, true));
+        }
+    }
+    if !source.ends_with('\n') && out.ends_with('\n') {
+        out.pop();
+    }
+    out
+}
+
+/// Replace runs of spaces and tabs with one space, optionally leaving quoted text alone.
+fn squeeze_spaces(line: &str, respect_quotes: bool) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut quote: Option<char> = None;
+    let mut escaped = false;
+    let mut prev_space = false;
+    for ch in line.chars() {
+        if let Some(q) = quote {
+            out.push(ch);
+            if !escaped && ch == q {
+                quote = None;
+            }
+            escaped = ch == '\\' && !escaped;
+            continue;
+        }
+        if ch == ' ' || ch == '\t' {
+            if !prev_space {
+                out.push(' ');
+            }
+            prev_space = true;
+            continue;
+        }
+        prev_space = false;
+        if respect_quotes && (ch == '"' || ch == '\'') {
+            quote = Some(ch);
+        }
+        out.push(ch);
+    }
+    out
+}
+
+fn push_line(out: &mut String, line: &str) {
+    out.push_str(line);
+    out.push('\n');
+}
@@ -1,13 +1,26 @@
 use std::{
+    collections::BTreeMap,
     fs, io,
     path::{Path, PathBuf},
 };
 
 use serde::{Deserialize, Serialize};
 
+use crate::core::{
+    BranchProfile, ContextWindow, DirectoryRule, DirectorySample, FileMarkers, NameMatching,
+    OutputLayout, OutputOrder, PathRewrite, PostProcess, TransformPipeline, TreeStyle,
+    write_with_backup,
+};
+
 /* ============================ Workspace settings ============================ */
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+/// Excluded directory names of a project without saved settings.
+pub const DEFAULT_EXCLUDE_DIRS: &str = ".git, node_modules, target, _target, .elan, .lake, .idea, .vscode, _app, .svelte-kit, .sqlx, venv, .venv, __pycache__, LICENSES, fixtures";
+/// Excluded file names of a project without saved settings.
+pub const DEFAULT_EXCLUDE_FILES: &str = "LICENSE, Cargo.lock, package-lock.json, yarn.lock, .DS_Store, .dockerignore, .gitignore, .npmignore, .pre-commit-config.yaml, .prettierignore, .prettierrc, eslint.config.js, .env, Thumbs.db";
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[allow(clippy::struct_excessive_bools)] // independent output toggles mirrored 1:1 in the UI
 pub struct WorkspaceSettings {
     pub version: u32,
     pub ext_filter: String,
@@ -17,10 +30,83 @@ pub struct WorkspaceSettings {
     pub remove_regex: String,
     pub hierarchy_only: bool,
     pub dirs_only: bool,
+    /// Append a summary of fully unselected top-level directories to the output.
+    #[serde(default)]
+    pub summarize_unselected: bool,
+    /// Prefix emitted file lines with their original line numbers (`42 | code`).
+    #[serde(default)]
+    pub line_numbers: bool,
+    /// Add a `=== DEPENDENCIES ===` summary parsed from the project's manifests
+    /// (`Cargo.toml`, `package.json`, `pyproject.toml`, `go.mod`).
+    #[serde(default)]
+    pub dependencies_section: bool,
+    /// Emit output that only depends on the selected files and settings, for golden
+    /// tests and CI snapshots: `/`-separated paths on every platform, error kinds instead
+    /// of OS messages in NOTES, and no refresh timestamp.
+    #[serde(default)]
+    pub deterministic: bool,
+    /// Strip UTF-8 BOMs and convert CRLF/CR line endings to LF in emitted files. NOTES
+    /// lists the files changed, or with this off, those with a BOM or mixed line endings.
+    #[serde(default)]
+    pub normalize_text: bool,
+    /// Section order, visibility and headers of the generated output.
+    #[serde(default)]
+    pub layout: OutputLayout,
+    /// Branch characters of the hierarchy (`ascii` for destinations that mangle Unicode).
+    #[serde(default)]
+    pub tree_style: TreeStyle,
+    /// Rewrites applied to emitted paths (hierarchy and file headers).
+    #[serde(default)]
+    pub path_rewrite: PathRewrite,
+    /// Lines written before and after each file's contents.
+    #[serde(default)]
+    pub file_markers: FileMarkers,
+    /// Use [`FileMarkers::cells`] instead of `file_markers`.
+    #[serde(default)]
+    pub cell_markers: bool,
+    /// Extension or file name → language identifier, over the built-in table
+    /// (e.g. `{"tpl": "handlebars"}`); see [`crate::core::lang_for_path`].
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub languages: BTreeMap<String, String>,
+    /// Per-file transforms and their order (strip prefixes, remove regex, language filters, ...).
+    #[serde(default, skip_serializing_if = "TransformPipeline::is_default")]
+    pub transforms: TransformPipeline,
+    /// Largest SVG, in bytes, emitted as XML under its image placeholder (`0` = never;
+    /// unset = [`crate::core::DEFAULT_SVG_INLINE_LIMIT`]).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub svg_inline_limit: Option<u64>,
+    /// Command the finished output is piped through before it's shown and copied.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_process: Option<PostProcess>,
+    /// Tokenizer for the token stats: a builtin encoding name or a `tokenizer.json`
+    /// path (empty = [`crate::core::DEFAULT_TOKENIZER`]).
+    #[serde(default)]
+    pub tokenizer: String,
+    /// Context-window preset the output is checked against.
+    #[serde(default)]
+    pub context_window: ContextWindow,
+    /// Order of the file blocks in the output.
+    #[serde(default)]
+    pub output_order: OutputOrder,
+    /// How excluded directory and file names are compared with names on disk.
+    #[serde(default)]
+    pub name_matching: NameMatching,
     #[serde(flatten)]
     pub rust: RustOptions,
     #[serde(flatten)]
     pub slint: SlintOptions,
+    /// C, C++, Java and C# filters (not editable in the UI).
+    #[serde(flatten)]
+    pub c_like: CLikeOptions,
+    /// Go filters (not editable in the UI).
+    #[serde(flatten)]
+    pub go: GoOptions,
+    /// HTML, CSS and SCSS filters (not editable in the UI).
+    #[serde(flatten)]
+    pub web: WebOptions,
+    /// SQL filters (not editable in the UI).
+    #[serde(flatten)]
+    pub sql: SqlOptions,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
@@ -43,10 +129,175 @@ pub struct SlintOptions {
     pub slint_remove_block_comments: bool,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct CLikeOptions {
+    #[serde(default)]
+    pub c_like_remove_line_comments: bool,
+    #[serde(default)]
+    pub c_like_remove_block_comments: bool,
+    #[serde(default)]
+    pub c_like_function_signatures_only: bool,
+}
+
+impl CLikeOptions {
+    #[must_use]
+    pub const fn filter_options(&self) -> crate::core::CLikeFilterOptions {
+        crate::core::CLikeFilterOptions {
+            remove_line_comments: self.c_like_remove_line_comments,
+            remove_block_comments: self.c_like_remove_block_comments,
+            function_signatures_only: self.c_like_function_signatures_only,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[allow(clippy::struct_excessive_bools)] // independent filter toggles mirrored 1:1 in the UI
+pub struct GoOptions {
+    #[serde(default)]
+    pub go_remove_line_comments: bool,
+    #[serde(default)]
+    pub go_remove_block_comments: bool,
+    #[serde(default)]
+    pub go_function_signatures_only: bool,
+    /// Keep protobuf-generated `*.pb.go` files in the output; they're left out by default.
+    #[serde(default)]
+    pub go_keep_generated: bool,
+}
+
+impl GoOptions {
+    #[must_use]
+    pub const fn filter_options(&self) -> crate::core::GoFilterOptions {
+        crate::core::GoFilterOptions {
+            remove_line_comments: self.go_remove_line_comments,
+            remove_block_comments: self.go_remove_block_comments,
+            function_signatures_only: self.go_function_signatures_only,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct WebOptions {
+    #[serde(default)]
+    pub web_remove_comments: bool,
+    #[serde(default)]
+    pub web_collapse_whitespace: bool,
+}
+
+impl WebOptions {
+    #[must_use]
+    pub const fn filter_options(&self) -> crate::core::WebFilterOptions {
+        crate::core::WebFilterOptions {
+            remove_comments: self.web_remove_comments,
+            collapse_whitespace: self.web_collapse_whitespace,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct SqlOptions {
+    #[serde(default)]
+    pub sql_remove_comments: bool,
+    #[serde(default)]
+    pub sql_collapse_data: bool,
+}
+
+impl SqlOptions {
+    #[must_use]
+    pub const fn filter_options(&self) -> crate::core::SqlFilterOptions {
+        crate::core::SqlFilterOptions {
+            remove_comments: self.sql_remove_comments,
+            collapse_data: self.sql_collapse_data,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[allow(clippy::struct_excessive_bools)] // independent per-machine preferences
 pub struct LocalSettings {
     #[serde(default)]
     pub current_profile: Option<String>,
+    /// Opt-in: look for a newer release on GitHub when the project is opened.
+    #[serde(default)]
+    pub check_for_updates: bool,
+    /// Show a heuristic token estimate instead of running the BPE tokenizer.
+    #[serde(default)]
+    pub approximate_tokens: bool,
+    /// Copied outputs kept in `.stitchworkspace/local/history/` (`0` turns the history off).
+    #[serde(default)]
+    pub history_limit: Option<usize>,
+    /// Opt-in: run the `command` steps of the transform pipeline. Off by default since
+    /// the pipeline comes from the shared workspace/profile files.
+    #[serde(default)]
+    pub external_commands: bool,
+    /// Command that opens an external diff/merge tool for each file changes are applied
+    /// to (see [`crate::core::review_with_merge_tool`]); unset uses the built-in summary.
+    #[serde(default)]
+    pub merge_tool: Option<String>,
+    /// Backups kept per workspace/profile file in `.stitchworkspace/backups/` (`0` turns
+    /// them off).
+    #[serde(default)]
+    pub backup_count: Option<usize>,
+    /// Force the high-contrast palette on or off; unset follows the OS setting.
+    #[serde(default)]
+    pub high_contrast: Option<bool>,
+    /// Force reduced motion (no fades, longer toasts) on or off; unset follows the OS setting.
+    #[serde(default)]
+    pub reduced_motion: Option<bool>,
+    /// Chars shown per page of the output pane ([`crate::core::DEFAULT_PREVIEW_CHAR_LIMIT`]
+    /// if unset, `0` shows the whole output at once).
+    #[serde(default)]
+    pub preview_char_limit: Option<usize>,
+    /// Read only the top-level directories when the project is opened and load the others
+    /// on first expand (or when a generation needs their files), for very large trees.
+    #[serde(default)]
+    pub lazy_scan: bool,
+    /// Keep the cache of transformed file contents in
+    /// `.stitchworkspace/local/content-cache.json`, so it survives restarts.
+    #[serde(default)]
+    pub persist_content_cache: bool,
+    /// Let the file watcher cover only the selected directories and the folders of
+    /// selected files, instead of the whole project.
+    #[serde(default)]
+    pub watch_selection_only: bool,
+    /// Profiles selected automatically for matching git branches, first match wins.
+    #[serde(default)]
+    pub profile_branches: Vec<BranchProfile>,
+    /// Environment variables the provenance header may show as `{env:NAME}`; any other
+    /// name renders empty so a shared format can't pull secrets into the output.
+    #[serde(default)]
+    pub provenance_env: Vec<String>,
+}
+
+impl LocalSettings {
+    /// Configured merge tool command, if it isn't blank.
+    #[must_use]
+    pub fn merge_tool(&self) -> Option<&str> {
+        self.merge_tool
+            .as_deref()
+            .map(str::trim)
+            .filter(|c| !c.is_empty())
+    }
+
+    /// Number of copied outputs to keep, [`crate::core::DEFAULT_HISTORY_LIMIT`] if unset.
+    #[must_use]
+    pub fn history_limit(&self) -> usize {
+        self.history_limit
+            .unwrap_or(crate::core::DEFAULT_HISTORY_LIMIT)
+    }
+
+    /// Chars per output pane page, `0` for no limit.
+    #[must_use]
+    pub fn preview_char_limit(&self) -> usize {
+        self.preview_char_limit
+            .unwrap_or(crate::core::DEFAULT_PREVIEW_CHAR_LIMIT)
+    }
+
+    /// Number of backups to keep per file, [`crate::core::DEFAULT_BACKUP_COUNT`] if unset.
+    #[must_use]
+    pub fn backup_count(&self) -> usize {
+        self.backup_count
+            .unwrap_or(crate::core::DEFAULT_BACKUP_COUNT)
+    }
 }
 
 /* ================================ Profiles ================================= */
@@ -70,6 +321,15 @@ pub struct Profile {
     pub settings: WorkspaceSettings,
     /// Explicit on/off checks captured relative to project root.
     pub explicit: Vec<ProfileSelection>,
+    /// Directories that emit only a sample of their files.
+    #[serde(default)]
+    pub samples: Vec<DirectorySample>,
+    /// Per-directory scan overrides ("always exclude here", ...).
+    #[serde(default)]
+    pub dir_rules: Vec<DirectoryRule>,
+    /// Files always emitted first, whatever is checked (project-relative, forward slashes).
+    #[serde(default)]
+    pub pinned: Vec<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -201,7 +461,9 @@ fn sanitize_profile_name(name: &str) -> String {
         .collect()
 }
 
-fn profile_path(project_root: &Path, scope: ProfileScope, name: &str) -> PathBuf {
+/// Path of the profile file `name` in `scope`, whether or not it exists.
+#[must_use]
+pub fn profile_path(project_root: &Path, scope: ProfileScope, name: &str) -> PathBuf {
     let base = match scope {
         ProfileScope::Shared => profiles_shared_dir(project_root),
         ProfileScope::Local => profiles_local_dir(project_root),
@@ -218,7 +480,8 @@ pub fn load_workspace(project_root: &Path) -> Option<WorkspaceSettings> {
     serde_json::from_slice::<WorkspaceSettings>(&data).ok()
 }
 
-/// Save the workspace settings atomically.
+/// Save the workspace settings atomically, keeping a backup of the previous file (see
+/// [`write_with_backup`]).
 ///
 /// # Errors
 /// Returns I/O errors from writing/renaming files, or serialization errors.
@@ -226,13 +489,14 @@ pub fn save_workspace(project_root: &Path, settings: &WorkspaceSettings) -> io::
     ensure_workspace_dir(project_root)?;
 
     let path = workspace_file(project_root);
-    let tmp = path.with_extension("json.tmp");
-
     let data = serde_json::to_vec_pretty(settings).map_err(|e| io::Error::other(e.to_string()))?;
+    write_with_backup(project_root, &path, &data, backup_count(project_root))
+}
 
-    fs::write(&tmp, data)?;
-    fs::rename(&tmp, &path)?;
-    Ok(())
+fn backup_count(project_root: &Path) -> usize {
+    load_local_settings(project_root)
+        .unwrap_or_default()
+        .backup_count()
 }
 
 /// Load per-user local settings for this workspace.
@@ -264,18 +528,16 @@ pub fn save_local_settings(project_root: &Path, settings: &LocalSettings) -> io:
 
 /* =============================== Profiles IO =============================== */
 
-/// Save a profile JSON file atomically for the given scope.
+/// Save a profile JSON file atomically for the given scope, keeping a backup of the
+/// previous file.
 ///
 /// # Errors
 /// Returns I/O errors from writing/renaming files, or serialization errors.
 pub fn save_profile(project_root: &Path, profile: &Profile, scope: ProfileScope) -> io::Result<()> {
     ensure_profiles_dirs(project_root)?;
     let path = profile_path(project_root, scope, &profile.name);
-    let tmp = path.with_extension("tmp");
     let data = serde_json::to_vec_pretty(profile).map_err(|e| io::Error::other(e.to_string()))?;
-    fs::write(&tmp, data)?;
-    fs::rename(&tmp, &path)?;
-    Ok(())
+    write_with_backup(project_root, &path, &data, backup_count(project_root))
 }
 
 /// Returns (Profile, Scope) preferring Local if both exist.
@@ -388,7 +650,7 @@ pub fn list_profiles(project_root: &Path) -> Vec<ProfileMeta> {
         .into_iter()
         .map(|(name, (scope, ts))| (name, scope, ts))
         .collect();
-    merged.sort_by(|a, b| b.2.cmp(&a.2));
+    merged.sort_by_key(|b| std::cmp::Reverse(b.2));
 
     merged
         .into_iter()
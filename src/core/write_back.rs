@@ -0,0 +1,422 @@
+use std::{
+    fmt::Write,
+    fs,
+    path::{Component, Path, PathBuf},
+    process::{Command, Stdio},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use crate::core::{
+    DiffLine, FileMarkers, diff_lines, display_path, load_local_settings, write_atomic,
+};
+
+/* ============================ Write-back safety ============================= */
+
+// Checks every flow that writes files of the project (rather than its workspace
+// settings) has to go through: targets stay inside the project, uncommitted work is
+// stashed first, and the user sees what changes before anything is written.
+
+/// Folders below the project root that are never written to.
+const PROTECTED_DIRS: [&str; 2] = [".git", ".stitchworkspace"];
+
+/// Message of the stash entry taken before writing.
+const STASH_MESSAGE: &str = "stitch: backup before write-back";
+
+/// New contents for one project file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileChange {
+    /// Project-relative path using forward slashes.
+    pub path: String,
+    pub contents: String,
+}
+
+/// Lines added and removed in one file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FileChangeStat {
+    pub path: String,
+    pub added: usize,
+    pub removed: usize,
+    /// The file doesn't exist yet.
+    pub created: bool,
+}
+
+/// How uncommitted work was protected before writing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GitSafety {
+    /// The working tree had no changes.
+    Clean,
+    /// Uncommitted changes were saved as a stash entry (listed by `git stash list`,
+    /// the working tree is left as it was). Holds the stash commit id.
+    Stashed(String),
+}
+
+/// Absolute path of the project file `rel`.
+///
+/// # Errors
+/// Refuses empty and absolute paths, `..` components, paths inside `.git` or
+/// `.stitchworkspace`, and paths that leave the project through a symlink.
+pub fn write_target(project_root: &Path, rel: &str) -> Result<PathBuf, String> {
+    let rel_path = Path::new(rel);
+    if rel.trim().is_empty() {
+        return Err("empty path".into());
+    }
+    if !rel_path
+        .components()
+        .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+    {
+        return Err(format!("{rel} is outside the project"));
+    }
+    if let Some(Component::Normal(first)) = rel_path.components().find(|c| *c != Component::CurDir)
+        && PROTECTED_DIRS.iter().any(|d| first == *d)
+    {
+        return Err(format!("{rel} is inside {}", first.to_string_lossy()));
+    }
+
+    let target = project_root.join(rel_path);
+    let root = dunce::canonicalize(project_root)
+        .map_err(|e| format!("{}: {e}", display_path(project_root)))?;
+    // The deepest part that exists decides where symlinks lead.
+    let existing = target
+        .ancestors()
+        .find(|p| p.exists())
+        .and_then(|p| dunce::canonicalize(p).ok());
+    if existing.is_none_or(|p| !p.starts_with(&root)) {
+        return Err(format!("{rel} is outside the project"));
+    }
+    Ok(target)
+}
+
+/// A checked set of file changes, written with [`WriteBackPlan::write`].
+#[derive(Debug, Clone)]
+pub struct WriteBackPlan {
+    root: PathBuf,
+    changes: Vec<(PathBuf, FileChange)>,
+    stats: Vec<FileChangeStat>,
+}
+
+impl WriteBackPlan {
+    /// Check every target and count the lines each change adds and removes.
+    ///
+    /// # Errors
+    /// Returns the first path [`write_target`] refuses, or a read error of an existing
+    /// file.
+    pub fn new(project_root: &Path, changes: &[FileChange]) -> Result<Self, String> {
+        let mut planned = Vec::new();
+        let mut stats = Vec::new();
+        for change in changes {
+            let target = write_target(project_root, &change.path)?;
+            let current = match fs::read_to_string(&target) {
+                Ok(text) => Some(text),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+                Err(e) => return Err(format!("{}: {e}", change.path)),
+            };
+            if current.as_deref() == Some(change.contents.as_str()) {
+                continue;
+            }
+            let mut stat = FileChangeStat {
+                path: change.path.clone(),
+                created: current.is_none(),
+                ..FileChangeStat::default()
+            };
+            for line in diff_lines(current.as_deref().unwrap_or(""), &change.contents) {
+                match line {
+                    DiffLine::Added(_) => stat.added += 1,
+                    DiffLine::Removed(_) => stat.removed += 1,
+                    DiffLine::Same(_) => {}
+                }
+            }
+            stats.push(stat);
+            planned.push((target, change.clone()));
+        }
+        Ok(Self {
+            root: project_root.to_path_buf(),
+            changes: planned,
+            stats,
+        })
+    }
+
+    /// Files that would change, in the order given; unchanged files are left out.
+    #[must_use]
+    pub fn stats(&self) -> &[FileChangeStat] {
+        &self.stats
+    }
+
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// What will be written, for confirmation: one line per file, then the totals.
+    #[must_use]
+    pub fn render_summary(&self) -> String {
+        let mut out = String::new();
+        let (mut added, mut removed) = (0, 0);
+        for s in &self.stats {
+            let marker = if s.created { 'A' } else { 'M' };
+            let _ = writeln!(out, "{marker} {}  +{} -{}", s.path, s.added, s.removed);
+            added += s.added;
+            removed += s.removed;
+        }
+        let files = self.stats.len();
+        let noun = if files == 1 { "file" } else { "files" };
+        let _ = writeln!(out, "{files} {noun} changed, +{added} -{removed}");
+        out
+    }
+
+    /// Protect uncommitted work, then write every changed file.
+    ///
+    /// # Errors
+    /// Refuses projects outside a git repository and files that git doesn't track yet
+    /// (their current contents couldn't be recovered); returns git and I/O errors.
+    /// Nothing is written when the git step fails.
+    pub fn write(&self) -> Result<GitSafety, String> {
+        if self.is_empty() {
+            return Ok(GitSafety::Clean);
+        }
+        let safety = self.secure_git_state()?;
+        for (target, change) in &self.changes {
+            // Checked again: the tree may have changed since the plan was made.
+            write_target(&self.root, &change.path)?;
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent).map_err(|e| format!("{}: {e}", change.path))?;
+            }
+            write_atomic(target, change.contents.as_bytes())
+                .map_err(|e| format!("{}: {e}", change.path))?;
+        }
+        Ok(safety)
+    }
+
+    fn secure_git_state(&self) -> Result<GitSafety, String> {
+        let status = git(
+            &self.root,
+            &["status", "--porcelain", "--untracked-files=all"],
+        )
+        .map_err(|e| {
+            format!(
+                "{} is not in a usable git repository ({e})",
+                display_path(&self.root)
+            )
+        })?;
+        if status.trim().is_empty() {
+            return Ok(GitSafety::Clean);
+        }
+        let prefix = git(&self.root, &["rev-parse", "--show-prefix"])?;
+        let prefix = prefix.trim();
+        for line in status.lines() {
+            let Some(path) = line.strip_prefix("?? ") else {
+                continue;
+            };
+            let untracked = path.trim_matches('"');
+            if let Some((_, change)) = self
+                .changes
+                .iter()
+                .find(|(_, c)| format!("{prefix}{}", c.path) == untracked)
+            {
+                return Err(format!(
+                    "{} is not tracked by git; commit it before writing over it",
+                    change.path
+                ));
+            }
+        }
+        let stash = git(&self.root, &["stash", "create", STASH_MESSAGE])?;
+        let stash = stash.trim();
+        if stash.is_empty() {
+            // Only untracked files, none of which are written.
+            return Ok(GitSafety::Clean);
+        }
+        git(&self.root, &["stash", "store", "-m", STASH_MESSAGE, stash])?;
+        Ok(GitSafety::Stashed(stash.to_string()))
+    }
+}
+
+/* ============================ External merge tool =========================== */
+
+/// Let the user review each change in an external diff/merge tool (meld, kdiff3, VS
+/// Code, ...) and return the contents they saved, to build a [`WriteBackPlan`] from.
+///
+/// `command` runs once per file through the platform shell (`sh -c`, `cmd /C` on
+/// Windows) in the project root, and Stitch waits for it to exit. It sees
+/// `STITCH_CURRENT` (a copy of the file as it is now, empty for new files),
+/// `STITCH_PROPOSED` (the proposed contents, read back once the tool exits) and
+/// `STITCH_PATH` (the project-relative path), e.g.
+/// `meld "$STITCH_CURRENT" "$STITCH_PROPOSED"` or
+/// `code --wait --diff "$STITCH_CURRENT" "$STITCH_PROPOSED"`. Both files are
+/// temporary copies; the project is only written by the plan.
+///
+/// # Errors
+/// Returns the first path [`write_target`] refuses, and stops at the first file whose
+/// tool can't start or exits unsuccessfully (e.g. a merge cancelled in kdiff3), or
+/// whose result isn't UTF-8.
+pub fn review_with_merge_tool(
+    project_root: &Path,
+    command: &str,
+    changes: &[FileChange],
+) -> Result<Vec<FileChange>, String> {
+    static RUNS: AtomicUsize = AtomicUsize::new(0);
+    let scratch = std::env::temp_dir().join(format!(
+        "stitch-merge-{}-{}",
+        std::process::id(),
+        RUNS.fetch_add(1, Ordering::Relaxed)
+    ));
+    let reviewed = changes
+        .iter()
+        .enumerate()
+        .map(|(i, change)| merge_one(project_root, command, change, &scratch.join(i.to_string())))
+        .collect();
+    let _ = fs::remove_dir_all(&scratch);
+    reviewed
+}
+
+fn merge_one(
+    project_root: &Path,
+    command: &str,
+    change: &FileChange,
+    dir: &Path,
+) -> Result<FileChange, String> {
+    let target = write_target(project_root, &change.path)?;
+    let current = match fs::read(&target) {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+        Err(e) => return Err(format!("{}: {e}", change.path)),
+    };
+    // Same file name on both sides, so the tool picks the same syntax for each.
+    let name = target.file_name().unwrap_or_default();
+    let current_file = dir.join("current").join(name);
+    let proposed_file = dir.join("proposed").join(name);
+    let io_err = |e: std::io::Error| format!("{}: {e}", change.path);
+    for (file, contents) in [
+        (&current_file, current.as_slice()),
+        (&proposed_file, change.contents.as_bytes()),
+    ] {
+        fs::create_dir_all(file.parent().unwrap_or(dir)).map_err(io_err)?;
+        fs::write(file, contents).map_err(io_err)?;
+    }
+
+    #[cfg(target_os = "windows")]
+    let mut cmd = {
+        let mut c = Command::new("cmd");
+        c.arg("/C").arg(command);
+        c
+    };
+    #[cfg(not(target_os = "windows"))]
+    let mut cmd = {
+        let mut c = Command::new("sh");
+        c.arg("-c").arg(command);
+        c
+    };
+    let status = cmd
+        .current_dir(project_root)
+        .env("STITCH_CURRENT", &current_file)
+        .env("STITCH_PROPOSED", &proposed_file)
+        .env("STITCH_PATH", &change.path)
+        .stdin(Stdio::null())
+        .status()
+        .map_err(|e| format!("merge tool could not be started: {e}"))?;
+    if !status.success() {
+        return Err(status.code().map_or_else(
+            || format!("merge tool was stopped by a signal on {}", change.path),
+            |code| format!("merge tool exited with code {code} on {}", change.path),
+        ));
+    }
+    let contents = fs::read(&proposed_file).map_err(io_err)?;
+    let contents = String::from_utf8(contents)
+        .map_err(|_| format!("{}: merge result is not UTF-8", change.path))?;
+    Ok(FileChange {
+        path: change.path.clone(),
+        contents,
+    })
+}
+
+/* ============================ Applying a response =========================== */
+
+/// The file blocks of an LLM `response` as changes.
+///
+/// A block is the lines between a `--- Start of file: PATH ---` line and the matching
+/// `--- End of file: PATH ---` line, the default [`FileMarkers`]. A code fence directly around a
+/// block's contents is dropped; a header without its footer is ignored.
+#[must_use]
+pub fn parse_file_changes(response: &str) -> Vec<FileChange> {
+    let markers = FileMarkers::default();
+    let mut changes = Vec::new();
+    let mut lines = response.lines();
+    while let Some(line) = lines.next() {
+        let Some(path) = markers.header_path(line) else {
+            continue;
+        };
+        #[allow(clippy::literal_string_with_formatting_args)] // the marker placeholder
+        let footer = markers.footer.replace("{path}", path);
+        let mut body: Vec<&str> = Vec::new();
+        let mut closed = false;
+        for line in lines.by_ref() {
+            if line == footer {
+                closed = true;
+                break;
+            }
+            body.push(line);
+        }
+        if !closed {
+            break;
+        }
+        changes.push(FileChange {
+            path: path.trim().to_string(),
+            contents: block_contents(&body),
+        });
+    }
+    changes
+}
+
+/// Contents of a block's lines, without a surrounding code fence and ending in a newline.
+fn block_contents(mut body: &[&str]) -> String {
+    while let [rest @ .., last] = body
+        && last.trim().is_empty()
+    {
+        body = rest;
+    }
+    if let [first, inner @ .., last] = body
+        && first.starts_with("```")
+        && last.trim() == "```"
+    {
+        body = inner;
+    }
+    let mut contents = body.join("\n");
+    if !contents.is_empty() {
+        contents.push('\n');
+    }
+    contents
+}
+
+/// Plan writing the changes proposed in `response` to the project at `project_root`.
+///
+/// With a `merge_tool` in the local settings, each change is reviewed there first (see
+/// [`review_with_merge_tool`]) and the plan holds what the tool saved.
+///
+/// # Errors
+/// Returns a one-line message when the response has no file blocks, the merge tool
+/// fails, or [`WriteBackPlan::new`] refuses a change.
+pub fn plan_response_changes(project_root: &Path, response: &str) -> Result<WriteBackPlan, String> {
+    let proposed = parse_file_changes(response);
+    if proposed.is_empty() {
+        return Err("the response has no file blocks".to_string());
+    }
+    let merge_tool =
+        load_local_settings(project_root).and_then(|ls| ls.merge_tool().map(str::to_string));
+    let changes = match merge_tool {
+        Some(command) => review_with_merge_tool(project_root, &command, &proposed)?,
+        None => proposed,
+    };
+    WriteBackPlan::new(project_root, &changes)
+}
+
+fn git(dir: &Path, args: &[&str]) -> Result<String, String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .map_err(|e| format!("git: {e}"))?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
@@ -1 +1,4 @@
 pub mod core;
+
+#[cfg(feature = "testing")]
+pub mod testing;
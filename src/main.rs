@@ -7,30 +7,52 @@ use std::{cell::RefCell, rc::Rc};
 #[cfg(feature = "ui")]
 use slint::ComponentHandle;
 
+#[cfg(feature = "ui")]
+use stitch::core::LaunchOptions;
+
 #[cfg(feature = "ui")]
 use ui::{
-    AppState, AppWindow, Row, SelectFromTextDialog, apply_selection_from_text, on_check_updates,
-    on_copy_output, on_filter_changed, on_generate_output, on_save_profile_as,
-    on_save_profile_current, on_select_folder, on_select_profile, on_toggle_check,
-    on_toggle_expand, on_toggle_fs_watcher,
+    AppState, AppWindow, CallGraphDialog, ContentSearchDialog, HistoryDialog, RankFilesDialog, Row,
+    SelectFromTextDialog, StatsDialog, Strings, TrimBudgetDialog, apply_ranked_selection,
+    apply_selection_from_text, on_check_updates, on_copy_output, on_filter_changed,
+    on_generate_output, on_output_mode_changed, on_save_profile_as, on_save_profile_current,
+    on_select_folder, on_select_profile, on_toggle_check, on_toggle_expand, on_toggle_fs_watcher,
 };
 
 #[cfg(feature = "ui")]
-fn spawn_window(registry: &Rc<RefCell<Vec<AppWindow>>>) -> anyhow::Result<()> {
+fn spawn_window(
+    registry: &Rc<RefCell<Vec<AppWindow>>>,
+    launch: &LaunchOptions,
+) -> anyhow::Result<()> {
     let app = AppWindow::new()?;
+    ui::i18n::select_ui_language();
 
     configure_app_defaults(&app);
 
     let state = Rc::new(RefCell::new(AppState {
         poll_interval_ms: 45_000,
+        preview_char_limit: stitch::core::DEFAULT_PREVIEW_CHAR_LIMIT,
         ..Default::default()
     }));
 
+    ui::subscribe_ui(&app, &state);
     setup_poll_timer(&app, &state);
     wire_browser_handlers(&app, &state);
     wire_generation_handlers(&app, &state);
+    wire_rank_handlers(&app, &state);
+    wire_content_search_handlers(&app, &state);
+    wire_snapshot_handlers(&app, &state);
+    wire_history_handlers(&app, &state);
+    wire_stats_handlers(&app, &state);
+    wire_trim_handlers(&app, &state);
+    wire_call_graph_handlers(&app, &state);
     wire_profile_handlers(&app, &state);
     wire_misc_handlers(&app, &state, registry);
+    ui::refresh_filter_presets(&app, &state, None);
+    ui::apply_user_preferences_to_ui(&app);
+    ui::apply_accessibility_preferences(&app, &state, None);
+
+    apply_launch_options(&app, &state, launch);
 
     app.show()?;
     registry.borrow_mut().push(app);
@@ -38,17 +60,53 @@ fn spawn_window(registry: &Rc<RefCell<Vec<AppWindow>>>) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Open the project/profile requested on the command line; problems are reported on stderr
+/// and leave the window in its usual empty state.
+#[cfg(feature = "ui")]
+fn apply_launch_options(app: &AppWindow, state: &Rc<RefCell<AppState>>, launch: &LaunchOptions) {
+    let Some(project) = &launch.project else {
+        return;
+    };
+    // "Open with" on a file opens the folder it's in.
+    let project = match project.parent() {
+        Some(parent) if project.is_file() && !parent.as_os_str().is_empty() => parent,
+        Some(_) if project.is_file() => std::path::Path::new("."),
+        _ => project.as_path(),
+    };
+    if !project.is_dir() {
+        eprintln!(
+            "stitch: '{}' is not a directory",
+            stitch::core::display_path(project)
+        );
+        return;
+    }
+    ui::open_project(app, state, &stitch::core::normalize_path(project));
+
+    if let Some(name) = &launch.profile
+        && !ui::select_profile_by_name(app, state, name)
+    {
+        eprintln!("stitch: no profile named '{name}' in this project");
+    }
+    for path in ui::apply_selection_paths(app, state, &launch.select, &launch.deselect) {
+        eprintln!("stitch: no file or folder '{path}' in this project");
+    }
+    if launch.generate {
+        on_generate_output(app, state);
+    }
+}
+
 #[cfg(feature = "ui")]
 fn configure_app_defaults(app: &AppWindow) {
     app.set_app_version(env!("CARGO_PKG_VERSION").into());
     app.set_ext_filter("".into());
-    app.set_exclude_dirs(".git, node_modules, target, _target, .elan, .lake, .idea, .vscode, _app, .svelte-kit, .sqlx, venv, .venv, __pycache__, LICENSES, fixtures".into());
-    app.set_exclude_files("LICENSE, Cargo.lock, package-lock.json, yarn.lock, .DS_Store, .dockerignore, .gitignore, .npmignore, .pre-commit-config.yaml, .prettierignore, .prettierrc, eslint.config.js, .env, Thumbs.db".into());
+    app.set_exclude_dirs(stitch::core::DEFAULT_EXCLUDE_DIRS.into());
+    app.set_exclude_files(stitch::core::DEFAULT_EXCLUDE_FILES.into());
     app.set_remove_prefix("".into());
     app.set_remove_regex("".into());
     app.set_hierarchy_only(false);
     app.set_dirs_only(false);
-    app.set_last_refresh("Last refresh: N/A".into());
+    let strings = app.global::<Strings>();
+    app.set_last_refresh(strings.invoke_last_refresh(strings.get_not_available()));
     app.set_tree_model(slint::ModelRc::new(slint::VecModel::<Row>::default()));
     app.set_output_text("".into());
     app.set_show_copy_toast(false);
@@ -99,6 +157,24 @@ fn wire_browser_handlers(app: &AppWindow, state: &Rc<RefCell<AppState>>) {
             }
         });
     }
+    {
+        let app_weak = app.as_weak();
+        let state = Rc::clone(state);
+        app.on_apply_exclude_suggestion(move |idx| {
+            if let (Some(app), Ok(idx_usize)) = (app_weak.upgrade(), usize::try_from(idx)) {
+                ui::on_apply_exclude_suggestion(&app, &state, idx_usize);
+            }
+        });
+    }
+    {
+        let app_weak = app.as_weak();
+        let state = Rc::clone(state);
+        app.on_dismiss_exclude_suggestions(move || {
+            if let Some(app) = app_weak.upgrade() {
+                ui::on_dismiss_exclude_suggestions(&app, &state);
+            }
+        });
+    }
     {
         let app_weak = app.as_weak();
         let state = Rc::clone(state);
@@ -117,6 +193,46 @@ fn wire_browser_handlers(app: &AppWindow, state: &Rc<RefCell<AppState>>) {
             }
         });
     }
+    {
+        let app_weak = app.as_weak();
+        let state = Rc::clone(state);
+        app.on_set_dir_sample(move |idx, count, most_recent| {
+            if let (Some(app), Ok(idx_usize), Ok(count_usize)) = (
+                app_weak.upgrade(),
+                usize::try_from(idx),
+                usize::try_from(count),
+            ) {
+                ui::on_set_dir_sample(&app, &state, idx_usize, count_usize, most_recent);
+            }
+        });
+    }
+    {
+        let app_weak = app.as_weak();
+        let state = Rc::clone(state);
+        app.on_set_dir_rule(move |idx, kind| {
+            if let (Some(app), Ok(idx_usize)) = (app_weak.upgrade(), usize::try_from(idx)) {
+                ui::on_set_dir_rule(&app, &state, idx_usize, kind);
+            }
+        });
+    }
+    {
+        let app_weak = app.as_weak();
+        let state = Rc::clone(state);
+        app.on_toggle_pin(move |idx| {
+            if let (Some(app), Ok(idx_usize)) = (app_weak.upgrade(), usize::try_from(idx)) {
+                ui::on_toggle_pin(&app, &state, idx_usize);
+            }
+        });
+    }
+    {
+        let app_weak = app.as_weak();
+        app.on_parent_row(
+            move |idx| match (app_weak.upgrade(), usize::try_from(idx)) {
+                (Some(app), Ok(idx_usize)) => ui::parent_row_index(&app, idx_usize),
+                _ => -1,
+            },
+        );
+    }
 }
 
 #[cfg(feature = "ui")]
@@ -130,6 +246,15 @@ fn wire_generation_handlers(app: &AppWindow, state: &Rc<RefCell<AppState>>) {
             }
         });
     }
+    {
+        let app_weak = app.as_weak();
+        let state = Rc::clone(state);
+        app.on_output_mode_changed(move || {
+            if let Some(app) = app_weak.upgrade() {
+                on_output_mode_changed(&app, &state);
+            }
+        });
+    }
     {
         let app_weak = app.as_weak();
         let state = Rc::clone(state);
@@ -148,6 +273,87 @@ fn wire_generation_handlers(app: &AppWindow, state: &Rc<RefCell<AppState>>) {
             }
         });
     }
+    {
+        let app_weak = app.as_weak();
+        let state = Rc::clone(state);
+        app.on_copy_hierarchy(move || {
+            if let Some(app) = app_weak.upgrade() {
+                ui::on_copy_hierarchy(&app, &state);
+            }
+        });
+    }
+    {
+        let app_weak = app.as_weak();
+        let state = Rc::clone(state);
+        app.on_save_hierarchy(move || {
+            if let Some(app) = app_weak.upgrade() {
+                ui::on_save_hierarchy(&app, &state);
+            }
+        });
+    }
+    {
+        let app_weak = app.as_weak();
+        let state = Rc::clone(state);
+        app.on_export_compressed(move || {
+            if let Some(app) = app_weak.upgrade() {
+                ui::on_export_compressed(&app, &state);
+            }
+        });
+    }
+    {
+        let app_weak = app.as_weak();
+        let state = Rc::clone(state);
+        app.on_export_split(move || {
+            if let Some(app) = app_weak.upgrade() {
+                ui::on_export_split(&app, &state);
+            }
+        });
+    }
+    {
+        let app_weak = app.as_weak();
+        let state = Rc::clone(state);
+        app.on_find_definition(move |name| {
+            if let Some(app) = app_weak.upgrade() {
+                ui::on_find_definition(&app, &state, &name);
+            }
+        });
+    }
+    {
+        let app_weak = app.as_weak();
+        let state = Rc::clone(state);
+        app.on_find_changed(move |text, case_sensitive| {
+            if let Some(app) = app_weak.upgrade() {
+                ui::on_find_changed(&app, &state, &text, case_sensitive);
+            }
+        });
+    }
+    {
+        let app_weak = app.as_weak();
+        let state = Rc::clone(state);
+        app.on_find_step(move |forward| {
+            if let Some(app) = app_weak.upgrade() {
+                ui::on_find_step(&app, &state, forward);
+            }
+        });
+    }
+    {
+        let app_weak = app.as_weak();
+        let state = Rc::clone(state);
+        app.on_find_closed(move || {
+            if let Some(app) = app_weak.upgrade() {
+                ui::on_find_closed(&app, &state);
+            }
+        });
+    }
+    {
+        let app_weak = app.as_weak();
+        let state = Rc::clone(state);
+        app.on_output_page_step(move |forward| {
+            if let Some(app) = app_weak.upgrade() {
+                ui::on_output_page_step(&app, &state, forward);
+            }
+        });
+    }
     {
         let app_weak = app.as_weak();
         let state = Rc::clone(state);
@@ -186,6 +392,391 @@ fn wire_generation_handlers(app: &AppWindow, state: &Rc<RefCell<AppState>>) {
     }
 }
 
+#[cfg(feature = "ui")]
+fn wire_rank_handlers(app: &AppWindow, state: &Rc<RefCell<AppState>>) {
+    {
+        let app_weak = app.as_weak();
+        let state = Rc::clone(state);
+        app.on_rank_files(move || {
+            if let Some(dlg) = state.borrow().rank_dialog.as_ref() {
+                let _ = dlg.show();
+                return;
+            }
+
+            let dlg = RankFilesDialog::new().expect("create RankFilesDialog");
+
+            let dlg_weak_rank = dlg.as_weak();
+            let state_rank = Rc::clone(&state);
+            dlg.on_rank(move |query, top_k, budget| {
+                if let Some(d) = dlg_weak_rank.upgrade() {
+                    ui::preview_ranked_selection(
+                        &d,
+                        &state_rank,
+                        query.as_ref(),
+                        top_k.as_ref(),
+                        budget.as_ref(),
+                    );
+                }
+            });
+
+            let dlg_weak_apply = dlg.as_weak();
+            let state_apply = Rc::clone(&state);
+            let app_weak_apply = app_weak.clone();
+            dlg.on_apply(move |query, top_k, budget| {
+                if let Some(app) = app_weak_apply.upgrade() {
+                    apply_ranked_selection(
+                        &app,
+                        &state_apply,
+                        query.as_ref(),
+                        top_k.as_ref(),
+                        budget.as_ref(),
+                    );
+                }
+                if let Some(d) = dlg_weak_apply.upgrade() {
+                    let _ = d.hide();
+                }
+            });
+
+            let dlg_weak_cancel = dlg.as_weak();
+            dlg.on_cancel(move || {
+                if let Some(d) = dlg_weak_cancel.upgrade() {
+                    let _ = d.hide();
+                }
+            });
+
+            state.borrow_mut().rank_dialog = Some(dlg);
+            let _ = state.borrow().rank_dialog.as_ref().unwrap().show();
+        });
+    }
+}
+
+#[cfg(feature = "ui")]
+fn wire_content_search_handlers(app: &AppWindow, state: &Rc<RefCell<AppState>>) {
+    let app_weak = app.as_weak();
+    let state = Rc::clone(state);
+    app.on_select_by_content(move || {
+        if let Some(dlg) = state.borrow().content_dialog.as_ref() {
+            let _ = dlg.show();
+            return;
+        }
+
+        let dlg = ContentSearchDialog::new().expect("create ContentSearchDialog");
+
+        let dlg_weak_search = dlg.as_weak();
+        let state_search = Rc::clone(&state);
+        dlg.on_search(move |pattern, use_regex, case_sensitive| {
+            if let Some(d) = dlg_weak_search.upgrade() {
+                let preview = ui::preview_content_selection(
+                    &state_search,
+                    pattern.as_ref(),
+                    use_regex,
+                    case_sensitive,
+                );
+                d.set_results(preview.into());
+            }
+        });
+
+        let dlg_weak_apply = dlg.as_weak();
+        let state_apply = Rc::clone(&state);
+        let app_weak_apply = app_weak.clone();
+        dlg.on_apply(move |pattern, use_regex, case_sensitive| {
+            if let Some(app) = app_weak_apply.upgrade() {
+                ui::apply_content_selection(
+                    &app,
+                    &state_apply,
+                    pattern.as_ref(),
+                    use_regex,
+                    case_sensitive,
+                );
+            }
+            if let Some(d) = dlg_weak_apply.upgrade() {
+                let _ = d.hide();
+            }
+        });
+
+        let dlg_weak_cancel = dlg.as_weak();
+        dlg.on_cancel(move || {
+            if let Some(d) = dlg_weak_cancel.upgrade() {
+                let _ = d.hide();
+            }
+        });
+
+        state.borrow_mut().content_dialog = Some(dlg);
+        let _ = state.borrow().content_dialog.as_ref().unwrap().show();
+    });
+}
+
+#[cfg(feature = "ui")]
+fn wire_snapshot_handlers(app: &AppWindow, state: &Rc<RefCell<AppState>>) {
+    {
+        let app_weak = app.as_weak();
+        let state = Rc::clone(state);
+        app.on_take_snapshot(move |label| {
+            if let Some(app) = app_weak.upgrade() {
+                ui::on_take_snapshot(&app, &state, &label);
+            }
+        });
+    }
+    {
+        let app_weak = app.as_weak();
+        let state = Rc::clone(state);
+        app.on_restore_snapshot(move |idx| {
+            if let Some(app) = app_weak.upgrade() {
+                ui::on_restore_snapshot(&app, &state, idx);
+            }
+        });
+    }
+    {
+        let app_weak = app.as_weak();
+        let state = Rc::clone(state);
+        app.on_delete_snapshot(move |idx| {
+            if let Some(app) = app_weak.upgrade() {
+                ui::on_delete_snapshot(&app, &state, idx);
+            }
+        });
+    }
+}
+
+#[cfg(feature = "ui")]
+fn wire_history_handlers(app: &AppWindow, state: &Rc<RefCell<AppState>>) {
+    let app_weak = app.as_weak();
+    let state = Rc::clone(state);
+    app.on_show_history(move || {
+        let existing = state
+            .borrow()
+            .history_dialog
+            .as_ref()
+            .map(ComponentHandle::clone_strong);
+        if let Some(dlg) = existing {
+            ui::refresh_history_dialog(&dlg, &state);
+            let _ = dlg.show();
+            return;
+        }
+
+        let dlg = HistoryDialog::new().expect("create HistoryDialog");
+
+        let dlg_weak = dlg.as_weak();
+        let state_show = Rc::clone(&state);
+        dlg.on_show_entry(move |idx| {
+            if let Some(d) = dlg_weak.upgrade() {
+                ui::on_history_show_entry(&d, &state_show, idx);
+            }
+        });
+
+        let dlg_weak = dlg.as_weak();
+        let state_diff = Rc::clone(&state);
+        let app_weak_diff = app_weak.clone();
+        dlg.on_diff_with_current(move |idx| {
+            if let (Some(app), Some(d)) = (app_weak_diff.upgrade(), dlg_weak.upgrade()) {
+                ui::on_history_diff_with_current(&app, &d, &state_diff, idx);
+            }
+        });
+
+        let dlg_weak = dlg.as_weak();
+        let state_older = Rc::clone(&state);
+        let app_weak_older = app_weak.clone();
+        dlg.on_diff_with_older(move |idx| {
+            if let (Some(app), Some(d)) = (app_weak_older.upgrade(), dlg_weak.upgrade()) {
+                ui::on_history_diff_with_older(&app, &d, &state_older, idx);
+            }
+        });
+
+        let dlg_weak = dlg.as_weak();
+        let state_copy = Rc::clone(&state);
+        let app_weak_copy = app_weak.clone();
+        dlg.on_copy_entry(move |idx| {
+            if let (Some(app), Some(d)) = (app_weak_copy.upgrade(), dlg_weak.upgrade()) {
+                ui::on_history_copy_entry(&app, &d, &state_copy, idx);
+            }
+        });
+
+        let dlg_weak = dlg.as_weak();
+        dlg.on_close(move || {
+            if let Some(d) = dlg_weak.upgrade() {
+                let _ = d.hide();
+            }
+        });
+
+        ui::refresh_history_dialog(&dlg, &state);
+        state.borrow_mut().history_dialog = Some(dlg);
+        let _ = state.borrow().history_dialog.as_ref().unwrap().show();
+    });
+}
+
+#[cfg(feature = "ui")]
+fn wire_stats_handlers(app: &AppWindow, state: &Rc<RefCell<AppState>>) {
+    let app_weak = app.as_weak();
+    let state = Rc::clone(state);
+    app.on_show_stats(move || {
+        let Some(app) = app_weak.upgrade() else {
+            return;
+        };
+        let existing = state
+            .borrow()
+            .stats_dialog
+            .as_ref()
+            .map(ComponentHandle::clone_strong);
+        if let Some(dlg) = existing {
+            ui::refresh_stats_dialog(&app, &dlg, &state);
+            let _ = dlg.show();
+            return;
+        }
+
+        let dlg = StatsDialog::new().expect("create StatsDialog");
+
+        let dlg_weak = dlg.as_weak();
+        let state_refresh = Rc::clone(&state);
+        let app_weak_refresh = app_weak.clone();
+        dlg.on_refresh(move || {
+            if let (Some(app), Some(d)) = (app_weak_refresh.upgrade(), dlg_weak.upgrade()) {
+                ui::refresh_stats_dialog(&app, &d, &state_refresh);
+            }
+        });
+
+        let dlg_weak = dlg.as_weak();
+        let app_weak_copy = app_weak.clone();
+        dlg.on_copy_report(move || {
+            if let (Some(app), Some(d)) = (app_weak_copy.upgrade(), dlg_weak.upgrade()) {
+                ui::on_stats_copy(&app, &d);
+            }
+        });
+
+        let dlg_weak = dlg.as_weak();
+        dlg.on_close(move || {
+            if let Some(d) = dlg_weak.upgrade() {
+                let _ = d.hide();
+            }
+        });
+
+        ui::refresh_stats_dialog(&app, &dlg, &state);
+        state.borrow_mut().stats_dialog = Some(dlg);
+        let _ = state.borrow().stats_dialog.as_ref().unwrap().show();
+    });
+}
+
+#[cfg(feature = "ui")]
+fn wire_trim_handlers(app: &AppWindow, state: &Rc<RefCell<AppState>>) {
+    let app_weak = app.as_weak();
+    let state = Rc::clone(state);
+    app.on_trim_to_budget(move || {
+        let Some(app) = app_weak.upgrade() else {
+            return;
+        };
+        let existing = state
+            .borrow()
+            .trim_dialog
+            .as_ref()
+            .map(ComponentHandle::clone_strong);
+        if let Some(dlg) = existing {
+            ui::refresh_trim_dialog(&app, &dlg, &state);
+            let _ = dlg.show();
+            return;
+        }
+
+        let dlg = TrimBudgetDialog::new().expect("create TrimBudgetDialog");
+
+        let dlg_weak = dlg.as_weak();
+        let state_toggle = Rc::clone(&state);
+        let app_weak_toggle = app_weak.clone();
+        dlg.on_toggle(move |idx, checked| {
+            if let (Some(app), Some(d)) = (app_weak_toggle.upgrade(), dlg_weak.upgrade()) {
+                ui::on_trim_toggle(&app, &d, &state_toggle, idx, checked);
+            }
+        });
+
+        let dlg_weak = dlg.as_weak();
+        let state_suggest = Rc::clone(&state);
+        let app_weak_suggest = app_weak.clone();
+        dlg.on_suggest(move || {
+            if let (Some(app), Some(d)) = (app_weak_suggest.upgrade(), dlg_weak.upgrade()) {
+                ui::on_trim_suggest(&app, &d, &state_suggest);
+            }
+        });
+
+        let dlg_weak = dlg.as_weak();
+        let state_apply = Rc::clone(&state);
+        let app_weak_apply = app_weak.clone();
+        dlg.on_apply(move || {
+            if let (Some(app), Some(d)) = (app_weak_apply.upgrade(), dlg_weak.upgrade()) {
+                ui::on_trim_apply(&app, &d, &state_apply);
+                let _ = d.hide();
+            }
+        });
+
+        let dlg_weak = dlg.as_weak();
+        dlg.on_cancel(move || {
+            if let Some(d) = dlg_weak.upgrade() {
+                let _ = d.hide();
+            }
+        });
+
+        ui::refresh_trim_dialog(&app, &dlg, &state);
+        state.borrow_mut().trim_dialog = Some(dlg);
+        let _ = state.borrow().trim_dialog.as_ref().unwrap().show();
+    });
+}
+
+#[cfg(feature = "ui")]
+fn wire_call_graph_handlers(app: &AppWindow, state: &Rc<RefCell<AppState>>) {
+    let app_weak = app.as_weak();
+    let state = Rc::clone(state);
+    app.on_call_graph(move |focused_row| {
+        let Some(app) = app_weak.upgrade() else {
+            return;
+        };
+        let existing = state
+            .borrow()
+            .call_graph_dialog
+            .as_ref()
+            .map(ComponentHandle::clone_strong);
+        if let Some(dlg) = existing {
+            ui::refresh_call_graph_dialog(&app, &dlg, &state, focused_row);
+            let _ = dlg.show();
+            return;
+        }
+
+        let dlg = CallGraphDialog::new().expect("create CallGraphDialog");
+
+        let dlg_weak = dlg.as_weak();
+        let state_file = Rc::clone(&state);
+        dlg.on_file_selected(move |idx| {
+            if let Some(d) = dlg_weak.upgrade() {
+                ui::on_call_graph_file_selected(&d, &state_file, idx);
+            }
+        });
+
+        let dlg_weak = dlg.as_weak();
+        let state_preview = Rc::clone(&state);
+        dlg.on_preview(move |file_idx, function_idx, depth| {
+            if let Some(d) = dlg_weak.upgrade() {
+                ui::on_call_graph_preview(&d, &state_preview, file_idx, function_idx, &depth);
+            }
+        });
+
+        let dlg_weak = dlg.as_weak();
+        let state_apply = Rc::clone(&state);
+        let app_weak_apply = app_weak.clone();
+        dlg.on_apply(move |file_idx, function_idx, depth| {
+            if let (Some(app), Some(d)) = (app_weak_apply.upgrade(), dlg_weak.upgrade())
+                && ui::on_call_graph_apply(&app, &d, &state_apply, file_idx, function_idx, &depth)
+            {
+                let _ = d.hide();
+            }
+        });
+
+        let dlg_weak = dlg.as_weak();
+        dlg.on_cancel(move || {
+            if let Some(d) = dlg_weak.upgrade() {
+                let _ = d.hide();
+            }
+        });
+
+        ui::refresh_call_graph_dialog(&app, &dlg, &state, focused_row);
+        state.borrow_mut().call_graph_dialog = Some(dlg);
+        let _ = state.borrow().call_graph_dialog.as_ref().unwrap().show();
+    });
+}
+
 #[cfg(feature = "ui")]
 fn wire_profile_handlers(app: &AppWindow, state: &Rc<RefCell<AppState>>) {
     {
@@ -215,6 +806,51 @@ fn wire_profile_handlers(app: &AppWindow, state: &Rc<RefCell<AppState>>) {
             }
         });
     }
+    {
+        let app_weak = app.as_weak();
+        let state = Rc::clone(state);
+        app.on_apply_filter_preset(move |idx| {
+            if let Some(app) = app_weak.upgrade() {
+                ui::on_apply_filter_preset(&app, &state, idx);
+            }
+        });
+    }
+    {
+        let app_weak = app.as_weak();
+        let state = Rc::clone(state);
+        app.on_save_filter_preset(move |name| {
+            if let Some(app) = app_weak.upgrade() {
+                ui::on_save_filter_preset(&app, &state, &name);
+            }
+        });
+    }
+    {
+        let app_weak = app.as_weak();
+        let state = Rc::clone(state);
+        app.on_import_settings(move || {
+            if let Some(app) = app_weak.upgrade() {
+                ui::on_import_settings(&app, &state);
+            }
+        });
+    }
+    {
+        let app_weak = app.as_weak();
+        let state = Rc::clone(state);
+        app.on_delete_filter_preset(move |idx| {
+            if let Some(app) = app_weak.upgrade() {
+                ui::on_delete_filter_preset(&app, &state, idx);
+            }
+        });
+    }
+    {
+        let app_weak = app.as_weak();
+        let state = Rc::clone(state);
+        app.on_run_task(move |idx| {
+            if let Some(app) = app_weak.upgrade() {
+                ui::on_run_task(&app, &state, idx);
+            }
+        });
+    }
 }
 
 #[cfg(feature = "ui")]
@@ -226,7 +862,7 @@ fn wire_misc_handlers(
     {
         let registry_clone = Rc::clone(registry);
         app.on_new_window(move || {
-            let _ = spawn_window(&registry_clone);
+            let _ = spawn_window(&registry_clone, &LaunchOptions::default());
         });
     }
     {
@@ -247,6 +883,15 @@ fn wire_misc_handlers(
             }
         });
     }
+    {
+        let app_weak = app.as_weak();
+        let state = Rc::clone(state);
+        app.on_clean_profile(move || {
+            if let Some(app) = app_weak.upgrade() {
+                ui::on_clean_profile(&app, &state);
+            }
+        });
+    }
     {
         let app_weak = app.as_weak();
         let state = Rc::clone(state);
@@ -256,15 +901,188 @@ fn wire_misc_handlers(
             }
         });
     }
+    {
+        let app_weak = app.as_weak();
+        app.on_theme_changed(move || {
+            if let Some(app) = app_weak.upgrade() {
+                ui::on_theme_changed(&app);
+            }
+        });
+    }
+    {
+        let app_weak = app.as_weak();
+        app.on_output_font_changed(move || {
+            if let Some(app) = app_weak.upgrade() {
+                ui::on_output_font_changed(&app);
+            }
+        });
+    }
+    {
+        let app_weak = app.as_weak();
+        let state = Rc::clone(state);
+        app.on_toggle_update_check(move || {
+            if let Some(app) = app_weak.upgrade() {
+                ui::on_toggle_update_check(&app, &state);
+            }
+        });
+    }
+    {
+        let app_weak = app.as_weak();
+        let state = Rc::clone(state);
+        app.on_token_mode_changed(move || {
+            if let Some(app) = app_weak.upgrade() {
+                ui::on_token_mode_changed(&app, &state);
+            }
+        });
+    }
+    {
+        let app_weak = app.as_weak();
+        app.on_open_release_page(move || {
+            if let Some(app) = app_weak.upgrade() {
+                ui::on_open_release_page(&app);
+            }
+        });
+    }
 }
 
-#[cfg(feature = "ui")]
 fn main() -> anyhow::Result<()> {
+    let launch = match stitch::core::parse_launch_args(std::env::args_os().skip(1)) {
+        Ok(launch) => launch,
+        Err(e) => {
+            eprintln!("stitch: {e}\n\n{}", stitch::core::usage());
+            std::process::exit(2);
+        }
+    };
+    if launch.help {
+        print!("{}", stitch::core::usage());
+        return Ok(());
+    }
+    if launch.version {
+        println!("stitch {}", env!("CARGO_PKG_VERSION"));
+        return Ok(());
+    }
+    if let Some(command) = launch.command {
+        let text = match command {
+            stitch::core::CliCommand::Apply { response, yes } => {
+                let dir = launch
+                    .project
+                    .as_deref()
+                    .unwrap_or(std::path::Path::new("."));
+                match apply_response(dir, &response, yes) {
+                    Ok(text) => text,
+                    Err(e) => {
+                        eprintln!("stitch: {e}");
+                        std::process::exit(1);
+                    }
+                }
+            }
+            stitch::core::CliCommand::Completions(shell) => stitch::core::render_completions(shell),
+            stitch::core::CliCommand::Man => {
+                stitch::core::render_man_page(env!("CARGO_PKG_VERSION"))
+            }
+            stitch::core::CliCommand::Stats => {
+                let dir = launch
+                    .project
+                    .as_deref()
+                    .unwrap_or(std::path::Path::new("."));
+                match stitch::core::project_stats_report(dir, launch.profile.as_deref()) {
+                    Ok(report) => report,
+                    Err(e) => {
+                        eprintln!("stitch: {e}");
+                        std::process::exit(1);
+                    }
+                }
+            }
+            stitch::core::CliCommand::Run(task) => {
+                let dir = launch
+                    .project
+                    .as_deref()
+                    .unwrap_or(std::path::Path::new("."));
+                match stitch::core::run_task(dir, &task) {
+                    Ok(stitch::core::TaskRun {
+                        written: Some(path),
+                        files,
+                        ..
+                    }) => format!(
+                        "Wrote {files} files to {}\n",
+                        stitch::core::display_path(&path)
+                    ),
+                    Ok(run) => run.text,
+                    Err(e) => {
+                        eprintln!("stitch: {e}");
+                        std::process::exit(1);
+                    }
+                }
+            }
+            stitch::core::CliCommand::Schema(target) => stitch::core::render_json_schema(target),
+            stitch::core::CliCommand::Integrate(platform) => {
+                let exe = std::env::current_exe()
+                    .map(|p| stitch::core::normalize_path(&p))
+                    .unwrap_or_else(|_| "stitch".into());
+                stitch::core::render_integration(platform, &exe)
+            }
+        };
+        // Ignore broken pipes, e.g. `stitch man | head`.
+        let _ = std::io::Write::write_all(&mut std::io::stdout(), text.as_bytes());
+        return Ok(());
+    }
+
+    launch_ui(&launch)
+}
+
+/// `stitch apply`: print what the response changes, ask unless `yes`, then write it.
+fn apply_response(
+    project: &std::path::Path,
+    response: &std::path::Path,
+    yes: bool,
+) -> Result<String, String> {
+    use std::io::{BufRead, Read, Write};
+
+    let from_stdin = response == std::path::Path::new("-");
+    let text = if from_stdin {
+        let mut text = String::new();
+        std::io::stdin()
+            .read_to_string(&mut text)
+            .map_err(|e| format!("stdin: {e}"))?;
+        text
+    } else {
+        std::fs::read_to_string(response)
+            .map_err(|e| format!("{}: {e}", stitch::core::display_path(response)))?
+    };
+    let plan = stitch::core::plan_response_changes(project, &text)?;
+    if plan.is_empty() {
+        return Ok("Nothing to change.\n".to_string());
+    }
+    print!("{}", plan.render_summary());
+    if !yes {
+        if from_stdin {
+            return Err("the response was read from stdin; pass --yes to write it".to_string());
+        }
+        print!("Write these changes? [y/N] ");
+        let _ = std::io::stdout().flush();
+        let mut answer = String::new();
+        let _ = std::io::stdin().lock().read_line(&mut answer);
+        if !matches!(answer.trim(), "y" | "Y" | "yes") {
+            return Ok("Nothing was written.\n".to_string());
+        }
+    }
+    let files = plan.stats().len();
+    let noun = if files == 1 { "file" } else { "files" };
+    Ok(match plan.write()? {
+        stitch::core::GitSafety::Clean => format!("Wrote {files} {noun}.\n"),
+        stitch::core::GitSafety::Stashed(id) => {
+            format!("Wrote {files} {noun}; uncommitted changes were saved as stash {id}.\n")
+        }
+    })
+}
+
+#[cfg(feature = "ui")]
+fn launch_ui(launch: &LaunchOptions) -> anyhow::Result<()> {
     // Keep all open windows alive in this registry
     let registry: Rc<RefCell<Vec<AppWindow>>> = Rc::new(RefCell::new(Vec::new()));
 
     // Create the initial window
-    spawn_window(&registry)?;
+    spawn_window(&registry, launch)?;
 
     // One global event loop; closes when all windows are closed
     slint::run_event_loop()?;
@@ -272,10 +1090,10 @@ fn main() -> anyhow::Result<()> {
 }
 
 #[cfg(not(feature = "ui"))]
-fn main() -> anyhow::Result<()> {
+fn launch_ui(_launch: &stitch::core::LaunchOptions) -> anyhow::Result<()> {
     eprintln!(
-        "Built without the `ui` feature; nothing to run. \
-Enable it with `--features ui`, or just run tests with `--no-default-features`."
+        "Built without the `ui` feature; only the subcommands are available (see `stitch --help`). \
+Enable the window with `--features ui`."
     );
-    Ok(())
+    std::process::exit(2);
 }
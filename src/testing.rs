@@ -0,0 +1,286 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use proptest::prelude::*;
+use tempfile::TempDir;
+
+/* ============================ Fixture project trees ========================== */
+
+/// A file to create under a fixture root: its directory components and file name.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FileSpec {
+    /// Relative directory components (empty for a file at the root).
+    pub dirs: Vec<String>,
+    /// File name, possibly with several dots.
+    pub fname: String,
+}
+
+impl FileSpec {
+    /// `a/b/c.rs` → dirs `["a", "b"]`, name `c.rs`.
+    #[must_use]
+    pub fn parse(rel: &str) -> Self {
+        let mut parts: Vec<String> = rel
+            .split('/')
+            .filter(|p| !p.is_empty())
+            .map(str::to_string)
+            .collect();
+        let fname = parts.pop().unwrap_or_default();
+        Self { dirs: parts, fname }
+    }
+
+    /// `/`-separated path relative to the fixture root.
+    #[must_use]
+    pub fn rel_path(&self) -> String {
+        let mut parts = self.dirs.clone();
+        parts.push(self.fname.clone());
+        parts.join("/")
+    }
+
+    /// Last extension, lowercased with its dot (`.rs`), or `""` for none — the form
+    /// [`crate::core::parse_extension_filters`] produces.
+    #[must_use]
+    pub fn last_ext(&self) -> String {
+        Path::new(&self.fname)
+            .extension()
+            .map_or_else(String::new, |e| {
+                format!(".{}", e.to_string_lossy().to_lowercase())
+            })
+    }
+}
+
+/// Write `body` to `root/rel` (`/`-separated), creating parent directories.
+///
+/// # Errors
+/// Returns I/O errors from creating directories or writing the file.
+pub fn write_file(root: &Path, rel: &str, body: impl AsRef<[u8]>) -> io::Result<PathBuf> {
+    let path = rel
+        .split('/')
+        .filter(|p| !p.is_empty())
+        .fold(root.to_path_buf(), |p, part| p.join(part));
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&path, body)?;
+    Ok(path)
+}
+
+/// Create every file in `files` under `root` with the contents `"x"`.
+///
+/// # Errors
+/// Returns I/O errors from creating directories or writing files.
+pub fn make_on_disk(root: &Path, files: &[FileSpec]) -> io::Result<()> {
+    for f in files {
+        write_file(root, &f.rel_path(), "x")?;
+    }
+    Ok(())
+}
+
+/// A project tree in a temporary directory, removed when dropped.
+#[derive(Debug)]
+pub struct Fixture {
+    dir: TempDir,
+}
+
+impl Fixture {
+    #[must_use]
+    pub fn root(&self) -> &Path {
+        self.dir.path()
+    }
+
+    /// Absolute path of `rel` (`/`-separated) inside the fixture.
+    #[must_use]
+    pub fn path(&self, rel: &str) -> PathBuf {
+        rel.split('/')
+            .filter(|p| !p.is_empty())
+            .fold(self.root().to_path_buf(), |p, part| p.join(part))
+    }
+
+    /// Add or overwrite a file after the fixture was built.
+    ///
+    /// # Errors
+    /// Returns I/O errors from writing the file.
+    pub fn write(&self, rel: &str, body: &str) -> io::Result<PathBuf> {
+        write_file(self.root(), rel, body)
+    }
+}
+
+/// Collects files and empty directories, then writes them to a fresh temporary directory:
+///
+/// ```
+/// # use stitch::testing::FixtureBuilder;
+/// let fx = FixtureBuilder::new()
+///     .file("src/main.rs", "fn main() {}")
+///     .dir("assets")
+///     .build()
+///     .unwrap();
+/// assert!(fx.path("src/main.rs").is_file());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct FixtureBuilder {
+    files: Vec<(String, String)>,
+    dirs: Vec<String>,
+}
+
+impl FixtureBuilder {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A file at `rel` (`/`-separated) with `body`.
+    #[must_use]
+    pub fn file(mut self, rel: &str, body: &str) -> Self {
+        self.files.push((rel.to_string(), body.to_string()));
+        self
+    }
+
+    /// Each of `rels` with the contents `"x"`.
+    #[must_use]
+    pub fn files<'a>(mut self, rels: impl IntoIterator<Item = &'a str>) -> Self {
+        self.files.extend(
+            rels.into_iter()
+                .map(|rel| (rel.to_string(), "x".to_string())),
+        );
+        self
+    }
+
+    /// The files of `specs` with the contents `"x"`.
+    #[must_use]
+    pub fn specs(mut self, specs: &[FileSpec]) -> Self {
+        self.files
+            .extend(specs.iter().map(|s| (s.rel_path(), "x".to_string())));
+        self
+    }
+
+    /// An (otherwise empty) directory at `rel`.
+    #[must_use]
+    pub fn dir(mut self, rel: &str) -> Self {
+        self.dirs.push(rel.to_string());
+        self
+    }
+
+    /// Write everything to a new temporary directory.
+    ///
+    /// # Errors
+    /// Returns I/O errors from creating the directory or its contents.
+    pub fn build(self) -> io::Result<Fixture> {
+        let fixture = Fixture {
+            dir: TempDir::new()?,
+        };
+        for dir in &self.dirs {
+            fs::create_dir_all(fixture.path(dir))?;
+        }
+        for (rel, body) in &self.files {
+            fixture.write(rel, body)?;
+        }
+        Ok(fixture)
+    }
+}
+
+/* ============================= Random project trees ========================== */
+
+/// Size and shape of a tree from [`random_tree`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TreeShape {
+    pub files: usize,
+    /// Directory components per file, at most.
+    pub max_depth: usize,
+    /// Distinct directory names per level, so files share directories.
+    pub dir_names: usize,
+    /// Extensions are picked from the first `extensions` of [`RANDOM_EXTENSIONS`].
+    pub extensions: usize,
+}
+
+impl Default for TreeShape {
+    fn default() -> Self {
+        Self {
+            files: 100,
+            max_depth: 3,
+            dir_names: 4,
+            extensions: 4,
+        }
+    }
+}
+
+pub const RANDOM_EXTENSIONS: [&str; 8] = ["rs", "toml", "md", "txt", "json", "py", "ts", "lock"];
+
+/// A deterministic tree for `seed`: the same seed and shape give the same files, sorted
+/// by path and without duplicates.
+#[must_use]
+pub fn random_tree(seed: u64, shape: TreeShape) -> Vec<FileSpec> {
+    let mut rng = SplitMix64(seed);
+    let extensions = shape.extensions.clamp(1, RANDOM_EXTENSIONS.len());
+    let mut specs: Vec<FileSpec> = (0..shape.files)
+        .map(|i| {
+            let depth = rng.below(shape.max_depth + 1);
+            let dirs = (0..depth)
+                .map(|level| format!("d{level}_{}", rng.below(shape.dir_names.max(1))))
+                .collect();
+            let ext = RANDOM_EXTENSIONS[rng.below(extensions)];
+            FileSpec {
+                dirs,
+                fname: format!("f{i}.{ext}"),
+            }
+        })
+        .collect();
+    specs.sort_by_key(FileSpec::rel_path);
+    specs.dedup();
+    specs
+}
+
+/// Small, dependency-free PRNG for reproducible trees.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    const fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        if n == 0 {
+            return 0;
+        }
+        usize::try_from(self.next() % n as u64).unwrap_or(0)
+    }
+}
+
+/* ============================== Proptest strategies ========================== */
+
+/// A directory or file name part: 1–8 of `[A-Za-z0-9_-]`.
+pub fn name_segment() -> impl Strategy<Value = String> {
+    "[A-Za-z0-9_\\-]{1,8}"
+}
+
+/// A lowercase extension of 1–3 letters, without the dot.
+pub fn extension_segment() -> impl Strategy<Value = String> {
+    "[a-z]{1,3}"
+}
+
+/// A file name with one extension (70%) or two (30%).
+pub fn file_name() -> impl Strategy<Value = String> {
+    prop_oneof![
+        7 => (name_segment(), extension_segment()).prop_map(|(base, e)| format!("{base}.{e}")),
+        3 => (name_segment(), extension_segment(), extension_segment())
+            .prop_map(|(base, e1, e2)| format!("{base}.{e1}.{e2}")),
+    ]
+}
+
+/// A file up to `max_depth` directories deep.
+pub fn file_spec(max_depth: usize) -> impl Strategy<Value = FileSpec> {
+    (
+        prop::collection::vec(name_segment(), 0..=max_depth),
+        file_name(),
+    )
+        .prop_map(|(dirs, fname)| FileSpec { dirs, fname })
+}
+
+/// Between `min` and `max` files, up to 2 directories deep.
+pub fn file_specs(min: usize, max: usize) -> impl Strategy<Value = Vec<FileSpec>> {
+    prop::collection::vec(file_spec(2), min..=max)
+}
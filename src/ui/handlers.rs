@@ -1,5 +1,8 @@
-use super::{AppWindow, Row};
-use crate::ui::state::{CommentRemoval, SharedState};
+use super::{
+    AppWindow, CallGraphDialog, HistoryDialog, RankFilesDialog, Row, StatsDialog, Strings,
+    TrimBudgetDialog, TrimRow, tree_model::TreeRows,
+};
+use crate::ui::state::{AppState, CachedOutput, OutputFind, OutputMode, SharedState};
 use chrono::Local;
 use slint::{ComponentHandle, Model, ModelRc, VecModel};
 use std::{
@@ -7,31 +10,61 @@ use std::{
     collections::{HashMap, HashSet},
     fs,
     path::{Path, PathBuf},
+    rc::Rc,
 };
 
 use notify::{RecommendedWatcher, RecursiveMode, Watcher};
-use std::sync::mpsc;
+use std::sync::{OnceLock, mpsc};
 
 use stitch::core::{
-    Node, Profile, ProfileScope, RustFilterOptions, RustOptions, SlintOptions, WorkspaceSettings,
-    apply_rust_filters, apply_slint_filters, clean_remove_regex, collapse_consecutive_blank_lines,
-    collect_selected_paths, compile_remove_regex_opt, delete_profile, ensure_profiles_dirs,
-    ensure_workspace_dir, gather_paths_set, is_ancestor_of, is_rust_file_path, is_slint_file_path,
-    list_profiles, load_local_settings, load_profile, load_workspace, parse_extension_filters,
-    parse_hierarchy_text, path_to_unix, render_unicode_tree_from_paths, save_local_settings,
-    save_profile, save_workspace, scan_dir_to_node_with_stats, signatures_filter_matches,
-    split_prefix_list,
+    CLikeOptions, CallGraphContext, CompressedFormat, ContentCache, ContentQuery,
+    ContentSearchReport, ContextWindow, DEFAULT_CALL_GRAPH_DEPTH, DEFAULT_TOKENIZER, DirRuleKind,
+    DirectoryRule, DirectorySample, ExcludeTarget, FileMarkers, FilterPreset, GenerationError,
+    GenerationOutput, GenerationProgress, GenerationRequest, Generator, GoOptions, HistoryEntry,
+    IgnoreRules, LocalSettings, NO_FOLDER_SELECTED, NO_ITEMS_SELECTED, NameMatching, Node,
+    OutputKind, OutputLayout, OutputOrder, OutputParts, PathRename, PathRewrite,
+    PlatformPreferences, PriceTable, Profile, ProfileChanges, ProfileScope, Provenance, RankedFile,
+    RustOptions, STITCHIGNORE_FILE, SampleOmission, SampleOrder, ScanFilters, ScanResult,
+    ScanStats, ScannedPaths, SelectionStats, SettingsField, SlintOptions, SqlOptions, SymbolIndex,
+    TaskRun, Theme, TokenizerSpec, TransformPipeline, TreeStyle, WatchTarget, WebOptions,
+    WorkspaceEvent, WorkspaceSettings, add_snapshot, append_filter_token, append_post_process_note,
+    apply_directory_samples, apply_pinned_files, apply_selection_diff, call_graph_context,
+    collapse_consecutive_blank_lines, collect_file_paths, collect_project_stats,
+    collect_selected_paths, compress_bundle, current_git_branch, delete_filter_preset,
+    delete_profile, delete_snapshot, detect_platform_preferences, diff_profiles,
+    diff_workspace_settings, dir_rule_for, display_path, ensure_profiles_dirs,
+    ensure_workspace_dir, estimate_tokens_with_markers, extension_filter_match_counts,
+    field_error_message, find_definition, find_filter_overlaps, find_stale_entries,
+    find_tool_config, format_byte_size, format_cost, gather_paths_set, git_head_file,
+    has_output_content, import_tool_config, is_event_path_relevant_with_name_matching,
+    is_rel_path_within, is_rust_file_path, is_unloaded_dir, list_backups, list_history,
+    list_profiles, load_content_cache, load_dir, load_filter_presets, load_history_output,
+    load_local_settings, load_price_table, load_profile, load_selected_dirs, load_snapshots,
+    load_tasks, load_tokenizer, load_user_preferences, load_workspace, loaded_dirs,
+    minimize_watch_targets, normalize_filter_settings, page_of, parse_extension_filters,
+    parse_hierarchy_text, path_to_unix, preview_pages, profile_for_branch, profile_path,
+    project_path_from_rel, projected_tokens, rank_files_by_query, rebase_path_keys, record_history,
+    remap_profile_paths, remove_stale_entries, render_dependencies_section, render_diff,
+    render_hierarchy, render_notes, render_unselected_dirs_summary, restore_latest_backup,
+    run_task, rust_function_names, save_filter_preset, save_local_settings, save_profile,
+    save_user_preferences, save_workspace, scan_dir_to_node_lazy,
+    scan_dir_to_node_with_name_matching, search_file_contents, select_only,
+    select_top_within_budget, selection_watch_targets, set_selection_for_subtree,
+    split_output_by_top_level_dir, suggest_excludes, suggest_trim,
+    summarize_unselected_top_level_dirs, trim_candidates, user_config_dir,
+    validate_workspace_settings, workspace_file,
 };
 
 fn walk_and_mark(
     node: &Node,
     project_root: &Path,
     wanted: &std::collections::HashSet<String>,
+    rewrite: &PathRewrite,
     explicit: &mut HashMap<PathBuf, bool>,
 ) {
     if node.is_dir {
         for c in &node.children {
-            walk_and_mark(c, project_root, wanted, explicit);
+            walk_and_mark(c, project_root, wanted, rewrite, explicit);
         }
     } else if let Ok(rel) = node.path.strip_prefix(project_root) {
         let key = rel
@@ -39,15 +72,13 @@ fn walk_and_mark(
             .map(|c| c.to_string_lossy())
             .collect::<Vec<_>>()
             .join("/");
-        if wanted.contains(&key) {
+        if wanted.contains(&key) || wanted.contains(&rewrite.apply(&key)) {
             explicit.insert(node.path.clone(), true);
         }
     }
 }
 
-const UI_OUTPUT_CHAR_LIMIT: usize = 50_000;
-
-#[cfg(feature = "tokens")]
+/// Larger outputs always get the heuristic estimate instead of a full BPE pass.
 const MAX_TOKENIZE_BYTES: usize = 16 * 1024 * 1024;
 
 struct SelectionSnapshot {
@@ -55,6 +86,7 @@ struct SelectionSnapshot {
     relative_paths: Vec<String>,
     selected_dir: PathBuf,
     root_name: String,
+    sample_omissions: Vec<SampleOmission>,
 }
 
 enum SelectionError {
@@ -62,89 +94,10 @@ enum SelectionError {
     NoItems,
 }
 
-struct GenerationJob {
-    header: String,
-    files: Vec<PathBuf>,
-    selected_dir: PathBuf,
-    remove_prefixes: Vec<String>,
-    remove_regex: Option<regex::Regex>,
-    rust_opts: RustFilterOptions,
-    rust_sig_filter: String,
-    slint_opts: stitch::core::SlintFilterOptions,
-    tx: mpsc::Sender<(u64, String)>,
-    seq: u64,
-}
-
-struct NotesContext {
-    exclude_dirs: Vec<String>,
-    exclude_files: Vec<String>,
-    existing_excluded_dirs: HashSet<String>,
-    existing_excluded_files: HashSet<String>,
-    include_exts: HashSet<String>,
-    exclude_exts: HashSet<String>,
-    remove_prefixes: Vec<String>,
-    remove_regex: Option<String>,
-    comment_removal: CommentRemoval,
-    signatures_filter: Option<String>,
-    slint_remove_line_comments: bool,
-    slint_remove_block_comments: bool,
-}
-
-struct SelectedPresence {
-    entries: std::collections::BTreeSet<String>,
-}
-
 fn profile_vec_index(idx: i32) -> Option<usize> {
     usize::try_from(idx).ok().and_then(|i| i.checked_sub(1))
 }
 
-impl SelectedPresence {
-    fn new(paths: &[String]) -> Self {
-        let entries = paths.iter().cloned().collect();
-        Self { entries }
-    }
-
-    fn collect_present_extensions(&self, filters: &HashSet<String>) -> Vec<String> {
-        let mut present = std::collections::BTreeSet::new();
-        for rel in &self.entries {
-            if let Some(ext) = std::path::Path::new(rel)
-                .extension()
-                .and_then(|e| e.to_str())
-            {
-                let dot = format!(".{}", ext.to_lowercase());
-                if filters.contains(&dot) {
-                    present.insert(dot);
-                }
-            }
-        }
-        present.into_iter().collect()
-    }
-
-    fn has_rust_files(&self) -> bool {
-        self.entries.iter().any(|rel| {
-            std::path::Path::new(rel)
-                .extension()
-                .and_then(|ext| ext.to_str())
-                .is_some_and(|ext| ext.eq_ignore_ascii_case("rs"))
-        })
-    }
-
-    fn has_slint_files(&self) -> bool {
-        self.entries.iter().any(|rel| {
-            std::path::Path::new(rel)
-                .extension()
-                .and_then(|ext| ext.to_str())
-                .is_some_and(|ext| ext.eq_ignore_ascii_case("slint"))
-        })
-    }
-
-    fn any_matches_filter(&self, filter: &str) -> bool {
-        self.entries
-            .iter()
-            .any(|rel| signatures_filter_matches(rel, filter))
-    }
-}
-
 /* =============================== UI Actions =============================== */
 
 pub fn apply_selection_from_text(app: &AppWindow, state: &SharedState, text: &str) {
@@ -168,222 +121,1052 @@ pub fn apply_selection_from_text(app: &AppWindow, state: &SharedState, text: &st
 
     {
         let mut s = state.borrow_mut();
-        walk_and_mark(&root, &selected_dir, &wanted, &mut s.explicit_states);
+        let rewrite = s.path_rewrite.clone();
+        walk_and_mark(
+            &root,
+            &selected_dir,
+            &wanted,
+            &rewrite,
+            &mut s.explicit_states,
+        );
     }
 
     refresh_flat_model(app, state);
-    on_generate_output(app, state);
+    // No autosave – let the user save; the subscriber just updates the button state
+    emit(state, WorkspaceEvent::SelectionChanged);
+}
 
-    // No autosave – let the user save; just update button state
+/// Check `select` and uncheck `deselect` (project-relative paths, each with its subtree)
+/// on top of the current selection. Returns the paths that don't exist in the project.
+pub fn apply_selection_paths(
+    app: &AppWindow,
+    state: &SharedState,
+    select: &[String],
+    deselect: &[String],
+) -> Vec<String> {
+    let Some(root) = state.borrow().selected_directory.clone() else {
+        return select.iter().chain(deselect).cloned().collect();
+    };
+    let missing: Vec<String> = select
+        .iter()
+        .chain(deselect)
+        .filter(|rel| !project_path_from_rel(&root, rel).exists())
+        .cloned()
+        .collect();
+    let to_abs = |rels: &[String]| -> Vec<PathBuf> {
+        rels.iter()
+            .filter(|rel| !missing.contains(rel))
+            .map(|rel| project_path_from_rel(&root, rel))
+            .collect()
+    };
+    let (adds, removes) = (to_abs(select), to_abs(deselect));
+    if adds.is_empty() && removes.is_empty() {
+        return missing;
+    }
+    {
+        let mut s = state.borrow_mut();
+        apply_selection_diff(&mut s.explicit_states, &adds, &removes);
+    }
+    refresh_flat_model(app, state);
     update_save_button_state(app, state);
+    missing
 }
 
-pub fn on_select_folder(app: &AppWindow, state: &SharedState) {
-    if let Some(dir) = rfd::FileDialog::new().set_directory(".").pick_folder() {
-        {
-            let mut s = state.borrow_mut();
-            s.selected_directory = Some(dir.clone());
-            s.explicit_states.clear();
-            s.last_mod_times.clear();
-            s.fs.dirty = true;
-            s.existing_excluded_dirs.clear();
-            s.existing_excluded_files.clear();
-        }
-
-        app.set_project_path(format_project_path_for_title(&dir).into());
-
-        let _ = ensure_workspace_dir(&dir);
-        let _ = ensure_profiles_dirs(&dir);
-
-        let ws_opt = load_workspace(&dir);
-        if let Some(ws) = ws_opt.as_ref() {
-            app.set_ext_filter(ws.ext_filter.clone().into());
-            app.set_exclude_dirs(ws.exclude_dirs.clone().into());
-            app.set_exclude_files(ws.exclude_files.clone().into());
-            app.set_remove_prefix(ws.remove_prefix.clone().into());
-            app.set_remove_regex(ws.remove_regex.clone().into());
-            app.set_hierarchy_only(ws.hierarchy_only);
-            app.set_dirs_only(ws.dirs_only);
-            app.set_show_rust_section(false);
-            app.set_rust_remove_inline_comments(ws.rust.rust_remove_inline_comments);
-            app.set_rust_remove_doc_comments(ws.rust.rust_remove_doc_comments);
-            app.set_rust_function_signatures_only(ws.rust.rust_function_signatures_only);
-            app.set_rust_signatures_only_filter(ws.rust.rust_signatures_only_filter.clone().into());
-            // Slint settings
-            app.set_slint_remove_line_comments(ws.slint.slint_remove_line_comments);
-            app.set_slint_remove_block_comments(ws.slint.slint_remove_block_comments);
-
-            state.borrow_mut().workspace_baseline = Some(ws.clone());
-        } else {
-            let seed = WorkspaceSettings {
-                version: 1,
-                ext_filter: app.get_ext_filter().to_string(),
-                exclude_dirs: app.get_exclude_dirs().to_string(),
-                exclude_files: app.get_exclude_files().to_string(),
-                remove_prefix: app.get_remove_prefix().to_string(),
-                remove_regex: app.get_remove_regex().to_string(),
-                hierarchy_only: app.get_hierarchy_only(),
-                dirs_only: app.get_dirs_only(),
-                rust: RustOptions {
-                    rust_remove_inline_comments: app.get_rust_remove_inline_comments(),
-                    rust_remove_doc_comments: app.get_rust_remove_doc_comments(),
-                    rust_function_signatures_only: app.get_rust_function_signatures_only(),
-                    rust_signatures_only_filter: app.get_rust_signatures_only_filter().to_string(),
-                },
-                slint: SlintOptions {
-                    slint_remove_line_comments: app.get_slint_remove_line_comments(),
-                    slint_remove_block_comments: app.get_slint_remove_block_comments(),
-                },
-            };
-            let _ = save_workspace(&dir, &seed);
-            state.borrow_mut().workspace_baseline = Some(seed);
-        }
+const DEFAULT_RANK_TOP_K: usize = 10;
 
-        {
-            let mut s = state.borrow_mut();
-            s.profiles = list_profiles(&dir);
-        }
-        refresh_profiles_ui(app, state);
+fn parse_rank_limits(top_k: &str, token_budget: &str) -> (usize, usize) {
+    (
+        top_k.trim().parse().unwrap_or(DEFAULT_RANK_TOP_K),
+        token_budget.trim().parse().unwrap_or(0),
+    )
+}
 
-        if let Some(local_settings) = load_local_settings(&dir)
-            && let Some(name) = local_settings.current_profile
-        {
-            if let Some((profile, _)) = load_profile(&dir, &name) {
-                apply_profile_to_ui(app, state, &profile);
-            } else {
-                let _ = stitch::core::clear_stale_current_profile(&dir);
-                refresh_profiles_ui(app, state);
+fn rank_candidates(state: &SharedState, query: &str) -> Option<(PathBuf, Vec<RankedFile>)> {
+    let (dir, candidates) = rank_inputs(state)?;
+    let ranked = rank_files_by_query(&dir, query, &candidates);
+    Some((dir, ranked))
+}
+
+/// Project folder and the files of the current scan, the input of a ranking.
+fn rank_inputs(state: &SharedState) -> Option<(PathBuf, Vec<PathBuf>)> {
+    let s = state.borrow();
+    let root = s.root_node.as_ref()?;
+    let dir = s.selected_directory.clone()?;
+    Some((dir, collect_file_paths(root)))
+}
+
+/// Show the ranking for `query` in the dialog, marking the files that would be selected.
+/// Reading the files happens on a worker thread; the dialog shows a placeholder meanwhile.
+pub fn preview_ranked_selection(
+    dlg: &RankFilesDialog,
+    state: &SharedState,
+    query: &str,
+    top_k: &str,
+    token_budget: &str,
+) {
+    let strings = dlg.global::<Strings>();
+    let Some((dir, candidates)) = rank_inputs(state) else {
+        dlg.set_results(NO_FOLDER_SELECTED.into());
+        return;
+    };
+    dlg.set_results(strings.get_ranking());
+
+    let no_match = strings.get_no_files_match_query();
+    let (query, top_k, token_budget) = (
+        query.to_string(),
+        top_k.to_string(),
+        token_budget.to_string(),
+    );
+    let dlg_weak = dlg.as_weak();
+    std::thread::spawn(move || {
+        let ranked = rank_files_by_query(&dir, &query, &candidates);
+        let text = if ranked.is_empty() {
+            no_match
+        } else {
+            render_ranked_preview(&dir, &ranked, &top_k, &token_budget).into()
+        };
+        let _ = slint::invoke_from_event_loop(move || {
+            if let Some(dlg) = dlg_weak.upgrade() {
+                dlg.set_results(text);
             }
-        }
+        });
+    });
+}
 
-        parse_filters_from_ui(app, state);
+fn render_ranked_preview(
+    dir: &Path,
+    ranked: &[RankedFile],
+    top_k: &str,
+    token_budget: &str,
+) -> String {
+    use std::fmt::Write;
 
-        // Only start fs watcher if it's not disabled
-        if !state.borrow().fs.watcher_disabled {
-            let _ = start_fs_watcher(app, state);
-        }
-        rebuild_tree_and_ui(app, state);
-        update_last_refresh(app);
+    let (k, budget) = parse_rank_limits(top_k, token_budget);
+    let picked: HashSet<PathBuf> = select_top_within_budget(ranked, k, budget)
+        .into_iter()
+        .collect();
 
-        state.borrow_mut().fs.dirty = false;
+    let mut out = String::new();
+    for rf in ranked {
+        let mark = if picked.contains(&rf.path) {
+            "✓"
+        } else {
+            " "
+        };
+        let rel = rf
+            .path
+            .strip_prefix(dir)
+            .map_or_else(|_| rf.path.to_string_lossy().into_owned(), path_to_unix);
+        let _ = writeln!(
+            out,
+            "{mark} {:>7.2}  {rel} (~{} tokens)",
+            rf.score, rf.est_tokens
+        );
     }
+    out
 }
 
-pub fn on_filter_changed(app: &AppWindow, state: &SharedState) {
-    parse_filters_from_ui(app, state);
-    rebuild_tree_and_ui(app, state);
-    on_generate_output(app, state);
-    update_last_refresh(app);
-}
+/// Replace the current selection with the top-ranked files for `query`.
+pub fn apply_ranked_selection(
+    app: &AppWindow,
+    state: &SharedState,
+    query: &str,
+    top_k: &str,
+    token_budget: &str,
+) {
+    let Some((_, ranked)) = rank_candidates(state, query) else {
+        return;
+    };
+    let (k, budget) = parse_rank_limits(top_k, token_budget);
+    let picked = select_top_within_budget(&ranked, k, budget);
+    if picked.is_empty() {
+        return;
+    }
 
-pub fn on_toggle_expand(app: &AppWindow, state: &SharedState, index: usize) {
-    if let Some(row) = get_row_by_index(app, index) {
-        let path = PathBuf::from(row.path.as_str());
-        if toggle_node_expanded(state, &path) {
-            refresh_flat_model(app, state);
-        }
+    {
+        let mut s = state.borrow_mut();
+        select_only(&mut s.explicit_states, picked);
     }
+
+    refresh_flat_model(app, state);
+    emit(state, WorkspaceEvent::SelectionChanged);
 }
 
-pub fn on_toggle_check(app: &AppWindow, state: &SharedState, index: usize) {
-    if let Some(row) = get_row_by_index(app, index) {
-        let path = PathBuf::from(row.path.as_str());
-        let is_dir = row.is_dir;
-        let effective = row.checked;
-        let new_state = !effective;
+fn search_candidates(
+    state: &SharedState,
+    pattern: &str,
+    use_regex: bool,
+    case_sensitive: bool,
+) -> Option<Result<(PathBuf, ContentSearchReport), String>> {
+    let s = state.borrow();
+    let root = s.root_node.as_ref()?;
+    let dir = s.selected_directory.clone()?;
+    let query = match ContentQuery::new(pattern, use_regex, case_sensitive) {
+        Ok(query) => query,
+        Err(e) => return Some(Err(e)),
+    };
+    let report = search_file_contents(&collect_file_paths(root), &query);
+    Some(Ok((dir, report)))
+}
 
-        {
-            let mut s = state.borrow_mut();
-            s.explicit_states.insert(path.clone(), new_state);
-        }
+/// Render the files containing `pattern`, with match counts, as a preview.
+pub fn preview_content_selection(
+    state: &SharedState,
+    pattern: &str,
+    use_regex: bool,
+    case_sensitive: bool,
+) -> String {
+    match search_candidates(state, pattern, use_regex, case_sensitive) {
+        None => NO_FOLDER_SELECTED.to_string(),
+        Some(Err(e)) => format!("{e}\n"),
+        Some(Ok((dir, report))) => report.render(&dir),
+    }
+}
 
-        if is_dir {
-            clear_descendant_explicit_states(state, &path);
-        }
+/// Replace the current selection with the files containing `pattern`.
+pub fn apply_content_selection(
+    app: &AppWindow,
+    state: &SharedState,
+    pattern: &str,
+    use_regex: bool,
+    case_sensitive: bool,
+) {
+    let Some(Ok((_, report))) = search_candidates(state, pattern, use_regex, case_sensitive) else {
+        return;
+    };
+    if report.files.is_empty() {
+        return;
+    }
 
-        refresh_flat_model(app, state);
-        on_generate_output(app, state);
+    {
+        let mut s = state.borrow_mut();
+        select_only(&mut s.explicit_states, report.paths());
+    }
 
-        // Reflect unsaved changes instead of autosaving
-        update_save_button_state(app, state);
+    refresh_flat_model(app, state);
+    emit(state, WorkspaceEvent::SelectionChanged);
+}
+
+pub fn on_select_folder(app: &AppWindow, state: &SharedState) {
+    if let Some(dir) = rfd::FileDialog::new().set_directory(".").pick_folder() {
+        open_project(app, state, &dir);
     }
 }
 
-pub fn on_toggle_fs_watcher(app: &AppWindow, state: &SharedState) {
-    let disable_fs_watcher = app.get_disable_fs_watcher();
+/// Open `dir` as the current project: load its workspace, profiles and local settings.
+pub fn open_project(app: &AppWindow, state: &SharedState, dir: &Path) {
+    {
+        let mut s = state.borrow_mut();
+        s.selected_directory = Some(dir.to_path_buf());
+        s.explicit_states.clear();
+        s.dir_samples.clear();
+        s.dir_rules.clear();
+        s.pinned_files.clear();
+        s.hierarchy_cache.clear();
+        s.output_layout = OutputLayout::default();
+        s.file_markers = FileMarkers::default();
+        s.languages.clear();
+        s.transforms = TransformPipeline::default();
+        s.svg_inline_limit = None;
+        s.normalize_text = false;
+        s.post_process = None;
+        s.c_like = CLikeOptions::default();
+        s.go = GoOptions::default();
+        s.web = WebOptions::default();
+        s.sql = SqlOptions::default();
+        s.tree_style = TreeStyle::default();
+        s.hierarchy_cache.set_style(TreeStyle::default());
+        s.last_mod_times.clear();
+        s.fs.dirty = true;
+        s.scan_stats = ScanStats::default();
+        s.exclude_suggestions_dismissed = false;
+    }
+    refresh_open_history_dialog(state);
+    refresh_snapshots(app, state, None);
+    refresh_tasks(app, state);
+
+    app.set_project_path(display_path(dir).into());
+
+    let _ = ensure_workspace_dir(dir);
+    let _ = ensure_profiles_dirs(dir);
+
+    let mut ws_opt = load_workspace(dir);
+    if ws_opt.is_none() && offer_backup_restore(app, dir, &workspace_file(dir)) {
+        ws_opt = load_workspace(dir);
+    }
+    if let Some(ws) = ws_opt.as_ref() {
+        apply_workspace_settings_to_ui(app, state, ws);
+        app.set_show_rust_section(false);
+
+        state.borrow_mut().workspace_baseline = Some(ws.clone());
+    } else {
+        let seed = workspace_settings_from_ui(app, state);
+        let _ = save_workspace(dir, &seed);
+        state.borrow_mut().workspace_baseline = Some(seed);
+    }
 
     {
         let mut s = state.borrow_mut();
-        s.fs.watcher_disabled = disable_fs_watcher;
+        s.profiles = list_profiles(dir);
     }
+    refresh_profiles_ui(app, state);
 
-    if disable_fs_watcher {
-        // Stop the fs watcher
+    let local_settings = load_local_settings(dir);
+    {
+        let mut s = state.borrow_mut();
+        s.lazy_scan = local_settings.as_ref().is_some_and(|ls| ls.lazy_scan);
+        s.fs.watch_selection_only = local_settings
+            .as_ref()
+            .is_some_and(|ls| ls.watch_selection_only);
+        s.persist_content_cache = local_settings
+            .as_ref()
+            .is_some_and(|ls| ls.persist_content_cache);
+        // A new cache rather than a cleared one: a worker may still use the old project's.
+        s.content_cache = std::sync::Arc::new(std::sync::Mutex::new(if s.persist_content_cache {
+            load_content_cache(dir)
+        } else {
+            ContentCache::new()
+        }));
+        s.preview_char_limit = local_settings
+            .clone()
+            .unwrap_or_default()
+            .preview_char_limit();
+    }
+    apply_local_preferences_to_ui(app, &local_settings.clone().unwrap_or_default());
+    apply_accessibility_preferences(app, state, local_settings.as_ref());
+    if app.get_check_for_updates() {
+        start_update_check(app, state);
+    }
+
+    let branch = current_git_branch(dir);
+    let bound = branch_profile(state, local_settings.as_ref(), branch.as_deref());
+    if let Some(name) = &bound {
+        let mut ls = local_settings.clone().unwrap_or_default();
+        if ls.current_profile.as_ref() != Some(name) {
+            ls.current_profile = Some(name.clone());
+            let _ = save_local_settings(dir, &ls);
+            refresh_profiles_ui(app, state);
+        }
+    }
+    {
+        let mut s = state.borrow_mut();
+        s.fs.git_head = git_head_file(dir);
+        s.fs.git_branch = branch;
+    }
+
+    if let Some(name) = bound.or_else(|| local_settings.and_then(|ls| ls.current_profile)) {
+        let unreadable = [ProfileScope::Local, ProfileScope::Shared]
+            .into_iter()
+            .map(|scope| profile_path(dir, scope, &name))
+            .find(|p| p.is_file());
+        let mut loaded = load_profile(dir, &name);
+        if loaded.is_none()
+            && let Some(path) = unreadable
+            && offer_backup_restore(app, dir, &path)
         {
-            let mut s = state.borrow_mut();
-            s.watcher = None;
-            s.fs_event_rx = None;
-            s.fs_pump_timer.stop();
+            loaded = load_profile(dir, &name);
+        }
+        if let Some((profile, _)) = loaded {
+            apply_profile_to_ui(app, state, &profile);
+        } else {
+            let _ = stitch::core::clear_stale_current_profile(dir);
+            refresh_profiles_ui(app, state);
         }
-    } else {
-        // Start the fs watcher
-        let _ = start_fs_watcher(app, state);
     }
-}
 
-pub fn on_generate_output(app: &AppWindow, state: &SharedState) {
-    if handle_generation_in_progress(app, state) {
-        return;
+    parse_filters_from_ui(app, state);
+
+    // Only start fs watcher if it's not disabled
+    if !state.borrow().fs.watcher_disabled {
+        let _ = start_fs_watcher(app, state);
     }
+    rebuild_tree_and_ui(app, state);
+    update_last_refresh(app);
 
+    state.borrow_mut().fs.dirty = false;
+}
+
+pub fn on_filter_changed(app: &AppWindow, state: &SharedState) {
     parse_filters_from_ui(app, state);
+    emit(state, WorkspaceEvent::FiltersChanged);
+}
 
-    let want_dirs_only = app.get_dirs_only();
-    let hierarchy_only = app.get_hierarchy_only();
-    let disable_notes = app.get_disable_notes_section();
+/// Deliver `event` to the subscribers of the state's [`stitch::core::EventBus`].
+fn emit(state: &SharedState, event: WorkspaceEvent) {
+    let events = Rc::clone(&state.borrow().events);
+    events.emit(event);
+}
 
-    let selection = match collect_selection_snapshot(state, want_dirs_only) {
-        Ok(snapshot) => snapshot,
-        Err(SelectionError::NoFolder) => {
-            set_output(app, state, NO_FOLDER_SELECTED);
-            update_last_refresh(app);
+/// Update the window from workspace events: regenerate on selection and filter changes
+/// and show finished generations.
+pub fn subscribe_ui(app: &AppWindow, state: &SharedState) {
+    let app_weak = app.as_weak();
+    // Weak, since the bus lives in the state.
+    let state_weak = Rc::downgrade(state);
+    state.borrow().events.subscribe(move |event| {
+        let (Some(app), Some(state)) = (app_weak.upgrade(), state_weak.upgrade()) else {
             return;
+        };
+        match event {
+            WorkspaceEvent::SelectionChanged => {
+                refresh_watch_scope(&state);
+                on_generate_output(&app, &state);
+                // Reflect unsaved changes instead of autosaving
+                update_save_button_state(&app, &state);
+            }
+            WorkspaceEvent::FiltersChanged => {
+                rebuild_tree_and_ui(&app, &state);
+                on_generate_output(&app, &state);
+                update_last_refresh(&app);
+            }
+            WorkspaceEvent::GenerationFinished { output, stats } => {
+                // Profile loads and rescans change the selection without a SelectionChanged.
+                refresh_watch_scope(&state);
+                set_output(&app, &state, output, OutputKind::Generated);
+                app.set_selection_breakdown(stats.render().into());
+                update_last_refresh(&app);
+                if !state.borrow().generation.in_progress {
+                    cache_output(&app, &state);
+                }
+            }
+            WorkspaceEvent::GenerationFailed { message } => {
+                set_output(&app, &state, message, OutputKind::Message);
+                app.set_selection_breakdown("".into());
+                update_last_refresh(&app);
+            }
         }
-        Err(SelectionError::NoItems) => {
-            set_output(app, state, NO_ITEMS_SELECTED);
-            update_last_refresh(app);
-            return;
+    });
+}
+
+/// Add the suggestion at `index` to its filter field and rescan.
+pub fn on_apply_exclude_suggestion(app: &AppWindow, state: &SharedState, index: usize) {
+    let Some(suggestion) = state.borrow().exclude_suggestions.get(index).cloned() else {
+        return;
+    };
+    let token = suggestion.filter_token();
+    match suggestion.target {
+        ExcludeTarget::Directory => {
+            app.set_exclude_dirs(append_filter_token(&app.get_exclude_dirs(), &token).into());
         }
+        ExcludeTarget::Extension => {
+            app.set_ext_filter(append_filter_token(&app.get_ext_filter(), &token).into());
+        }
+    }
+    on_filter_changed(app, state);
+    update_save_button_state(app, state);
+}
+
+pub fn on_dismiss_exclude_suggestions(app: &AppWindow, state: &SharedState) {
+    state.borrow_mut().exclude_suggestions_dismissed = true;
+    refresh_exclude_suggestions(app, state);
+}
+
+/// Reload the per-user filter presets into the dropdown and select `selected` (by name).
+pub fn refresh_filter_presets(app: &AppWindow, state: &SharedState, selected: Option<&str>) {
+    let presets = user_config_dir()
+        .map(|dir| load_filter_presets(&dir))
+        .unwrap_or_default();
+    let mut names: Vec<slint::SharedString> = vec![app.global::<Strings>().get_no_filter_preset()];
+    names.extend(
+        presets
+            .iter()
+            .map(|p| slint::SharedString::from(p.name.as_str())),
+    );
+    let index = selected
+        .and_then(|name| {
+            presets
+                .iter()
+                .position(|p| p.name.eq_ignore_ascii_case(name))
+        })
+        .and_then(|i| i32::try_from(i + 1).ok())
+        .unwrap_or(0);
+    state.borrow_mut().filter_presets = presets;
+    app.set_filter_presets(ModelRc::new(VecModel::from(names)));
+    app.set_filter_preset_index(index);
+}
+
+/// Copy the preset's filters into the filter fields and rescan.
+pub fn on_apply_filter_preset(app: &AppWindow, state: &SharedState, index: i32) {
+    let Some(preset) = usize::try_from(index - 1)
+        .ok()
+        .and_then(|i| state.borrow().filter_presets.get(i).cloned())
+    else {
+        return;
     };
+    let mut settings = workspace_settings_from_ui(app, state);
+    preset.apply_to(&mut settings);
+    app.set_ext_filter(settings.ext_filter.into());
+    app.set_exclude_dirs(settings.exclude_dirs.into());
+    app.set_exclude_files(settings.exclude_files.into());
+    app.set_remove_prefix(settings.remove_prefix.into());
+    on_filter_changed(app, state);
+    update_save_button_state(app, state);
+}
 
-    if !want_dirs_only {
-        update_last_mod_times(state, &selection.files);
+/// Import filters and output settings from a repomix or code2prompt config into the UI.
+///
+/// Like applying a preset, nothing is saved until the user saves the workspace or profile.
+pub fn on_import_settings(app: &AppWindow, state: &SharedState) {
+    let Some(root) = state.borrow().selected_directory.clone() else {
+        return;
+    };
+    let strings = app.global::<Strings>();
+    let mut dialog = rfd::FileDialog::new()
+        .set_title(strings.get_import_settings_title().as_str())
+        .set_directory(&root);
+    if let Some(name) = find_tool_config(&root)
+        .as_deref()
+        .and_then(Path::file_name)
+        .and_then(|n| n.to_str())
+    {
+        dialog = dialog.set_file_name(name);
     }
+    let Some(path) = dialog.pick_file() else {
+        return;
+    };
 
-    let header = build_hierarchy_header(state, &selection, disable_notes);
+    let mut settings = workspace_settings_from_ui(app, state);
+    let skipped = match import_tool_config(&path, &mut settings) {
+        Ok(skipped) => skipped,
+        Err(e) => {
+            rfd::MessageDialog::new()
+                .set_level(rfd::MessageLevel::Warning)
+                .set_title(strings.get_import_failed().as_str())
+                .set_description(e)
+                .show();
+            return;
+        }
+    };
+    apply_workspace_settings_to_ui(app, state, &settings);
+    on_filter_changed(app, state);
+    update_save_button_state(app, state);
 
-    if hierarchy_only || want_dirs_only {
-        set_output(app, state, &header);
-        update_last_refresh(app);
+    if !skipped.is_empty() {
+        rfd::MessageDialog::new()
+            .set_level(rfd::MessageLevel::Info)
+            .set_title(strings.get_settings_imported().as_str())
+            .set_description(
+                strings.invoke_settings_not_carried_over(
+                    path.file_name()
+                        .unwrap_or_default()
+                        .to_string_lossy()
+                        .as_ref()
+                        .into(),
+                    skipped.join("\n- ").into(),
+                ),
+            )
+            .show();
+    }
+}
+
+/// Save the current filter fields as a per-user preset (replacing one with the same name).
+pub fn on_save_filter_preset(app: &AppWindow, state: &SharedState, name: &str) {
+    let Some(dir) = user_config_dir() else {
         return;
+    };
+    normalize_filter_fields(app, state);
+    let preset = FilterPreset::from_settings(name, &workspace_settings_from_ui(app, state));
+    if save_filter_preset(&dir, &preset).is_ok() {
+        app.set_new_filter_preset_name(slint::SharedString::default());
+        refresh_filter_presets(app, state, Some(&preset.name));
     }
+}
 
-    prepare_async_generation(app, state, selection, header);
+pub fn on_delete_filter_preset(app: &AppWindow, state: &SharedState, index: i32) {
+    let name = usize::try_from(index - 1)
+        .ok()
+        .and_then(|i| state.borrow().filter_presets.get(i).map(|p| p.name.clone()));
+    if let (Some(dir), Some(name)) = (user_config_dir(), name) {
+        let _ = delete_filter_preset(&dir, &name);
+        refresh_filter_presets(app, state, None);
+    }
 }
 
-fn handle_generation_in_progress(app: &AppWindow, state: &SharedState) -> bool {
-    let mut s = state.borrow_mut();
+pub fn on_toggle_expand(app: &AppWindow, state: &SharedState, index: usize) {
+    if let Some(row) = get_row_by_index(app, index) {
+        let path = PathBuf::from(row.path.as_str());
+        load_unloaded_dir(state, &path);
+        if toggle_node_expanded(state, &path) && !patch_expanded_rows(app, state, index) {
+            refresh_flat_model(app, state);
+        }
+    }
+}
+
+pub fn on_toggle_check(app: &AppWindow, state: &SharedState, index: usize) {
+    if let Some(row) = get_row_by_index(app, index) {
+        let path = PathBuf::from(row.path.as_str());
+        let is_dir = row.is_dir;
+        let effective = row.checked;
+        let new_state = !effective;
+
+        {
+            let mut s = state.borrow_mut();
+            set_selection_for_subtree(&mut s.explicit_states, &path, new_state);
+        }
+
+        // The row and the rows below it (which now inherit from it) are all that change.
+        let patched = with_tree_rows(app, |tree| {
+            let end = if is_dir {
+                tree.subtree_end(index)
+            } else {
+                index + 1
+            };
+            tree.modify(index..end, |r| r.checked = new_state);
+        });
+        if patched.is_none() {
+            refresh_flat_model(app, state);
+        }
+        emit(state, WorkspaceEvent::SelectionChanged);
+    }
+}
+
+/// Row index of the directory containing the row at `index` (the nearest row above it
+/// one level up), or -1 for the root and out-of-range indices.
+pub fn parent_row_index(app: &AppWindow, index: usize) -> i32 {
+    let model = app.get_tree_model();
+    let Some(level) = model.row_data(index).map(|row| row.level) else {
+        return -1;
+    };
+    (0..index)
+        .rev()
+        .find(|&i| model.row_data(i).is_some_and(|row| row.level < level))
+        .and_then(|i| i32::try_from(i).ok())
+        .unwrap_or(-1)
+}
+
+/// Toggle the pin of the file at `index`; on a directory, unpin every file below it.
+pub fn on_toggle_pin(app: &AppWindow, state: &SharedState, index: usize) {
+    let Some(row) = get_row_by_index(app, index) else {
+        return;
+    };
+    let Some(dir) = state.borrow().selected_directory.clone() else {
+        return;
+    };
+    let Ok(rel) = Path::new(row.path.as_str()).strip_prefix(&dir) else {
+        return;
+    };
+    let rel = path_to_unix(rel);
+
+    {
+        let mut s = state.borrow_mut();
+        if row.is_dir {
+            s.pinned_files.retain(|pin| !is_rel_path_within(pin, &rel));
+        } else if let Some(pos) = s.pinned_files.iter().position(|pin| *pin == rel) {
+            s.pinned_files.remove(pos);
+        } else {
+            s.pinned_files.push(rel);
+            s.pinned_files.sort();
+        }
+    }
+
+    refresh_flat_model(app, state);
+    emit(state, WorkspaceEvent::SelectionChanged);
+}
+
+/// Set the scan override of the directory at `index`: 1 = always exclude it,
+/// 2 = ignore the extension filters below it, 0 = clear overrides at and below it.
+pub fn on_set_dir_rule(app: &AppWindow, state: &SharedState, index: usize, kind: i32) {
+    let Some(row) = get_row_by_index(app, index) else {
+        return;
+    };
+    if !row.is_dir {
+        return;
+    }
+    let Some(dir) = state.borrow().selected_directory.clone() else {
+        return;
+    };
+    let Ok(rel) = Path::new(row.path.as_str()).strip_prefix(&dir) else {
+        return;
+    };
+    let rel = path_to_unix(rel);
+    let kind = match kind {
+        // Excluding the project root would leave nothing to clear the rule from.
+        1 if !rel.is_empty() => Some(DirRuleKind::Exclude),
+        2 => Some(DirRuleKind::IgnoreExtensionFilters),
+        0 => None,
+        _ => return,
+    };
+
+    {
+        let mut s = state.borrow_mut();
+        if let Some(kind) = kind {
+            s.dir_rules.retain(|rule| rule.path != rel);
+            s.dir_rules.push(DirectoryRule { path: rel, kind });
+            s.dir_rules.sort_by(|a, b| a.path.cmp(&b.path));
+        } else {
+            s.dir_rules
+                .retain(|rule| !is_rel_path_within(&rule.path, &rel));
+        }
+    }
+
+    rebuild_tree_and_ui(app, state);
+    refresh_flat_model(app, state);
+    emit(state, WorkspaceEvent::SelectionChanged);
+}
+
+/// Set (or clear, when `count` is zero) the sampling rule of the directory at `index`.
+pub fn on_set_dir_sample(
+    app: &AppWindow,
+    state: &SharedState,
+    index: usize,
+    count: usize,
+    most_recent: bool,
+) {
+    let Some(row) = get_row_by_index(app, index) else {
+        return;
+    };
+    if !row.is_dir {
+        return;
+    }
+    let Some(dir) = state.borrow().selected_directory.clone() else {
+        return;
+    };
+    let Ok(rel) = Path::new(row.path.as_str()).strip_prefix(&dir) else {
+        return;
+    };
+    let rel = path_to_unix(rel);
+
+    {
+        let mut s = state.borrow_mut();
+        s.dir_samples.retain(|rule| rule.path != rel);
+        if count > 0 {
+            s.dir_samples.push(DirectorySample {
+                path: rel,
+                count,
+                order: if most_recent {
+                    SampleOrder::MostRecent
+                } else {
+                    SampleOrder::First
+                },
+            });
+            s.dir_samples.sort_by(|a, b| a.path.cmp(&b.path));
+        }
+    }
+
+    refresh_flat_model(app, state);
+    emit(state, WorkspaceEvent::SelectionChanged);
+}
+
+pub fn on_toggle_fs_watcher(app: &AppWindow, state: &SharedState) {
+    let disable_fs_watcher = app.get_disable_fs_watcher();
+
+    {
+        let mut s = state.borrow_mut();
+        s.fs.watcher_disabled = disable_fs_watcher;
+    }
+
+    if disable_fs_watcher {
+        // Stop the fs watcher
+        {
+            let mut s = state.borrow_mut();
+            s.watcher = None;
+            s.fs_event_rx = None;
+            s.fs_pump_timer.stop();
+        }
+    } else {
+        // Start the fs watcher
+        let _ = start_fs_watcher(app, state);
+    }
+}
+
+/// The OS accessibility settings, read once per process.
+fn os_preferences() -> PlatformPreferences {
+    static OS_PREFERENCES: OnceLock<PlatformPreferences> = OnceLock::new();
+    *OS_PREFERENCES.get_or_init(detect_platform_preferences)
+}
+
+/// Follow the OS high-contrast and reduced-motion settings, unless the project's local
+/// settings override them.
+pub fn apply_accessibility_preferences(
+    app: &AppWindow,
+    state: &SharedState,
+    ls: Option<&LocalSettings>,
+) {
+    let prefs = os_preferences().with_overrides(
+        ls.and_then(|ls| ls.high_contrast),
+        ls.and_then(|ls| ls.reduced_motion),
+    );
+    app.set_high_contrast(prefs.high_contrast);
+    app.set_reduce_motion(prefs.reduced_motion);
+    state.borrow_mut().accessibility = prefs;
+}
+
+/// Apply the theme and output font saved in the user config dir; called when the window
+/// opens.
+pub fn apply_user_preferences_to_ui(app: &AppWindow) {
+    let prefs = user_config_dir()
+        .map(|dir| load_user_preferences(&dir))
+        .unwrap_or_default();
+    app.set_theme_index(prefs.theme.index());
+    app.set_output_font_family(prefs.output_font_family().into());
+    app.set_output_font_size(i32::try_from(prefs.output_font_size()).unwrap_or(i32::MAX));
+}
+
+/// Persist the theme and output font from the UI to the user config dir, with or without
+/// a project.
+fn save_user_preferences_from_ui(app: &AppWindow) {
+    let Some(dir) = user_config_dir() else {
+        return;
+    };
+    let mut prefs = load_user_preferences(&dir);
+    prefs.theme = Theme::from_index(app.get_theme_index());
+    let family = app.get_output_font_family().trim().to_string();
+    prefs.output_font_family = (!family.is_empty()).then_some(family);
+    prefs.output_font_size = u32::try_from(app.get_output_font_size()).ok();
+    let _ = save_user_preferences(&dir, &prefs);
+}
+
+fn apply_local_preferences_to_ui(app: &AppWindow, ls: &LocalSettings) {
+    app.set_check_for_updates(ls.check_for_updates);
+    app.set_approximate_tokens(ls.approximate_tokens);
+}
+
+/// Persist the update check and token mode from the UI to the project's local settings.
+fn save_local_preferences_from_ui(app: &AppWindow, state: &SharedState) {
+    let Some(dir) = state.borrow().selected_directory.clone() else {
+        return;
+    };
+    let mut ls = load_local_settings(&dir).unwrap_or_default();
+    ls.check_for_updates = app.get_check_for_updates();
+    ls.approximate_tokens = app.get_approximate_tokens();
+    let _ = save_local_settings(&dir, &ls);
+}
+
+pub fn on_theme_changed(app: &AppWindow) {
+    save_user_preferences_from_ui(app);
+}
+
+pub fn on_output_font_changed(app: &AppWindow) {
+    save_user_preferences_from_ui(app);
+}
+
+pub fn on_token_mode_changed(app: &AppWindow, state: &SharedState) {
+    save_local_preferences_from_ui(app, state);
+    let (text, kind) = {
+        let s = state.borrow();
+        (s.full_output_text.clone(), s.output_kind)
+    };
+    set_output(app, state, &text, kind);
+}
+
+pub fn on_toggle_update_check(app: &AppWindow, state: &SharedState) {
+    save_local_preferences_from_ui(app, state);
+    if app.get_check_for_updates() {
+        start_update_check(app, state);
+    } else {
+        app.set_update_notice("".into());
+    }
+}
+
+/// Ask GitHub for the latest release in the background; a newer one shows a notice in the top bar.
+#[cfg(feature = "update-check")]
+fn start_update_check(app: &AppWindow, state: &SharedState) {
+    {
+        let mut s = state.borrow_mut();
+        if s.update_check_started {
+            return;
+        }
+        s.update_check_started = true;
+    }
+
+    let app_weak = app.as_weak();
+    std::thread::spawn(move || {
+        // Failures (offline, rate-limited, ...) are silent: the notice is best-effort.
+        let Ok(Some(release)) = stitch::core::check_for_update(env!("CARGO_PKG_VERSION")) else {
+            return;
+        };
+        let _ = slint::invoke_from_event_loop(move || {
+            if let Some(app) = app_weak.upgrade()
+                && app.get_check_for_updates()
+            {
+                let notice = app
+                    .global::<Strings>()
+                    .invoke_update_available(release.version.into());
+                app.set_update_notice(notice);
+                app.set_update_url(release.url.into());
+            }
+        });
+    });
+}
+
+#[cfg(not(feature = "update-check"))]
+fn start_update_check(_app: &AppWindow, _state: &SharedState) {}
+
+pub fn on_open_release_page(app: &AppWindow) {
+    let url = app.get_update_url().to_string();
+    if url.is_empty() {
+        return;
+    }
+    #[cfg(target_os = "windows")]
+    let mut cmd = {
+        let mut c = std::process::Command::new("cmd");
+        c.args(["/C", "start", ""]);
+        c
+    };
+    #[cfg(target_os = "macos")]
+    let mut cmd = std::process::Command::new("open");
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    let mut cmd = std::process::Command::new("xdg-open");
+    let _ = cmd.arg(url).spawn();
+}
+
+pub fn on_generate_output(app: &AppWindow, state: &SharedState) {
+    state.borrow_mut().output_cache.clear();
+    generate_output(app, state);
+}
+
+/// Show the last output of the mode just toggled to if nothing else changed since it was
+/// generated; otherwise generate it.
+pub fn on_output_mode_changed(app: &AppWindow, state: &SharedState) {
+    let mode = OutputMode::from_toggles(app.get_hierarchy_only(), app.get_dirs_only());
+    let cached = {
+        let mut s = state.borrow_mut();
+        if s.generation.in_progress {
+            None
+        } else {
+            s.output_cache.remove(&mode)
+        }
+    };
+    let Some(cached) = cached else {
+        generate_output(app, state);
+        return;
+    };
+    set_output(app, state, &cached.text, OutputKind::Generated);
+    app.set_selection_breakdown(cached.breakdown.as_str().into());
+    update_last_refresh(app);
+    let mut s = state.borrow_mut();
+    s.split_source.clone_from(&cached.split_source);
+    s.selection_stats.clone_from(&cached.selection_stats);
+    s.output_cache.insert(mode, cached);
+}
+
+/// Keep the output just shown for its mode (see [`on_output_mode_changed`]).
+fn cache_output(app: &AppWindow, state: &SharedState) {
+    let mode = OutputMode::from_toggles(app.get_hierarchy_only(), app.get_dirs_only());
+    let mut s = state.borrow_mut();
+    if s.output_kind != OutputKind::Generated {
+        return;
+    }
+    let cached = CachedOutput {
+        text: s.full_output_text.clone(),
+        breakdown: app.get_selection_breakdown().to_string(),
+        split_source: s.split_source.clone(),
+        selection_stats: s.selection_stats.clone(),
+    };
+    s.output_cache.insert(mode, cached);
+}
+
+fn generate_output(app: &AppWindow, state: &SharedState) {
+    if revalidate_project_root(app, state) {
+        return;
+    }
+    if handle_generation_in_progress(app, state) {
+        return;
+    }
+
+    parse_filters_from_ui(app, state);
+    {
+        let mut s = state.borrow_mut();
+        s.split_source = None;
+        s.selection_stats = SelectionStats::default();
+    }
+
+    let want_dirs_only = app.get_dirs_only();
+    let hierarchy_only = app.get_hierarchy_only();
+    let disable_notes = app.get_disable_notes_section();
+
+    let selection = match collect_selection_snapshot(state, want_dirs_only) {
+        Ok(snapshot) => snapshot,
+        Err(SelectionError::NoFolder) => {
+            set_output(app, state, NO_FOLDER_SELECTED, OutputKind::Message);
+            update_last_refresh(app);
+            return;
+        }
+        Err(SelectionError::NoItems) => {
+            set_output(app, state, NO_ITEMS_SELECTED, OutputKind::Message);
+            update_last_refresh(app);
+            return;
+        }
+    };
+
+    if !want_dirs_only {
+        update_last_mod_times(state, &selection.files);
+    }
+
+    let summarize_unselected = app.get_summarize_unselected() && !hierarchy_only && !want_dirs_only;
+    let dependencies = app.get_dependencies_section() && !want_dirs_only;
+    let settings = workspace_settings_from_ui(app, state);
+    let mut parts = build_header_parts(
+        state,
+        &selection,
+        &settings,
+        disable_notes,
+        summarize_unselected,
+        dependencies,
+    );
+    if let Some(format) = state.borrow().output_layout.provenance_format() {
+        let profile = app.get_profile_name();
+        let provenance = Provenance::new(
+            &selection.selected_dir,
+            Some(profile.as_str()).filter(|p| !p.is_empty()),
+            app.get_deterministic(),
+        )
+        .with_sources(
+            &selection.selected_dir,
+            format,
+            &load_local_settings(&selection.selected_dir)
+                .map(|ls| ls.provenance_env)
+                .unwrap_or_default(),
+        );
+        parts.provenance = Some(provenance.render(format));
+    }
+
+    if hierarchy_only || want_dirs_only {
+        let (layout, post_process) = {
+            let s = state.borrow();
+            (s.output_layout.clone(), s.post_process.clone())
+        };
+        let mut text = layout.render(&parts);
+        if let Some(post) = post_process
+            && load_local_settings(&selection.selected_dir).is_some_and(|ls| ls.external_commands)
+        {
+            text = post
+                .run(&text, &selection.selected_dir)
+                .unwrap_or_else(|e| {
+                    append_post_process_note(parts.notes.get_or_insert_with(String::new), &e);
+                    layout.render(&parts)
+                });
+        }
+        set_output(app, state, &text, OutputKind::Generated);
+        cache_output(app, state);
+        update_last_refresh(app);
+        return;
+    }
+
+    prepare_async_generation(app, state, selection, parts, &settings);
+}
+
+fn handle_generation_in_progress(app: &AppWindow, state: &SharedState) -> bool {
+    let mut s = state.borrow_mut();
     if s.generation.in_progress {
+        // The running generation is out of date; stop it and start over once it stops.
         s.generation.queue_another = true;
+        if let Some(handle) = s.generation.handle.as_ref() {
+            handle.cancel();
+        }
+        let strings = app.global::<Strings>();
         app.set_output_text(
             format!(
-                "{} Generating{} (queued)",
+                "{} {}{} {}",
                 stitch::core::GLYPH_HOURGLASS,
-                stitch::core::GLYPH_ELLIPSIS
+                strings.get_generating(),
+                stitch::core::GLYPH_ELLIPSIS,
+                strings.get_queued()
             )
             .into(),
         );
         app.set_output_stats("".into());
+        app.set_output_page_label("".into());
         return true;
     }
     false
@@ -393,6 +1176,7 @@ fn collect_selection_snapshot(
     state: &SharedState,
     want_dirs_only: bool,
 ) -> Result<SelectionSnapshot, SelectionError> {
+    load_selected_tree_dirs(state);
     let s = state.borrow();
     let Some(root) = s.root_node.as_ref() else {
         return Err(SelectionError::NoFolder);
@@ -405,6 +1189,14 @@ fn collect_selection_snapshot(
     let mut dirs = Vec::new();
     collect_selected_paths(root, &s.explicit_states, None, &mut files, &mut dirs);
 
+    let mut sample_omissions = Vec::new();
+    if !want_dirs_only && !s.dir_samples.is_empty() {
+        (files, sample_omissions) = apply_directory_samples(selected_dir, &files, &s.dir_samples);
+    }
+    if !want_dirs_only {
+        files = apply_pinned_files(selected_dir, &files, &s.pinned_files);
+    }
+
     if (!want_dirs_only && files.is_empty()) || (want_dirs_only && dirs.is_empty()) {
         return Err(SelectionError::NoItems);
     }
@@ -439,489 +1231,997 @@ fn collect_selection_snapshot(
         relative_paths: rels,
         selected_dir: selected_dir.clone(),
         root_name,
+        sample_omissions,
     })
 }
 
-fn update_last_mod_times(state: &SharedState, files: &[PathBuf]) {
-    let mut s = state.borrow_mut();
-    for fp in files {
-        let mtime = fs::metadata(fp).ok().and_then(|m| m.modified().ok());
-        s.last_mod_times.insert(fp.clone(), mtime);
-    }
+fn update_last_mod_times(state: &SharedState, files: &[PathBuf]) {
+    let mut s = state.borrow_mut();
+    for fp in files {
+        let mtime = fs::metadata(fp).ok().and_then(|m| m.modified().ok());
+        s.last_mod_times.insert(fp.clone(), mtime);
+    }
+}
+
+fn build_header_parts(
+    state: &SharedState,
+    selection: &SelectionSnapshot,
+    settings: &WorkspaceSettings,
+    disable_notes: bool,
+    summarize_unselected: bool,
+    dependencies: bool,
+) -> OutputParts {
+    let rewrite = state.borrow().path_rewrite.clone();
+    let shown_paths: Vec<String> = selection
+        .relative_paths
+        .iter()
+        .map(|p| rewrite.apply(p))
+        .collect();
+    let hierarchy = state
+        .borrow_mut()
+        .hierarchy_cache
+        .render(&shown_paths, Some(&selection.root_name));
+    let mut parts = OutputParts {
+        hierarchy: Some(hierarchy),
+        ..OutputParts::default()
+    };
+
+    if summarize_unselected {
+        let s = state.borrow();
+        if let Some(root) = s.root_node.as_ref() {
+            let unselected = summarize_unselected_top_level_dirs(root, &s.explicit_states);
+            if !unselected.is_empty() {
+                parts.unselected_directories = Some(render_unselected_dirs_summary(&unselected));
+            }
+        }
+    }
+
+    if dependencies {
+        parts.dependencies = render_dependencies_section(&selection.selected_dir);
+    }
+
+    if !disable_notes {
+        let notes = render_notes(
+            settings,
+            &state.borrow().scan_stats,
+            &selection.selected_dir,
+            &selection.relative_paths,
+            &selection.sample_omissions,
+        );
+        if !notes.trim().is_empty() {
+            parts.notes = Some(notes + "\n");
+        }
+    }
+
+    parts
+}
+
+fn prepare_async_generation(
+    app: &AppWindow,
+    state: &SharedState,
+    selection: SelectionSnapshot,
+    parts: OutputParts,
+    settings: &WorkspaceSettings,
+) {
+    app.set_output_text(
+        format!(
+            "{} {}{}",
+            stitch::core::GLYPH_HOURGLASS,
+            app.global::<Strings>().get_generating(),
+            stitch::core::GLYPH_ELLIPSIS
+        )
+        .into(),
+    );
+    app.set_output_stats("".into());
+    app.set_output_page_label("".into());
+    app.set_selection_breakdown("".into());
+
+    ensure_generation_pump(app, state);
+    let mut request =
+        GenerationRequest::from_settings(&selection.selected_dir, selection.files, parts, settings);
+    let mut s = state.borrow_mut();
+    request.transform_opts.expand_cache = std::sync::Arc::clone(&s.expand_cache);
+    request.content_cache = std::sync::Arc::clone(&s.content_cache);
+    request.persist_content_cache = s.persist_content_cache;
+    s.generation.in_progress = true;
+    s.generation.queue_another = false;
+    s.generation.shown_progress = GenerationProgress::default();
+    s.generation.handle = Some(Generator::new(request).spawn());
+}
+
+/// Markers the file blocks of the output are written with: the workspace's, or `# %%`
+/// cells when Cell Markers is on.
+fn output_file_markers(app: &AppWindow, state: &SharedState) -> FileMarkers {
+    if app.get_cell_markers() {
+        FileMarkers::cells()
+    } else {
+        state.borrow().file_markers.clone()
+    }
+}
+
+/// Start the timer that shows the running generation's progress and picks up its result.
+fn ensure_generation_pump(app: &AppWindow, state: &SharedState) {
+    let s = state.borrow();
+    if s.gen_pump_timer.running() {
+        return;
+    }
+
+    let app_weak = app.as_weak();
+    let state_rc = state.clone();
+    s.gen_pump_timer.start(
+        slint::TimerMode::Repeated,
+        std::time::Duration::from_millis(120),
+        move || {
+            if let Some(app) = app_weak.upgrade() {
+                pump_generation(&app, &state_rc);
+            }
+        },
+    );
+}
+
+fn pump_generation(app: &AppWindow, state: &SharedState) {
+    let result = {
+        let mut guard = state.borrow_mut();
+        let g = &mut guard.generation;
+        let Some(handle) = g.handle.as_ref() else {
+            return;
+        };
+        let Some(result) = handle.try_result() else {
+            let progress = handle.progress();
+            if progress != g.shown_progress && !g.queue_another && progress.files_total > 0 {
+                g.shown_progress = progress;
+                app.set_output_text(
+                    format!(
+                        "{} {}{} {}/{}",
+                        stitch::core::GLYPH_HOURGLASS,
+                        app.global::<Strings>().get_generating(),
+                        stitch::core::GLYPH_ELLIPSIS,
+                        progress.files_done,
+                        progress.files_total
+                    )
+                    .into(),
+                );
+            }
+            return;
+        };
+        g.handle = None;
+        g.in_progress = false;
+        result
+    };
+    let rerun = std::mem::take(&mut state.borrow_mut().generation.queue_another);
+
+    match result {
+        Ok(GenerationOutput { text, stats, split }) => {
+            {
+                let mut st = state.borrow_mut();
+                st.split_source = (!split.blocks.is_empty()).then_some(split);
+                st.selection_stats = stats.clone();
+            }
+            emit(
+                state,
+                WorkspaceEvent::GenerationFinished {
+                    output: text,
+                    stats,
+                },
+            );
+        }
+        // Superseded by the queued generation below.
+        Err(GenerationError::Cancelled) => {}
+        Err(GenerationError::Failed(detail)) => {
+            {
+                let mut st = state.borrow_mut();
+                st.split_source = None;
+                st.selection_stats = SelectionStats::default();
+            }
+            emit(
+                state,
+                WorkspaceEvent::GenerationFailed {
+                    message: app
+                        .global::<Strings>()
+                        .invoke_generation_failed(detail.into())
+                        .into(),
+                },
+            );
+        }
+    }
+
+    if rerun {
+        on_generate_output(app, state);
+    }
+}
+
+/// Show `text` under the copy button for `millis` milliseconds.
+fn show_copy_toast(app: &AppWindow, state: &SharedState, text: slint::SharedString, millis: u64) {
+    app.set_copy_toast_text(text);
+    app.set_show_copy_toast(true);
+
+    let s = state.borrow_mut();
+    let millis = s.accessibility.toast_millis(millis);
+    let app_weak = app.as_weak();
+    s.copy_toast_timer.start(
+        slint::TimerMode::SingleShot,
+        std::time::Duration::from_millis(millis),
+        move || {
+            if let Some(app) = app_weak.upgrade() {
+                app.set_show_copy_toast(false);
+            }
+        },
+    );
+}
+
+/// The current output if it can be copied or saved. Otherwise shows why not: a
+/// generation is still running (the stored text is the previous output) or the output
+/// is empty or a placeholder, toasting `nothing`.
+fn exportable_output(
+    app: &AppWindow,
+    state: &SharedState,
+    nothing: slint::SharedString,
+) -> Option<String> {
+    let (generating, text, kind) = {
+        let s = state.borrow();
+        (
+            s.generation.in_progress,
+            s.full_output_text.clone(),
+            s.output_kind,
+        )
+    };
+    let strings = app.global::<Strings>();
+    if generating {
+        show_copy_toast(app, state, strings.get_output_not_ready(), 1200);
+        return None;
+    }
+    if !has_output_content(kind, &text) {
+        show_copy_toast(app, state, nothing, 1200);
+        return None;
+    }
+    Some(text)
+}
+
+pub fn on_copy_output(app: &AppWindow, state: &SharedState) {
+    let nothing = app.global::<Strings>().get_nothing_to_copy();
+    let Some(text) = exportable_output(app, state, nothing) else {
+        return;
+    };
+
+    let ok = arboard::Clipboard::new().is_ok_and(|mut cb| cb.set_text(text.as_str()).is_ok());
+    if ok {
+        record_output_history(app, state, &text);
+    }
+
+    let strings = app.global::<Strings>();
+    let toast = if ok {
+        strings.get_copied()
+    } else {
+        strings.get_copy_failed()
+    };
+    show_copy_toast(app, state, toast, 1200);
+}
+
+/* ----------------------------- Hierarchy export ----------------------------- */
+
+/// Tree of the current selection as "Hierarchy Only" shows it, without the section header.
+///
+/// Built on its own so the main output and its stats are left untouched.
+fn selection_tree(app: &AppWindow, state: &SharedState) -> Option<String> {
+    let selection = collect_selection_snapshot(state, app.get_dirs_only()).ok()?;
+    let (rewrite, style) = {
+        let s = state.borrow();
+        (s.path_rewrite.clone(), s.tree_style)
+    };
+    Some(render_hierarchy(
+        &selection.relative_paths,
+        &selection.root_name,
+        &rewrite,
+        style,
+    ))
+}
+
+pub fn on_copy_hierarchy(app: &AppWindow, state: &SharedState) {
+    let strings = app.global::<Strings>();
+    let Some(tree) = selection_tree(app, state) else {
+        show_copy_toast(app, state, strings.get_nothing_to_copy(), 900);
+        return;
+    };
+    let ok = arboard::Clipboard::new().is_ok_and(|mut cb| cb.set_text(tree).is_ok());
+    let toast = if ok {
+        strings.get_copied()
+    } else {
+        strings.get_copy_failed()
+    };
+    show_copy_toast(app, state, toast, 1200);
+}
+
+pub fn on_save_hierarchy(app: &AppWindow, state: &SharedState) {
+    let strings = app.global::<Strings>();
+    let Some(tree) = selection_tree(app, state) else {
+        show_copy_toast(app, state, strings.get_nothing_to_save(), 900);
+        return;
+    };
+    let mut dialog = rfd::FileDialog::new()
+        .set_file_name("hierarchy.txt")
+        .add_filter("Text", &["txt"]);
+    if let Some(dir) = state.borrow().selected_directory.as_ref() {
+        dialog = dialog.set_directory(dir);
+    }
+    let Some(path) = dialog.save_file() else {
+        return;
+    };
+    let toast = if fs::write(&path, tree).is_ok() {
+        strings.get_saved()
+    } else {
+        strings.get_save_failed()
+    };
+    show_copy_toast(app, state, toast, 1200);
+}
+
+/* ----------------------------- Compressed export ---------------------------- */
+
+/// Write the full output as `.md.gz`, or as a `.zip` with the selection tree as its own
+/// entry, for chat tools with attachment size limits. The toast shows the file size.
+pub fn on_export_compressed(app: &AppWindow, state: &SharedState) {
+    let strings = app.global::<Strings>();
+    let Some(text) = exportable_output(app, state, strings.get_nothing_to_save()) else {
+        return;
+    };
+    let mut dialog = rfd::FileDialog::new()
+        .set_file_name(CompressedFormat::Gzip.default_file_name())
+        .add_filter("Gzip", &["gz"])
+        .add_filter("Zip", &["zip"]);
+    if let Some(dir) = state.borrow().selected_directory.as_ref() {
+        dialog = dialog.set_directory(dir);
+    }
+    let Some(path) = dialog.save_file() else {
+        return;
+    };
+    let format = CompressedFormat::from_path(&path);
+    let tree = match format {
+        CompressedFormat::Zip => selection_tree(app, state),
+        CompressedFormat::Gzip => None,
+    };
+    let written = compress_bundle(format, &text, tree.as_deref())
+        .and_then(|bytes| fs::write(&path, &bytes).map(|()| bytes.len()));
+    let toast = match written {
+        Ok(size) => strings.invoke_exported(format_byte_size(size as u64).into()),
+        Err(_) => strings.get_save_failed(),
+    };
+    show_copy_toast(app, state, toast, 1800);
+}
+
+/* ------------------------------- Split export ------------------------------- */
+
+/// Select the definition of `name` in the output pane, or say where it is defined when
+/// the output doesn't contain it.
+pub fn on_find_definition(app: &AppWindow, state: &SharedState, name: &str) {
+    let name = name.trim();
+    if name.is_empty() {
+        return;
+    }
+    let found = find_definition(&state.borrow().full_output_text, name);
+    if let Some(def) = found {
+        reveal_output_range(app, state, def.start..def.end);
+        return;
+    }
+
+    let index = {
+        let s = state.borrow();
+        match (s.selected_directory.as_deref(), s.root_node.as_ref()) {
+            (Some(dir), Some(root)) => SymbolIndex::build(dir, &collect_file_paths(root)),
+            _ => SymbolIndex::default(),
+        }
+    };
+    let strings = app.global::<Strings>();
+    let toast = index.files_defining(name).first().map_or_else(
+        || strings.invoke_no_definition(name.into()),
+        |path| strings.invoke_defined_outside_output(name.into(), path.into()),
+    );
+    show_copy_toast(app, state, toast, 2200);
+}
+
+/// Write one output per selected top-level directory (`backend.md`, `frontend.md`, ...)
+/// into a chosen folder, each with the shared sections of the last generation.
+pub fn on_export_split(app: &AppWindow, state: &SharedState) {
+    let strings = app.global::<Strings>();
+    if exportable_output(app, state, strings.get_nothing_to_save()).is_none() {
+        return;
+    }
+    let Some(source) = state.borrow().split_source.clone() else {
+        show_copy_toast(app, state, strings.get_nothing_to_save(), 1200);
+        return;
+    };
+    let mut dialog = rfd::FileDialog::new();
+    if let Some(dir) = state.borrow().selected_directory.as_ref() {
+        dialog = dialog.set_directory(dir);
+    }
+    let Some(folder) = dialog.pick_folder() else {
+        return;
+    };
+    let layout = state.borrow().output_layout.clone();
+    let splits = split_output_by_top_level_dir(&layout, &source);
+    let written = splits
+        .iter()
+        .try_for_each(|split| fs::write(folder.join(&split.file_name), &split.text));
+    let toast = match written {
+        Ok(()) => strings.invoke_exported_files(i32::try_from(splits.len()).unwrap_or(i32::MAX)),
+        Err(_) => strings.get_save_failed(),
+    };
+    show_copy_toast(app, state, toast, 1800);
+}
+
+/* ----------------------------- Budget trimming ------------------------------ */
+
+/// Estimated tokens of the current output and the context window it should fit in.
+fn trim_budget(app: &AppWindow, state: &SharedState) -> (usize, Option<usize>) {
+    let total = estimate_tokens_with_markers(
+        &state.borrow().full_output_text,
+        &output_file_markers(app, state),
+    );
+    let limit = ContextWindow::from_index(app.get_context_window_index()).tokens();
+    (total, limit)
+}
+
+/// Fill the trim dialog with the files of the last generation, most tokens first.
+pub fn refresh_trim_dialog(app: &AppWindow, dlg: &TrimBudgetDialog, state: &SharedState) {
+    let candidates = trim_candidates(&state.borrow().selection_stats);
+    let rows: Vec<TrimRow> = candidates
+        .iter()
+        .map(|c| TrimRow {
+            label: format!("{}  (~{} tokens)", c.path, c.tokens).into(),
+            checked: false,
+        })
+        .collect();
+    state.borrow_mut().trim_candidates = candidates;
+    dlg.set_rows(ModelRc::new(VecModel::from(rows)));
+    dlg.set_has_limit(trim_budget(app, state).1.is_some());
+    update_trim_projection(app, dlg, state);
+}
+
+fn trim_dropped(dlg: &TrimBudgetDialog) -> Vec<bool> {
+    dlg.get_rows().iter().map(|row| row.checked).collect()
+}
+
+fn update_trim_projection(app: &AppWindow, dlg: &TrimBudgetDialog, state: &SharedState) {
+    let (total, limit) = trim_budget(app, state);
+    let projected = projected_tokens(total, &state.borrow().trim_candidates, &trim_dropped(dlg));
+    let label = limit.map_or_else(
+        || format!("~{projected} tokens"),
+        |limit| format!("~{projected} / {limit} tokens"),
+    );
+    dlg.set_projected(label.into());
+    dlg.set_fits(limit.is_none_or(|limit| projected <= limit));
+}
+
+pub fn on_trim_toggle(
+    app: &AppWindow,
+    dlg: &TrimBudgetDialog,
+    state: &SharedState,
+    index: i32,
+    checked: bool,
+) {
+    let rows = dlg.get_rows();
+    if let Some(i) = usize::try_from(index).ok()
+        && let Some(mut row) = rows.row_data(i)
+    {
+        row.checked = checked;
+        rows.set_row_data(i, row);
+    }
+    update_trim_projection(app, dlg, state);
+}
+
+/// Check the largest files until the output fits the context window.
+pub fn on_trim_suggest(app: &AppWindow, dlg: &TrimBudgetDialog, state: &SharedState) {
+    let (total, Some(limit)) = trim_budget(app, state) else {
+        return;
+    };
+    let dropped = suggest_trim(total, &state.borrow().trim_candidates, limit);
+    let rows = dlg.get_rows();
+    for (i, drop) in dropped.into_iter().enumerate() {
+        if let Some(mut row) = rows.row_data(i) {
+            row.checked = drop;
+            rows.set_row_data(i, row);
+        }
+    }
+    update_trim_projection(app, dlg, state);
+}
+
+/// Uncheck (and unpin) the checked files, then regenerate.
+pub fn on_trim_apply(app: &AppWindow, dlg: &TrimBudgetDialog, state: &SharedState) {
+    let dropped = trim_dropped(dlg);
+    {
+        let mut s = state.borrow_mut();
+        let Some(root) = s.selected_directory.clone() else {
+            return;
+        };
+        let paths: Vec<String> = s
+            .trim_candidates
+            .iter()
+            .zip(&dropped)
+            .filter(|&(_, &drop)| drop)
+            .map(|(c, _)| c.path.clone())
+            .collect();
+        if paths.is_empty() {
+            return;
+        }
+        let removes: Vec<PathBuf> = paths
+            .iter()
+            .map(|rel| project_path_from_rel(&root, rel))
+            .collect();
+        apply_selection_diff(&mut s.explicit_states, &[], &removes);
+        s.pinned_files.retain(|pin| !paths.contains(pin));
+    }
+    refresh_flat_model(app, state);
+    emit(state, WorkspaceEvent::SelectionChanged);
+}
+
+/* ---------------------------- Call-graph context ---------------------------- */
+
+/// Fill the dialog with the project's Rust files, starting on the file of tree row
+/// `focused_row` if it is one.
+pub fn refresh_call_graph_dialog(
+    app: &AppWindow,
+    dlg: &CallGraphDialog,
+    state: &SharedState,
+    focused_row: i32,
+) {
+    let (dir, files) = {
+        let s = state.borrow();
+        let files: Vec<PathBuf> = s.root_node.as_ref().map_or_else(Vec::new, |root| {
+            let mut files: Vec<PathBuf> = collect_file_paths(root)
+                .into_iter()
+                .filter(|p| is_rust_file_path(p))
+                .collect();
+            files.sort();
+            files
+        });
+        (s.selected_directory.clone(), files)
+    };
+    let focused = usize::try_from(focused_row)
+        .ok()
+        .and_then(|i| app.get_tree_model().row_data(i))
+        .map(|row| PathBuf::from(row.path.as_str()));
+    let index = focused
+        .and_then(|path| files.iter().position(|f| *f == path))
+        .unwrap_or(0);
+
+    let labels: Vec<slint::SharedString> = files
+        .iter()
+        .map(|f| {
+            dir.as_deref()
+                .and_then(|dir| f.strip_prefix(dir).ok())
+                .map_or_else(|| f.to_string_lossy().into_owned(), path_to_unix)
+                .into()
+        })
+        .collect();
+    let has_files = !files.is_empty();
+    state.borrow_mut().call_graph_files = files;
+    dlg.set_files(ModelRc::new(VecModel::from(labels)));
+    dlg.set_file_index(if has_files {
+        i32::try_from(index).unwrap_or(0)
+    } else {
+        -1
+    });
+    dlg.set_results(if has_files {
+        "".into()
+    } else {
+        "No Rust files in the project.\n".into()
+    });
+    on_call_graph_file_selected(dlg, state, dlg.get_file_index());
+}
+
+/// List the functions of the chosen file.
+pub fn on_call_graph_file_selected(dlg: &CallGraphDialog, state: &SharedState, index: i32) {
+    let file = usize::try_from(index)
+        .ok()
+        .and_then(|i| state.borrow().call_graph_files.get(i).cloned());
+    let names = file
+        .and_then(|f| fs::read_to_string(f).ok())
+        .map(|source| rust_function_names(&source))
+        .unwrap_or_default();
+    let labels: Vec<slint::SharedString> = names.iter().map(Into::into).collect();
+    dlg.set_function_index(if names.is_empty() { -1 } else { 0 });
+    dlg.set_functions(ModelRc::new(VecModel::from(labels)));
 }
 
-fn build_hierarchy_header(
+fn call_graph_for(
+    dlg: &CallGraphDialog,
     state: &SharedState,
-    selection: &SelectionSnapshot,
-    disable_notes: bool,
-) -> String {
-    let mut header = String::from("=== FILE HIERARCHY ===\n\n");
-    header.push_str(&render_unicode_tree_from_paths(
-        &selection.relative_paths,
-        Some(&selection.root_name),
-    ));
-
-    if !disable_notes {
-        let notes = build_notes_section(state, &selection.selected_dir, &selection.relative_paths);
-        if !notes.trim().is_empty() {
-            header.push_str("\n=== NOTES ===\n\n");
-            header.push_str(&notes);
-            header.push('\n');
-        }
-    }
-
-    header
+    file_index: i32,
+    function_index: i32,
+    depth: &str,
+) -> Option<(PathBuf, CallGraphContext)> {
+    let s = state.borrow();
+    let dir = s.selected_directory.clone()?;
+    let file = s.call_graph_files.get(usize::try_from(file_index).ok()?)?;
+    let function = dlg
+        .get_functions()
+        .row_data(usize::try_from(function_index).ok()?)?;
+    let depth = depth.trim().parse().unwrap_or(DEFAULT_CALL_GRAPH_DEPTH);
+    let context = call_graph_context(&s.call_graph_files, file, &function, depth)?;
+    Some((dir, context))
 }
 
-fn prepare_async_generation(
-    app: &AppWindow,
+/// Show the items the chosen function reaches, without changing the selection.
+pub fn on_call_graph_preview(
+    dlg: &CallGraphDialog,
     state: &SharedState,
-    selection: SelectionSnapshot,
-    header: String,
+    file_index: i32,
+    function_index: i32,
+    depth: &str,
 ) {
-    app.set_output_text(
-        format!(
-            "{} Generating{}",
-            stitch::core::GLYPH_HOURGLASS,
-            stitch::core::GLYPH_ELLIPSIS
-        )
-        .into(),
+    let text = call_graph_for(dlg, state, file_index, function_index, depth).map_or_else(
+        || dlg.global::<Strings>().get_pick_rust_function(),
+        |(dir, context)| context.render(&dir).into(),
     );
-    app.set_output_stats("".into());
-
-    ensure_generation_channel(app, state);
-    let job = build_generation_job(state, selection, header);
-    spawn_generation_worker(job);
+    dlg.set_results(text);
 }
 
-fn ensure_generation_channel(app: &AppWindow, state: &SharedState) {
-    let mut s = state.borrow_mut();
-    if s.gen_result_tx.is_some() && s.gen_result_rx.is_some() {
-        return;
+/// Replace the selection with the files of the chosen function's call graph.
+pub fn on_call_graph_apply(
+    app: &AppWindow,
+    dlg: &CallGraphDialog,
+    state: &SharedState,
+    file_index: i32,
+    function_index: i32,
+    depth: &str,
+) -> bool {
+    let Some((_, context)) = call_graph_for(dlg, state, file_index, function_index, depth) else {
+        return false;
+    };
+    {
+        let mut s = state.borrow_mut();
+        select_only(&mut s.explicit_states, context.files);
     }
+    refresh_flat_model(app, state);
+    emit(state, WorkspaceEvent::SelectionChanged);
+    true
+}
 
-    let (tx, rx) = mpsc::channel::<(u64, String)>();
-    s.gen_result_tx = Some(tx);
-    s.gen_result_rx = Some(rx);
-
-    let app_weak = app.as_weak();
-    let state_rc = state.clone();
-    s.gen_pump_timer.start(
-        slint::TimerMode::Repeated,
-        std::time::Duration::from_millis(120),
-        move || {
-            if let (Some(app), Some(out)) = (app_weak.upgrade(), drain_latest_result(&state_rc)) {
-                #[allow(clippy::print_stdout)]
-                set_output(&app, &state_rc, &out);
-                update_last_refresh(&app);
-
-                let rerun = {
-                    let mut st = state_rc.borrow_mut();
-                    st.generation.in_progress = false;
-                    let again = st.generation.queue_another;
-                    st.generation.queue_another = false;
-                    again
-                };
+/* ---------------------------- Selection snapshots --------------------------- */
 
-                if rerun {
-                    on_generate_output(&app, &state_rc);
-                }
-            }
-        },
-    );
+/// Reload the project's snapshots into the list and select the one with id `selected`.
+fn refresh_snapshots(app: &AppWindow, state: &SharedState, selected: Option<u64>) {
+    let root = state.borrow().selected_directory.clone();
+    let snapshots = root.as_deref().map(load_snapshots).unwrap_or_default();
+    let labels: Vec<slint::SharedString> = snapshots
+        .iter()
+        .map(|snapshot| snapshot.display().into())
+        .collect();
+    let index = selected
+        .and_then(|id| snapshots.iter().position(|snapshot| snapshot.id == id))
+        .and_then(|i| i32::try_from(i).ok())
+        .unwrap_or(-1);
+    state.borrow_mut().snapshots = snapshots;
+    app.set_snapshots(ModelRc::new(VecModel::from(labels)));
+    app.set_snapshot_index(index);
 }
 
-fn drain_latest_result(state: &SharedState) -> Option<String> {
-    let mut last: Option<(u64, String)> = None;
-    let guard = state.borrow();
-    if let Some(rx) = guard.gen_result_rx.as_ref() {
-        while let Ok(msg) = rx.try_recv() {
-            last = Some(msg);
-        }
+/// Save the current explicit checks as a new snapshot.
+pub fn on_take_snapshot(app: &AppWindow, state: &SharedState, label: &str) {
+    let Some(root) = state.borrow().selected_directory.clone() else {
+        return;
+    };
+    let explicit = explicit_selections(state, &root);
+    let created = Local::now().format("%Y-%m-%d %H:%M").to_string();
+    if let Ok(snapshot) = add_snapshot(&root, label, &created, explicit) {
+        app.set_snapshot_label(slint::SharedString::default());
+        refresh_snapshots(app, state, Some(snapshot.id));
     }
-    drop(guard);
-    last.map(|(_, s)| s)
 }
 
-fn build_generation_job(
-    state: &SharedState,
-    selection: SelectionSnapshot,
-    header: String,
-) -> GenerationJob {
-    let SelectionSnapshot {
-        files,
-        selected_dir,
-        ..
-    } = selection;
-
-    let (remove_prefixes, remove_regex, rust_opts, rust_sig_filter, slint_opts) = {
-        let s = state.borrow();
-        let comment = s.rust_ui.comment_removal;
-        let opts = RustFilterOptions {
-            remove_inline_regular_comments: comment.removes_inline(),
-            remove_doc_comments: comment.removes_doc(),
-            function_signatures_only: s.rust_ui.signatures_filter.is_some(),
-        };
-        let filter = s.rust_ui.signatures_filter.clone().unwrap_or_default();
-        let slint_opts = stitch::core::SlintFilterOptions {
-            remove_line_comments: s.slint_ui.remove_line_comments,
-            remove_block_comments: s.slint_ui.remove_block_comments,
-        };
-        (
-            s.remove_prefixes.clone(),
-            s.remove_regex.clone(),
-            opts,
-            filter,
-            slint_opts,
-        )
+/// Replace the explicit checks with the snapshot at `index`; settings stay as they are.
+pub fn on_restore_snapshot(app: &AppWindow, state: &SharedState, index: i32) {
+    let Some(snapshot) = usize::try_from(index)
+        .ok()
+        .and_then(|i| state.borrow().snapshots.get(i).cloned())
+    else {
+        return;
     };
+    restore_explicit_selections(state, &snapshot.explicit);
+    rebuild_tree_and_ui(app, state);
+    on_generate_output(app, state);
+    update_save_button_state(app, state);
+}
 
-    let tx = {
+pub fn on_delete_snapshot(app: &AppWindow, state: &SharedState, index: i32) {
+    let (root, id) = {
         let s = state.borrow();
-        s.gen_result_tx
-            .clone()
-            .expect("generation channel must exist")
-    };
-
-    let seq = {
-        let mut s = state.borrow_mut();
-        s.generation.in_progress = true;
-        s.generation.queue_another = false;
-        s.gen_seq = s.gen_seq.wrapping_add(1);
-        s.gen_seq
+        let id = usize::try_from(index)
+            .ok()
+            .and_then(|i| s.snapshots.get(i))
+            .map(|snapshot| snapshot.id);
+        (s.selected_directory.clone(), id)
     };
-
-    GenerationJob {
-        header,
-        files,
-        selected_dir,
-        remove_prefixes,
-        remove_regex,
-        rust_opts,
-        rust_sig_filter,
-        slint_opts,
-        tx,
-        seq,
+    if let (Some(root), Some(id)) = (root, id) {
+        let _ = delete_snapshot(&root, id);
+        refresh_snapshots(app, state, None);
     }
 }
 
-fn spawn_generation_worker(job: GenerationJob) {
-    std::thread::spawn(move || run_generation_job(job));
+/* ----------------------------------- Tasks ---------------------------------- */
+
+/// Reload the project's task names into the task dropdown. A tasks file that doesn't
+/// parse lists none (`stitch run` reports why).
+fn refresh_tasks(app: &AppWindow, state: &SharedState) {
+    let root = state.borrow().selected_directory.clone();
+    let names: Vec<String> = root
+        .as_deref()
+        .and_then(|dir| load_tasks(dir).ok())
+        .map(|tasks| tasks.into_keys().collect())
+        .unwrap_or_default();
+    let labels: Vec<slint::SharedString> = names.iter().map(|n| n.as_str().into()).collect();
+    state.borrow_mut().tasks = names;
+    app.set_tasks(ModelRc::new(VecModel::from(labels)));
+    app.set_task_index(0);
 }
 
-fn run_generation_job(job: GenerationJob) {
-    use std::fmt::Write;
-
-    let GenerationJob {
-        header,
-        files,
-        selected_dir,
-        remove_prefixes,
-        remove_regex,
-        rust_opts,
-        rust_sig_filter,
-        slint_opts,
-        tx,
-        seq,
-    } = job;
-
-    let mut skipped: Vec<(PathBuf, std::io::Error)> = Vec::new();
-    let mut file_sections = String::new();
-
-    for fp in files {
-        let rel: PathBuf = fp.strip_prefix(&selected_dir).map_or_else(
-            |_| PathBuf::from(fp.file_name().unwrap_or_default()),
-            std::path::Path::to_path_buf,
-        );
+/// Run the task at `index` of the dropdown in the background. The toast says where the
+/// bundle was written; a task without an output path copies it instead.
+pub fn on_run_task(app: &AppWindow, state: &SharedState, index: i32) {
+    let job = {
+        let s = state.borrow();
+        let name = usize::try_from(index)
+            .ok()
+            .and_then(|i| s.tasks.get(i).cloned());
+        s.selected_directory.clone().zip(name)
+    };
+    let Some((root, name)) = job else {
+        return;
+    };
+    let strings = app.global::<Strings>();
+    show_copy_toast(
+        app,
+        state,
+        strings.invoke_running_task(name.as_str().into()),
+        1200,
+    );
 
-        let mut contents = match fs::read_to_string(&fp) {
-            Ok(s) => s,
-            Err(e) => {
-                skipped.push((fp.clone(), e));
-                continue;
-            }
-        };
+    let millis = state.borrow().accessibility.toast_millis(2400);
+    let app_weak = app.as_weak();
+    std::thread::spawn(move || {
+        let result = run_task(&root, &name);
+        let _ = slint::invoke_from_event_loop(move || {
+            let Some(app) = app_weak.upgrade() else {
+                return;
+            };
+            let strings = app.global::<Strings>();
+            let toast = match result {
+                Ok(TaskRun {
+                    written: Some(path),
+                    ..
+                }) => {
+                    let shown = path.strip_prefix(&root).unwrap_or(&path);
+                    strings.invoke_task_written(display_path(shown).into())
+                }
+                Ok(TaskRun { text, .. }) => {
+                    let ok =
+                        arboard::Clipboard::new().is_ok_and(|mut cb| cb.set_text(text).is_ok());
+                    if ok {
+                        strings.get_copied()
+                    } else {
+                        strings.get_copy_failed()
+                    }
+                }
+                Err(e) => strings.invoke_task_failed(e.into()),
+            };
+            app.set_copy_toast_text(toast);
+            app.set_show_copy_toast(true);
+            let app_weak = app.as_weak();
+            slint::Timer::single_shot(std::time::Duration::from_millis(millis), move || {
+                if let Some(app) = app_weak.upgrade() {
+                    app.set_show_copy_toast(false);
+                }
+            });
+        });
+    });
+}
 
-        // Simple debug: show hex bytes of key lines right after read
-        #[allow(clippy::print_stdout)]
-        if !remove_prefixes.is_empty() {
-            contents = stitch::core::strip_lines_and_inline_comments(&contents, &remove_prefixes);
-        }
-        if let Some(rr) = &remove_regex {
-            contents = rr.replace_all(&contents, "").to_string();
-        }
+/* ------------------------------ Output history ------------------------------ */
 
-        if is_rust_file_path(&fp) {
-            let rel_for_match = rel
-                .iter()
-                .map(|c| c.to_string_lossy())
-                .collect::<Vec<_>>()
-                .join("/");
-            let mut eff = rust_opts.clone();
-            if !rust_sig_filter.trim().is_empty()
-                && !signatures_filter_matches(&rel_for_match, &rust_sig_filter)
-            {
-                eff.function_signatures_only = false;
-            }
-            contents = apply_rust_filters(&contents, &eff);
-        } else if is_slint_file_path(&fp) {
-            contents = apply_slint_filters(&contents, &slint_opts);
-        }
-
-        let rel_display = rel.to_string_lossy().into_owned();
-        let _ = writeln!(file_sections, "--- Start of file: {rel_display} ---");
-        file_sections.push_str(&contents);
-        file_sections.push('\n');
-        let _ = writeln!(file_sections, "--- End of file: {rel_display} ---\n");
-    }
-    // Merge skipped file notes into the existing NOTES section within the header
-    let mut final_header = header;
-    if !skipped.is_empty() && final_header.contains("=== NOTES ===") {
-        let count = skipped.len();
-        let _ = writeln!(final_header, "Skipped files ({count}):");
-        for (path, err) in skipped {
-            let rel: PathBuf = path.strip_prefix(&selected_dir).map_or_else(
-                |_| PathBuf::from(path.to_string_lossy().to_string()),
-                std::path::Path::to_path_buf,
-            );
-            let reason = err.kind();
-            let msg = match reason {
-                std::io::ErrorKind::InvalidData => "not UTF-8".to_string(),
-                std::io::ErrorKind::PermissionDenied => "Permission denied".to_string(),
-                _ => err.to_string(),
-            };
-            let _ = writeln!(final_header, "- {}: {}", rel.to_string_lossy(), msg);
-        }
-        final_header.push('\n');
+/// Keep a copied output in the project's history (and refresh the history window if open).
+fn record_output_history(app: &AppWindow, state: &SharedState, text: &str) {
+    let Some(root) = state.borrow().selected_directory.clone() else {
+        return;
+    };
+    let profile = profile_vec_index(app.get_selected_profile_index())
+        .and_then(|i| state.borrow().profiles.get(i).map(|meta| meta.name.clone()));
+    let entry = HistoryEntry {
+        created: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        profile,
+        tokens: estimate_tokens_with_markers(text, &output_file_markers(app, state)),
+        tokens_estimated: true,
+        chars: text.chars().count(),
+        ..HistoryEntry::default()
+    };
+    let keep = load_local_settings(&root)
+        .unwrap_or_default()
+        .history_limit();
+    if matches!(record_history(&root, &entry, text, keep), Ok(Some(_))) {
+        refresh_open_history_dialog(state);
     }
-
-    final_header.push_str("\n=== FILE CONTENTS ===\n\n");
-    let mut out = final_header;
-    out.push_str(&file_sections);
-    let _ = tx.send((seq, out));
 }
 
-fn note_excluded_dirs(ctx: &NotesContext, _selected: &SelectedPresence) -> Option<String> {
-    if ctx.exclude_dirs.is_empty() {
-        return None;
-    }
-    if ctx.existing_excluded_dirs.is_empty() {
-        return None;
-    }
-    let mut present: Vec<&str> = ctx
-        .exclude_dirs
-        .iter()
-        .filter(|d| ctx.existing_excluded_dirs.contains(*d))
-        .map(std::string::String::as_str)
-        .collect();
-    present.sort_unstable();
-    if present.is_empty() {
-        None
-    } else {
-        Some(format!("Excluded directories: {}", present.join(", ")))
+fn refresh_open_history_dialog(state: &SharedState) {
+    let dlg = state
+        .borrow()
+        .history_dialog
+        .as_ref()
+        .map(ComponentHandle::clone_strong);
+    if let Some(dlg) = dlg {
+        refresh_history_dialog(&dlg, state);
     }
 }
 
-fn note_excluded_files(ctx: &NotesContext, _selected: &SelectedPresence) -> Option<String> {
-    if ctx.exclude_files.is_empty() {
-        return None;
-    }
-    if ctx.existing_excluded_files.is_empty() {
-        return None;
-    }
-    let mut present: Vec<&str> = ctx
-        .exclude_files
+/// Reload the history list of the current project into the dialog.
+pub fn refresh_history_dialog(dlg: &HistoryDialog, state: &SharedState) {
+    let root = state.borrow().selected_directory.clone();
+    let entries = root.as_deref().map(list_history).unwrap_or_default();
+    let items: Vec<slint::StandardListViewItem> = entries
         .iter()
-        .filter(|f| ctx.existing_excluded_files.contains(*f))
-        .map(std::string::String::as_str)
+        .map(|entry| slint::StandardListViewItem::from(entry.label().as_str()))
         .collect();
-    present.sort_unstable();
-    if present.is_empty() {
-        None
-    } else {
-        Some(format!("Excluded files: {}", present.join(", ")))
-    }
-}
-
-fn note_extension_filters(ctx: &NotesContext, selected: &SelectedPresence) -> Vec<String> {
-    let mut lines = Vec::new();
-    if !ctx.include_exts.is_empty() {
-        let present = selected.collect_present_extensions(&ctx.include_exts);
-        if !present.is_empty() {
-            lines.push(format!("Included extensions: {}", present.join(", ")));
-        }
-    }
-    if !ctx.exclude_exts.is_empty() {
-        let present = selected.collect_present_extensions(&ctx.exclude_exts);
-        if !present.is_empty() {
-            lines.push(format!("Excluded extensions: {}", present.join(", ")));
-        }
-    }
-    lines
+    state.borrow_mut().history = entries;
+    dlg.set_entries(ModelRc::new(VecModel::from(items)));
+    dlg.set_current_index(-1);
+    dlg.set_preview(slint::SharedString::default());
+    dlg.set_status(slint::SharedString::default());
 }
 
-fn note_remove_settings(ctx: &NotesContext) -> Vec<String> {
-    let mut lines = Vec::new();
-    if !ctx.remove_prefixes.is_empty() {
-        lines.push(format!(
-            "Removed lines starting with: {}",
-            ctx.remove_prefixes.join(", ")
-        ));
-    }
-    if let Some(pattern) = ctx.remove_regex.as_ref()
-        && !pattern.trim().is_empty()
-    {
-        lines.push(format!("Applied remove-regex: {pattern}"));
-    }
-    lines
+/// The history entry at `index` with its text.
+fn history_output(state: &SharedState, index: i32) -> Option<(HistoryEntry, String)> {
+    let s = state.borrow();
+    let root = s.selected_directory.as_deref()?;
+    let entry = s.history.get(usize::try_from(index).ok()?)?.clone();
+    let text = load_history_output(root, &entry.id)?;
+    Some((entry, text))
 }
 
-fn note_rust_settings(ctx: &NotesContext, selected: &SelectedPresence) -> Vec<String> {
-    let mut lines = Vec::new();
-    if !selected.has_rust_files() {
-        return lines;
-    }
-    let comment_removal = ctx.comment_removal;
-    if comment_removal.removes_inline() {
-        lines.push("Removed Rust inline comments (//, /* */)".to_string());
-    }
-    if comment_removal.removes_doc() {
-        lines.push("Removed Rust doc comments (///, //!, /** */)".to_string());
+pub fn on_history_show_entry(dlg: &HistoryDialog, state: &SharedState, index: i32) {
+    if let Some((entry, text)) = history_output(state, index) {
+        let total_chars = text.chars().count();
+        let limit = state.borrow().preview_char_limit;
+        dlg.set_preview(truncate_for_ui(text, total_chars, limit, "Copy").into());
+        dlg.set_status(entry.label().into());
     }
-    if let Some(filter) = ctx.signatures_filter.as_ref() {
-        let trimmed = filter.trim();
-        if trimmed.is_empty() {
-            if selected.has_rust_files() {
-                lines.push(
-                    "Functions bodies omitted (signatures only) for all Rust files".to_string(),
-                );
-            }
-        } else if selected.any_matches_filter(trimmed) {
-            lines.push(format!(
-                "Functions bodies omitted (signatures only) for: {filter}"
-            ));
-        }
-    }
-    lines
 }
 
-fn note_slint_settings(ctx: &NotesContext, selected: &SelectedPresence) -> Vec<String> {
-    let mut lines = Vec::new();
-    if !selected.has_slint_files() {
-        return lines;
-    }
-    if ctx.slint_remove_line_comments {
-        lines.push("Removed Slint single-line comments (//)".to_string());
-    }
-    if ctx.slint_remove_block_comments {
-        lines.push("Removed Slint multi-line comments (/* */)".to_string());
-    }
-    lines
+/// Show what changed from the entry at `index` to the current output.
+pub fn on_history_diff_with_current(
+    app: &AppWindow,
+    dlg: &HistoryDialog,
+    state: &SharedState,
+    index: i32,
+) {
+    let Some((entry, old)) = history_output(state, index) else {
+        return;
+    };
+    let current = state.borrow().full_output_text.clone();
+    let strings = app.global::<Strings>();
+    show_history_diff(
+        dlg,
+        state,
+        &old,
+        &current,
+        strings.invoke_diff_title(entry.created.into(), strings.get_current_output()),
+        strings.get_no_differences(),
+    );
 }
 
-fn build_notes_section(
+/// Show what changed from the entry before the one at `index` to it.
+pub fn on_history_diff_with_older(
+    app: &AppWindow,
+    dlg: &HistoryDialog,
     state: &SharedState,
-    _project_root: &std::path::Path,
-    rel_selected_paths: &[String],
-) -> String {
-    let ctx = {
-        let s = state.borrow();
-
-        let mut exclude_dirs: Vec<String> = s
-            .exclude_dirs
-            .iter()
-            .filter(|dir| dir.as_str() != ".stitchworkspace")
-            .cloned()
-            .collect();
-        let mut exclude_files: Vec<String> = s.exclude_files.iter().cloned().collect();
-        exclude_dirs.sort_unstable();
-        exclude_files.sort_unstable();
-
-        let existing_excluded_dirs: HashSet<String> = exclude_dirs
-            .iter()
-            .filter(|dir| s.existing_excluded_dirs.contains(*dir))
-            .cloned()
-            .collect();
-        let existing_excluded_files: HashSet<String> = exclude_files
-            .iter()
-            .filter(|file| s.existing_excluded_files.contains(*file))
-            .cloned()
-            .collect();
-
-        NotesContext {
-            exclude_dirs,
-            exclude_files,
-            existing_excluded_dirs,
-            existing_excluded_files,
-            include_exts: s.include_exts.clone(),
-            exclude_exts: s.exclude_exts.clone(),
-            remove_prefixes: s.remove_prefixes.clone(),
-            remove_regex: s.remove_regex_str.clone(),
-            comment_removal: s.rust_ui.comment_removal,
-            signatures_filter: s.rust_ui.signatures_filter.clone(),
-            slint_remove_line_comments: s.slint_ui.remove_line_comments,
-            slint_remove_block_comments: s.slint_ui.remove_block_comments,
-        }
+    index: i32,
+) {
+    let (Some((older, old)), Some((newer, new))) = (
+        history_output(state, index + 1),
+        history_output(state, index),
+    ) else {
+        return;
     };
+    let strings = app.global::<Strings>();
+    show_history_diff(
+        dlg,
+        state,
+        &old,
+        &new,
+        strings.invoke_diff_title(older.created.into(), newer.created.into()),
+        strings.get_no_differences(),
+    );
+}
 
-    let selected = SelectedPresence::new(rel_selected_paths);
-    let mut lines: Vec<String> = Vec::new();
-
-    if let Some(line) = note_excluded_dirs(&ctx, &selected) {
-        lines.push(line);
-    }
-    if let Some(line) = note_excluded_files(&ctx, &selected) {
-        lines.push(line);
+fn show_history_diff(
+    dlg: &HistoryDialog,
+    state: &SharedState,
+    old: &str,
+    new: &str,
+    title: slint::SharedString,
+    no_differences: slint::SharedString,
+) {
+    let diff = render_diff(old, new, 3);
+    if diff.is_empty() {
+        dlg.set_preview(no_differences);
+    } else {
+        let total_chars = diff.chars().count();
+        let limit = state.borrow().preview_char_limit;
+        dlg.set_preview(truncate_for_ui(diff, total_chars, limit, "Copy").into());
     }
-    lines.extend(note_extension_filters(&ctx, &selected));
-    lines.extend(note_remove_settings(&ctx));
-    lines.extend(note_rust_settings(&ctx, &selected));
-    lines.extend(note_slint_settings(&ctx, &selected));
-
-    lines.join("\n")
+    dlg.set_status(title);
 }
 
-pub fn on_copy_output(app: &AppWindow, state: &SharedState) {
-    let text = { state.borrow().full_output_text.clone() };
-
-    if text.is_empty() {
-        app.set_copy_toast_text("Nothing to copy".into());
-        app.set_show_copy_toast(true);
-
-        {
-            let s = state.borrow_mut();
-            let app_weak = app.as_weak();
-            s.copy_toast_timer.start(
-                slint::TimerMode::SingleShot,
-                std::time::Duration::from_millis(900),
-                move || {
-                    if let Some(app) = app_weak.upgrade() {
-                        app.set_show_copy_toast(false);
-                    }
-                },
-            );
-        }
+pub fn on_history_copy_entry(
+    app: &AppWindow,
+    dlg: &HistoryDialog,
+    state: &SharedState,
+    index: i32,
+) {
+    let Some((_, text)) = history_output(state, index) else {
         return;
-    }
-
+    };
     let ok = arboard::Clipboard::new().is_ok_and(move |mut cb| cb.set_text(text).is_ok());
+    let strings = app.global::<Strings>();
+    dlg.set_status(if ok {
+        strings.get_copied()
+    } else {
+        strings.get_copy_failed()
+    });
+}
 
-    app.set_copy_toast_text(if ok { "Copied!" } else { "Copy failed" }.into());
-    app.set_show_copy_toast(true);
+/// Count the project's files under the current filters in the background and show the
+/// report in the dialog.
+pub fn refresh_stats_dialog(app: &AppWindow, dlg: &StatsDialog, state: &SharedState) {
+    let strings = app.global::<Strings>();
+    let job = {
+        let s = state.borrow();
+        s.selected_directory.clone().map(|root| {
+            (
+                root,
+                s.include_exts.clone(),
+                s.exclude_exts.clone(),
+                s.exclude_dirs.clone(),
+                s.exclude_files.clone(),
+                s.dir_rules.clone(),
+                s.name_matching,
+            )
+        })
+    };
+    let Some((root, include_exts, exclude_exts, exclude_dirs, exclude_files, rules, names)) = job
+    else {
+        dlg.set_report(slint::SharedString::default());
+        dlg.set_status(strings.get_no_project());
+        return;
+    };
+    dlg.set_status(strings.get_counting());
+
+    let dlg_weak = dlg.as_weak();
+    std::thread::spawn(move || {
+        let filters = ScanFilters {
+            include_exts: &include_exts,
+            exclude_exts: &exclude_exts,
+            exclude_dirs: &exclude_dirs,
+            exclude_files: &exclude_files,
+            rules: &rules,
+            names,
+        };
+        let report = collect_project_stats(&root, &filters).render();
+        let status = display_path(&root);
+        let _ = slint::invoke_from_event_loop(move || {
+            if let Some(dlg) = dlg_weak.upgrade() {
+                dlg.set_report(report.into());
+                dlg.set_status(status.into());
+            }
+        });
+    });
+}
 
-    {
-        let s = state.borrow_mut();
-        let app_weak = app.as_weak();
-        s.copy_toast_timer.start(
-            slint::TimerMode::SingleShot,
-            std::time::Duration::from_millis(1200),
-            move || {
-                if let Some(app) = app_weak.upgrade() {
-                    app.set_show_copy_toast(false);
-                }
-            },
-        );
-    }
+pub fn on_stats_copy(app: &AppWindow, dlg: &StatsDialog) {
+    let text = dlg.get_report().to_string();
+    let ok = arboard::Clipboard::new().is_ok_and(move |mut cb| cb.set_text(text).is_ok());
+    let strings = app.global::<Strings>();
+    dlg.set_status(if ok {
+        strings.get_copied()
+    } else {
+        strings.get_copy_failed()
+    });
 }
 
 pub fn rebuild_tree_and_ui(app: &AppWindow, state: &SharedState) {
@@ -930,33 +2230,31 @@ pub fn rebuild_tree_and_ui(app: &AppWindow, state: &SharedState) {
         let s = state.borrow();
         if s.selected_directory.is_none() {
             set_tree_model(app, Vec::new());
+            app.set_ext_filter_warning("".into());
             return;
         }
     }
     {
-        let (root, snapshot, scan_stats) = {
+        let (root, snapshot, scan_stats, dir) = {
             let s = state.borrow();
             let dir = s.selected_directory.as_ref().unwrap().clone();
-            let include = s.include_exts.clone();
-            let exclude = s.exclude_exts.clone();
-            let ex_dirs = s.exclude_dirs.clone();
-            let ex_files = s.exclude_files.clone();
-            let scan = scan_dir_to_node_with_stats(&dir, &include, &exclude, &ex_dirs, &ex_files);
+            let scan = scan_project(&s, &dir);
             let snap = gather_paths_set(&scan.node);
-            (scan.node, snap, scan.stats)
+            (scan.node, snap, scan.stats, dir)
         };
 
         {
             let mut s = state.borrow_mut();
             s.path_snapshot = Some(snapshot);
             s.root_node = Some(root);
-            s.existing_excluded_dirs = scan_stats.excluded_dirs_found;
-            s.existing_excluded_files = scan_stats.excluded_files_found;
-            s.remove_regex = compile_remove_regex_opt(s.remove_regex_str.as_deref());
+            s.scan_stats = scan_stats;
+            s.stitchignore = IgnoreRules::load(&dir);
         }
     }
 
     refresh_flat_model(app, state);
+    refresh_ext_filter_warning(app, state);
+    refresh_exclude_suggestions(app, state);
 
     // Detect presence of any .rs file to toggle Rust section visibility
     let has_rs = {
@@ -1016,8 +2314,9 @@ pub fn rebuild_tree_and_ui(app: &AppWindow, state: &SharedState) {
 fn refresh_flat_model(app: &AppWindow, state: &SharedState) {
     let rows = {
         let s = state.borrow();
-        if let Some(root) = &s.root_node {
-            flatten_tree(root, &s.explicit_states, None, 0)
+        if let (Some(root), Some(dir)) = (&s.root_node, &s.selected_directory) {
+            let labels = RowLabels::of(&s, dir);
+            flatten_tree(root, &s.explicit_states, &labels, None, 0)
         } else {
             Vec::new()
         }
@@ -1025,12 +2324,131 @@ fn refresh_flat_model(app: &AppWindow, state: &SharedState) {
     set_tree_model(app, rows);
 }
 
+/// Warn about include-extension tokens that match no file in the scanned tree (e.g. `.tx`).
+fn refresh_ext_filter_warning(app: &AppWindow, state: &SharedState) {
+    let unmatched: Vec<String> = {
+        let s = state.borrow();
+        match s.root_node.as_ref() {
+            Some(root) if !s.include_exts.is_empty() => {
+                extension_filter_match_counts(root, &s.include_exts)
+                    .into_iter()
+                    .filter(|(_, n)| *n == 0)
+                    .map(|(token, _)| token)
+                    .collect()
+            }
+            _ => Vec::new(),
+        }
+    };
+    let overlaps = find_filter_overlaps(&workspace_settings_from_ui(app, state));
+    let mut warning = field_error_message(&overlaps, SettingsField::ExtFilter);
+    if !unmatched.is_empty() {
+        let no_match = app
+            .global::<Strings>()
+            .invoke_no_files_match(unmatched.join(", ").into());
+        if !warning.is_empty() {
+            warning.push_str("; ");
+        }
+        warning.push_str(&no_match);
+    }
+    app.set_ext_filter_warning(warning.into());
+}
+
+/// `target` exists but couldn't be parsed: offer to put its newest valid backup back.
+/// Returns whether a backup was restored.
+fn offer_backup_restore(app: &AppWindow, project_root: &Path, target: &Path) -> bool {
+    let backups = list_backups(project_root, target);
+    if !target.is_file() || backups.is_empty() {
+        return false;
+    }
+    let strings = app.global::<Strings>();
+    let name = target.file_name().unwrap_or_default().to_string_lossy();
+    let wants_restore = rfd::MessageDialog::new()
+        .set_level(rfd::MessageLevel::Warning)
+        .set_title(strings.get_settings_damaged().as_str())
+        .set_description(strings.invoke_restore_backup_question(name.as_ref().into()))
+        .set_buttons(rfd::MessageButtons::YesNo)
+        .show()
+        == rfd::MessageDialogResult::Yes;
+    if !wants_restore {
+        return false;
+    }
+    let keep = load_local_settings(project_root)
+        .unwrap_or_default()
+        .backup_count()
+        .max(1);
+    match restore_latest_backup(project_root, target, keep) {
+        Ok(Some(_)) => true,
+        Ok(None) => {
+            rfd::MessageDialog::new()
+                .set_level(rfd::MessageLevel::Warning)
+                .set_title(strings.get_restore_failed().as_str())
+                .set_description(strings.invoke_no_valid_backup(name.as_ref().into()))
+                .show();
+            false
+        }
+        Err(e) => {
+            rfd::MessageDialog::new()
+                .set_level(rfd::MessageLevel::Warning)
+                .set_title(strings.get_restore_failed().as_str())
+                .set_description(e.to_string())
+                .show();
+            false
+        }
+    }
+}
+
+fn refresh_exclude_suggestions(app: &AppWindow, state: &SharedState) {
+    let labels: Vec<slint::SharedString> = {
+        let mut s = state.borrow_mut();
+        let mut suggestions = match s.root_node.as_ref() {
+            Some(root) if !s.exclude_suggestions_dismissed => suggest_excludes(root),
+            _ => Vec::new(),
+        };
+        // Include filters win over extension excludes, so those would do nothing.
+        if !s.include_exts.is_empty() {
+            suggestions.retain(|sg| sg.target != ExcludeTarget::Extension);
+        }
+        let labels = suggestions.iter().map(|sg| sg.label().into()).collect();
+        s.exclude_suggestions = suggestions;
+        labels
+    };
+    app.set_exclude_suggestions(ModelRc::new(VecModel::from(labels)));
+}
+
+fn refresh_validation_errors(app: &AppWindow, state: &SharedState) {
+    let errors = validate_workspace_settings(&workspace_settings_from_ui(app, state));
+    let msg = |field| slint::SharedString::from(field_error_message(&errors, field));
+    app.set_ext_filter_error(msg(SettingsField::ExtFilter));
+    app.set_exclude_dirs_error(msg(SettingsField::ExcludeDirs));
+    app.set_exclude_files_error(msg(SettingsField::ExcludeFiles));
+    app.set_remove_regex_error(msg(SettingsField::RemoveRegex));
+    app.set_path_strip_prefix_error(msg(SettingsField::PathStripPrefix));
+    app.set_path_prepend_error(msg(SettingsField::PathPrepend));
+    app.set_tokenizer_error(msg(SettingsField::Tokenizer));
+
+    let overlaps = find_filter_overlaps(&workspace_settings_from_ui(app, state));
+    let warning = |field| slint::SharedString::from(field_error_message(&overlaps, field));
+    app.set_exclude_dirs_warning(warning(SettingsField::ExcludeDirs));
+    app.set_exclude_files_warning(warning(SettingsField::ExcludeFiles));
+}
+
+/// Drop repeated filter entries from the fields before they're stored (see
+/// [`normalize_filter_settings`]).
+fn normalize_filter_fields(app: &AppWindow, state: &SharedState) {
+    let mut ws = workspace_settings_from_ui(app, state);
+    if normalize_filter_settings(&mut ws) {
+        app.set_ext_filter(ws.ext_filter.into());
+        app.set_exclude_dirs(ws.exclude_dirs.into());
+        app.set_exclude_files(ws.exclude_files.into());
+        parse_filters_from_ui(app, state);
+        refresh_ext_filter_warning(app, state);
+    }
+}
+
 fn parse_filters_from_ui(app: &AppWindow, state: &SharedState) {
     let ext_raw = app.get_ext_filter().to_string();
     let exclude_dirs_raw = app.get_exclude_dirs().to_string();
     let exclude_files_raw = app.get_exclude_files().to_string();
-    let remove_prefix_raw = app.get_remove_prefix().to_string();
-    let remove_regex_raw = app.get_remove_regex().to_string();
 
     let (include_exts, exclude_exts) = parse_extension_filters(&ext_raw);
 
@@ -1039,39 +2457,21 @@ fn parse_filters_from_ui(app: &AppWindow, state: &SharedState) {
 
     exclude_dirs_set.insert(".stitchworkspace".to_string());
 
-    let mut remove_regex_str = {
-        let cleaned = clean_remove_regex(&remove_regex_raw);
-        if cleaned.trim().is_empty() {
-            None
-        } else {
-            Some(cleaned)
-        }
-    };
-
-    let remove_regex = compile_remove_regex_opt(remove_regex_str.as_deref());
-
     {
         let mut st = state.borrow_mut();
         st.include_exts = include_exts;
         st.exclude_exts = exclude_exts;
         st.exclude_dirs = exclude_dirs_set;
         st.exclude_files = exclude_files_set;
-        st.remove_prefixes = split_prefix_list(&remove_prefix_raw);
-        st.remove_regex_str = remove_regex_str.take();
-        st.remove_regex = remove_regex;
-        let remove_inline = app.get_rust_remove_inline_comments();
-        let remove_doc = app.get_rust_remove_doc_comments();
-        st.rust_ui.comment_removal = CommentRemoval::from_flags(remove_inline, remove_doc);
-        st.rust_ui.signatures_filter = if app.get_rust_function_signatures_only() {
-            Some(app.get_rust_signatures_only_filter().to_string())
-        } else {
-            None
+        st.name_matching = NameMatching::from_index(app.get_name_matching_index());
+        st.path_rewrite = PathRewrite {
+            strip_prefix: app.get_path_strip_prefix().to_string(),
+            prepend: app.get_path_prepend().to_string(),
         };
-        // Slint toggles
-        st.slint_ui.remove_line_comments = app.get_slint_remove_line_comments();
-        st.slint_ui.remove_block_comments = app.get_slint_remove_block_comments();
     }
 
+    refresh_validation_errors(app, state);
+
     let Some(dir) = state.borrow().selected_directory.clone() else {
         app.set_save_enabled(false);
         return;
@@ -1094,6 +2494,90 @@ fn parse_filters_from_ui(app: &AppWindow, state: &SharedState) {
     update_save_button_state(app, state);
 }
 
+/// Scan the project folder `dir`; lazily (keeping the directories already loaded in
+/// the current tree) when the project asks for it.
+fn scan_project(s: &AppState, dir: &Path) -> ScanResult {
+    if s.lazy_scan {
+        let loaded = s.root_node.as_ref().map(loaded_dirs).unwrap_or_default();
+        scan_dir_to_node_lazy(dir, &scan_filters(s), &loaded)
+    } else {
+        scan_dir_to_node_with_name_matching(
+            dir,
+            &s.include_exts,
+            &s.exclude_exts,
+            &s.exclude_dirs,
+            &s.exclude_files,
+            &s.dir_rules,
+            s.name_matching,
+        )
+    }
+}
+
+fn scan_filters(s: &AppState) -> ScanFilters<'_, std::hash::RandomState> {
+    ScanFilters {
+        include_exts: &s.include_exts,
+        exclude_exts: &s.exclude_exts,
+        exclude_dirs: &s.exclude_dirs,
+        exclude_files: &s.exclude_files,
+        rules: &s.dir_rules,
+        names: s.name_matching,
+    }
+}
+
+/// Run `load` on the tree of a lazily scanned project, then record what it read in the
+/// scan stats and the path snapshot (so the next rescan doesn't see it as a change).
+fn load_tree_dirs(
+    state: &SharedState,
+    load: impl FnOnce(&AppState, &Path, &mut Node) -> ScanStats,
+) {
+    let mut s = state.borrow_mut();
+    if !s.lazy_scan {
+        return;
+    }
+    let (Some(dir), Some(mut root)) = (s.selected_directory.clone(), s.root_node.take()) else {
+        return;
+    };
+    let stats = load(&s, &dir, &mut root);
+    s.path_snapshot = Some(gather_paths_set(&root));
+    s.scan_stats.absorb(stats);
+    s.root_node = Some(root);
+}
+
+/// Read the children of the unloaded directory at `path`, if it is one.
+fn load_unloaded_dir(state: &SharedState, path: &Path) {
+    let unloaded = {
+        let s = state.borrow();
+        s.root_node
+            .as_ref()
+            .and_then(|root| find_node(root, path))
+            .is_some_and(is_unloaded_dir)
+    };
+    if !unloaded {
+        return;
+    }
+    load_tree_dirs(state, |s, dir, root| {
+        fn rec<'a>(n: &'a mut Node, path: &Path) -> Option<&'a mut Node> {
+            if n.path == path {
+                return Some(n);
+            }
+            n.children
+                .iter_mut()
+                .find(|c| path.starts_with(&c.path))
+                .and_then(|c| rec(c, path))
+        }
+        rec(root, path).map_or_else(ScanStats::default, |node| {
+            load_dir(dir, node, &scan_filters(s), false)
+        })
+    });
+}
+
+/// Read the unloaded directories the selection reaches into, before collecting it.
+fn load_selected_tree_dirs(state: &SharedState) {
+    load_tree_dirs(state, |s, dir, root| {
+        load_selected_dirs(dir, root, &s.explicit_states, &scan_filters(s))
+    });
+}
+
 fn toggle_node_expanded(state: &SharedState, path: &Path) -> bool {
     fn rec(n: &mut Node, target: &Path) -> bool {
         if n.path == target {
@@ -1116,20 +2600,36 @@ fn toggle_node_expanded(state: &SharedState, path: &Path) -> bool {
     false
 }
 
-fn clear_descendant_explicit_states(state: &SharedState, dir: &Path) {
-    let mut to_clear = Vec::new();
-    {
-        let s = state.borrow();
-        for p in s.explicit_states.keys() {
-            if is_ancestor_of(dir, p) && p != dir {
-                to_clear.push(p.clone());
-            }
-        }
-    }
-    if !to_clear.is_empty() {
-        let mut s = state.borrow_mut();
-        for p in to_clear {
-            s.explicit_states.remove(&p);
+/// Badges shown next to tree rows, keyed by absolute path.
+struct RowLabels {
+    samples: HashMap<PathBuf, String>,
+    rules: HashMap<PathBuf, String>,
+    pinned: HashSet<PathBuf>,
+    permission_denied: HashSet<PathBuf>,
+}
+
+impl RowLabels {
+    fn of(s: &AppState, dir: &Path) -> Self {
+        Self {
+            samples: s
+                .dir_samples
+                .iter()
+                .map(|rule| (project_path_from_rel(dir, &rule.path), rule.describe()))
+                .collect(),
+            rules: s
+                .dir_rules
+                .iter()
+                .map(|rule| {
+                    let path = project_path_from_rel(dir, &rule.path);
+                    (path, rule.describe().to_string())
+                })
+                .collect(),
+            pinned: s
+                .pinned_files
+                .iter()
+                .map(|rel| project_path_from_rel(dir, rel))
+                .collect(),
+            permission_denied: s.scan_stats.permission_denied.iter().cloned().collect(),
         }
     }
 }
@@ -1137,12 +2637,14 @@ fn clear_descendant_explicit_states(state: &SharedState, dir: &Path) {
 fn flatten_tree(
     root: &Node,
     explicit: &HashMap<PathBuf, bool>,
+    labels: &RowLabels,
     inherited: Option<bool>,
     level: usize,
 ) -> Vec<Row> {
     fn walk(
         n: &Node,
         explicit: &HashMap<PathBuf, bool>,
+        labels: &RowLabels,
         inherited: Option<bool>,
         level: usize,
         rows: &mut Vec<Row>,
@@ -1161,16 +2663,30 @@ fn flatten_tree(
             expanded: if n.is_dir { n.expanded } else { false },
             checked: effective,
             has_children,
+            sample_label: labels
+                .samples
+                .get(&n.path)
+                .cloned()
+                .unwrap_or_default()
+                .into(),
+            rule_label: labels
+                .rules
+                .get(&n.path)
+                .cloned()
+                .unwrap_or_default()
+                .into(),
+            pinned: labels.pinned.contains(&n.path),
+            permission_denied: labels.permission_denied.contains(&n.path),
         });
         if n.is_dir && n.expanded {
             let next_inherited = effective;
             for c in &n.children {
-                walk(c, explicit, Some(next_inherited), level + 1, rows);
+                walk(c, explicit, labels, Some(next_inherited), level + 1, rows);
             }
         }
     }
     let mut rows = Vec::new();
-    walk(root, explicit, inherited, level, &mut rows);
+    walk(root, explicit, labels, inherited, level, &mut rows);
     rows
 }
 
@@ -1183,20 +2699,82 @@ fn get_row_by_index(app: &AppWindow, index: usize) -> Option<Row> {
     model.row_data(index)
 }
 
+/// Show `rows` in the tree, patching the current model where it is a [`TreeRows`].
 fn set_tree_model(app: &AppWindow, rows: Vec<Row>) {
-    let model = VecModel::from(rows);
-    app.set_tree_model(ModelRc::new(model));
+    let model = app.get_tree_model();
+    if let Some(tree) = model.as_any().downcast_ref::<TreeRows>() {
+        tree.update(rows);
+    } else {
+        app.set_tree_model(ModelRc::new(TreeRows::new(rows)));
+    }
+}
+
+/// Run `f` on the tree model; `None` before the first [`set_tree_model`].
+fn with_tree_rows<R>(app: &AppWindow, f: impl FnOnce(&TreeRows) -> R) -> Option<R> {
+    let model = app.get_tree_model();
+    model.as_any().downcast_ref::<TreeRows>().map(f)
+}
+
+fn find_node<'a>(node: &'a Node, path: &Path) -> Option<&'a Node> {
+    if node.path == path {
+        return Some(node);
+    }
+    node.children
+        .iter()
+        .find(|c| path.starts_with(&c.path))
+        .and_then(|c| find_node(c, path))
+}
+
+/// Replace the rows below the directory row at `index` with its children's rows (none
+/// when it is collapsed), and refresh the row itself.
+fn patch_expanded_rows(app: &AppWindow, state: &SharedState, index: usize) -> bool {
+    let Some(row) = get_row_by_index(app, index) else {
+        return false;
+    };
+    let (expanded, children) = {
+        let s = state.borrow();
+        let (Some(root), Some(dir)) = (&s.root_node, &s.selected_directory) else {
+            return false;
+        };
+        let Some(node) = find_node(root, Path::new(row.path.as_str())) else {
+            return false;
+        };
+        let labels = RowLabels::of(&s, dir);
+        let level = usize::try_from(row.level).unwrap_or(0);
+        let mut rows = Vec::new();
+        if node.expanded {
+            for child in &node.children {
+                rows.extend(flatten_tree(
+                    child,
+                    &s.explicit_states,
+                    &labels,
+                    Some(row.checked),
+                    level + 1,
+                ));
+            }
+        }
+        (node.expanded, rows)
+    };
+    with_tree_rows(app, |tree| {
+        tree.modify(index..index + 1, |r| r.expanded = expanded);
+        tree.splice(index + 1..tree.subtree_end(index), children);
+    })
+    .is_some()
 }
 
-fn set_output(app: &AppWindow, state: &SharedState, s: &str) {
+fn set_output(app: &AppWindow, state: &SharedState, s: &str, kind: OutputKind) {
     let normalized = collapse_consecutive_blank_lines(s);
     {
         let mut st = state.borrow_mut();
         st.full_output_text.clone_from(&normalized);
+        st.output_kind = kind;
     }
 
-    // Check if this is a placeholder message that shouldn't count towards stats
-    let is_placeholder = is_placeholder_message(&normalized);
+    // Messages in place of output don't count towards stats
+    let is_placeholder = kind == OutputKind::Message;
+    if is_placeholder {
+        app.set_selection_breakdown("".into());
+    }
 
     let total_chars = if is_placeholder {
         0
@@ -1209,82 +2787,299 @@ fn set_output(app: &AppWindow, state: &SharedState, s: &str) {
         normalized.lines().count()
     };
 
-    #[cfg(feature = "tokens")]
-    {
+    // Without the `tokens` feature the builtin encodings aren't available; estimate quietly.
+    let tokenizer = TokenizerSpec::parse(&app.get_tokenizer())
+        .ok()
+        .filter(|spec| cfg!(feature = "tokens") || matches!(spec, TokenizerSpec::HuggingFace(_)));
+    let tokenizer = tokenizer
+        .filter(|_| !app.get_approximate_tokens() && normalized.len() <= MAX_TOKENIZE_BYTES);
+
+    let project_root = state
+        .borrow()
+        .selected_directory
+        .clone()
+        .unwrap_or_default();
+    let prices = load_price_table(&project_root).unwrap_or_default();
+    let context_window = ContextWindow::from_index(app.get_context_window_index());
+
+    let markers = output_file_markers(app, state);
+    let Some(spec) = tokenizer else {
+        let (tokens, cost) = if is_placeholder {
+            (0, String::new())
+        } else {
+            let tokens = estimate_tokens_with_markers(&normalized, &markers);
+            (tokens, cost_suffix(tokens, &prices, "~"))
+        };
         app.set_output_stats(
-            format!(
-                "{total_chars} chars • {} tokens • {total_lines} LOC",
-                stitch::core::GLYPH_ELLIPSIS
-            )
-            .into(),
+            format!("{total_chars} chars • ~{tokens} tokens{cost} • {total_lines} LOC").into(),
         );
-        let text = normalized.clone();
-        let app_weak = app.as_weak();
+        app.set_output_over_budget(context_window.is_exceeded_by(tokens));
+        set_output_text(app, state);
+        return;
+    };
 
-        if text.len() <= MAX_TOKENIZE_BYTES {
-            std::thread::spawn(move || {
-                let is_placeholder = is_placeholder_message(&text);
-                let tokens = if is_placeholder {
-                    0
-                } else {
-                    count_tokens(&text)
-                };
-                let chars = if is_placeholder {
-                    0
-                } else {
-                    text.chars().count()
-                };
-                let lines = if is_placeholder || text.is_empty() {
-                    0
-                } else {
-                    text.lines().count()
-                };
-                let label = format!("{chars} chars • {tokens} tokens • {lines} LOC");
-                let _ = slint::invoke_from_event_loop(move || {
-                    if let Some(app) = app_weak.upgrade() {
-                        app.set_output_stats(label.into());
-                    }
-                });
-            });
+    app.set_output_stats(
+        format!(
+            "{total_chars} chars • {} tokens • {total_lines} LOC",
+            stitch::core::GLYPH_ELLIPSIS
+        )
+        .into(),
+    );
+    let text = normalized.clone();
+    let app_weak = app.as_weak();
+    let token_cache = std::sync::Arc::clone(&state.borrow().token_cache);
+
+    std::thread::spawn(move || {
+        let (label, tokens) = if is_placeholder {
+            ("0 chars • 0 tokens • 0 LOC".to_string(), 0)
         } else {
-            app.set_output_stats(
-                format!("{total_chars} chars • (token count skipped for large output) • {total_lines} LOC").into(),
-            );
-        }
+            let chars = text.chars().count();
+            let lines = text.lines().count();
+            match load_tokenizer(&spec, &project_root) {
+                Ok((key, counter)) => {
+                    // Only files whose text changed since the last count get re-tokenized.
+                    let tokens = token_cache.lock().map_or_else(
+                        |_| counter.count(&text),
+                        |mut cache| {
+                            cache.set_tokenizer(&key);
+                            cache.set_markers(&markers);
+                            cache.count(&text, |s| counter.count(s))
+                        },
+                    );
+                    let model = if spec == TokenizerSpec::Builtin(DEFAULT_TOKENIZER) {
+                        String::new()
+                    } else {
+                        format!(" ({})", spec.label())
+                    };
+                    let cost = cost_suffix(tokens, &prices, "");
+                    (
+                        format!("{chars} chars • {tokens} tokens{model}{cost} • {lines} LOC"),
+                        tokens,
+                    )
+                }
+                Err(e) => {
+                    let tokens = estimate_tokens_with_markers(&text, &markers);
+                    let cost = cost_suffix(tokens, &prices, "~");
+                    (
+                        format!("{chars} chars • ~{tokens} tokens ({e}){cost} • {lines} LOC"),
+                        tokens,
+                    )
+                }
+            }
+        };
+        let _ = slint::invoke_from_event_loop(move || {
+            // The user may have switched to the estimate while this was counting.
+            if let Some(app) = app_weak.upgrade()
+                && !app.get_approximate_tokens()
+            {
+                app.set_output_stats(label.into());
+                app.set_output_over_budget(context_window.is_exceeded_by(tokens));
+            }
+        });
+    });
+
+    set_output_text(app, state);
+}
+
+/// ` • $0.43 @ gpt-4o`, or nothing when the selected model has no price.
+fn cost_suffix(tokens: usize, prices: &PriceTable, approx: &str) -> String {
+    format_cost(tokens, prices).map_or_else(String::new, |cost| format!(" • {approx}{cost}"))
+}
+
+/// Split the output just stored in `full_output_text` into pages and show the first.
+fn set_output_text(app: &AppWindow, state: &SharedState) {
+    {
+        let mut s = state.borrow_mut();
+        s.output_pages = preview_pages(&s.full_output_text, s.preview_char_limit);
+    }
+    show_output_page(app, state, 0);
+
+    if state.borrow().output_find.query.is_some() {
+        update_output_find(app, state, false);
     }
+}
+
+fn show_output_page(app: &AppWindow, state: &SharedState, page: usize) {
+    let (text, pages) = {
+        let mut s = state.borrow_mut();
+        let page = page.min(s.output_pages.len().saturating_sub(1));
+        s.output_page = page;
+        let range = s.output_pages.get(page).cloned().unwrap_or_default();
+        (s.full_output_text[range].to_string(), s.output_pages.len())
+    };
+    app.set_output_text(text.into());
+    let label = if pages > 1 {
+        let to_i32 = |n: usize| i32::try_from(n).unwrap_or(i32::MAX);
+        app.global::<Strings>()
+            .invoke_output_page(to_i32(page + 1), to_i32(pages))
+    } else {
+        "".into()
+    };
+    app.set_output_page_label(label);
+}
+
+/// Show the next (or previous) page of a long output.
+pub fn on_output_page_step(app: &AppWindow, state: &SharedState, forward: bool) {
+    let (page, pages) = {
+        let s = state.borrow();
+        (s.output_page, s.output_pages.len())
+    };
+    let next = if forward {
+        (page + 1).min(pages.saturating_sub(1))
+    } else {
+        page.saturating_sub(1)
+    };
+    if next != page {
+        show_output_page(app, state, next);
+    }
+}
+
+/* ------------------------------ Find in output ------------------------------ */
+
+/// Search the full output for `text` and go to the first match.
+pub fn on_find_changed(app: &AppWindow, state: &SharedState, text: &str, case_sensitive: bool) {
+    {
+        let mut s = state.borrow_mut();
+        s.output_find.query = (!text.is_empty()).then(|| (text.to_string(), case_sensitive));
+        s.output_find.current = 0;
+    }
+    update_output_find(app, state, true);
+}
 
-    #[cfg(not(feature = "tokens"))]
+/// Go to the next (or previous) match, wrapping around.
+pub fn on_find_step(app: &AppWindow, state: &SharedState, forward: bool) {
     {
-        let total_tokens = if is_placeholder {
-            0
+        let mut s = state.borrow_mut();
+        let count = s.output_find.matches.len();
+        if count == 0 {
+            return;
+        }
+        let current = s.output_find.current;
+        s.output_find.current = if forward {
+            (current + 1) % count
         } else {
-            count_tokens(&normalized)
+            (current + count - 1) % count
         };
-        app.set_output_stats(
-            format!("{total_chars} chars • {total_tokens} tokens • {total_lines} LOC").into(),
-        );
     }
+    reveal_current_match(app, state);
+}
+
+/// Forget the search.
+pub fn on_find_closed(app: &AppWindow, state: &SharedState) {
+    state.borrow_mut().output_find = OutputFind::default();
+    app.set_find_status("".into());
+}
 
-    let displayed: String = if total_chars <= UI_OUTPUT_CHAR_LIMIT {
-        normalized
+/// Recompute the matches of the current query; `reveal` moves to the current one.
+fn update_output_find(app: &AppWindow, state: &SharedState, reveal: bool) {
+    let result = {
+        let s = state.borrow();
+        s.output_find.query.as_ref().map(|(text, case_sensitive)| {
+            ContentQuery::new(text, false, *case_sensitive)
+                .map(|q| q.find_ranges(&s.full_output_text))
+                .unwrap_or_default()
+        })
+    };
+    let matches = result.unwrap_or_default();
+    let count = matches.len();
+    {
+        let mut s = state.borrow_mut();
+        s.output_find.matches = matches;
+        if s.output_find.current >= count {
+            s.output_find.current = 0;
+        }
+    }
+    if count == 0 {
+        let empty = state.borrow().output_find.query.is_none();
+        let status = if empty {
+            "".into()
+        } else {
+            app.global::<Strings>().get_no_matches()
+        };
+        app.set_find_status(status);
+        return;
+    }
+    if reveal {
+        reveal_current_match(app, state);
     } else {
-        let footer = format!(
-            "\n… [truncated: showing {UI_OUTPUT_CHAR_LIMIT} of {total_chars} chars — use “Copy Output” to copy all]\n"
-        );
-        let keep = UI_OUTPUT_CHAR_LIMIT.saturating_sub(footer.chars().count());
-        let mut head: String = normalized.chars().take(keep).collect();
-        head.push_str(&footer);
-        head
+        let current = state.borrow().output_find.current;
+        set_find_status(app, current, count);
+    }
+}
+
+fn set_find_status(app: &AppWindow, current: usize, count: usize) {
+    let to_i32 = |n: usize| i32::try_from(n).unwrap_or(i32::MAX);
+    let status = app
+        .global::<Strings>()
+        .invoke_match_position(to_i32(current + 1), to_i32(count));
+    app.set_find_status(status);
+}
+
+/// Select the current match in the output pane.
+fn reveal_current_match(app: &AppWindow, state: &SharedState) {
+    let (range, current, count) = {
+        let s = state.borrow();
+        let f = &s.output_find;
+        let Some(range) = f.matches.get(f.current).cloned() else {
+            return;
+        };
+        (range, f.current, f.matches.len())
     };
+    set_find_status(app, current, count);
+    reveal_output_range(app, state, range);
+}
+
+/// Select bytes `range` of the full output, turning to the page that holds them.
+fn reveal_output_range(app: &AppWindow, state: &SharedState, range: std::ops::Range<usize>) {
+    let (page, shown) = {
+        let s = state.borrow();
+        (s.output_page, page_of(&s.output_pages, range.start))
+    };
+    if shown != page {
+        show_output_page(app, state, shown);
+    }
+    let page_range = state
+        .borrow()
+        .output_pages
+        .get(shown)
+        .cloned()
+        .unwrap_or_default();
+    let displayed = app.get_output_text().to_string();
+    let start = range.start - page_range.start;
+    let end = range.end.min(page_range.end) - page_range.start;
+
+    let line = displayed[..start].matches('\n').count();
+    let lines = displayed.matches('\n').count() + 1;
+    let to_i32 = |n: usize| i32::try_from(n).unwrap_or(i32::MAX);
+    app.invoke_reveal_output(to_i32(start), to_i32(end), to_i32(line), to_i32(lines));
+}
+
+fn truncation_footer(limit: usize, total_chars: usize, copy_button: &str) -> String {
+    format!(
+        "\n… [truncated: showing {limit} of {total_chars} chars — use “{copy_button}” to copy all]\n"
+    )
+}
 
-    // Simple debug: show hex bytes of a representative line just before display
-    #[allow(clippy::print_stdout)]
-    app.set_output_text(displayed.into());
+/// Cut `text` to `limit` chars (`0` = no limit) with a footer pointing at `copy_button`.
+fn truncate_for_ui(text: String, total_chars: usize, limit: usize, copy_button: &str) -> String {
+    if limit == 0 || total_chars <= limit {
+        return text;
+    }
+    let footer = truncation_footer(limit, total_chars, copy_button);
+    let keep = limit.saturating_sub(footer.chars().count());
+    let mut head: String = text.chars().take(keep).collect();
+    head.push_str(&footer);
+    head
 }
 
 fn update_last_refresh(app: &AppWindow) {
-    let now_str = Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
-    app.set_last_refresh(format!("Last refresh: {now_str}").into());
+    let strings = app.global::<Strings>();
+    let now_str = if app.get_deterministic() {
+        strings.get_not_available()
+    } else {
+        Local::now().format("%Y-%m-%d %H:%M:%S").to_string().into()
+    };
+    app.set_last_refresh(strings.invoke_last_refresh(now_str));
 }
 
 fn split_csv_set(s: &slint::SharedString) -> std::collections::HashSet<String> {
@@ -1294,50 +3089,198 @@ fn split_csv_set(s: &slint::SharedString) -> std::collections::HashSet<String> {
         .collect()
 }
 
-pub fn on_check_updates(app: &AppWindow, state: &SharedState) {
-    let should_scan = {
-        let s = state.borrow();
-        s.selected_directory.is_some() && s.root_node.is_some() && s.fs.dirty
-    };
-    if !should_scan {
-        return;
-    }
+pub fn on_check_updates(app: &AppWindow, state: &SharedState) {
+    if revalidate_project_root(app, state) {
+        return;
+    }
+
+    let should_scan = {
+        let s = state.borrow();
+        s.selected_directory.is_some() && s.root_node.is_some() && s.fs.dirty
+    };
+    if !should_scan {
+        return;
+    }
+    {
+        state.borrow_mut().fs.dirty = false;
+    }
+
+    let (changed, new_scan, new_snapshot) = {
+        let s = state.borrow();
+        let dir = s.selected_directory.as_ref().unwrap().clone();
+
+        let scan = scan_project(&s, &dir);
+        let fresh_snapshot = gather_paths_set(&scan.node);
+        let changed = s
+            .path_snapshot
+            .as_ref()
+            .is_none_or(|old| *old != fresh_snapshot);
+        (changed, scan, fresh_snapshot)
+    };
+    {
+        let mut s = state.borrow_mut();
+        let ignore = s.selected_directory.as_deref().map(IgnoreRules::load);
+        s.stitchignore = ignore.unwrap_or_default();
+    }
+
+    if changed {
+        {
+            let mut s = state.borrow_mut();
+            s.root_node = Some(new_scan.node.clone());
+            s.path_snapshot = Some(new_snapshot);
+            s.scan_stats = new_scan.stats;
+        }
+        refresh_flat_model(app, state);
+        refresh_ext_filter_warning(app, state);
+        refresh_exclude_suggestions(app, state);
+        return;
+    }
+
+    let want_dirs_only = app.get_dirs_only();
+    if !want_dirs_only {
+        on_generate_output(app, state);
+    }
+}
+
+/* ============================== Moved projects ============================== */
+
+/// Check that the open project's folder still exists. If it was moved or renamed, offer to
+/// locate it (carrying the selection over) or close the project.
+///
+/// Returns `true` when the root was missing and the caller should stop.
+fn revalidate_project_root(app: &AppWindow, state: &SharedState) -> bool {
+    let old_root = {
+        let s = state.borrow();
+        if s.relocate_prompt_open {
+            return true;
+        }
+        match &s.selected_directory {
+            Some(dir) if !dir.is_dir() => dir.clone(),
+            _ => return false,
+        }
+    };
+
+    state.borrow_mut().relocate_prompt_open = true;
+    let strings = app.global::<Strings>();
+    let wants_relocate = rfd::MessageDialog::new()
+        .set_level(rfd::MessageLevel::Warning)
+        .set_title(strings.get_project_not_found().as_str())
+        .set_description(strings.invoke_locate_project(display_path(&old_root).into()))
+        .set_buttons(rfd::MessageButtons::YesNo)
+        .show()
+        == rfd::MessageDialogResult::Yes;
+    let new_root = if wants_relocate {
+        let start = old_root
+            .ancestors()
+            .find(|p| p.is_dir())
+            .map_or_else(|| PathBuf::from("."), Path::to_path_buf);
+        rfd::FileDialog::new().set_directory(start).pick_folder()
+    } else {
+        None
+    };
+    state.borrow_mut().relocate_prompt_open = false;
+
+    match new_root {
+        Some(new_root) => relocate_project(app, state, &old_root, &new_root),
+        None => close_project(app, state),
+    }
+    true
+}
+
+/// Point the open project at `new_root`, remapping absolute-path caches from `old_root`.
+fn relocate_project(app: &AppWindow, state: &SharedState, old_root: &Path, new_root: &Path) {
+    {
+        let mut s = state.borrow_mut();
+        s.explicit_states =
+            rebase_path_keys(std::mem::take(&mut s.explicit_states), old_root, new_root);
+        s.last_mod_times =
+            rebase_path_keys(std::mem::take(&mut s.last_mod_times), old_root, new_root);
+        s.path_snapshot = None;
+        s.selected_directory = Some(new_root.to_path_buf());
+        s.fs.dirty = false;
+    }
+
+    app.set_project_path(display_path(new_root).into());
+    let _ = ensure_workspace_dir(new_root);
+    state.borrow_mut().profiles = list_profiles(new_root);
+    refresh_profiles_ui(app, state);
+
+    if !state.borrow().fs.watcher_disabled {
+        let _ = start_fs_watcher(app, state);
+    }
+    rebuild_tree_and_ui(app, state);
+    on_generate_output(app, state);
+    update_last_refresh(app);
+}
+
+/// Drop the open project, returning the window to its "no folder selected" state.
+fn close_project(app: &AppWindow, state: &SharedState) {
     {
-        state.borrow_mut().fs.dirty = false;
+        let mut s = state.borrow_mut();
+        s.selected_directory = None;
+        s.root_node = None;
+        s.path_snapshot = None;
+        s.explicit_states.clear();
+        s.last_mod_times.clear();
+        s.watcher = None;
+        s.fs_event_rx = None;
+        s.fs_pump_timer.stop();
+        s.fs.dirty = false;
+        s.profiles.clear();
+        s.profile_baseline = None;
+        s.workspace_baseline = None;
     }
 
-    let (changed, new_scan, new_snapshot) = {
-        let s = state.borrow();
-        let include = s.include_exts.clone();
-        let exclude = s.exclude_exts.clone();
-        let ex_dirs = s.exclude_dirs.clone();
-        let ex_files = s.exclude_files.clone();
-        let dir = s.selected_directory.as_ref().unwrap().clone();
-
-        let scan = scan_dir_to_node_with_stats(&dir, &include, &exclude, &ex_dirs, &ex_files);
-        let fresh_snapshot = gather_paths_set(&scan.node);
-        let changed = s
-            .path_snapshot
-            .as_ref()
-            .is_none_or(|old| *old != fresh_snapshot);
-        (changed, scan, fresh_snapshot)
-    };
+    app.set_project_path("".into());
+    refresh_profiles_ui(app, state);
+    set_tree_model(app, Vec::new());
+    set_output(app, state, NO_FOLDER_SELECTED, OutputKind::Message);
+    update_last_refresh(app);
+}
 
-    if changed {
-        {
-            let mut s = state.borrow_mut();
-            s.root_node = Some(new_scan.node.clone());
-            s.path_snapshot = Some(new_snapshot);
-            s.existing_excluded_dirs = new_scan.stats.excluded_dirs_found;
-            s.existing_excluded_files = new_scan.stats.excluded_files_found;
+/// Watcher relevance of `path` under the current filters and per-directory overrides.
+fn is_watched_path_relevant(s: &AppState, project_root: &Path, path: &Path) -> bool {
+    let rule = path
+        .strip_prefix(project_root)
+        .ok()
+        .and_then(|rel| dir_rule_for(&s.dir_rules, &path_to_unix(rel)));
+    if let Ok(rel) = path.strip_prefix(project_root)
+        && s.pinned_files.contains(&path_to_unix(rel))
+    {
+        return true;
+    }
+    if let Ok(rel) = path.strip_prefix(project_root) {
+        let rel = path_to_unix(rel);
+        if rel == STITCHIGNORE_FILE {
+            return true;
+        }
+        if s.stitchignore.is_ignored(&rel, path.is_dir()) {
+            return false;
         }
-        refresh_flat_model(app, state);
-        return;
     }
-
-    let want_dirs_only = app.get_dirs_only();
-    if !want_dirs_only {
-        on_generate_output(app, state);
+    match rule {
+        Some(DirRuleKind::Exclude) => false,
+        Some(DirRuleKind::IgnoreExtensionFilters) => {
+            let any_ext = HashSet::new();
+            is_event_path_relevant_with_name_matching(
+                project_root,
+                path,
+                &any_ext,
+                &any_ext,
+                &s.exclude_dirs,
+                &s.exclude_files,
+                s.name_matching,
+            )
+        }
+        None => is_event_path_relevant_with_name_matching(
+            project_root,
+            path,
+            &s.include_exts,
+            &s.exclude_exts,
+            &s.exclude_dirs,
+            &s.exclude_files,
+            s.name_matching,
+        ),
     }
 }
 
@@ -1358,11 +3301,19 @@ fn start_fs_watcher(app: &AppWindow, state: &SharedState) -> notify::Result<()>
 
     let (tx, rx) = mpsc::channel();
     let mut watcher: RecommendedWatcher = notify::recommended_watcher(tx)?;
-    watcher.watch(&root, RecursiveMode::Recursive)?;
+    let targets = watch_targets(&state.borrow(), &root);
+    for target in &targets {
+        let watched = watch_target(&mut watcher, target);
+        // Other folders (a selected one, the git folder) may have gone away meanwhile.
+        if target.path == root {
+            watched?;
+        }
+    }
 
     {
         let mut s = state.borrow_mut();
         s.watcher = Some(watcher);
+        s.fs.watch_targets = targets;
         s.fs_event_rx = Some(rx);
 
         let app_weak = app.as_weak();
@@ -1373,8 +3324,9 @@ fn start_fs_watcher(app: &AppWindow, state: &SharedState) -> notify::Result<()>
             std::time::Duration::from_millis(250),
             move || {
                 if let Some(app) = app_weak.upgrade() {
-                    let any_relevant = {
-                        let s = state_rc.borrow();
+                    let (any_relevant, renames, head_changed) = {
+                        let mut guard = state_rc.borrow_mut();
+                        let s = &mut *guard;
                         let Some(rx_ref) = s.fs_event_rx.as_ref() else {
                             return;
                         };
@@ -1384,33 +3336,35 @@ fn start_fs_watcher(app: &AppWindow, state: &SharedState) -> notify::Result<()>
                             Some(p) => p.clone(),
                             None => return,
                         };
-                        let include_exts = s.include_exts.clone();
-                        let exclude_exts = s.exclude_exts.clone();
-                        let exclude_dirs = s.exclude_dirs.clone();
-                        let exclude_files = s.exclude_files.clone();
 
-                        // Drain and check relevance with the shared helper
+                        // Drain, pair renames and check relevance with the shared helper
                         let mut relevant = false;
+                        let mut renames = Vec::new();
+                        let mut head_changed = false;
                         while let Ok(ev_res) = rx_ref.try_recv() {
                             if let Ok(ev) = ev_res {
+                                renames.extend(s.fs.renames.observe(&ev));
+                                head_changed |=
+                                    s.fs.git_head
+                                        .as_ref()
+                                        .is_some_and(|head| ev.paths.contains(head));
                                 for p in ev.paths {
-                                    if stitch::core::is_event_path_relevant(
-                                        &project_root,
-                                        &p,
-                                        &include_exts,
-                                        &exclude_exts,
-                                        &exclude_dirs,
-                                        &exclude_files,
-                                    ) {
+                                    if is_watched_path_relevant(s, &project_root, &p) {
                                         relevant = true;
                                         break;
                                     }
                                 }
                             }
                         }
-                        relevant
+                        (relevant, renames, head_changed)
                     };
 
+                    if head_changed {
+                        on_git_head_changed(&app, &state_rc);
+                    }
+                    if !renames.is_empty() {
+                        offer_profile_remap(&app, &state_rc, renames);
+                    }
                     if any_relevant {
                         state_rc.borrow_mut().fs.dirty = true;
                         on_check_updates(&app, &state_rc);
@@ -1423,31 +3377,175 @@ fn start_fs_watcher(app: &AppWindow, state: &SharedState) -> notify::Result<()>
     Ok(())
 }
 
-/* ============================ Placeholder detection ============================ */
+/// What the watcher should cover: the whole project, or with `watch_selection_only` the
+/// selected subtrees; plus the folder of git `HEAD` (which may sit above the project) to
+/// notice checkouts.
+fn watch_targets(s: &AppState, root: &Path) -> Vec<WatchTarget> {
+    let mut targets = if s.fs.watch_selection_only {
+        let (mut files, mut dirs) = (Vec::new(), Vec::new());
+        if let Some(node) = s.root_node.as_ref() {
+            collect_selected_paths(node, &s.explicit_states, None, &mut files, &mut dirs);
+        }
+        files.extend(
+            s.pinned_files
+                .iter()
+                .map(|rel| project_path_from_rel(root, rel)),
+        );
+        selection_watch_targets(root, &files, &dirs)
+    } else {
+        vec![WatchTarget::recursive(root)]
+    };
+    if let Some(dir) = s.fs.git_head.as_ref().and_then(|h| h.parent()) {
+        targets.push(WatchTarget::flat(dir));
+    }
+    minimize_watch_targets(targets)
+}
 
-// Constants for placeholder messages that shouldn't count towards statistics
-const NO_FOLDER_SELECTED: &str = "No folder selected.\n";
-const NO_ITEMS_SELECTED: &str = "No items selected.\n";
+fn watch_target(watcher: &mut RecommendedWatcher, target: &WatchTarget) -> notify::Result<()> {
+    let mode = if target.recursive {
+        RecursiveMode::Recursive
+    } else {
+        RecursiveMode::NonRecursive
+    };
+    watcher.watch(&target.path, mode)
+}
 
-/// Determines if the given text is a placeholder message that shouldn't count towards statistics
-fn is_placeholder_message(text: &str) -> bool {
-    text == NO_FOLDER_SELECTED || text == NO_ITEMS_SELECTED
+/// Re-target a watcher limited to the selection after the selection changed.
+fn refresh_watch_scope(state: &SharedState) {
+    let mut guard = state.borrow_mut();
+    let s = &mut *guard;
+    if !s.fs.watch_selection_only || s.watcher.is_none() {
+        return;
+    }
+    let Some(root) = s.selected_directory.clone() else {
+        return;
+    };
+    let targets = watch_targets(s, &root);
+    let Some(watcher) = s.watcher.as_mut() else {
+        return;
+    };
+    if targets == s.fs.watch_targets {
+        return;
+    }
+    for old in s.fs.watch_targets.iter().filter(|t| !targets.contains(t)) {
+        let _ = watcher.unwatch(&old.path);
+    }
+    for new in targets.iter().filter(|t| !s.fs.watch_targets.contains(t)) {
+        let _ = watch_target(watcher, new);
+    }
+    s.fs.watch_targets = targets;
 }
 
-/* ============================ Token counting ============================ */
+/// After files or folders were renamed, offer to point the selections, samples, rules
+/// and pins that used the old paths at the new ones, instead of losing them.
+fn offer_profile_remap(app: &AppWindow, state: &SharedState, renames: Vec<PathRename>) {
+    let (root, pending) = {
+        let mut s = state.borrow_mut();
+        s.fs.pending_renames.extend(renames);
+        if s.rename_prompt_open || s.fs.pending_renames.is_empty() {
+            return;
+        }
+        let Some(root) = s.selected_directory.clone() else {
+            s.fs.pending_renames.clear();
+            return;
+        };
+        (root, std::mem::take(&mut s.fs.pending_renames))
+    };
+    let Some(mut profile) = capture_profile_from_ui(app, state, "") else {
+        return;
+    };
+    let mut changed = 0;
+    let mut listed = Vec::new();
+    for (from, to) in pending.iter().filter_map(|r| r.relative_to(&root)) {
+        let n = remap_profile_paths(&mut profile, &from, &to);
+        if n > 0 {
+            changed += n;
+            listed.push(format!("  {from} → {to}"));
+        }
+    }
+    if changed == 0 {
+        return;
+    }
+
+    state.borrow_mut().rename_prompt_open = true;
+    let confirmed = rfd::MessageDialog::new()
+        .set_level(rfd::MessageLevel::Info)
+        .set_title(app.global::<Strings>().get_files_renamed().as_str())
+        .set_description(
+            app.global::<Strings>()
+                .invoke_update_renamed_entries(
+                    listed.join("\n").into(),
+                    i32::try_from(changed).unwrap_or(i32::MAX),
+                )
+                .as_str(),
+        )
+        .set_buttons(rfd::MessageButtons::YesNo)
+        .show()
+        == rfd::MessageDialogResult::Yes;
+    state.borrow_mut().rename_prompt_open = false;
+
+    if confirmed {
+        restore_explicit_selections(state, &profile.explicit);
+        {
+            let mut s = state.borrow_mut();
+            s.dir_samples = profile.samples;
+            s.dir_rules = profile.dir_rules;
+            s.pinned_files = profile.pinned;
+        }
+        refresh_flat_model(app, state);
+        update_save_button_state(app, state);
+    }
+    // Renames that arrived while the prompt was open.
+    offer_profile_remap(app, state, Vec::new());
+}
 
-#[cfg(all(feature = "ui", feature = "tokens"))]
-fn count_tokens(text: &str) -> usize {
-    use std::sync::OnceLock;
-    use tiktoken_rs::{CoreBPE, o200k_base};
-    static BPE: OnceLock<CoreBPE> = OnceLock::new();
-    let bpe = BPE.get_or_init(|| o200k_base().expect("failed to load o200k_base BPE"));
-    bpe.encode_with_special_tokens(text).len()
+/// Existing profile bound to `branch` in the local settings.
+fn branch_profile(
+    state: &SharedState,
+    local_settings: Option<&LocalSettings>,
+    branch: Option<&str>,
+) -> Option<String> {
+    let name = profile_for_branch(&local_settings?.profile_branches, branch?)?;
+    let s = state.borrow();
+    s.profiles
+        .iter()
+        .any(|p| p.name == name)
+        .then(|| name.to_string())
 }
 
-#[cfg(all(feature = "ui", not(feature = "tokens")))]
-fn count_tokens(text: &str) -> usize {
-    text.split_whitespace().filter(|s| !s.is_empty()).count()
+/// After a checkout, switch to the profile bound to the new branch. Unsaved edits are
+/// never thrown away for it: the switch is skipped with a toast instead.
+fn on_git_head_changed(app: &AppWindow, state: &SharedState) {
+    let Some(root) = state.borrow().selected_directory.clone() else {
+        return;
+    };
+    let branch = current_git_branch(&root);
+    {
+        let mut s = state.borrow_mut();
+        if s.fs.git_branch == branch {
+            return;
+        }
+        s.fs.git_branch.clone_from(&branch);
+    }
+    let Some(name) = branch_profile(
+        state,
+        load_local_settings(&root).as_ref(),
+        branch.as_deref(),
+    ) else {
+        return;
+    };
+    if app.get_profile_name() == name.as_str() {
+        return;
+    }
+    let strings = app.global::<Strings>();
+    let toast = if app.get_save_enabled() {
+        strings.invoke_branch_profile_skipped(name.into())
+    } else if select_profile_by_name(app, state, &name) {
+        strings.invoke_branch_profile_selected(name.into())
+    } else {
+        return;
+    };
+    show_copy_toast(app, state, toast, 2500);
 }
 
 fn refresh_profiles_ui(app: &AppWindow, state: &SharedState) {
@@ -1518,95 +3616,75 @@ fn refresh_profiles_ui(app: &AppWindow, state: &SharedState) {
 fn capture_profile_from_ui(app: &AppWindow, state: &SharedState, name: &str) -> Option<Profile> {
     let dir = { state.borrow().selected_directory.clone()? };
 
-    let ws = WorkspaceSettings {
-        version: 1,
-        ext_filter: app.get_ext_filter().to_string(),
-        exclude_dirs: app.get_exclude_dirs().to_string(),
-        exclude_files: app.get_exclude_files().to_string(),
-        remove_prefix: app.get_remove_prefix().to_string(),
-        remove_regex: app.get_remove_regex().to_string(),
-        hierarchy_only: app.get_hierarchy_only(),
-        dirs_only: app.get_dirs_only(),
-        rust: RustOptions {
-            rust_remove_inline_comments: app.get_rust_remove_inline_comments(),
-            rust_remove_doc_comments: app.get_rust_remove_doc_comments(),
-            rust_function_signatures_only: app.get_rust_function_signatures_only(),
-            rust_signatures_only_filter: app.get_rust_signatures_only_filter().to_string(),
-        },
-        slint: SlintOptions {
-            slint_remove_line_comments: app.get_slint_remove_line_comments(),
-            slint_remove_block_comments: app.get_slint_remove_block_comments(),
-        },
-    };
+    let ws = workspace_settings_from_ui(app, state);
+    let explicit = explicit_selections(state, &dir);
 
-    // NOTE: Preserve root selection by storing an empty relative path ("")
-    // when the explicit key equals the project root.
-    let explicit = {
-        let s = state.borrow();
-        s.explicit_states
-            .iter()
-            .filter_map(|(abs, &st)| {
-                abs.strip_prefix(&dir).ok().map(|rel| {
-                    let path = if rel.as_os_str().is_empty() {
-                        String::new()
-                    } else {
-                        path_to_unix(rel)
-                    };
-                    stitch::core::ProfileSelection { path, state: st }
-                })
-            })
-            .collect::<Vec<_>>()
-    };
+    let samples = state.borrow().dir_samples.clone();
+    let dir_rules = state.borrow().dir_rules.clone();
+    let pinned = state.borrow().pinned_files.clone();
 
     Some(Profile {
         name: name.to_string(),
         settings: ws,
         explicit,
+        samples,
+        dir_rules,
+        pinned,
     })
 }
 
+/// Explicit checks as project-relative selections (profiles and snapshots).
+fn explicit_selections(state: &SharedState, dir: &Path) -> Vec<stitch::core::ProfileSelection> {
+    // NOTE: Preserve root selection by storing an empty relative path ("")
+    // when the explicit key equals the project root.
+    let s = state.borrow();
+    s.explicit_states
+        .iter()
+        .filter_map(|(abs, &st)| {
+            abs.strip_prefix(dir).ok().map(|rel| {
+                let path = if rel.as_os_str().is_empty() {
+                    String::new()
+                } else {
+                    path_to_unix(rel)
+                };
+                stitch::core::ProfileSelection { path, state: st }
+            })
+        })
+        .collect()
+}
+
+/// Replace the explicit checks with `selections` (relative to the open project).
+fn restore_explicit_selections(state: &SharedState, selections: &[stitch::core::ProfileSelection]) {
+    let base = { state.borrow().selected_directory.clone() };
+    let mut s = state.borrow_mut();
+    s.explicit_states.clear();
+    if let Some(root) = base.as_ref() {
+        let sep = std::path::MAIN_SEPARATOR.to_string();
+        for sel in selections {
+            let abs = if sel.path.is_empty() {
+                // Empty relative path means: project root itself.
+                root.clone()
+            } else {
+                root.join(sel.path.replace('/', sep.as_str()))
+            };
+            s.explicit_states.insert(abs, sel.state);
+        }
+    }
+}
+
 fn apply_profile_to_ui(app: &AppWindow, state: &SharedState, profile: &Profile) {
-    app.set_ext_filter(profile.settings.ext_filter.clone().into());
-    app.set_exclude_dirs(profile.settings.exclude_dirs.clone().into());
-    app.set_exclude_files(profile.settings.exclude_files.clone().into());
-    app.set_remove_prefix(profile.settings.remove_prefix.clone().into());
-    app.set_remove_regex(profile.settings.remove_regex.clone().into());
-    app.set_hierarchy_only(profile.settings.hierarchy_only);
-    app.set_dirs_only(profile.settings.dirs_only);
-    app.set_rust_remove_inline_comments(profile.settings.rust.rust_remove_inline_comments);
-    app.set_rust_remove_doc_comments(profile.settings.rust.rust_remove_doc_comments);
-    app.set_rust_function_signatures_only(profile.settings.rust.rust_function_signatures_only);
-    app.set_rust_signatures_only_filter(
-        profile
-            .settings
-            .rust
-            .rust_signatures_only_filter
-            .clone()
-            .into(),
-    );
-    app.set_slint_remove_line_comments(profile.settings.slint.slint_remove_line_comments);
-    app.set_slint_remove_block_comments(profile.settings.slint.slint_remove_block_comments);
+    apply_workspace_settings_to_ui(app, state, &profile.settings);
 
     app.set_profile_name(profile.name.clone().into());
 
     parse_filters_from_ui(app, state);
 
-    let base = { state.borrow().selected_directory.clone() };
+    restore_explicit_selections(state, &profile.explicit);
     {
         let mut s = state.borrow_mut();
-        s.explicit_states.clear();
-        if let Some(root) = base.as_ref() {
-            let sep = std::path::MAIN_SEPARATOR.to_string();
-            for sel in &profile.explicit {
-                let abs = if sel.path.is_empty() {
-                    // Empty relative path means: project root itself.
-                    root.clone()
-                } else {
-                    root.join(sel.path.replace('/', sep.as_str()))
-                };
-                s.explicit_states.insert(abs, sel.state);
-            }
-        }
+        s.dir_samples.clone_from(&profile.samples);
+        s.dir_rules.clone_from(&profile.dir_rules);
+        s.pinned_files.clone_from(&profile.pinned);
         s.profile_baseline = Some(profile.clone());
     }
 
@@ -1627,25 +3705,16 @@ pub fn on_select_profile(app: &AppWindow, state: &SharedState, index: i32) {
         local_settings.current_profile = None;
         let _ = save_local_settings(&root, &local_settings);
         if let Some(ws) = load_workspace(&root) {
-            app.set_ext_filter(ws.ext_filter.clone().into());
-            app.set_exclude_dirs(ws.exclude_dirs.clone().into());
-            app.set_exclude_files(ws.exclude_files.clone().into());
-            app.set_remove_prefix(ws.remove_prefix.clone().into());
-            app.set_remove_regex(ws.remove_regex.clone().into());
-            app.set_hierarchy_only(ws.hierarchy_only);
-            app.set_dirs_only(ws.dirs_only);
-            app.set_rust_remove_inline_comments(ws.rust.rust_remove_inline_comments);
-            app.set_rust_remove_doc_comments(ws.rust.rust_remove_doc_comments);
-            app.set_rust_function_signatures_only(ws.rust.rust_function_signatures_only);
-            app.set_rust_signatures_only_filter(ws.rust.rust_signatures_only_filter.clone().into());
-            app.set_slint_remove_line_comments(ws.slint.slint_remove_line_comments);
-            app.set_slint_remove_block_comments(ws.slint.slint_remove_block_comments);
+            apply_workspace_settings_to_ui(app, state, &ws);
 
             state.borrow_mut().workspace_baseline = Some(ws);
 
             parse_filters_from_ui(app, state);
 
             state.borrow_mut().explicit_states.clear();
+            state.borrow_mut().dir_samples.clear();
+            state.borrow_mut().dir_rules.clear();
+            state.borrow_mut().pinned_files.clear();
             state.borrow_mut().profile_baseline = None;
             app.set_profile_name("".into());
             app.set_save_enabled(false);
@@ -1676,39 +3745,37 @@ pub fn on_select_profile(app: &AppWindow, state: &SharedState, index: i32) {
     }
 }
 
+/// Select the saved profile called `name`, as if picked in the selector.
+///
+/// Returns `false` if the open project has no such profile.
+pub fn select_profile_by_name(app: &AppWindow, state: &SharedState, name: &str) -> bool {
+    let position = state.borrow().profiles.iter().position(|p| p.name == name);
+    let Some(index) = position.and_then(|i| i32::try_from(i + 1).ok()) else {
+        return false;
+    };
+    app.set_selected_profile_index(index);
+    on_select_profile(app, state, index);
+    true
+}
+
 pub fn on_save_profile_current(app: &AppWindow, state: &SharedState) {
     let idx = app.get_selected_profile_index();
     if idx < 0 {
         return;
     }
+    normalize_filter_fields(app, state);
 
     if idx == 0 {
         let Some(project_root) = state.borrow().selected_directory.clone() else {
             return;
         };
 
-        let ws = WorkspaceSettings {
-            version: 1,
-            ext_filter: app.get_ext_filter().to_string(),
-            exclude_dirs: app.get_exclude_dirs().to_string(),
-            exclude_files: app.get_exclude_files().to_string(),
-            remove_prefix: app.get_remove_prefix().to_string(),
-            remove_regex: app.get_remove_regex().to_string(),
-            hierarchy_only: app.get_hierarchy_only(),
-            dirs_only: app.get_dirs_only(),
-            rust: RustOptions {
-                rust_remove_inline_comments: app.get_rust_remove_inline_comments(),
-                rust_remove_doc_comments: app.get_rust_remove_doc_comments(),
-                rust_function_signatures_only: app.get_rust_function_signatures_only(),
-                rust_signatures_only_filter: app.get_rust_signatures_only_filter().to_string(),
-            },
-            slint: SlintOptions {
-                slint_remove_line_comments: app.get_slint_remove_line_comments(),
-                slint_remove_block_comments: app.get_slint_remove_block_comments(),
-            },
-        };
+        let ws = workspace_settings_from_ui(app, state);
 
-        let _ = save_workspace(&project_root, &ws);
+        if let Err(e) = save_workspace(&project_root, &ws) {
+            report_save_error(app, None, &e);
+            return;
+        }
 
         {
             let mut s = state.borrow_mut();
@@ -1743,7 +3810,10 @@ pub fn on_save_profile_current(app: &AppWindow, state: &SharedState) {
         return;
     };
 
-    let _ = save_profile(&project_root, &profile, scope);
+    if let Err(e) = save_profile(&project_root, &profile, scope) {
+        report_save_error(app, Some(&new_name), &e);
+        return;
+    }
 
     if new_name != old_name {
         let _ = delete_profile(&project_root, scope, &old_name);
@@ -1763,6 +3833,26 @@ pub fn on_save_profile_current(app: &AppWindow, state: &SharedState) {
     app.set_save_enabled(false);
 }
 
+/// Tell the user a save of the workspace settings (or of `profile`) didn't happen, e.g.
+/// because another window held the write lock.
+fn report_save_error(app: &AppWindow, profile: Option<&str>, e: &std::io::Error) {
+    let strings = app.global::<Strings>();
+    let mut text = match profile {
+        Some(name) => strings.invoke_profile_not_saved(name.into(), e.to_string().into()),
+        None => strings.invoke_workspace_not_saved(e.to_string().into()),
+    }
+    .to_string();
+    if e.kind() == std::io::ErrorKind::WouldBlock {
+        text.push_str("\n\n");
+        text.push_str(&strings.get_changes_kept());
+    }
+    rfd::MessageDialog::new()
+        .set_level(rfd::MessageLevel::Warning)
+        .set_title(strings.get_save_failed().as_str())
+        .set_description(text)
+        .show();
+}
+
 pub fn on_save_profile_as(app: &AppWindow, state: &SharedState) {
     if let Some(d) = state.borrow().save_profile_dialog.as_ref() {
         let _ = d.show();
@@ -1785,11 +3875,15 @@ pub fn on_save_profile_as(app: &AppWindow, state: &SharedState) {
                 ProfileScope::Shared
             };
 
+            normalize_filter_fields(&app, &state_rc);
             let project_root = { state_rc.borrow().selected_directory.clone() };
             if let Some(root) = project_root
                 && let Some(profile) = capture_profile_from_ui(&app, &state_rc, name.as_str())
             {
-                let _ = save_profile(&root, &profile, scope);
+                if let Err(e) = save_profile(&root, &profile, scope) {
+                    report_save_error(&app, Some(&profile.name), &e);
+                    return;
+                }
 
                 let mut local_settings = load_local_settings(&root).unwrap_or_default();
                 local_settings.current_profile = Some(profile.name.clone());
@@ -1845,26 +3939,7 @@ pub fn on_save_profile_as(app: &AppWindow, state: &SharedState) {
 }
 
 fn profiles_equal(a: &Profile, b: &Profile) -> bool {
-    if a.name != b.name {
-        return false;
-    }
-    let sa = &a.settings;
-    let sb = &b.settings;
-    if sa.version != sb.version
-        || sa.ext_filter != sb.ext_filter
-        || sa.exclude_dirs != sb.exclude_dirs
-        || sa.exclude_files != sb.exclude_files
-        || sa.remove_prefix != sb.remove_prefix
-        || sa.remove_regex != sb.remove_regex
-        || sa.hierarchy_only != sb.hierarchy_only
-        || sa.dirs_only != sb.dirs_only
-        || sa.rust.rust_remove_inline_comments != sb.rust.rust_remove_inline_comments
-        || sa.rust.rust_remove_doc_comments != sb.rust.rust_remove_doc_comments
-        || sa.rust.rust_function_signatures_only != sb.rust.rust_function_signatures_only
-        || sa.rust.rust_signatures_only_filter != sb.rust.rust_signatures_only_filter
-        || sa.slint.slint_remove_line_comments != sb.slint.slint_remove_line_comments
-        || sa.slint.slint_remove_block_comments != sb.slint.slint_remove_block_comments
-    {
+    if a.name != b.name || a.settings != b.settings {
         return false;
     }
     // Compare explicit selections ignoring order
@@ -1878,12 +3953,34 @@ fn profiles_equal(a: &Profile, b: &Profile) -> bool {
         Ordering::Equal => x.state.cmp(&y.state),
         o => o,
     });
-    ea == eb
+    if ea != eb {
+        return false;
+    }
+    let mut sa = a.samples.clone();
+    let mut sb = b.samples.clone();
+    sa.sort_by(|x, y| x.path.cmp(&y.path));
+    sb.sort_by(|x, y| x.path.cmp(&y.path));
+    if sa != sb {
+        return false;
+    }
+    let mut ra = a.dir_rules.clone();
+    let mut rb = b.dir_rules.clone();
+    ra.sort_by(|x, y| x.path.cmp(&y.path));
+    rb.sort_by(|x, y| x.path.cmp(&y.path));
+    if ra != rb {
+        return false;
+    }
+    let mut pa = a.pinned.clone();
+    let mut pb = b.pinned.clone();
+    pa.sort();
+    pb.sort();
+    pa == pb
 }
 
 fn update_save_button_state(app: &AppWindow, state: &SharedState) {
     let idx = app.get_selected_profile_index();
 
+    app.set_unsaved_changes(slint::SharedString::default());
     if idx < 0 {
         app.set_save_enabled(false);
         return;
@@ -1891,29 +3988,17 @@ fn update_save_button_state(app: &AppWindow, state: &SharedState) {
 
     // Workspace (— Workspace —)
     if idx == 0 {
-        let current = WorkspaceSettings {
-            version: 1,
-            ext_filter: app.get_ext_filter().to_string(),
-            exclude_dirs: app.get_exclude_dirs().to_string(),
-            exclude_files: app.get_exclude_files().to_string(),
-            remove_prefix: app.get_remove_prefix().to_string(),
-            remove_regex: app.get_remove_regex().to_string(),
-            hierarchy_only: app.get_hierarchy_only(),
-            dirs_only: app.get_dirs_only(),
-            rust: RustOptions {
-                rust_remove_inline_comments: app.get_rust_remove_inline_comments(),
-                rust_remove_doc_comments: app.get_rust_remove_doc_comments(),
-                rust_function_signatures_only: app.get_rust_function_signatures_only(),
-                rust_signatures_only_filter: app.get_rust_signatures_only_filter().to_string(),
-            },
-            slint: SlintOptions {
-                slint_remove_line_comments: app.get_slint_remove_line_comments(),
-                slint_remove_block_comments: app.get_slint_remove_block_comments(),
-            },
-        };
+        let current = workspace_settings_from_ui(app, state);
 
         let baseline_opt = { state.borrow().workspace_baseline.clone() };
-        let dirty = baseline_opt.is_none_or(|b| !workspace_settings_equal(&b, &current));
+        let dirty = baseline_opt.as_ref().is_none_or(|b| *b != current);
+        if let Some(baseline) = &baseline_opt {
+            let changes = ProfileChanges {
+                settings: diff_workspace_settings(baseline, &current),
+                ..ProfileChanges::default()
+            };
+            app.set_unsaved_changes(changes.render().into());
+        }
         app.set_save_enabled(dirty);
         return;
     }
@@ -1932,7 +4017,12 @@ fn update_save_button_state(app: &AppWindow, state: &SharedState) {
     };
 
     let baseline_opt = { state.borrow().profile_baseline.clone() };
-    let dirty = baseline_opt.is_none_or(|b| !profiles_equal(&b, &current));
+    let dirty = baseline_opt
+        .as_ref()
+        .is_none_or(|b| !profiles_equal(b, &current));
+    if let Some(baseline) = &baseline_opt {
+        app.set_unsaved_changes(diff_profiles(baseline, &current).render().into());
+    }
     app.set_save_enabled(dirty);
 }
 
@@ -1966,19 +4056,7 @@ pub fn on_delete_profile(app: &AppWindow, state: &SharedState) {
     local_settings.current_profile = None;
     let _ = save_local_settings(&project_root, &local_settings);
     if let Some(ws) = load_workspace(&project_root) {
-        app.set_ext_filter(ws.ext_filter.clone().into());
-        app.set_exclude_dirs(ws.exclude_dirs.clone().into());
-        app.set_exclude_files(ws.exclude_files.clone().into());
-        app.set_remove_prefix(ws.remove_prefix.clone().into());
-        app.set_remove_regex(ws.remove_regex.clone().into());
-        app.set_hierarchy_only(ws.hierarchy_only);
-        app.set_dirs_only(ws.dirs_only);
-        app.set_rust_remove_inline_comments(ws.rust.rust_remove_inline_comments);
-        app.set_rust_remove_doc_comments(ws.rust.rust_remove_doc_comments);
-        app.set_rust_function_signatures_only(ws.rust.rust_function_signatures_only);
-        app.set_rust_signatures_only_filter(ws.rust.rust_signatures_only_filter.clone().into());
-        app.set_slint_remove_line_comments(ws.slint.slint_remove_line_comments);
-        app.set_slint_remove_block_comments(ws.slint.slint_remove_block_comments);
+        apply_workspace_settings_to_ui(app, state, &ws);
 
         // Update baseline
         state.borrow_mut().workspace_baseline = Some(ws);
@@ -1989,6 +4067,9 @@ pub fn on_delete_profile(app: &AppWindow, state: &SharedState) {
         s.profiles = list_profiles(&project_root);
         s.profile_baseline = None;
         s.explicit_states.clear();
+        s.dir_samples.clear();
+        s.dir_rules.clear();
+        s.pinned_files.clear();
     }
     refresh_profiles_ui(app, state);
     app.set_profile_name("".into());
@@ -1999,6 +4080,79 @@ pub fn on_delete_profile(app: &AppWindow, state: &SharedState) {
     on_generate_output(app, state);
 }
 
+/// Remove the entries of the current profile whose path is no longer in the scanned
+/// tree, after listing them for confirmation. The saved profile file is cleaned as
+/// well; other unsaved edits are left alone.
+pub fn on_clean_profile(app: &AppWindow, state: &SharedState) {
+    let Some(profile_idx) = profile_vec_index(app.get_selected_profile_index()) else {
+        return;
+    };
+    let (project_root, meta, scanned) = {
+        let s = state.borrow();
+        let (Some(dir), Some(root), Some(meta)) = (
+            s.selected_directory.clone(),
+            s.root_node.as_ref(),
+            s.profiles.get(profile_idx).cloned(),
+        ) else {
+            return;
+        };
+        let scanned = ScannedPaths::from_tree(&dir, root);
+        (dir, meta, scanned)
+    };
+    let Some(current) = capture_profile_from_ui(app, state, &meta.name) else {
+        return;
+    };
+    let mut saved = load_profile(&project_root, &meta.name).map(|(p, _)| p);
+    let mut stale = find_stale_entries(&current, &scanned);
+    if let Some(saved) = &saved {
+        stale.absorb(find_stale_entries(saved, &scanned));
+    }
+
+    let strings = app.global::<Strings>();
+    if stale.is_empty() {
+        show_copy_toast(app, state, strings.get_no_stale_entries(), 1500);
+        return;
+    }
+    let confirmed = rfd::MessageDialog::new()
+        .set_level(rfd::MessageLevel::Warning)
+        .set_title(strings.get_clean_profile().as_str())
+        .set_description(
+            strings
+                .invoke_remove_stale_entries(meta.name.as_str().into(), stale.render().into())
+                .as_str(),
+        )
+        .set_buttons(rfd::MessageButtons::YesNo)
+        .show()
+        == rfd::MessageDialogResult::Yes;
+    if !confirmed {
+        return;
+    }
+
+    if let Some(saved) = saved.as_mut() {
+        remove_stale_entries(saved, &stale);
+        if let Err(e) = save_profile(&project_root, saved, meta.scope) {
+            report_save_error(app, Some(&meta.name), &e);
+            return;
+        }
+    }
+    {
+        let mut s = state.borrow_mut();
+        s.explicit_states.retain(|abs, _| {
+            abs.strip_prefix(&project_root)
+                .map_or(true, |rel| !stale.explicit.contains(&path_to_unix(rel)))
+        });
+        s.dir_samples.retain(|d| !stale.samples.contains(&d.path));
+        s.dir_rules.retain(|r| !stale.dir_rules.contains(&r.path));
+        s.pinned_files.retain(|p| !stale.pinned.contains(p));
+        if let Some(baseline) = s.profile_baseline.as_mut() {
+            remove_stale_entries(baseline, &stale);
+        }
+    }
+    update_save_button_state(app, state);
+    let toast = strings.invoke_stale_entries_removed(stale.len() as i32);
+    show_copy_toast(app, state, toast, 1800);
+}
+
 pub fn on_discard_changes(app: &AppWindow, state: &SharedState) {
     let idx = app.get_selected_profile_index();
     if idx < 0 {
@@ -2017,25 +4171,16 @@ pub fn on_discard_changes(app: &AppWindow, state: &SharedState) {
         });
 
         if let Some(ws) = ws_opt {
-            app.set_ext_filter(ws.ext_filter.clone().into());
-            app.set_exclude_dirs(ws.exclude_dirs.clone().into());
-            app.set_exclude_files(ws.exclude_files.clone().into());
-            app.set_remove_prefix(ws.remove_prefix.clone().into());
-            app.set_remove_regex(ws.remove_regex.clone().into());
-            app.set_hierarchy_only(ws.hierarchy_only);
-            app.set_dirs_only(ws.dirs_only);
-            app.set_rust_remove_inline_comments(ws.rust.rust_remove_inline_comments);
-            app.set_rust_remove_doc_comments(ws.rust.rust_remove_doc_comments);
-            app.set_rust_function_signatures_only(ws.rust.rust_function_signatures_only);
-            app.set_rust_signatures_only_filter(ws.rust.rust_signatures_only_filter.clone().into());
-            app.set_slint_remove_line_comments(ws.slint.slint_remove_line_comments);
-            app.set_slint_remove_block_comments(ws.slint.slint_remove_block_comments);
+            apply_workspace_settings_to_ui(app, state, &ws);
 
             parse_filters_from_ui(app, state);
 
             {
                 let mut s = state.borrow_mut();
                 s.explicit_states.clear();
+                s.dir_samples.clear();
+                s.dir_rules.clear();
+                s.pinned_files.clear();
                 s.profile_baseline = None;
                 s.workspace_baseline = Some(ws);
             }
@@ -2074,31 +4219,93 @@ pub fn on_discard_changes(app: &AppWindow, state: &SharedState) {
     }
 }
 
-fn workspace_settings_equal(a: &WorkspaceSettings, b: &WorkspaceSettings) -> bool {
-    a.version == b.version
-        && a.ext_filter == b.ext_filter
-        && a.exclude_dirs == b.exclude_dirs
-        && a.exclude_files == b.exclude_files
-        && a.remove_prefix == b.remove_prefix
-        && a.remove_regex == b.remove_regex
-        && a.hierarchy_only == b.hierarchy_only
-        && a.dirs_only == b.dirs_only
-        && a.rust == b.rust
-        && a.slint == b.slint
-    // Note: we intentionally ignore `current_profile` here for dirtiness comparison
-}
-
-fn format_project_path_for_title(dir: &Path) -> String {
-    // Prefer HOME (Unix) or USERPROFILE (Windows) for "~" replacement.
-    let home_opt = std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE"));
-    if let Some(home_os) = home_opt {
-        let home = std::path::PathBuf::from(home_os);
-        if let Ok(rel) = dir.strip_prefix(&home) {
-            if rel.as_os_str().is_empty() {
-                return "~".to_string();
-            }
-            return format!("~/{}", path_to_unix(rel));
-        }
+fn workspace_settings_from_ui(app: &AppWindow, state: &SharedState) -> WorkspaceSettings {
+    WorkspaceSettings {
+        version: 1,
+        ext_filter: app.get_ext_filter().to_string(),
+        exclude_dirs: app.get_exclude_dirs().to_string(),
+        exclude_files: app.get_exclude_files().to_string(),
+        remove_prefix: app.get_remove_prefix().to_string(),
+        remove_regex: app.get_remove_regex().to_string(),
+        hierarchy_only: app.get_hierarchy_only(),
+        dirs_only: app.get_dirs_only(),
+        summarize_unselected: app.get_summarize_unselected(),
+        line_numbers: app.get_line_numbers(),
+        dependencies_section: app.get_dependencies_section(),
+        deterministic: app.get_deterministic(),
+        cell_markers: app.get_cell_markers(),
+        layout: state.borrow().output_layout.clone(),
+        path_rewrite: PathRewrite {
+            strip_prefix: app.get_path_strip_prefix().to_string(),
+            prepend: app.get_path_prepend().to_string(),
+        },
+        file_markers: state.borrow().file_markers.clone(),
+        languages: state.borrow().languages.clone(),
+        transforms: state.borrow().transforms.clone(),
+        svg_inline_limit: state.borrow().svg_inline_limit,
+        normalize_text: state.borrow().normalize_text,
+        post_process: state.borrow().post_process.clone(),
+        tree_style: state.borrow().tree_style,
+        tokenizer: app.get_tokenizer().to_string(),
+        context_window: ContextWindow::from_index(app.get_context_window_index()),
+        output_order: OutputOrder::from_index(app.get_output_order_index()),
+        name_matching: NameMatching::from_index(app.get_name_matching_index()),
+        rust: RustOptions {
+            rust_remove_inline_comments: app.get_rust_remove_inline_comments(),
+            rust_remove_doc_comments: app.get_rust_remove_doc_comments(),
+            rust_function_signatures_only: app.get_rust_function_signatures_only(),
+            rust_signatures_only_filter: app.get_rust_signatures_only_filter().to_string(),
+        },
+        slint: SlintOptions {
+            slint_remove_line_comments: app.get_slint_remove_line_comments(),
+            slint_remove_block_comments: app.get_slint_remove_block_comments(),
+        },
+        c_like: state.borrow().c_like.clone(),
+        go: state.borrow().go.clone(),
+        web: state.borrow().web.clone(),
+        sql: state.borrow().sql.clone(),
+    }
+}
+
+fn apply_workspace_settings_to_ui(app: &AppWindow, state: &SharedState, ws: &WorkspaceSettings) {
+    {
+        let mut s = state.borrow_mut();
+        s.output_layout = ws.layout.clone();
+        s.file_markers = ws.file_markers.clone();
+        s.languages = ws.languages.clone();
+        s.transforms = ws.transforms.clone();
+        s.svg_inline_limit = ws.svg_inline_limit;
+        s.normalize_text = ws.normalize_text;
+        s.post_process = ws.post_process.clone();
+        s.c_like = ws.c_like.clone();
+        s.go = ws.go.clone();
+        s.web = ws.web.clone();
+        s.sql = ws.sql.clone();
+        s.tree_style = ws.tree_style;
+        s.hierarchy_cache.set_style(ws.tree_style);
     }
-    path_to_unix(dir)
+    app.set_ext_filter(ws.ext_filter.clone().into());
+    app.set_exclude_dirs(ws.exclude_dirs.clone().into());
+    app.set_exclude_files(ws.exclude_files.clone().into());
+    app.set_remove_prefix(ws.remove_prefix.clone().into());
+    app.set_remove_regex(ws.remove_regex.clone().into());
+    app.set_hierarchy_only(ws.hierarchy_only);
+    app.set_dirs_only(ws.dirs_only);
+    app.set_summarize_unselected(ws.summarize_unselected);
+    app.set_line_numbers(ws.line_numbers);
+    app.set_dependencies_section(ws.dependencies_section);
+    app.set_deterministic(ws.deterministic);
+    app.set_cell_markers(ws.cell_markers);
+    app.set_path_strip_prefix(ws.path_rewrite.strip_prefix.clone().into());
+    app.set_path_prepend(ws.path_rewrite.prepend.clone().into());
+    app.set_tokenizer(ws.tokenizer.clone().into());
+    app.set_context_window_index(ws.context_window.index());
+    app.set_output_order_index(ws.output_order.index());
+    app.set_name_matching_index(ws.name_matching.index());
+    app.set_rust_remove_inline_comments(ws.rust.rust_remove_inline_comments);
+    app.set_rust_remove_doc_comments(ws.rust.rust_remove_doc_comments);
+    app.set_rust_function_signatures_only(ws.rust.rust_function_signatures_only);
+    app.set_rust_signatures_only_filter(ws.rust.rust_signatures_only_filter.clone().into());
+    app.set_slint_remove_line_comments(ws.slint.slint_remove_line_comments);
+    app.set_slint_remove_block_comments(ws.slint.slint_remove_block_comments);
 }
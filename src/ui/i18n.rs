@@ -0,0 +1,34 @@
+//! UI language selection for the translations bundled from `ui/lang/`.
+//!
+//! Only the interface is translated; generated output stays English so it reads the same
+//! to models and round-trips through "Select from Text…".
+
+/// Environment variables consulted in order; `STITCH_LANG` overrides the system locale.
+const LANGUAGE_VARS: &[&str] = &["STITCH_LANG", "LC_ALL", "LC_MESSAGES", "LANG"];
+
+/// Reduce a POSIX locale such as `de_DE.UTF-8` to its language code (`de`).
+fn language_from_locale(locale: &str) -> Option<String> {
+    let lang = locale
+        .split(['_', '.', '@', '-'])
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    (!lang.is_empty() && lang != "c" && lang != "posix").then_some(lang)
+}
+
+fn preferred_language() -> Option<String> {
+    LANGUAGE_VARS
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|v| !v.trim().is_empty())
+        .and_then(|v| language_from_locale(&v))
+}
+
+/// Switch the bundled translation to the user's language; unknown languages stay English.
+/// Must run after the first window is created (the Slint platform has to exist).
+pub fn select_ui_language() {
+    if let Some(lang) = preferred_language() {
+        let _ = slint::select_bundled_translation(&lang);
+    }
+}
@@ -1,13 +1,29 @@
 slint::include_modules!();
 
 pub mod handlers;
+pub mod i18n;
 pub mod state;
+pub mod tree_model;
 
 pub use handlers::{
-    apply_selection_from_text, on_check_updates, on_copy_output, on_delete_profile,
-    on_discard_changes, on_filter_changed, on_generate_output, on_profile_name_changed,
-    on_save_profile_as, on_save_profile_current, on_select_folder, on_select_profile,
-    on_toggle_check, on_toggle_expand, on_toggle_fs_watcher,
+    apply_accessibility_preferences, apply_content_selection, apply_ranked_selection,
+    apply_selection_from_text, apply_selection_paths, apply_user_preferences_to_ui,
+    on_apply_exclude_suggestion, on_apply_filter_preset, on_call_graph_apply,
+    on_call_graph_file_selected, on_call_graph_preview, on_check_updates, on_clean_profile,
+    on_copy_hierarchy, on_copy_output, on_delete_filter_preset, on_delete_profile,
+    on_delete_snapshot, on_discard_changes, on_dismiss_exclude_suggestions, on_export_compressed,
+    on_export_split, on_filter_changed, on_find_changed, on_find_closed, on_find_definition,
+    on_find_step, on_generate_output, on_history_copy_entry, on_history_diff_with_current,
+    on_history_diff_with_older, on_history_show_entry, on_import_settings, on_open_release_page,
+    on_output_font_changed, on_output_mode_changed, on_output_page_step, on_profile_name_changed,
+    on_restore_snapshot, on_run_task, on_save_filter_preset, on_save_hierarchy, on_save_profile_as,
+    on_save_profile_current, on_select_folder, on_select_profile, on_set_dir_rule,
+    on_set_dir_sample, on_stats_copy, on_take_snapshot, on_theme_changed, on_toggle_check,
+    on_toggle_expand, on_toggle_fs_watcher, on_toggle_pin, on_toggle_update_check,
+    on_token_mode_changed, on_trim_apply, on_trim_suggest, on_trim_toggle, open_project,
+    parent_row_index, preview_content_selection, preview_ranked_selection,
+    refresh_call_graph_dialog, refresh_filter_presets, refresh_history_dialog,
+    refresh_stats_dialog, refresh_trim_dialog, select_profile_by_name, subscribe_ui,
 };
 
 pub use state::AppState;
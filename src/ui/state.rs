@@ -1,10 +1,8 @@
-use regex::Regex;
 use std::{
     cell::RefCell,
     collections::{HashMap, HashSet},
     path::PathBuf,
     rc::Rc,
-    sync::mpsc,
     time::SystemTime,
 };
 
@@ -12,57 +10,76 @@ use std::{
 pub struct FsState {
     pub dirty: bool,
     pub watcher_disabled: bool,
+    /// Pairs the watcher's rename events.
+    pub renames: stitch::core::RenameTracker,
+    /// Renames seen while the remap prompt was showing.
+    pub pending_renames: Vec<stitch::core::PathRename>,
+    /// Git `HEAD` of the open project and the branch it named, to notice checkouts.
+    pub git_head: Option<PathBuf>,
+    pub git_branch: Option<String>,
+    /// Watch only the selected subtrees (`watch_selection_only` in the local settings).
+    pub watch_selection_only: bool,
+    /// What the watcher currently covers.
+    pub watch_targets: Vec<stitch::core::WatchTarget>,
 }
 
 #[derive(Default)]
 pub struct GenerationState {
     pub in_progress: bool,
     pub queue_another: bool,
+    /// The running generation.
+    pub handle: Option<stitch::core::GenerationHandle>,
+    /// Progress last shown in the output pane.
+    pub shown_progress: stitch::core::GenerationProgress,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
-pub enum CommentRemoval {
-    #[default]
-    None,
-    InlineOnly,
-    DocOnly,
-    InlineAndDoc,
+/// Which output the "Hierarchy Only" / "Directories Only" toggles ask for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OutputMode {
+    Full,
+    HierarchyOnly,
+    DirsOnly,
 }
 
-impl CommentRemoval {
-    #[must_use]
-    pub const fn removes_inline(self) -> bool {
-        matches!(self, Self::InlineOnly | Self::InlineAndDoc)
+impl OutputMode {
+    /// "Directories Only" wins when both toggles are on, as in generation.
+    pub const fn from_toggles(hierarchy_only: bool, dirs_only: bool) -> Self {
+        if dirs_only {
+            Self::DirsOnly
+        } else if hierarchy_only {
+            Self::HierarchyOnly
+        } else {
+            Self::Full
+        }
     }
+}
 
-    #[must_use]
-    pub const fn removes_doc(self) -> bool {
-        matches!(self, Self::DocOnly | Self::InlineAndDoc)
-    }
+/// An output kept for its [`OutputMode`] until the selection or settings change.
+pub struct CachedOutput {
+    pub text: String,
+    pub breakdown: String,
+    pub split_source: Option<stitch::core::SplitSource>,
+    pub selection_stats: stitch::core::SelectionStats,
+}
 
-    #[must_use]
-    pub const fn from_flags(remove_inline: bool, remove_doc: bool) -> Self {
-        match (remove_inline, remove_doc) {
-            (true, true) => Self::InlineAndDoc,
-            (true, false) => Self::InlineOnly,
-            (false, true) => Self::DocOnly,
-            (false, false) => Self::None,
-        }
-    }
+/// The output pane's find bar.
+#[derive(Default)]
+pub struct OutputFind {
+    /// Search text and whether case matters; `None` while the bar is empty or closed.
+    pub query: Option<(String, bool)>,
+    /// Byte ranges of the matches in `full_output_text`.
+    pub matches: Vec<std::ops::Range<usize>>,
+    pub current: usize,
 }
 
 #[derive(Default)]
 pub struct RustUiState {
     pub has_files: bool,
-    pub comment_removal: CommentRemoval,
-    pub signatures_filter: Option<String>,
 }
 
 #[derive(Default)]
 pub struct SlintUiState {
     pub has_files: bool,
-    pub remove_line_comments: bool,
-    pub remove_block_comments: bool,
 }
 
 #[derive(Default)]
@@ -70,41 +87,131 @@ pub struct AppState {
     pub selected_directory: Option<PathBuf>,
     pub root_node: Option<stitch::core::Node>,
     pub explicit_states: HashMap<PathBuf, bool>,
+    /// Per-directory "include only N files" rules (project-relative), saved with profiles.
+    pub dir_samples: Vec<stitch::core::DirectorySample>,
+    /// Per-directory scan overrides (project-relative), saved with profiles.
+    pub dir_rules: Vec<stitch::core::DirectoryRule>,
+    /// Pinned files (project-relative, sorted), saved with profiles.
+    pub pinned_files: Vec<String>,
     pub last_mod_times: HashMap<PathBuf, Option<SystemTime>>,
     pub poll_interval_ms: u64,
     pub path_snapshot: Option<HashSet<PathBuf>>,
-    pub remove_prefixes: Vec<String>,
-    pub remove_regex_str: Option<String>,
-    pub remove_regex: Option<Regex>,
+    pub path_rewrite: stitch::core::PathRewrite,
     pub include_exts: HashSet<String>,
     pub exclude_exts: HashSet<String>,
     pub exclude_dirs: HashSet<String>,
     pub exclude_files: HashSet<String>,
-    pub existing_excluded_dirs: HashSet<String>,
-    pub existing_excluded_files: HashSet<String>,
+    /// How `exclude_dirs` / `exclude_files` are compared with names on disk.
+    pub name_matching: stitch::core::NameMatching,
+    /// Directories are read on first expand (`lazy_scan` in the local settings).
+    pub lazy_scan: bool,
+    /// What the last scan left out of the tree, for the NOTES section.
+    pub scan_stats: stitch::core::ScanStats,
+    /// The project's `.stitchignore` as of the last scan, so the watcher skips ignored paths.
+    pub stitchignore: stitch::core::IgnoreRules,
+    /// Exclusions offered for the current tree, in the order shown in the panel.
+    pub exclude_suggestions: Vec<stitch::core::ExcludeSuggestion>,
+    /// The user closed the suggestions panel; stays hidden until another project is opened.
+    pub exclude_suggestions_dismissed: bool,
     pub copy_toast_timer: slint::Timer,
+    /// High-contrast / reduced-motion preferences in effect (OS settings plus local overrides).
+    pub accessibility: stitch::core::PlatformPreferences,
     pub select_dialog: Option<crate::ui::SelectFromTextDialog>,
+    pub rank_dialog: Option<crate::ui::RankFilesDialog>,
+    pub content_dialog: Option<crate::ui::ContentSearchDialog>,
+    pub history_dialog: Option<crate::ui::HistoryDialog>,
+    pub trim_dialog: Option<crate::ui::TrimBudgetDialog>,
+    pub call_graph_dialog: Option<crate::ui::CallGraphDialog>,
+    pub stats_dialog: Option<crate::ui::StatsDialog>,
+    /// Rust files offered in the call-graph dialog, in the order of its file list.
+    pub call_graph_files: Vec<PathBuf>,
+    /// Files listed in the trim dialog, in the order of its rows.
+    pub trim_candidates: Vec<stitch::core::TrimCandidate>,
+    /// History entries listed in the history dialog, newest first.
+    pub history: Vec<stitch::core::HistoryEntry>,
     pub fs: FsState,
     pub watcher: Option<notify::RecommendedWatcher>,
     pub fs_event_rx: Option<std::sync::mpsc::Receiver<notify::Result<notify::Event>>>,
     pub fs_pump_timer: slint::Timer,
     pub full_output_text: String,
+    /// Whether `full_output_text` is generated output or a message in its place.
+    pub output_kind: stitch::core::OutputKind,
+    /// Chars per output pane page (`preview_char_limit` in the local settings, `0` = all).
+    pub preview_char_limit: usize,
+    /// Byte ranges of `full_output_text` shown one at a time in the output pane.
+    pub output_pages: Vec<std::ops::Range<usize>>,
+    pub output_page: usize,
+    /// The last output of each mode since anything but the mode toggles changed.
+    pub output_cache: HashMap<OutputMode, CachedOutput>,
+    pub output_find: OutputFind,
+    /// Shared sections and file blocks of the last full generation, for "Split Export".
+    pub split_source: Option<stitch::core::SplitSource>,
+    /// Per-file token estimates of the last full generation, for the trim dialog.
+    pub selection_stats: stitch::core::SelectionStats,
     pub poll_timer: slint::Timer,
 
     /// Available profiles (name + scope). Order is alphabetical by name.
     pub profiles: Vec<stitch::core::ProfileMeta>,
+    /// Per-user filter presets, in the order of the preset dropdown (after its first entry).
+    pub filter_presets: Vec<stitch::core::FilterPreset>,
+    /// Selection snapshots of the open project, oldest first (as listed in the tree panel).
+    pub snapshots: Vec<stitch::core::SelectionSnapshot>,
+    /// Task names of the open project, in the order of the task dropdown.
+    pub tasks: Vec<String>,
 
     /// The "Save As…" dialog instance, if shown.
     pub save_profile_dialog: Option<crate::ui::SaveProfileDialog>,
     pub profile_baseline: Option<stitch::core::Profile>,
 
     pub workspace_baseline: Option<stitch::core::WorkspaceSettings>,
+    /// Section layout from the active workspace/profile settings (not editable in the UI).
+    pub output_layout: stitch::core::OutputLayout,
+    /// File header/footer formats from the active workspace/profile settings (not editable in the UI).
+    pub file_markers: stitch::core::FileMarkers,
+    /// Language overrides from the active workspace/profile settings (not editable in the UI).
+    pub languages: std::collections::BTreeMap<String, String>,
+    /// Per-file transform order from the active workspace/profile settings (not editable in the UI).
+    pub transforms: stitch::core::TransformPipeline,
+    /// Tree branch style from the active workspace/profile settings (not editable in the UI).
+    pub tree_style: stitch::core::TreeStyle,
+    /// SVG inlining limit from the active workspace/profile settings (not editable in the UI).
+    pub svg_inline_limit: Option<u64>,
+    /// BOM and line-ending normalization from the active workspace/profile settings (not editable in the UI).
+    pub normalize_text: bool,
+    /// Output post-processing command from the active workspace/profile settings (not editable in the UI).
+    pub post_process: Option<stitch::core::PostProcess>,
+    /// C, C++, Java and C# filters from the active workspace/profile settings (not editable in the UI).
+    pub c_like: stitch::core::CLikeOptions,
+    /// Go filters from the active workspace/profile settings (not editable in the UI).
+    pub go: stitch::core::GoOptions,
+    /// HTML/CSS filters from the active workspace/profile settings (not editable in the UI).
+    pub web: stitch::core::WebOptions,
+    /// SQL filters from the active workspace/profile settings (not editable in the UI).
+    pub sql: stitch::core::SqlOptions,
+    /// Set while the "project folder moved" prompt is showing, so timers don't stack prompts.
+    pub relocate_prompt_open: bool,
+    /// Set while the "update renamed paths" prompt is showing.
+    pub rename_prompt_open: bool,
+    /// The GitHub release check runs at most once per window.
+    #[cfg(feature = "update-check")]
+    pub update_check_started: bool,
 
     pub generation: GenerationState,
-    pub gen_seq: u64,
-    pub gen_result_tx: Option<mpsc::Sender<(u64, String)>>,
-    pub gen_result_rx: Option<mpsc::Receiver<(u64, String)>>,
     pub gen_pump_timer: slint::Timer,
+    /// Per-file token counts shared with the background counting thread.
+    pub token_cache: std::sync::Arc<std::sync::Mutex<stitch::core::TokenCache>>,
+    /// `cargo expand` output per crate target, shared with the generation worker.
+    pub expand_cache: std::sync::Arc<std::sync::Mutex<stitch::core::ExpandCache>>,
+    /// Transformed file contents by path and mtime, shared with the generation worker.
+    pub content_cache: std::sync::Arc<std::sync::Mutex<stitch::core::ContentCache>>,
+    /// Save [`Self::content_cache`] after each generation (`persist_content_cache` in the
+    /// local settings).
+    pub persist_content_cache: bool,
+    /// Last rendered FILE HIERARCHY, reused while the selection is unchanged.
+    pub hierarchy_cache: stitch::core::HierarchyCache,
+    /// Selection, filter and generation events; the window subscribes in
+    /// [`crate::ui::handlers::subscribe_ui`].
+    pub events: Rc<stitch::core::EventBus>,
     // Rust-specific filters and detection
     pub rust_ui: RustUiState,
     // Slint-specific filters and detection
@@ -0,0 +1,136 @@
+use std::{cell::RefCell, ops::Range};
+
+use slint::{Model, ModelNotify, ModelTracker};
+
+use super::Row;
+
+/// The flattened tree, patched in place: expanding, collapsing or checking a row only
+/// notifies the list about the rows that changed, so a large tree keeps its scroll
+/// position and the view only refreshes the rows on screen.
+#[derive(Default)]
+pub struct TreeRows {
+    rows: RefCell<Vec<Row>>,
+    notify: ModelNotify,
+}
+
+impl TreeRows {
+    pub fn new(rows: Vec<Row>) -> Self {
+        Self {
+            rows: RefCell::new(rows),
+            notify: ModelNotify::default(),
+        }
+    }
+
+    /// Replace the rows in `range` with `rows`.
+    pub fn splice(&self, range: Range<usize>, rows: Vec<Row>) {
+        let start = range.start;
+        let old_len = range.len();
+        let new_len = rows.len();
+        let changed: Vec<usize> = {
+            let mut current = self.rows.borrow_mut();
+            let changed = current[range.clone()]
+                .iter()
+                .zip(&rows)
+                .enumerate()
+                .filter(|(_, (old, new))| old != new)
+                .map(|(i, _)| start + i)
+                .collect();
+            current.splice(range, rows);
+            changed
+        };
+        for row in changed {
+            self.notify.row_changed(row);
+        }
+        let common = old_len.min(new_len);
+        if new_len > common {
+            self.notify.row_added(start + common, new_len - common);
+        } else if old_len > common {
+            self.notify.row_removed(start + common, old_len - common);
+        }
+    }
+
+    /// Make the rows equal to `rows`, touching only the span between the unchanged
+    /// leading and trailing rows.
+    pub fn update(&self, mut rows: Vec<Row>) {
+        let (prefix, suffix) = {
+            let current = self.rows.borrow();
+            let prefix = current
+                .iter()
+                .zip(&rows)
+                .take_while(|(a, b)| a == b)
+                .count();
+            let max_suffix = current.len().min(rows.len()) - prefix;
+            let suffix = current
+                .iter()
+                .rev()
+                .zip(rows.iter().rev())
+                .take(max_suffix)
+                .take_while(|(a, b)| a == b)
+                .count();
+            (prefix, suffix)
+        };
+        let old_len = self.rows.borrow().len();
+        rows.truncate(rows.len() - suffix);
+        rows.drain(..prefix);
+        self.splice(prefix..old_len - suffix, rows);
+    }
+
+    /// End of the rows below the row at `index` (its visible descendants).
+    pub fn subtree_end(&self, index: usize) -> usize {
+        let rows = self.rows.borrow();
+        let Some(level) = rows.get(index).map(|row| row.level) else {
+            return index;
+        };
+        rows[index + 1..]
+            .iter()
+            .position(|row| row.level <= level)
+            .map_or(rows.len(), |offset| index + 1 + offset)
+    }
+
+    /// Apply `f` to the rows in `range`, notifying the ones it changed.
+    pub fn modify(&self, range: Range<usize>, f: impl Fn(&mut Row)) {
+        let mut changed = Vec::new();
+        {
+            let mut rows = self.rows.borrow_mut();
+            for (i, row) in rows[range.clone()].iter_mut().enumerate() {
+                let before = row.clone();
+                f(row);
+                if *row != before {
+                    changed.push(range.start + i);
+                }
+            }
+        }
+        for row in changed {
+            self.notify.row_changed(row);
+        }
+    }
+}
+
+impl Model for TreeRows {
+    type Data = Row;
+
+    fn row_count(&self) -> usize {
+        self.rows.borrow().len()
+    }
+
+    fn row_data(&self, row: usize) -> Option<Row> {
+        self.rows.borrow().get(row).cloned()
+    }
+
+    fn set_row_data(&self, row: usize, data: Row) {
+        if let Some(slot) = self.rows.borrow_mut().get_mut(row) {
+            *slot = data;
+        } else {
+            return;
+        }
+        self.notify.row_changed(row);
+    }
+
+    fn model_tracker(&self) -> &dyn ModelTracker {
+        &self.notify
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
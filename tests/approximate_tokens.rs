@@ -0,0 +1,43 @@
+use stitch::core::{LocalSettings, estimate_tokens};
+
+fn block(path: &str, body: &str) -> String {
+    format!("--- Start of file: {path} ---\n{body}\n--- End of file: {path} ---\n\n")
+}
+
+#[test]
+fn empty_text_has_no_tokens() {
+    assert_eq!(estimate_tokens(""), 0);
+}
+
+#[test]
+fn estimate_is_close_to_o200k_for_source_code() {
+    let bpe = tiktoken_rs::o200k_base().unwrap();
+    let src = include_str!("../src/core/text.rs");
+    let text = format!(
+        "=== FILE CONTENTS ===\n\n{}",
+        block("src/core/text.rs", src)
+    );
+
+    let exact = bpe.encode_with_special_tokens(&text).len();
+    let estimate = estimate_tokens(&text);
+    let error = exact.abs_diff(estimate) * 100 / exact;
+    assert!(
+        error <= 25,
+        "estimate {estimate} vs exact {exact} ({error}% off)"
+    );
+}
+
+#[test]
+fn ratio_depends_on_file_extension() {
+    let body = "x".repeat(4200);
+    let json = estimate_tokens(&block("data.json", &body));
+    let rust = estimate_tokens(&block("lib.rs", &body));
+    let slint = estimate_tokens(&block("ui/APP.SLINT", &body));
+    assert!(json > rust && rust > slint, "{json} {rust} {slint}");
+}
+
+#[test]
+fn approximate_mode_is_off_for_existing_settings() {
+    let ls: LocalSettings = serde_json::from_str(r#"{"current_profile":null}"#).unwrap();
+    assert!(!ls.approximate_tokens);
+}
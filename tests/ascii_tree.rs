@@ -0,0 +1,79 @@
+use std::collections::HashSet;
+
+use pretty_assertions::assert_eq;
+
+use stitch::core::{
+    HierarchyCache, TreeStyle, WorkspaceSettings, parse_hierarchy_text, render_tree_from_paths,
+};
+
+fn paths() -> Vec<String> {
+    ["src/main.rs", "src/core/fs.rs", "README.md", "-notes.txt"]
+        .iter()
+        .map(|p| (*p).to_string())
+        .collect()
+}
+
+#[test]
+fn ascii_style_uses_plain_branches() {
+    let tree = render_tree_from_paths(&paths(), Some("app"), TreeStyle::Ascii);
+    assert_eq!(
+        tree,
+        "app\n|-- -notes.txt\n|-- README.md\n`-- src\n    |-- core\n    |   `-- fs.rs\n    `-- main.rs\n"
+    );
+    assert!(tree.is_ascii());
+}
+
+#[test]
+fn both_styles_parse_back_to_the_same_paths() {
+    let expected: HashSet<String> = [
+        "-notes.txt",
+        "README.md",
+        "src",
+        "src/core",
+        "src/core/fs.rs",
+        "src/main.rs",
+    ]
+    .iter()
+    .map(|p| (*p).to_string())
+    .collect();
+    for style in [TreeStyle::Unicode, TreeStyle::Ascii] {
+        let tree = render_tree_from_paths(&paths(), Some("app"), style);
+        assert_eq!(
+            parse_hierarchy_text(&tree),
+            Some(expected.clone()),
+            "{style:?}"
+        );
+    }
+}
+
+#[test]
+fn cache_redraws_when_the_style_changes() {
+    let mut cache = HierarchyCache::new();
+    let unicode = cache.render(&paths(), Some("app"));
+    assert!(unicode.contains("└── src"));
+
+    cache.set_style(TreeStyle::Ascii);
+    assert!(!cache.is_cached(&paths(), Some("app")));
+    assert!(cache.render(&paths(), Some("app")).contains("`-- src"));
+}
+
+#[test]
+fn style_is_a_workspace_setting_defaulting_to_unicode() {
+    assert_eq!(WorkspaceSettings::default().tree_style, TreeStyle::Unicode);
+    let ws: WorkspaceSettings = serde_json::from_str(
+        r#"{"version":1,"ext_filter":"","exclude_dirs":"","exclude_files":"","remove_prefix":"","remove_regex":"","hierarchy_only":false,"dirs_only":false,"tree_style":"ascii"}"#,
+    )
+    .unwrap();
+    assert_eq!(ws.tree_style, TreeStyle::Ascii);
+}
+
+#[test]
+fn dash_named_directory_round_trips() {
+    // A directory called "-" must not be mistaken for an ASCII branch
+    let paths = vec!["-/0".to_string()];
+    for style in [TreeStyle::Unicode, TreeStyle::Ascii] {
+        let tree = render_tree_from_paths(&paths, Some("root"), style);
+        let parsed = parse_hierarchy_text(&tree).expect("root line is present");
+        assert!(parsed.contains("-/0"), "{style:?}:\n{tree}");
+    }
+}
@@ -0,0 +1,87 @@
+use std::fs;
+
+use pretty_assertions::assert_eq;
+use stitch::core::{
+    BranchProfile, LocalSettings, branch_from_head, current_git_branch, git_head_file,
+    profile_for_branch,
+};
+use tempfile::TempDir;
+
+fn bind(branch: &str, profile: &str) -> BranchProfile {
+    BranchProfile {
+        branch: branch.into(),
+        profile: profile.into(),
+    }
+}
+
+#[test]
+fn first_matching_pattern_wins() {
+    let bindings = [
+        bind("feature/ui-*", "frontend"),
+        bind("feature/*", "backend"),
+        bind("main", "release"),
+    ];
+    assert_eq!(
+        profile_for_branch(&bindings, "feature/ui-login"),
+        Some("frontend")
+    );
+    assert_eq!(
+        profile_for_branch(&bindings, "feature/api/v2"),
+        Some("backend")
+    );
+    assert_eq!(profile_for_branch(&bindings, "main"), Some("release"));
+    assert_eq!(profile_for_branch(&bindings, "mainline"), None);
+    assert_eq!(profile_for_branch(&[bind("*", " ")], "main"), None);
+}
+
+#[test]
+fn head_contents_name_the_branch() {
+    assert_eq!(
+        branch_from_head("ref: refs/heads/feature/ui-x\n").as_deref(),
+        Some("feature/ui-x")
+    );
+    assert_eq!(
+        branch_from_head("0123456789abcdef0123456789abcdef01234567\n"),
+        None
+    );
+}
+
+#[test]
+fn branch_is_found_from_nested_folders_and_worktrees() {
+    let tmp = TempDir::new().unwrap();
+    let repo = tmp.path().join("repo");
+    fs::create_dir_all(repo.join(".git")).unwrap();
+    fs::create_dir_all(repo.join("app/web")).unwrap();
+    fs::write(repo.join(".git/HEAD"), "ref: refs/heads/main\n").unwrap();
+    assert_eq!(
+        current_git_branch(&repo.join("app/web")).as_deref(),
+        Some("main")
+    );
+    assert_eq!(git_head_file(&repo).unwrap(), repo.join(".git/HEAD"));
+
+    let worktree = tmp.path().join("wt");
+    let wt_git = repo.join(".git/worktrees/wt");
+    fs::create_dir_all(&worktree).unwrap();
+    fs::create_dir_all(&wt_git).unwrap();
+    fs::write(wt_git.join("HEAD"), "ref: refs/heads/feature/ui-x\n").unwrap();
+    fs::write(
+        worktree.join(".git"),
+        format!("gitdir: {}\n", wt_git.display()),
+    )
+    .unwrap();
+    assert_eq!(
+        current_git_branch(&worktree).as_deref(),
+        Some("feature/ui-x")
+    );
+}
+
+#[test]
+fn bindings_live_in_local_settings() {
+    let ls: LocalSettings = serde_json::from_str(
+        r#"{"profile_branches":[{"branch":"feature/ui-*","profile":"frontend"}]}"#,
+    )
+    .unwrap();
+    assert_eq!(ls.profile_branches, [bind("feature/ui-*", "frontend")]);
+    let legacy: LocalSettings = serde_json::from_str("{}").unwrap();
+    assert!(legacy.profile_branches.is_empty());
+}
@@ -0,0 +1,66 @@
+use pretty_assertions::assert_eq;
+
+use stitch::core::{
+    SelectionStats, TrimCandidate, projected_tokens, suggest_trim, trim_candidates,
+};
+
+fn stats() -> SelectionStats {
+    let mut stats = SelectionStats::default();
+    stats.add_file("src/main.rs", 400, 100);
+    stats.add_file("tests/big.rs", 4000, 1000);
+    stats.add_file("README.md", 200, 50);
+    stats.add_file("src/lib.rs", 400, 100);
+    stats
+}
+
+fn candidate(path: &str, tokens: usize) -> TrimCandidate {
+    TrimCandidate {
+        path: path.to_string(),
+        tokens,
+    }
+}
+
+#[test]
+fn candidates_are_sorted_by_tokens_then_path() {
+    assert_eq!(
+        trim_candidates(&stats()),
+        vec![
+            candidate("tests/big.rs", 1000),
+            candidate("src/lib.rs", 100),
+            candidate("src/main.rs", 100),
+            candidate("README.md", 50),
+        ]
+    );
+    assert!(trim_candidates(&SelectionStats::default()).is_empty());
+}
+
+#[test]
+fn projection_subtracts_dropped_files() {
+    let candidates = trim_candidates(&stats());
+    assert_eq!(projected_tokens(1500, &candidates, &[]), 1500);
+    assert_eq!(
+        projected_tokens(1500, &candidates, &[true, false, true, false]),
+        400
+    );
+    // Never below zero, even if the total was estimated differently.
+    assert_eq!(projected_tokens(500, &candidates, &[true, true]), 0);
+}
+
+#[test]
+fn suggestion_drops_the_largest_files_until_it_fits() {
+    let candidates = trim_candidates(&stats());
+    assert_eq!(
+        suggest_trim(1500, &candidates, 450),
+        [true, true, false, false]
+    );
+    assert_eq!(
+        suggest_trim(1500, &candidates, 500),
+        [true, false, false, false]
+    );
+    assert_eq!(
+        suggest_trim(1500, &candidates, 2000),
+        [false, false, false, false]
+    );
+    // Even dropping everything can't fit: all are flagged.
+    assert_eq!(suggest_trim(1500, &candidates, 10), [true; 4]);
+}
@@ -0,0 +1,205 @@
+use pretty_assertions::assert_eq;
+use stitch::core::{
+    CLikeFilterOptions, TransformOptions, TransformPipeline, apply_c_like_filters,
+    is_c_like_file_path,
+};
+
+fn comments_only() -> CLikeFilterOptions {
+    CLikeFilterOptions {
+        remove_line_comments: true,
+        remove_block_comments: true,
+        function_signatures_only: false,
+    }
+}
+
+fn signatures_only() -> CLikeFilterOptions {
+    CLikeFilterOptions {
+        function_signatures_only: true,
+        ..CLikeFilterOptions::default()
+    }
+}
+
+#[test]
+fn detects_c_cpp_java_and_csharp_paths() {
+    use std::path::Path;
+    for path in [
+        "main.c",
+        "lib.h",
+        "app.cpp",
+        "x.HPP",
+        "Main.java",
+        "Program.cs",
+    ] {
+        assert!(is_c_like_file_path(Path::new(path)), "{path}");
+    }
+    assert!(!is_c_like_file_path(Path::new("main.rs")));
+    assert!(!is_c_like_file_path(Path::new("Makefile")));
+}
+
+#[test]
+fn no_options_returns_input() {
+    let src = "// c\nint x;\n";
+    assert_eq!(
+        apply_c_like_filters(src, &CLikeFilterOptions::default()),
+        src
+    );
+}
+
+#[test]
+fn removes_line_and_block_comments() {
+    let src = "/* header\n * more */\n#include <stdio.h>\n\nint x = 1; // trailing\n// own line\nint y = /* inline */ 2;\n";
+    let got = apply_c_like_filters(src, &comments_only());
+    assert_eq!(got, "#include <stdio.h>\n\nint x = 1;\nint y = 2;\n");
+}
+
+#[test]
+fn removes_only_the_selected_comment_kind() {
+    let src = "// line\n/* block */\nint x;\n";
+    let line_only = CLikeFilterOptions {
+        remove_line_comments: true,
+        ..CLikeFilterOptions::default()
+    };
+    assert_eq!(
+        apply_c_like_filters(src, &line_only),
+        "/* block */\nint x;\n"
+    );
+    let block_only = CLikeFilterOptions {
+        remove_block_comments: true,
+        ..CLikeFilterOptions::default()
+    };
+    assert_eq!(apply_c_like_filters(src, &block_only), "// line\nint x;\n");
+}
+
+#[test]
+fn block_comments_do_not_nest() {
+    let src = "/* a /* b */ int x;\n";
+    assert_eq!(apply_c_like_filters(src, &comments_only()), " int x;\n");
+}
+
+#[test]
+fn keeps_comment_markers_inside_literals() {
+    let src = concat!(
+        "const char* url = \"http://example.com /* no */\";\n",
+        "char c = '/';\n",
+        "auto raw = R\"x(// still \" text)x\";\n",
+        "string path = @\"C:\\dir\\\"\"// kept\";\n",
+        "String block = \"\"\"\n  // kept\n  \"\"\";\n",
+        "long big = 1'000'000; // gone\n",
+    );
+    let got = apply_c_like_filters(src, &comments_only());
+    let expected = src.replace(" // gone", "");
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn reduces_function_bodies_to_signatures() {
+    let src = concat!(
+        "#include <vector>\n",
+        "\n",
+        "static int add(int a, int b) {\n",
+        "    if (a > b) { return a; }\n",
+        "    return a + b;\n",
+        "}\n",
+        "\n",
+        "namespace util {\n",
+        "struct Point { int x; int y; };\n",
+        "int Point::norm() const noexcept {\n",
+        "    return x * x + y * y;\n",
+        "}\n",
+        "}\n",
+    );
+    let got = apply_c_like_filters(src, &signatures_only());
+    assert_eq!(
+        got,
+        concat!(
+            "#include <vector>\n",
+            "\n",
+            "static int add(int a, int b);\n",
+            "\n",
+            "namespace util {\n",
+            "struct Point { int x; int y; };\n",
+            "int Point::norm() const noexcept;\n",
+            "}\n",
+        )
+    );
+}
+
+#[test]
+fn brace_initializers_belong_to_the_constructor_header() {
+    let src = concat!(
+        "Foo::Foo(int x) : m_x{x} {\n",
+        "  go();\n",
+        "}\n",
+        "S::S() : a(1), b{2} { init(); }\n",
+        "Box<T>::Box() : items{}, base_t<T>{ {1, 2} } {\n",
+        "  fill();\n",
+        "}\n",
+    );
+    let got = apply_c_like_filters(src, &signatures_only());
+    assert_eq!(
+        got,
+        concat!(
+            "Foo::Foo(int x) : m_x{x};\n",
+            "S::S() : a(1), b{2};\n",
+            "Box<T>::Box() : items{}, base_t<T>{ {1, 2} };\n",
+        )
+    );
+}
+
+#[test]
+fn keeps_java_and_csharp_types_but_drops_method_bodies() {
+    let src = concat!(
+        "@Service\n",
+        "public class Greeter extends Base implements Api {\n",
+        "    private final Map<String, Integer> seen = new HashMap<>() {{ put(\"}\", 1); }};\n",
+        "\n",
+        "    @Override\n",
+        "    public <T> String greet(T name) throws IOException {\n",
+        "        synchronized (this) { return \"hi {\" + name; }\n",
+        "    }\n",
+        "}\n",
+        "\n",
+        "public record Pair(int a, int b) {\n",
+        "    public int Sum() => a + b;\n",
+        "    public static T Max<T>(T x, T y) where T : IComparable<T> { return x; }\n",
+        "}\n",
+    );
+    let got = apply_c_like_filters(src, &signatures_only());
+    assert_eq!(
+        got,
+        concat!(
+            "@Service\n",
+            "public class Greeter extends Base implements Api {\n",
+            "    private final Map<String, Integer> seen = new HashMap<>() {{ put(\"}\", 1); }};\n",
+            "\n",
+            "    @Override\n",
+            "    public <T> String greet(T name) throws IOException;\n",
+            "}\n",
+            "\n",
+            "public record Pair(int a, int b) {\n",
+            "    public int Sum() => a + b;\n",
+            "    public static T Max<T>(T x, T y) where T : IComparable<T>;\n",
+            "}\n",
+        )
+    );
+}
+
+#[test]
+fn default_pipeline_runs_the_filters_on_c_like_files_only() {
+    let opts = TransformOptions {
+        c_like: comments_only(),
+        ..TransformOptions::default()
+    };
+    let pipeline = TransformPipeline::default();
+    let src = "int x; // note\n";
+    assert_eq!(
+        pipeline
+            .apply("src/Main.java", src.to_string(), &opts)
+            .contents,
+        "int x;\n"
+    );
+    assert_eq!(
+        pipeline.apply("notes.txt", src.to_string(), &opts).contents,
+        src
+    );
+}
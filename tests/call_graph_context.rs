@@ -0,0 +1,167 @@
+use std::path::{Path, PathBuf};
+
+use pretty_assertions::assert_eq;
+
+use stitch::core::{call_graph_context, rust_function_names};
+use stitch::testing::write_file;
+
+const MAIN: &str = r#"
+use crate::config::Config;
+use crate::util::normalize as norm;
+
+fn main() {
+    let config = Config::load("app.toml");
+    let name = norm(&config.name);
+    println!("{}", greet(&name));
+}
+
+fn greet(name: &str) -> String {
+    format!("hello {name}")
+}
+
+fn unused() {
+    crate::net::fetch();
+}
+"#;
+
+const CONFIG: &str = r"
+pub struct Config {
+    pub name: String,
+    pub limits: Limits,
+}
+
+pub struct Limits {
+    pub max: usize,
+}
+
+impl Config {
+    pub fn load(path: &str) -> Self {
+        Self::parse(path)
+    }
+
+    fn parse(text: &str) -> Self {
+        Config { name: text.to_string(), limits: Limits { max: DEFAULT_MAX } }
+    }
+}
+
+const DEFAULT_MAX: usize = 10;
+";
+
+const UTIL: &str = r"
+pub fn normalize(s: &str) -> String {
+    s.trim().to_string()
+}
+";
+
+const NET: &str = r"
+pub fn fetch() {}
+";
+
+fn project(root: &Path) -> (PathBuf, Vec<PathBuf>) {
+    let main = write_file(root, "src/main.rs", MAIN).unwrap();
+    let files = vec![
+        write_file(root, "src/config.rs", CONFIG).unwrap(),
+        main.clone(),
+        write_file(root, "src/net.rs", NET).unwrap(),
+        write_file(root, "src/util.rs", UTIL).unwrap(),
+    ];
+    (main, files)
+}
+
+fn rel(root: &Path, files: &[PathBuf]) -> Vec<String> {
+    files
+        .iter()
+        .map(|f| {
+            f.strip_prefix(root)
+                .unwrap()
+                .to_string_lossy()
+                .replace('\\', "/")
+        })
+        .collect()
+}
+
+#[test]
+fn function_names_include_methods_with_their_type() {
+    assert_eq!(rust_function_names(MAIN), ["main", "greet", "unused"]);
+    assert_eq!(
+        rust_function_names(CONFIG),
+        ["Config::load", "Config::parse"]
+    );
+    assert!(rust_function_names("fn broken(").is_empty());
+}
+
+#[test]
+fn context_follows_calls_renames_and_self_across_files() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    let (main, files) = project(root);
+
+    let context = call_graph_context(&files, &main, "main", 5).unwrap();
+    let names: Vec<String> = context
+        .items
+        .iter()
+        .map(|ci| ci.item.qualified_name())
+        .collect();
+    for expected in [
+        "main",
+        "Config",
+        "Config::load",
+        "Config::parse",
+        "Limits",
+        "DEFAULT_MAX",
+        "normalize",
+        "greet",
+    ] {
+        assert!(
+            names.contains(&expected.to_string()),
+            "{expected} in {names:?}"
+        );
+    }
+    assert!(!names.contains(&"unused".to_string()));
+    assert!(!names.contains(&"fetch".to_string()));
+    assert_eq!(context.items[0].depth, 0);
+
+    assert_eq!(
+        rel(root, &context.files),
+        ["src/main.rs", "src/config.rs", "src/util.rs"]
+    );
+}
+
+#[test]
+fn depth_limits_how_far_references_are_followed() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    let (main, files) = project(root);
+
+    let direct = call_graph_context(&files, &main, "main", 1).unwrap();
+    let names: Vec<String> = direct
+        .items
+        .iter()
+        .map(|ci| ci.item.qualified_name())
+        .collect();
+    assert!(names.contains(&"Config::load".to_string()));
+    assert!(!names.contains(&"Config::parse".to_string()));
+
+    let only = call_graph_context(&files, &main, "greet", 0).unwrap();
+    assert_eq!(only.items.len(), 1);
+    assert_eq!(rel(root, &only.files), ["src/main.rs"]);
+}
+
+#[test]
+fn methods_can_be_the_start_and_unknown_functions_give_none() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    let (_, files) = project(root);
+    let config = root.join("src/config.rs");
+
+    let context = call_graph_context(&files, &config, "Config::load", 3).unwrap();
+    assert_eq!(rel(root, &context.files), ["src/config.rs"]);
+    assert!(
+        context
+            .render(root)
+            .starts_with("fn Config::load  (src/config.rs)\n  ")
+    );
+
+    assert!(call_graph_context(&files, &config, "missing", 3).is_none());
+    assert!(call_graph_context(&files, &root.join("src/main.rs"), "Config::load", 3).is_none());
+}
@@ -0,0 +1,106 @@
+use std::{fs, sync::atomic::AtomicBool};
+
+use pretty_assertions::assert_eq;
+use stitch::core::{
+    GenerationRequest, Generator, OutputLayout, OutputParts, PathRewrite, append_checksum_footer,
+    parse_checksum_footer, sha256_hex, verify_checksum_footer,
+};
+use tempfile::TempDir;
+
+#[test]
+fn sha256_matches_the_standard_vectors() {
+    assert_eq!(
+        sha256_hex(b""),
+        "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+    );
+    assert_eq!(
+        sha256_hex(b"abc"),
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+    );
+    // 56 bytes: the padding spills into a second block.
+    assert_eq!(
+        sha256_hex(b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"),
+        "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1"
+    );
+    assert_eq!(
+        sha256_hex(&[b'a'; 1000]),
+        "41edece42d63e8d9bf515a9ba6932e1c20cbc9f5a5d134645adb5db1b9737ea3"
+    );
+}
+
+#[test]
+fn footer_round_trips_and_catches_edits() {
+    let mut text = "=== FILE CONTENTS ===\n\nbody\n".to_string();
+    append_checksum_footer(&mut text, &[("src/a.rs", "block a")]);
+
+    let footer = verify_checksum_footer(&text).unwrap();
+    assert_eq!(
+        footer.content,
+        sha256_hex(b"=== FILE CONTENTS ===\n\nbody\n")
+    );
+    assert_eq!(
+        footer.files,
+        vec![(sha256_hex(b"block a"), "src/a.rs".to_string())]
+    );
+    assert!(text.ends_with(&format!("{}  src/a.rs\n", sha256_hex(b"block a"))));
+
+    let edited = text.replacen("body", "bodY", 1);
+    assert_eq!(
+        verify_checksum_footer(&edited).unwrap_err(),
+        "checksum mismatch: the output was truncated or modified"
+    );
+    // A bundle cut off in transit loses its footer.
+    assert_eq!(
+        verify_checksum_footer(&text[..20]).unwrap_err(),
+        "no checksum footer"
+    );
+    let (body, _) = parse_checksum_footer(&text).unwrap();
+    assert_eq!(body, "=== FILE CONTENTS ===\n\nbody\n");
+}
+
+#[test]
+fn generation_appends_the_footer_when_enabled() {
+    let tmp = TempDir::new().unwrap();
+    let file = tmp.path().join("a.txt");
+    fs::write(&file, "ay\n").unwrap();
+    let mut request = GenerationRequest::new(tmp.path(), vec![file], OutputParts::default());
+    request.layout = OutputLayout {
+        checksum_footer: true,
+        ..OutputLayout::default()
+    };
+    let out = Generator::new(request)
+        .run(&AtomicBool::new(false), |_| {})
+        .unwrap();
+
+    let footer = verify_checksum_footer(&out.text).unwrap();
+    let (path, block) = &out.split.blocks[0];
+    assert_eq!(
+        footer.files,
+        vec![(sha256_hex(block.as_bytes()), path.clone())]
+    );
+}
+
+#[test]
+fn footer_lists_the_paths_shown_in_the_headers() {
+    let tmp = TempDir::new().unwrap();
+    fs::create_dir_all(tmp.path().join("src")).unwrap();
+    let file = tmp.path().join("src").join("a.txt");
+    fs::write(&file, "ay\n").unwrap();
+    let mut request = GenerationRequest::new(tmp.path(), vec![file], OutputParts::default());
+    request.layout = OutputLayout {
+        checksum_footer: true,
+        ..OutputLayout::default()
+    };
+    request.path_rewrite = PathRewrite {
+        strip_prefix: "src/".into(),
+        prepend: "lib/".into(),
+    };
+    let out = Generator::new(request)
+        .run(&AtomicBool::new(false), |_| {})
+        .unwrap();
+
+    let footer = verify_checksum_footer(&out.text).unwrap();
+    assert_eq!(footer.files.len(), 1);
+    assert_eq!(footer.files[0].1, "lib/a.txt");
+    assert!(out.text.contains("lib/a.txt"));
+}
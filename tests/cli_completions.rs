@@ -0,0 +1,66 @@
+use stitch::core::{Shell, render_completions, render_man_page, usage};
+
+const OPTIONS: [&str; 5] = ["open", "profile", "select", "deselect", "generate"];
+
+#[test]
+fn usage_lists_every_option_and_command() {
+    let text = usage();
+    for opt in OPTIONS {
+        assert!(text.contains(&format!("--{opt}")), "{opt}");
+    }
+    for command in [
+        "apply",
+        "completions",
+        "integrate",
+        "man",
+        "run",
+        "schema",
+        "stats",
+    ] {
+        assert!(text.contains(command), "{command}");
+    }
+}
+
+#[test]
+fn every_script_completes_every_option_and_command() {
+    for shell in Shell::ALL {
+        let script = render_completions(shell);
+        for opt in OPTIONS {
+            assert!(script.contains(opt), "{shell:?} misses --{opt}");
+        }
+        for word in [
+            "completions",
+            "integrate",
+            "man",
+            "schema",
+            "workspace",
+            "bash",
+            "zsh",
+            "fish",
+            "macos",
+            "windows",
+        ] {
+            assert!(script.contains(word), "{shell:?} misses {word}");
+        }
+    }
+}
+
+#[test]
+fn scripts_use_each_shell_registration() {
+    assert!(render_completions(Shell::Bash).contains("complete -F _stitch "));
+    assert!(render_completions(Shell::Zsh).starts_with("#compdef stitch\n"));
+    let fish = render_completions(Shell::Fish);
+    assert!(fish.contains("complete -c stitch "));
+    assert!(fish.contains(" -l profile "));
+}
+
+#[test]
+fn man_page_is_roff_with_escaped_dashes() {
+    let page = render_man_page("1.2.3");
+    assert!(page.contains(".TH stitch 1  \"stitch 1.2.3\""));
+    assert!(page.contains(".SH OPTIONS\n"));
+    assert!(page.contains("\\fB\\-h\\fR, \\fB\\-\\-help\\fR\n"));
+    assert!(page.contains("\\fB\\-\\-profile\\fR \\fI<NAME>\\fR\n"));
+    assert!(page.contains("stitch\\-completions(1)\n"));
+    assert!(!page.contains(" --"));
+}
@@ -0,0 +1,259 @@
+use std::path::PathBuf;
+
+use stitch::core::{CliCommand, LaunchOptions, Platform, Shell, parse_launch_args};
+
+fn parse(args: &[&str]) -> Result<LaunchOptions, String> {
+    parse_launch_args(args.iter().copied())
+}
+
+#[test]
+fn no_arguments_opens_an_empty_window() {
+    assert_eq!(parse(&[]).unwrap(), LaunchOptions::default());
+}
+
+#[test]
+fn project_profile_and_generate() {
+    let opts = parse(&["/work/app", "--profile", "backend", "--generate"]).unwrap();
+    assert_eq!(opts.project, Some(PathBuf::from("/work/app")));
+    assert_eq!(opts.profile.as_deref(), Some("backend"));
+    assert!(opts.generate);
+
+    let opts = parse(&["--profile=api docs", "."]).unwrap();
+    assert_eq!(opts.profile.as_deref(), Some("api docs"));
+    assert_eq!(opts.project, Some(PathBuf::from(".")));
+}
+
+#[test]
+fn double_dash_allows_dash_prefixed_folders() {
+    let opts = parse(&["--", "-weird"]).unwrap();
+    assert_eq!(opts.project, Some(PathBuf::from("-weird")));
+}
+
+#[test]
+fn help_and_version_flags() {
+    assert!(parse(&["-h"]).unwrap().help);
+    assert!(parse(&["--version"]).unwrap().version);
+    assert!(parse(&["--profile", "x", "--help"]).is_ok());
+}
+
+#[test]
+fn invalid_usage_is_rejected() {
+    assert_eq!(
+        parse(&["--profile"]).unwrap_err(),
+        "a value is required for '--profile <NAME>' but none was supplied"
+    );
+    assert_eq!(
+        parse(&["--profile", "backend"]).unwrap_err(),
+        "--profile requires a project directory"
+    );
+    assert_eq!(
+        parse(&["--frobnicate"]).unwrap_err(),
+        "unexpected argument '--frobnicate' found"
+    );
+    assert_eq!(
+        parse(&["a", "b"]).unwrap_err(),
+        "the subcommand 'b' cannot be used with '[PROJECT_DIR]'"
+    );
+}
+
+#[test]
+fn macos_process_serial_number_is_ignored() {
+    assert_eq!(parse(&["-psn_0_12345"]).unwrap(), LaunchOptions::default());
+}
+
+#[test]
+fn completions_and_man_commands() {
+    let opts = parse(&["completions", "zsh"]).unwrap();
+    assert_eq!(opts.command, Some(CliCommand::Completions(Shell::Zsh)));
+    assert_eq!(opts.project, None);
+    assert_eq!(parse(&["man"]).unwrap().command, Some(CliCommand::Man));
+
+    // Only as the first positional; paths with a slash stay folders.
+    let opts = parse(&["./man"]).unwrap();
+    assert_eq!(opts.command, None);
+    assert_eq!(opts.project, Some(PathBuf::from("./man")));
+    let opts = parse(&["--", "man"]).unwrap();
+    assert_eq!(opts.project, Some(PathBuf::from("man")));
+    assert_eq!(
+        parse(&["/work/app", "man"]).unwrap_err(),
+        "the subcommand 'man' cannot be used with '[PROJECT_DIR]'"
+    );
+}
+
+#[test]
+fn completions_need_a_known_shell() {
+    assert_eq!(
+        parse(&["completions"]).unwrap_err(),
+        "the following required arguments were not provided: <SHELL>"
+    );
+    assert_eq!(
+        parse(&["completions", "tcsh"]).unwrap_err(),
+        "invalid value 'tcsh' for '<SHELL>' [possible values: bash, zsh, fish]"
+    );
+    assert_eq!(
+        parse(&["man", "extra"]).unwrap_err(),
+        "unexpected argument 'extra' found"
+    );
+}
+
+#[test]
+fn open_accepts_paths_and_urls() {
+    let opts = parse(&["--open", "/work/app"]).unwrap();
+    assert_eq!(opts.project, Some(PathBuf::from("/work/app")));
+
+    let opts = parse(&["--open=file:///work/my%20app"]).unwrap();
+    assert_eq!(opts.project, Some(PathBuf::from("/work/my app")));
+
+    let opts = parse(&["stitch://open?path=%2Fwork%2Fapp&profile=api&generate"]).unwrap();
+    assert_eq!(opts.project, Some(PathBuf::from("/work/app")));
+    assert_eq!(opts.profile.as_deref(), Some("api"));
+    assert!(opts.generate);
+
+    // Flags on the command line win over the URL's.
+    let opts = parse(&[
+        "--profile",
+        "docs",
+        "--open",
+        "stitch://open?path=/work/app&profile=api",
+    ])
+    .unwrap();
+    assert_eq!(opts.profile.as_deref(), Some("docs"));
+}
+
+#[test]
+fn open_rejects_bad_targets() {
+    assert_eq!(
+        parse(&["--open"]).unwrap_err(),
+        "a value is required for '--open <PATH|URL>' but none was supplied"
+    );
+    assert_eq!(
+        parse(&["/work/app", "--open", "/work/other"]).unwrap_err(),
+        "unexpected argument '/work/other'"
+    );
+    assert_eq!(
+        parse(&["stitch://open"]).unwrap_err(),
+        "URL 'stitch://open' has no path"
+    );
+    assert_eq!(
+        parse(&["--open", "stitch://delete?path=/"]).unwrap_err(),
+        "unsupported URL 'stitch://delete?path=/' (expected stitch://open?path=…)"
+    );
+}
+
+#[test]
+fn integrate_command_needs_a_known_platform() {
+    assert_eq!(
+        parse(&["integrate", "windows"]).unwrap().command,
+        Some(CliCommand::Integrate(Platform::Windows))
+    );
+    assert_eq!(
+        parse(&["integrate"]).unwrap_err(),
+        "the following required arguments were not provided: <PLATFORM>"
+    );
+    assert_eq!(
+        parse(&["integrate", "beos"]).unwrap_err(),
+        "invalid value 'beos' for '<PLATFORM>' [possible values: macos, windows, linux]"
+    );
+}
+
+#[test]
+fn select_and_deselect_collect_project_relative_paths() {
+    let opts = parse(&[
+        "/work/app",
+        "--select",
+        "src",
+        "--deselect=./src/generated/",
+        "--select=.",
+    ])
+    .unwrap();
+    assert_eq!(opts.select, ["src", ""]);
+    assert_eq!(opts.deselect, ["src/generated"]);
+
+    assert_eq!(
+        parse(&["/work/app", "--select"]).unwrap_err(),
+        "a value is required for '--select <PATH>' but none was supplied"
+    );
+    assert_eq!(
+        parse(&["/work/app", "--deselect="]).unwrap_err(),
+        "a value is required for '--deselect <PATH>' but none was supplied"
+    );
+    assert_eq!(
+        parse(&["--select", "src"]).unwrap_err(),
+        "--select and --deselect require a project directory"
+    );
+}
+
+#[test]
+fn run_takes_a_task_and_an_optional_project() {
+    let opts = parse(&["run", "review"]).unwrap();
+    assert_eq!(opts.command, Some(CliCommand::Run("review".into())));
+    assert_eq!(opts.project, None);
+
+    let opts = parse(&["run", "review", "/work/app"]).unwrap();
+    assert_eq!(opts.project, Some(PathBuf::from("/work/app")));
+
+    assert_eq!(
+        parse(&["run"]),
+        Err("the following required arguments were not provided: <TASK>".into())
+    );
+    assert_eq!(
+        parse(&["run", "review", "--profile", "p"]),
+        Err("run only takes TASK and PROJECT_DIR".into())
+    );
+    assert!(parse(&["run", "review", "a", "b"]).is_err());
+}
+
+#[test]
+fn stats_takes_an_optional_project_and_profile() {
+    let opts = parse(&["stats"]).unwrap();
+    assert_eq!(opts.command, Some(CliCommand::Stats));
+    assert_eq!(opts.project, None);
+
+    let opts = parse(&["stats", "/work/app", "--profile", "backend"]).unwrap();
+    assert_eq!(opts.command, Some(CliCommand::Stats));
+    assert_eq!(opts.project, Some(PathBuf::from("/work/app")));
+    assert_eq!(opts.profile.as_deref(), Some("backend"));
+    assert_eq!(parse(&["stats", "--profile", "p"]).unwrap().project, None);
+
+    assert_eq!(
+        parse(&["stats", "--generate"]),
+        Err("unexpected argument '--generate' found".into())
+    );
+    assert!(parse(&["stats", "a", "b"]).is_err());
+    assert_eq!(
+        parse(&["./stats"]).unwrap().project,
+        Some(PathBuf::from("./stats"))
+    );
+}
+
+#[test]
+fn apply_takes_a_response_an_optional_project_and_yes() {
+    let opts = parse(&["apply", "answer.md"]).unwrap();
+    assert_eq!(
+        opts.command,
+        Some(CliCommand::Apply {
+            response: PathBuf::from("answer.md"),
+            yes: false,
+        })
+    );
+    assert_eq!(opts.project, None);
+
+    let opts = parse(&["apply", "-", "/work/app", "--yes"]).unwrap();
+    assert_eq!(
+        opts.command,
+        Some(CliCommand::Apply {
+            response: PathBuf::from("-"),
+            yes: true,
+        })
+    );
+    assert_eq!(opts.project, Some(PathBuf::from("/work/app")));
+
+    assert_eq!(
+        parse(&["apply"]),
+        Err("the following required arguments were not provided: <RESPONSE>".into())
+    );
+    assert_eq!(
+        parse(&["apply", "answer.md", "--profile", "p"]),
+        Err("apply only takes RESPONSE, PROJECT_DIR and --yes".into())
+    );
+}
@@ -0,0 +1,102 @@
+use std::{
+    io::{Cursor, Read},
+    path::Path,
+};
+
+use flate2::read::GzDecoder;
+use pretty_assertions::assert_eq;
+
+use stitch::core::{
+    CompressedFormat, ZIP_HIERARCHY_ENTRY, ZIP_OUTPUT_ENTRY, compress_bundle, compressed_size,
+    gzip_text, zip_entries,
+};
+
+/// `(name, contents)` of every entry, in archive order.
+fn read_zip(bytes: &[u8]) -> Vec<(String, String)> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(bytes)).unwrap();
+    (0..archive.len())
+        .map(|i| {
+            let mut entry = archive.by_index(i).unwrap();
+            let mut contents = String::new();
+            entry.read_to_string(&mut contents).unwrap();
+            (entry.name().unwrap().into_owned(), contents)
+        })
+        .collect()
+}
+
+#[test]
+fn gzip_round_trips_and_is_smaller_for_repetitive_text() {
+    let text = "fn main() {}\n".repeat(500);
+    let bytes = gzip_text(&text).unwrap();
+    assert!(bytes.len() < text.len() / 10);
+
+    let mut back = String::new();
+    GzDecoder::new(bytes.as_slice())
+        .read_to_string(&mut back)
+        .unwrap();
+    assert_eq!(back, text);
+}
+
+#[test]
+fn zip_holds_output_and_hierarchy_as_separate_entries() {
+    let bytes = compress_bundle(
+        CompressedFormat::Zip,
+        "output ✓\n",
+        Some("root/\n└── a.rs\n"),
+    )
+    .unwrap();
+    assert_eq!(
+        read_zip(&bytes),
+        vec![
+            (ZIP_OUTPUT_ENTRY.to_string(), "output ✓\n".to_string()),
+            (
+                ZIP_HIERARCHY_ENTRY.to_string(),
+                "root/\n└── a.rs\n".to_string()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn zip_without_hierarchy_and_empty_archives() {
+    let bytes = compress_bundle(CompressedFormat::Zip, "only", None).unwrap();
+    assert_eq!(
+        read_zip(&bytes),
+        vec![(ZIP_OUTPUT_ENTRY.to_string(), "only".to_string())]
+    );
+    assert!(read_zip(&zip_entries(&[]).unwrap()).is_empty());
+}
+
+#[test]
+fn archives_are_reproducible_and_sized_by_compressed_size() {
+    let text = "line\n".repeat(100);
+    for format in [CompressedFormat::Gzip, CompressedFormat::Zip] {
+        let a = compress_bundle(format, &text, Some("tree")).unwrap();
+        let b = compress_bundle(format, &text, Some("tree")).unwrap();
+        assert_eq!(a, b);
+        assert_eq!(
+            compressed_size(format, &text, Some("tree")).unwrap(),
+            a.len()
+        );
+    }
+}
+
+#[test]
+fn format_follows_the_chosen_extension() {
+    assert_eq!(
+        CompressedFormat::from_path(Path::new("out/stitch.zip")),
+        CompressedFormat::Zip
+    );
+    assert_eq!(
+        CompressedFormat::from_path(Path::new("out/Stitch.ZIP")),
+        CompressedFormat::Zip
+    );
+    assert_eq!(
+        CompressedFormat::from_path(Path::new("out/stitch.md.gz")),
+        CompressedFormat::Gzip
+    );
+    assert_eq!(
+        CompressedFormat::from_path(Path::new("stitch")),
+        CompressedFormat::Gzip
+    );
+}
@@ -0,0 +1,139 @@
+use pretty_assertions::assert_eq;
+use std::{
+    fs,
+    path::PathBuf,
+    time::{Duration, SystemTime},
+};
+use stitch::core::{
+    ContentCache, FileStamp, RustFilterOptions, TransformOptions, TransformPipeline,
+    content_cache_file, load_content_cache, save_content_cache, transform_config_key,
+};
+use tempfile::tempdir;
+
+fn stamp(secs: u64, len: u64) -> FileStamp {
+    FileStamp {
+        modified: SystemTime::UNIX_EPOCH + Duration::from_secs(secs),
+        len,
+    }
+}
+
+#[test]
+fn entries_are_reused_only_for_the_same_stamp() {
+    let mut cache = ContentCache::new();
+    cache.begin(1);
+    let path = PathBuf::from("/p/src/lib.rs");
+    cache.insert(path.clone(), stamp(10, 5), "fn a() {}".into());
+
+    assert_eq!(cache.get(&path, stamp(10, 5)).as_deref(), Some("fn a() {}"));
+    assert_eq!(cache.get(&path, stamp(11, 5)), None);
+    assert_eq!(cache.get(&path, stamp(10, 6)), None);
+    assert_eq!(
+        cache.get(&PathBuf::from("/p/src/main.rs"), stamp(10, 5)),
+        None
+    );
+}
+
+#[test]
+fn a_new_transform_config_drops_all_entries() {
+    let mut cache = ContentCache::new();
+    cache.begin(1);
+    cache.insert("/p/a.rs".into(), stamp(1, 1), "a".into());
+    cache.begin(1);
+    assert_eq!(cache.len(), 1);
+    cache.begin(2);
+    assert!(cache.is_empty());
+}
+
+#[test]
+fn trimming_keeps_entries_used_by_the_current_generation() {
+    let mut cache = ContentCache::new();
+    cache.begin(1);
+    cache.insert("/p/old.rs".into(), stamp(1, 3), "old".into());
+    cache.insert("/p/kept.rs".into(), stamp(1, 4), "kept".into());
+    cache.begin(1);
+    assert!(
+        cache
+            .get(&PathBuf::from("/p/kept.rs"), stamp(1, 4))
+            .is_some()
+    );
+
+    cache.trim(100);
+    assert_eq!(cache.len(), 2);
+    cache.trim(5);
+    assert_eq!(cache.len(), 1);
+    assert_eq!(cache.total_bytes(), 4);
+}
+
+#[test]
+fn config_key_follows_pipeline_and_options() {
+    let pipeline = TransformPipeline::default();
+    let opts = TransformOptions::default();
+    let base = transform_config_key(&pipeline, &opts, false);
+    assert_eq!(transform_config_key(&pipeline, &opts, false), base);
+    assert_ne!(transform_config_key(&pipeline, &opts, true), base);
+
+    let prefixes = TransformOptions {
+        remove_prefixes: vec!["//".into()],
+        ..TransformOptions::default()
+    };
+    assert_ne!(transform_config_key(&pipeline, &prefixes, false), base);
+
+    let comments = TransformOptions {
+        rust: RustFilterOptions {
+            remove_doc_comments: true,
+            ..RustFilterOptions::default()
+        },
+        ..TransformOptions::default()
+    };
+    assert_ne!(transform_config_key(&pipeline, &comments, false), base);
+
+    let empty = TransformPipeline { steps: Vec::new() };
+    assert_ne!(transform_config_key(&empty, &opts, false), base);
+}
+
+#[test]
+fn config_key_is_stable_across_builds() {
+    // The key is saved with the cache; it must not depend on the compiler's hasher.
+    let pipeline = TransformPipeline { steps: Vec::new() };
+    let opts = TransformOptions {
+        working_dir: PathBuf::from("/project"),
+        ..TransformOptions::default()
+    };
+    assert_eq!(
+        transform_config_key(&pipeline, &opts, false),
+        16_832_533_060_179_725_174
+    );
+}
+
+#[test]
+fn file_stamp_changes_when_the_file_is_rewritten() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("a.txt");
+    fs::write(&path, "one").unwrap();
+    let first = FileStamp::of(&path).expect("stamp");
+    assert_eq!(first.len, 3);
+    fs::write(&path, "three").unwrap();
+    assert_ne!(FileStamp::of(&path), Some(first));
+    assert_eq!(FileStamp::of(&dir.path().join("missing.txt")), None);
+}
+
+#[test]
+fn saved_cache_loads_back() {
+    let dir = tempdir().unwrap();
+    assert!(load_content_cache(dir.path()).is_empty());
+
+    let mut cache = ContentCache::new();
+    cache.begin(7);
+    let path = dir.path().join("a.rs");
+    cache.insert(path.clone(), stamp(3, 2), "ab".into());
+    assert!(cache.take_changed());
+    assert!(!cache.take_changed());
+    save_content_cache(dir.path(), &cache).unwrap();
+    assert!(content_cache_file(dir.path()).is_file());
+
+    let mut loaded = load_content_cache(dir.path());
+    loaded.begin(7);
+    assert_eq!(loaded.get(&path, stamp(3, 2)).as_deref(), Some("ab"));
+    loaded.begin(8);
+    assert!(loaded.is_empty());
+}
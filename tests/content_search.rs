@@ -0,0 +1,99 @@
+use std::path::{Path, PathBuf};
+
+use pretty_assertions::assert_eq;
+
+use stitch::core::{ContentMatch, ContentQuery, search_file_contents};
+use stitch::testing::write_file;
+
+fn project(root: &Path) -> Vec<PathBuf> {
+    vec![
+        write_file(root, "src/a.rs", b"fn parse() {}\n// TODO: parse more\n").unwrap(),
+        write_file(root, "src/b.rs", b"fn run() { Parse::new(); }\n").unwrap(),
+        write_file(root, "docs/notes.md", b"nothing here\n").unwrap(),
+        write_file(root, "assets/logo.bin", [0xff_u8, 0xfe, 0x00, 0x70]).unwrap(),
+        root.join("gone.rs"),
+    ]
+}
+
+fn found(root: &Path, report: &[ContentMatch]) -> Vec<(String, usize)> {
+    report
+        .iter()
+        .map(|m| {
+            let rel = m.path.strip_prefix(root).unwrap().to_string_lossy();
+            (rel.replace('\\', "/"), m.matches)
+        })
+        .collect()
+}
+
+#[test]
+fn literal_search_ignores_case_by_default() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    let files = project(root);
+
+    let query = ContentQuery::new("parse", false, false).unwrap();
+    let report = search_file_contents(&files, &query);
+    assert_eq!(
+        found(root, &report.files),
+        vec![("src/a.rs".to_string(), 2), ("src/b.rs".to_string(), 1)]
+    );
+    assert_eq!(report.total_matches(), 3);
+    assert_eq!(report.searched, 3);
+    assert_eq!(report.skipped, 2);
+
+    let query = ContentQuery::new("Parse", false, true).unwrap();
+    let report = search_file_contents(&files, &query);
+    assert_eq!(
+        found(root, &report.files),
+        vec![("src/b.rs".to_string(), 1)]
+    );
+}
+
+#[test]
+fn literal_search_escapes_regex_syntax_and_regex_mode_does_not() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    let files = project(root);
+
+    let literal = ContentQuery::new("parse()", false, false).unwrap();
+    assert_eq!(search_file_contents(&files, &literal).files.len(), 1);
+
+    let anchored = ContentQuery::new(r"^fn \w+", true, true).unwrap();
+    let report = search_file_contents(&files, &anchored);
+    assert_eq!(
+        found(root, &report.files),
+        vec![("src/a.rs".to_string(), 1), ("src/b.rs".to_string(), 1)]
+    );
+}
+
+#[test]
+fn invalid_and_empty_patterns_are_rejected() {
+    assert!(
+        ContentQuery::new("(", true, false)
+            .unwrap_err()
+            .starts_with("invalid regex")
+    );
+    assert!(ContentQuery::new("(", false, false).is_ok());
+    assert_eq!(
+        ContentQuery::new("", false, false).unwrap_err(),
+        "empty search"
+    );
+}
+
+#[test]
+fn report_renders_counts_relative_to_the_project() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    let files = project(root);
+
+    let query = ContentQuery::new("TODO", false, true).unwrap();
+    let report = search_file_contents(&files, &query);
+    assert_eq!(
+        report.render(root),
+        "1 match in 1 of 3 files (2 unreadable)\n     1  src/a.rs\n"
+    );
+    assert_eq!(
+        search_file_contents(&[], &query).render(root),
+        "0 matches in 0 of 0 files\n"
+    );
+}
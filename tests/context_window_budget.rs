@@ -0,0 +1,73 @@
+use stitch::core::{ContextWindow, OVER_BUDGET_LARGEST_FILES, WorkspaceSettings, over_budget_note};
+
+fn files(n: usize) -> Vec<(String, usize)> {
+    (0..n).map(|i| (format!("src/f{i}.rs"), i * 1000)).collect()
+}
+
+#[test]
+fn presets_have_their_sizes_and_round_trip_indices() {
+    assert_eq!(ContextWindow::Off.tokens(), None);
+    assert_eq!(ContextWindow::K128.tokens(), Some(128_000));
+    assert_eq!(ContextWindow::K200.tokens(), Some(200_000));
+    assert_eq!(ContextWindow::M1.tokens(), Some(1_000_000));
+    for w in [
+        ContextWindow::Off,
+        ContextWindow::K128,
+        ContextWindow::K200,
+        ContextWindow::M1,
+    ] {
+        assert_eq!(ContextWindow::from_index(w.index()), w);
+    }
+    assert_eq!(ContextWindow::from_index(42), ContextWindow::Off);
+}
+
+#[test]
+fn only_output_above_the_window_exceeds_it() {
+    assert!(!ContextWindow::Off.is_exceeded_by(usize::MAX));
+    assert!(!ContextWindow::K128.is_exceeded_by(128_000));
+    assert!(ContextWindow::K128.is_exceeded_by(128_001));
+}
+
+#[test]
+fn no_note_within_budget() {
+    assert_eq!(
+        over_budget_note(128_000, ContextWindow::K128, &files(3)),
+        None
+    );
+    assert_eq!(
+        over_budget_note(5_000_000, ContextWindow::Off, &files(3)),
+        None
+    );
+}
+
+#[test]
+fn note_lists_overshoot_and_largest_files_first() {
+    let note = over_budget_note(150_000, ContextWindow::K128, &files(8)).unwrap();
+    assert!(note.starts_with("Over the 128k context window: ~150000 tokens (~22000 too many)."));
+    let listed: Vec<&str> = note.lines().filter(|l| l.starts_with("- ")).collect();
+    assert_eq!(listed.len(), OVER_BUDGET_LARGEST_FILES);
+    assert_eq!(listed[0], "- src/f7.rs (~7000 tokens)");
+    assert_eq!(listed[4], "- src/f3.rs (~3000 tokens)");
+    assert!(note.contains("To trim:"));
+
+    let note = over_budget_note(2_000_000, ContextWindow::M1, &[]).unwrap();
+    assert!(!note.contains("Largest files"));
+}
+
+#[test]
+fn preset_is_saved_with_the_profile_settings() {
+    let ws = WorkspaceSettings {
+        context_window: ContextWindow::K200,
+        ..WorkspaceSettings::default()
+    };
+    let json = serde_json::to_string(&ws).unwrap();
+    assert!(json.contains(r#""context_window":"200k""#), "{json}");
+    let back: WorkspaceSettings = serde_json::from_str(&json).unwrap();
+    assert_eq!(back.context_window, ContextWindow::K200);
+
+    // Settings saved before the preset existed have no budget.
+    let mut value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    value.as_object_mut().unwrap().remove("context_window");
+    let old: WorkspaceSettings = serde_json::from_value(value).unwrap();
+    assert_eq!(old.context_window, ContextWindow::Off);
+}
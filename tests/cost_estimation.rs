@@ -0,0 +1,74 @@
+use std::fs;
+
+use stitch::core::{PriceTable, estimate_cost, format_cost, load_price_table, prices_file};
+
+fn table(model: &str, prices: &[(&str, f64)]) -> PriceTable {
+    PriceTable {
+        model: model.to_string(),
+        prices: prices.iter().map(|(m, p)| ((*m).to_string(), *p)).collect(),
+    }
+}
+
+#[test]
+fn cost_scales_with_price_per_million_tokens() {
+    let t = table("gpt-4o", &[("gpt-4o", 2.5), ("mini", 0.15)]);
+    let cost = estimate_cost(172_000, "gpt-4o", &t).unwrap();
+    assert!((cost - 0.43).abs() < 1e-9, "{cost}");
+    let cost = estimate_cost(1_000_000, "mini", &t).unwrap();
+    assert!((cost - 0.15).abs() < 1e-9, "{cost}");
+    assert_eq!(estimate_cost(0, "gpt-4o", &t), Some(0.0));
+}
+
+#[test]
+fn unknown_models_and_bad_prices_have_no_cost() {
+    let t = table("", &[("neg", -1.0), ("nan", f64::NAN)]);
+    assert_eq!(estimate_cost(1000, "gpt-4o", &t), None);
+    assert_eq!(estimate_cost(1000, "neg", &t), None);
+    assert_eq!(estimate_cost(1000, "nan", &t), None);
+    assert_eq!(format_cost(1000, &t), None);
+}
+
+#[test]
+fn label_names_the_selected_model() {
+    let t = table("gpt-4o", &[("gpt-4o", 2.5)]);
+    assert_eq!(format_cost(172_000, &t).as_deref(), Some("$0.43 @ gpt-4o"));
+    assert_eq!(format_cost(100, &t).as_deref(), Some("<$0.01 @ gpt-4o"));
+    assert_eq!(format_cost(0, &t).as_deref(), Some("$0.00 @ gpt-4o"));
+}
+
+#[test]
+fn defaults_price_the_default_model() {
+    let t = PriceTable::default();
+    assert!(t.prices.contains_key(&t.model));
+    assert!(format_cost(1000, &t).is_some());
+}
+
+#[test]
+fn price_table_is_read_from_the_workspace() {
+    let dir = tempfile::tempdir().unwrap();
+    assert_eq!(load_price_table(dir.path()), None);
+
+    let path = prices_file(dir.path());
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(
+        &path,
+        r#"{ "model": "llama-3-70b", "prices": { "llama-3-70b": 0.59 } }"#,
+    )
+    .unwrap();
+    let t = load_price_table(dir.path()).unwrap();
+    assert_eq!(
+        format_cost(1_000_000, &t).as_deref(),
+        Some("$0.59 @ llama-3-70b")
+    );
+
+    // Only switching the model keeps the built-in prices.
+    fs::write(&path, r#"{ "model": "claude-sonnet-4" }"#).unwrap();
+    let t = load_price_table(dir.path()).unwrap();
+    assert_eq!(
+        format_cost(1_000_000, &t).as_deref(),
+        Some("$3.00 @ claude-sonnet-4")
+    );
+
+    fs::write(&path, "not json").unwrap();
+    assert_eq!(load_price_table(dir.path()), None);
+}
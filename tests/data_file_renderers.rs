@@ -0,0 +1,151 @@
+use pretty_assertions::assert_eq;
+
+use stitch::core::{
+    DEFAULT_TABLE_ROWS, TransformKind, TransformOptions, TransformPipeline, TransformStep,
+    notebook_cells, table_head,
+};
+
+fn csv(rows: usize) -> String {
+    let mut text = String::from("id,name\n");
+    for i in 1..=rows {
+        text.push_str(&format!("{i},row{i}\n"));
+    }
+    text
+}
+
+#[test]
+fn small_tables_are_unchanged() {
+    assert_eq!(table_head(&csv(3), 3), csv(3));
+    assert_eq!(table_head("a\tb", 0), "a\tb");
+    assert_eq!(table_head("", 5), "");
+}
+
+#[test]
+fn large_tables_keep_header_and_first_rows() {
+    assert_eq!(
+        table_head(&csv(5), 2),
+        "id,name\n1,row1\n2,row2\n[... 3 more rows omitted; 5 data rows in total]\n"
+    );
+    assert_eq!(
+        table_head(&csv(2), 1),
+        "id,name\n1,row1\n[... 1 more row omitted; 2 data rows in total]\n"
+    );
+}
+
+#[test]
+fn quoted_newlines_stay_in_one_row() {
+    let text = "id,note\n1,\"two\nlines\"\n2,\"say \"\"hi\"\"\"\n3,x\n";
+    assert_eq!(
+        table_head(text, 2),
+        "id,note\n1,\"two\nlines\"\n2,\"say \"\"hi\"\"\"\n[... 1 more row omitted; 3 data rows in total]\n"
+    );
+}
+
+const NOTEBOOK: &str = r##"{
+  "cells": [
+    {"cell_type": "markdown", "metadata": {}, "source": ["# Title\n", "\n", "Some text"]},
+    {"cell_type": "code", "execution_count": 1, "metadata": {}, "outputs": [{"output_type": "stream", "text": ["noise\n"]}],
+     "source": ["import pandas as pd\n", "df = pd.read_csv('x.csv')"]},
+    {"cell_type": "raw", "metadata": {}, "source": "raw"},
+    {"cell_type": "code", "metadata": {}, "outputs": [], "source": "df.head()\n"}
+  ],
+  "metadata": {},
+  "nbformat": 4,
+  "nbformat_minor": 5
+}"##;
+
+#[test]
+fn notebook_code_cells_become_a_percent_script() {
+    assert_eq!(
+        notebook_cells(NOTEBOOK, false).unwrap(),
+        "# %%\nimport pandas as pd\ndf = pd.read_csv('x.csv')\n\n# %%\ndf.head()\n\n"
+    );
+}
+
+#[test]
+fn notebook_markdown_cells_are_optional_comments() {
+    assert_eq!(
+        notebook_cells(NOTEBOOK, true).unwrap(),
+        "# %% [markdown]\n# # Title\n#\n# Some text\n\n\
+         # %%\nimport pandas as pd\ndf = pd.read_csv('x.csv')\n\n# %%\ndf.head()\n\n"
+    );
+}
+
+#[test]
+fn invalid_notebooks_are_left_alone() {
+    assert_eq!(notebook_cells("not json", false), None);
+}
+
+#[test]
+fn default_pipeline_leaves_tables_and_notebooks_alone() {
+    let p = TransformPipeline::default();
+    let opts = TransformOptions::default();
+
+    let big = csv(DEFAULT_TABLE_ROWS + 5);
+    assert_eq!(p.apply("data/big.csv", big.clone(), &opts).contents, big);
+    assert_eq!(
+        p.apply("nb/analysis.ipynb", NOTEBOOK.into(), &opts)
+            .contents,
+        NOTEBOOK
+    );
+}
+
+#[test]
+fn listed_renderers_apply_to_their_extensions() {
+    let p = TransformPipeline {
+        steps: vec![
+            TransformStep::for_extensions(TransformKind::Notebook { markdown: false }, &["ipynb"]),
+            TransformStep::for_extensions(
+                TransformKind::TableHead {
+                    rows: DEFAULT_TABLE_ROWS,
+                },
+                &["csv", "tsv"],
+            ),
+        ],
+    };
+    let opts = TransformOptions::default();
+
+    let big = csv(DEFAULT_TABLE_ROWS + 5);
+    let out = p.apply("data/big.TSV", big.clone(), &opts).contents;
+    assert_eq!(out.lines().count(), DEFAULT_TABLE_ROWS + 2);
+    assert!(out.ends_with("[... 5 more rows omitted; 25 data rows in total]\n"));
+    // Other extensions are untouched.
+    assert_eq!(p.apply("data/big.txt", big.clone(), &opts).contents, big);
+
+    let out = p
+        .apply("nb/analysis.ipynb", NOTEBOOK.into(), &opts)
+        .contents;
+    assert!(out.starts_with("# %%\nimport pandas"));
+
+    let out = p.apply("a.ipynb", "{broken".into(), &opts);
+    assert_eq!(out.contents, "{broken");
+}
+
+#[test]
+fn renderer_steps_round_trip_through_json() {
+    let steps = vec![
+        TransformStep::new(TransformKind::TableHead { rows: 5 }),
+        TransformStep::new(TransformKind::Notebook { markdown: true }),
+        TransformStep::new(TransformKind::Notebook { markdown: false }),
+    ];
+    let json = serde_json::to_value(&steps).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!([
+            { "kind": "table_head", "rows": 5 },
+            { "kind": "notebook", "markdown": true },
+            { "kind": "notebook" }
+        ])
+    );
+    let back: Vec<TransformStep> = serde_json::from_value(json).unwrap();
+    assert_eq!(back, steps);
+
+    let defaulted: TransformStep =
+        serde_json::from_value(serde_json::json!({ "kind": "table_head" })).unwrap();
+    assert_eq!(
+        defaulted.kind,
+        TransformKind::TableHead {
+            rows: DEFAULT_TABLE_ROWS
+        }
+    );
+}
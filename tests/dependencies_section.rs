@@ -0,0 +1,143 @@
+use std::fs;
+
+use stitch::core::{
+    CargoDependency, OutputLayout, OutputParts, collect_cargo_manifests, parse_cargo_manifest,
+    render_dependencies_section,
+};
+use tempfile::TempDir;
+
+#[test]
+fn parses_versions_features_and_sources() {
+    let manifest = r#"
+[package]
+name = "demo"
+version = "0.3.0"
+
+[dependencies]
+anyhow = "1"
+serde = { version = "1.0", features = ["derive"] }
+slint = { version = "1.13", optional = true }
+tokenizers = { version = "0.23", default-features = false, features = ["fancy-regex"] }
+local = { path = "../local" }
+upstream = { git = "https://example.com/up.git", tag = "v2" }
+renamed = { package = "real-name", version = "0.1" }
+
+[dev-dependencies]
+tempfile = "3"
+
+[target.'cfg(windows)'.dependencies]
+winapi = "0.3"
+"#;
+    let krate = parse_cargo_manifest("Cargo.toml", manifest, None, None).unwrap();
+    assert_eq!(krate.name.as_deref(), Some("demo"));
+    assert_eq!(krate.version.as_deref(), Some("0.3.0"));
+
+    let tables: Vec<&str> = krate.tables.iter().map(|(t, _)| t.as_str()).collect();
+    assert_eq!(
+        tables,
+        vec![
+            "dependencies",
+            "dev-dependencies",
+            "target.'cfg(windows)'.dependencies"
+        ]
+    );
+    let lines: Vec<String> = krate.tables[0]
+        .1
+        .iter()
+        .map(CargoDependency::describe)
+        .collect();
+    assert_eq!(
+        lines,
+        vec![
+            "anyhow 1",
+            "local (path ../local)",
+            "renamed 0.1 (package real-name)",
+            "serde 1.0 [derive]",
+            "slint 1.13 (optional)",
+            "tokenizers 0.23 [fancy-regex] (no default features)",
+            "upstream (git https://example.com/up.git tag v2)",
+        ]
+    );
+}
+
+#[test]
+fn invalid_manifest_is_an_error() {
+    assert!(parse_cargo_manifest("Cargo.toml", "[package\nname = ", None, None).is_err());
+}
+
+#[test]
+fn workspace_members_inherit_workspace_dependencies() {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path();
+    fs::write(
+        root.join("Cargo.toml"),
+        r#"
+[workspace]
+members = ["crates/*", "tools/cli"]
+exclude = ["crates/ignored"]
+
+[workspace.package]
+version = "2.0.0"
+
+[workspace.dependencies]
+serde = { version = "1.0", features = ["derive"] }
+"#,
+    )
+    .unwrap();
+    for (dir, body) in [
+        (
+            "crates/core",
+            "[package]\nname = \"core\"\nversion.workspace = true\n\n[dependencies]\nserde = { workspace = true, features = [\"rc\"], optional = true }\n",
+        ),
+        ("crates/ignored", "[package]\nname = \"ignored\"\n"),
+        (
+            "tools/cli",
+            "[package]\nname = \"cli\"\nversion = \"0.1.0\"\n",
+        ),
+    ] {
+        fs::create_dir_all(root.join(dir)).unwrap();
+        fs::write(root.join(dir).join("Cargo.toml"), body).unwrap();
+    }
+    // Matched by the glob but not a crate.
+    fs::create_dir_all(root.join("crates/notes")).unwrap();
+
+    let manifests: Vec<_> = collect_cargo_manifests(root)
+        .into_iter()
+        .map(Result::unwrap)
+        .collect();
+    let names: Vec<&str> = manifests.iter().filter_map(|m| m.name.as_deref()).collect();
+    assert_eq!(names, vec!["core", "cli"]);
+    assert_eq!(manifests[0].manifest, "crates/core/Cargo.toml");
+    assert_eq!(manifests[0].version.as_deref(), Some("2.0.0"));
+    assert_eq!(
+        manifests[0].tables[0].1[0].describe(),
+        "serde 1.0 [derive, rc] (optional)"
+    );
+
+    let section = render_dependencies_section(root).unwrap();
+    assert_eq!(
+        section,
+        "core 2.0.0 (crates/core/Cargo.toml)\n  dependencies:\n    serde 1.0 [derive, rc] (optional)\n\n\
+         cli 0.1.0 (tools/cli/Cargo.toml)\n  (no dependencies)\n\n"
+    );
+}
+
+#[test]
+fn section_is_absent_without_a_manifest_and_rendered_when_present() {
+    let tmp = TempDir::new().unwrap();
+    assert_eq!(render_dependencies_section(tmp.path()), None);
+
+    fs::write(tmp.path().join("Cargo.toml"), "not = [valid").unwrap();
+    let section = render_dependencies_section(tmp.path()).unwrap();
+    assert!(section.starts_with("Cargo.toml: could not parse ("));
+
+    let out = OutputLayout::default().render(&OutputParts {
+        hierarchy: Some("root/\n".into()),
+        dependencies: Some("demo 0.1.0 (Cargo.toml)\n".into()),
+        ..OutputParts::default()
+    });
+    assert_eq!(
+        out,
+        "=== FILE HIERARCHY ===\n\nroot/\n\n=== DEPENDENCIES ===\n\ndemo 0.1.0 (Cargo.toml)\n"
+    );
+}
@@ -0,0 +1,77 @@
+use std::{io, path::Path};
+
+use stitch::core::{
+    PathRewrite, WorkspaceSettings, load_workspace, save_workspace, skipped_file_reason,
+};
+use tempfile::TempDir;
+
+#[test]
+fn display_uses_forward_slashes_when_deterministic() {
+    let rel = Path::new("src").join("ui").join("mod.rs");
+    let identity = PathRewrite::default();
+    assert_eq!(identity.display(&rel, true), "src/ui/mod.rs");
+    assert_eq!(
+        identity.display(&rel, false),
+        rel.to_string_lossy().into_owned()
+    );
+}
+
+#[test]
+fn display_applies_rewrite_either_way() {
+    let rewrite = PathRewrite {
+        strip_prefix: "src".into(),
+        prepend: "app".into(),
+    };
+    let rel = Path::new("src").join("lib.rs");
+    assert_eq!(rewrite.display(&rel, false), "app/lib.rs");
+    assert_eq!(rewrite.display(&rel, true), "app/lib.rs");
+}
+
+#[test]
+fn skipped_reasons_keep_known_kinds() {
+    let invalid = io::Error::new(
+        io::ErrorKind::InvalidData,
+        "stream did not contain valid UTF-8",
+    );
+    let denied = io::Error::new(io::ErrorKind::PermissionDenied, "Zugriff verweigert");
+    for deterministic in [false, true] {
+        assert_eq!(skipped_file_reason(&invalid, deterministic), "not UTF-8");
+        assert_eq!(
+            skipped_file_reason(&denied, deterministic),
+            "Permission denied"
+        );
+    }
+}
+
+#[test]
+fn skipped_reasons_drop_os_messages_when_deterministic() {
+    let err = io::Error::new(
+        io::ErrorKind::NotFound,
+        "Das System kann die angegebene Datei nicht finden. (os error 2)",
+    );
+    assert_eq!(
+        skipped_file_reason(&err, false),
+        "Das System kann die angegebene Datei nicht finden. (os error 2)"
+    );
+    assert_eq!(
+        skipped_file_reason(&err, true),
+        io::ErrorKind::NotFound.to_string()
+    );
+}
+
+#[test]
+fn deterministic_flag_round_trips_and_defaults_off() {
+    let tmp = TempDir::new().unwrap();
+    let ws = WorkspaceSettings {
+        deterministic: true,
+        ..WorkspaceSettings::default()
+    };
+    save_workspace(tmp.path(), &ws).unwrap();
+    assert!(load_workspace(tmp.path()).unwrap().deterministic);
+
+    let old: WorkspaceSettings = serde_json::from_str(
+        r#"{"version":1,"ext_filter":"","exclude_dirs":"","exclude_files":"","remove_prefix":"","remove_regex":"","hierarchy_only":false,"dirs_only":false}"#,
+    )
+    .unwrap();
+    assert!(!old.deterministic);
+}
@@ -0,0 +1,168 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+use stitch::core::{
+    DirRuleKind, DirectoryRule, Profile, ProfileScope, dir_rule_for, gather_paths_set,
+    load_profile, path_to_unix, save_profile, scan_dir_to_node_with_rules,
+};
+use tempfile::TempDir;
+
+fn rule(path: &str, kind: DirRuleKind) -> DirectoryRule {
+    DirectoryRule {
+        path: path.into(),
+        kind,
+    }
+}
+
+fn set(items: &[&str]) -> HashSet<String> {
+    items.iter().map(|s| (*s).to_string()).collect()
+}
+
+fn project() -> TempDir {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path();
+    for dir in ["src/gen", "docs/img", "vendor/lib"] {
+        fs::create_dir_all(root.join(dir)).unwrap();
+    }
+    for file in [
+        "src/main.rs",
+        "src/notes.txt",
+        "src/gen/out.rs",
+        "docs/guide.md",
+        "docs/img/logo.svg",
+        "vendor/lib/dep.rs",
+    ] {
+        fs::write(root.join(file), "x").unwrap();
+    }
+    tmp
+}
+
+fn rel_paths(root: &Path, rules: &[DirectoryRule], include: &[&str]) -> Vec<String> {
+    let none = HashSet::new();
+    let tree = scan_dir_to_node_with_rules(root, &set(include), &none, &none, &none, rules).node;
+    let mut paths: Vec<String> = gather_paths_set(&tree)
+        .iter()
+        .filter_map(|p| p.strip_prefix(root).ok().map(path_to_unix))
+        .filter(|p| !p.is_empty())
+        .collect();
+    paths.sort();
+    paths
+}
+
+#[test]
+fn exclude_rule_drops_only_that_directory() {
+    let tmp = project();
+    let paths = rel_paths(tmp.path(), &[rule("src/gen", DirRuleKind::Exclude)], &[]);
+    assert!(paths.contains(&"src/main.rs".to_string()));
+    assert!(!paths.iter().any(|p| p.starts_with("src/gen")), "{paths:?}");
+
+    let paths = rel_paths(tmp.path(), &[rule("vendor", DirRuleKind::Exclude)], &[]);
+    assert!(!paths.iter().any(|p| p.starts_with("vendor")), "{paths:?}");
+    assert!(paths.contains(&"docs/guide.md".to_string()));
+}
+
+#[test]
+fn excluded_by_rule_is_not_reported_as_a_name_exclusion() {
+    let tmp = project();
+    let none = HashSet::new();
+    let scan = scan_dir_to_node_with_rules(
+        tmp.path(),
+        &none,
+        &none,
+        &none,
+        &none,
+        &[rule("vendor", DirRuleKind::Exclude)],
+    );
+    assert!(scan.stats.excluded_dirs_found.is_empty());
+}
+
+#[test]
+fn ignoring_extension_filters_applies_below_the_directory_only() {
+    let tmp = project();
+    let rules = [rule("docs", DirRuleKind::IgnoreExtensionFilters)];
+    let paths = rel_paths(tmp.path(), &rules, &[".rs"]);
+    assert!(paths.contains(&"docs/guide.md".to_string()), "{paths:?}");
+    assert!(
+        paths.contains(&"docs/img/logo.svg".to_string()),
+        "{paths:?}"
+    );
+    assert!(paths.contains(&"src/main.rs".to_string()));
+    assert!(!paths.contains(&"src/notes.txt".to_string()), "{paths:?}");
+
+    // Without the rule, docs/ has no matching files and is hidden in include mode.
+    let paths = rel_paths(tmp.path(), &[], &[".rs"]);
+    assert!(!paths.iter().any(|p| p.starts_with("docs")), "{paths:?}");
+}
+
+#[test]
+fn name_exclusions_still_apply_below_an_ignore_rule() {
+    let tmp = project();
+    let none = HashSet::new();
+    let tree = scan_dir_to_node_with_rules(
+        tmp.path(),
+        &set(&[".rs"]),
+        &none,
+        &set(&["img"]),
+        &set(&["guide.md"]),
+        &[rule("docs", DirRuleKind::IgnoreExtensionFilters)],
+    )
+    .node;
+    let paths = gather_paths_set(&tree);
+    assert!(!paths.iter().any(|p| p.ends_with("guide.md")));
+    assert!(!paths.iter().any(|p| p.ends_with("logo.svg")));
+}
+
+#[test]
+fn rule_lookup_respects_path_boundaries_and_exclusion_wins() {
+    let rules = [
+        rule("src", DirRuleKind::IgnoreExtensionFilters),
+        rule("src/gen", DirRuleKind::Exclude),
+    ];
+    assert_eq!(
+        dir_rule_for(&rules, "src"),
+        Some(DirRuleKind::IgnoreExtensionFilters)
+    );
+    assert_eq!(
+        dir_rule_for(&rules, "src/main.rs"),
+        Some(DirRuleKind::IgnoreExtensionFilters)
+    );
+    assert_eq!(
+        dir_rule_for(&rules, "src/gen/out.rs"),
+        Some(DirRuleKind::Exclude)
+    );
+    assert_eq!(dir_rule_for(&rules, "src2/lib.rs"), None);
+    assert_eq!(dir_rule_for(&rules, "docs"), None);
+
+    let root = [rule("", DirRuleKind::IgnoreExtensionFilters)];
+    assert_eq!(
+        dir_rule_for(&root, "any/file.txt"),
+        Some(DirRuleKind::IgnoreExtensionFilters)
+    );
+}
+
+#[test]
+fn rules_roundtrip_through_profile_and_default_when_missing() {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path();
+
+    let prof = Profile {
+        name: "overrides".into(),
+        dir_rules: vec![
+            rule("docs", DirRuleKind::IgnoreExtensionFilters),
+            rule("vendor", DirRuleKind::Exclude),
+        ],
+        ..Default::default()
+    };
+    save_profile(root, &prof, ProfileScope::Shared).unwrap();
+    let (loaded, _) = load_profile(root, "overrides").unwrap();
+    assert_eq!(loaded.dir_rules, prof.dir_rules);
+
+    let raw = fs::read_to_string(root.join(".stitchworkspace/profiles/overrides.json")).unwrap();
+    assert!(raw.contains("\"ignore_extension_filters\""), "{raw}");
+
+    let legacy = r#"{"name":"legacy","settings":{"version":1,"ext_filter":"","exclude_dirs":"","exclude_files":"","remove_prefix":"","remove_regex":"","hierarchy_only":false,"dirs_only":false},"explicit":[]}"#;
+    fs::write(root.join(".stitchworkspace/profiles/legacy.json"), legacy).unwrap();
+    let (legacy, _) = load_profile(root, "legacy").unwrap();
+    assert!(legacy.dir_rules.is_empty());
+}
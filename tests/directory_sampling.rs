@@ -0,0 +1,170 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use stitch::core::{
+    DirectorySample, Profile, ProfileScope, SampleOrder, apply_directory_samples, load_profile,
+    save_profile,
+};
+use tempfile::TempDir;
+
+fn touch(root: &Path, rel: &str, age_secs: u64) -> PathBuf {
+    let p = root.join(rel);
+    fs::create_dir_all(p.parent().unwrap()).unwrap();
+    fs::write(&p, rel).unwrap();
+    let f = fs::File::options().write(true).open(&p).unwrap();
+    f.set_modified(SystemTime::now() - Duration::from_secs(age_secs))
+        .unwrap();
+    p
+}
+
+#[test]
+fn no_rules_keeps_everything() {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path();
+    let files = vec![touch(root, "a.rs", 0), touch(root, "b.rs", 0)];
+
+    let (kept, omitted) = apply_directory_samples(root, &files, &[]);
+    assert_eq!(kept, files);
+    assert!(omitted.is_empty());
+}
+
+#[test]
+fn first_n_keeps_lexicographically_first_and_preserves_input_order() {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path();
+    let files = vec![
+        touch(root, "migrations/003.sql", 0),
+        touch(root, "migrations/001.sql", 0),
+        touch(root, "migrations/002.sql", 0),
+        touch(root, "src/main.rs", 0),
+    ];
+    let rules = [DirectorySample {
+        path: "migrations".into(),
+        count: 2,
+        order: SampleOrder::First,
+    }];
+
+    let (kept, omitted) = apply_directory_samples(root, &files, &rules);
+    assert_eq!(
+        kept,
+        vec![files[1].clone(), files[2].clone(), files[3].clone()]
+    );
+    assert_eq!(omitted.len(), 1);
+    assert_eq!(omitted[0].path, "migrations");
+    assert_eq!(omitted[0].kept, 2);
+    assert_eq!(omitted[0].total, 3);
+    assert_eq!(omitted[0].omitted(), 1);
+}
+
+#[test]
+fn most_recent_uses_modification_time() {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path();
+    let files = vec![
+        touch(root, "fixtures/old.json", 3_000),
+        touch(root, "fixtures/new.json", 10),
+        touch(root, "fixtures/mid.json", 600),
+    ];
+    let rules = [DirectorySample {
+        path: "fixtures".into(),
+        count: 1,
+        order: SampleOrder::MostRecent,
+    }];
+
+    let (kept, omitted) = apply_directory_samples(root, &files, &rules);
+    assert_eq!(kept, vec![files[1].clone()]);
+    assert_eq!(omitted[0].omitted(), 2);
+}
+
+#[test]
+fn deepest_rule_governs_nested_directories() {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path();
+    let files = vec![
+        touch(root, "data/a.csv", 0),
+        touch(root, "data/b.csv", 0),
+        touch(root, "data/big/1.csv", 0),
+        touch(root, "data/big/2.csv", 0),
+        touch(root, "data/big/3.csv", 0),
+    ];
+    let rules = [
+        DirectorySample {
+            path: "data".into(),
+            count: 5,
+            order: SampleOrder::First,
+        },
+        DirectorySample {
+            path: "data/big".into(),
+            count: 1,
+            order: SampleOrder::First,
+        },
+    ];
+
+    let (kept, omitted) = apply_directory_samples(root, &files, &rules);
+    assert_eq!(kept.len(), 3, "data keeps its 2 files, data/big keeps 1");
+    assert_eq!(omitted.len(), 1);
+    assert_eq!(omitted[0].path, "data/big");
+}
+
+#[test]
+fn rule_does_not_match_sibling_with_common_prefix() {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path();
+    let files = vec![
+        touch(root, "logs/a.txt", 0),
+        touch(root, "logs2/a.txt", 0),
+        touch(root, "logs2/b.txt", 0),
+    ];
+    let rules = [DirectorySample {
+        path: "logs".into(),
+        count: 0,
+        order: SampleOrder::First,
+    }];
+
+    let (kept, _) = apply_directory_samples(root, &files, &rules);
+    assert_eq!(kept, vec![files[1].clone(), files[2].clone()]);
+}
+
+#[test]
+fn describe_labels() {
+    let first = DirectorySample {
+        path: String::new(),
+        count: 5,
+        order: SampleOrder::First,
+    };
+    let recent = DirectorySample {
+        order: SampleOrder::MostRecent,
+        ..first.clone()
+    };
+    assert_eq!(first.describe(), "first 5");
+    assert_eq!(recent.describe(), "5 most recent");
+}
+
+#[test]
+fn samples_roundtrip_through_profile_and_default_when_missing() {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path();
+
+    let prof = Profile {
+        name: "sampled".into(),
+        samples: vec![DirectorySample {
+            path: "migrations".into(),
+            count: 3,
+            order: SampleOrder::MostRecent,
+        }],
+        ..Default::default()
+    };
+    save_profile(root, &prof, ProfileScope::Shared).unwrap();
+    let (loaded, _) = load_profile(root, "sampled").unwrap();
+    assert_eq!(loaded.samples, prof.samples);
+
+    // Older profiles without the field still load.
+    let legacy = r#"{"name":"legacy","settings":{"version":1,"ext_filter":"","exclude_dirs":"","exclude_files":"","remove_prefix":"","remove_regex":"","hierarchy_only":false,"dirs_only":false},"explicit":[]}"#;
+    let dir = root.join(".stitchworkspace/profiles");
+    fs::write(dir.join("legacy.json"), legacy).unwrap();
+    let (legacy, _) = load_profile(root, "legacy").unwrap();
+    assert!(legacy.samples.is_empty());
+}
@@ -0,0 +1,91 @@
+use std::path::Path;
+
+use pretty_assertions::assert_eq;
+use stitch::core::display_path_with_home;
+
+#[cfg(not(windows))]
+#[test]
+fn home_and_paths_below_it_use_a_tilde() {
+    let home = Path::new("/home/ann");
+    assert_eq!(display_path_with_home(Path::new("/home/ann"), home), "~");
+    assert_eq!(
+        display_path_with_home(Path::new("/home/ann/code/stitch"), home),
+        "~/code/stitch"
+    );
+    assert_eq!(
+        display_path_with_home(Path::new("/home/ann/code"), Path::new("/home/ann/")),
+        "~/code"
+    );
+}
+
+#[cfg(not(windows))]
+#[test]
+fn other_paths_are_left_alone() {
+    let home = Path::new("/home/ann");
+    assert_eq!(
+        display_path_with_home(Path::new("/home/anna/code"), home),
+        "/home/anna/code"
+    );
+    assert_eq!(
+        display_path_with_home(Path::new("/home/Ann/code"), home),
+        "/home/Ann/code"
+    );
+    assert_eq!(
+        display_path_with_home(Path::new("/srv/app"), home),
+        "/srv/app"
+    );
+}
+
+#[cfg(not(windows))]
+#[test]
+fn a_root_home_is_not_abbreviated() {
+    assert_eq!(
+        display_path_with_home(Path::new("/srv/app"), Path::new("/")),
+        "/srv/app"
+    );
+}
+
+#[cfg(not(windows))]
+#[test]
+fn macos_home_follows_the_same_rules() {
+    let home = Path::new("/Users/ann");
+    assert_eq!(
+        display_path_with_home(Path::new("/Users/ann/Library/x"), home),
+        "~/Library/x"
+    );
+    assert_eq!(
+        display_path_with_home(Path::new("/Volumes/ann/x"), home),
+        "/Volumes/ann/x"
+    );
+}
+
+#[cfg(windows)]
+#[test]
+fn profile_match_ignores_case_and_uses_forward_slashes() {
+    let home = Path::new(r"C:\Users\Ann");
+    assert_eq!(
+        display_path_with_home(Path::new(r"c:\users\ann\code\stitch"), home),
+        "~/code/stitch"
+    );
+    assert_eq!(
+        display_path_with_home(Path::new(r"C:\Users\Ann"), home),
+        "~"
+    );
+    assert_eq!(
+        display_path_with_home(Path::new(r"D:\Users\Ann\x"), home),
+        "D:/Users/Ann/x"
+    );
+}
+
+#[cfg(not(windows))]
+#[test]
+fn absolute_paths_keep_a_single_leading_slash() {
+    assert_eq!(
+        display_path_with_home(Path::new("/srv/app/"), Path::new("/home/ann")),
+        "/srv/app"
+    );
+    assert_eq!(
+        display_path_with_home(Path::new("/"), Path::new("/home/ann")),
+        "/"
+    );
+}
@@ -0,0 +1,71 @@
+use std::path::Path;
+
+use pretty_assertions::assert_eq;
+
+use stitch::core::{cap_document_text, docx_text_from_xml, is_document_path};
+
+const DOCUMENT_XML: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<w:document xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main"><w:body>
+<w:p><w:pPr><w:pStyle w:val="Title"/></w:pPr><w:r><w:t>Product spec</w:t></w:r></w:p>
+<w:p><w:r><w:t xml:space="preserve">Limits: </w:t></w:r><w:r><w:t>a &lt; b &amp;&amp; c</w:t></w:r></w:p>
+<w:p><w:r><w:t>col1</w:t><w:tab/><w:t>col2</w:t><w:br/><w:t>next &#x2192; line</w:t></w:r></w:p>
+<w:p/>
+</w:body></w:document>"#;
+
+#[test]
+fn docx_xml_becomes_one_line_per_paragraph() {
+    assert_eq!(
+        docx_text_from_xml(DOCUMENT_XML),
+        "Product spec\nLimits: a < b && c\ncol1\tcol2\nnext \u{2192} line\n"
+    );
+}
+
+#[test]
+fn document_paths_by_extension() {
+    assert!(is_document_path(Path::new("docs/spec.pdf")));
+    assert!(is_document_path(Path::new("docs/Spec.DOCX")));
+    assert!(!is_document_path(Path::new("docs/spec.doc")));
+    assert!(!is_document_path(Path::new("pdf")));
+}
+
+#[test]
+fn capping_keeps_whole_lines_within_the_budget() {
+    let text = "word ".repeat(20) + "\n";
+    let text = text.repeat(100);
+    let (kept, truncated) = cap_document_text("a.pdf", &text, 10_000);
+    assert!(!truncated);
+    assert_eq!(kept, text);
+
+    let (kept, truncated) = cap_document_text("a.pdf", &text, 100);
+    assert!(truncated);
+    assert!(!kept.is_empty() && kept.len() < text.len());
+    assert!(kept.ends_with('\n'));
+}
+
+#[cfg(feature = "doc-extract")]
+#[test]
+fn extracts_docx_files_and_rejects_others() {
+    use std::io::Write;
+
+    use stitch::core::extract_document;
+
+    let tmp = tempfile::tempdir().unwrap();
+    let docx = tmp.path().join("spec.docx");
+    let mut zip = zip::ZipWriter::new(std::fs::File::create(&docx).unwrap());
+    zip.start_file(
+        "word/document.xml",
+        zip::write::SimpleFileOptions::default(),
+    )
+    .unwrap();
+    zip.write_all(DOCUMENT_XML.as_bytes()).unwrap();
+    zip.finish().unwrap();
+
+    let doc = extract_document(&docx, 1_000).unwrap();
+    assert!(doc.text.starts_with("Product spec\n"));
+    assert!(!doc.truncated);
+    assert!(extract_document(&docx, 3).unwrap().truncated);
+
+    let bogus = tmp.path().join("fake.pdf");
+    std::fs::write(&bogus, "not a pdf").unwrap();
+    assert!(extract_document(&bogus, 1_000).is_err());
+}
@@ -0,0 +1,76 @@
+use std::{cell::RefCell, rc::Rc};
+
+use pretty_assertions::assert_eq;
+use stitch::core::{EventBus, SelectionStats, WorkspaceEvent};
+
+fn recorder(bus: &EventBus, name: &'static str, log: &Rc<RefCell<Vec<String>>>) {
+    let log = Rc::clone(log);
+    bus.subscribe(move |event| log.borrow_mut().push(format!("{name}: {event:?}")));
+}
+
+#[test]
+fn subscribers_get_events_in_subscription_order() {
+    let bus = EventBus::new();
+    let log = Rc::new(RefCell::new(Vec::new()));
+    recorder(&bus, "a", &log);
+    recorder(&bus, "b", &log);
+    bus.emit(WorkspaceEvent::SelectionChanged);
+    bus.emit(WorkspaceEvent::GenerationFinished {
+        output: "out".into(),
+        stats: SelectionStats::default(),
+    });
+    bus.emit(WorkspaceEvent::GenerationFailed {
+        message: "boom".into(),
+    });
+    let log = log.borrow();
+    assert_eq!(log.len(), 6);
+    assert_eq!(log[0], "a: SelectionChanged");
+    assert_eq!(log[1], "b: SelectionChanged");
+    assert!(log[2].starts_with("a: GenerationFinished { output: \"out\""));
+    assert_eq!(log[5], "b: GenerationFailed { message: \"boom\" }");
+}
+
+#[test]
+fn events_emitted_by_subscribers_are_queued() {
+    let bus = Rc::new(EventBus::new());
+    let log = Rc::new(RefCell::new(Vec::new()));
+    {
+        let bus_weak = Rc::downgrade(&bus);
+        let log = Rc::clone(&log);
+        bus.subscribe(move |event| {
+            log.borrow_mut().push(format!("first: {event:?}"));
+            if *event == WorkspaceEvent::FiltersChanged {
+                bus_weak
+                    .upgrade()
+                    .unwrap()
+                    .emit(WorkspaceEvent::SelectionChanged);
+            }
+        });
+    }
+    recorder(&bus, "second", &log);
+    bus.emit(WorkspaceEvent::FiltersChanged);
+    assert_eq!(
+        *log.borrow(),
+        [
+            "first: FiltersChanged",
+            "second: FiltersChanged",
+            "first: SelectionChanged",
+            "second: SelectionChanged",
+        ]
+    );
+}
+
+#[test]
+fn unsubscribed_handlers_stop_getting_events() {
+    let bus = EventBus::new();
+    let count = Rc::new(RefCell::new(0));
+    let id = {
+        let count = Rc::clone(&count);
+        bus.subscribe(move |_| *count.borrow_mut() += 1)
+    };
+    bus.emit(WorkspaceEvent::SelectionChanged);
+    assert!(bus.unsubscribe(id));
+    assert!(!bus.unsubscribe(id));
+    bus.emit(WorkspaceEvent::SelectionChanged);
+    assert_eq!(*count.borrow(), 1);
+}
@@ -0,0 +1,130 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+use stitch::core::{
+    ExcludeSuggestion, ExcludeTarget, SuggestionReason, append_filter_token, scan_dir_to_node,
+    suggest_excludes,
+};
+use tempfile::TempDir;
+
+fn write_files(root: &Path, dir: &str, count: usize, ext: &str) {
+    let dir = root.join(dir);
+    fs::create_dir_all(&dir).unwrap();
+    for i in 0..count {
+        fs::write(dir.join(format!("f{i}{ext}")), "x").unwrap();
+    }
+}
+
+fn suggestions(root: &Path, exclude_dirs: &[&str]) -> Vec<ExcludeSuggestion> {
+    let none = HashSet::new();
+    let dirs: HashSet<String> = exclude_dirs.iter().map(|d| (*d).to_string()).collect();
+    let tree = scan_dir_to_node(root, &none, &none, &dirs, &none);
+    suggest_excludes(&tree)
+}
+
+#[test]
+fn noise_directories_and_extensions_are_suggested_most_files_first() {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path();
+    write_files(root, "src", 10, ".js");
+    write_files(root, "dist", 4, ".js");
+    write_files(root, "packages/a/dist", 2, ".js");
+    write_files(root, "coverage", 3, ".html");
+    fs::write(root.join("src/vendor.min.js"), "x").unwrap();
+    fs::write(root.join("src/app.js.map"), "x").unwrap();
+    fs::write(root.join("src/lib.js.map"), "x").unwrap();
+
+    let labels: Vec<String> = suggestions(root, &[])
+        .iter()
+        .map(ExcludeSuggestion::label)
+        .collect();
+    assert_eq!(
+        labels,
+        vec![
+            "dist/ (6 files)",
+            "coverage/ (3 files)",
+            "*.map (2 files)",
+            "*.min.js (1 file)",
+        ]
+    );
+}
+
+#[test]
+fn files_inside_noise_directories_count_only_towards_the_directory() {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path();
+    write_files(root, "src", 3, ".rs");
+    write_files(root, "build/out", 5, ".map");
+
+    let got = suggestions(root, &[]);
+    assert_eq!(got.len(), 1, "{got:?}");
+    assert_eq!(got[0].pattern, "build");
+    assert_eq!(got[0].files, 5);
+    assert_eq!(got[0].reason, SuggestionReason::Noise);
+}
+
+#[test]
+fn already_excluded_directories_are_not_suggested() {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path();
+    write_files(root, "src", 3, ".rs");
+    write_files(root, "dist", 3, ".js");
+
+    assert!(suggestions(root, &["dist"]).is_empty());
+}
+
+#[test]
+fn large_directories_are_suggested_outermost_first() {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path();
+    write_files(root, "src", 20, ".rs");
+    write_files(root, "fixtures/big", 80, ".json");
+    write_files(root, "docs", 5, ".md");
+
+    let got = suggestions(root, &[]);
+    assert_eq!(got.len(), 1, "{got:?}");
+    assert_eq!(got[0].target, ExcludeTarget::Directory);
+    assert_eq!(got[0].pattern, "fixtures");
+    assert_eq!(got[0].files, 80);
+    assert_eq!(got[0].reason, SuggestionReason::Large);
+    assert_eq!(got[0].filter_token(), "fixtures");
+}
+
+#[test]
+fn small_projects_get_no_large_directory_suggestions() {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path();
+    write_files(root, "src", 30, ".rs");
+    write_files(root, "tests", 2, ".rs");
+
+    assert!(suggestions(root, &[]).is_empty());
+}
+
+#[test]
+fn extension_suggestions_use_the_exclude_filter_syntax() {
+    let suggestion = ExcludeSuggestion {
+        target: ExcludeTarget::Extension,
+        pattern: ".min.css".to_string(),
+        files: 2,
+        reason: SuggestionReason::Noise,
+    };
+    assert_eq!(suggestion.filter_token(), "-.min.css");
+    assert_eq!(suggestion.label(), "*.min.css (2 files)");
+}
+
+#[test]
+fn append_filter_token_adds_once() {
+    assert_eq!(append_filter_token("", "dist"), "dist");
+    assert_eq!(append_filter_token("  ", "dist"), "dist");
+    assert_eq!(
+        append_filter_token("target,node_modules", "dist"),
+        "target,node_modules, dist"
+    );
+    assert_eq!(append_filter_token("target, ", "dist"), "target, dist");
+    assert_eq!(append_filter_token("target, dist", "dist"), "target, dist");
+    assert_eq!(
+        append_filter_token(".rs,-.lock", "-.map"),
+        ".rs,-.lock, -.map"
+    );
+}
@@ -0,0 +1,63 @@
+use std::collections::HashSet;
+use std::fs;
+
+use stitch::core::{extension_filter_match_counts, parse_extension_filters, scan_dir_to_node};
+use tempfile::TempDir;
+
+fn project() -> TempDir {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path();
+    fs::create_dir_all(root.join("src/ui")).unwrap();
+    for file in [
+        "src/App.tsx",
+        "src/ui/Button.TSX",
+        "src/main.ts",
+        "backup.tar.gz",
+        "justfile",
+        "README.md",
+    ] {
+        fs::write(root.join(file), "x").unwrap();
+    }
+    tmp
+}
+
+fn counts(tmp: &TempDir, filter: &str) -> Vec<(String, usize)> {
+    let (include, exclude) = parse_extension_filters(filter);
+    let none = HashSet::new();
+    let root = scan_dir_to_node(tmp.path(), &include, &exclude, &none, &none);
+    extension_filter_match_counts(&root, &include)
+}
+
+fn count(counts: &[(String, usize)], token: &str) -> usize {
+    counts.iter().find(|(t, _)| t == token).unwrap().1
+}
+
+#[test]
+fn typo_in_include_filter_counts_zero() {
+    let tmp = project();
+    let c = counts(&tmp, ".tx, .ts");
+    assert_eq!(c, vec![(".ts".to_string(), 1), (".tx".to_string(), 0)]);
+}
+
+#[test]
+fn counts_are_case_insensitive_and_per_token() {
+    let tmp = project();
+    let c = counts(&tmp, ".tsx,.md");
+    assert_eq!(count(&c, ".tsx"), 2);
+    assert_eq!(count(&c, ".md"), 1);
+}
+
+#[test]
+fn multi_dot_and_extensionless_tokens_match_like_the_scan() {
+    let tmp = project();
+    let c = counts(&tmp, ".tar.gz, .gz, justfile");
+    assert_eq!(count(&c, ".tar.gz"), 1);
+    assert_eq!(count(&c, ".gz"), 1);
+    assert_eq!(count(&c, ".justfile"), 1);
+}
+
+#[test]
+fn no_filters_means_no_counts() {
+    let tmp = project();
+    assert!(counts(&tmp, "").is_empty());
+}
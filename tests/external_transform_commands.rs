@@ -0,0 +1,142 @@
+#![cfg(unix)]
+
+use std::time::Duration;
+
+use pretty_assertions::assert_eq;
+
+use stitch::core::{
+    ExternalFile, TransformKind, TransformOptions, TransformPipeline, TransformStep,
+    run_external_command,
+};
+
+fn command_step(command: &str, extensions: &[&str]) -> TransformStep {
+    TransformStep::for_extensions(
+        TransformKind::Command {
+            command: command.into(),
+            timeout_secs: None,
+        },
+        extensions,
+    )
+}
+
+fn allowed(dir: &std::path::Path) -> TransformOptions {
+    TransformOptions {
+        allow_commands: true,
+        working_dir: dir.to_path_buf(),
+        ..TransformOptions::default()
+    }
+}
+
+#[test]
+fn command_stdout_replaces_contents_of_matching_files() {
+    let tmp = tempfile::tempdir().unwrap();
+    let p = TransformPipeline {
+        steps: vec![command_step("tr a-z A-Z", &["proto"])],
+    };
+    let opts = allowed(tmp.path());
+
+    let out = p.apply("api/user.proto", "message user {}\n".into(), &opts);
+    assert_eq!(out.contents, "MESSAGE USER {}\n");
+    assert!(out.command_failures.is_empty());
+
+    let out = p.apply("src/lib.rs", "fn f() {}\n".into(), &opts);
+    assert_eq!(out.contents, "fn f() {}\n");
+}
+
+#[test]
+fn commands_do_not_run_unless_allowed() {
+    let p = TransformPipeline {
+        steps: vec![command_step("echo replaced", &[])],
+    };
+    assert!(p.has_commands());
+    assert!(!TransformPipeline::default().has_commands());
+    let out = p.apply("a.txt", "original\n".into(), &TransformOptions::default());
+    assert_eq!(out.contents, "original\n");
+    assert!(out.command_failures.is_empty());
+}
+
+#[test]
+fn failing_command_keeps_contents_and_reports_why() {
+    let tmp = tempfile::tempdir().unwrap();
+    let p = TransformPipeline {
+        steps: vec![
+            command_step("echo 'bad input' >&2; exit 3", &[]),
+            TransformStep::new(TransformKind::Minify),
+        ],
+    };
+    let out = p.apply("a.txt", "x  \n\ny\n".into(), &allowed(tmp.path()));
+    assert_eq!(out.contents, "x\ny\n");
+    assert_eq!(
+        out.command_failures,
+        vec!["`echo 'bad input' >&2; exit 3` exited with code 3: bad input".to_string()]
+    );
+}
+
+#[test]
+fn slow_command_times_out() {
+    let tmp = tempfile::tempdir().unwrap();
+    let p = TransformPipeline {
+        steps: vec![TransformStep::new(TransformKind::Command {
+            command: "sleep 5".into(),
+            timeout_secs: Some(0),
+        })],
+    };
+    let out = p.apply("a.txt", "kept\n".into(), &allowed(tmp.path()));
+    assert_eq!(out.contents, "kept\n");
+    assert_eq!(out.command_failures, vec!["`sleep 5` timed out after 0s"]);
+}
+
+#[test]
+fn command_sees_file_paths_and_runs_in_the_working_dir() {
+    let tmp = tempfile::tempdir().unwrap();
+    std::fs::write(tmp.path().join("marker"), "here").unwrap();
+    let full = tmp.path().join("docs/a.md");
+    let file = ExternalFile {
+        path: "docs/a.md",
+        full_path: &full,
+        working_dir: tmp.path(),
+    };
+    let out = run_external_command(
+        r#"printf '%s|%s|' "$STITCH_PATH" "$(cat marker)"; cat"#,
+        "stdin",
+        &file,
+        Duration::from_secs(10),
+    )
+    .unwrap();
+    assert_eq!(out, "docs/a.md|here|stdin");
+
+    let out = run_external_command(
+        r#"printf %s "$STITCH_FILE""#,
+        "",
+        &file,
+        Duration::from_secs(10),
+    )
+    .unwrap();
+    assert_eq!(out, full.to_string_lossy());
+}
+
+#[test]
+fn command_step_round_trips_through_json() {
+    let p = TransformPipeline {
+        steps: vec![
+            TransformStep::new(TransformKind::Redact),
+            TransformStep {
+                extensions: vec!["proto".into()],
+                ..TransformStep::new(TransformKind::Command {
+                    command: "protoc-doc".into(),
+                    timeout_secs: Some(30),
+                })
+            },
+        ],
+    };
+    let json = serde_json::to_value(&p).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!([
+            { "kind": "redact" },
+            { "kind": "command", "command": "protoc-doc", "timeout_secs": 30, "extensions": ["proto"] }
+        ])
+    );
+    let back: TransformPipeline = serde_json::from_value(json).unwrap();
+    assert_eq!(back, p);
+}
@@ -0,0 +1,165 @@
+use std::{
+    collections::BTreeMap,
+    time::{Duration, SystemTime},
+};
+
+use pretty_assertions::assert_eq;
+use stitch::core::{
+    FileMarkers, WorkspaceSettings, estimate_file_tokens, estimate_tokens,
+    estimate_tokens_with_markers, format_modified_time, lang_for_path, load_workspace,
+    save_workspace, split_output_segments, split_output_segments_with_markers,
+};
+use tempfile::TempDir;
+
+fn markers(header: &str, footer: &str) -> FileMarkers {
+    FileMarkers {
+        header: header.into(),
+        footer: footer.into(),
+    }
+}
+
+fn block(markers: &FileMarkers, path: &str, contents: &str) -> String {
+    let mut out = String::new();
+    markers.write_block(
+        &mut out,
+        path,
+        lang_for_path(path, &BTreeMap::new()),
+        contents,
+        None,
+    );
+    out
+}
+
+#[test]
+fn default_markers_keep_the_classic_delimiters() {
+    let out = block(&FileMarkers::default(), "src/a.rs", "fn a() {}");
+    assert_eq!(
+        out,
+        "--- Start of file: src/a.rs ---\nfn a() {}\n--- End of file: src/a.rs ---\n\n"
+    );
+    // Token counting still splits default blocks per file.
+    let text = format!("tree\n{out}{}", block(&FileMarkers::default(), "b.rs", "b"));
+    assert_eq!(split_output_segments(&text).len(), 3);
+}
+
+#[test]
+fn placeholders_are_filled() {
+    let m = markers(
+        "<file path=\"{path}\" lang=\"{lang}\" size=\"{size}\" tokens=\"{tokens}\">",
+        "</file>",
+    );
+    let contents = "fn main() {}\n";
+    let estimated = estimate_file_tokens("src/main.rs", contents);
+    assert_eq!(
+        block(&m, "src/main.rs", contents),
+        format!(
+            "<file path=\"src/main.rs\" lang=\"rust\" size=\"13\" tokens=\"{estimated}\">\n\
+             fn main() {{}}\n\n</file>\n\n"
+        )
+    );
+}
+
+#[test]
+fn unknown_braces_are_kept_and_empty_formats_drop_the_line() {
+    let m = markers("### {path} {unknown} {", "");
+    assert_eq!(
+        block(&m, "x.txt", "hello"),
+        "### x.txt {unknown} {\nhello\n\n"
+    );
+    let fence = markers("```{lang}", "```");
+    assert_eq!(block(&fence, "a.py", "pass"), "```python\npass\n```\n\n");
+}
+
+#[test]
+fn cell_markers_open_a_cell_per_file() {
+    let cells = FileMarkers::cells();
+    let out = format!(
+        "{}{}",
+        block(&cells, "src/app.py", "print(1)"),
+        block(&cells, "README.md", "# Title")
+    );
+    assert_eq!(
+        out,
+        "# %% src/app.py\nprint(1)\n\n# %% README.md\n# Title\n\n"
+    );
+}
+
+#[test]
+fn cell_markers_setting_defaults_off() {
+    let json = serde_json::to_value(WorkspaceSettings::default()).unwrap();
+    assert_eq!(json["cell_markers"], false);
+    let mut json = json;
+    json["cell_markers"] = true.into();
+    let ws: WorkspaceSettings = serde_json::from_value(json).unwrap();
+    assert!(ws.cell_markers);
+}
+
+#[test]
+fn markers_persist_in_workspace_and_default_when_missing() {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path();
+    let ws = WorkspaceSettings {
+        version: 1,
+        file_markers: markers("=== {path} ===", ""),
+        ..Default::default()
+    };
+    save_workspace(root, &ws).unwrap();
+    assert_eq!(load_workspace(root).unwrap().file_markers, ws.file_markers);
+
+    let partial = r#"{"version":1,"ext_filter":"","exclude_dirs":"","exclude_files":"","remove_prefix":"","remove_regex":"","hierarchy_only":false,"dirs_only":false,
+        "file_markers":{"footer":"END"}}"#;
+    std::fs::write(root.join(".stitchworkspace/workspace.json"), partial).unwrap();
+    let loaded = load_workspace(root).unwrap().file_markers;
+    assert_eq!(loaded.header, FileMarkers::default().header);
+    assert_eq!(loaded.footer, "END");
+}
+
+#[test]
+fn modified_time_goes_on_the_header_line() {
+    let mut out = String::new();
+    let modified =
+        format_modified_time(SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000));
+    assert_eq!(modified, "2023-11-14 22:13 UTC");
+    FileMarkers::default().write_block(&mut out, "src/a.rs", "rust", "fn a() {}", Some(&modified));
+    assert_eq!(
+        out,
+        "--- Start of file: src/a.rs --- (modified 2023-11-14 22:13 UTC)\nfn a() {}\n--- End of file: src/a.rs ---\n\n"
+    );
+    // The block is still estimated with Rust's token ratio.
+    assert_eq!(
+        estimate_tokens(&out),
+        estimate_file_tokens("src/a.rs", &out)
+    );
+
+    let mut cell = String::new();
+    FileMarkers::cells().write_block(&mut cell, "nb.py", "python", "x = 1", Some(&modified));
+    assert_eq!(
+        cell,
+        "# %% nb.py (modified 2023-11-14 22:13 UTC)\nx = 1\n\n"
+    );
+}
+
+#[test]
+fn custom_and_cell_markers_split_tokens_per_file() {
+    for m in [
+        markers("<file path=\"{path}\" lang=\"{lang}\">", "</file>"),
+        FileMarkers::cells(),
+    ] {
+        let a = block(&m, "src/a.rs", "fn a() {}");
+        let text = format!("tree\n{a}{}", block(&m, "data.json", "{}"));
+        let segments = split_output_segments_with_markers(&text, &m);
+        assert_eq!(segments, vec!["tree\n", a.as_str(), &text[5 + a.len()..]]);
+        assert_eq!(
+            m.header_path(segments[1].lines().next().unwrap()),
+            Some("src/a.rs")
+        );
+        assert_eq!(
+            estimate_tokens_with_markers(&a, &m),
+            estimate_file_tokens("src/a.rs", &a)
+        );
+    }
+    // A header with nothing before {path} can't be told apart from content.
+    let bare = markers("{path}", "");
+    let text = format!("{}{}", block(&bare, "a.rs", "a"), block(&bare, "b.rs", "b"));
+    assert_eq!(split_output_segments_with_markers(&text, &bare).len(), 1);
+}
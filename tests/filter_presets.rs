@@ -0,0 +1,105 @@
+use std::fs;
+
+use stitch::core::{
+    FilterPreset, WorkspaceSettings, delete_filter_preset, filter_presets_file,
+    load_filter_presets, save_filter_preset,
+};
+use tempfile::TempDir;
+
+fn preset(name: &str, ext_filter: &str) -> FilterPreset {
+    FilterPreset {
+        name: name.to_string(),
+        ext_filter: ext_filter.to_string(),
+        exclude_dirs: "target, node_modules".to_string(),
+        exclude_files: "Cargo.lock".to_string(),
+        remove_prefix: "//".to_string(),
+    }
+}
+
+#[test]
+fn missing_or_corrupt_file_yields_no_presets() {
+    let tmp = TempDir::new().unwrap();
+    assert!(load_filter_presets(tmp.path()).is_empty());
+
+    fs::write(filter_presets_file(tmp.path()), "{ not json").unwrap();
+    assert!(load_filter_presets(tmp.path()).is_empty());
+}
+
+#[test]
+fn presets_round_trip_sorted_by_name() {
+    let tmp = TempDir::new().unwrap();
+    let dir = tmp.path().join("nested/config");
+    save_filter_preset(&dir, &preset("web", ".ts,.tsx")).unwrap();
+    save_filter_preset(&dir, &preset("Rust", ".rs,.toml")).unwrap();
+    save_filter_preset(&dir, &preset("docs", ".md")).unwrap();
+
+    let loaded = load_filter_presets(&dir);
+    let names: Vec<&str> = loaded.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(names, vec!["docs", "Rust", "web"]);
+    assert_eq!(loaded[1], preset("Rust", ".rs,.toml"));
+}
+
+#[test]
+fn saving_an_existing_name_replaces_it() {
+    let tmp = TempDir::new().unwrap();
+    save_filter_preset(tmp.path(), &preset("Rust", ".rs")).unwrap();
+    save_filter_preset(tmp.path(), &preset("  rust ", ".rs,.toml")).unwrap();
+
+    let loaded = load_filter_presets(tmp.path());
+    assert_eq!(loaded.len(), 1);
+    assert_eq!(loaded[0].name, "rust");
+    assert_eq!(loaded[0].ext_filter, ".rs,.toml");
+}
+
+#[test]
+fn empty_names_are_rejected() {
+    let tmp = TempDir::new().unwrap();
+    let err = save_filter_preset(tmp.path(), &preset("   ", ".rs")).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    assert!(!filter_presets_file(tmp.path()).exists());
+}
+
+#[test]
+fn delete_removes_only_the_named_preset() {
+    let tmp = TempDir::new().unwrap();
+    save_filter_preset(tmp.path(), &preset("Rust", ".rs")).unwrap();
+    save_filter_preset(tmp.path(), &preset("web", ".ts")).unwrap();
+
+    delete_filter_preset(tmp.path(), "RUST").unwrap();
+    delete_filter_preset(tmp.path(), "missing").unwrap();
+
+    let loaded = load_filter_presets(tmp.path());
+    assert_eq!(loaded.len(), 1);
+    assert_eq!(loaded[0].name, "web");
+}
+
+#[test]
+fn presets_only_touch_filter_fields() {
+    let settings = WorkspaceSettings {
+        ext_filter: ".rs".to_string(),
+        exclude_dirs: "target".to_string(),
+        exclude_files: "LICENSE".to_string(),
+        remove_prefix: "#".to_string(),
+        remove_regex: "TODO.*".to_string(),
+        line_numbers: true,
+        ..WorkspaceSettings::default()
+    };
+    let captured = FilterPreset::from_settings(" Rust ", &settings);
+    assert_eq!(captured.name, "Rust");
+    assert_eq!(captured.ext_filter, ".rs");
+    assert_eq!(captured.remove_prefix, "#");
+
+    let mut other = WorkspaceSettings {
+        remove_regex: "keep".to_string(),
+        dirs_only: true,
+        ..WorkspaceSettings::default()
+    };
+    captured.apply_to(&mut other);
+    assert_eq!(other.ext_filter, ".rs");
+    assert_eq!(other.exclude_dirs, "target");
+    assert_eq!(other.exclude_files, "LICENSE");
+    assert_eq!(other.remove_prefix, "#");
+    assert_eq!(other.remove_regex, "keep");
+    assert!(other.dirs_only);
+    assert!(!other.line_numbers);
+}
@@ -0,0 +1,116 @@
+use std::{
+    path::PathBuf,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use pretty_assertions::assert_eq;
+use stitch::core::{
+    GenerationError, GenerationProgress, GenerationRequest, Generator, OutputOrder, OutputParts,
+};
+use stitch::testing::write_file;
+use tempfile::TempDir;
+
+fn project(files: &[(&str, &str)]) -> (TempDir, Vec<PathBuf>) {
+    let tmp = TempDir::new().unwrap();
+    let paths = files
+        .iter()
+        .map(|(rel, text)| write_file(tmp.path(), rel, text).unwrap())
+        .collect();
+    (tmp, paths)
+}
+
+fn request(root: &TempDir, files: Vec<PathBuf>) -> GenerationRequest {
+    let mut request = GenerationRequest::new(
+        root.path(),
+        files,
+        OutputParts {
+            notes: Some(String::new()),
+            ..OutputParts::default()
+        },
+    );
+    request.output_order = OutputOrder::Alphabetical;
+    request
+}
+
+#[test]
+fn run_reports_progress_and_emits_every_file() {
+    let (tmp, files) = project(&[("b.txt", "bee"), ("a.txt", "ay")]);
+    let mut seen = Vec::new();
+    let out = Generator::new(request(&tmp, files))
+        .run(&AtomicBool::new(false), |p| seen.push(p))
+        .unwrap();
+
+    assert_eq!(
+        seen,
+        (0..=2)
+            .map(|files_done| GenerationProgress {
+                files_done,
+                files_total: 2,
+            })
+            .collect::<Vec<_>>()
+    );
+    let a = out.text.find("--- Start of file: a.txt ---\nay\n").unwrap();
+    let b = out
+        .text
+        .find("--- Start of file: b.txt ---\nbee\n")
+        .unwrap();
+    assert!(a < b);
+    assert_eq!(out.split.blocks.len(), 2);
+}
+
+#[test]
+fn cancelling_stops_before_the_next_file() {
+    let (tmp, files) = project(&[("a.txt", "a"), ("b.txt", "b"), ("c.txt", "c")]);
+    let cancel = AtomicBool::new(false);
+    let mut last = GenerationProgress::default();
+    let result = Generator::new(request(&tmp, files)).run(&cancel, |p| {
+        last = p;
+        if p.files_done == 1 {
+            cancel.store(true, Ordering::Relaxed);
+        }
+    });
+    assert_eq!(result, Err(GenerationError::Cancelled));
+    assert_eq!(last.files_done, 1);
+}
+
+#[test]
+fn unreadable_files_are_listed_in_notes() {
+    let (tmp, mut files) = project(&[("a.txt", "a")]);
+    files.push(tmp.path().join("gone.txt"));
+    let out = Generator::new(request(&tmp, files))
+        .run(&AtomicBool::new(false), |_| {})
+        .unwrap();
+    assert!(out.text.contains("Skipped files (1):\n- gone.txt: "));
+    assert_eq!(out.split.blocks.len(), 1);
+}
+
+#[test]
+fn hierarchy_only_requests_read_no_files() {
+    let (tmp, mut files) = project(&[("a.txt", "ay")]);
+    files.push(tmp.path().join("gone.txt"));
+    let mut req = request(&tmp, files);
+    req.parts.hierarchy = Some("root/\n└── a.txt\n".to_string());
+    req.hierarchy_only = true;
+    let mut seen = Vec::new();
+    let out = Generator::new(req)
+        .run(&AtomicBool::new(false), |p| seen.push(p))
+        .unwrap();
+
+    assert!(out.text.contains("└── a.txt"));
+    assert!(!out.text.contains("ay"));
+    assert!(!out.text.contains("gone.txt"));
+    assert!(out.split.blocks.is_empty());
+    assert!(seen.is_empty());
+}
+
+#[test]
+fn spawned_generation_matches_the_synchronous_run() {
+    let (tmp, files) = project(&[("src/lib.rs", "pub fn a() {}\n"), ("README.md", "# A\n")]);
+    let expected = Generator::new(request(&tmp, files.clone()))
+        .run(&AtomicBool::new(false), |_| {})
+        .unwrap();
+
+    let handle = Generator::new(request(&tmp, files)).spawn();
+    assert!(!handle.is_cancelled());
+    assert_eq!(handle.wait(), Ok(expected));
+}
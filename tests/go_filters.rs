@@ -0,0 +1,141 @@
+use pretty_assertions::assert_eq;
+use std::path::Path;
+use stitch::core::{
+    GoFilterOptions, GoOptions, TransformOptions, TransformPipeline, WorkspaceSettings,
+    apply_go_filters, is_generated_go_file_path, is_go_file_path,
+};
+
+fn signatures_only() -> GoFilterOptions {
+    GoFilterOptions {
+        function_signatures_only: true,
+        ..GoFilterOptions::default()
+    }
+}
+
+#[test]
+fn detects_go_and_generated_go_paths() {
+    assert!(is_go_file_path(Path::new("cmd/main.go")));
+    assert!(!is_go_file_path(Path::new("go.mod")));
+    assert!(is_generated_go_file_path(Path::new("api/v1/user.pb.go")));
+    assert!(is_generated_go_file_path(Path::new(
+        "api/v1/user_grpc.pb.go"
+    )));
+    assert!(!is_generated_go_file_path(Path::new("api/v1/user.go")));
+}
+
+#[test]
+fn removes_comments_outside_literals() {
+    let src = concat!(
+        "// Package api serves users.\n",
+        "package api\n",
+        "\n",
+        "/* block\n   comment */\n",
+        "const url = \"http://example.com\" // trailing\n",
+        "const raw = `// kept\n/* kept */`\n",
+        "const slash = '/'\n",
+    );
+    let opts = GoFilterOptions {
+        remove_line_comments: true,
+        remove_block_comments: true,
+        ..GoFilterOptions::default()
+    };
+    assert_eq!(
+        apply_go_filters(src, &opts),
+        concat!(
+            "package api\n",
+            "\n",
+            "const url = \"http://example.com\"\n",
+            "const raw = `// kept\n/* kept */`\n",
+            "const slash = '/'\n",
+        )
+    );
+}
+
+#[test]
+fn keeps_signatures_with_receivers_and_type_parameters() {
+    let src = concat!(
+        "package api\n",
+        "\n",
+        "type Server struct {\n",
+        "\taddr string\n",
+        "}\n",
+        "\n",
+        "func (s *Server) Start(ctx context.Context) error {\n",
+        "\tgo func() { s.run(\"}\") }()\n",
+        "\treturn nil\n",
+        "}\n",
+        "\n",
+        "func Map[T, U any](xs []T, f func(T) U) []U {\n",
+        "\treturn nil\n",
+        "}\n",
+        "\n",
+        "func Empty() interface{} { return struct{}{} }\n",
+        "\n",
+        "func multi(\n",
+        "\ta int,\n",
+        ") (n int, err error) {\n",
+        "\treturn\n",
+        "}\n",
+        "\n",
+        "var handler = func() {}\n",
+    );
+    assert_eq!(
+        apply_go_filters(src, &signatures_only()),
+        concat!(
+            "package api\n",
+            "\n",
+            "type Server struct {\n",
+            "\taddr string\n",
+            "}\n",
+            "\n",
+            "func (s *Server) Start(ctx context.Context) error\n",
+            "\n",
+            "func Map[T, U any](xs []T, f func(T) U) []U\n",
+            "\n",
+            "func Empty() interface{}\n",
+            "\n",
+            "func multi(\n",
+            "\ta int,\n",
+            ") (n int, err error)\n",
+            "\n",
+            "var handler = func() {}\n",
+        )
+    );
+}
+
+#[test]
+fn settings_keys_map_to_filter_options() {
+    let mut json = serde_json::to_value(WorkspaceSettings::default()).expect("serialize");
+    json["go_remove_line_comments"] = true.into();
+    json["go_function_signatures_only"] = true.into();
+    let ws: WorkspaceSettings = serde_json::from_value(json).expect("parse settings");
+    assert_eq!(
+        ws.go,
+        GoOptions {
+            go_remove_line_comments: true,
+            go_function_signatures_only: true,
+            ..GoOptions::default()
+        }
+    );
+    let opts = ws.go.filter_options();
+    assert!(opts.remove_line_comments && !opts.remove_block_comments);
+    assert!(!ws.go.go_keep_generated);
+}
+
+#[test]
+fn default_pipeline_runs_the_filters_on_go_files_only() {
+    let opts = TransformOptions {
+        go: signatures_only(),
+        ..TransformOptions::default()
+    };
+    let src = "func main() {\n\trun()\n}\n";
+    let pipeline = TransformPipeline::default();
+    assert_eq!(
+        pipeline.apply("main.go", src.to_string(), &opts).contents,
+        "func main()\n"
+    );
+    assert_eq!(
+        pipeline.apply("main.txt", src.to_string(), &opts).contents,
+        src
+    );
+}
@@ -0,0 +1,51 @@
+use stitch::core::{HierarchyCache, render_unicode_tree_from_paths};
+
+fn paths(list: &[&str]) -> Vec<String> {
+    list.iter().map(|s| (*s).to_string()).collect()
+}
+
+#[test]
+fn cached_render_matches_uncached() {
+    let p = paths(&["src/main.rs", "src/ui/mod.rs", "README.md"]);
+    let mut cache = HierarchyCache::new();
+    let expected = render_unicode_tree_from_paths(&p, Some("proj"));
+    assert_eq!(cache.render(&p, Some("proj")), expected);
+    assert_eq!(cache.render(&p, Some("proj")), expected);
+}
+
+#[test]
+fn unchanged_selection_is_served_from_cache_in_any_order() {
+    let p = paths(&["a/b.rs", "a/c.rs", "d.rs"]);
+    let mut cache = HierarchyCache::new();
+    assert!(!cache.is_cached(&p, Some("root")));
+    let first = cache.render(&p, Some("root"));
+    assert!(cache.is_cached(&p, Some("root")));
+
+    let shuffled = paths(&["d.rs", "a/c.rs", "a/b.rs"]);
+    assert!(cache.is_cached(&shuffled, Some("root")));
+    assert_eq!(cache.render(&shuffled, Some("root")), first);
+}
+
+#[test]
+fn selection_or_root_changes_invalidate() {
+    let p = paths(&["a/b.rs", "d.rs"]);
+    let mut cache = HierarchyCache::new();
+    let _ = cache.render(&p, Some("root"));
+
+    let grown = paths(&["a/b.rs", "a/e.rs", "d.rs"]);
+    assert!(!cache.is_cached(&grown, Some("root")));
+    assert_eq!(
+        cache.render(&grown, Some("root")),
+        render_unicode_tree_from_paths(&grown, Some("root"))
+    );
+
+    assert!(!cache.is_cached(&grown, Some("other")));
+    assert!(!cache.is_cached(&grown, None));
+    assert_eq!(
+        cache.render(&grown, None),
+        render_unicode_tree_from_paths(&grown, None)
+    );
+
+    cache.clear();
+    assert!(!cache.is_cached(&grown, None));
+}
@@ -0,0 +1,92 @@
+use std::{collections::HashMap, path::PathBuf};
+
+use stitch::core::{
+    Node, PathRewrite, TreeStyle, render_hierarchy, render_unicode_tree_from_paths,
+    selection_hierarchy,
+};
+
+fn file(parent: &str, name: &str) -> Node {
+    Node {
+        name: name.into(),
+        path: PathBuf::from(parent).join(name),
+        is_dir: false,
+        children: Vec::new(),
+        expanded: false,
+        has_children: false,
+    }
+}
+
+fn project() -> Node {
+    let src = Node {
+        name: "src".into(),
+        path: PathBuf::from("/p/app/src"),
+        is_dir: true,
+        children: vec![file("/p/app/src", "lib.rs"), file("/p/app/src", "main.rs")],
+        expanded: true,
+        has_children: true,
+    };
+    Node {
+        name: "app".into(),
+        path: PathBuf::from("/p/app"),
+        is_dir: true,
+        children: vec![file("/p/app", "README.md"), src],
+        expanded: true,
+        has_children: true,
+    }
+}
+
+#[test]
+fn nothing_selected_has_no_tree() {
+    assert_eq!(
+        selection_hierarchy(
+            &project(),
+            &HashMap::new(),
+            &PathRewrite::default(),
+            TreeStyle::Unicode
+        ),
+        None
+    );
+}
+
+#[test]
+fn tree_matches_the_hierarchy_section_body() {
+    let explicit = HashMap::from([
+        (PathBuf::from("/p/app/src"), true),
+        (PathBuf::from("/p/app/src/main.rs"), false),
+    ]);
+    let tree = selection_hierarchy(
+        &project(),
+        &explicit,
+        &PathRewrite::default(),
+        TreeStyle::Unicode,
+    )
+    .unwrap();
+    assert_eq!(
+        tree,
+        render_unicode_tree_from_paths(&["src/lib.rs".to_string()], Some("app"))
+    );
+    assert!(!tree.contains("==="));
+    assert!(!tree.contains("main.rs"));
+}
+
+#[test]
+fn path_rewrite_applies_to_the_tree() {
+    let rewrite = PathRewrite {
+        strip_prefix: "src".into(),
+        prepend: "crate".into(),
+    };
+    let explicit = HashMap::from([(PathBuf::from("/p/app"), true)]);
+    let tree = selection_hierarchy(&project(), &explicit, &rewrite, TreeStyle::Unicode).unwrap();
+    let paths = ["README.md", "src/lib.rs", "src/main.rs"].map(str::to_string);
+    assert_eq!(
+        tree,
+        render_hierarchy(&paths, "app", &rewrite, TreeStyle::Unicode)
+    );
+    assert_eq!(
+        tree,
+        render_unicode_tree_from_paths(
+            &["crate/README.md", "crate/lib.rs", "crate/main.rs"].map(str::to_string),
+            Some("app")
+        )
+    );
+}
@@ -0,0 +1,49 @@
+use std::collections::HashSet;
+
+use pretty_assertions::assert_eq;
+
+use stitch::core::parse_hierarchy_text;
+
+fn set(items: &[&str]) -> HashSet<String> {
+    items.iter().map(|s| (*s).to_string()).collect()
+}
+
+fn expected() -> HashSet<String> {
+    set(&[
+        "src",
+        "src/core",
+        "src/core/fs.rs",
+        "src/main.rs",
+        "README.md",
+    ])
+}
+
+#[test]
+fn two_and_four_space_listings_are_detected() {
+    let two = "app/\n  src/\n    core/\n      fs.rs\n    main.rs\n  README.md\n";
+    assert_eq!(parse_hierarchy_text(two), Some(expected()));
+
+    let four = "app\n    src\n        core\n            fs.rs\n        main.rs\n    README.md\n";
+    assert_eq!(parse_hierarchy_text(four), Some(expected()));
+}
+
+#[test]
+fn children_may_start_at_the_root_column_or_use_tabs() {
+    let flush = "app\nsrc\n  core\n    fs.rs\n  main.rs\nREADME.md\n";
+    assert_eq!(parse_hierarchy_text(flush), Some(expected()));
+
+    let tabs = "app\n\tsrc\n\t\tcore\n\t\t\tfs.rs\n\t\tmain.rs\n\tREADME.md\n";
+    assert_eq!(parse_hierarchy_text(tabs), Some(expected()));
+}
+
+#[test]
+fn flat_listings_and_crlf_still_work() {
+    assert_eq!(
+        parse_hierarchy_text("app\r\na.rs\r\nb.rs\r\n"),
+        Some(set(&["a.rs", "b.rs"]))
+    );
+    assert_eq!(
+        parse_hierarchy_text("app\r\n  src/\r\n    lib.rs  \r\n\r\n"),
+        Some(set(&["src", "src/lib.rs"]))
+    );
+}
@@ -0,0 +1,105 @@
+use std::path::Path;
+
+use pretty_assertions::assert_eq;
+
+use stitch::core::{
+    WorkspaceSettings, format_byte_size, image_dimensions, image_placeholder, is_image_path,
+};
+
+fn png(width: u32, height: u32) -> Vec<u8> {
+    let mut bytes = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR".to_vec();
+    bytes.extend(width.to_be_bytes());
+    bytes.extend(height.to_be_bytes());
+    bytes.extend([8, 6, 0, 0, 0]);
+    bytes
+}
+
+#[test]
+fn reads_dimensions_from_binary_headers() {
+    assert_eq!(image_dimensions(&png(640, 480)), Some((640, 480)));
+
+    let gif = [b"GIF89a".as_slice(), &[0x20, 0x01, 0x10, 0x00]].concat();
+    assert_eq!(image_dimensions(&gif), Some((288, 16)));
+
+    // SOI, an APP0 segment, then SOF0 with height 0x0100 and width 0x0200.
+    let jpeg = [
+        0xff, 0xd8, 0xff, 0xe0, 0x00, 0x04, 0x00, 0x00, 0xff, 0xc0, 0x00, 0x11, 0x08, 0x01, 0x00,
+        0x02, 0x00,
+    ];
+    assert_eq!(image_dimensions(&jpeg), Some((512, 256)));
+
+    let mut bmp = vec![0u8; 26];
+    bmp[..2].copy_from_slice(b"BM");
+    bmp[18..22].copy_from_slice(&100i32.to_le_bytes());
+    bmp[22..26].copy_from_slice(&(-50i32).to_le_bytes());
+    assert_eq!(image_dimensions(&bmp), Some((100, 50)));
+
+    let mut webp = b"RIFF\0\0\0\0WEBPVP8X".to_vec();
+    webp.extend([0u8; 8]);
+    webp.extend([0x3f, 0x00, 0x00, 0x1f, 0x00, 0x00]);
+    assert_eq!(image_dimensions(&webp), Some((64, 32)));
+
+    assert_eq!(image_dimensions(b"\x89PNG"), None);
+    assert_eq!(image_dimensions(b"plain text"), None);
+}
+
+#[test]
+fn reads_svg_size_from_attributes_or_view_box() {
+    let sized = br#"<?xml version="1.0"?><svg xmlns="http://www.w3.org/2000/svg" width="24px" height="16"></svg>"#;
+    assert_eq!(image_dimensions(sized), Some((24, 16)));
+    let view_box = br#"<svg viewBox="0 0 100 50.4" xmlns="http://www.w3.org/2000/svg"/>"#;
+    assert_eq!(image_dimensions(view_box), Some((100, 50)));
+    let percent = br#"<svg width="100%" height="100%"/>"#;
+    assert_eq!(image_dimensions(percent), None);
+}
+
+#[test]
+fn placeholder_lists_name_dimensions_and_size() {
+    assert_eq!(
+        image_placeholder(Path::new("assets/logo.png"), &png(64, 64), 4096),
+        "[image: logo.png, 64x64 px, 29 B]\n"
+    );
+    assert_eq!(
+        image_placeholder(Path::new("photo.JPG"), &[0u8; 3000], 4096),
+        "[image: photo.JPG, 2.9 KiB]\n"
+    );
+}
+
+#[test]
+fn small_svgs_are_inlined_under_the_limit() {
+    let svg = br#"<svg width="8" height="8"><rect/></svg>"#;
+    let size = svg.len();
+    assert_eq!(
+        image_placeholder(Path::new("icon.svg"), svg, 4096),
+        format!(
+            "[image: icon.svg, 8x8 px, {size} B]\n<svg width=\"8\" height=\"8\"><rect/></svg>\n"
+        )
+    );
+    assert_eq!(
+        image_placeholder(Path::new("icon.svg"), svg, 0),
+        format!("[image: icon.svg, 8x8 px, {size} B]\n")
+    );
+}
+
+#[test]
+fn image_paths_and_sizes() {
+    assert!(is_image_path(Path::new("a/b.PNG")));
+    assert!(is_image_path(Path::new("x.svg")));
+    assert!(!is_image_path(Path::new("x.rs")));
+    assert_eq!(format_byte_size(1023), "1023 B");
+    assert_eq!(format_byte_size(1536), "1.5 KiB");
+    assert_eq!(format_byte_size(5 * 1024 * 1024), "5.0 MiB");
+}
+
+#[test]
+fn svg_inline_limit_is_optional_in_workspace_json() {
+    let json = serde_json::to_value(WorkspaceSettings::default()).unwrap();
+    assert!(json.get("svg_inline_limit").is_none());
+    let ws = WorkspaceSettings {
+        svg_inline_limit: Some(0),
+        ..WorkspaceSettings::default()
+    };
+    let back: WorkspaceSettings =
+        serde_json::from_value(serde_json::to_value(&ws).unwrap()).unwrap();
+    assert_eq!(back.svg_inline_limit, Some(0));
+}
@@ -0,0 +1,210 @@
+use pretty_assertions::assert_eq;
+use serde_json::{Value, json};
+use stitch::core::{
+    CliCommand, DirRuleKind, DirectoryRule, DirectorySample, OutputLayout, PostProcess, Profile,
+    ProfileSelection, SchemaTarget, TransformKind, TransformPipeline, TransformStep,
+    WorkspaceSettings, json_schema, parse_launch_args, render_json_schema,
+};
+
+/// Errors of `value` against `schema`, for the subset of draft-07 the settings schemas use.
+fn validate(root: &Value, schema: &Value, value: &Value, at: &str, errors: &mut Vec<String>) {
+    if let Some(target) = schema.get("$ref").and_then(Value::as_str) {
+        let name = target.trim_start_matches("#/definitions/");
+        validate(root, &root["definitions"][name], value, at, errors);
+        return;
+    }
+    if let Some(variants) = schema.get("oneOf").and_then(Value::as_array) {
+        let matching = variants
+            .iter()
+            .filter(|variant| {
+                let mut sub = Vec::new();
+                validate(root, variant, value, at, &mut sub);
+                sub.is_empty()
+            })
+            .count();
+        if matching != 1 {
+            errors.push(format!("{at}: matches {matching} variants"));
+        }
+        return;
+    }
+    if let Some(allowed) = schema.get("enum").and_then(Value::as_array)
+        && !allowed.contains(value)
+    {
+        errors.push(format!("{at}: {value} not in {allowed:?}"));
+    }
+    if let Some(expected) = schema.get("const")
+        && expected != value
+    {
+        errors.push(format!("{at}: {value} != {expected}"));
+    }
+    let type_ok = match schema.get("type").and_then(Value::as_str) {
+        Some("object") => value.is_object(),
+        Some("array") => value.is_array(),
+        Some("string") => value.is_string(),
+        Some("boolean") => value.is_boolean(),
+        Some("integer") => value.is_u64() || value.is_i64(),
+        _ => true,
+    };
+    if !type_ok {
+        errors.push(format!("{at}: {value} is not {}", schema["type"]));
+        return;
+    }
+    if let Some(min) = schema.get("minimum").and_then(Value::as_i64)
+        && value.as_i64().is_some_and(|n| n < min)
+    {
+        errors.push(format!("{at}: {value} < {min}"));
+    }
+    if let (Some(items), Some(values)) = (schema.get("items"), value.as_array()) {
+        for (i, item) in values.iter().enumerate() {
+            validate(root, items, item, &format!("{at}[{i}]"), errors);
+        }
+    }
+    let Some(fields) = value.as_object() else {
+        return;
+    };
+    for name in schema["required"].as_array().into_iter().flatten() {
+        if !fields.contains_key(name.as_str().unwrap()) {
+            errors.push(format!("{at}: missing {name}"));
+        }
+    }
+    for (name, field) in fields {
+        let at = format!("{at}.{name}");
+        match schema.get("properties").and_then(|p| p.get(name)) {
+            Some(property) => validate(root, property, field, &at, errors),
+            None => match schema.get("additionalProperties") {
+                Some(Value::Bool(false)) => errors.push(format!("{at}: unknown key")),
+                Some(extra @ Value::Object(_)) => validate(root, extra, field, &at, errors),
+                _ => {}
+            },
+        }
+    }
+}
+
+fn errors(target: SchemaTarget, value: &Value) -> Vec<String> {
+    let schema = json_schema(target);
+    let mut errors = Vec::new();
+    validate(&schema, &schema, value, "$", &mut errors);
+    errors
+}
+
+/// Settings with every optional field set, so all keys are serialized.
+fn full_settings() -> WorkspaceSettings {
+    let mut settings = WorkspaceSettings {
+        version: 1,
+        languages: [("tpl".to_string(), "handlebars".to_string())].into(),
+        svg_inline_limit: Some(0),
+        post_process: Some(PostProcess {
+            command: "./wrap".into(),
+            timeout_secs: Some(5),
+        }),
+        layout: OutputLayout {
+            provenance: Some(String::new()),
+            modified_times: true,
+            checksum_footer: true,
+            ..OutputLayout::default()
+        },
+        ..WorkspaceSettings::default()
+    };
+    settings.layout.sections[0].header = Some("## Tree".into());
+    settings.transforms = TransformPipeline {
+        steps: vec![
+            TransformStep::for_extensions(TransformKind::SchemaOnly, &["json"]),
+            TransformStep::new(TransformKind::TableHead { rows: 5 }),
+            TransformStep::new(TransformKind::Notebook { markdown: true }),
+            TransformStep::new(TransformKind::Command {
+                command: "fmt".into(),
+                timeout_secs: Some(3),
+            }),
+            TransformStep::new(TransformKind::MacroExpand {
+                timeout_secs: Some(60),
+            }),
+        ],
+    };
+    settings
+}
+
+#[test]
+fn default_and_full_workspace_settings_validate() {
+    let default = serde_json::to_value(WorkspaceSettings::default()).unwrap();
+    assert_eq!(
+        errors(SchemaTarget::Workspace, &default),
+        Vec::<String>::new()
+    );
+    let full = serde_json::to_value(full_settings()).unwrap();
+    assert_eq!(errors(SchemaTarget::Workspace, &full), Vec::<String>::new());
+}
+
+#[test]
+fn every_default_transform_kind_is_known() {
+    let steps = serde_json::to_value(TransformPipeline::default()).unwrap();
+    let mut value = serde_json::to_value(WorkspaceSettings::default()).unwrap();
+    value["transforms"] = steps;
+    assert_eq!(
+        errors(SchemaTarget::Workspace, &value),
+        Vec::<String>::new()
+    );
+}
+
+#[test]
+fn profiles_validate() {
+    let profile = Profile {
+        name: "review".into(),
+        settings: full_settings(),
+        explicit: vec![ProfileSelection {
+            path: "src".into(),
+            state: true,
+        }],
+        samples: vec![DirectorySample {
+            path: "tests".into(),
+            count: 3,
+            ..DirectorySample::default()
+        }],
+        dir_rules: vec![DirectoryRule {
+            path: "vendor".into(),
+            kind: DirRuleKind::IgnoreExtensionFilters,
+        }],
+        pinned: vec!["README.md".into()],
+    };
+    let mut value = serde_json::to_value(profile).unwrap();
+    value["$schema"] = json!("./stitch-profile.schema.json");
+    assert_eq!(errors(SchemaTarget::Profile, &value), Vec::<String>::new());
+}
+
+#[test]
+fn typos_and_wrong_types_are_reported() {
+    let mut value = serde_json::to_value(WorkspaceSettings::default()).unwrap();
+    value["line_number"] = json!(true);
+    value["tree_style"] = json!("fancy");
+    value["transforms"] = json!([{ "kind": "command" }, { "kind": "minfy" }]);
+    value["context_window"] = json!(128_000);
+    assert_eq!(
+        errors(SchemaTarget::Workspace, &value),
+        [
+            "$.context_window: 128000 not in [String(\"off\"), String(\"128k\"), String(\"200k\"), String(\"1m\")]",
+            "$.line_number: unknown key",
+            "$.transforms[0]: matches 0 variants",
+            "$.transforms[1]: matches 0 variants",
+            "$.tree_style: \"fancy\" not in [String(\"unicode\"), String(\"ascii\")]",
+        ]
+    );
+}
+
+#[test]
+fn schema_command_prints_pretty_json() {
+    assert_eq!(
+        parse_launch_args(["schema", "profile"]).unwrap().command,
+        Some(CliCommand::Schema(SchemaTarget::Profile))
+    );
+    assert_eq!(
+        parse_launch_args(["schema"]).unwrap_err(),
+        "the following required arguments were not provided: <FILE>"
+    );
+    assert_eq!(
+        parse_launch_args(["schema", "local"]).unwrap_err(),
+        "invalid value 'local' for '<FILE>' [possible values: workspace, profile]"
+    );
+    let text = render_json_schema(SchemaTarget::Workspace);
+    assert!(text.ends_with("}\n"));
+    let parsed: Value = serde_json::from_str(&text).unwrap();
+    assert_eq!(parsed["title"], "Stitch workspace settings");
+}
@@ -0,0 +1,70 @@
+use std::collections::BTreeMap;
+
+use stitch::core::{
+    WorkspaceSettings, lang_for_path, load_workspace, override_for, save_workspace,
+};
+use tempfile::TempDir;
+
+fn map(entries: &[(&str, &str)]) -> BTreeMap<String, String> {
+    entries
+        .iter()
+        .map(|(key, lang)| ((*key).to_string(), (*lang).to_string()))
+        .collect()
+}
+
+#[test]
+fn builtin_languages() {
+    let none = BTreeMap::new();
+    assert_eq!(lang_for_path("src/lib.rs", &none), "rust");
+    assert_eq!(lang_for_path("web/App.TSX", &none), "tsx");
+    assert_eq!(lang_for_path("ci/build.yml", &none), "yaml");
+    assert_eq!(lang_for_path("docker/Dockerfile", &none), "dockerfile");
+    assert_eq!(lang_for_path("CMakeLists.txt", &none), "cmake");
+    assert_eq!(lang_for_path(r"win\path\main.py", &none), "python");
+}
+
+#[test]
+fn unknown_extensions_map_to_themselves_and_extensionless_to_nothing() {
+    let none = BTreeMap::new();
+    assert_eq!(lang_for_path("data.parquet", &none), "parquet");
+    assert_eq!(lang_for_path("LICENSE", &none), "");
+    assert_eq!(lang_for_path(".gitignore", &none), "");
+}
+
+#[test]
+fn overrides_win_over_the_builtin_table() {
+    let overrides = map(&[
+        (".tpl", "handlebars"),
+        ("H", "cpp"),
+        ("Justfile", "just"),
+        ("ts", "ts"),
+        ("d.ts", "typescript-declarations"),
+    ]);
+    assert_eq!(lang_for_path("views/page.tpl", &overrides), "handlebars");
+    assert_eq!(lang_for_path("include/api.h", &overrides), "cpp");
+    assert_eq!(lang_for_path("justfile", &overrides), "just");
+    assert_eq!(lang_for_path("src/app.ts", &overrides), "ts");
+    assert_eq!(
+        lang_for_path("types/index.d.ts", &overrides),
+        "typescript-declarations"
+    );
+    assert_eq!(lang_for_path("src/lib.rs", &overrides), "rust");
+    // A dotfile named like a key is not an extension match.
+    assert_eq!(override_for(".tpl", &overrides), None);
+}
+
+#[test]
+fn overrides_persist_in_workspace() {
+    let tmp = TempDir::new().unwrap();
+    let ws = WorkspaceSettings {
+        version: 1,
+        languages: map(&[("tpl", "handlebars")]),
+        ..Default::default()
+    };
+    save_workspace(tmp.path(), &ws).unwrap();
+    assert_eq!(load_workspace(tmp.path()).unwrap().languages, ws.languages);
+
+    save_workspace(tmp.path(), &WorkspaceSettings::default()).unwrap();
+    let json = std::fs::read_to_string(tmp.path().join(".stitchworkspace/workspace.json")).unwrap();
+    assert!(!json.contains("languages"));
+}
@@ -0,0 +1,176 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use pretty_assertions::assert_eq;
+
+use stitch::core::{
+    NameMatching, Node, ScanFilters, collect_selected_paths, gather_paths_set, is_unloaded_dir,
+    load_dir, load_selected_dirs, loaded_dirs, scan_dir_to_node_lazy, scan_dir_to_node_with_stats,
+};
+use stitch::testing::{FileSpec, make_on_disk, write_file};
+
+fn set(items: &[&str]) -> HashSet<String> {
+    items.iter().map(|s| (*s).to_string()).collect()
+}
+
+fn project(root: &Path) {
+    let files = [
+        "README.md",
+        "src/main.rs",
+        "src/core/fs.rs",
+        "src/core/deep/more/leaf.rs",
+        "docs/guide.md",
+    ];
+    make_on_disk(root, &files.map(FileSpec::parse)).unwrap();
+    fs::create_dir_all(root.join("empty/nested")).unwrap();
+}
+
+fn child<'a>(node: &'a Node, name: &str) -> &'a Node {
+    node.children.iter().find(|c| c.name == name).unwrap()
+}
+
+fn child_mut<'a>(node: &'a mut Node, name: &str) -> &'a mut Node {
+    node.children.iter_mut().find(|c| c.name == name).unwrap()
+}
+
+struct Sets {
+    include: HashSet<String>,
+    none: HashSet<String>,
+}
+
+impl Sets {
+    fn new(include: &[&str]) -> Self {
+        Self {
+            include: set(include),
+            none: HashSet::new(),
+        }
+    }
+
+    fn filters(&self) -> ScanFilters<'_, std::hash::RandomState> {
+        ScanFilters {
+            include_exts: &self.include,
+            exclude_exts: &self.none,
+            exclude_dirs: &self.none,
+            exclude_files: &self.none,
+            rules: &[],
+            names: NameMatching::Exact,
+        }
+    }
+}
+
+#[test]
+fn only_the_top_level_is_read_up_front() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    project(root);
+    let sets = Sets::new(&[]);
+
+    let tree = scan_dir_to_node_lazy(root, &sets.filters(), &HashSet::<PathBuf>::new()).node;
+    let names: Vec<&str> = tree.children.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(names, vec!["README.md", "docs", "empty", "src"]);
+
+    let src = child(&tree, "src");
+    assert!(is_unloaded_dir(src));
+    assert!(!src.expanded);
+    // A directory with no files is read in full; there's nothing to defer.
+    let empty = child(&tree, "empty");
+    assert!(!is_unloaded_dir(empty) && !empty.has_children);
+    assert_eq!(empty.children.len(), 1);
+
+    assert_eq!(
+        loaded_dirs(&tree),
+        HashSet::from([
+            root.to_path_buf(),
+            root.join("empty"),
+            root.join("empty/nested")
+        ])
+    );
+}
+
+#[test]
+fn include_mode_hides_directories_without_matching_files() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    project(root);
+    let sets = Sets::new(&[".rs"]);
+
+    let tree = scan_dir_to_node_lazy(root, &sets.filters(), &HashSet::<PathBuf>::new()).node;
+    let names: Vec<&str> = tree.children.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(names, vec!["src"]);
+    assert!(is_unloaded_dir(child(&tree, "src")));
+}
+
+#[test]
+fn probed_directories_without_matches_report_like_the_full_scan() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    project(root);
+    write_file(root, "docs/api/index.md", "x").unwrap();
+    let sets = Sets::new(&[".rs"]);
+
+    let lazy = scan_dir_to_node_lazy(root, &sets.filters(), &HashSet::<PathBuf>::new()).stats;
+    let full =
+        scan_dir_to_node_with_stats(root, &sets.include, &sets.none, &sets.none, &sets.none).stats;
+    // README.md, docs/guide.md and docs/api/index.md; src/ is left unloaded.
+    assert_eq!(lazy.filtered_files, 3);
+    assert_eq!(full.filtered_files, 3);
+}
+
+#[test]
+fn expanding_loads_one_level_and_matches_the_full_scan() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    project(root);
+    let sets = Sets::new(&[]);
+
+    let mut tree = scan_dir_to_node_lazy(root, &sets.filters(), &HashSet::<PathBuf>::new()).node;
+    let src = child_mut(&mut tree, "src");
+    load_dir(root, src, &sets.filters(), false);
+    assert!(!is_unloaded_dir(src));
+    let names: Vec<&str> = src.children.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(names, vec!["main.rs", "core"]);
+    assert!(is_unloaded_dir(child(src, "core")));
+
+    // Loading everything gives the same paths as scanning eagerly.
+    load_dir(root, child_mut(src, "core"), &sets.filters(), true);
+    load_dir(root, child_mut(&mut tree, "docs"), &sets.filters(), true);
+    let full =
+        scan_dir_to_node_with_stats(root, &sets.none, &sets.none, &sets.none, &sets.none).node;
+    assert_eq!(gather_paths_set(&tree), gather_paths_set(&full));
+
+    // A rescan keeps what was loaded.
+    let rescan = scan_dir_to_node_lazy(root, &sets.filters(), &loaded_dirs(&tree)).node;
+    assert_eq!(gather_paths_set(&rescan), gather_paths_set(&full));
+}
+
+#[test]
+fn selected_unloaded_directories_are_loaded_before_collecting() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    project(root);
+    let sets = Sets::new(&[]);
+
+    let mut tree = scan_dir_to_node_lazy(root, &sets.filters(), &HashSet::<PathBuf>::new()).node;
+    let explicit = HashMap::from([
+        (root.join("docs"), true),
+        (root.join("src/core/deep/more/leaf.rs"), true),
+    ]);
+    load_selected_dirs(root, &mut tree, &explicit, &sets.filters());
+    let loaded = loaded_dirs(&tree);
+    assert!(loaded.contains(&root.join("docs")));
+    assert!(loaded.contains(&root.join("src/core/deep/more")));
+
+    let (mut files, mut dirs) = (Vec::new(), Vec::new());
+    collect_selected_paths(&tree, &explicit, None, &mut files, &mut dirs);
+    files.sort();
+    assert_eq!(
+        files,
+        vec![
+            root.join("docs/guide.md"),
+            root.join("src/core/deep/more/leaf.rs")
+        ]
+    );
+}
@@ -0,0 +1,71 @@
+use std::fmt::Write;
+
+use stitch::core::{
+    RustFilterOptions, WorkspaceSettings, apply_rust_filters, load_workspace,
+    number_lines_from_original, save_workspace,
+};
+use tempfile::TempDir;
+
+#[test]
+fn unfiltered_content_is_numbered_sequentially() {
+    let src = "a\nb\n\nc\n";
+    assert_eq!(
+        number_lines_from_original(src, src),
+        "1 | a\n2 | b\n3 |\n4 | c\n"
+    );
+}
+
+#[test]
+fn gutter_is_right_aligned_to_the_widest_number() {
+    let src: String = (1..=10).fold(String::new(), |mut acc, i| {
+        let _ = writeln!(acc, "l{i}");
+        acc
+    });
+    let out = number_lines_from_original(&src, &src);
+    assert!(out.starts_with(" 1 | l1\n"));
+    assert!(out.ends_with("10 | l10\n"));
+}
+
+#[test]
+fn removed_lines_keep_original_numbers() {
+    let original = "fn a() {\n    // note\n    let x = 1; // trailing\n}\n";
+    let filtered = "fn a() {\n    let x = 1;\n}\n";
+    assert_eq!(
+        number_lines_from_original(original, filtered),
+        "1 | fn a() {\n3 |     let x = 1;\n4 | }\n"
+    );
+}
+
+#[test]
+fn works_with_rust_comment_filter() {
+    let original = "/// docs\nfn main() {\n    // hi\n    run();\n}\n";
+    let filtered = apply_rust_filters(
+        original,
+        &RustFilterOptions {
+            remove_inline_regular_comments: true,
+            remove_doc_comments: true,
+            function_signatures_only: false,
+        },
+    );
+    let numbered = number_lines_from_original(original, &filtered);
+    assert!(numbered.contains("2 | fn main() {"), "{numbered}");
+    assert!(numbered.contains("4 |     run();"), "{numbered}");
+}
+
+#[test]
+fn unmatched_lines_get_blank_gutter_and_no_trailing_newline_is_preserved() {
+    let out = number_lines_from_original("x\ny", "x\nsynthesized\ny");
+    assert_eq!(out, "1 | x\n  | synthesized\n2 | y");
+}
+
+#[test]
+fn setting_persists_in_workspace() {
+    let tmp = TempDir::new().unwrap();
+    let ws = WorkspaceSettings {
+        version: 1,
+        line_numbers: true,
+        ..Default::default()
+    };
+    save_workspace(tmp.path(), &ws).unwrap();
+    assert!(load_workspace(tmp.path()).unwrap().line_numbers);
+}
@@ -0,0 +1,135 @@
+use pretty_assertions::assert_eq;
+
+use stitch::core::{
+    TransformKind, TransformOptions, TransformPipeline, TransformStep, summarize_lockfile,
+};
+
+const CARGO_LOCK: &str = r#"# This file is automatically @generated by Cargo.
+version = 4
+
+[[package]]
+name = "app"
+version = "0.1.0"
+dependencies = [
+ "core-lib",
+ "serde",
+ "rand 0.8.5",
+ "rand 0.9.1",
+]
+
+[[package]]
+name = "core-lib"
+version = "0.1.0"
+dependencies = [
+ "anyhow",
+]
+
+[[package]]
+name = "serde"
+version = "1.0.228"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+dependencies = [
+ "serde_derive",
+]
+
+[[package]]
+name = "serde_derive"
+version = "1.0.228"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "anyhow"
+version = "1.0.100"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "rand"
+version = "0.8.5"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "rand"
+version = "0.9.1"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+"#;
+
+#[test]
+fn cargo_lock_lists_direct_dependencies_of_local_packages() {
+    assert_eq!(
+        summarize_lockfile("Cargo.lock", CARGO_LOCK).unwrap(),
+        "\
+Cargo.lock summary: direct dependencies of app, core-lib (7 packages locked)
+anyhow 1.0.100
+rand 0.8.5, 0.9.1
+serde 1.0.228
+"
+    );
+}
+
+#[test]
+fn package_lock_v3_resolves_root_dependencies() {
+    let lock = r#"{
+      "name": "web",
+      "lockfileVersion": 3,
+      "packages": {
+        "": {
+          "name": "web",
+          "dependencies": { "react": "^18.2.0" },
+          "devDependencies": { "vite": "^5.0.0" }
+        },
+        "node_modules/react": { "version": "18.3.1" },
+        "node_modules/loose-envify": { "version": "1.4.0" },
+        "node_modules/vite": { "version": "5.4.2", "dev": true }
+      }
+    }"#;
+    assert_eq!(
+        summarize_lockfile("frontend/package-lock.json", lock).unwrap(),
+        "\
+package-lock.json summary: direct dependencies of web (3 packages locked)
+react 18.3.1
+vite 5.4.2
+"
+    );
+}
+
+#[test]
+fn package_lock_v1_uses_top_level_dependencies() {
+    let lock = r#"{
+      "name": "old",
+      "lockfileVersion": 1,
+      "dependencies": {
+        "lodash": { "version": "4.17.21" },
+        "left-pad": { "version": "1.3.0" }
+      }
+    }"#;
+    assert_eq!(
+        summarize_lockfile("package-lock.json", lock).unwrap(),
+        "\
+package-lock.json summary: direct dependencies of old (2 packages locked)
+left-pad 1.3.0
+lodash 4.17.21
+"
+    );
+}
+
+#[test]
+fn other_files_and_broken_lockfiles_are_left_alone() {
+    assert_eq!(summarize_lockfile("yarn.lock", "x"), None);
+    assert_eq!(summarize_lockfile("Cargo.lock", "not [toml"), None);
+    assert_eq!(summarize_lockfile("package-lock.json", "{}"), None);
+
+    let p = TransformPipeline {
+        steps: vec![TransformStep::new(TransformKind::SummarizeLockfiles)],
+    };
+    let opts = TransformOptions::default();
+    assert_eq!(
+        p.apply("src/main.rs", "fn main() {}\n".into(), &opts)
+            .contents,
+        "fn main() {}\n"
+    );
+    assert!(
+        p.apply("Cargo.lock", CARGO_LOCK.into(), &opts)
+            .contents
+            .starts_with("Cargo.lock summary")
+    );
+}
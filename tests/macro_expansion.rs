@@ -0,0 +1,162 @@
+use std::{cell::Cell, path::Path};
+
+use pretty_assertions::assert_eq;
+
+use stitch::core::{
+    ExpandCache, ExpandTarget, TransformKind, TransformOptions, TransformPipeline, TransformStep,
+    expand_target,
+};
+use stitch::testing::write_file;
+
+fn args(root: &Path, rel: &str) -> Option<Vec<String>> {
+    expand_target(root, rel).map(|t| t.args)
+}
+
+#[test]
+fn files_map_to_lib_bin_and_module_targets() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    write_file(root, "Cargo.toml", "[package]\nname = \"app\"\n").unwrap();
+    write_file(root, "src/lib.rs", "").unwrap();
+    write_file(
+        root,
+        "crates/cli/Cargo.toml",
+        "[package]\nname = \"cli-tool\"\n",
+    )
+    .unwrap();
+    write_file(root, "crates/cli/src/main.rs", "").unwrap();
+
+    assert_eq!(args(root, "src/lib.rs").unwrap(), ["--lib"]);
+    assert_eq!(
+        args(root, "src/net/http.rs").unwrap(),
+        ["--lib", "net::http"]
+    );
+    assert_eq!(args(root, "src/net/mod.rs").unwrap(), ["--lib", "net"]);
+    assert_eq!(args(root, "src/bin/tool.rs").unwrap(), ["--bin", "tool"]);
+    assert_eq!(
+        args(root, "src/bin/tool/main.rs").unwrap(),
+        ["--bin", "tool"]
+    );
+
+    let cli = expand_target(root, "crates/cli/src/main.rs").unwrap();
+    assert_eq!(cli.crate_dir, root.join("crates/cli"));
+    assert_eq!(cli.args, ["--bin", "cli-tool"]);
+    assert_eq!(
+        args(root, "crates/cli/src/commands.rs").unwrap(),
+        ["--bin", "cli-tool", "commands"]
+    );
+    assert_eq!(cli.command(), "cargo expand --color never --bin cli-tool");
+}
+
+#[test]
+fn files_outside_src_or_with_odd_names_have_no_target() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    write_file(root, "Cargo.toml", "[package]\nname = \"app\"\n").unwrap();
+
+    assert_eq!(args(root, "tests/it.rs"), None);
+    assert_eq!(args(root, "build.rs"), None);
+    assert_eq!(args(root, "src/bin/tool/helpers.rs"), None);
+    assert_eq!(args(root, "src/my-mod.rs"), None);
+    assert_eq!(args(root, "src/notes.md"), None);
+    assert_eq!(
+        args(tempfile::tempdir().unwrap().path(), "src/lib.rs"),
+        None
+    );
+}
+
+#[test]
+fn cache_reuses_expansions_until_the_crate_changes() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    write_file(root, "Cargo.toml", "[package]\nname = \"app\"\n").unwrap();
+    write_file(root, "src/lib.rs", "").unwrap();
+    let target = ExpandTarget {
+        crate_dir: root.to_path_buf(),
+        args: vec!["--lib".into()],
+    };
+
+    let runs = Cell::new(0);
+    let run = |_: &ExpandTarget| {
+        runs.set(runs.get() + 1);
+        Ok(format!("expanded #{}", runs.get()))
+    };
+    let mut cache = ExpandCache::default();
+    assert_eq!(cache.expand_with(&target, run).unwrap(), "expanded #1");
+    assert_eq!(cache.expand_with(&target, run).unwrap(), "expanded #1");
+    assert_eq!(runs.get(), 1);
+
+    write_file(root, "src/extra.rs", "").unwrap();
+    assert_eq!(cache.expand_with(&target, run).unwrap(), "expanded #2");
+
+    let failing = ExpandTarget {
+        args: vec!["--bin".into(), "app".into()],
+        ..target
+    };
+    assert!(
+        cache
+            .expand_with(&failing, |_| Err("exited with code 101".into()))
+            .is_err()
+    );
+    assert_eq!(
+        cache.expand_with(&failing, |_| Ok("not run".into())),
+        Err("exited with code 101".to_string())
+    );
+}
+
+#[test]
+fn expansion_step_needs_commands_to_be_allowed() {
+    let tmp = tempfile::tempdir().unwrap();
+    write_file(tmp.path(), "Cargo.toml", "[package]\nname = \"app\"\n").unwrap();
+    write_file(tmp.path(), "src/lib.rs", "fn f() {}\n").unwrap();
+    let p = TransformPipeline {
+        steps: vec![TransformStep::for_extensions(
+            TransformKind::MacroExpand { timeout_secs: None },
+            &["rs"],
+        )],
+    };
+    assert!(p.has_commands());
+
+    let opts = TransformOptions {
+        working_dir: tmp.path().to_path_buf(),
+        ..TransformOptions::default()
+    };
+    let out = p.apply("src/lib.rs", "fn f() {}\n".into(), &opts);
+    assert_eq!(out.contents, "fn f() {}\n");
+    assert!(out.command_failures.is_empty());
+}
+
+#[test]
+fn files_without_a_target_keep_their_source_with_a_note() {
+    let tmp = tempfile::tempdir().unwrap();
+    let p = TransformPipeline {
+        steps: vec![TransformStep::new(TransformKind::MacroExpand {
+            timeout_secs: None,
+        })],
+    };
+    let opts = TransformOptions {
+        allow_commands: true,
+        working_dir: tmp.path().to_path_buf(),
+        ..TransformOptions::default()
+    };
+    let out = p.apply("tests/it.rs", "fn t() {}\n".into(), &opts);
+    assert_eq!(out.contents, "fn t() {}\n");
+    assert_eq!(out.command_failures.len(), 1);
+    assert!(out.command_failures[0].starts_with("`cargo expand` "));
+}
+
+#[test]
+fn step_round_trips_through_json() {
+    let json = r#"[{"kind":"macro_expand","timeout_secs":300,"extensions":["rs"]}]"#;
+    let p: TransformPipeline = serde_json::from_str(json).unwrap();
+    assert_eq!(
+        p.steps,
+        vec![TransformStep::for_extensions(
+            TransformKind::MacroExpand {
+                timeout_secs: Some(300)
+            },
+            &["rs"],
+        )]
+    );
+    assert_eq!(serde_json::to_string(&p).unwrap(), json);
+}
@@ -0,0 +1,151 @@
+use std::fs;
+
+use stitch::core::{
+    render_dependencies_section, summarize_go_mod, summarize_package_json, summarize_pyproject,
+};
+use tempfile::TempDir;
+
+fn groups(summary: &stitch::core::ManifestSummary) -> Vec<(&str, Vec<&str>)> {
+    summary
+        .groups
+        .iter()
+        .map(|(g, lines)| (g.as_str(), lines.iter().map(String::as_str).collect()))
+        .collect()
+}
+
+#[test]
+fn package_json_lists_scripts_and_dependency_maps() {
+    let source = r#"{
+        "name": "web",
+        "version": "1.2.0",
+        "scripts": { "build": "vite build", "test": "vitest" },
+        "dependencies": { "react": "^18.2.0" },
+        "devDependencies": { "vite": "^5.0.0" },
+        "files": ["dist"]
+    }"#;
+    let summary = summarize_package_json("package.json", source).unwrap();
+    assert_eq!(summary.name.as_deref(), Some("web"));
+    assert_eq!(summary.version.as_deref(), Some("1.2.0"));
+    assert_eq!(
+        groups(&summary),
+        vec![
+            ("scripts", vec!["build: vite build", "test: vitest"]),
+            ("dependencies", vec!["react ^18.2.0"]),
+            ("devDependencies", vec!["vite ^5.0.0"]),
+        ]
+    );
+    assert!(summarize_package_json("package.json", "{ nope").is_err());
+}
+
+#[test]
+fn pyproject_covers_pep621_and_poetry() {
+    let pep621 = r#"
+[project]
+name = "tool"
+version = "0.1.0"
+requires-python = ">=3.10"
+dependencies = ["requests>=2.31", "rich"]
+
+[project.optional-dependencies]
+dev = ["pytest"]
+
+[project.scripts]
+tool = "tool.cli:main"
+
+[dependency-groups]
+lint = ["ruff"]
+"#;
+    let summary = summarize_pyproject("pyproject.toml", pep621).unwrap();
+    assert_eq!(summary.name.as_deref(), Some("tool"));
+    assert_eq!(
+        groups(&summary),
+        vec![
+            ("requires-python", vec![">=3.10"]),
+            ("dependencies", vec!["requests>=2.31", "rich"]),
+            ("optional-dependencies.dev", vec!["pytest"]),
+            ("scripts", vec!["tool: tool.cli:main"]),
+            ("dependency-groups.lint", vec!["ruff"]),
+        ]
+    );
+
+    let poetry = r#"
+[tool.poetry]
+name = "app"
+version = "2.0.0"
+
+[tool.poetry.dependencies]
+python = "^3.11"
+black = { version = "24.1", extras = ["d"], optional = true }
+mylib = { path = "../mylib" }
+
+[tool.poetry.group.test.dependencies]
+pytest = "^8"
+"#;
+    let summary = summarize_pyproject("pyproject.toml", poetry).unwrap();
+    assert_eq!(summary.version.as_deref(), Some("2.0.0"));
+    assert_eq!(
+        groups(&summary),
+        vec![
+            (
+                "tool.poetry.dependencies",
+                vec![
+                    "black 24.1 [d] (optional)",
+                    "mylib (path ../mylib)",
+                    "python ^3.11"
+                ]
+            ),
+            ("tool.poetry.group.test.dependencies", vec!["pytest ^8"]),
+        ]
+    );
+}
+
+#[test]
+fn go_mod_lists_requirements_and_replacements() {
+    let source = "module example.com/app\n\ngo 1.22\n\nrequire (\n\tgithub.com/pkg/errors v0.9.1\n\tgolang.org/x/sys v0.20.0 // indirect\n)\n\nrequire github.com/stretchr/testify v1.9.0\n\nreplace example.com/lib => ../lib\n";
+    let summary = summarize_go_mod("go.mod", source);
+    assert_eq!(summary.name.as_deref(), Some("example.com/app"));
+    assert_eq!(summary.version.as_deref(), Some("go 1.22"));
+    assert_eq!(
+        groups(&summary),
+        vec![
+            (
+                "require",
+                vec![
+                    "github.com/pkg/errors v0.9.1",
+                    "golang.org/x/sys v0.20.0 (indirect)",
+                    "github.com/stretchr/testify v1.9.0",
+                ]
+            ),
+            ("replace", vec!["example.com/lib => ../lib"]),
+        ]
+    );
+}
+
+#[test]
+fn section_combines_all_root_manifests() {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path();
+    fs::write(
+        root.join("Cargo.toml"),
+        "[package]\nname = \"core\"\nversion = \"0.1.0\"\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("package.json"),
+        r#"{"name":"ui","scripts":{"dev":"vite"}}"#,
+    )
+    .unwrap();
+    fs::write(root.join("pyproject.toml"), "[project\n").unwrap();
+    fs::write(root.join("go.mod"), "module example.com/x\n").unwrap();
+
+    let section = render_dependencies_section(root).unwrap();
+    let headers: Vec<&str> = section
+        .lines()
+        .filter(|l| !l.is_empty() && !l.starts_with(' '))
+        .collect();
+    assert_eq!(headers[0], "core 0.1.0 (Cargo.toml)");
+    assert_eq!(headers[1], "ui (package.json)");
+    assert!(headers[2].starts_with("pyproject.toml: could not parse ("));
+    assert_eq!(headers[3], "example.com/x (go.mod)");
+    assert!(section.contains("ui (package.json)\n  scripts:\n    dev: vite\n"));
+}
@@ -0,0 +1,96 @@
+#![cfg(unix)]
+
+use std::fs;
+
+use pretty_assertions::assert_eq;
+use stitch::core::{
+    FileChange, LocalSettings, plan_response_changes, review_with_merge_tool, save_local_settings,
+};
+use tempfile::TempDir;
+
+fn project() -> TempDir {
+    let tmp = TempDir::new().unwrap();
+    fs::create_dir_all(tmp.path().join("src")).unwrap();
+    fs::write(tmp.path().join("src/lib.rs"), "fn a() {}\nfn b() {}\n").unwrap();
+    tmp
+}
+
+fn change(path: &str, contents: &str) -> FileChange {
+    FileChange {
+        path: path.into(),
+        contents: contents.into(),
+    }
+}
+
+#[test]
+fn merge_tool_results_are_read_back() {
+    let tmp = project();
+    let root = tmp.path();
+    // Keeps the current first line and the proposed rest, like a hand-merged result.
+    let tool = r#"test "$STITCH_PATH" = src/lib.rs || exit 3
+        { head -n 1 "$STITCH_CURRENT"; tail -n +2 "$STITCH_PROPOSED"; } > "$STITCH_PROPOSED.merged"
+        mv "$STITCH_PROPOSED.merged" "$STITCH_PROPOSED""#;
+    let reviewed = review_with_merge_tool(
+        root,
+        tool,
+        &[change("src/lib.rs", "fn x() {}\nfn y() {}\n")],
+    )
+    .unwrap();
+    assert_eq!(reviewed, [change("src/lib.rs", "fn a() {}\nfn y() {}\n")]);
+    // The tool only saw copies.
+    assert_eq!(
+        fs::read_to_string(root.join("src/lib.rs")).unwrap(),
+        "fn a() {}\nfn b() {}\n"
+    );
+}
+
+#[test]
+fn new_files_start_from_an_empty_current_side() {
+    let tmp = project();
+    let reviewed = review_with_merge_tool(
+        tmp.path(),
+        r#"test ! -s "$STITCH_CURRENT" && echo ok >> "$STITCH_PROPOSED""#,
+        &[change("src/new.rs", "fn n() {}\n")],
+    )
+    .unwrap();
+    assert_eq!(reviewed, [change("src/new.rs", "fn n() {}\nok\n")]);
+    assert!(!tmp.path().join("src/new.rs").exists());
+}
+
+#[test]
+fn a_failing_merge_tool_stops_the_review() {
+    let tmp = project();
+    let root = tmp.path();
+    assert_eq!(
+        review_with_merge_tool(root, "exit 1", &[change("src/lib.rs", "x\n")]).unwrap_err(),
+        "merge tool exited with code 1 on src/lib.rs"
+    );
+    assert_eq!(
+        review_with_merge_tool(root, "true", &[change("../x.rs", "x\n")]).unwrap_err(),
+        "../x.rs is outside the project"
+    );
+    assert_eq!(
+        review_with_merge_tool(root, "true", &[change(".git/config", "x\n")]).unwrap_err(),
+        ".git/config is inside .git"
+    );
+}
+
+#[test]
+fn applying_a_response_goes_through_the_configured_merge_tool() {
+    let tmp = project();
+    let root = tmp.path();
+    let response = "--- Start of file: src/lib.rs ---\nfn a() {}\nfn c() {}\n--- End of file: src/lib.rs ---\n";
+    let plan = plan_response_changes(root, response).unwrap();
+    assert_eq!(
+        plan.render_summary(),
+        "M src/lib.rs  +1 -1\n1 file changed, +1 -1\n"
+    );
+
+    let ls = LocalSettings {
+        merge_tool: Some(r#"cp "$STITCH_CURRENT" "$STITCH_PROPOSED""#.into()),
+        ..LocalSettings::default()
+    };
+    save_local_settings(root, &ls).unwrap();
+    // The user kept the current contents in the tool, so nothing is left to write.
+    assert!(plan_response_changes(root, response).unwrap().is_empty());
+}
@@ -0,0 +1,39 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use stitch::core::{rebase_path, rebase_path_keys};
+
+#[test]
+fn paths_under_old_root_move_to_new_root() {
+    let old = Path::new("/home/me/old/proj");
+    let new = Path::new("/srv/proj");
+    assert_eq!(
+        rebase_path(Path::new("/home/me/old/proj/src/main.rs"), old, new),
+        PathBuf::from("/srv/proj/src/main.rs")
+    );
+    assert_eq!(rebase_path(old, old, new), PathBuf::from("/srv/proj"));
+}
+
+#[test]
+fn paths_outside_old_root_are_untouched() {
+    let old = Path::new("/a/proj");
+    let new = Path::new("/b/proj");
+    assert_eq!(
+        rebase_path(Path::new("/a/project-two/x.rs"), old, new),
+        PathBuf::from("/a/project-two/x.rs")
+    );
+}
+
+#[test]
+fn cache_keys_are_rebased_and_values_kept() {
+    let old = Path::new("/a/proj");
+    let new = Path::new("/b/renamed");
+    let mut explicit = HashMap::new();
+    explicit.insert(PathBuf::from("/a/proj/src"), true);
+    explicit.insert(PathBuf::from("/a/proj/README.md"), false);
+
+    let moved = rebase_path_keys(explicit, old, new);
+    assert_eq!(moved.len(), 2);
+    assert_eq!(moved.get(Path::new("/b/renamed/src")), Some(&true));
+    assert_eq!(moved.get(Path::new("/b/renamed/README.md")), Some(&false));
+}
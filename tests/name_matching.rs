@@ -0,0 +1,105 @@
+use std::{collections::HashSet, fs};
+
+use stitch::core::{
+    NameMatching, NameSet, fold_name, is_event_path_relevant_with_name_matching,
+    scan_dir_to_node_with_name_matching,
+};
+use tempfile::TempDir;
+
+fn set(items: &[&str]) -> HashSet<String> {
+    items.iter().map(|s| (*s).to_string()).collect()
+}
+
+#[test]
+fn folding_ignores_case_accents_and_normalization_form() {
+    assert_eq!(fold_name("LICENSE"), "license");
+    assert_eq!(fold_name("Résumé.md"), "resume.md");
+    // Decomposed (NFD) spelling, as macOS file systems report it.
+    assert_eq!(fold_name("Re\u{301}sume\u{301}.md"), "resume.md");
+    assert_eq!(fold_name("Ångström"), "angstrom");
+    assert_eq!(fold_name("日本語"), "日本語");
+}
+
+#[test]
+fn name_sets_follow_the_matching_mode() {
+    let names = set(&["LICENSE", "Résumé.md"]);
+    let exact = NameSet::new(&names, NameMatching::Exact);
+    assert!(exact.contains("LICENSE"));
+    assert!(!exact.contains("License"));
+
+    let folded = NameSet::new(&names, NameMatching::Insensitive);
+    assert!(folded.contains("License"));
+    assert!(folded.contains("resume.md"));
+    assert!(!folded.contains("resume.txt"));
+
+    let platform = NameSet::new(&names, NameMatching::Platform);
+    assert_eq!(
+        platform.contains("license"),
+        cfg!(any(windows, target_os = "macos"))
+    );
+}
+
+#[test]
+fn ui_index_round_trips() {
+    for m in [
+        NameMatching::Platform,
+        NameMatching::Exact,
+        NameMatching::Insensitive,
+    ] {
+        assert_eq!(NameMatching::from_index(m.index()), m);
+    }
+    assert_eq!(NameMatching::from_index(-1), NameMatching::Platform);
+}
+
+#[test]
+fn insensitive_scan_excludes_other_spellings() {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path();
+    fs::write(root.join("License"), "x").unwrap();
+    fs::write(root.join("main.rs"), "fn main() {}").unwrap();
+    fs::create_dir(root.join("Node_Modules")).unwrap();
+    fs::write(root.join("Node_Modules").join("a.js"), "x").unwrap();
+
+    let none = HashSet::new();
+    let dirs = set(&["node_modules"]);
+    let files = set(&["LICENSE"]);
+    let names = |m| {
+        let scan = scan_dir_to_node_with_name_matching(root, &none, &none, &dirs, &files, &[], m);
+        let mut names: Vec<String> = scan.node.children.iter().map(|n| n.name.clone()).collect();
+        names.sort();
+        (names, scan.stats)
+    };
+
+    let (exact, _) = names(NameMatching::Exact);
+    assert_eq!(exact, vec!["License", "Node_Modules", "main.rs"]);
+
+    let (folded, stats) = names(NameMatching::Insensitive);
+    assert_eq!(folded, vec!["main.rs"]);
+    // Stats keep the names as found on disk.
+    assert_eq!(stats.excluded_files_found.get("License"), Some(&1));
+    assert_eq!(stats.excluded_dirs_found.get("Node_Modules"), Some(&1));
+}
+
+#[test]
+fn insensitive_event_relevance_matches_the_scan() {
+    let root = std::path::Path::new("/project");
+    let none = HashSet::new();
+    let dirs = set(&["target"]);
+    let files = set(&["LICENSE"]);
+    let relevant = |path: &str, m| {
+        is_event_path_relevant_with_name_matching(
+            root,
+            &root.join(path),
+            &none,
+            &none,
+            &dirs,
+            &files,
+            m,
+        )
+    };
+    assert!(relevant("Target/debug/x", NameMatching::Exact));
+    assert!(!relevant("Target/debug/x", NameMatching::Insensitive));
+    assert!(relevant("license", NameMatching::Exact));
+    assert!(!relevant("license", NameMatching::Insensitive));
+    assert!(relevant("src/lib.rs", NameMatching::Insensitive));
+}
@@ -0,0 +1,72 @@
+use std::path::{Path, PathBuf};
+
+use stitch::core::{OpenTarget, Platform, parse_open_target, render_integration};
+
+#[test]
+fn plain_paths_pass_through() {
+    assert_eq!(
+        parse_open_target("relative/app").unwrap(),
+        OpenTarget {
+            path: PathBuf::from("relative/app"),
+            ..OpenTarget::default()
+        }
+    );
+}
+
+#[test]
+fn file_urls_are_decoded() {
+    let target = parse_open_target("file://localhost/home/me/caf%C3%A9").unwrap();
+    assert_eq!(target.path, PathBuf::from("/home/me/café"));
+    assert_eq!(
+        parse_open_target("file:///C:/work/app").unwrap().path,
+        PathBuf::from("C:/work/app")
+    );
+    assert_eq!(
+        parse_open_target("file://server/share").unwrap_err(),
+        "unsupported file URL 'file://server/share'"
+    );
+    assert_eq!(
+        parse_open_target("file:///bad%zz").unwrap_err(),
+        "invalid file URL 'file:///bad%zz'"
+    );
+}
+
+#[test]
+fn stitch_urls_carry_profile_and_generate() {
+    let target = parse_open_target("STITCH://open/?path=/w/a+b&generate=0&profile=").unwrap();
+    assert_eq!(target.path, PathBuf::from("/w/a b"));
+    assert_eq!(target.profile, None);
+    assert!(!target.generate);
+}
+
+#[test]
+fn windows_registration_adds_folder_menus_and_url_protocol() {
+    let reg = render_integration(Platform::Windows, Path::new(r"C:\Tools\stitch.exe"));
+    assert!(reg.starts_with("Windows Registry Editor Version 5.00\n"));
+    assert!(reg.contains("[HKEY_CURRENT_USER\\Software\\Classes\\Directory\\shell\\Stitch]\n"));
+    assert!(reg.contains("@=\"\\\"C:\\\\Tools\\\\stitch.exe\\\" --open \\\"%V\\\"\"\n"));
+    assert!(reg.contains("[HKEY_CURRENT_USER\\Software\\Classes\\stitch]\n"));
+    assert!(reg.contains("\"URL Protocol\"=\"\"\n"));
+}
+
+#[test]
+fn linux_desktop_entry_quotes_the_executable() {
+    let entry = render_integration(Platform::Linux, Path::new("/opt/my apps/stitch"));
+    assert!(entry.contains("Exec=\"/opt/my apps/stitch\" --open %u\n"));
+    assert!(entry.contains("MimeType=inode/directory;x-scheme-handler/stitch;\n"));
+
+    let entry = render_integration(Platform::Linux, Path::new("/usr/bin/stitch"));
+    assert!(entry.contains("Exec=/usr/bin/stitch --open %u\n"));
+}
+
+#[test]
+fn macos_quick_action_opens_each_folder() {
+    let script = render_integration(
+        Platform::Macos,
+        Path::new("/Applications/Stitch.app/Contents/MacOS/stitch"),
+    );
+    assert!(script.starts_with("#!/bin/sh\n"));
+    assert!(script.contains(
+        "'/Applications/Stitch.app/Contents/MacOS/stitch' --open \"$f\" >/dev/null 2>&1 &\n"
+    ));
+}
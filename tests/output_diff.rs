@@ -0,0 +1,125 @@
+use std::fmt::Write;
+
+use pretty_assertions::assert_eq;
+use proptest::prelude::*;
+
+use stitch::core::{DiffLine, diff_lines, render_diff};
+
+fn numbered(prefix: &str, range: std::ops::Range<usize>) -> String {
+    range.fold(String::new(), |mut out, i| {
+        let _ = writeln!(out, "{prefix} {i}");
+        out
+    })
+}
+
+fn sides(lines: &[DiffLine<'_>]) -> (Vec<String>, Vec<String>) {
+    let mut old = Vec::new();
+    let mut new = Vec::new();
+    for line in lines {
+        match line {
+            DiffLine::Same(t) => {
+                old.push((*t).to_string());
+                new.push((*t).to_string());
+            }
+            DiffLine::Removed(t) => old.push((*t).to_string()),
+            DiffLine::Added(t) => new.push((*t).to_string()),
+        }
+    }
+    (old, new)
+}
+
+#[test]
+fn identical_texts_have_no_hunks() {
+    let text = "=== FILE HIERARCHY ===\nsrc/\n  main.rs\n";
+    assert!(
+        diff_lines(text, text)
+            .iter()
+            .all(|l| matches!(l, DiffLine::Same(_)))
+    );
+    assert_eq!(render_diff(text, text, 3), "");
+}
+
+#[test]
+fn changed_line_is_removed_then_added() {
+    let old = "a\nb\nc\n";
+    let new = "a\nB\nc\nd\n";
+    assert_eq!(
+        diff_lines(old, new),
+        vec![
+            DiffLine::Same("a"),
+            DiffLine::Removed("b"),
+            DiffLine::Added("B"),
+            DiffLine::Same("c"),
+            DiffLine::Added("d"),
+        ]
+    );
+}
+
+#[test]
+fn hunks_keep_context_and_merge_when_close() {
+    let old = numbered("line", 1..21);
+    let new = old
+        .replace("line 3\n", "line three\n")
+        .replace("line 5\n", "")
+        .replace("line 18\n", "line 18\nextra\n");
+
+    assert_eq!(
+        render_diff(&old, &new, 1),
+        "@@ -2,5 +2,4 @@\n line 2\n-line 3\n+line three\n line 4\n-line 5\n line 6\n\
+         @@ -18,2 +17,3 @@\n line 18\n+extra\n line 19\n"
+    );
+}
+
+#[test]
+fn diff_of_empty_text() {
+    assert_eq!(render_diff("", "x\ny\n", 3), "@@ -1,0 +1,2 @@\n+x\n+y\n");
+    assert_eq!(render_diff("x\n", "", 3), "@@ -1,1 +1,0 @@\n-x\n");
+}
+
+proptest! {
+    #[test]
+    fn diff_reproduces_both_texts(
+        old in prop::collection::vec("[abc]", 0..40),
+        new in prop::collection::vec("[abc]", 0..40),
+    ) {
+        let old_text = old.join("\n");
+        let new_text = new.join("\n");
+        let lines = diff_lines(&old_text, &new_text);
+        let (got_old, got_new) = sides(&lines);
+        prop_assert_eq!(got_old, old_text.lines().map(str::to_string).collect::<Vec<_>>());
+        prop_assert_eq!(got_new, new_text.lines().map(str::to_string).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn diff_is_minimal_for_single_insertions(
+        lines in prop::collection::vec("[a-z]{1,3}", 1..30),
+        at in 0usize..30,
+    ) {
+        let at = at % (lines.len() + 1);
+        let mut changed = lines.clone();
+        changed.insert(at, "INSERTED".to_string());
+        let edits = diff_lines(&lines.join("\n"), &changed.join("\n"))
+            .into_iter()
+            .filter(|l| !matches!(l, DiffLine::Same(_)))
+            .count();
+        prop_assert_eq!(edits, 1);
+    }
+}
+
+#[test]
+fn large_rewrites_fall_back_to_removed_then_added() {
+    let old = numbered("old", 0..1500);
+    let new = numbered("new", 0..1500);
+    let lines = diff_lines(&old, &new);
+    assert_eq!(lines.len(), 3000);
+    assert!(
+        lines[..1500]
+            .iter()
+            .all(|l| matches!(l, DiffLine::Removed(_)))
+    );
+    assert!(
+        lines[1500..]
+            .iter()
+            .all(|l| matches!(l, DiffLine::Added(_)))
+    );
+}
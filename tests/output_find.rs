@@ -0,0 +1,53 @@
+use pretty_assertions::assert_eq;
+
+use stitch::core::{ContentQuery, excerpt_around};
+
+#[test]
+fn find_ranges_are_byte_ranges_of_every_match() {
+    let text = "Über fn\nfn ✓ FN\n";
+    let query = ContentQuery::new("fn", false, false).unwrap();
+    let ranges = query.find_ranges(text);
+    assert_eq!(ranges.len(), 3);
+    for r in &ranges {
+        assert!(text[r.clone()].eq_ignore_ascii_case("fn"));
+    }
+
+    let exact = ContentQuery::new("FN", false, true).unwrap();
+    assert_eq!(
+        exact.find_ranges(text),
+        vec![text.rfind("FN").unwrap()..text.len() - 1]
+    );
+
+    // Empty matches (possible with regexes) are never reported.
+    let empty = ContentQuery::new("x*", true, false).unwrap();
+    assert!(empty.find_ranges("abc").is_empty());
+}
+
+#[test]
+fn excerpt_covers_the_match_on_whole_lines() {
+    let text: String = (0..100).map(|i| format!("line {i:03}\n")).collect();
+    let at = text.find("line 050").unwrap();
+    let range = at..at + 8;
+
+    let window = excerpt_around(&text, range.clone(), 100);
+    assert!(window.start <= range.start && range.end <= window.end);
+    assert!(window.end - window.start <= 100);
+    let excerpt = &text[window];
+    assert!(excerpt.starts_with("line "));
+    assert!(excerpt.ends_with('\n'));
+    assert!(excerpt.contains("line 049\nline 050\n"));
+}
+
+#[test]
+fn excerpt_at_the_edges_and_longer_than_the_limit() {
+    let text = "alpha\nbeta\ngamma\n";
+    assert_eq!(excerpt_around(text, 0..5, 1000), 0..text.len());
+
+    let end = text.len();
+    let window = excerpt_around(text, end - 6..end - 1, 8);
+    assert_eq!(&text[window], "gamma\n");
+
+    // A match longer than the limit is kept whole.
+    let window = excerpt_around(text, 0..16, 4);
+    assert_eq!(window, 0..16);
+}
@@ -0,0 +1,53 @@
+use stitch::core::{
+    DEFAULT_OUTPUT_FONT_FAMILY, DEFAULT_OUTPUT_FONT_SIZE, OUTPUT_FONT_SIZE_RANGE, UserPreferences,
+    load_user_preferences, save_user_preferences,
+};
+use tempfile::TempDir;
+
+#[test]
+fn unset_font_falls_back_to_defaults() {
+    let prefs = UserPreferences::default();
+    assert_eq!(prefs.output_font_family(), DEFAULT_OUTPUT_FONT_FAMILY);
+    assert_eq!(prefs.output_font_size(), DEFAULT_OUTPUT_FONT_SIZE);
+
+    let blank = UserPreferences {
+        output_font_family: Some("   ".into()),
+        ..Default::default()
+    };
+    assert_eq!(blank.output_font_family(), DEFAULT_OUTPUT_FONT_FAMILY);
+}
+
+#[test]
+fn font_size_is_clamped() {
+    let tiny = UserPreferences {
+        output_font_size: Some(0),
+        ..Default::default()
+    };
+    let huge = UserPreferences {
+        output_font_size: Some(500),
+        ..Default::default()
+    };
+    assert_eq!(tiny.output_font_size(), *OUTPUT_FONT_SIZE_RANGE.start());
+    assert_eq!(huge.output_font_size(), *OUTPUT_FONT_SIZE_RANGE.end());
+}
+
+#[test]
+fn font_is_persisted_in_the_user_config_dir() {
+    let tmp = TempDir::new().unwrap();
+    let config_dir = tmp.path().join("stitch");
+    assert_eq!(
+        load_user_preferences(&config_dir),
+        UserPreferences::default()
+    );
+
+    let prefs = UserPreferences {
+        output_font_family: Some(" Fira Code ".into()),
+        output_font_size: Some(16),
+        ..UserPreferences::default()
+    };
+    save_user_preferences(&config_dir, &prefs).unwrap();
+
+    let loaded = load_user_preferences(&config_dir);
+    assert_eq!(loaded.output_font_family(), "Fira Code");
+    assert_eq!(loaded.output_font_size(), 16);
+}
@@ -0,0 +1,134 @@
+use std::fs;
+
+use stitch::core::{
+    DEFAULT_HISTORY_LIMIT, HistoryEntry, LocalSettings, delete_history_entry, history_dir,
+    list_history, load_history_output, record_history,
+};
+use tempfile::TempDir;
+
+fn entry(created: &str, profile: Option<&str>, tokens: usize) -> HistoryEntry {
+    HistoryEntry {
+        created: created.to_string(),
+        profile: profile.map(str::to_string),
+        tokens,
+        tokens_estimated: false,
+        chars: tokens * 4,
+        ..HistoryEntry::default()
+    }
+}
+
+#[test]
+fn empty_project_has_no_history() {
+    let tmp = TempDir::new().unwrap();
+    assert!(list_history(tmp.path()).is_empty());
+    assert!(load_history_output(tmp.path(), "000000000000001").is_none());
+}
+
+#[test]
+fn entries_are_listed_newest_first_with_their_text() {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path();
+    let first = record_history(root, &entry("10:00", None, 10), "first", 5)
+        .unwrap()
+        .unwrap();
+    let second = record_history(root, &entry("11:00", Some("backend"), 20), "second", 5)
+        .unwrap()
+        .unwrap();
+    assert!(second.id > first.id);
+
+    let listed = list_history(root);
+    assert_eq!(listed.len(), 2);
+    assert_eq!(listed[0], second);
+    assert_eq!(listed[1], first);
+    assert_eq!(
+        load_history_output(root, &listed[0].id).as_deref(),
+        Some("second")
+    );
+    assert_eq!(
+        load_history_output(root, &listed[1].id).as_deref(),
+        Some("first")
+    );
+    assert!(history_dir(root).starts_with(root.join(".stitchworkspace/local")));
+}
+
+#[test]
+fn repeating_the_newest_output_records_nothing() {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path();
+    record_history(root, &entry("10:00", None, 10), "same", 5).unwrap();
+    assert!(
+        record_history(root, &entry("10:05", None, 10), "same", 5)
+            .unwrap()
+            .is_none()
+    );
+    record_history(root, &entry("10:10", None, 10), "other", 5).unwrap();
+    record_history(root, &entry("10:15", None, 10), "same", 5).unwrap();
+    assert_eq!(list_history(root).len(), 3);
+}
+
+#[test]
+fn oldest_entries_are_dropped_beyond_the_limit() {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path();
+    for i in 0..5 {
+        record_history(
+            root,
+            &entry(&format!("{i}"), None, i),
+            &format!("out {i}"),
+            3,
+        )
+        .unwrap();
+    }
+    let created: Vec<String> = list_history(root).into_iter().map(|e| e.created).collect();
+    assert_eq!(created, vec!["4", "3", "2"]);
+    // Both files of dropped entries are gone.
+    assert_eq!(fs::read_dir(history_dir(root)).unwrap().count(), 6);
+}
+
+#[test]
+fn zero_limit_disables_the_history() {
+    let tmp = TempDir::new().unwrap();
+    assert!(
+        record_history(tmp.path(), &entry("10:00", None, 1), "x", 0)
+            .unwrap()
+            .is_none()
+    );
+    assert!(!history_dir(tmp.path()).exists());
+}
+
+#[test]
+fn entries_without_text_are_skipped_and_delete_removes_both_files() {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path();
+    let kept = record_history(root, &entry("10:00", None, 1), "kept", 5)
+        .unwrap()
+        .unwrap();
+    let broken = record_history(root, &entry("10:01", None, 1), "broken", 5)
+        .unwrap()
+        .unwrap();
+    fs::remove_file(history_dir(root).join(format!("{}.txt", broken.id))).unwrap();
+    assert_eq!(list_history(root), vec![kept.clone()]);
+
+    delete_history_entry(root, &kept.id).unwrap();
+    delete_history_entry(root, &kept.id).unwrap();
+    assert!(list_history(root).is_empty());
+}
+
+#[test]
+fn labels_and_limit_setting() {
+    let mut estimated = entry("2026-10-15 14:03:22", Some("backend"), 12034);
+    assert_eq!(
+        estimated.label(),
+        "2026-10-15 14:03:22 • backend • 12034 tokens"
+    );
+    estimated.tokens_estimated = true;
+    estimated.profile = None;
+    assert_eq!(estimated.label(), "2026-10-15 14:03:22 • ~12034 tokens");
+
+    assert_eq!(
+        LocalSettings::default().history_limit(),
+        DEFAULT_HISTORY_LIMIT
+    );
+    let settings: LocalSettings = serde_json::from_str(r#"{ "history_limit": 0 }"#).unwrap();
+    assert_eq!(settings.history_limit(), 0);
+}
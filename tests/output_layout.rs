@@ -0,0 +1,128 @@
+use stitch::core::{
+    OutputLayout, OutputParts, OutputSection, SectionLayout, WorkspaceSettings, load_workspace,
+    save_workspace,
+};
+use tempfile::TempDir;
+
+fn parts() -> OutputParts {
+    OutputParts {
+        provenance: None,
+        hierarchy: Some("root/\n└── a.rs\n".into()),
+        unselected_directories: None,
+        dependencies: None,
+        notes: Some("Excluded files: x\n\n".into()),
+        contents: Some(
+            "--- Start of file: a.rs ---\nfn a() {}\n--- End of file: a.rs ---\n\n".into(),
+        ),
+    }
+}
+
+#[test]
+fn default_layout_matches_classic_format() {
+    let out = OutputLayout::default().render(&parts());
+    assert_eq!(
+        out,
+        "=== FILE HIERARCHY ===\n\nroot/\n└── a.rs\n\
+         \n=== NOTES ===\n\nExcluded files: x\n\n\
+         \n=== FILE CONTENTS ===\n\n--- Start of file: a.rs ---\nfn a() {}\n--- End of file: a.rs ---\n\n"
+    );
+}
+
+#[test]
+fn sections_can_be_reordered_renamed_and_disabled() {
+    let layout = OutputLayout {
+        sections: vec![
+            SectionLayout {
+                section: OutputSection::Contents,
+                enabled: true,
+                header: Some("## Files".into()),
+            },
+            SectionLayout {
+                section: OutputSection::Hierarchy,
+                enabled: true,
+                header: Some("## Tree".into()),
+            },
+            SectionLayout {
+                section: OutputSection::Notes,
+                enabled: false,
+                header: None,
+            },
+        ],
+        provenance: None,
+        modified_times: false,
+        checksum_footer: false,
+    };
+    let out = layout.render(&parts());
+    assert!(out.starts_with("## Files\n\n--- Start of file: a.rs ---"));
+    assert!(out.contains("\n## Tree\n\nroot/\n"));
+    assert!(!out.contains("NOTES"));
+    assert!(!layout.is_enabled(OutputSection::Notes));
+}
+
+#[test]
+fn unlisted_sections_follow_in_default_order_and_duplicates_are_ignored() {
+    let layout = OutputLayout {
+        sections: vec![
+            SectionLayout::new(OutputSection::Notes),
+            SectionLayout {
+                enabled: false,
+                ..SectionLayout::new(OutputSection::Notes)
+            },
+        ],
+        provenance: None,
+        modified_times: false,
+        checksum_footer: false,
+    };
+    let order: Vec<OutputSection> = layout.resolved().iter().map(|s| s.section).collect();
+    assert_eq!(
+        order,
+        vec![
+            OutputSection::Notes,
+            OutputSection::Hierarchy,
+            OutputSection::UnselectedDirectories,
+            OutputSection::Dependencies,
+            OutputSection::Contents,
+        ]
+    );
+    assert!(layout.is_enabled(OutputSection::Notes));
+}
+
+#[test]
+fn missing_parts_are_skipped() {
+    let out = OutputLayout::default().render(&OutputParts {
+        hierarchy: Some("root/\n".into()),
+        ..OutputParts::default()
+    });
+    assert_eq!(out, "=== FILE HIERARCHY ===\n\nroot/\n");
+}
+
+#[test]
+fn layout_persists_in_workspace_and_defaults_when_missing() {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path();
+
+    let mut ws = WorkspaceSettings {
+        version: 1,
+        ..Default::default()
+    };
+    ws.layout.sections[0].header = Some("# Project tree".into());
+    ws.layout.sections[2].enabled = false;
+    save_workspace(root, &ws).unwrap();
+    let loaded = load_workspace(root).unwrap();
+    assert_eq!(loaded.layout, ws.layout);
+
+    let json = r#"{"version":1,"ext_filter":"","exclude_dirs":"","exclude_files":"","remove_prefix":"","remove_regex":"","hierarchy_only":false,"dirs_only":false,
+        "layout":{"sections":[{"section":"contents"},{"section":"hierarchy","header":"TREE"}]}}"#;
+    std::fs::write(root.join(".stitchworkspace/workspace.json"), json).unwrap();
+    let loaded = load_workspace(root).unwrap();
+    assert_eq!(loaded.layout.sections.len(), 2);
+    assert!(loaded.layout.sections[0].enabled);
+    assert_eq!(loaded.layout.resolved()[1].header(), "TREE");
+
+    let legacy = r#"{"version":1,"ext_filter":"","exclude_dirs":"","exclude_files":"","remove_prefix":"","remove_regex":"","hierarchy_only":false,"dirs_only":false}"#;
+    std::fs::write(root.join(".stitchworkspace/workspace.json"), legacy).unwrap();
+    assert_eq!(
+        load_workspace(root).unwrap().layout,
+        OutputLayout::default()
+    );
+}
@@ -0,0 +1,135 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use stitch::core::{OutputOrder, WorkspaceSettings, order_output_files};
+use tempfile::TempDir;
+
+fn project() -> TempDir {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path();
+    fs::create_dir_all(root.join("src/core")).unwrap();
+    for (file, len) in [
+        ("src/main.rs", 30),
+        ("src/core/a.rs", 10),
+        ("src/z.rs", 5),
+        ("README.md", 20),
+        ("build.rs", 10),
+    ] {
+        fs::write(root.join(file), "x".repeat(len)).unwrap();
+    }
+    tmp
+}
+
+/// Selection order as produced by the tree (pinned README first, then traversal).
+fn selection(root: &Path) -> Vec<PathBuf> {
+    [
+        "README.md",
+        "build.rs",
+        "src/main.rs",
+        "src/z.rs",
+        "src/core/a.rs",
+    ]
+    .iter()
+    .map(|rel| root.join(rel))
+    .collect()
+}
+
+fn names(root: &Path, files: &[PathBuf]) -> Vec<String> {
+    files
+        .iter()
+        .map(|p| {
+            p.strip_prefix(root)
+                .unwrap()
+                .to_string_lossy()
+                .replace('\\', "/")
+        })
+        .collect()
+}
+
+#[test]
+fn pinned_first_keeps_the_selection_order() {
+    let tmp = project();
+    let root = tmp.path();
+    let files = order_output_files(root, selection(root), OutputOrder::PinnedFirst);
+    assert_eq!(files, selection(root));
+}
+
+#[test]
+fn alphabetical_sorts_by_relative_path() {
+    let tmp = project();
+    let root = tmp.path();
+    let files = order_output_files(root, selection(root), OutputOrder::Alphabetical);
+    assert_eq!(
+        names(root, &files),
+        [
+            "README.md",
+            "build.rs",
+            "src/core/a.rs",
+            "src/main.rs",
+            "src/z.rs"
+        ]
+    );
+}
+
+#[test]
+fn directory_grouped_keeps_each_directory_together() {
+    let tmp = project();
+    let root = tmp.path();
+    let files = order_output_files(root, selection(root), OutputOrder::DirectoryGrouped);
+    assert_eq!(
+        names(root, &files),
+        [
+            "README.md",
+            "build.rs",
+            "src/main.rs",
+            "src/z.rs",
+            "src/core/a.rs"
+        ]
+    );
+}
+
+#[test]
+fn smallest_first_sorts_by_size_then_path() {
+    let tmp = project();
+    let root = tmp.path();
+    let mut selected = selection(root);
+    selected.push(root.join("missing.rs"));
+    let files = order_output_files(root, selected, OutputOrder::SmallestFirst);
+    assert_eq!(
+        names(root, &files),
+        [
+            "src/z.rs",
+            "build.rs",
+            "src/core/a.rs",
+            "README.md",
+            "src/main.rs",
+            "missing.rs"
+        ]
+    );
+}
+
+#[test]
+fn order_roundtrips_indices_and_settings() {
+    for order in [
+        OutputOrder::PinnedFirst,
+        OutputOrder::Alphabetical,
+        OutputOrder::DirectoryGrouped,
+        OutputOrder::SmallestFirst,
+    ] {
+        assert_eq!(OutputOrder::from_index(order.index()), order);
+    }
+
+    let ws = WorkspaceSettings {
+        output_order: OutputOrder::SmallestFirst,
+        ..WorkspaceSettings::default()
+    };
+    let json = serde_json::to_string(&ws).unwrap();
+    assert!(
+        json.contains(r#""output_order":"smallest_first""#),
+        "{json}"
+    );
+    let mut value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    value.as_object_mut().unwrap().remove("output_order");
+    let old: WorkspaceSettings = serde_json::from_value(value).unwrap();
+    assert_eq!(old.output_order, OutputOrder::PinnedFirst);
+}
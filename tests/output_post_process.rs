@@ -0,0 +1,59 @@
+#![cfg(unix)]
+
+use pretty_assertions::assert_eq;
+
+use stitch::core::{PostProcess, WorkspaceSettings, load_workspace, save_workspace};
+
+fn post(command: &str) -> PostProcess {
+    PostProcess {
+        command: command.into(),
+        timeout_secs: None,
+    }
+}
+
+#[test]
+fn output_is_piped_through_the_command_in_the_project_root() {
+    let tmp = tempfile::tempdir().unwrap();
+    std::fs::write(tmp.path().join("wrapper.txt"), "<context>\n").unwrap();
+    let out = post("cat wrapper.txt - && printf '</context>\\n'")
+        .run("=== FILE CONTENTS ===\n", tmp.path())
+        .unwrap();
+    assert_eq!(out, "<context>\n=== FILE CONTENTS ===\n</context>\n");
+}
+
+#[test]
+fn failures_are_reported_in_one_line() {
+    let tmp = tempfile::tempdir().unwrap();
+    assert_eq!(
+        post("echo broken >&2; exit 3").run("x", tmp.path()),
+        Err("Post-processing command `echo broken >&2; exit 3` exited with code 3: broken".into())
+    );
+    assert_eq!(
+        post("  ").run("x", tmp.path()),
+        Err("Post-processing command is empty".into())
+    );
+    let slow = PostProcess {
+        command: "sleep 5".into(),
+        timeout_secs: Some(0),
+    };
+    assert!(slow.run("x", tmp.path()).unwrap_err().contains("timed out"));
+}
+
+#[test]
+fn post_process_persists_in_workspace_and_is_off_by_default() {
+    let tmp = tempfile::tempdir().unwrap();
+    let ws = WorkspaceSettings {
+        version: 1,
+        post_process: Some(PostProcess {
+            command: "./scripts/sanitize".into(),
+            timeout_secs: Some(5),
+        }),
+        ..Default::default()
+    };
+    save_workspace(tmp.path(), &ws).unwrap();
+    assert_eq!(
+        load_workspace(tmp.path()).unwrap().post_process,
+        ws.post_process
+    );
+    assert_eq!(WorkspaceSettings::default().post_process, None);
+}
@@ -0,0 +1,59 @@
+use pretty_assertions::assert_eq;
+
+use stitch::core::{DEFAULT_PREVIEW_CHAR_LIMIT, LocalSettings, page_of, preview_pages};
+
+#[test]
+fn pages_cover_the_text_and_end_on_line_breaks() {
+    let text: String = (0..10).map(|i| format!("line {i}\n")).collect();
+    let pages = preview_pages(&text, 20);
+    assert_eq!(pages.first().unwrap().start, 0);
+    assert_eq!(pages.last().unwrap().end, text.len());
+    for pair in pages.windows(2) {
+        assert_eq!(pair[0].end, pair[1].start);
+    }
+    for page in &pages {
+        assert!(text[page.clone()].chars().count() <= 20);
+        assert!(text[page.clone()].ends_with('\n'));
+    }
+    assert_eq!(&text[pages[0].clone()], "line 0\nline 1\n");
+}
+
+#[test]
+fn long_lines_and_multibyte_text_are_cut_at_the_limit() {
+    let text = "ääääääääää";
+    let pages = preview_pages(text, 4);
+    let parts: Vec<&str> = pages.iter().map(|r| &text[r.clone()]).collect();
+    assert_eq!(parts, vec!["ääää", "ääää", "ää"]);
+}
+
+#[test]
+fn no_limit_or_short_text_is_one_page() {
+    let whole = |text: &str, limit| {
+        let pages = preview_pages(text, limit);
+        pages.len() == 1 && pages[0] == (0..text.len())
+    };
+    assert!(whole("abc\ndef", 0));
+    assert!(whole("abc\ndef", 100));
+    assert!(whole("", 10));
+}
+
+#[test]
+fn page_of_finds_the_page_holding_an_offset() {
+    let pages = vec![0..10, 10..20, 20..25];
+    assert_eq!(page_of(&pages, 0), 0);
+    assert_eq!(page_of(&pages, 9), 0);
+    assert_eq!(page_of(&pages, 10), 1);
+    assert_eq!(page_of(&pages, 24), 2);
+    assert_eq!(page_of(&pages, 99), 2);
+    assert_eq!(page_of(&[], 3), 0);
+}
+
+#[test]
+fn limit_comes_from_local_settings() {
+    assert_eq!(
+        LocalSettings::default().preview_char_limit(),
+        DEFAULT_PREVIEW_CHAR_LIMIT
+    );
+    let ls: LocalSettings = serde_json::from_str(r#"{"preview_char_limit":0}"#).unwrap();
+    assert_eq!(ls.preview_char_limit(), 0);
+}
@@ -0,0 +1,61 @@
+use stitch::core::{
+    PathRewrite, WorkspaceSettings, load_workspace, render_unicode_tree_from_paths, save_workspace,
+};
+use tempfile::TempDir;
+
+fn rw(strip_prefix: &str, prepend: &str) -> PathRewrite {
+    PathRewrite {
+        strip_prefix: strip_prefix.into(),
+        prepend: prepend.into(),
+    }
+}
+
+#[test]
+fn default_is_identity() {
+    let r = PathRewrite::default();
+    assert!(r.is_identity());
+    assert_eq!(r.apply("src/main.rs"), "src/main.rs");
+    assert!(rw(" / ", "/").is_identity());
+}
+
+#[test]
+fn strips_only_a_whole_leading_directory() {
+    let r = rw("packages/foo/", "");
+    assert_eq!(r.apply("packages/foo/src/lib.rs"), "src/lib.rs");
+    assert_eq!(r.apply("packages/foobar/x.rs"), "packages/foobar/x.rs");
+    assert_eq!(r.apply("README.md"), "README.md");
+}
+
+#[test]
+fn prepends_virtual_root_after_stripping() {
+    let r = rw("/packages/foo", "app/");
+    assert_eq!(r.apply("packages/foo/src/lib.rs"), "app/src/lib.rs");
+    assert_eq!(r.apply("Cargo.toml"), "app/Cargo.toml");
+}
+
+#[test]
+fn rewritten_paths_render_as_tree() {
+    let r = rw("packages/foo", "");
+    let paths: Vec<String> = ["packages/foo/src/a.rs", "packages/foo/b.rs"]
+        .iter()
+        .map(|p| r.apply(p))
+        .collect();
+    let tree = render_unicode_tree_from_paths(&paths, Some("repo"));
+    assert!(tree.contains("src"));
+    assert!(!tree.contains("packages"));
+}
+
+#[test]
+fn rewrite_persists_in_workspace() {
+    let tmp = TempDir::new().unwrap();
+    let ws = WorkspaceSettings {
+        version: 1,
+        path_rewrite: rw("packages/foo", "foo"),
+        ..Default::default()
+    };
+    save_workspace(tmp.path(), &ws).unwrap();
+    assert_eq!(
+        load_workspace(tmp.path()).unwrap().path_rewrite,
+        ws.path_rewrite
+    );
+}
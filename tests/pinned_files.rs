@@ -0,0 +1,102 @@
+use std::fs;
+use std::path::PathBuf;
+
+use stitch::core::{
+    Profile, ProfileScope, apply_pinned_files, is_rel_path_within, load_profile, save_profile,
+};
+use tempfile::TempDir;
+
+fn project() -> TempDir {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path();
+    fs::create_dir_all(root.join("src")).unwrap();
+    fs::create_dir_all(root.join("docs")).unwrap();
+    for file in [
+        "src/a.rs",
+        "src/b.rs",
+        "src/lib.rs",
+        "docs/ARCH.md",
+        "Cargo.toml",
+    ] {
+        fs::write(root.join(file), "x").unwrap();
+    }
+    tmp
+}
+
+#[test]
+fn pinned_files_come_first_in_pin_order() {
+    let tmp = project();
+    let root = tmp.path();
+    let selected = vec![
+        root.join("src/a.rs"),
+        root.join("src/b.rs"),
+        root.join("src/lib.rs"),
+    ];
+    let pinned = vec!["src/lib.rs".to_string(), "Cargo.toml".to_string()];
+
+    let files = apply_pinned_files(root, &selected, &pinned);
+    assert_eq!(
+        files,
+        vec![
+            root.join("src/lib.rs"),
+            root.join("Cargo.toml"),
+            root.join("src/a.rs"),
+            root.join("src/b.rs"),
+        ]
+    );
+}
+
+#[test]
+fn pinned_files_are_emitted_even_when_not_selected() {
+    let tmp = project();
+    let root = tmp.path();
+    let files = apply_pinned_files(root, &[], &["docs/ARCH.md".to_string()]);
+    assert_eq!(files, vec![root.join("docs/ARCH.md")]);
+}
+
+#[test]
+fn stale_and_directory_pins_are_skipped() {
+    let tmp = project();
+    let root = tmp.path();
+    let selected = vec![root.join("src/a.rs")];
+    let pinned = vec!["gone.rs".to_string(), "src".to_string()];
+    assert_eq!(apply_pinned_files(root, &selected, &pinned), selected);
+}
+
+#[test]
+fn no_pins_keeps_the_selection_untouched() {
+    let selected = vec![PathBuf::from("/p/b.rs"), PathBuf::from("/p/a.rs")];
+    assert_eq!(
+        apply_pinned_files(&PathBuf::from("/p"), &selected, &[]),
+        selected
+    );
+}
+
+#[test]
+fn rel_path_containment_respects_component_boundaries() {
+    assert!(is_rel_path_within("src/a.rs", "src"));
+    assert!(is_rel_path_within("src", "src"));
+    assert!(is_rel_path_within("anything", ""));
+    assert!(!is_rel_path_within("src2/a.rs", "src"));
+    assert!(!is_rel_path_within("src", "src/a"));
+}
+
+#[test]
+fn pins_roundtrip_through_profile_and_default_when_missing() {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path();
+
+    let prof = Profile {
+        name: "pinned".into(),
+        pinned: vec!["README.md".into(), "src/lib.rs".into()],
+        ..Default::default()
+    };
+    save_profile(root, &prof, ProfileScope::Shared).unwrap();
+    let (loaded, _) = load_profile(root, "pinned").unwrap();
+    assert_eq!(loaded.pinned, prof.pinned);
+
+    let legacy = r#"{"name":"legacy","settings":{"version":1,"ext_filter":"","exclude_dirs":"","exclude_files":"","remove_prefix":"","remove_regex":"","hierarchy_only":false,"dirs_only":false},"explicit":[]}"#;
+    fs::write(root.join(".stitchworkspace/profiles/legacy.json"), legacy).unwrap();
+    let (legacy, _) = load_profile(root, "legacy").unwrap();
+    assert!(legacy.pinned.is_empty());
+}
@@ -0,0 +1,36 @@
+use stitch::core::{
+    NO_FOLDER_SELECTED, NO_ITEMS_SELECTED, OutputKind, has_output_content, is_placeholder_message,
+};
+
+#[test]
+fn placeholders_are_detected_exactly() {
+    assert!(is_placeholder_message(NO_FOLDER_SELECTED));
+    assert!(is_placeholder_message(NO_ITEMS_SELECTED));
+    assert!(!is_placeholder_message("No items selected."));
+    assert!(!is_placeholder_message(
+        "=== FILE HIERARCHY ===\n\nNo items selected.\n"
+    ));
+}
+
+#[test]
+fn messages_are_never_content() {
+    assert_eq!(OutputKind::default(), OutputKind::Message);
+    assert!(!has_output_content(OutputKind::Message, NO_FOLDER_SELECTED));
+    assert!(!has_output_content(OutputKind::Message, NO_ITEMS_SELECTED));
+    assert!(!has_output_content(
+        OutputKind::Message,
+        "Generation failed: worker panicked\n\nAdjust the selection or filters and try again.\n"
+    ));
+}
+
+#[test]
+fn only_non_blank_generated_output_counts_as_content() {
+    assert!(!has_output_content(OutputKind::Generated, ""));
+    assert!(!has_output_content(OutputKind::Generated, "\n  \n"));
+    assert!(has_output_content(
+        OutputKind::Generated,
+        "=== FILE HIERARCHY ===\n\nproj/\n"
+    ));
+    // Generated output that happens to read like a placeholder is still output.
+    assert!(has_output_content(OutputKind::Generated, NO_ITEMS_SELECTED));
+}
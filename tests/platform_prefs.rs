@@ -0,0 +1,69 @@
+use stitch::core::{
+    LocalSettings, PlatformPreferences, REDUCED_MOTION_TOAST_MILLIS, load_local_settings,
+    parse_bool_setting, parse_reg_query_value, save_local_settings,
+};
+use tempfile::TempDir;
+
+#[test]
+fn reg_query_output_is_parsed() {
+    let out = "\r\nHKEY_CURRENT_USER\\Control Panel\\Accessibility\\HighContrast\r\n    Flags    REG_SZ    127\r\n\r\n";
+    assert_eq!(parse_reg_query_value(out, "Flags"), Some("127"));
+    assert_eq!(parse_reg_query_value(out, "flags"), Some("127"));
+    assert_eq!(parse_reg_query_value(out, "MinAnimate"), None);
+    assert_eq!(parse_reg_query_value("Flags are not set", "Flags"), None);
+}
+
+#[test]
+fn defaults_and_gsettings_booleans() {
+    assert_eq!(parse_bool_setting("1\n"), Some(true));
+    assert_eq!(parse_bool_setting("0\n"), Some(false));
+    assert_eq!(parse_bool_setting("true\n"), Some(true));
+    assert_eq!(parse_bool_setting("'false'"), Some(false));
+    assert_eq!(parse_bool_setting(""), None);
+    assert_eq!(
+        parse_bool_setting("The domain/default pair does not exist"),
+        None
+    );
+}
+
+#[test]
+fn local_overrides_win_over_the_os() {
+    let os = PlatformPreferences {
+        high_contrast: true,
+        reduced_motion: false,
+    };
+    assert_eq!(os.with_overrides(None, None), os);
+    assert_eq!(
+        os.with_overrides(Some(false), Some(true)),
+        PlatformPreferences {
+            high_contrast: false,
+            reduced_motion: true,
+        }
+    );
+}
+
+#[test]
+fn reduced_motion_keeps_toasts_up_longer() {
+    let calm = PlatformPreferences {
+        reduced_motion: true,
+        ..PlatformPreferences::default()
+    };
+    assert_eq!(PlatformPreferences::default().toast_millis(1200), 1200);
+    assert_eq!(calm.toast_millis(1200), REDUCED_MOTION_TOAST_MILLIS);
+    assert_eq!(calm.toast_millis(10_000), 10_000);
+}
+
+#[test]
+fn overrides_round_trip_through_local_settings() {
+    let tmp = TempDir::new().unwrap();
+    assert_eq!(LocalSettings::default().high_contrast, None);
+    let ls = LocalSettings {
+        high_contrast: Some(true),
+        reduced_motion: Some(false),
+        ..Default::default()
+    };
+    save_local_settings(tmp.path(), &ls).unwrap();
+    let loaded = load_local_settings(tmp.path()).unwrap();
+    assert_eq!(loaded.high_contrast, Some(true));
+    assert_eq!(loaded.reduced_motion, Some(false));
+}
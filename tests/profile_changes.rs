@@ -0,0 +1,111 @@
+use pretty_assertions::assert_eq;
+
+use stitch::core::{
+    DirRuleKind, DirectoryRule, Profile, ProfileSelection, SettingChange, WorkspaceSettings,
+    diff_profiles, diff_workspace_settings,
+};
+
+fn sel(path: &str, state: bool) -> ProfileSelection {
+    ProfileSelection {
+        path: path.into(),
+        state,
+    }
+}
+
+#[test]
+fn unchanged_profile_has_no_changes() {
+    let p = Profile {
+        name: "api".into(),
+        explicit: vec![sel("src", true)],
+        ..Default::default()
+    };
+    let changes = diff_profiles(&p, &p.clone());
+    assert!(changes.is_empty());
+    assert_eq!(changes.render(), "");
+}
+
+#[test]
+fn settings_changes_name_nested_fields() {
+    let before = WorkspaceSettings::default();
+    let mut after = before.clone();
+    after.ext_filter = ".rs, .toml".into();
+    after.path_rewrite.prepend = "app".into();
+    after.svg_inline_limit = Some(0);
+
+    let changes = diff_workspace_settings(&before, &after);
+    let fields: Vec<&str> = changes.iter().map(|c| c.field.as_str()).collect();
+    assert_eq!(
+        fields,
+        vec!["ext_filter", "path_rewrite.prepend", "svg_inline_limit"]
+    );
+    assert_eq!(
+        changes[0],
+        SettingChange {
+            field: "ext_filter".into(),
+            before: "(empty)".into(),
+            after: "\".rs, .toml\"".into(),
+        }
+    );
+    assert_eq!(changes[2].before, "(unset)");
+}
+
+#[test]
+fn selections_rules_and_pins_are_listed_by_path() {
+    let before = Profile {
+        name: "api".into(),
+        explicit: vec![sel("src", true), sel("docs", false), sel("tests", true)],
+        pinned: vec!["README.md".into()],
+        ..Default::default()
+    };
+    let after = Profile {
+        name: "api-v2".into(),
+        explicit: vec![sel("src", true), sel("docs", true), sel("bench", false)],
+        dir_rules: vec![DirectoryRule {
+            path: "vendor".into(),
+            kind: DirRuleKind::Exclude,
+        }],
+        pinned: vec!["Cargo.toml".into()],
+        ..Default::default()
+    };
+
+    let changes = diff_profiles(&before, &after);
+    assert_eq!(changes.checked, vec!["docs"]);
+    assert_eq!(changes.unchecked, vec!["bench"]);
+    assert_eq!(changes.cleared, vec!["tests"]);
+    assert_eq!(changes.dir_rules, vec!["vendor"]);
+    assert_eq!(
+        changes.render(),
+        "\
+Renamed: api → api-v2
+Selections: 1 checked, 1 unchecked, 1 cleared
+  + docs
+  - bench
+  ~ tests
+Directory rules changed: 1
+    vendor
+Pinned: 1
+    Cargo.toml
+Unpinned: 1
+    README.md
+"
+    );
+}
+
+#[test]
+fn long_path_lists_are_cut() {
+    let before = Profile::default();
+    let after = Profile {
+        explicit: (0..12).map(|i| sel(&format!("f{i:02}.rs"), true)).collect(),
+        ..Default::default()
+    };
+    let rendered = diff_profiles(&before, &after).render();
+    assert!(
+        rendered.starts_with("Selections: 12 checked\n"),
+        "{rendered}"
+    );
+    assert!(
+        rendered.contains("  + f09.rs\n  … and 2 more\n"),
+        "{rendered}"
+    );
+    assert!(!rendered.contains("f10.rs"));
+}
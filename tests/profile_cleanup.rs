@@ -0,0 +1,117 @@
+use std::path::Path;
+
+use pretty_assertions::assert_eq;
+use stitch::core::{
+    DirRuleKind, DirectoryRule, DirectorySample, Node, Profile, ProfileSelection, SampleOrder,
+    ScannedPaths, StaleEntries, find_stale_entries, remove_stale_entries,
+};
+
+fn node(path: &Path, children: Vec<Node>, has_children: bool) -> Node {
+    Node {
+        name: path.file_name().unwrap().to_string_lossy().into_owned(),
+        path: path.to_path_buf(),
+        is_dir: has_children,
+        children,
+        expanded: false,
+        has_children,
+    }
+}
+
+/// proj/{src/{main.rs}, vendor/ (not loaded yet), README.md}
+fn tree(root: &Path) -> Node {
+    let src = root.join("src");
+    node(
+        root,
+        vec![
+            node(&src, vec![node(&src.join("main.rs"), vec![], false)], true),
+            node(&root.join("vendor"), vec![], true),
+            node(&root.join("README.md"), vec![], false),
+        ],
+        true,
+    )
+}
+
+fn profile() -> Profile {
+    let sel = |path: &str, state| ProfileSelection {
+        path: path.into(),
+        state,
+    };
+    Profile {
+        name: "backend".into(),
+        explicit: vec![
+            sel("", true),
+            sel("src/main.rs", true),
+            sel("src/old.rs", true),
+            sel("docs", false),
+            sel("vendor/lib/x.rs", true),
+        ],
+        samples: vec![DirectorySample {
+            path: "migrations".into(),
+            count: 3,
+            order: SampleOrder::default(),
+        }],
+        dir_rules: vec![DirectoryRule {
+            path: "src".into(),
+            kind: DirRuleKind::Exclude,
+        }],
+        pinned: vec!["README.md".into(), "src/gone.rs".into()],
+        ..Profile::default()
+    }
+}
+
+#[test]
+fn entries_missing_from_the_scan_are_stale() {
+    let root = Path::new("/work/proj");
+    let scanned = ScannedPaths::from_tree(root, &tree(root));
+    assert!(scanned.contains(""));
+    assert!(scanned.contains("src/main.rs"));
+    assert!(scanned.contains("vendor/lib/x.rs"));
+    assert!(!scanned.contains("vendorx/a.rs"));
+
+    let stale = find_stale_entries(&profile(), &scanned);
+    assert_eq!(
+        stale,
+        StaleEntries {
+            explicit: vec!["src/old.rs".into(), "docs".into()],
+            samples: vec!["migrations".into()],
+            dir_rules: vec![],
+            pinned: vec!["src/gone.rs".into()],
+        }
+    );
+    assert_eq!(stale.len(), 4);
+    assert_eq!(
+        stale.render(),
+        "Selections (2):\n  src/old.rs\n  docs\nSamples (1):\n  migrations\nPinned files (1):\n  src/gone.rs\n"
+    );
+}
+
+#[test]
+fn removing_keeps_everything_else() {
+    let root = Path::new("/work/proj");
+    let scanned = ScannedPaths::from_tree(root, &tree(root));
+    let mut profile = profile();
+    let stale = find_stale_entries(&profile, &scanned);
+    remove_stale_entries(&mut profile, &stale);
+
+    let paths: Vec<&str> = profile.explicit.iter().map(|s| s.path.as_str()).collect();
+    assert_eq!(paths, ["", "src/main.rs", "vendor/lib/x.rs"]);
+    assert!(profile.samples.is_empty());
+    assert_eq!(profile.dir_rules.len(), 1);
+    assert_eq!(profile.pinned, ["README.md"]);
+    assert!(find_stale_entries(&profile, &scanned).is_empty());
+}
+
+#[test]
+fn absorbing_skips_paths_already_listed() {
+    let mut a = StaleEntries {
+        explicit: vec!["a.rs".into()],
+        ..StaleEntries::default()
+    };
+    a.absorb(StaleEntries {
+        explicit: vec!["a.rs".into(), "b.rs".into()],
+        pinned: vec!["c.rs".into()],
+        ..StaleEntries::default()
+    });
+    assert_eq!(a.explicit, ["a.rs", "b.rs"]);
+    assert_eq!(a.pinned, ["c.rs"]);
+}
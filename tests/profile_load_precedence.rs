@@ -3,7 +3,7 @@ use stitch::core::{
 };
 use tempfile::TempDir;
 
-const fn ws() -> WorkspaceSettings {
+fn ws() -> WorkspaceSettings {
     WorkspaceSettings {
         version: 1,
         ext_filter: String::new(),
@@ -23,6 +23,7 @@ const fn ws() -> WorkspaceSettings {
             slint_remove_line_comments: false,
             slint_remove_block_comments: false,
         },
+        ..Default::default()
     }
 }
 
@@ -35,6 +36,7 @@ fn load_profile_prefers_local_over_shared() {
         name: "same".into(),
         settings: ws(),
         explicit: vec![],
+        ..Default::default()
     };
     shared.settings.ext_filter = ".rs".into();
     save_profile(root, &shared, ProfileScope::Shared).unwrap();
@@ -43,6 +45,7 @@ fn load_profile_prefers_local_over_shared() {
         name: "same".into(),
         settings: ws(),
         explicit: vec![],
+        ..Default::default()
     };
     local.settings.ext_filter = ".md".into();
     save_profile(root, &local, ProfileScope::Local).unwrap();
@@ -41,6 +41,7 @@ fn sample_ws() -> WorkspaceSettings {
             slint_remove_line_comments: false,
             slint_remove_block_comments: false,
         },
+        ..Default::default()
     }
 }
 
@@ -56,6 +57,7 @@ fn save_then_load_profile_roundtrip_shared_scope() {
             path: "src/lib.rs".into(),
             state: true,
         }],
+        ..Default::default()
     };
 
     save_profile(root, &prof, ProfileScope::Shared).expect("save profile");
@@ -78,16 +80,19 @@ fn list_profiles_local_overrides_shared_same_name() {
         name: "same".into(),
         settings: sample_ws(),
         explicit: vec![],
+        ..Default::default()
     };
     let p_local = Profile {
         name: "same".into(),
         settings: sample_ws(),
         explicit: vec![],
+        ..Default::default()
     };
     let p_beta = Profile {
         name: "beta".into(),
         settings: sample_ws(),
         explicit: vec![],
+        ..Default::default()
     };
 
     save_profile(root, &p_shared, ProfileScope::Shared).unwrap();
@@ -121,6 +126,7 @@ fn delete_profile_removes_entry() {
         name: "to_delete".into(),
         settings: sample_ws(),
         explicit: vec![],
+        ..Default::default()
     };
     save_profile(root, &p, ProfileScope::Shared).unwrap();
 
@@ -0,0 +1,147 @@
+use pretty_assertions::assert_eq;
+use stitch::core::{
+    ProjectFile, ProjectStatGroup, ProjectStats, WorkspaceSettings, count_lines,
+    project_stats_for_settings, project_stats_report,
+};
+use stitch::testing::write_file;
+use tempfile::TempDir;
+
+fn project() -> TempDir {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path();
+    write_file(root, "src/main.rs", "fn main() {}\n// end\n").unwrap();
+    write_file(root, "src/lib.rs", "pub mod a;\n").unwrap();
+    write_file(root, "docs/guide.md", "# Guide\n\nText\n").unwrap();
+    write_file(root, "README", "readme").unwrap();
+    write_file(root, "Cargo.lock", "# lock\n").unwrap();
+    write_file(root, "target/debug/out.rs", "fn skipped() {}\n").unwrap();
+    tmp
+}
+
+fn settings() -> WorkspaceSettings {
+    WorkspaceSettings {
+        ext_filter: ".rs".into(),
+        exclude_dirs: "target".into(),
+        exclude_files: "Cargo.lock".into(),
+        ..WorkspaceSettings::default()
+    }
+}
+
+#[test]
+fn totals_count_every_file_outside_excluded_directories() {
+    let tmp = project();
+    let stats = project_stats_for_settings(tmp.path(), &settings(), &[]);
+    let paths: Vec<&str> = stats.files().iter().map(|f| f.path.as_str()).collect();
+    assert_eq!(
+        paths,
+        [
+            "Cargo.lock",
+            "README",
+            "docs/guide.md",
+            "src/lib.rs",
+            "src/main.rs"
+        ]
+    );
+    assert_eq!(
+        stats.total(),
+        ProjectStatGroup {
+            key: String::new(),
+            files: 5,
+            lines: 8,
+            bytes: 58,
+            included_files: 2,
+            included_lines: 3,
+            included_bytes: 31,
+        }
+    );
+}
+
+#[test]
+fn groups_by_extension_and_top_level_directory() {
+    let tmp = project();
+    let stats = project_stats_for_settings(tmp.path(), &settings(), &[]);
+    let ext: Vec<(String, usize, usize)> = stats
+        .by_extension()
+        .into_iter()
+        .map(|g| (g.key, g.files, g.included_files))
+        .collect();
+    assert_eq!(
+        ext,
+        [
+            (".rs".into(), 2, 2),
+            ("(none)".into(), 1, 0),
+            (".lock".into(), 1, 0),
+            (".md".into(), 1, 0),
+        ]
+    );
+    let dirs: Vec<(String, usize)> = stats
+        .by_directory()
+        .into_iter()
+        .map(|g| (g.key, g.lines))
+        .collect();
+    assert_eq!(
+        dirs,
+        [
+            ("docs/".into(), 3),
+            ("src/".into(), 3),
+            ("(root)".into(), 2)
+        ]
+    );
+}
+
+#[test]
+fn largest_files_and_report() {
+    let mut stats = ProjectStats::default();
+    for (path, bytes, included) in [
+        ("a.rs", 10, true),
+        ("big.bin", 4096, false),
+        ("b.rs", 20, true),
+    ] {
+        stats.add_file(ProjectFile {
+            path: path.into(),
+            bytes,
+            lines: 1,
+            included,
+        });
+    }
+    let largest: Vec<&str> = stats.largest(2).iter().map(|f| f.path.as_str()).collect();
+    assert_eq!(largest, ["big.bin", "b.rs"]);
+
+    let report = stats.render();
+    assert!(report.starts_with(
+        "Files: 3 (2 included, 66.7%)\nLines: 3 (2 included, 66.7%)\nSize:  4.0 KiB (30 B included)\n"
+    ));
+    assert!(report.contains("\nExtension   files  included"));
+    assert!(
+        report.contains("\nLargest files\n   4.0 KiB         1 lines  big.bin  (filtered out)\n")
+    );
+}
+
+#[test]
+fn line_counts_skip_binary_data() {
+    assert_eq!(count_lines(b""), 0);
+    assert_eq!(count_lines(b"a\nb"), 2);
+    assert_eq!(count_lines(b"a\nb\n"), 2);
+    assert_eq!(count_lines(b"\x89PNG\0\n\n"), 0);
+}
+
+#[test]
+fn cli_report_uses_saved_settings_or_reports_errors() {
+    let tmp = project();
+    let report = project_stats_report(tmp.path(), None).unwrap();
+    // The default exclusions leave out `target/` and count `Cargo.lock` as filtered.
+    assert!(
+        report.starts_with("Files: 5 (4 included, 80.0%)\n"),
+        "{report}"
+    );
+
+    assert_eq!(
+        project_stats_report(tmp.path(), Some("missing")),
+        Err("no profile named 'missing' in this project".into())
+    );
+    assert!(
+        project_stats_report(&tmp.path().join("nope"), None)
+            .unwrap_err()
+            .ends_with("is not a directory")
+    );
+}
@@ -1,74 +1,13 @@
 // tests/prop_fs_scanner_random_tree.rs
 use proptest::prelude::*;
 use std::collections::{BTreeSet, HashSet};
-use std::fs;
 use std::path::Path;
 use tempfile::TempDir;
 
 use stitch::core::{Node, path_to_unix, scan_dir_to_node};
-
-/// ===== Generators =====
-fn seg() -> impl Strategy<Value = String> {
-    // directory / file name part (no slash)
-    // small to keep FS work cheap
-    "[A-Za-z0-9_\\-]{1,8}".prop_map(|s| s)
-}
-
-fn extseg() -> impl Strategy<Value = String> {
-    // lowercase extension segment
-    "[a-z]{1,3}".prop_map(|s| s)
-}
-
-#[derive(Clone, Debug)]
-struct FileSpec {
-    dirs: Vec<String>, // relative directory components
-    fname: String,     // file name (may have one or two dots)
-    last_ext: String,  // last extension normalized like ".rs" (lower) or "" if none
-}
-
-fn filename_with_last_ext() -> impl Strategy<Value = (String, String)> {
-    // 70% single extension:  name.ext   (last_ext=".ext")
-    // 30% double extension: name.ext1.ext2 (last_ext=".ext2")
-    prop_oneof![
-        (seg(), extseg()).prop_map(|(base, e)| { (format!("{base}.{e}"), format!(".{e}")) }),
-        (seg(), extseg(), extseg())
-            .prop_map(|(base, e1, e2)| { (format!("{base}.{e1}.{e2}"), format!(".{e2}")) }),
-    ]
-}
-
-fn file_spec() -> impl Strategy<Value = FileSpec> {
-    (
-        prop::collection::vec(seg(), 0..=2),
-        filename_with_last_ext(),
-    )
-        .prop_map(|(dirs, (fname, last_ext))| FileSpec {
-            dirs,
-            fname,
-            last_ext,
-        })
-}
+use stitch::testing::{FileSpec, file_specs, make_on_disk};
 
 /// ===== Helpers =====
-fn make_on_disk(root: &Path, files: &[FileSpec]) {
-    for f in files {
-        let mut p = root.to_path_buf();
-        for d in &f.dirs {
-            p.push(d);
-        }
-        fs::create_dir_all(&p).unwrap();
-        p.push(&f.fname);
-        fs::write(p, "x").unwrap();
-    }
-}
-
-fn last_ext_of_filename(fname: &str) -> String {
-    use std::path::Path;
-    let p = Path::new(fname);
-    p.extension().map_or_else(String::new, |e| {
-        format!(".{}", e.to_string_lossy().to_lowercase())
-    })
-}
-
 fn collect_tree_paths(
     root: &Path,
     node: &Node,
@@ -175,8 +114,9 @@ fn derive_filter_sets(
     let mut file_basenames: BTreeSet<String> = BTreeSet::new();
 
     for f in files {
-        if !f.last_ext.is_empty() {
-            present_exts.insert(f.last_ext.clone());
+        let last_ext = f.last_ext();
+        if !last_ext.is_empty() {
+            present_exts.insert(last_ext);
         }
         for d in &f.dirs {
             dir_names.insert(d.clone());
@@ -249,14 +189,14 @@ proptest! {
     })]
 
     #[test]
-    fn scanner_respects_filters_and_order(files in prop::collection::vec(file_spec(), 1..20)) {
+    fn scanner_respects_filters_and_order(files in file_specs(1, 19)) {
         use std::collections::BTreeSet;
 
         let tmp = TempDir::new().unwrap();
         let root = tmp.path();
 
         // Materialize the random tree.
-        make_on_disk(root, &files);
+        make_on_disk(root, &files).unwrap();
 
         // Derive deterministic filter sets from what's present.
         let (include_exts, exclude_exts, exclude_dirs, exclude_files, include_mode) =
@@ -282,7 +222,7 @@ proptest! {
 
             // Basename & last ext
             let basename = f.fname.as_str();
-            let last_ext = last_ext_of_filename(basename);
+            let last_ext = f.last_ext();
 
             if should_include_file(
                 &comps,
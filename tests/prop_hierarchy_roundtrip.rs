@@ -11,6 +11,7 @@ fn ext() -> impl Strategy<Value = String> {
     "[a-z]{1,3}".prop_map(|s| s)
 }
 
+#[allow(clippy::unnecessary_min_or_max)]
 fn path() -> impl Strategy<Value = String> {
     // depth: 1..=3; last element 50% dir, 50% file
     (1usize..=3, prop::bool::ANY)
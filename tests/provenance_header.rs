@@ -0,0 +1,73 @@
+use std::path::Path;
+
+use pretty_assertions::assert_eq;
+
+use stitch::core::{DEFAULT_PROVENANCE_FORMAT, OutputLayout, OutputParts, Provenance};
+
+fn provenance() -> Provenance {
+    Provenance {
+        time: "2026-01-02T03:04:05+00:00".into(),
+        version: "1.2.3".into(),
+        profile: "backend".into(),
+        root_hash: "0123456789ab".into(),
+        ..Provenance::default()
+    }
+}
+
+#[test]
+fn default_format_lists_every_field() {
+    assert_eq!(
+        provenance().render(DEFAULT_PROVENANCE_FORMAT),
+        "Generated by stitch 1.2.3 at 2026-01-02T03:04:05+00:00\nProfile: backend\nRoot: 0123456789ab\n"
+    );
+}
+
+#[test]
+fn lines_with_only_empty_fields_are_dropped() {
+    let p = Provenance {
+        time: String::new(),
+        profile: String::new(),
+        ..provenance()
+    };
+    assert_eq!(
+        p.render("# time: {time}\n# profile: {profile}\n# stitch {version} ({time})\n# static"),
+        "# stitch 1.2.3 ()\n# static\n"
+    );
+}
+
+#[test]
+fn deterministic_provenance_has_no_time_and_a_stable_root_hash() {
+    let a = Provenance::new(Path::new("/work/project"), None, true);
+    let b = Provenance::new(Path::new("/work/project"), Some("p"), true);
+    assert!(a.time.is_empty());
+    assert_eq!(a.version, env!("CARGO_PKG_VERSION"));
+    assert_eq!(a.root_hash, b.root_hash);
+    assert_eq!(a.root_hash.len(), 12);
+    assert_ne!(
+        a.root_hash,
+        Provenance::new(Path::new("/work/other"), None, true).root_hash
+    );
+    assert!(
+        !Provenance::new(Path::new("/work"), None, false)
+            .time
+            .is_empty()
+    );
+}
+
+#[test]
+fn layout_puts_the_header_first_and_is_off_by_default() {
+    assert_eq!(OutputLayout::default().provenance_format(), None);
+
+    let layout: OutputLayout = serde_json::from_str(r#"{"sections":[],"provenance":""}"#).unwrap();
+    assert_eq!(layout.provenance_format(), Some(DEFAULT_PROVENANCE_FORMAT));
+
+    let parts = OutputParts {
+        provenance: Some(provenance().render("Bundle from {profile}")),
+        hierarchy: Some("root/\n".into()),
+        ..OutputParts::default()
+    };
+    assert_eq!(
+        layout.render(&parts),
+        "Bundle from backend\n\n=== FILE HIERARCHY ===\n\nroot/\n"
+    );
+}
@@ -0,0 +1,63 @@
+use std::{path::Path, process::Command};
+
+use pretty_assertions::assert_eq;
+use stitch::core::Provenance;
+use tempfile::TempDir;
+
+fn git(dir: &Path, args: &[&str]) {
+    let out = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(
+        out.status.success(),
+        "git {args:?}: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+}
+
+#[test]
+fn git_variables_come_from_the_last_commit() {
+    let tmp = TempDir::new().unwrap();
+    let dir = tmp.path();
+    git(dir, &["init", "-q", "-b", "feature/x"]);
+    git(dir, &["config", "user.name", "Jo Doe"]);
+    git(dir, &["config", "user.email", "jo@example.com"]);
+    std::fs::write(dir.join("a.txt"), "a").unwrap();
+    git(dir, &["add", "."]);
+    git(dir, &["commit", "-q", "-m", "Add a\n\nbody text"]);
+
+    let format = "Branch: {git_branch}\nLast: {git_commit} ({git_author})";
+    let p = Provenance::new(dir, None, true).with_sources(dir, format, &[]);
+    assert_eq!(
+        p.render(format),
+        "Branch: feature/x\nLast: Add a (Jo Doe)\n"
+    );
+}
+
+#[test]
+fn git_lines_are_dropped_outside_a_repository() {
+    let tmp = TempDir::new().unwrap();
+    let format = "Branch: {git_branch}\nstitch {version}";
+    let p = Provenance::new(tmp.path(), None, true).with_sources(tmp.path(), format, &[]);
+    assert!(p.git_branch.is_empty() && p.git_commit.is_empty());
+    assert_eq!(
+        p.render(format),
+        format!("stitch {}\n", env!("CARGO_PKG_VERSION"))
+    );
+}
+
+#[test]
+fn only_allowlisted_environment_variables_are_read() {
+    let path = std::env::var("PATH").unwrap();
+    let format = "Path: {env:PATH}\nHome: {env:HOME}\n{env:}";
+    let p = Provenance::new(Path::new("/work"), None, true).with_sources(
+        Path::new("/work"),
+        format,
+        &["PATH".to_string()],
+    );
+    assert_eq!(p.env, vec![("PATH".to_string(), path.clone())]);
+    assert_eq!(p.render(format), format!("Path: {path}\n{{env:}}\n"));
+}
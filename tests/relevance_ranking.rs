@@ -0,0 +1,89 @@
+use std::{collections::HashSet, fs, path::Path};
+
+use stitch::core::{
+    RANK_MAX_FILE_BYTES, RankedFile, collect_file_paths, query_terms, rank_files_by_query,
+    scan_dir_to_node, select_top_within_budget,
+};
+use tempfile::TempDir;
+
+fn write(root: &Path, rel: &str, contents: &str) {
+    let p = root.join(rel);
+    fs::create_dir_all(p.parent().unwrap()).unwrap();
+    fs::write(p, contents).unwrap();
+}
+
+#[test]
+fn query_terms_lowercases_dedups_and_drops_stopwords() {
+    let terms = query_terms("How does the Token counter handle TOKEN limits?");
+    assert_eq!(terms, vec!["token", "counter", "handle", "limits"]);
+    assert!(query_terms("a an the ?").is_empty());
+}
+
+#[test]
+fn ranks_content_and_path_matches_above_unrelated_files() {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path();
+    write(
+        root,
+        "src/tokens.rs",
+        "fn count_tokens() { /* token token */ }",
+    );
+    write(root, "src/ui.rs", "fn draw() { let token = 1; }");
+    write(root, "src/fs.rs", "fn scan() {}");
+
+    let none: HashSet<String> = HashSet::new();
+    let tree = scan_dir_to_node(root, &none, &none, &none, &none);
+    let files = collect_file_paths(&tree);
+    assert_eq!(files.len(), 3);
+
+    let ranked = rank_files_by_query(root, "token", &files);
+    let names: Vec<_> = ranked
+        .iter()
+        .map(|r| r.path.file_name().unwrap().to_string_lossy().into_owned())
+        .collect();
+    assert_eq!(names, vec!["tokens.rs", "ui.rs"], "fs.rs has no match");
+    assert!(ranked[0].score > ranked[1].score);
+}
+
+#[test]
+fn files_over_the_size_limit_are_not_ranked() {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path();
+    write(root, "small.rs", "fn token() {}");
+    let limit = usize::try_from(RANK_MAX_FILE_BYTES).unwrap();
+    write(root, "bundle.js", &"token ".repeat(limit / 6 + 1));
+
+    let files = vec![root.join("small.rs"), root.join("bundle.js")];
+    let ranked = rank_files_by_query(root, "token", &files);
+    assert_eq!(ranked.len(), 1);
+    assert_eq!(ranked[0].path, root.join("small.rs"));
+}
+
+#[test]
+fn empty_query_yields_nothing() {
+    let tmp = TempDir::new().unwrap();
+    write(tmp.path(), "a.txt", "anything");
+    let files = vec![tmp.path().join("a.txt")];
+    assert!(rank_files_by_query(tmp.path(), "  the  ", &files).is_empty());
+}
+
+#[test]
+fn budget_selection_skips_oversized_files_and_respects_top_k() {
+    let mk = |name: &str, score: f64, est_tokens: usize| RankedFile {
+        path: name.into(),
+        score,
+        est_tokens,
+    };
+    let ranked = vec![
+        mk("big", 9.0, 900),
+        mk("a", 8.0, 100),
+        mk("b", 7.0, 100),
+        mk("c", 6.0, 100),
+    ];
+
+    let picked = select_top_within_budget(&ranked, 10, 250);
+    assert_eq!(picked, vec![Path::new("a"), Path::new("b")]);
+
+    let picked = select_top_within_budget(&ranked, 2, 0);
+    assert_eq!(picked, vec![Path::new("big"), Path::new("a")]);
+}
@@ -0,0 +1,43 @@
+use std::time::Duration;
+
+use stitch::core::{
+    RegexTimeout, compile_remove_regex, compile_remove_regex_opt, remove_matches_with_timeout,
+};
+
+#[test]
+fn valid_pattern_compiles_with_multiline_flags() {
+    let re = compile_remove_regex("BEGIN.*?END").unwrap();
+    assert_eq!(re.replace_all("a BEGIN\nx\nEND b", ""), "a  b");
+}
+
+#[test]
+fn invalid_pattern_reports_friendly_message() {
+    let err = compile_remove_regex("(").unwrap_err();
+    assert!(err.starts_with("invalid regex:"), "{err}");
+    assert!(!err.contains('\n'));
+    assert!(compile_remove_regex_opt(Some("(")).is_none());
+}
+
+#[test]
+fn oversized_pattern_is_rejected_as_too_complex() {
+    // Nested counted repetitions blow up the compiled program far past the limit.
+    let err = compile_remove_regex(r"(\w{1000}){1000}").unwrap_err();
+    assert_eq!(err, "regex too complex");
+}
+
+#[test]
+fn replacement_finishes_within_timeout() {
+    let re = compile_remove_regex(r"//[^\n]*").unwrap();
+    let out = remove_matches_with_timeout(&re, "a // x\nb\n", Duration::from_secs(5)).unwrap();
+    assert_eq!(out, "a \nb\n");
+}
+
+#[test]
+fn replacement_exceeding_timeout_is_stopped() {
+    let re = compile_remove_regex(r"\w+\s\w+\s\w+").unwrap();
+    let big = "word ".repeat(2_000_000);
+    assert_eq!(
+        remove_matches_with_timeout(&re, &big, Duration::ZERO),
+        Err(RegexTimeout)
+    );
+}
@@ -0,0 +1,155 @@
+use std::path::{Path, PathBuf};
+
+use notify::{
+    Event, EventKind,
+    event::{ModifyKind, RenameMode},
+};
+use pretty_assertions::assert_eq;
+use stitch::core::{
+    DirectorySample, PathRename, Profile, ProfileSelection, RenameTracker, remap_profile_paths,
+    renamed_path,
+};
+use tempfile::TempDir;
+
+fn rename_event(mode: RenameMode, paths: &[&Path]) -> Event {
+    paths.iter().fold(
+        Event::new(EventKind::Modify(ModifyKind::Name(mode))),
+        |ev, p| ev.add_path(p.to_path_buf()),
+    )
+}
+
+fn rename(from: &Path, to: &Path) -> PathRename {
+    PathRename {
+        from: from.to_path_buf(),
+        to: to.to_path_buf(),
+    }
+}
+
+#[test]
+fn combined_events_are_renames() {
+    let mut tracker = RenameTracker::default();
+    let (a, b) = (Path::new("/p/src/old.rs"), Path::new("/p/src/new.rs"));
+    assert_eq!(
+        tracker.observe(&rename_event(RenameMode::Both, &[a, b])),
+        Some(rename(a, b))
+    );
+    let modify = Event::new(EventKind::Modify(ModifyKind::Any)).add_path(a.to_path_buf());
+    assert_eq!(tracker.observe(&modify), None);
+}
+
+#[test]
+fn from_and_to_halves_pair_by_tracker_and_the_combined_repeat_is_dropped() {
+    let mut tracker = RenameTracker::default();
+    let (a, b) = (Path::new("/p/a.rs"), Path::new("/p/b.rs"));
+    let (c, d) = (Path::new("/p/c.rs"), Path::new("/p/d.rs"));
+    assert_eq!(
+        tracker.observe(&rename_event(RenameMode::From, &[a]).set_tracker(1)),
+        None
+    );
+    assert_eq!(
+        tracker.observe(&rename_event(RenameMode::From, &[c]).set_tracker(2)),
+        None
+    );
+    assert_eq!(
+        tracker.observe(&rename_event(RenameMode::To, &[b]).set_tracker(1)),
+        Some(rename(a, b))
+    );
+    // inotify follows up with the combined event.
+    assert_eq!(
+        tracker.observe(&rename_event(RenameMode::Both, &[a, b]).set_tracker(1)),
+        None
+    );
+    assert_eq!(
+        tracker.observe(&rename_event(RenameMode::To, &[d]).set_tracker(2)),
+        Some(rename(c, d))
+    );
+    assert_eq!(
+        tracker.observe(&rename_event(RenameMode::To, &[d]).set_tracker(3)),
+        None
+    );
+}
+
+#[test]
+fn untyped_renames_pair_the_missing_path_with_the_existing_one() {
+    let tmp = TempDir::new().unwrap();
+    let old = tmp.path().join("old.rs");
+    let new = tmp.path().join("new.rs");
+    std::fs::write(&new, "").unwrap();
+
+    let mut tracker = RenameTracker::default();
+    assert_eq!(
+        tracker.observe(&rename_event(RenameMode::Any, &[&old])),
+        None
+    );
+    assert_eq!(
+        tracker.observe(&rename_event(RenameMode::Any, &[&new])),
+        Some(rename(&old, &new))
+    );
+}
+
+#[test]
+fn renames_are_made_project_relative() {
+    let root = PathBuf::from("/p");
+    let r = rename(
+        &root.join("src").join("a.rs"),
+        &root.join("lib").join("a.rs"),
+    );
+    assert_eq!(
+        r.relative_to(&root),
+        Some(("src/a.rs".to_string(), "lib/a.rs".to_string()))
+    );
+    assert_eq!(
+        rename(Path::new("/p/a.rs"), Path::new("/q/a.rs")).relative_to(&root),
+        None
+    );
+}
+
+#[test]
+fn renamed_paths_cover_the_path_and_everything_below() {
+    assert_eq!(
+        renamed_path("src/old.rs", "src/old.rs", "src/new.rs").as_deref(),
+        Some("src/new.rs")
+    );
+    assert_eq!(
+        renamed_path("src/a/b.rs", "src/a", "lib").as_deref(),
+        Some("lib/b.rs")
+    );
+    assert_eq!(renamed_path("src/ab.rs", "src/a", "lib"), None);
+}
+
+#[test]
+fn profile_entries_follow_the_rename() {
+    let sel = |path: &str, state| ProfileSelection {
+        path: path.into(),
+        state,
+    };
+    let mut profile = Profile {
+        explicit: vec![
+            sel("", true),
+            sel("old/a.rs", true),
+            sel("old/b.rs", false),
+            sel("new/a.rs", false),
+        ],
+        samples: vec![DirectorySample {
+            path: "old".into(),
+            count: 2,
+            order: Default::default(),
+        }],
+        pinned: vec!["old/a.rs".into(), "README.md".into()],
+        ..Profile::default()
+    };
+    assert_eq!(remap_profile_paths(&mut profile, "old", "new"), 4);
+
+    let explicit: Vec<(&str, bool)> = profile
+        .explicit
+        .iter()
+        .map(|s| (s.path.as_str(), s.state))
+        .collect();
+    assert_eq!(
+        explicit,
+        [("", true), ("new/a.rs", true), ("new/b.rs", false)]
+    );
+    assert_eq!(profile.samples[0].path, "new");
+    assert_eq!(profile.pinned, ["new/a.rs", "README.md"]);
+    assert_eq!(remap_profile_paths(&mut profile, "old", "new"), 0);
+}
@@ -0,0 +1,111 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use stitch::core::{OutputOrder, order_output_files, parse_mod_declarations, rust_module_order};
+use tempfile::TempDir;
+
+fn write(root: &Path, rel: &str, body: &str) -> PathBuf {
+    let path = root.join(rel);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(&path, body).unwrap();
+    path
+}
+
+fn rels(root: &Path, files: &[PathBuf]) -> Vec<String> {
+    files
+        .iter()
+        .map(|p| {
+            p.strip_prefix(root)
+                .unwrap()
+                .to_string_lossy()
+                .replace('\\', "/")
+        })
+        .collect()
+}
+
+#[test]
+fn parses_out_of_line_mod_declarations_in_order() {
+    let src = r"
+//! Crate docs mentioning mod fake;
+mod zeta;
+pub mod alpha;
+pub(crate) mod beta;
+#[cfg(test)] mod tests;
+// mod commented;
+/* mod blocked; */
+mod inline {
+    mod nested;
+}
+mod r#async;
+use crate::alpha::modular;
+";
+    assert_eq!(
+        parse_mod_declarations(src),
+        ["zeta", "alpha", "beta", "tests", "async"]
+    );
+}
+
+#[test]
+fn crate_root_then_modules_depth_first() {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path();
+    let lib = write(root, "src/lib.rs", "mod util;\npub mod core;\n");
+    let core_mod = write(root, "src/core/mod.rs", "mod scan;\nmod text;\n");
+    let text = write(root, "src/core/text.rs", "");
+    let scan = write(root, "src/core/scan.rs", "mod walk;\n");
+    let walk = write(root, "src/core/scan/walk.rs", "");
+    let util = write(root, "src/util.rs", "");
+    let readme = write(root, "README.md", "");
+
+    // Alphabetical selection order, as the tree produces it.
+    let files = vec![readme, core_mod, scan, walk, text, lib, util];
+    assert_eq!(
+        rels(root, &rust_module_order(&files)),
+        [
+            "src/lib.rs",
+            "src/util.rs",
+            "src/core/mod.rs",
+            "src/core/scan.rs",
+            "src/core/scan/walk.rs",
+            "src/core/text.rs",
+            "README.md",
+        ]
+    );
+}
+
+#[test]
+fn unselected_modules_still_lead_to_selected_submodules() {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path();
+    let main = write(root, "src/main.rs", "mod ui;\nmod cli;\n");
+    write(root, "src/ui/mod.rs", "mod handlers;\n");
+    let handlers = write(root, "src/ui/handlers.rs", "");
+    let cli = write(root, "src/cli.rs", "");
+
+    let files = vec![cli, handlers, main];
+    assert_eq!(
+        rels(root, &rust_module_order(&files)),
+        ["src/main.rs", "src/ui/handlers.rs", "src/cli.rs"]
+    );
+}
+
+#[test]
+fn lib_comes_before_main_and_modules_are_emitted_once() {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path();
+    let main = write(root, "src/main.rs", "mod a;\n");
+    let lib = write(root, "src/lib.rs", "mod a;\n");
+    let a = write(root, "src/a.rs", "mod b;\n");
+    // A submodule named like its parent lives one directory deeper.
+    let b = write(root, "src/a/b.rs", "mod b;\n");
+    write(root, "src/a/b/b.rs", "");
+
+    let files = vec![main, a, b, lib];
+    assert_eq!(
+        rels(
+            root,
+            &order_output_files(root, files, OutputOrder::RustModules)
+        ),
+        ["src/lib.rs", "src/a.rs", "src/a/b.rs", "src/main.rs"]
+    );
+}
@@ -0,0 +1,96 @@
+use std::collections::HashSet;
+use std::fs;
+
+use stitch::core::{
+    DirRuleKind, DirectoryRule, scan_dir_to_node_with_rules, scan_dir_to_node_with_stats,
+};
+use tempfile::TempDir;
+
+fn set(items: &[&str]) -> HashSet<String> {
+    items.iter().map(|s| (*s).to_string()).collect()
+}
+
+fn project() -> TempDir {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path();
+    for dir in [
+        "src",
+        "node_modules/a",
+        "web/node_modules",
+        "vendor/lib",
+        "target",
+    ] {
+        fs::create_dir_all(root.join(dir)).unwrap();
+    }
+    for file in [
+        "src/main.rs",
+        "src/notes.md",
+        "src/.env",
+        ".env",
+        "README.md",
+        "logo.png",
+        "vendor/lib/x.rs",
+    ] {
+        fs::write(root.join(file), "x").unwrap();
+    }
+    tmp
+}
+
+#[test]
+fn counts_each_skipped_entry_by_name() {
+    let tmp = project();
+    let none = HashSet::new();
+    let scan = scan_dir_to_node_with_stats(
+        tmp.path(),
+        &none,
+        &none,
+        &set(&["node_modules", "dist"]),
+        &set(&[".env"]),
+    );
+    let stats = scan.stats;
+    assert_eq!(stats.excluded_dirs_found.get("node_modules"), Some(&2));
+    assert!(!stats.excluded_dirs_found.contains_key("dist"));
+    assert_eq!(stats.excluded_files_found.get(".env"), Some(&2));
+    assert_eq!(stats.filtered_files, 0);
+    assert_eq!(stats.override_excluded_dirs, 0);
+}
+
+#[test]
+fn counts_extension_filtered_files_and_override_exclusions() {
+    let tmp = project();
+    let none = HashSet::new();
+    let rules = [DirectoryRule {
+        path: "vendor".into(),
+        kind: DirRuleKind::Exclude,
+    }];
+    let scan = scan_dir_to_node_with_rules(tmp.path(), &set(&[".rs"]), &none, &none, &none, &rules);
+    // README.md, logo.png, src/notes.md, and both .env files; vendor/ isn't scanned.
+    assert_eq!(scan.stats.filtered_files, 5);
+    assert_eq!(scan.stats.override_excluded_dirs, 1);
+
+    let scan = scan_dir_to_node_with_stats(tmp.path(), &none, &set(&[".png"]), &none, &none);
+    assert_eq!(scan.stats.filtered_files, 1);
+}
+
+#[cfg(unix)]
+#[test]
+fn records_directories_it_may_not_read() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let tmp = project();
+    let locked = tmp.path().join("vendor");
+    fs::set_permissions(&locked, fs::Permissions::from_mode(0o000)).unwrap();
+    if fs::read_dir(&locked).is_ok() {
+        // Running as root: permissions aren't enforced.
+        fs::set_permissions(&locked, fs::Permissions::from_mode(0o755)).unwrap();
+        return;
+    }
+    let none = HashSet::new();
+    let scan = scan_dir_to_node_with_stats(tmp.path(), &set(&[".rs"]), &none, &none, &none);
+    fs::set_permissions(&locked, fs::Permissions::from_mode(0o755)).unwrap();
+
+    assert_eq!(scan.stats.permission_denied, vec![locked.clone()]);
+    // Shown (empty) despite the include filter, so the tree can flag it.
+    let vendor = scan.node.children.iter().find(|c| c.path == locked);
+    assert!(vendor.is_some_and(|n| n.children.is_empty()));
+}
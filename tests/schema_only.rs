@@ -0,0 +1,171 @@
+use pretty_assertions::assert_eq;
+use stitch::core::{
+    SCHEMA_MAX_KEYS, TransformKind, TransformOptions, TransformPipeline, TransformStep, schema_only,
+};
+
+#[test]
+fn json_keeps_keys_in_order_with_types_and_samples() {
+    let src = r#"{
+        "name": "my-app",
+        "private": true,
+        "version": 3,
+        "ratio": 0.5,
+        "parent": null,
+        "description": "A very long description that goes on and on past the limit",
+        "scripts": {"build": "vite build", "test": "vitest"},
+        "files": ["dist", "src", "README.md"],
+        "users": [{"id": 1, "tags": []}, {"id": 2, "tags": ["x"]}],
+        "empty": {}
+    }"#;
+    assert_eq!(
+        schema_only("package.json", src).as_deref(),
+        Some(concat!(
+            "name: string  # \"my-app\"\n",
+            "private: bool  # true\n",
+            "version: number  # 3\n",
+            "ratio: number  # 0.5\n",
+            "parent: null\n",
+            "description: string  # \"A very long description that goes on and…\"\n",
+            "scripts:\n",
+            "  build: string  # \"vite build\"\n",
+            "  test: string  # \"vitest\"\n",
+            "files: array  # 3 items\n",
+            "  - string  # \"dist\"\n",
+            "users: array  # 2 items\n",
+            "  -\n",
+            "    id: number  # 1\n",
+            "    tags: array  # 0 items\n",
+            "empty: object  # empty\n",
+        ))
+    );
+}
+
+#[test]
+fn json_objects_with_many_keys_are_cut() {
+    let entries: Vec<String> = (0..SCHEMA_MAX_KEYS + 3)
+        .map(|i| format!("\"k{i}\": {i}"))
+        .collect();
+    let src = format!("{{{}}}", entries.join(","));
+    let got = schema_only("big.json", &src).expect("json");
+    assert_eq!(got.lines().count(), SCHEMA_MAX_KEYS + 1);
+    assert!(got.ends_with("… 3 more keys\n"));
+}
+
+#[test]
+fn invalid_json_and_other_files_are_left_alone() {
+    assert_eq!(schema_only("broken.json", "{\"a\": "), None);
+    assert_eq!(schema_only("notes.txt", "a: 1"), None);
+}
+
+#[test]
+fn yaml_mappings_sequences_and_scalars() {
+    let src = concat!(
+        "# CI config\n",
+        "name: build # trailing comment\n",
+        "on:\n",
+        "  push:\n",
+        "    branches: [main, 'release/*']\n",
+        "env:\n",
+        "  RETRIES: 3\n",
+        "  DEBUG: false\n",
+        "  EMPTY:\n",
+        "jobs:\n",
+        "  test:\n",
+        "    runs-on: ubuntu-latest\n",
+        "    steps:\n",
+        "      - uses: actions/checkout@v4\n",
+        "        with:\n",
+        "          fetch-depth: 0\n",
+        "      - name: Test\n",
+        "        run: |\n",
+        "          cargo test\n",
+        "          cargo clippy\n",
+        "    matrix:\n",
+        "    - \"it's quoted\"\n",
+        "    - plain\n",
+    );
+    assert_eq!(
+        schema_only(".github/workflows/ci.yml", src).as_deref(),
+        Some(concat!(
+            "name: string  # \"build\"\n",
+            "on:\n",
+            "  push:\n",
+            "    branches: array  # [main, 'release/*']\n",
+            "env:\n",
+            "  RETRIES: number  # 3\n",
+            "  DEBUG: bool  # false\n",
+            "  EMPTY: null\n",
+            "jobs:\n",
+            "  test:\n",
+            "    runs-on: string  # \"ubuntu-latest\"\n",
+            "    steps: array  # 2 items\n",
+            "      -\n",
+            "        uses: string  # \"actions/checkout@v4\"\n",
+            "        with:\n",
+            "          fetch-depth: number  # 0\n",
+            "        name: string  # \"Test\"\n",
+            "        run: string  # \"cargo test…\"\n",
+            "    matrix: array  # 2 items\n",
+            "      - string  # \"it's quoted\"\n",
+        ))
+    );
+}
+
+#[test]
+fn array_items_show_keys_of_every_item() {
+    assert_eq!(
+        schema_only(
+            "list.json",
+            r#"[{"a":1},{"a":2,"d":3},{"b":{"x":1}},{"b":{"y":true}}]"#
+        )
+        .as_deref(),
+        Some(concat!(
+            "array  # 4 items\n",
+            "  -\n",
+            "    a: number  # 1\n",
+            "    d: number  # 3\n",
+            "    b:\n",
+            "      x: number  # 1\n",
+            "      y: bool  # true\n",
+        ))
+    );
+    assert_eq!(
+        schema_only("list.yaml", "- name: a\n- name: b\n  extra: 1\n").as_deref(),
+        Some("array  # 2 items\n  -\n    name: string  # \"a\"\n    extra: number  # 1\n")
+    );
+}
+
+#[test]
+fn yaml_block_scalars_and_documents() {
+    let src = "a: >-\n  folded\n  text\n---\n- 1\n- 2\n";
+    assert_eq!(
+        schema_only("multi.yaml", src).as_deref(),
+        Some("a: string  # \"folded…\"\n---\narray  # 2 items\n  - number  # 1\n")
+    );
+}
+
+#[test]
+fn runs_only_where_the_pipeline_lists_it() {
+    let pipeline = TransformPipeline {
+        steps: vec![TransformStep::for_extensions(
+            TransformKind::SchemaOnly,
+            &["json"],
+        )],
+    };
+    let opts = TransformOptions::default();
+    assert_eq!(
+        pipeline
+            .apply("a.json", "{\"a\": 1}".to_string(), &opts)
+            .contents,
+        "a: number  # 1\n"
+    );
+    assert_eq!(
+        pipeline
+            .apply("a.yaml", "a: 1\n".to_string(), &opts)
+            .contents,
+        "a: 1\n"
+    );
+    let parsed: TransformStep =
+        serde_json::from_str(r#"{"kind":"schema_only","extensions":["yaml"]}"#).expect("step");
+    assert_eq!(parsed.kind, TransformKind::SchemaOnly);
+}
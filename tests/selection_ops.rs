@@ -0,0 +1,77 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use pretty_assertions::assert_eq;
+use stitch::core::{apply_selection_diff, select_only, set_selection_for_subtree};
+
+fn p(rel: &str) -> PathBuf {
+    Path::new("/proj").join(rel)
+}
+
+fn sorted(explicit: &HashMap<PathBuf, bool>) -> Vec<(String, bool)> {
+    let mut out: Vec<(String, bool)> = explicit
+        .iter()
+        .map(|(k, v)| (k.strip_prefix("/proj").unwrap().display().to_string(), *v))
+        .collect();
+    out.sort();
+    out
+}
+
+#[test]
+fn a_subtree_drops_its_descendants_entries_only() {
+    let mut explicit = HashMap::from([
+        (p("src/a.rs"), false),
+        (p("src/ui"), true),
+        (p("srcx/b.rs"), true),
+        (p("README.md"), true),
+    ]);
+    set_selection_for_subtree(&mut explicit, &p("src"), true);
+    assert_eq!(
+        sorted(&explicit),
+        [
+            ("README.md".into(), true),
+            ("src".into(), true),
+            ("srcx/b.rs".into(), true),
+        ]
+    );
+
+    set_selection_for_subtree(&mut explicit, &p("README.md"), false);
+    assert_eq!(explicit[&p("README.md")], false);
+}
+
+#[test]
+fn diffs_apply_parents_before_children() {
+    let mut explicit = HashMap::new();
+    apply_selection_diff(
+        &mut explicit,
+        &[p("src/generated/keep.rs"), p("src")],
+        &[p("src/generated")],
+    );
+    assert_eq!(
+        sorted(&explicit),
+        [
+            ("src".into(), true),
+            ("src/generated".into(), false),
+            ("src/generated/keep.rs".into(), true),
+        ]
+    );
+}
+
+#[test]
+fn removal_wins_when_a_path_is_in_both_lists() {
+    let mut explicit = HashMap::from([(p("docs/a.md"), true)]);
+    apply_selection_diff(&mut explicit, &[p("docs")], &[p("docs")]);
+    assert_eq!(sorted(&explicit), [("docs".into(), false)]);
+}
+
+#[test]
+fn select_only_replaces_everything() {
+    let mut explicit = HashMap::from([(p(""), true), (p("old.rs"), false)]);
+    select_only(&mut explicit, [p("a.rs"), p("b.rs")]);
+    assert_eq!(
+        sorted(&explicit),
+        [("a.rs".into(), true), ("b.rs".into(), true)]
+    );
+}
@@ -0,0 +1,72 @@
+use std::fs;
+
+use stitch::core::{
+    ProfileSelection, add_snapshot, delete_snapshot, load_snapshots, snapshots_file,
+};
+use tempfile::TempDir;
+
+fn sel(path: &str, state: bool) -> ProfileSelection {
+    ProfileSelection {
+        path: path.to_string(),
+        state,
+    }
+}
+
+#[test]
+fn no_snapshots_by_default() {
+    let tmp = TempDir::new().unwrap();
+    assert!(load_snapshots(tmp.path()).is_empty());
+}
+
+#[test]
+fn snapshots_round_trip_in_chronological_order() {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path();
+    let first = add_snapshot(root, "baseline", "2026-10-15 09:00", vec![sel("", true)]).unwrap();
+    let second = add_snapshot(
+        root,
+        "  only ui  ",
+        "2026-10-15 09:30",
+        vec![sel("src/ui", true), sel("src/core", false)],
+    )
+    .unwrap();
+
+    assert!(second.id > first.id);
+    assert_eq!(second.label, "only ui");
+    assert_eq!(second.display(), "only ui (2026-10-15 09:30)");
+    assert_eq!(load_snapshots(root), vec![first, second]);
+    assert!(snapshots_file(root).starts_with(root.join(".stitchworkspace/local")));
+}
+
+#[test]
+fn blank_labels_are_numbered() {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path();
+    add_snapshot(root, "", "t1", Vec::new()).unwrap();
+    let second = add_snapshot(root, "   ", "t2", Vec::new()).unwrap();
+    assert_eq!(second.label, "Snapshot 2");
+}
+
+#[test]
+fn deleting_keeps_ids_increasing() {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path();
+    let a = add_snapshot(root, "a", "t1", Vec::new()).unwrap();
+    let b = add_snapshot(root, "b", "t2", Vec::new()).unwrap();
+    delete_snapshot(root, b.id).unwrap();
+    delete_snapshot(root, 999).unwrap();
+
+    let c = add_snapshot(root, "c", "t3", Vec::new()).unwrap();
+    let labels: Vec<String> = load_snapshots(root).into_iter().map(|s| s.label).collect();
+    assert_eq!(labels, vec!["a", "c"]);
+    assert!(c.id > a.id);
+}
+
+#[test]
+fn corrupt_file_yields_no_snapshots() {
+    let tmp = TempDir::new().unwrap();
+    let path = snapshots_file(tmp.path());
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(&path, "[not json").unwrap();
+    assert!(load_snapshots(tmp.path()).is_empty());
+}
@@ -0,0 +1,75 @@
+use pretty_assertions::assert_eq;
+
+use stitch::core::{NO_EXTENSION, ROOT_DIRECTORY, SelectionStats, StatGroup, share_percent};
+
+fn group(key: &str, files: usize, chars: usize, tokens: usize) -> StatGroup {
+    StatGroup {
+        key: key.into(),
+        files,
+        chars,
+        tokens,
+    }
+}
+
+fn sample() -> SelectionStats {
+    let mut stats = SelectionStats::default();
+    stats.add_file("src/main.rs", 400, 100);
+    stats.add_file("src/lib.RS", 200, 50);
+    stats.add_file("tests/it.rs", 600, 200);
+    stats.add_file("README.md", 150, 40);
+    stats.add_file("Makefile", 40, 10);
+    stats
+}
+
+#[test]
+fn groups_by_lowercase_extension_largest_first() {
+    assert_eq!(
+        sample().by_extension(),
+        vec![
+            group(".rs", 3, 1200, 350),
+            group(".md", 1, 150, 40),
+            group(NO_EXTENSION, 1, 40, 10),
+        ]
+    );
+}
+
+#[test]
+fn groups_by_top_level_directory() {
+    assert_eq!(
+        sample().by_directory(),
+        vec![
+            group("tests/", 1, 600, 200),
+            group("src/", 2, 600, 150),
+            group(ROOT_DIRECTORY, 2, 190, 50),
+        ]
+    );
+}
+
+#[test]
+fn dotfiles_have_no_extension() {
+    let mut stats = SelectionStats::default();
+    stats.add_file(".env", 10, 3);
+    assert_eq!(stats.by_extension()[0].key, NO_EXTENSION);
+}
+
+#[test]
+fn totals_and_shares() {
+    let stats = sample();
+    assert_eq!(stats.total_tokens(), 400);
+    assert_eq!(stats.total_chars(), 1390);
+    assert_eq!(share_percent(200, 400), "50.0%");
+    assert_eq!(share_percent(1, 3), "33.3%");
+    assert_eq!(share_percent(2, 3), "66.7%");
+    assert_eq!(share_percent(5, 0), "0.0%");
+}
+
+#[test]
+fn render_lists_both_tables() {
+    let text = sample().render();
+    let lines: Vec<&str> = text.lines().collect();
+    assert!(lines[0].starts_with("Extension"));
+    assert!(lines[1].starts_with(".rs ") && lines[1].contains("87.5%"));
+    assert!(text.contains("\nDirectory"));
+    assert!(text.contains("tests/ ") && text.contains("50.0%"));
+    assert_eq!(SelectionStats::default().render(), "");
+}
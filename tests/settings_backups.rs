@@ -0,0 +1,157 @@
+use std::fs;
+
+use pretty_assertions::assert_eq;
+use stitch::core::{
+    LocalSettings, Profile, ProfileScope, WorkspaceSettings, backups_dir, list_backups,
+    load_workspace, profile_path, restore_backup, restore_latest_backup, save_local_settings,
+    save_profile, save_workspace, workspace_file, write_with_backup,
+};
+use tempfile::TempDir;
+
+fn settings(ext: &str) -> WorkspaceSettings {
+    WorkspaceSettings {
+        ext_filter: ext.into(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn first_save_takes_no_backup_and_later_saves_do() {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path();
+    let wf = workspace_file(root);
+
+    save_workspace(root, &settings(".rs")).unwrap();
+    assert!(list_backups(root, &wf).is_empty());
+
+    save_workspace(root, &settings(".rs")).unwrap();
+    assert!(list_backups(root, &wf).is_empty(), "unchanged contents");
+
+    save_workspace(root, &settings(".toml")).unwrap();
+    let backups = list_backups(root, &wf);
+    assert_eq!(backups.len(), 1);
+    assert!(backups[0].path.starts_with(backups_dir(root)));
+    let old: WorkspaceSettings =
+        serde_json::from_slice(&fs::read(&backups[0].path).unwrap()).unwrap();
+    assert_eq!(old.ext_filter, ".rs");
+    assert_eq!(load_workspace(root).unwrap().ext_filter, ".toml");
+
+    // The folder keeps itself out of git.
+    assert_eq!(
+        fs::read_to_string(backups_dir(root).join(".gitignore")).unwrap(),
+        "*\n"
+    );
+}
+
+#[test]
+fn oldest_backups_are_rotated_out() {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path();
+    save_local_settings(
+        root,
+        &LocalSettings {
+            backup_count: Some(2),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    for ext in [".a", ".b", ".c", ".d"] {
+        save_workspace(root, &settings(ext)).unwrap();
+    }
+    let backups = list_backups(root, &workspace_file(root));
+    let kept: Vec<String> = backups
+        .iter()
+        .map(|b| {
+            serde_json::from_slice::<WorkspaceSettings>(&fs::read(&b.path).unwrap())
+                .unwrap()
+                .ext_filter
+        })
+        .collect();
+    assert_eq!(kept, vec![".c", ".b"], "newest first");
+}
+
+#[test]
+fn profiles_are_backed_up_per_scope_and_name() {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path();
+    let mut profile = Profile {
+        name: "api".into(),
+        ..Default::default()
+    };
+    save_profile(root, &profile, ProfileScope::Local).unwrap();
+    profile.pinned = vec!["README.md".into()];
+    save_profile(root, &profile, ProfileScope::Local).unwrap();
+
+    let local = profile_path(root, ProfileScope::Local, "api");
+    let shared = profile_path(root, ProfileScope::Shared, "api");
+    assert_eq!(list_backups(root, &local).len(), 1);
+    assert!(list_backups(root, &shared).is_empty());
+    assert!(
+        list_backups(root, &local)[0]
+            .path
+            .starts_with(backups_dir(root).join("local").join("profiles"))
+    );
+}
+
+#[test]
+fn corrupt_file_is_restored_from_the_newest_valid_backup() {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path();
+    let wf = workspace_file(root);
+    save_workspace(root, &settings(".rs")).unwrap();
+    save_workspace(root, &settings(".md")).unwrap();
+    // A backup that is itself broken is skipped.
+    write_with_backup(root, &wf, b"{ broken", 5).unwrap();
+    fs::write(&wf, "{ also broken").unwrap();
+    assert!(load_workspace(root).is_none());
+
+    let restored = restore_latest_backup(root, &wf, 5).unwrap().unwrap();
+    assert_eq!(load_workspace(root).unwrap().ext_filter, ".md");
+
+    // The damaged contents were kept, so the restore can be undone.
+    let newest = &list_backups(root, &wf)[0];
+    assert_eq!(fs::read_to_string(&newest.path).unwrap(), "{ also broken");
+    assert_ne!(newest.id, restored.id);
+}
+
+#[test]
+fn invalid_backups_and_foreign_files_are_rejected() {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path();
+    let wf = workspace_file(root);
+    save_workspace(root, &settings(".rs")).unwrap();
+    write_with_backup(root, &wf, b"{ broken", 5).unwrap();
+    fs::write(&wf, "{}").unwrap();
+
+    // The only backup is the `.rs` settings that `{ broken` replaced...
+    let backup = list_backups(root, &wf).remove(0);
+    restore_backup(root, &backup, 5).unwrap();
+    assert_eq!(load_workspace(root).unwrap().ext_filter, ".rs");
+
+    // ...while a broken one is refused without touching the file.
+    fs::write(&backup.path, "nope").unwrap();
+    let err = restore_backup(root, &backup, 5).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    assert_eq!(load_workspace(root).unwrap().ext_filter, ".rs");
+
+    let outside = root.join("notes.json");
+    write_with_backup(root, &outside, b"{}", 5).expect("no backup needed for a new file");
+    let err = write_with_backup(root, &outside, b"[]", 5).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+}
+
+#[test]
+fn zero_backup_count_turns_backups_off() {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path();
+    let profile = Profile {
+        name: "x".into(),
+        ..Default::default()
+    };
+    save_profile(root, &profile, ProfileScope::Shared).unwrap();
+    let path = profile_path(root, ProfileScope::Shared, "x");
+    write_with_backup(root, &path, b"{}", 0).unwrap();
+    assert!(list_backups(root, &path).is_empty());
+    assert_eq!(fs::read_to_string(&path).unwrap(), "{}");
+}
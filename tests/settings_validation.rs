@@ -0,0 +1,179 @@
+use stitch::core::{
+    FieldError, NameMatching, PathRewrite, SettingsField, WorkspaceSettings, field_error_message,
+    find_filter_overlaps, normalize_filter_settings, validate_workspace_settings,
+};
+
+fn fields(errors: &[FieldError]) -> Vec<SettingsField> {
+    errors.iter().map(|e| e.field).collect()
+}
+
+#[test]
+fn defaults_are_valid() {
+    assert!(validate_workspace_settings(&WorkspaceSettings::default()).is_empty());
+}
+
+#[test]
+fn typical_settings_are_valid() {
+    let ws = WorkspaceSettings {
+        ext_filter: ".rs, .toml,md".into(),
+        exclude_dirs: "target,node_modules,.git".into(),
+        exclude_files: "LICENSE,Cargo.lock".into(),
+        remove_regex: r#""""(?s)/\*.*?\*/""""#.into(),
+        path_rewrite: PathRewrite {
+            strip_prefix: "packages/foo/".into(),
+            prepend: "app".into(),
+        },
+        ..Default::default()
+    };
+    assert_eq!(validate_workspace_settings(&ws), vec![]);
+}
+
+#[test]
+fn malformed_extensions_are_reported() {
+    let ws = WorkspaceSettings {
+        ext_filter: ".rs, -, src/*.rs, .t xt".into(),
+        ..Default::default()
+    };
+    let errors = validate_workspace_settings(&ws);
+    assert_eq!(fields(&errors), vec![SettingsField::ExtFilter; 3]);
+    let msg = field_error_message(&errors, SettingsField::ExtFilter);
+    assert!(msg.contains("'-' has no extension"), "{msg}");
+    assert!(msg.contains("'src/*.rs' is not an extension"), "{msg}");
+    assert!(msg.contains("'.t xt'"), "{msg}");
+}
+
+#[test]
+fn mixing_includes_and_excludes_is_flagged() {
+    let ws = WorkspaceSettings {
+        ext_filter: ".rs,-.lock".into(),
+        ..Default::default()
+    };
+    let errors = validate_workspace_settings(&ws);
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].message.contains("ignored"));
+}
+
+#[test]
+fn exclusion_lists_reject_paths_and_wildcards() {
+    let ws = WorkspaceSettings {
+        exclude_dirs: "target, src/generated".into(),
+        exclude_files: "*.lock".into(),
+        ..Default::default()
+    };
+    let errors = validate_workspace_settings(&ws);
+    assert_eq!(
+        fields(&errors),
+        vec![SettingsField::ExcludeDirs, SettingsField::ExcludeFiles]
+    );
+    assert!(errors[1].message.contains("wildcards"));
+}
+
+#[test]
+fn bad_regex_and_paths_are_reported() {
+    let ws = WorkspaceSettings {
+        remove_regex: "(unclosed".into(),
+        path_rewrite: PathRewrite {
+            strip_prefix: r"packages\foo".into(),
+            prepend: "../up".into(),
+        },
+        ..Default::default()
+    };
+    let errors = validate_workspace_settings(&ws);
+    assert_eq!(
+        fields(&errors),
+        vec![
+            SettingsField::RemoveRegex,
+            SettingsField::PathStripPrefix,
+            SettingsField::PathPrepend,
+        ]
+    );
+    assert!(errors[0].message.starts_with("invalid regex"));
+    assert!(field_error_message(&errors, SettingsField::ExtFilter).is_empty());
+}
+
+#[test]
+fn repeated_and_conflicting_entries_are_reported() {
+    let ws = WorkspaceSettings {
+        ext_filter: ".rs, RS, .toml, -.rs".into(),
+        exclude_dirs: "target, node_modules, target, target".into(),
+        exclude_files: "LICENSE, notes.txt, Target".into(),
+        name_matching: NameMatching::Exact,
+        ..Default::default()
+    };
+    let overlaps = find_filter_overlaps(&ws);
+    assert_eq!(
+        field_error_message(&overlaps, SettingsField::ExtFilter),
+        "'RS' repeats '.rs'; '-.rs' conflicts with '.rs' (the inclusion wins)"
+    );
+    assert_eq!(
+        field_error_message(&overlaps, SettingsField::ExcludeDirs),
+        "'target' is listed twice"
+    );
+    // Only .rs/.toml files are included, so these names can never show up.
+    assert_eq!(
+        field_error_message(&overlaps, SettingsField::ExcludeFiles),
+        "'LICENSE' is already left out by the extension filter; \
+         'notes.txt' is already left out by the extension filter; \
+         'Target' is already left out by the extension filter"
+    );
+}
+
+#[test]
+fn file_names_covered_by_extension_exclusions_are_reported() {
+    let ws = WorkspaceSettings {
+        ext_filter: "-.lock".into(),
+        exclude_files: "Cargo.lock, LICENSE".into(),
+        ..Default::default()
+    };
+    let overlaps = find_filter_overlaps(&ws);
+    assert_eq!(
+        overlaps,
+        vec![FieldError {
+            field: SettingsField::ExcludeFiles,
+            message: "'Cargo.lock' is already left out by the extension filter".into(),
+        }]
+    );
+    assert!(find_filter_overlaps(&WorkspaceSettings::default()).is_empty());
+}
+
+#[test]
+fn normalizing_drops_repeats_and_keeps_first_spelling() {
+    let mut ws = WorkspaceSettings {
+        ext_filter: ".rs,RS, .toml,-.rs".into(),
+        exclude_dirs: "target,node_modules,target".into(),
+        exclude_files: "Cargo.lock,Cargo.lock,cargo.lock".into(),
+        name_matching: NameMatching::Exact,
+        ..Default::default()
+    };
+    assert!(normalize_filter_settings(&mut ws));
+    assert_eq!(ws.ext_filter, ".rs, .toml");
+    assert_eq!(ws.exclude_dirs, "target, node_modules");
+    // Names match exactly, so both spellings stay.
+    assert_eq!(ws.exclude_files, "Cargo.lock, cargo.lock");
+
+    let before = ws.clone();
+    assert!(!normalize_filter_settings(&mut ws));
+    assert_eq!(ws, before);
+}
+
+#[test]
+fn folded_name_matching_treats_spellings_as_repeats() {
+    let mut ws = WorkspaceSettings {
+        exclude_dirs: "Build, build, BUILD".into(),
+        exclude_files: "Résumé.md, resume.md".into(),
+        name_matching: NameMatching::Insensitive,
+        ..Default::default()
+    };
+    let overlaps = find_filter_overlaps(&ws);
+    assert_eq!(
+        field_error_message(&overlaps, SettingsField::ExcludeDirs),
+        "'build' repeats 'Build'; 'BUILD' repeats 'Build'"
+    );
+    assert_eq!(
+        field_error_message(&overlaps, SettingsField::ExcludeFiles),
+        "'resume.md' repeats 'Résumé.md'"
+    );
+    assert!(normalize_filter_settings(&mut ws));
+    assert_eq!(ws.exclude_dirs, "Build");
+    assert_eq!(ws.exclude_files, "Résumé.md");
+}
@@ -0,0 +1,68 @@
+use pretty_assertions::assert_eq;
+
+use stitch::core::{
+    TransformOptions, TransformPipeline, prefixes_for_file, script_comment_prefix,
+    strip_lines_and_inline_comments,
+};
+
+#[test]
+fn shebang_line_survives_a_hash_prefix() {
+    let src = "#!/usr/bin/env bash\n# setup\necho hi # greet\n";
+    assert_eq!(
+        strip_lines_and_inline_comments(src, &["#".into()]),
+        "#!/usr/bin/env bash\necho hi\n"
+    );
+    // Only the first line is a shebang.
+    assert_eq!(
+        strip_lines_and_inline_comments("echo\n#!not a shebang\n", &["#".into()]),
+        "echo\n"
+    );
+}
+
+#[test]
+fn script_prefix_by_extension_or_shebang() {
+    assert_eq!(script_comment_prefix("tools/build.sh", ""), Some("#"));
+    assert_eq!(script_comment_prefix("app/Main.PY", ""), Some("#"));
+    assert_eq!(script_comment_prefix("Rakefile.rake", ""), Some("#"));
+    assert_eq!(script_comment_prefix("src/lib.rs", "#!/bin/sh\n"), None);
+    assert_eq!(
+        script_comment_prefix("bin/deploy", "#!/usr/bin/env -S python3 -u\n"),
+        Some("#")
+    );
+    assert_eq!(script_comment_prefix("bin/run", "#!/bin/bash\n"), Some("#"));
+    assert_eq!(
+        script_comment_prefix("bin/node-tool", "#!/usr/bin/env node\n"),
+        None
+    );
+    assert_eq!(script_comment_prefix(".bashrc", "alias x=y\n"), None);
+}
+
+#[test]
+fn script_prefix_joins_configured_prefixes_only() {
+    let slashes = vec!["//".to_string()];
+    assert_eq!(
+        prefixes_for_file("a.py", "", &slashes).as_ref(),
+        ["//".to_string(), "#".to_string()]
+    );
+    assert_eq!(prefixes_for_file("a.rs", "", &slashes).as_ref(), slashes);
+    assert!(prefixes_for_file("a.py", "", &[]).is_empty());
+    let both = vec!["#".to_string(), "//".to_string()];
+    assert_eq!(prefixes_for_file("a.rb", "", &both).as_ref(), both);
+}
+
+#[test]
+fn strip_prefixes_step_uses_script_defaults() {
+    let opts = TransformOptions {
+        remove_prefixes: vec!["//".into()],
+        ..TransformOptions::default()
+    };
+    let pipeline = TransformPipeline::default();
+    let py = pipeline.apply(
+        "scripts/job.py",
+        "#!/usr/bin/env python3\n# doc\nx = '#keep'  # note\n".into(),
+        &opts,
+    );
+    assert_eq!(py.contents, "#!/usr/bin/env python3\nx = '#keep'\n");
+    let rs = pipeline.apply("src/lib.rs", "// c\nlet x = 1; # y\n".into(), &opts);
+    assert_eq!(rs.contents, "let x = 1; # y\n");
+}
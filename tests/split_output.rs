@@ -0,0 +1,80 @@
+use pretty_assertions::assert_eq;
+
+use stitch::core::{
+    OutputLayout, OutputParts, OutputSection, SectionLayout, SplitSource,
+    split_output_by_top_level_dir,
+};
+
+fn source(paths: &[&str]) -> SplitSource {
+    let mut source = SplitSource {
+        parts: OutputParts {
+            hierarchy: Some("proj/\n".to_string()),
+            notes: Some("note\n".to_string()),
+            ..OutputParts::default()
+        },
+        blocks: Vec::new(),
+    };
+    for path in paths {
+        source.add_block(path, &format!("[{path}]\n"));
+    }
+    source
+}
+
+#[test]
+fn one_part_per_top_level_directory_with_shared_sections() {
+    let splits = split_output_by_top_level_dir(
+        &OutputLayout::default(),
+        &source(&[
+            "frontend/app.ts",
+            "backend/main.rs",
+            "README.md",
+            "frontend/ui/x.ts",
+        ]),
+    );
+    let names: Vec<&str> = splits.iter().map(|s| s.file_name.as_str()).collect();
+    assert_eq!(names, ["backend.md", "frontend.md", "root.md"]);
+    assert_eq!(splits[2].directory, None);
+
+    assert_eq!(splits[1].directory.as_deref(), Some("frontend/"));
+    assert_eq!(
+        splits[1].text,
+        "=== PART 2/3: frontend/ ===\n\n\
+         === FILE HIERARCHY ===\n\nproj/\n\n\
+         === NOTES ===\n\nnote\n\n\
+         === FILE CONTENTS ===\n\n[frontend/app.ts]\n[frontend/ui/x.ts]\n"
+    );
+    assert!(splits[2].text.starts_with("=== PART 3/3: (root) ===\n"));
+    assert!(splits[2].text.ends_with("[README.md]\n"));
+}
+
+#[test]
+fn root_part_name_avoids_a_root_directory() {
+    let splits =
+        split_output_by_top_level_dir(&OutputLayout::default(), &source(&["Root/a.rs", "b.rs"]));
+    let names: Vec<&str> = splits.iter().map(|s| s.file_name.as_str()).collect();
+    assert_eq!(names, ["Root.md", "root-2.md"]);
+}
+
+#[test]
+fn parts_follow_the_layout_and_empty_sources_give_nothing() {
+    let layout = OutputLayout {
+        sections: vec![
+            SectionLayout::new(OutputSection::Contents),
+            SectionLayout {
+                enabled: false,
+                ..SectionLayout::new(OutputSection::Notes)
+            },
+        ],
+        provenance: None,
+        modified_times: false,
+        checksum_footer: false,
+    };
+    let splits = split_output_by_top_level_dir(&layout, &source(&["src/lib.rs"]));
+    assert_eq!(
+        splits[0].text,
+        "=== PART 1/1: src/ ===\n\n\
+         === FILE CONTENTS ===\n\n[src/lib.rs]\n\n\
+         === FILE HIERARCHY ===\n\nproj/\n"
+    );
+    assert!(split_output_by_top_level_dir(&layout, &source(&[])).is_empty());
+}
@@ -0,0 +1,133 @@
+use pretty_assertions::assert_eq;
+use std::fmt::Write;
+use std::path::Path;
+use stitch::core::{
+    SQL_COLLAPSE_MIN_ROWS, SqlFilterOptions, TransformOptions, TransformPipeline,
+    apply_sql_filters, is_sql_file_path,
+};
+
+const COMMENTS: SqlFilterOptions = SqlFilterOptions {
+    remove_comments: true,
+    collapse_data: false,
+};
+const DATA: SqlFilterOptions = SqlFilterOptions {
+    remove_comments: false,
+    collapse_data: true,
+};
+
+fn single_row_inserts(count: usize) -> String {
+    let mut out = String::new();
+    for i in 0..count {
+        let _ = writeln!(
+            out,
+            "INSERT INTO users (id, name) VALUES ({i}, 'user; {i}');"
+        );
+    }
+    out
+}
+
+#[test]
+fn detects_sql_paths() {
+    assert!(is_sql_file_path(Path::new("db/schema.sql")));
+    assert!(is_sql_file_path(Path::new("DUMP.SQL")));
+    assert!(!is_sql_file_path(Path::new("db/schema.sqlite")));
+}
+
+#[test]
+fn removes_comments_outside_literals() {
+    let src = concat!(
+        "-- Schema\n",
+        "/* generated\n   by hand */\n",
+        "CREATE TABLE t (\n",
+        "  id int, -- key\n",
+        "  note text DEFAULT '-- not a comment'\n",
+        ");\n",
+        "CREATE FUNCTION f() RETURNS int AS $$ SELECT 1 -- kept\n$$ LANGUAGE sql;\n",
+    );
+    assert_eq!(
+        apply_sql_filters(src, &COMMENTS),
+        concat!(
+            "CREATE TABLE t (\n",
+            "  id int,\n",
+            "  note text DEFAULT '-- not a comment'\n",
+            ");\n",
+            "CREATE FUNCTION f() RETURNS int AS $$ SELECT 1 -- kept\n$$ LANGUAGE sql;\n",
+        )
+    );
+}
+
+#[test]
+fn collapses_runs_of_inserts_into_the_same_table() {
+    let src = format!(
+        "CREATE TABLE users (id int, name text);\n\n{}\nINSERT INTO roles (id) VALUES (1);\n",
+        single_row_inserts(12)
+    );
+    assert_eq!(
+        apply_sql_filters(&src, &DATA),
+        concat!(
+            "CREATE TABLE users (id int, name text);\n",
+            "\n",
+            "-- INSERT INTO users (id, name): 12 rows omitted\n",
+            "\n",
+            "INSERT INTO roles (id) VALUES (1);\n",
+        )
+    );
+}
+
+#[test]
+fn counts_rows_of_multi_row_inserts() {
+    let rows: Vec<String> = (0..SQL_COLLAPSE_MIN_ROWS)
+        .map(|i| format!("({i}, 'a (b)')"))
+        .collect();
+    let src = format!(
+        "insert into t values\n  {}\nON CONFLICT (id) DO NOTHING;\n",
+        rows.join(",\n  ")
+    );
+    assert_eq!(
+        apply_sql_filters(&src, &DATA),
+        format!("-- insert into t: {SQL_COLLAPSE_MIN_ROWS} rows omitted\n")
+    );
+}
+
+#[test]
+fn keeps_small_data_blocks() {
+    let src = single_row_inserts(SQL_COLLAPSE_MIN_ROWS - 1);
+    assert_eq!(apply_sql_filters(&src, &DATA), src);
+}
+
+#[test]
+fn collapses_copy_data() {
+    let mut data = String::new();
+    for i in 0..25 {
+        let _ = writeln!(data, "{i}\tname {i}");
+    }
+    let src = format!(
+        "COPY public.users (id, name) FROM stdin;\n{data}\\.\n\nALTER TABLE users ADD PRIMARY KEY (id);\n"
+    );
+    assert_eq!(
+        apply_sql_filters(&src, &DATA),
+        concat!(
+            "-- COPY public.users (id, name) FROM stdin: 25 rows omitted\n",
+            "\n",
+            "ALTER TABLE users ADD PRIMARY KEY (id);\n",
+        )
+    );
+}
+
+#[test]
+fn default_pipeline_runs_the_filters_on_sql_files_only() {
+    let opts = TransformOptions {
+        sql: COMMENTS,
+        ..TransformOptions::default()
+    };
+    let src = "SELECT 1; -- one\n";
+    let pipeline = TransformPipeline::default();
+    assert_eq!(
+        pipeline.apply("q.sql", src.to_string(), &opts).contents,
+        "SELECT 1;\n"
+    );
+    assert_eq!(
+        pipeline.apply("q.txt", src.to_string(), &opts).contents,
+        src
+    );
+}
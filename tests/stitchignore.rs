@@ -0,0 +1,112 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+use stitch::core::{
+    IgnoreRules, STITCHIGNORE_FILE, gather_paths_set, path_to_unix, scan_dir_to_node_with_stats,
+};
+use tempfile::TempDir;
+
+fn scanned_paths(root: &Path) -> (Vec<String>, usize) {
+    let none = HashSet::new();
+    let scan = scan_dir_to_node_with_stats(root, &none, &none, &none, &none);
+    let mut paths: Vec<String> = gather_paths_set(&scan.node)
+        .iter()
+        .filter_map(|p| p.strip_prefix(root).ok().map(path_to_unix))
+        .filter(|p| !p.is_empty())
+        .collect();
+    paths.sort();
+    (paths, scan.stats.stitchignored)
+}
+
+#[test]
+fn basename_patterns_match_at_any_depth() {
+    let rules = IgnoreRules::parse("# comment\n\n*.log\nbuild/\n");
+    assert!(rules.matches("app.log", false));
+    assert!(rules.matches("a/b/app.log", false));
+    assert!(rules.matches("web/build", true));
+    // Trailing slash = directories only.
+    assert!(!rules.matches("web/build", false));
+    assert!(!rules.matches("src/main.rs", false));
+}
+
+#[test]
+fn slashes_anchor_patterns_to_the_root() {
+    let rules = IgnoreRules::parse("/TODO.md\ndocs/*.pdf\n");
+    assert!(rules.matches("TODO.md", false));
+    assert!(!rules.matches("sub/TODO.md", false));
+    assert!(rules.matches("docs/a.pdf", false));
+    assert!(!rules.matches("docs/deep/a.pdf", false));
+    assert!(!rules.matches("x/docs/a.pdf", false));
+}
+
+#[test]
+fn double_star_and_character_classes() {
+    let rules = IgnoreRules::parse("**/fixtures/**\nsrc/**/gen_*.rs\nv[0-9].txt\nlog[!s]\n");
+    assert!(rules.matches("fixtures/a.json", false));
+    assert!(rules.matches("tests/fixtures/deep/a.json", false));
+    assert!(rules.matches("src/gen_a.rs", false));
+    assert!(rules.matches("src/a/b/gen_b.rs", false));
+    assert!(rules.matches("v1.txt", false));
+    assert!(!rules.matches("vx.txt", false));
+    assert!(rules.matches("logx", false));
+    assert!(!rules.matches("logs", false));
+}
+
+#[test]
+fn later_negation_re_includes_but_not_below_an_ignored_directory() {
+    let rules = IgnoreRules::parse("*.log\n!keep.log\nout/\n!out/keep.txt\n");
+    assert!(rules.matches("a.log", false));
+    assert!(!rules.matches("keep.log", false));
+    assert!(!rules.is_ignored("sub/keep.log", false));
+    assert!(rules.is_ignored("out/keep.txt", false));
+    assert!(rules.is_ignored("out/other.rs", false));
+}
+
+#[test]
+fn escapes_are_literal() {
+    let rules = IgnoreRules::parse("\\#notes\n\\!important\nfile\\ \n");
+    assert!(rules.matches("#notes", false));
+    assert!(rules.matches("!important", false));
+    assert!(rules.matches("file ", false));
+    assert!(!rules.matches("file", false));
+}
+
+#[test]
+fn scan_applies_stitchignore_and_counts_skipped_entries() {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path();
+    for dir in ["src", "target/debug", "tests/data"] {
+        fs::create_dir_all(root.join(dir)).unwrap();
+    }
+    for file in [
+        "src/main.rs",
+        "src/big.snap",
+        "tests/data/a.bin",
+        "tests/t.rs",
+    ] {
+        fs::write(root.join(file), "x").unwrap();
+    }
+    fs::write(
+        root.join(STITCHIGNORE_FILE),
+        "target/\n*.snap\n/tests/data\n",
+    )
+    .unwrap();
+
+    let (paths, skipped) = scanned_paths(root);
+    assert_eq!(
+        paths,
+        vec![".stitchignore", "src", "src/main.rs", "tests", "tests/t.rs"]
+    );
+    assert_eq!(skipped, 3);
+}
+
+#[test]
+fn scan_without_stitchignore_keeps_everything() {
+    let tmp = TempDir::new().unwrap();
+    fs::write(tmp.path().join("a.log"), "x").unwrap();
+    let (paths, skipped) = scanned_paths(tmp.path());
+    assert_eq!(paths, vec!["a.log"]);
+    assert_eq!(skipped, 0);
+    assert!(IgnoreRules::load(tmp.path()).is_empty());
+}
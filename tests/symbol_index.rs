@@ -0,0 +1,121 @@
+use std::{fs, path::PathBuf};
+
+use pretty_assertions::assert_eq;
+
+use stitch::core::{SymbolIndex, SymbolKind, find_definition, symbol_definitions};
+
+fn names(text: &str) -> Vec<(String, SymbolKind)> {
+    symbol_definitions(text)
+        .into_iter()
+        .map(|d| (d.name, d.kind))
+        .collect()
+}
+
+fn def(name: &str, kind: SymbolKind) -> (String, SymbolKind) {
+    (name.to_string(), kind)
+}
+
+#[test]
+fn rust_definitions_with_visibility_and_qualifiers() {
+    let src = "\
+pub(crate) async fn load() {}
+    pub const unsafe fn raw() {}
+pub struct Config;
+enum Mode { A }
+pub trait Render {}
+type Alias = u8;
+pub static mut COUNTER: u32 = 0;
+const MAX: usize = 3;
+mod net;
+macro_rules! log { () => {} }
+    let local = 1;
+";
+    assert_eq!(
+        names(src),
+        vec![
+            def("load", SymbolKind::Function),
+            def("raw", SymbolKind::Function),
+            def("Config", SymbolKind::Type),
+            def("Mode", SymbolKind::Type),
+            def("Render", SymbolKind::Type),
+            def("Alias", SymbolKind::Type),
+            def("COUNTER", SymbolKind::Constant),
+            def("MAX", SymbolKind::Constant),
+            def("net", SymbolKind::Module),
+            def("log", SymbolKind::Macro),
+        ]
+    );
+}
+
+#[test]
+fn python_javascript_go_and_slint_definitions() {
+    let src = "\
+async def fetch(url):
+class Client:
+export default async function main() {}
+export interface Props {}
+export const API_URL = 'x';
+const limit: number = 3;
+func (s *Server) Serve(addr string) error {
+type Server struct {
+export component AppWindow inherits Window {
+";
+    assert_eq!(
+        names(src),
+        vec![
+            def("fetch", SymbolKind::Function),
+            def("Client", SymbolKind::Type),
+            def("main", SymbolKind::Function),
+            def("Props", SymbolKind::Type),
+            def("API_URL", SymbolKind::Constant),
+            def("limit", SymbolKind::Constant),
+            def("Serve", SymbolKind::Function),
+            def("Server", SymbolKind::Type),
+            def("AppWindow", SymbolKind::Type),
+        ]
+    );
+}
+
+#[test]
+fn find_definition_returns_byte_range_and_line() {
+    let text = "=== a.rs ===\nfn helper() {}\n// ✓ note\npub fn Parse() {}\n";
+    let found = find_definition(text, "helper").unwrap();
+    assert_eq!(found.line, 1);
+    assert_eq!(&text[found.start..found.end], "helper");
+
+    // Exact case wins; otherwise case is ignored.
+    let found = find_definition(text, "parse").unwrap();
+    assert_eq!(found.line, 3);
+    assert_eq!(&text[found.start..found.end], "Parse");
+
+    assert!(find_definition(text, "missing").is_none());
+    assert!(find_definition(text, "  ").is_none());
+}
+
+#[test]
+fn index_maps_symbols_to_project_files() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    let write = |rel: &str, text: &str| -> PathBuf {
+        let path = root.join(rel);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, text).unwrap();
+        path
+    };
+    let files = vec![
+        write("src/b.rs", "pub fn run() {}\n"),
+        write("src/a.rs", "struct Job;\n\nfn run() {}\n"),
+        write("tools/x.py", "def run():\n    pass\n"),
+        root.join("missing.rs"),
+    ];
+
+    let index = SymbolIndex::build(root, &files);
+    assert_eq!(
+        index.files_defining("run"),
+        ["src/a.rs", "src/b.rs", "tools/x.py"]
+    );
+    assert_eq!(index.find("Job")[0].path, "src/a.rs");
+    assert_eq!(index.find("run")[1].line, 2);
+    assert!(index.find("nothing").is_empty());
+    assert_eq!(index.len(), 2);
+}
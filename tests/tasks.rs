@@ -0,0 +1,163 @@
+use std::fs;
+
+use pretty_assertions::assert_eq;
+use stitch::core::{
+    Profile, ProfileScope, ProfileSelection, Task, TaskFormat, WorkspaceSettings, load_tasks,
+    parse_tasks, run_task, save_profile,
+};
+use stitch::testing::write_file;
+use tempfile::TempDir;
+
+fn project(tasks: &str) -> TempDir {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path();
+    write_file(root, "src/main.rs", "fn main() {}\n").unwrap();
+    write_file(root, "docs/guide.md", "# Guide\n").unwrap();
+    write_file(root, ".stitchworkspace/tasks.toml", tasks).unwrap();
+    tmp
+}
+
+#[test]
+fn parses_tasks_by_name() {
+    let tasks = parse_tasks(
+        r#"
+[tasks.review]
+profile = "backend"
+output = "bundles/review.md.gz"
+format = "gzip"
+post_process = "sed s/secret/***/"
+
+[tasks.quick]
+"#,
+    )
+    .unwrap();
+    assert_eq!(tasks.keys().collect::<Vec<_>>(), ["quick", "review"]);
+    assert_eq!(tasks["quick"], Task::default());
+    assert_eq!(
+        tasks["review"],
+        Task {
+            profile: Some("backend".into()),
+            output: Some("bundles/review.md.gz".into()),
+            format: TaskFormat::Gzip,
+            post_process: Some("sed s/secret/***/".into()),
+        }
+    );
+}
+
+#[test]
+fn rejects_unknown_keys_and_formats() {
+    assert!(parse_tasks("[tasks.a]\nprofle = \"x\"\n").is_err());
+    assert!(parse_tasks("[tasks.a]\nformat = \"rar\"\n").is_err());
+}
+
+#[test]
+fn a_project_without_a_tasks_file_has_no_tasks() {
+    let tmp = TempDir::new().unwrap();
+    assert!(load_tasks(tmp.path()).unwrap().is_empty());
+}
+
+#[test]
+fn runs_every_file_without_a_profile() {
+    let tmp = project("[tasks.all]\n");
+    let run = run_task(tmp.path(), "all").unwrap();
+    assert_eq!(run.files, 2);
+    assert_eq!(run.written, None);
+    assert!(run.text.contains("--- Start of file: docs/guide.md ---"));
+    assert!(run.text.contains("--- Start of file: src/main.rs ---"));
+}
+
+#[test]
+fn writes_the_profile_selection_to_the_output_path() {
+    let tmp = project("[tasks.code]\nprofile = \"code\"\noutput = \"out/code.txt\"\n");
+    let root = tmp.path();
+    let profile = Profile {
+        name: "code".into(),
+        settings: WorkspaceSettings {
+            line_numbers: true,
+            ..WorkspaceSettings::default()
+        },
+        explicit: vec![ProfileSelection {
+            path: "src".into(),
+            state: true,
+        }],
+        ..Profile::default()
+    };
+    save_profile(root, &profile, ProfileScope::Shared).unwrap();
+
+    let run = run_task(root, "code").unwrap();
+    assert_eq!(run.files, 1);
+    assert_eq!(run.written, Some(root.join("out/code.txt")));
+    let written = fs::read_to_string(root.join("out/code.txt")).unwrap();
+    assert_eq!(written, run.text);
+    assert!(written.contains("--- Start of file: src/main.rs ---\n1 | fn main() {}\n"));
+    assert!(!written.contains("guide.md"));
+}
+
+#[test]
+fn reports_bad_tasks_in_one_line() {
+    let tmp = project(
+        "[tasks.zip]\nformat = \"zip\"\n\n[tasks.missing]\nprofile = \"nope\"\n\n\
+         [tasks.post]\npost_process = \"cat\"\n",
+    );
+    let root = tmp.path();
+    assert_eq!(
+        run_task(root, "zip").unwrap_err(),
+        "task 'zip': format 'zip' needs an output path"
+    );
+    assert_eq!(
+        run_task(root, "missing").unwrap_err(),
+        "task 'missing': no profile named 'nope' in this project"
+    );
+    assert_eq!(
+        run_task(root, "post").unwrap_err(),
+        "task 'post': post_process needs external commands enabled in the local settings"
+    );
+    assert!(
+        run_task(root, "other")
+            .unwrap_err()
+            .starts_with("no task named 'other' in ")
+    );
+}
+
+#[test]
+fn refuses_output_paths_outside_the_project() {
+    let tmp = project(
+        "[tasks.up]\noutput = \"../escape.md\"\n\n[tasks.meta]\noutput = \".git/bundle.md\"\n",
+    );
+    let root = tmp.path();
+    assert_eq!(
+        run_task(root, "up").unwrap_err(),
+        "task 'up': output: ../escape.md is outside the project"
+    );
+    assert_eq!(
+        run_task(root, "meta").unwrap_err(),
+        "task 'meta': output: .git/bundle.md is inside .git"
+    );
+    assert!(!root.parent().unwrap().join("escape.md").exists());
+}
+
+#[test]
+fn output_matches_generate_with_notes_and_toggles() {
+    let tmp = project("[tasks.tree]\nprofile = \"tree\"\n");
+    let root = tmp.path();
+    let profile = Profile {
+        name: "tree".into(),
+        settings: WorkspaceSettings {
+            exclude_dirs: "docs".into(),
+            hierarchy_only: true,
+            ..WorkspaceSettings::default()
+        },
+        explicit: vec![ProfileSelection {
+            path: String::new(),
+            state: true,
+        }],
+        ..Profile::default()
+    };
+    save_profile(root, &profile, ProfileScope::Shared).unwrap();
+
+    let run = run_task(root, "tree").unwrap();
+    assert_eq!(run.files, 0);
+    assert!(run.text.contains("main.rs"));
+    assert!(run.text.contains("Excluded directories: docs"));
+    assert!(!run.text.contains("--- Start of file:"));
+}
@@ -0,0 +1,88 @@
+use std::collections::HashSet;
+use std::fs;
+
+use proptest::prelude::*;
+use stitch::core::{path_to_unix, scan_dir_to_node};
+use stitch::testing::{FileSpec, FixtureBuilder, TreeShape, file_specs, random_tree};
+
+#[test]
+fn builder_writes_files_and_empty_dirs() {
+    let fx = FixtureBuilder::new()
+        .file("src/main.rs", "fn main() {}")
+        .files(["README.md", "docs/guide.md"])
+        .dir("assets/empty")
+        .build()
+        .unwrap();
+    assert_eq!(
+        fs::read_to_string(fx.path("src/main.rs")).unwrap(),
+        "fn main() {}"
+    );
+    assert_eq!(fs::read_to_string(fx.path("docs/guide.md")).unwrap(), "x");
+    assert!(fx.path("assets/empty").is_dir());
+
+    fx.write("src/lib.rs", "pub mod x;").unwrap();
+    assert!(fx.path("src/lib.rs").is_file());
+}
+
+#[test]
+fn fixture_is_removed_on_drop() {
+    let fx = FixtureBuilder::new().files(["a.txt"]).build().unwrap();
+    let root = fx.root().to_path_buf();
+    drop(fx);
+    assert!(!root.exists());
+}
+
+#[test]
+fn file_spec_paths_and_extensions() {
+    let spec = FileSpec::parse("a/b/Archive.TAR.GZ");
+    assert_eq!(spec.dirs, ["a", "b"]);
+    assert_eq!(spec.fname, "Archive.TAR.GZ");
+    assert_eq!(spec.rel_path(), "a/b/Archive.TAR.GZ");
+    assert_eq!(spec.last_ext(), ".gz");
+    assert_eq!(FileSpec::parse("Makefile").last_ext(), "");
+}
+
+#[test]
+fn random_trees_are_reproducible() {
+    let shape = TreeShape {
+        files: 50,
+        ..TreeShape::default()
+    };
+    let a = random_tree(7, shape);
+    assert_eq!(a, random_tree(7, shape));
+    assert_ne!(a, random_tree(8, shape));
+    assert_eq!(a.len(), 50);
+    assert!(a.iter().all(|f| f.dirs.len() <= shape.max_depth));
+}
+
+#[test]
+fn scanning_a_random_tree_finds_every_file() {
+    let specs = random_tree(42, TreeShape::default());
+    let fx = FixtureBuilder::new().specs(&specs).build().unwrap();
+    let none = HashSet::new();
+    let tree = scan_dir_to_node(fx.root(), &none, &none, &none, &none);
+
+    let mut found = Vec::new();
+    let mut stack = vec![&tree];
+    while let Some(node) = stack.pop() {
+        if node.is_dir {
+            stack.extend(&node.children);
+        } else {
+            found.push(path_to_unix(node.path.strip_prefix(fx.root()).unwrap()));
+        }
+    }
+    found.sort();
+    let expected: Vec<String> = specs.iter().map(FileSpec::rel_path).collect();
+    assert_eq!(found, expected);
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig { cases: 32, .. ProptestConfig::default() })]
+
+    #[test]
+    fn generated_specs_round_trip_through_parse(files in file_specs(1, 10)) {
+        for f in &files {
+            prop_assert_eq!(&FileSpec::parse(&f.rel_path()), f);
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use std::{path::PathBuf, sync::atomic::AtomicBool};
+
+use pretty_assertions::assert_eq;
+use stitch::core::{
+    GenerationRequest, Generator, OutputParts, TextQuirks, WorkspaceSettings, normalize_text,
+};
+use stitch::testing::write_file;
+use tempfile::TempDir;
+
+#[test]
+fn detects_boms_and_each_kind_of_line_ending() {
+    assert_eq!(
+        TextQuirks::detect("a\nb\n"),
+        TextQuirks {
+            lf: true,
+            ..TextQuirks::default()
+        }
+    );
+    let quirks = TextQuirks::detect("\u{feff}a\r\nb\r\n");
+    assert!(quirks.bom && quirks.crlf && !quirks.lf && !quirks.cr);
+    assert!(!quirks.mixed_line_endings());
+    assert_eq!(quirks.describe(), "BOM, CRLF");
+
+    let mixed = TextQuirks::detect("a\r\nb\nc\rd");
+    assert!(mixed.crlf && mixed.lf && mixed.cr);
+    assert_eq!(mixed.describe(), "mixed line endings");
+    assert!(mixed.is_suspicious());
+    // CRLF alone is normalized when asked, but not worth a warning.
+    assert!(!TextQuirks::detect("a\r\n").is_suspicious());
+    assert!(TextQuirks::detect("a\r\n").needs_normalizing());
+}
+
+#[test]
+fn normalizing_strips_the_bom_and_converts_to_lf() {
+    assert_eq!(normalize_text("\u{feff}a\r\nb\rc\n"), "a\nb\nc\n");
+    assert_eq!(normalize_text("plain\n"), "plain\n");
+    // Only a leading BOM is a BOM.
+    assert_eq!(normalize_text("a\u{feff}"), "a\u{feff}");
+}
+
+fn project() -> (TempDir, Vec<PathBuf>) {
+    let tmp = TempDir::new().unwrap();
+    let files = [
+        ("bom.txt", "\u{feff}hello\r\nworld\r\n"),
+        ("mixed.txt", "one\r\ntwo\n"),
+        ("plain.txt", "fine\n"),
+    ];
+    let paths = files
+        .iter()
+        .map(|(name, text)| write_file(tmp.path(), name, text).unwrap())
+        .collect();
+    (tmp, paths)
+}
+
+fn generate(request: GenerationRequest) -> String {
+    Generator::new(request)
+        .run(&AtomicBool::new(false), |_| {})
+        .unwrap()
+        .text
+}
+
+fn request(tmp: &TempDir, files: Vec<PathBuf>, normalize: bool) -> GenerationRequest {
+    let parts = OutputParts {
+        notes: Some(String::new()),
+        ..OutputParts::default()
+    };
+    let mut request = GenerationRequest::new(tmp.path(), files, parts);
+    request.transform_opts.normalize_text = normalize;
+    request
+}
+
+#[test]
+fn notes_warn_about_files_left_as_is() {
+    let (tmp, files) = project();
+    let out = generate(request(&tmp, files, false));
+    assert!(out.contains("\u{feff}hello\r\n"));
+    assert!(out.contains(
+        "Files with a BOM or mixed line endings, emitted as is (2; set \"normalize_text\": true to fix):\n\
+         - bom.txt: BOM, CRLF\n\
+         - mixed.txt: mixed line endings\n"
+    ));
+}
+
+#[test]
+fn normalized_files_are_listed_and_stay_listed_from_the_cache() {
+    let (tmp, files) = project();
+    let first = request(&tmp, files.clone(), true);
+    let mut second = request(&tmp, files, true);
+    second.content_cache = std::sync::Arc::clone(&first.content_cache);
+
+    let expected = "BOMs and line endings normalized to LF (2):\n\
+                    - bom.txt: BOM, CRLF\n\
+                    - mixed.txt: mixed line endings\n";
+    let out = generate(first);
+    assert!(out.contains("--- Start of file: bom.txt ---\nhello\nworld\n"));
+    assert!(!out.contains('\r') && !out.contains('\u{feff}'));
+    assert!(out.contains(expected));
+
+    assert_eq!(second.content_cache.lock().unwrap().len(), 3);
+    assert_eq!(generate(second), out);
+}
+
+#[test]
+fn the_setting_defaults_to_off() {
+    let settings: WorkspaceSettings = serde_json::from_str(
+        r#"{"version":1,"ext_filter":"","exclude_dirs":"","exclude_files":"","remove_prefix":"","remove_regex":"","hierarchy_only":false,"dirs_only":false}"#,
+    )
+    .unwrap();
+    assert!(!settings.normalize_text);
+}
@@ -0,0 +1,38 @@
+use stitch::core::{
+    LocalSettings, Theme, UserPreferences, load_user_preferences, save_user_preferences,
+};
+use tempfile::TempDir;
+
+#[test]
+fn theme_index_round_trips() {
+    for theme in [Theme::System, Theme::Light, Theme::Dark] {
+        assert_eq!(Theme::from_index(theme.index()), theme);
+    }
+    assert_eq!(Theme::from_index(-1), Theme::System);
+    assert_eq!(Theme::from_index(7), Theme::System);
+}
+
+#[test]
+fn theme_is_persisted_in_the_user_config_dir() {
+    let tmp = TempDir::new().unwrap();
+    let prefs = UserPreferences {
+        theme: Theme::Dark,
+        ..Default::default()
+    };
+    save_user_preferences(tmp.path(), &prefs).unwrap();
+    assert_eq!(load_user_preferences(tmp.path()).theme, Theme::Dark);
+}
+
+#[test]
+fn missing_theme_defaults_to_system() {
+    let prefs: UserPreferences = serde_json::from_str("{}").unwrap();
+    assert_eq!(prefs.theme, Theme::System);
+
+    let prefs: UserPreferences = serde_json::from_str(r#"{"theme":"light"}"#).unwrap();
+    assert_eq!(prefs.theme, Theme::Light);
+
+    // Project settings written before the theme moved still load.
+    let ls: LocalSettings =
+        serde_json::from_str(r#"{"current_profile":"dev","theme":"dark"}"#).unwrap();
+    assert_eq!(ls.current_profile.as_deref(), Some("dev"));
+}
@@ -0,0 +1,81 @@
+use std::cell::Cell;
+
+use stitch::core::{FileMarkers, TokenCache, split_output_segments};
+
+fn sample_output(main_body: &str) -> String {
+    format!(
+        "=== FILE HIERARCHY ===\n\nproj/\n├── main.rs\n└── lib.rs\n\n=== FILE CONTENTS ===\n\n\
+--- Start of file: main.rs ---\n{main_body}\n--- End of file: main.rs ---\n\n\
+--- Start of file: lib.rs ---\npub fn add(a: i32, b: i32) -> i32 {{ a + b }}\n--- End of file: lib.rs ---\n\n"
+    )
+}
+
+fn words(s: &str) -> usize {
+    s.split_whitespace().count()
+}
+
+#[test]
+fn segments_split_before_each_file_and_concatenate_back() {
+    let text = sample_output("fn main() {}");
+    let segments = split_output_segments(&text);
+    assert_eq!(segments.len(), 3);
+    assert!(segments[0].starts_with("=== FILE HIERARCHY ==="));
+    assert!(segments[1].starts_with("--- Start of file: main.rs ---"));
+    assert!(segments[2].starts_with("--- Start of file: lib.rs ---"));
+    assert_eq!(segments.concat(), text);
+
+    assert!(split_output_segments("").is_empty());
+    assert_eq!(split_output_segments("no files\n"), vec!["no files\n"]);
+}
+
+#[test]
+fn unchanged_segments_are_not_recounted() {
+    let calls = Cell::new(0);
+    let counter = |s: &str| {
+        calls.set(calls.get() + 1);
+        words(s)
+    };
+    let mut cache = TokenCache::new();
+
+    let first = sample_output("fn main() {}");
+    assert_eq!(cache.count(&first, counter), words(&first));
+    assert_eq!(calls.get(), 3);
+
+    let edited = sample_output("fn main() { run(); }");
+    assert_eq!(cache.count(&edited, counter), words(&edited));
+    assert_eq!(calls.get(), 4, "only the edited file is recounted");
+    assert_eq!(
+        cache.len(),
+        3,
+        "the stale entry for the old main.rs is dropped"
+    );
+}
+
+#[test]
+fn cached_sum_matches_full_o200k_count() {
+    let bpe = tiktoken_rs::o200k_base().unwrap();
+    let count = |s: &str| bpe.encode_with_special_tokens(s).len();
+    let text = sample_output("fn main() {\n    println!(\"hi // there\");\n}");
+
+    let mut cache = TokenCache::new();
+    assert_eq!(cache.count(&text, count), count(&text));
+}
+
+#[test]
+fn cache_splits_at_the_configured_markers() {
+    let calls = Cell::new(0);
+    let counter = |s: &str| {
+        calls.set(calls.get() + 1);
+        words(s)
+    };
+    let cells = |main_body: &str| {
+        format!("proj/\n\n# %% main.rs\n{main_body}\n\n# %% lib.rs\npub fn lib() {{}}\n\n")
+    };
+    let mut cache = TokenCache::new();
+    cache.set_markers(&FileMarkers::cells());
+
+    cache.count(&cells("fn main() {}"), counter);
+    assert_eq!(calls.get(), 3);
+    cache.count(&cells("fn main() { run(); }"), counter);
+    assert_eq!(calls.get(), 4, "only the edited cell is recounted");
+}
@@ -0,0 +1,139 @@
+use std::path::{Path, PathBuf};
+
+use stitch::core::{
+    BUILTIN_TOKENIZERS, DEFAULT_TOKENIZER, SettingsField, TokenCache, TokenizerSpec,
+    WorkspaceSettings, load_tokenizer, validate_workspace_settings,
+};
+
+#[test]
+fn empty_setting_means_default_tokenizer() {
+    assert_eq!(
+        TokenizerSpec::parse(""),
+        Ok(TokenizerSpec::Builtin(DEFAULT_TOKENIZER))
+    );
+    assert_eq!(
+        TokenizerSpec::parse("   "),
+        Ok(TokenizerSpec::Builtin(DEFAULT_TOKENIZER))
+    );
+}
+
+#[test]
+fn builtin_names_are_case_insensitive() {
+    for name in BUILTIN_TOKENIZERS {
+        assert_eq!(
+            TokenizerSpec::parse(&name.to_uppercase()),
+            Ok(TokenizerSpec::Builtin(name))
+        );
+    }
+}
+
+#[test]
+fn json_paths_are_hugging_face_tokenizers() {
+    assert_eq!(
+        TokenizerSpec::parse(" models/llama3/tokenizer.json "),
+        Ok(TokenizerSpec::HuggingFace(PathBuf::from(
+            "models/llama3/tokenizer.json"
+        )))
+    );
+    assert_eq!(
+        TokenizerSpec::parse("mistral.JSON"),
+        Ok(TokenizerSpec::HuggingFace(PathBuf::from("mistral.JSON")))
+    );
+}
+
+#[test]
+fn unknown_tokenizers_are_rejected_with_the_alternatives() {
+    let err = TokenizerSpec::parse("gpt2").unwrap_err();
+    assert!(err.contains("'gpt2'"), "{err}");
+    for name in BUILTIN_TOKENIZERS {
+        assert!(err.contains(name), "{err}");
+    }
+}
+
+#[test]
+fn labels_name_the_model_family() {
+    assert_eq!(TokenizerSpec::Builtin("cl100k_base").label(), "cl100k_base");
+    assert_eq!(
+        TokenizerSpec::HuggingFace(PathBuf::from("models/llama3/tokenizer.json")).label(),
+        "llama3"
+    );
+    assert_eq!(
+        TokenizerSpec::HuggingFace(PathBuf::from("mistral-7b.json")).label(),
+        "mistral-7b"
+    );
+}
+
+#[test]
+fn validation_flags_unknown_tokenizer() {
+    let ws = WorkspaceSettings {
+        tokenizer: "gpt2".into(),
+        ..WorkspaceSettings::default()
+    };
+    let errors = validate_workspace_settings(&ws);
+    assert!(errors.iter().any(|e| e.field == SettingsField::Tokenizer));
+
+    let ws = WorkspaceSettings {
+        tokenizer: "cl100k_base".into(),
+        ..WorkspaceSettings::default()
+    };
+    assert!(validate_workspace_settings(&ws).is_empty());
+}
+
+#[test]
+fn switching_tokenizer_clears_cached_counts() {
+    let mut cache = TokenCache::default();
+    cache.set_tokenizer("o200k_base");
+    cache.count("--- Start of file: a.rs ---\nfn a() {}\n", str::len);
+    assert!(!cache.is_empty());
+
+    cache.set_tokenizer("o200k_base");
+    assert!(!cache.is_empty());
+
+    cache.set_tokenizer("cl100k_base");
+    assert!(cache.is_empty());
+}
+
+#[cfg(not(feature = "hf-tokenizers"))]
+#[test]
+fn hugging_face_tokenizers_need_their_feature() {
+    let spec = TokenizerSpec::HuggingFace(PathBuf::from("tokenizer.json"));
+    let err = load_tokenizer(&spec, Path::new("."))
+        .err()
+        .expect("loading should fail without the feature");
+    assert!(err.contains("hf-tokenizers"), "{err}");
+}
+
+#[cfg(feature = "hf-tokenizers")]
+#[test]
+fn missing_hugging_face_file_is_an_error() {
+    let dir = tempfile::tempdir().unwrap();
+    let spec = TokenizerSpec::HuggingFace(PathBuf::from("missing/tokenizer.json"));
+    assert!(load_tokenizer(&spec, dir.path()).is_err());
+}
+
+#[cfg(feature = "tokens")]
+#[test]
+fn builtin_tokenizers_count_like_tiktoken() {
+    let text = "fn main() {\n    println!(\"héllo, wörld\");\n}\n";
+    let (o200k_key, o200k) =
+        load_tokenizer(&TokenizerSpec::Builtin("o200k_base"), Path::new(".")).unwrap();
+    let (cl100k_key, cl100k) =
+        load_tokenizer(&TokenizerSpec::Builtin("cl100k_base"), Path::new(".")).unwrap();
+    assert_ne!(o200k_key, cl100k_key);
+
+    let expected_o200k = tiktoken_rs::o200k_base()
+        .unwrap()
+        .encode_with_special_tokens(text)
+        .len();
+    let expected_cl100k = tiktoken_rs::cl100k_base()
+        .unwrap()
+        .encode_with_special_tokens(text)
+        .len();
+    assert_eq!(o200k.count(text), expected_o200k);
+    assert_eq!(cl100k.count(text), expected_cl100k);
+
+    // Loaded once, then reused.
+    let (_, again) =
+        load_tokenizer(&TokenizerSpec::Builtin("cl100k_base"), Path::new(".")).unwrap();
+    assert!(std::sync::Arc::ptr_eq(&cl100k, &again));
+}
@@ -0,0 +1,160 @@
+use pretty_assertions::assert_eq;
+use stitch::core::{
+    FileMarkers, OutputOrder, OutputSection, WorkspaceSettings, find_tool_config,
+    import_code2prompt_config, import_repomix_config, import_tool_config,
+};
+use tempfile::TempDir;
+
+fn settings() -> WorkspaceSettings {
+    WorkspaceSettings {
+        version: 1,
+        ext_filter: ".md".into(),
+        exclude_dirs: "old".into(),
+        remove_prefix: "#".into(),
+        ..WorkspaceSettings::default()
+    }
+}
+
+#[test]
+fn repomix_patterns_become_filters() {
+    let mut ws = settings();
+    let skipped = import_repomix_config(
+        r#"{
+            "include": ["src/**/*.ts", "**/*.{js,jsx}", "*.md"],
+            "ignore": {
+                "useGitignore": true,
+                "customPatterns": ["**/node_modules/**", "dist/", "package-lock.json", "*.log", "src/legacy/**"]
+            }
+        }"#,
+        &mut ws,
+    )
+    .unwrap();
+
+    assert_eq!(ws.ext_filter, ".ts,.js,.jsx,.md");
+    assert_eq!(ws.exclude_dirs, "node_modules,dist");
+    assert_eq!(ws.exclude_files, "package-lock.json");
+    // Untouched by the import.
+    assert_eq!(ws.remove_prefix, "#");
+    assert_eq!(
+        skipped,
+        vec![
+            "include \"src/**/*.ts\" (kept the extension, not the path)".to_string(),
+            "exclude \"src/legacy/**\"".to_string(),
+            "exclude \"*.log\" (only listed extensions are included)".to_string(),
+        ]
+    );
+}
+
+#[test]
+fn repomix_output_settings() {
+    let mut ws = settings();
+    let skipped = import_repomix_config(
+        r#"{
+            "output": {
+                "style": "markdown",
+                "showLineNumbers": true,
+                "removeComments": true,
+                "directoryStructure": false,
+                "headerText": "Read carefully",
+                "removeEmptyLines": true
+            },
+            "tokenCount": { "encoding": "cl100k_base" }
+        }"#,
+        &mut ws,
+    )
+    .unwrap();
+
+    // No patterns in the file: filters stay as they were.
+    assert_eq!(ws.ext_filter, ".md");
+    assert_eq!(ws.file_markers.header, "## File: {path}\n```{lang}");
+    assert!(ws.line_numbers);
+    assert!(ws.rust.rust_remove_doc_comments && ws.slint.slint_remove_block_comments);
+    assert!(!ws.layout.is_enabled(OutputSection::Hierarchy));
+    assert!(ws.layout.is_enabled(OutputSection::Contents));
+    assert_eq!(ws.tokenizer, "cl100k_base");
+    assert_eq!(
+        skipped,
+        vec!["output.headerText", "output.removeEmptyLines"]
+    );
+}
+
+#[test]
+fn repomix_exclude_only_patterns_and_unknown_values() {
+    let mut ws = settings();
+    let skipped = import_repomix_config(
+        r#"{ "include": ["**/*"], "ignore": { "customPatterns": ["*.log", "**/*.min.js", ".env", "tmp/*.txt"] },
+             "output": { "style": "json" }, "tokenCount": { "encoding": "p50k_base" } }"#,
+        &mut ws,
+    )
+    .unwrap();
+    assert_eq!(ws.ext_filter, "-.log,-.min.js");
+    assert_eq!(ws.exclude_dirs, ".env");
+    assert_eq!(ws.exclude_files, "");
+    assert_eq!(ws.file_markers, FileMarkers::default());
+    assert_eq!(
+        skipped,
+        vec![
+            "exclude \"tmp/*.txt\"",
+            "output.style \"json\"",
+            "tokenCount.encoding \"p50k_base\"",
+        ]
+    );
+    assert!(import_repomix_config("[1, 2]", &mut ws).is_err());
+    assert!(import_repomix_config("{ nope", &mut ws).is_err());
+}
+
+#[test]
+fn code2prompt_config() {
+    let mut ws = settings();
+    let skipped = import_code2prompt_config(
+        r#"
+include_patterns = "*.rs,*.toml"
+exclude_patterns = ["target/**", "Cargo.lock"]
+output_format = "xml"
+line_numbers = true
+sort_method = "name_asc"
+encoding = "o200k"
+full_directory_tree = true
+"#,
+        &mut ws,
+    )
+    .unwrap();
+
+    assert_eq!(ws.ext_filter, ".rs,.toml");
+    assert_eq!(ws.exclude_dirs, "target");
+    assert_eq!(ws.exclude_files, "Cargo.lock");
+    assert_eq!(ws.file_markers.header, "<file path=\"{path}\">");
+    assert!(ws.line_numbers);
+    assert_eq!(ws.output_order, OutputOrder::Alphabetical);
+    assert_eq!(ws.tokenizer, "o200k_base");
+    assert_eq!(skipped, vec!["full_directory_tree"]);
+
+    let mut ws = settings();
+    let skipped =
+        import_code2prompt_config("no_codeblock = true\nsort_method = \"date_desc\"", &mut ws)
+            .unwrap();
+    assert_eq!(ws.file_markers.header, "`{path}`:\n");
+    assert_eq!(ws.file_markers.footer, "");
+    assert_eq!(skipped, vec!["sort_method \"date_desc\""]);
+    assert!(import_code2prompt_config("include_patterns = [", &mut ws).is_err());
+}
+
+#[test]
+fn config_files_are_found_and_dispatched_by_name() {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path();
+    assert_eq!(find_tool_config(root), None);
+
+    std::fs::write(root.join(".c2pconfig"), "include_patterns = [\"*.py\"]").unwrap();
+    assert_eq!(find_tool_config(root), Some(root.join(".c2pconfig")));
+    std::fs::write(root.join("repomix.config.json"), r#"{"include":["*.go"]}"#).unwrap();
+    let found = find_tool_config(root).unwrap();
+    assert_eq!(found, root.join("repomix.config.json"));
+
+    let mut ws = settings();
+    import_tool_config(&found, &mut ws).unwrap();
+    assert_eq!(ws.ext_filter, ".go");
+    import_tool_config(&root.join(".c2pconfig"), &mut ws).unwrap();
+    assert_eq!(ws.ext_filter, ".py");
+    assert!(import_tool_config(&root.join("missing.toml"), &mut ws).is_err());
+}
@@ -0,0 +1,106 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::Path,
+};
+
+use stitch::core::{
+    UnselectedDir, WorkspaceSettings, load_workspace, render_unselected_dirs_summary,
+    save_workspace, scan_dir_to_node, summarize_unselected_top_level_dirs,
+};
+use tempfile::TempDir;
+
+fn write(root: &Path, rel: &str) {
+    let p = root.join(rel);
+    fs::create_dir_all(p.parent().unwrap()).unwrap();
+    fs::write(p, rel).unwrap();
+}
+
+#[test]
+fn lists_only_top_level_dirs_without_selected_files() {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path();
+    write(root, "src/main.rs");
+    write(root, "src/lib.rs");
+    write(root, "docs/guide.md");
+    write(root, "docs/api/index.md");
+    write(root, "assets/logo.svg");
+    write(root, "vendor/a/b.rs");
+    write(root, "README.md");
+    fs::create_dir_all(root.join("empty")).unwrap();
+
+    let none: HashSet<String> = HashSet::new();
+    let tree = scan_dir_to_node(root, &none, &none, &none, &none);
+
+    let mut explicit = HashMap::new();
+    explicit.insert(root.join("src"), true);
+    explicit.insert(root.join("vendor/a/b.rs"), true);
+
+    let summary = summarize_unselected_top_level_dirs(&tree, &explicit);
+    assert_eq!(
+        summary,
+        vec![
+            UnselectedDir {
+                name: "assets".into(),
+                file_count: 1,
+            },
+            UnselectedDir {
+                name: "docs".into(),
+                file_count: 2,
+            },
+        ]
+    );
+}
+
+#[test]
+fn selecting_the_root_leaves_nothing_unselected() {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path();
+    write(root, "a/x.txt");
+    write(root, "b/y.txt");
+
+    let none: HashSet<String> = HashSet::new();
+    let tree = scan_dir_to_node(root, &none, &none, &none, &none);
+
+    let mut explicit = HashMap::new();
+    explicit.insert(root.to_path_buf(), true);
+    explicit.insert(root.join("b/y.txt"), false);
+
+    let summary = summarize_unselected_top_level_dirs(&tree, &explicit);
+    assert_eq!(summary.len(), 1, "b lost its only file");
+    assert_eq!(summary[0].name, "b");
+}
+
+#[test]
+fn render_uses_singular_and_plural() {
+    let out = render_unselected_dirs_summary(&[
+        UnselectedDir {
+            name: "docs".into(),
+            file_count: 12,
+        },
+        UnselectedDir {
+            name: "assets".into(),
+            file_count: 1,
+        },
+    ]);
+    assert_eq!(out, "docs/ (12 files)\nassets/ (1 file)\n");
+    assert!(render_unselected_dirs_summary(&[]).is_empty());
+}
+
+#[test]
+fn setting_persists_and_defaults_off() {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path();
+
+    let ws = WorkspaceSettings {
+        version: 1,
+        summarize_unselected: true,
+        ..Default::default()
+    };
+    save_workspace(root, &ws).unwrap();
+    assert!(load_workspace(root).unwrap().summarize_unselected);
+
+    let legacy = r#"{"version":1,"ext_filter":"","exclude_dirs":"","exclude_files":"","remove_prefix":"","remove_regex":"","hierarchy_only":false,"dirs_only":false}"#;
+    fs::write(root.join(".stitchworkspace/workspace.json"), legacy).unwrap();
+    assert!(!load_workspace(root).unwrap().summarize_unselected);
+}
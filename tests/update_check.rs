@@ -0,0 +1,38 @@
+use stitch::core::{ReleaseInfo, is_newer_version, parse_latest_release, parse_version};
+
+#[test]
+fn parses_plain_and_prefixed_versions() {
+    assert_eq!(parse_version("0.1.21"), Some((0, 1, 21)));
+    assert_eq!(parse_version("v1.2.3"), Some((1, 2, 3)));
+    assert_eq!(parse_version("2.0"), Some((2, 0, 0)));
+    assert_eq!(parse_version("1.4.0-beta.1"), Some((1, 4, 0)));
+    assert_eq!(parse_version("nightly"), None);
+    assert_eq!(parse_version("1.2.3.4"), None);
+}
+
+#[test]
+fn compares_versions_numerically() {
+    assert!(is_newer_version("0.1.9", "0.1.10"));
+    assert!(is_newer_version("0.1.21", "v0.2.0"));
+    assert!(!is_newer_version("0.1.21", "0.1.21"));
+    assert!(!is_newer_version("0.2.0", "0.1.99"));
+    assert!(!is_newer_version("0.1.21", "garbage"));
+}
+
+#[test]
+fn reads_tag_and_link_from_github_response() {
+    let json = r#"{
+        "tag_name": "v0.2.0",
+        "html_url": "https://github.com/gramistella/stitch/releases/tag/v0.2.0",
+        "draft": false,
+        "assets": []
+    }"#;
+    assert_eq!(
+        parse_latest_release(json),
+        Some(ReleaseInfo {
+            version: "0.2.0".into(),
+            url: "https://github.com/gramistella/stitch/releases/tag/v0.2.0".into(),
+        })
+    );
+    assert_eq!(parse_latest_release(r#"{"message":"Not Found"}"#), None);
+}
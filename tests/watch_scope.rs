@@ -0,0 +1,68 @@
+use std::path::PathBuf;
+
+use pretty_assertions::assert_eq;
+use stitch::core::{WatchTarget, minimize_watch_targets, selection_watch_targets};
+
+fn paths(items: &[&str]) -> Vec<PathBuf> {
+    items.iter().map(PathBuf::from).collect()
+}
+
+#[test]
+fn checked_dirs_are_watched_with_subtrees_and_file_folders_alone() {
+    let targets = selection_watch_targets(
+        "/p".as_ref(),
+        &paths(&["/p/README.md", "/p/src/ui/app.rs", "/p/src/ui/state.rs"]),
+        &paths(&["/p/docs"]),
+    );
+    assert_eq!(
+        targets,
+        [
+            WatchTarget::flat("/p"),
+            WatchTarget::recursive("/p/docs"),
+            WatchTarget::flat("/p/src/ui"),
+        ]
+    );
+}
+
+#[test]
+fn nothing_under_a_watched_subtree_is_listed_again() {
+    let targets = selection_watch_targets(
+        "/p".as_ref(),
+        &paths(&["/p/src/lib.rs", "/p/src/core/a.rs"]),
+        &paths(&["/p/src", "/p/src/core"]),
+    );
+    assert_eq!(
+        targets,
+        [WatchTarget::flat("/p"), WatchTarget::recursive("/p/src")]
+    );
+
+    let whole = selection_watch_targets("/p".as_ref(), &[], &paths(&["/p"]));
+    assert_eq!(whole, [WatchTarget::recursive("/p")]);
+}
+
+#[test]
+fn paths_outside_the_project_are_dropped() {
+    let targets = selection_watch_targets(
+        "/p".as_ref(),
+        &paths(&["/old/src/a.rs"]),
+        &paths(&["/old/docs"]),
+    );
+    assert_eq!(targets, [WatchTarget::flat("/p")]);
+}
+
+#[test]
+fn minimizing_keeps_folders_outside_recursive_targets() {
+    let targets = minimize_watch_targets(vec![
+        WatchTarget::flat("/repo/.git"),
+        WatchTarget::recursive("/repo/app"),
+        WatchTarget::flat("/repo/app/.git"),
+        WatchTarget::flat("/repo/.git"),
+    ]);
+    assert_eq!(
+        targets,
+        [
+            WatchTarget::flat("/repo/.git"),
+            WatchTarget::recursive("/repo/app"),
+        ]
+    );
+}
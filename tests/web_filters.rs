@@ -0,0 +1,147 @@
+use pretty_assertions::assert_eq;
+use std::path::Path;
+use stitch::core::{
+    TransformOptions, TransformPipeline, WebFilterOptions, WebLanguage, apply_web_filters,
+    is_web_file_path,
+};
+
+const COMMENTS: WebFilterOptions = WebFilterOptions {
+    remove_comments: true,
+    collapse_whitespace: false,
+};
+const WHITESPACE: WebFilterOptions = WebFilterOptions {
+    remove_comments: false,
+    collapse_whitespace: true,
+};
+
+#[test]
+fn detects_languages_by_extension() {
+    assert_eq!(
+        WebLanguage::from_path(Path::new("site/index.HTML")),
+        Some(WebLanguage::Html)
+    );
+    assert_eq!(
+        WebLanguage::from_path(Path::new("a.htm")),
+        Some(WebLanguage::Html)
+    );
+    assert_eq!(
+        WebLanguage::from_path(Path::new("a.css")),
+        Some(WebLanguage::Css)
+    );
+    assert_eq!(
+        WebLanguage::from_path(Path::new("a.scss")),
+        Some(WebLanguage::Scss)
+    );
+    assert!(!is_web_file_path(Path::new("a.js")));
+}
+
+#[test]
+fn removes_html_comments() {
+    let src = "<!-- banner\n  spans lines -->\n<p>Hi <!-- inline --> there</p>\n  <!-- own line -->\n<br>\n";
+    assert_eq!(
+        apply_web_filters(src, WebLanguage::Html, &COMMENTS),
+        "<p>Hi there</p>\n<br>\n"
+    );
+}
+
+#[test]
+fn keeps_comment_markers_inside_script_and_style() {
+    let src = concat!(
+        "<!-- head -->\n",
+        "<SCRIPT type=\"module\">\n",
+        "  const banner = \"<!-- x -->\";\n",
+        "</SCRIPT>\n",
+        "<style>p::before { content: \"<!-- y -->\"; }</style>\n",
+        "<p>a<!-- z -->b</p>\n",
+    );
+    assert_eq!(
+        apply_web_filters(src, WebLanguage::Html, &COMMENTS),
+        concat!(
+            "<SCRIPT type=\"module\">\n",
+            "  const banner = \"<!-- x -->\";\n",
+            "</SCRIPT>\n",
+            "<style>p::before { content: \"<!-- y -->\"; }</style>\n",
+            "<p>ab</p>\n",
+        )
+    );
+}
+
+#[test]
+fn removes_css_block_comments_but_keeps_strings() {
+    let src = "/* reset */\na { content: \"/* not */\"; } /* tail */\n";
+    assert_eq!(
+        apply_web_filters(src, WebLanguage::Css, &COMMENTS),
+        "a { content: \"/* not */\"; }\n"
+    );
+}
+
+#[test]
+fn removes_scss_line_comments_but_keeps_urls() {
+    let src =
+        "// vars\n$c: red; // brand\n.a { background: url(https://cdn.example.com/x.png); }\n";
+    assert_eq!(
+        apply_web_filters(src, WebLanguage::Scss, &COMMENTS),
+        "$c: red;\n.a { background: url(https://cdn.example.com/x.png); }\n"
+    );
+}
+
+#[test]
+fn collapses_html_whitespace_but_keeps_pre_and_script_contents() {
+    let src = concat!(
+        "<div>\n",
+        "    <p>Some    spaced\ttext</p>\n",
+        "\n",
+        "    <pre>\n",
+        "  keep   this\n",
+        "\n",
+        "    </pre>\n",
+        "    <script>\n",
+        "        const s = \"a    b\";\n",
+        "    </script>\n",
+        "</div>\n",
+    );
+    assert_eq!(
+        apply_web_filters(src, WebLanguage::Html, &WHITESPACE),
+        concat!(
+            "<div>\n",
+            "<p>Some spaced text</p>\n",
+            "<pre>\n",
+            "  keep   this\n",
+            "\n",
+            "    </pre>\n",
+            "<script>\n",
+            "const s = \"a    b\";\n",
+            "</script>\n",
+            "</div>\n",
+        )
+    );
+}
+
+#[test]
+fn collapses_css_whitespace_outside_strings() {
+    let src = "\n.a  {\n    font-family:   \"Open   Sans\";\n\n    margin:\t0   auto;\n}\n";
+    assert_eq!(
+        apply_web_filters(src, WebLanguage::Css, &WHITESPACE),
+        ".a {\nfont-family: \"Open   Sans\";\nmargin: 0 auto;\n}\n"
+    );
+}
+
+#[test]
+fn default_pipeline_runs_the_filters_on_web_files_only() {
+    let opts = TransformOptions {
+        web: COMMENTS,
+        ..TransformOptions::default()
+    };
+    let src = "<!-- x -->\n<p>y</p>\n";
+    let pipeline = TransformPipeline::default();
+    assert_eq!(
+        pipeline
+            .apply("docs/index.html", src.to_string(), &opts)
+            .contents,
+        "<p>y</p>\n"
+    );
+    assert_eq!(
+        pipeline.apply("notes.md", src.to_string(), &opts).contents,
+        src
+    );
+}
@@ -11,6 +11,7 @@ fn clears_stale_current_profile_when_profile_missing() {
 
     let local_settings = LocalSettings {
         current_profile: Some("ghost".into()),
+        ..Default::default()
     };
     save_local_settings(root, &local_settings).expect("save local settings");
 
@@ -58,13 +59,16 @@ fn no_clear_when_shared_profile_exists() {
                 slint_remove_line_comments: false,
                 slint_remove_block_comments: false,
             },
+            ..Default::default()
         },
         explicit: vec![],
+        ..Default::default()
     };
     save_profile(root, &prof, ProfileScope::Shared).expect("save shared profile");
 
     let local_settings = LocalSettings {
         current_profile: Some("alpha".into()),
+        ..Default::default()
     };
     save_local_settings(root, &local_settings).expect("save local settings");
 
@@ -101,13 +105,16 @@ fn no_clear_when_local_profile_exists() {
                 slint_remove_line_comments: false,
                 slint_remove_block_comments: false,
             },
+            ..Default::default()
         },
         explicit: vec![],
+        ..Default::default()
     };
     save_profile(root, &prof, ProfileScope::Local).expect("save local profile");
 
     let local_settings = LocalSettings {
         current_profile: Some("beta".into()),
+        ..Default::default()
     };
     save_local_settings(root, &local_settings).expect("save local settings");
 
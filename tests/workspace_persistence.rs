@@ -45,6 +45,7 @@ fn save_then_load_roundtrip_and_overwrite_is_atomic() {
             slint_remove_line_comments: true,
             slint_remove_block_comments: false,
         },
+        ..Default::default()
     };
     save_workspace(root, &s1).expect("save v1");
 
@@ -0,0 +1,186 @@
+use std::{fs, path::Path, process::Command};
+
+use pretty_assertions::assert_eq;
+use stitch::core::{
+    FileChange, GitSafety, WriteBackPlan, parse_file_changes, plan_response_changes, write_target,
+};
+use tempfile::TempDir;
+
+fn git(dir: &Path, args: &[&str]) -> String {
+    let out = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(
+        out.status.success(),
+        "git {args:?}: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    String::from_utf8_lossy(&out.stdout).into_owned()
+}
+
+fn repo() -> TempDir {
+    let tmp = TempDir::new().unwrap();
+    let dir = tmp.path();
+    git(dir, &["init", "-q"]);
+    git(dir, &["config", "user.name", "Test"]);
+    git(dir, &["config", "user.email", "test@example.com"]);
+    fs::create_dir_all(dir.join("src")).unwrap();
+    fs::write(dir.join("src/lib.rs"), "fn a() {}\nfn b() {}\n").unwrap();
+    git(dir, &["add", "."]);
+    git(dir, &["commit", "-q", "-m", "init"]);
+    tmp
+}
+
+fn change(path: &str, contents: &str) -> FileChange {
+    FileChange {
+        path: path.into(),
+        contents: contents.into(),
+    }
+}
+
+#[test]
+fn targets_must_stay_inside_the_project() {
+    let tmp = repo();
+    let root = tmp.path();
+    assert!(write_target(root, "src/lib.rs").is_ok());
+    assert!(write_target(root, "src/new/mod.rs").is_ok());
+    for bad in [
+        "",
+        "../x.rs",
+        "src/../../x.rs",
+        "/etc/passwd",
+        ".git/config",
+        ".stitchworkspace/workspace.json",
+    ] {
+        assert!(write_target(root, bad).is_err(), "{bad}");
+    }
+    #[cfg(unix)]
+    {
+        let outside = TempDir::new().unwrap();
+        std::os::unix::fs::symlink(outside.path(), root.join("link")).unwrap();
+        assert_eq!(
+            write_target(root, "link/x.rs").unwrap_err(),
+            "link/x.rs is outside the project"
+        );
+    }
+}
+
+#[test]
+fn summary_counts_added_and_removed_lines() {
+    let tmp = repo();
+    let plan = WriteBackPlan::new(
+        tmp.path(),
+        &[
+            change("src/lib.rs", "fn a() {}\nfn c() {}\nfn d() {}\n"),
+            change("src/new.rs", "x\n"),
+            change("src/lib.rs", "fn a() {}\nfn b() {}\n"),
+        ],
+    )
+    .unwrap();
+    assert_eq!(
+        plan.render_summary(),
+        "M src/lib.rs  +2 -1\nA src/new.rs  +1 -0\n2 files changed, +3 -1\n"
+    );
+}
+
+#[test]
+fn clean_trees_are_written_directly() {
+    let tmp = repo();
+    let plan = WriteBackPlan::new(tmp.path(), &[change("src/lib.rs", "fn z() {}\n")]).unwrap();
+    assert_eq!(plan.write().unwrap(), GitSafety::Clean);
+    assert_eq!(
+        fs::read_to_string(tmp.path().join("src/lib.rs")).unwrap(),
+        "fn z() {}\n"
+    );
+}
+
+#[test]
+fn uncommitted_work_is_stashed_first_and_kept_in_place() {
+    let tmp = repo();
+    let root = tmp.path();
+    fs::write(root.join("src/lib.rs"), "fn edited() {}\n").unwrap();
+    fs::write(root.join("notes.txt"), "scratch\n").unwrap();
+
+    let plan = WriteBackPlan::new(root, &[change("src/new.rs", "fn n() {}\n")]).unwrap();
+    let GitSafety::Stashed(id) = plan.write().unwrap() else {
+        panic!("expected a stash");
+    };
+    assert!(git(root, &["stash", "list"]).contains("stitch: backup before write-back"));
+    assert_eq!(
+        git(root, &["show", &format!("{id}:src/lib.rs")]),
+        "fn edited() {}\n"
+    );
+    assert_eq!(
+        fs::read_to_string(root.join("src/lib.rs")).unwrap(),
+        "fn edited() {}\n"
+    );
+    assert_eq!(
+        fs::read_to_string(root.join("src/new.rs")).unwrap(),
+        "fn n() {}\n"
+    );
+}
+
+#[test]
+fn untracked_files_and_folders_outside_git_are_not_overwritten() {
+    let tmp = repo();
+    let root = tmp.path();
+    fs::write(root.join("draft.rs"), "keep me\n").unwrap();
+    let plan = WriteBackPlan::new(root, &[change("draft.rs", "new\n")]).unwrap();
+    assert_eq!(
+        plan.write().unwrap_err(),
+        "draft.rs is not tracked by git; commit it before writing over it"
+    );
+    assert_eq!(
+        fs::read_to_string(root.join("draft.rs")).unwrap(),
+        "keep me\n"
+    );
+
+    let plain = TempDir::new().unwrap();
+    let plan = WriteBackPlan::new(plain.path(), &[change("a.rs", "x\n")]).unwrap();
+    assert!(plan.write().is_err());
+    assert!(!plain.path().join("a.rs").exists());
+}
+
+#[test]
+fn responses_are_split_into_file_blocks() {
+    let response = "Here is the fix:\n\n\
+--- Start of file: src/lib.rs ---\n\
+```rust\n\
+fn a() {}\n\
+```\n\
+--- End of file: src/lib.rs ---\n\
+\n\
+--- Start of file: src/new.rs --- (modified 2026-01-01 10:00)\n\
+fn n() {}\n\
+\n\
+--- End of file: src/new.rs ---\n\
+--- Start of file: src/cut.rs ---\n\
+fn cut";
+    assert_eq!(
+        parse_file_changes(response),
+        [
+            change("src/lib.rs", "fn a() {}\n"),
+            change("src/new.rs", "fn n() {}\n")
+        ]
+    );
+}
+
+#[test]
+fn responses_without_file_blocks_are_refused() {
+    let tmp = repo();
+    assert_eq!(
+        plan_response_changes(tmp.path(), "Looks good to me.").unwrap_err(),
+        "the response has no file blocks"
+    );
+    assert_eq!(
+        plan_response_changes(
+            tmp.path(),
+            "--- Start of file: ../x.rs ---\nx\n--- End of file: ../x.rs ---\n"
+        )
+        .unwrap_err(),
+        "../x.rs is outside the project"
+    );
+}
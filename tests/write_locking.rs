@@ -0,0 +1,84 @@
+use std::{
+    io,
+    sync::mpsc,
+    thread,
+    time::{Duration, Instant},
+};
+
+use stitch::core::{
+    WorkspaceSettings, load_workspace, save_workspace, with_write_lock, write_lock_file,
+};
+use tempfile::TempDir;
+
+/// Hold the project's write lock on another thread until `release` is dropped or sent to.
+fn hold_lock(root: &std::path::Path) -> (mpsc::Sender<()>, thread::JoinHandle<()>) {
+    let (locked_tx, locked_rx) = mpsc::channel();
+    let (release_tx, release_rx) = mpsc::channel::<()>();
+    let root = root.to_path_buf();
+    let handle = thread::spawn(move || {
+        with_write_lock(&root, Duration::from_secs(1), || {
+            locked_tx.send(()).unwrap();
+            let _ = release_rx.recv();
+            Ok(())
+        })
+        .unwrap();
+    });
+    locked_rx.recv().unwrap();
+    (release_tx, handle)
+}
+
+#[test]
+fn lock_file_lives_in_the_local_folder() {
+    let tmp = TempDir::new().unwrap();
+    with_write_lock(tmp.path(), Duration::ZERO, || Ok(())).unwrap();
+    let lock = write_lock_file(tmp.path());
+    assert!(lock.is_file());
+    assert!(lock.ends_with(".stitchworkspace/local/write.lock"));
+}
+
+#[test]
+fn second_writer_gives_up_after_the_timeout() {
+    let tmp = TempDir::new().unwrap();
+    let (release, handle) = hold_lock(tmp.path());
+
+    let start = Instant::now();
+    let err = with_write_lock(tmp.path(), Duration::from_millis(120), || Ok(())).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::WouldBlock);
+    assert!(err.to_string().contains("try saving again"), "{err}");
+    assert!(start.elapsed() >= Duration::from_millis(120));
+
+    release.send(()).unwrap();
+    handle.join().unwrap();
+    with_write_lock(tmp.path(), Duration::ZERO, || Ok(())).unwrap();
+}
+
+#[test]
+fn save_waits_for_a_briefly_held_lock() {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path();
+    let (release, handle) = hold_lock(root);
+    let releaser = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(150));
+        release.send(()).unwrap();
+    });
+
+    let ws = WorkspaceSettings {
+        ext_filter: ".rs".into(),
+        ..Default::default()
+    };
+    save_workspace(root, &ws).unwrap();
+    assert_eq!(load_workspace(root).unwrap().ext_filter, ".rs");
+
+    releaser.join().unwrap();
+    handle.join().unwrap();
+}
+
+#[test]
+fn errors_from_the_closure_are_passed_through() {
+    let tmp = TempDir::new().unwrap();
+    let err = with_write_lock(tmp.path(), Duration::ZERO, || -> io::Result<()> {
+        Err(io::Error::other("disk full"))
+    })
+    .unwrap_err();
+    assert_eq!(err.to_string(), "disk full");
+}